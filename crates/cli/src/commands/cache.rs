@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
-use butterflow_core::utils::get_cache_dir;
+use butterflow_core::utils::{get_cache_dir, get_result_cache_dir, get_semantic_index_dir};
 use clap::{Args, Subcommand};
+use codemod_sandbox::sandbox::engine::ResultCache;
+use language_core::IndexStore;
 use log::info;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -31,6 +33,12 @@ enum CacheAction {
         /// Clear all cached packages
         #[arg(long)]
         all: bool,
+        /// Clear cached per-file codemod results
+        #[arg(long)]
+        results: bool,
+        /// Clear the persistent workspace semantic index
+        #[arg(long)]
+        semantic_index: bool,
     },
     /// Prune old or unused cache entries
     Prune {
@@ -47,8 +55,17 @@ pub async fn handler(args: &Command) -> Result<()> {
     match &args.action {
         CacheAction::Info => show_cache_info().await,
         CacheAction::List { detailed } => list_cached_packages(*detailed).await,
-        CacheAction::Clear { package, all } => clear_cache(package.as_deref(), *all).await,
-        CacheAction::Prune { max_age, dry_run } => prune_cache(*max_age, *dry_run).await,
+        CacheAction::Clear {
+            package,
+            all,
+            results,
+            semantic_index,
+        } => clear_cache(package.as_deref(), *all, *results, *semantic_index).await,
+        CacheAction::Prune { max_age, dry_run } => {
+            prune_cache(*max_age, *dry_run).await?;
+            prune_result_cache(*max_age, *dry_run)?;
+            prune_semantic_index(*max_age, *dry_run)
+        }
     }
 }
 
@@ -57,7 +74,7 @@ async fn show_cache_info() -> Result<()> {
 
     if !cache_dir.exists() {
         println!("No cache directory found. Cache is empty.");
-        return Ok(());
+        return show_result_cache_info();
     }
 
     let cache_stats = calculate_cache_stats(&cache_dir)?;
@@ -75,6 +92,26 @@ async fn show_cache_info() -> Result<()> {
         );
     }
 
+    show_result_cache_info()
+}
+
+fn show_result_cache_info() -> Result<()> {
+    let result_cache = ResultCache::new(get_result_cache_dir()?);
+    let stats = result_cache.stats()?;
+
+    println!("🧮 Result Cache Information");
+    println!("   📁 Location: {}", result_cache.root().display());
+    println!("   📊 Cached file results: {}", stats.entries);
+    println!("   💾 Total size: {}", format_size(stats.total_size));
+
+    let index_dir = get_semantic_index_dir()?;
+    let stats = IndexStore::stats(&index_dir)?;
+
+    println!("🧭 Semantic Index Information");
+    println!("   📁 Location: {}", index_dir.display());
+    println!("   📊 Indexed files: {}", stats.entries);
+    println!("   💾 Total size: {}", format_size(stats.total_size));
+
     Ok(())
 }
 
@@ -152,7 +189,26 @@ async fn list_cached_packages(detailed: bool) -> Result<()> {
     Ok(())
 }
 
-async fn clear_cache(package: Option<&str>, all: bool) -> Result<()> {
+async fn clear_cache(
+    package: Option<&str>,
+    all: bool,
+    results: bool,
+    semantic_index: bool,
+) -> Result<()> {
+    if results {
+        info!("Clearing cached codemod results...");
+        ResultCache::new(get_result_cache_dir()?).clear()?;
+        println!("✓ All cached codemod results cleared");
+    }
+    if semantic_index {
+        info!("Clearing semantic index...");
+        IndexStore::clear(&get_semantic_index_dir()?)?;
+        println!("✓ Semantic index cleared");
+    }
+    if (results || semantic_index) && package.is_none() && !all {
+        return Ok(());
+    }
+
     let cache_dir = get_cache_dir()?;
 
     if !cache_dir.exists() {
@@ -177,7 +233,9 @@ async fn clear_cache(package: Option<&str>, all: bool) -> Result<()> {
             println!("Package not found in cache: {package_name}");
         }
     } else {
-        return Err(anyhow!("Either specify a package name or use --all"));
+        return Err(anyhow!(
+            "Either specify a package name or use --all, --results or --semantic-index"
+        ));
     }
 
     Ok(())
@@ -250,6 +308,53 @@ async fn prune_cache(max_age_days: u32, dry_run: bool) -> Result<()> {
     Ok(())
 }
 
+fn prune_result_cache(max_age_days: u32, dry_run: bool) -> Result<()> {
+    let result_cache = ResultCache::new(get_result_cache_dir()?);
+    let max_age = std::time::Duration::from_secs(max_age_days as u64 * 24 * 60 * 60);
+    let pruned = result_cache.prune(max_age, dry_run)?;
+
+    if dry_run {
+        println!(
+            "Would prune {} cached results ({}) older than {} days",
+            pruned.entries,
+            format_size(pruned.total_size),
+            max_age_days
+        );
+    } else {
+        println!(
+            "✓ Pruned {} cached results ({}) older than {} days",
+            pruned.entries,
+            format_size(pruned.total_size),
+            max_age_days
+        );
+    }
+
+    Ok(())
+}
+
+fn prune_semantic_index(max_age_days: u32, dry_run: bool) -> Result<()> {
+    let max_age = std::time::Duration::from_secs(max_age_days as u64 * 24 * 60 * 60);
+    let pruned = IndexStore::prune(&get_semantic_index_dir()?, max_age, dry_run)?;
+
+    if dry_run {
+        println!(
+            "Would prune {} semantic index entries ({}) older than {} days",
+            pruned.entries,
+            format_size(pruned.total_size),
+            max_age_days
+        );
+    } else {
+        println!(
+            "✓ Pruned {} semantic index entries ({}) older than {} days",
+            pruned.entries,
+            format_size(pruned.total_size),
+            max_age_days
+        );
+    }
+
+    Ok(())
+}
+
 struct CacheStats {
     package_count: usize,
     version_count: usize,
@@ -86,10 +86,13 @@ impl Command {
         if let Some(output_path) = &self.output {
             if self.verbose {
                 eprintln!("💾 Bundle written to: {}", output_path.display());
+                if result.source_map.is_some() {
+                    eprintln!("🗺️  Source map written to: {}.map", output_path.display());
+                }
             }
         } else {
-            // Output to stdout
-            println!("{}", result.code);
+            // Output to stdout, inlining the source map when enabled
+            println!("{}", result.code_with_inline_source_map());
         }
 
         Ok(())
@@ -0,0 +1,394 @@
+//! Golden-diff regression test over a corpus (`jssg test --corpus`).
+//!
+//! The codemod runs over a directory or a git checkout pinned to a commit
+//! without writing anything, and the diffs it produces are compared with an
+//! approved snapshot kept next to the test fixtures.
+
+use anyhow::{Context, Result};
+use butterflow_core::execution::CodemodExecutionConfig;
+use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use codemod_sandbox::sandbox::engine::{
+    execute_codemod_with_quickjs, CodemodOutput, ExecutionResult, JssgExecutionOptions,
+};
+use codemod_sandbox::sandbox::resolvers::OxcResolver;
+use codemod_sandbox::CodemodLang;
+use language_core::SemanticProvider;
+use semantic_factory::LazySemanticProvider;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Instant;
+use testing_utils::{
+    render_corpus_snapshot, report_results, CorpusComparison, CorpusFileResult, CorpusSnapshot,
+    TestOptions, TestResultDetail,
+};
+
+/// Directory under the test directory holding approved corpus snapshots.
+const CORPUS_SNAPSHOT_DIR: &str = "__corpus__";
+
+/// Characters of the commit in the default snapshot name.
+const SHORT_COMMIT_LEN: usize = 12;
+
+/// Everything a corpus run needs from `jssg test`.
+pub(super) struct CorpusRun<'a> {
+    /// `--corpus` argument
+    pub source: &'a str,
+    /// `--corpus-snapshot` argument
+    pub snapshot_path: Option<PathBuf>,
+    pub test_directory: &'a Path,
+    pub codemod_path: &'a Path,
+    pub resolver: Arc<OxcResolver>,
+    pub language: &'a str,
+    pub params: Option<HashMap<String, serde_json::Value>>,
+    pub capabilities: HashSet<LlrtSupportedModules>,
+    pub options: &'a TestOptions,
+}
+
+enum CorpusSource {
+    Directory(PathBuf),
+    Git {
+        repository: String,
+        /// Full commit SHA; branches and tags can move under the snapshot
+        commit: String,
+    },
+}
+
+impl CorpusSource {
+    fn parse(spec: &str) -> Result<Self> {
+        let path = Path::new(spec);
+        if path.is_dir() {
+            return Ok(Self::Directory(path.to_path_buf()));
+        }
+        // The ref may not contain ':', so `git@host:org/repo` is not split
+        // on the user name
+        match spec.rsplit_once('@') {
+            Some((repository, revision))
+                if !repository.is_empty() && !revision.is_empty() && !revision.contains(':') =>
+            {
+                if !is_commit_sha(revision) {
+                    anyhow::bail!(
+                        "Corpus '{spec}' must be pinned to a full commit SHA, not '{revision}': branches and tags can move and change the snapshot"
+                    );
+                }
+                Ok(Self::Git {
+                    repository: repository.to_string(),
+                    commit: revision.to_ascii_lowercase(),
+                })
+            }
+            _ => anyhow::bail!(
+                "Corpus '{spec}' is neither a directory nor a pinned repository (<repository>@<commit>)"
+            ),
+        }
+    }
+
+    /// Name of the corpus, used for the default snapshot file and the test
+    fn name(&self) -> String {
+        match self {
+            Self::Directory(path) => path
+                .canonicalize()
+                .ok()
+                .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into()))
+                .unwrap_or_else(|| "corpus".to_string()),
+            Self::Git { repository, commit } => {
+                let repository = repository
+                    .trim_end_matches('/')
+                    .rsplit(['/', ':'])
+                    .next()
+                    .unwrap_or(repository)
+                    .trim_end_matches(".git");
+                format!("{repository}@{}", &commit[..SHORT_COMMIT_LEN])
+            }
+        }
+    }
+}
+
+/// Full SHA-1 or SHA-256 object name.
+fn is_commit_sha(revision: &str) -> bool {
+    matches!(revision.len(), 40 | 64) && revision.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// A corpus ready to run on. Git checkouts live in a temporary directory
+/// removed on drop.
+struct Checkout {
+    root: PathBuf,
+    /// Directory name, or repository and commit, recorded in the snapshot
+    /// header
+    label: String,
+    _temp_dir: Option<tempfile::TempDir>,
+}
+
+fn checkout(source: &CorpusSource) -> Result<Checkout> {
+    match source {
+        // Only the directory name, so snapshots match across machines
+        CorpusSource::Directory(path) => Ok(Checkout {
+            root: path.clone(),
+            label: source.name(),
+            _temp_dir: None,
+        }),
+        CorpusSource::Git { repository, commit } => {
+            let temp_dir =
+                tempfile::TempDir::new().context("Failed to create corpus checkout directory")?;
+            let root = temp_dir.path().to_path_buf();
+            let root_arg = root.to_string_lossy();
+            // Blobs are fetched for the pinned commit only. The repository and
+            // commit come from the command line, so they are never options.
+            git(
+                &[
+                    "clone",
+                    "--quiet",
+                    "--no-checkout",
+                    "--filter=blob:none",
+                    "--end-of-options",
+                    repository,
+                    &root_arg,
+                ],
+                None,
+            )?;
+            git(
+                &[
+                    "checkout",
+                    "--quiet",
+                    "--detach",
+                    "--end-of-options",
+                    commit,
+                ],
+                Some(&root),
+            )?;
+            let head = git(&["rev-parse", "HEAD"], Some(&root))?;
+            if !head.eq_ignore_ascii_case(commit) {
+                anyhow::bail!("Corpus checkout of {repository} is at {head}, expected {commit}");
+            }
+            Ok(Checkout {
+                root,
+                label: format!("{repository}@{commit}"),
+                _temp_dir: Some(temp_dir),
+            })
+        }
+    }
+}
+
+fn git(args: &[&str], dir: Option<&Path>) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command.args(args).output().context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run the codemod over the corpus and check its diffs against the approved
+/// snapshot, or write the snapshot with `--update-snapshots`.
+pub(super) async fn handler(run: CorpusRun<'_>) -> Result<()> {
+    let source = CorpusSource::parse(run.source)?;
+    let name = source.name();
+    let snapshot_path = run.snapshot_path.clone().unwrap_or_else(|| {
+        run.test_directory
+            .join(CORPUS_SNAPSHOT_DIR)
+            .join(format!("{name}.diff"))
+    });
+    let checkout = checkout(&source)?;
+
+    let started = Instant::now();
+    let results = run_codemod(&run, &checkout.root).await?;
+    let actual = render_corpus_snapshot(&checkout.label, &results);
+
+    // Like metrics snapshots, a missing snapshot is created on the first run
+    let outcome = if run.options.update_snapshots || !snapshot_path.exists() {
+        write_snapshot(&snapshot_path, &actual)
+    } else {
+        let approved = std::fs::read_to_string(&snapshot_path).with_context(|| {
+            format!("Failed to read corpus snapshot {}", snapshot_path.display())
+        })?;
+        let comparison = CorpusComparison::compare(
+            &CorpusSnapshot::parse(&approved),
+            &CorpusSnapshot::parse(&actual),
+        );
+        if comparison.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "{comparison}\nRun with --update-snapshots to approve the new output."
+            ))
+        }
+    };
+
+    let duration = started.elapsed();
+    let summary = report_results(
+        vec![TestResultDetail {
+            name: format!("corpus {name}"),
+            passed: outcome.is_ok(),
+            error_message: outcome.err().map(|e| format!("{e:#}")),
+            duration,
+            skipped: None,
+            strictness: run.options.strictness,
+        }],
+        run.options,
+        duration,
+    );
+    if !summary.is_success() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn write_snapshot(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write corpus snapshot {}", path.display()))
+}
+
+/// Results of the files the codemod changed or failed on, by path relative
+/// to `root`. Files are processed one at a time, in path order.
+async fn run_codemod(
+    run: &CorpusRun<'_>,
+    root: &Path,
+) -> Result<BTreeMap<String, CorpusFileResult>> {
+    let language: CodemodLang = run.language.parse().map_err(anyhow::Error::msg)?;
+    let config = CodemodExecutionConfig {
+        pre_run_callback: None,
+        progress_callback: Arc::new(None),
+        target_path: Some(root.to_path_buf()),
+        base_path: None,
+        include_globs: None,
+        explicit_files: None,
+        exclude_globs: None,
+        dry_run: true,
+        languages: Some(vec![run.language.to_string()]),
+        threads: None,
+        capabilities: Some(run.capabilities.clone()),
+        include_components: true,
+    };
+    let semantic_provider: Option<Arc<dyn SemanticProvider>> =
+        Some(Arc::new(LazySemanticProvider::file_scope()));
+
+    let mut files = config.collect_files();
+    files.sort();
+    let mut results = BTreeMap::new();
+    for file_path in files.iter().filter(|path| path.is_file()) {
+        // Binary and non-UTF-8 files cannot be transformed
+        let Ok(content) = std::fs::read_to_string(file_path) else {
+            continue;
+        };
+        let output = execute_codemod_with_quickjs(JssgExecutionOptions {
+            script_path: run.codemod_path,
+            resolver: run.resolver.clone(),
+            language,
+            file_path,
+            content: &content,
+            selector_config: None,
+            params: run.params.clone(),
+            matrix_values: None,
+            capabilities: Some(run.capabilities.clone()),
+            semantic_provider: semantic_provider.clone(),
+            metrics_context: None,
+            llm_request_handler: None,
+            shared_state_context: None,
+            runtime_event_callback: None,
+            cancellation_flag: None,
+            test_mode: false,
+            // Writes go to an overlay, so the corpus stays as checked out and
+            // later files see the original content
+            dry_run: true,
+            target_directory: root,
+            coverage: None,
+        })
+        .await;
+
+        match output {
+            Ok(CodemodOutput { primary, secondary }) => {
+                let changes = std::iter::once((file_path.clone(), primary, Some(content.clone())))
+                    .chain(
+                        secondary
+                            .into_iter()
+                            .map(|change| (change.path, change.result, change.base_content)),
+                    );
+                for (path, result, base_content) in changes {
+                    let ExecutionResult::Modified(modified) = result else {
+                        continue;
+                    };
+                    let original = base_content
+                        .or_else(|| std::fs::read_to_string(&path).ok())
+                        .unwrap_or_default();
+                    if original == modified.content && modified.rename_to.is_none() {
+                        continue;
+                    }
+                    results.insert(
+                        relative_path(root, &path),
+                        CorpusFileResult::Modified {
+                            original,
+                            modified: modified.content,
+                            renamed_to: modified
+                                .rename_to
+                                .map(|rename_to| relative_path(root, &rename_to)),
+                        },
+                    );
+                }
+            }
+            Err(error) => {
+                results.insert(
+                    relative_path(root, file_path),
+                    CorpusFileResult::Failed(error.to_string()),
+                );
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// `path` relative to `root` with `/` separators, so snapshots are portable.
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_sources_are_directories_or_pinned_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = dir.path().to_string_lossy().to_string();
+        assert!(matches!(
+            CorpusSource::parse(&spec).unwrap(),
+            CorpusSource::Directory(_)
+        ));
+
+        let commit = "3f6c0b9e2d41a7c85e0f9b1d2c3a4e5f60718293";
+        let git = CorpusSource::parse(&format!("https://github.com/org/app.git@{commit}")).unwrap();
+        assert_eq!(git.name(), "app@3f6c0b9e2d41");
+        let ssh = CorpusSource::parse(&format!("git@github.com:org/app.git@{commit}")).unwrap();
+        assert_eq!(ssh.name(), "app@3f6c0b9e2d41");
+
+        // Branches, tags and abbreviated commits can resolve differently later
+        for revision in ["v1.2.0", "release/2024", "3f6c0b9e2d41", "--upload-pack=sh"] {
+            assert!(
+                CorpusSource::parse(&format!("https://github.com/org/app.git@{revision}")).is_err(),
+                "{revision}"
+            );
+        }
+        assert!(CorpusSource::parse("git@github.com:org/app.git").is_err());
+        assert!(CorpusSource::parse("./does-not-exist").is_err());
+    }
+
+    #[test]
+    fn relative_paths_use_forward_slashes() {
+        let root = Path::new("/corpus");
+        assert_eq!(
+            relative_path(root, &root.join("src").join("a.ts")),
+            "src/a.ts"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use ast_grep_config::CombinedScan;
 use butterflow_core::execution::CodemodExecutionConfig;
+use butterflow_core::utils::parse_params;
 use clap::Args;
 use codemod_sandbox::sandbox::engine::{extract_selector_with_quickjs, SelectorEngineOptions};
 use codemod_sandbox::sandbox::resolvers::OxcResolver;
@@ -14,6 +15,7 @@ use std::{
 };
 
 use crate::utils::resolve_capabilities::{resolve_capabilities, ResolveCapabilitiesArgs};
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
 
 #[derive(Args, Debug)]
 pub struct Command {
@@ -32,6 +34,10 @@ pub struct Command {
     #[arg(long)]
     pub language: String,
 
+    /// Parameters to pass to the codemod's `getSelector`
+    #[arg(long = "param", value_name = "KEY=VALUE")]
+    pub params: Option<Vec<String>>,
+
     /// Allow fs access
     #[arg(long)]
     pub allow_fs: bool,
@@ -79,6 +85,10 @@ pub async fn handler(args: &Command) -> Result<()> {
         None,
         Some(script_base_dir.to_path_buf()),
     );
+    configure_languages_from_working_dir(&script_base_dir)?;
+
+    let params = parse_params(args.params.as_deref().unwrap_or(&[]))
+        .map_err(|e| anyhow::anyhow!("Failed to parse parameters: {}", e))?;
 
     let config = CodemodExecutionConfig {
         pre_run_callback: None,
@@ -92,6 +102,7 @@ pub async fn handler(args: &Command) -> Result<()> {
         languages: Some(vec![args.language.clone()]),
         threads: args.max_threads,
         capabilities: Some(capabilities),
+        include_components: true,
     };
 
     let selector_config = extract_selector_with_quickjs(SelectorEngineOptions {
@@ -100,6 +111,8 @@ pub async fn handler(args: &Command) -> Result<()> {
         resolver: resolver.clone(),
         capabilities: config.capabilities.clone(),
         target_directory: None,
+        params: Some(params),
+        matrix_values: None,
     })
     .await?;
     let combined_scan: Option<Arc<CombinedScan<CodemodLang>>> = selector_config
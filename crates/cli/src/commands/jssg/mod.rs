@@ -1,5 +1,6 @@
 pub mod bundle;
 pub mod config;
+pub mod corpus;
 pub mod exec;
 pub mod list_applicable;
 pub mod run;
@@ -3,17 +3,26 @@ use crate::engine::create_progress_callback;
 use crate::engine::create_registry_client;
 use crate::utils::resolve_capabilities::resolve_capabilities;
 use crate::utils::resolve_capabilities::ResolveCapabilitiesArgs;
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
 use crate::TelemetrySenderMutex;
 use crate::CLI_VERSION;
 use crate::{capabilities_security_callback::capabilities_security_callback, dirty_git_check};
 use anyhow::Result;
+use butterflow_core::affected;
 use butterflow_core::diff::{generate_unified_diff, DiffConfig, DiffMetadata, FileDiff};
+use butterflow_core::file_ops::apply_project_edits;
 use butterflow_core::report::{convert_diffs, convert_metrics, ExecutionReport};
+use butterflow_core::sarif::sarif_report;
 use butterflow_core::utils::generate_execution_id;
-use butterflow_core::utils::parse_params;
+use butterflow_core::utils::{get_result_cache_dir, parse_params, with_semantic_index};
 use butterflow_core::{execution::CodemodExecutionConfig, execution::PreRunCallback};
 use clap::Args;
-use codemod_sandbox::sandbox::engine::{CodemodOutput, ExecutionResult, JssgExecutionOptions};
+use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use codemod_sandbox::sandbox::engine::{
+    execute_project_codemod_with_quickjs, introduced_syntax_issues, summarize_syntax_issues,
+    syntax_check_language, CachedResult, CodemodFingerprint, CodemodOutput, ExecutionResult,
+    JssgExecutionOptions, ProjectEdit, ProjectExecutionOptions, ResultCache, ResultCacheKey,
+};
 use codemod_sandbox::sandbox::{
     engine::execute_codemod_with_quickjs, filesystem::RealFileSystem, resolvers::OxcResolver,
 };
@@ -22,10 +31,10 @@ use codemod_sandbox::{MetricsContext, SharedStateContext};
 use codemod_telemetry::send_event::BaseEvent;
 use language_core::SemanticProvider;
 use log::{debug, error, warn};
-use semantic_factory::LazySemanticProvider;
+use semantic_factory::{LazySemanticProvider, SemanticConfig};
 use std::sync::{Arc, Mutex};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     time::Instant,
 };
@@ -80,6 +89,11 @@ pub struct Command {
     #[arg(long)]
     pub semantic_workspace: Option<PathBuf>,
 
+    /// Only run on these files (or the files changed in a git range such as
+    /// `main..HEAD`) and the files that transitively import them
+    #[arg(long = "affected-by", value_name = "FILES|GIT_RANGE")]
+    pub affected_by: Option<Vec<String>>,
+
     /// Disable colored diff output in dry-run mode
     #[arg(long)]
     pub no_color: bool,
@@ -88,9 +102,29 @@ pub struct Command {
     #[arg(long)]
     pub report: bool,
 
+    /// Write the changes a dry run would make to this file as SARIF
+    #[arg(long, value_name = "FILE", requires = "dry_run")]
+    pub sarif: Option<PathBuf>,
+
     /// Show verbose output (e.g. shared state after execution)
     #[arg(long, short)]
     pub verbose: bool,
+
+    /// Re-execute every file instead of reusing cached results
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Refuse to write files the codemod leaves with syntax errors they did not have
+    #[arg(long)]
+    pub check_syntax: bool,
+
+    /// Refuse to write files the codemod would change again when re-run on its output
+    #[arg(long)]
+    pub check_idempotent: bool,
+
+    /// Skip `.vue` and `.svelte` files, which JavaScript and TypeScript codemods run on by default
+    #[arg(long)]
+    pub no_components: bool,
 }
 
 pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<()> {
@@ -140,6 +174,7 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         None,
         Some(script_base_dir.to_path_buf()),
     );
+    configure_languages_from_working_dir(&script_base_dir)?;
 
     let capabilities_security_callback = capabilities_security_callback(args.no_interactive, None);
     let pre_run_callback = PreRunCallback {
@@ -154,7 +189,7 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         })),
     };
 
-    let config = CodemodExecutionConfig {
+    let mut config = CodemodExecutionConfig {
         pre_run_callback: Some(pre_run_callback),
         progress_callback: Arc::new(Some(create_progress_callback())),
         target_path: Some(target_directory.to_path_buf()),
@@ -166,6 +201,7 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         languages: Some(vec![args.language.clone()]),
         threads: args.max_threads,
         capabilities: Some(capabilities),
+        include_components: !args.no_components,
     };
 
     let started = Instant::now();
@@ -176,13 +212,28 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
     // Create semantic provider once, shared across all files
     let semantic_provider: Option<Arc<dyn SemanticProvider>> =
         if let Some(workspace_root) = &args.semantic_workspace {
-            Some(Arc::new(LazySemanticProvider::workspace_scope(
+            Some(Arc::new(workspace_semantic_provider(
                 workspace_root.clone(),
             )))
         } else {
             Some(Arc::new(LazySemanticProvider::file_scope()))
         };
 
+    if let Some(affected_by) = &args.affected_by {
+        let selection = affected::select_affected(
+            &target_directory,
+            affected_by,
+            config.collect_files(),
+            semantic_provider.as_deref(),
+        )?;
+        debug!(
+            "Limiting run to {} file(s) affected by {} changed file(s)",
+            selection.files.len(),
+            selection.changed
+        );
+        config.explicit_files = Some(selection.files);
+    }
+
     // For workspace scope semantic analysis, pre-index all target files
     if let Some(ref provider) = semantic_provider {
         if provider.mode() == language_core::ProviderMode::WorkspaceScope {
@@ -197,6 +248,13 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         }
     }
 
+    let result_cache = open_result_cache(
+        args,
+        js_file_path,
+        config.capabilities.as_ref(),
+        semantic_provider.as_ref(),
+    );
+
     let capabilities_for_closure = config.capabilities.clone();
     let language: codemod_sandbox::CodemodLang = args
         .language
@@ -220,180 +278,367 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
     // Clone target_directory for use after the closure moves it
     let target_directory_for_report = target_directory.clone();
 
-    let _ = config.execute(move |file_path, _config| {
-        // Only process files
-        if !file_path.is_file() {
-            return;
-        }
-
+    let project_handled = if std::fs::read_to_string(js_file_path)
+        .map(|source| source.contains("transformProject"))
+        .unwrap_or(false)
+    {
         let runtime_event_buffer = super::RuntimeEventBuffer::new();
-        let runtime_event_callback = runtime_event_buffer.callback_for_title(
-            super::display_path_title(file_path, Some(&target_directory)),
-        );
-        let runtime_event_output = runtime_event_output.clone();
-
-        // Use a tokio runtime to handle the async execution within the sync callback
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(async {
-            // Read file content
-            let content = match tokio::fs::read_to_string(&file_path).await {
-                Ok(content) => content,
-                Err(e) => {
-                    warn!("Failed to read file {}: {}", file_path.display(), e);
-                    return;
-                }
-            };
+        let output = execute_project_codemod_with_quickjs(ProjectExecutionOptions {
+            script_path: js_file_path,
+            resolver: resolver.clone(),
+            language,
+            files: config
+                .collect_files()
+                .into_iter()
+                .filter(|path| path.is_file())
+                .collect(),
+            params: Some(params.clone()),
+            matrix_values: None,
+            capabilities: capabilities_for_closure.clone(),
+            semantic_provider: semantic_provider.clone(),
+            metrics_context: Some(metrics_context.clone()),
+            llm_request_handler: None,
+            shared_state_context: Some(shared_state_context.clone()),
+            runtime_event_callback: Some(runtime_event_buffer.callback_for_title("project")),
+            cancellation_flag: None,
+            dry_run: args.dry_run,
+            target_directory: &target_directory,
+        })
+        .await;
+        runtime_event_output.flush(&runtime_event_buffer);
 
-            let options = JssgExecutionOptions {
-                script_path: js_file_path,
-                resolver: resolver.clone(),
-                language,
-                file_path,
-                content: &content,
-                selector_config: None,
-                params: Some(params.clone()),
-                matrix_values: None,
-                capabilities: capabilities_for_closure.clone(),
-                semantic_provider: semantic_provider.clone(),
-                metrics_context: Some(metrics_context_clone.clone()),
-                llm_request_handler: None,
-                shared_state_context: Some(shared_state_context_clone.clone()),
-                runtime_event_callback: Some(runtime_event_callback),
-                cancellation_flag: None,
-                test_mode: false,
-                dry_run: false,
-                target_directory: &target_directory,
-            };
+        match output {
+            Ok(Some(output)) => {
+                handle_project_edits(
+                    &output.edits,
+                    args.dry_run,
+                    &diff_config,
+                    diff_collector.as_ref(),
+                )?;
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to execute transformProject:\n{}",
+                    e
+                ));
+            }
+        }
+    } else {
+        false
+    };
 
-            // Execute the codemod on this file
-            match execute_codemod_with_quickjs(options).await {
-                Ok(CodemodOutput { primary, secondary }) => {
-                    // Collect all file changes: primary + secondary from jssgTransform
-                    let mut all_changes: Vec<(
-                        std::path::PathBuf,
-                        &codemod_sandbox::sandbox::engine::ExecutionResult,
-                    )> = Vec::new();
-                    if let ExecutionResult::Modified(_) = &primary {
-                        all_changes.push((file_path.to_path_buf(), &primary));
+    if !project_handled {
+        let _ = config.execute(move |file_path, _config| {
+            // Only process files
+            if !file_path.is_file() {
+                return;
+            }
+
+            let runtime_event_buffer = super::RuntimeEventBuffer::new();
+            let runtime_event_callback = runtime_event_buffer.callback_for_title(
+                super::display_path_title(file_path, Some(&target_directory)),
+            );
+            let runtime_event_output = runtime_event_output.clone();
+
+            // Use a tokio runtime to handle the async execution within the sync callback
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                // Read file content
+                let content = match tokio::fs::read_to_string(&file_path).await {
+                    Ok(content) => content,
+                    Err(e) => {
+                        warn!("Failed to read file {}: {}", file_path.display(), e);
+                        return;
                     }
-                    for change in &secondary {
-                        if let ExecutionResult::Modified(_) = &change.result {
-                            all_changes.push((change.path.clone(), &change.result));
+                };
+
+                let result_cache_key = result_cache.as_ref().map(|(_, fingerprint)| {
+                    ResultCacheKey::new(
+                        fingerprint,
+                        Some(&params),
+                        None,
+                        language,
+                        file_path,
+                        &content,
+                    )
+                });
+                let cached_output = result_cache
+                    .as_ref()
+                    .zip(result_cache_key.as_ref())
+                    .and_then(|((cache, _), key)| cache.get(key))
+                    .map(CachedResult::into_output);
+                let cache_hit = cached_output.is_some();
+
+                let options = JssgExecutionOptions {
+                    script_path: js_file_path,
+                    resolver: resolver.clone(),
+                    language,
+                    file_path,
+                    content: &content,
+                    selector_config: None,
+                    params: Some(params.clone()),
+                    matrix_values: None,
+                    capabilities: capabilities_for_closure.clone(),
+                    semantic_provider: semantic_provider.clone(),
+                    metrics_context: Some(metrics_context_clone.clone()),
+                    llm_request_handler: None,
+                    shared_state_context: Some(shared_state_context_clone.clone()),
+                    runtime_event_callback: Some(runtime_event_callback),
+                    cancellation_flag: None,
+                    test_mode: false,
+                    dry_run: false,
+                    target_directory: &target_directory,
+                    coverage: None,
+                };
+
+                // Execute the codemod on this file, unless an identical run is cached
+                let execution_result = match cached_output {
+                    Some(output) => Ok(output),
+                    None => execute_codemod_with_quickjs(options).await,
+                };
+                if let (Some((cache, _)), Some(key), false, Ok(output)) = (
+                    result_cache.as_ref(),
+                    result_cache_key.as_ref(),
+                    cache_hit,
+                    &execution_result,
+                ) {
+                    if let Some(cached) = CachedResult::from_output(output) {
+                        if let Err(e) = cache.insert(key, &cached) {
+                            debug!("Failed to cache result for {}: {}", file_path.display(), e);
                         }
                     }
+                }
 
-                    for (change_path, change_result) in &all_changes {
-                        if let ExecutionResult::Modified(ref modified) = change_result {
-                            let write_path = modified.rename_to.as_deref().unwrap_or(change_path);
-                            if !config.dry_run {
-                                if let Err(e) =
-                                    tokio::fs::write(write_path, &modified.content).await
-                                {
-                                    if let Ok(mut errors) = execution_errors_for_closure.lock() {
-                                        errors.push(format!(
-                                            "Failed to write modified file {}: {}",
-                                            write_path.display(),
-                                            e
-                                        ));
+                match execution_result {
+                    Ok(CodemodOutput { primary, secondary }) => {
+                        // Collect all file changes: primary + secondary from jssgTransform
+                        let mut all_changes: Vec<(
+                            std::path::PathBuf,
+                            &codemod_sandbox::sandbox::engine::ExecutionResult,
+                        )> = Vec::new();
+                        if let ExecutionResult::Modified(_) = &primary {
+                            all_changes.push((file_path.to_path_buf(), &primary));
+                        }
+                        for change in &secondary {
+                            if change.is_stale() {
+                                if let Ok(mut errors) = execution_errors_for_closure.lock() {
+                                    errors.push(format!(
+                                        "Skipping change to {}: file was modified after the change was computed",
+                                        change.path.display()
+                                    ));
+                                }
+                                continue;
+                            }
+                            if let ExecutionResult::Modified(_) = &change.result {
+                                all_changes.push((change.path.clone(), &change.result));
+                            }
+                        }
+
+                        // Guards requested by --check-syntax and --check-idempotent
+                        let mut refused = Vec::new();
+                        for (change_path, change_result) in &all_changes {
+                            let ExecutionResult::Modified(modified) = change_result else {
+                                continue;
+                            };
+                            let original = if change_path == file_path {
+                                content.clone()
+                            } else {
+                                tokio::fs::read_to_string(change_path)
+                                    .await
+                                    .unwrap_or_default()
+                            };
+                            let syntax_language = args
+                                .check_syntax
+                                .then(|| syntax_check_language(file_path, language, change_path))
+                                .flatten();
+                            let issues = match syntax_language {
+                                Some(syntax_language) => introduced_syntax_issues(
+                                    syntax_language,
+                                    &original,
+                                    &modified.content,
+                                ),
+                                None => Vec::new(),
+                            };
+                            let reason = if !issues.is_empty() {
+                                Some(format!(
+                                    "the output no longer parses: {}",
+                                    summarize_syntax_issues(&issues)
+                                ))
+                            } else if args.check_idempotent && change_path == file_path {
+                                // Reruns must not count metrics or touch shared state
+                                let rerun = execute_codemod_with_quickjs(JssgExecutionOptions {
+                                    script_path: js_file_path,
+                                    resolver: resolver.clone(),
+                                    language,
+                                    file_path,
+                                    content: &modified.content,
+                                    selector_config: None,
+                                    params: Some(params.clone()),
+                                    matrix_values: None,
+                                    capabilities: capabilities_for_closure.clone(),
+                                    semantic_provider: semantic_provider.clone(),
+                                    metrics_context: None,
+                                    llm_request_handler: None,
+                                    shared_state_context: None,
+                                    runtime_event_callback: None,
+                                    cancellation_flag: None,
+                                    test_mode: false,
+                                    dry_run: true,
+                                    target_directory: &target_directory,
+                                    coverage: None,
+                                })
+                                .await;
+                                match rerun.map(|output| output.primary) {
+                                    Ok(ExecutionResult::Modified(again))
+                                        if again.content != modified.content =>
+                                    {
+                                        Some(
+                                            "a second run changes the output again".to_string(),
+                                        )
                                     }
-                                } else {
-                                    // If renamed, delete the original file
-                                    if modified.rename_to.is_some()
-                                        && write_path != change_path.as_path()
+                                    Ok(_) => None,
+                                    Err(e) => Some(format!("a second run failed: {e}")),
+                                }
+                            } else {
+                                None
+                            };
+                            if let Some(reason) = reason {
+                                if let Ok(mut errors) = execution_errors_for_closure.lock() {
+                                    errors.push(format!(
+                                        "Refusing to write {}: {}",
+                                        change_path.display(),
+                                        reason
+                                    ));
+                                }
+                                refused.push(change_path.clone());
+                            }
+                        }
+                        all_changes.retain(|(change_path, _)| !refused.contains(change_path));
+
+                        for (change_path, change_result) in &all_changes {
+                            if let ExecutionResult::Modified(ref modified) = change_result {
+                                let write_path =
+                                    modified.rename_to.as_deref().unwrap_or(change_path);
+                                if !config.dry_run {
+                                    if let Err(e) =
+                                        tokio::fs::write(write_path, &modified.content).await
                                     {
-                                        if let Err(e) = tokio::fs::remove_file(change_path).await {
-                                            if let Ok(mut errors) =
-                                                execution_errors_for_closure.lock()
+                                        if let Ok(mut errors) = execution_errors_for_closure.lock()
+                                        {
+                                            errors.push(format!(
+                                                "Failed to write modified file {}: {}",
+                                                write_path.display(),
+                                                e
+                                            ));
+                                        }
+                                    } else {
+                                        // If renamed, delete the original file
+                                        if modified.rename_to.is_some()
+                                            && write_path != change_path.as_path()
+                                        {
+                                            if let Err(e) =
+                                                tokio::fs::remove_file(change_path).await
                                             {
-                                                errors.push(format!(
-                                                    "Failed to remove original file {}: {}",
+                                                if let Ok(mut errors) =
+                                                    execution_errors_for_closure.lock()
+                                                {
+                                                    errors.push(format!(
+                                                        "Failed to remove original file {}: {}",
+                                                        change_path.display(),
+                                                        e
+                                                    ));
+                                                }
+                                            } else {
+                                                debug!(
+                                                    "Renamed file: {} -> {}",
                                                     change_path.display(),
-                                                    e
-                                                ));
+                                                    write_path.display()
+                                                );
                                             }
                                         } else {
-                                            debug!(
-                                                "Renamed file: {} -> {}",
-                                                change_path.display(),
-                                                write_path.display()
+                                            debug!("Modified file: {}", change_path.display());
+                                        }
+                                        // Notify semantic provider of the change
+                                        if let Some(ref provider) = semantic_provider {
+                                            let _ = provider.notify_file_processed(
+                                                write_path,
+                                                &modified.content,
                                             );
                                         }
-                                    } else {
-                                        debug!("Modified file: {}", change_path.display());
-                                    }
-                                    // Notify semantic provider of the change
-                                    if let Some(ref provider) = semantic_provider {
-                                        let _ = provider
-                                            .notify_file_processed(write_path, &modified.content);
                                     }
-                                }
-                            } else {
-                                // Dry-run mode: print diff
-                                if modified.rename_to.is_some() {
-                                    println!(
-                                        "Rename: {} -> {}",
-                                        change_path.display(),
-                                        write_path.display()
-                                    );
-                                }
-                                // For secondary changes, read original content from disk
-                                let original = if change_path == file_path {
-                                    content.clone()
                                 } else {
-                                    tokio::fs::read_to_string(change_path)
-                                        .await
-                                        .unwrap_or_default()
-                                };
-                                let diff = generate_unified_diff(
-                                    change_path,
-                                    &original,
-                                    &modified.content,
-                                    &diff_config,
-                                    DiffMetadata::default(),
-                                );
-                                diff.print();
-
-                                // Collect plain-text diff for report
-                                if let Some(ref collector) = diff_collector_clone {
-                                    let plain_config = DiffConfig {
-                                        color: false,
-                                        ..DiffConfig::default()
+                                    // Dry-run mode: print diff
+                                    if modified.rename_to.is_some() {
+                                        println!(
+                                            "Rename: {} -> {}",
+                                            change_path.display(),
+                                            write_path.display()
+                                        );
+                                    }
+                                    // For secondary changes, read original content from disk
+                                    let original = if change_path == file_path {
+                                        content.clone()
+                                    } else {
+                                        tokio::fs::read_to_string(change_path)
+                                            .await
+                                            .unwrap_or_default()
                                     };
-                                    let plain_diff = generate_unified_diff(
+                                    let diff = generate_unified_diff(
                                         change_path,
                                         &original,
                                         &modified.content,
-                                        &plain_config,
-                                        DiffMetadata {
-                                            step_id: Some("jssg".to_string()),
-                                            step_name: Some("JSSG".to_string()),
-                                            ..DiffMetadata::default()
-                                        },
+                                        &diff_config,
+                                        DiffMetadata::default(),
                                     );
-                                    if let Ok(mut diffs) = collector.lock() {
-                                        diffs.push(plain_diff);
+                                    diff.print();
+
+                                    // Collect plain-text diff for report
+                                    if let Some(ref collector) = diff_collector_clone {
+                                        let plain_config = DiffConfig {
+                                            color: false,
+                                            ..DiffConfig::default()
+                                        };
+                                        let plain_diff = generate_unified_diff(
+                                            change_path,
+                                            &original,
+                                            &modified.content,
+                                            &plain_config,
+                                            DiffMetadata {
+                                                step_id: Some("jssg".to_string()),
+                                                step_name: Some("JSSG".to_string()),
+                                                ..DiffMetadata::default()
+                                            },
+                                        );
+                                        if let Ok(mut diffs) = collector.lock() {
+                                            diffs.push(plain_diff);
+                                        }
                                     }
-                                }
 
-                                debug!("Would modify file (dry run): {}", change_path.display());
+                                    debug!(
+                                        "Would modify file (dry run): {}",
+                                        change_path.display()
+                                    );
+                                }
                             }
                         }
                     }
-                }
-                Err(e) => {
-                    if let Ok(mut errors) = execution_errors_for_closure.lock() {
-                        errors.push(format!(
-                            "Failed to execute codemod on {}:\n{}",
-                            file_path.display(),
-                            e
-                        ));
+                    Err(e) => {
+                        if let Ok(mut errors) = execution_errors_for_closure.lock() {
+                            errors.push(format!(
+                                "Failed to execute codemod on {}:\n{}",
+                                file_path.display(),
+                                e
+                            ));
+                        }
                     }
                 }
-            }
-        });
+            });
 
-        runtime_event_output.flush(&runtime_event_buffer);
-    });
+            runtime_event_output.flush(&runtime_event_buffer);
+        });
+    }
 
     let metrics_data = metrics_context.get_all();
 
@@ -424,6 +669,26 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         .unwrap_or_default();
     let files_modified = collected_diffs.len();
 
+    if let Some(sarif_path) = &args.sarif {
+        let rule_id = js_file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "codemod".to_string());
+        let log = sarif_report(
+            &collected_diffs,
+            &target_directory_for_report,
+            &rule_id,
+            CLI_VERSION,
+        );
+        std::fs::write(sarif_path, serde_json::to_string_pretty(&log)?).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to write SARIF output to {}: {}",
+                sarif_path.display(),
+                e
+            )
+        })?;
+    }
+
     if crate::utils::metrics::should_show_report(
         args.report,
         args.no_interactive,
@@ -482,3 +747,125 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
 
     Ok(())
 }
+
+/// Workspace-scope provider backed by the persistent semantic index.
+fn workspace_semantic_provider(root: PathBuf) -> LazySemanticProvider {
+    LazySemanticProvider::new(with_semantic_index(SemanticConfig::workspace_scope(root)))
+}
+
+/// Open the per-file result cache unless disabled. Runs with capabilities or
+/// workspace-wide semantics depend on more than the file being transformed,
+/// so they always execute.
+fn open_result_cache(
+    args: &Command,
+    js_file_path: &Path,
+    capabilities: Option<&HashSet<LlrtSupportedModules>>,
+    semantic_provider: Option<&Arc<dyn SemanticProvider>>,
+) -> Option<(ResultCache, CodemodFingerprint)> {
+    if args.no_cache
+        || capabilities.is_some_and(|capabilities| !capabilities.is_empty())
+        || semantic_provider
+            .is_some_and(|provider| provider.mode() == language_core::ProviderMode::WorkspaceScope)
+    {
+        return None;
+    }
+
+    let cache_dir = get_result_cache_dir().ok()?;
+    match CodemodFingerprint::for_script(js_file_path) {
+        Ok(fingerprint) if fingerprint.cacheable => {
+            Some((ResultCache::new(cache_dir), fingerprint))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            debug!(
+                "Result cache disabled, failed to fingerprint {}: {}",
+                js_file_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Print or apply the edits returned by `transformProject`. In dry-run mode
+/// each edit is rendered as a diff; otherwise the batch is applied atomically.
+fn handle_project_edits(
+    edits: &[ProjectEdit],
+    dry_run: bool,
+    diff_config: &DiffConfig,
+    diff_collector: Option<&Arc<Mutex<Vec<FileDiff>>>>,
+) -> Result<()> {
+    let mut changes: Vec<(PathBuf, String, String)> = Vec::new();
+    for edit in edits {
+        match edit {
+            ProjectEdit::Edit { path, content } => changes.push((
+                path.clone(),
+                std::fs::read_to_string(path).unwrap_or_default(),
+                content.clone(),
+            )),
+            ProjectEdit::Create { path, content } => {
+                changes.push((path.clone(), String::new(), content.clone()))
+            }
+            ProjectEdit::Rename { from, to, content } => {
+                let original = std::fs::read_to_string(from).unwrap_or_default();
+                if dry_run {
+                    println!("Rename: {} -> {}", from.display(), to.display());
+                }
+                changes.push((from.clone(), original.clone(), String::new()));
+                changes.push((
+                    to.clone(),
+                    String::new(),
+                    content.clone().unwrap_or(original),
+                ));
+            }
+            ProjectEdit::Delete { path } => changes.push((
+                path.clone(),
+                std::fs::read_to_string(path).unwrap_or_default(),
+                String::new(),
+            )),
+        }
+    }
+
+    for (path, original, modified) in &changes {
+        if dry_run {
+            generate_unified_diff(
+                path,
+                original,
+                modified,
+                diff_config,
+                DiffMetadata::default(),
+            )
+            .print();
+        }
+        if let Some(collector) = diff_collector {
+            let plain_config = DiffConfig {
+                color: false,
+                ..DiffConfig::default()
+            };
+            let plain_diff = generate_unified_diff(
+                path,
+                original,
+                modified,
+                &plain_config,
+                DiffMetadata {
+                    step_id: Some("jssg".to_string()),
+                    step_name: Some("JSSG".to_string()),
+                    ..DiffMetadata::default()
+                },
+            );
+            if let Ok(mut diffs) = collector.lock() {
+                diffs.push(plain_diff);
+            }
+        }
+    }
+
+    if !dry_run {
+        apply_project_edits(edits).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to apply transformProject edits, all changes were rolled back: {e}"
+            )
+        })?;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,18 @@
 use anyhow::Result;
+use butterflow_core::engine::env_llm_request_handler;
+use butterflow_core::llm_usage::LlmUsageContext;
 use clap::Args;
+use codemod_sandbox::llm::cassette::{LlmCassette, CASSETTE_FILE_NAME};
+use codemod_sandbox::llm::LlmRequestHandler;
 use codemod_sandbox::metrics::MetricEntry;
-use codemod_sandbox::sandbox::engine::{CodemodOutput, JssgExecutionOptions};
+use codemod_sandbox::sandbox::engine::coverage::{CoverageCollector, CoverageReport};
+use codemod_sandbox::sandbox::engine::{
+    CachedResult, CodemodFingerprint, CodemodOutput, JssgExecutionOptions, ResultCache,
+    ResultCacheKey,
+};
+use codemod_sandbox::workflow_global::{clear_step_outputs, get_step_outputs};
 use codemod_sandbox::MetricsData;
+use codemod_sandbox::SharedStateContext;
 use codemod_telemetry::send_event::BaseEvent;
 use language_core::SemanticProvider;
 use semantic_factory::LazySemanticProvider;
@@ -22,12 +32,13 @@ use codemod_sandbox::{
     utils::project_discovery::find_tsconfig,
 };
 use testing_utils::{
-    map_execution_result, ExecutionRequest, ReporterType, TestOptions, TestRunner, TestSource,
+    map_execution_result, ExecutionRequest, SemanticMode, TestOptions, TestRunner, TestSource,
     TransformationResult,
 };
 
 use crate::commands::TelemetrySenderExt;
 use crate::utils::resolve_capabilities::{resolve_capabilities, ResolveCapabilitiesArgs};
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
 use crate::{TelemetrySenderMutex, CLI_VERSION};
 
 use super::config::{ResolvedTestConfig, TestConfig};
@@ -55,6 +66,41 @@ impl SharedMetricsState {
     }
 }
 
+/// State of the test case being run, shared by its executions.
+#[derive(Default)]
+struct FixtureRun {
+    shared_state: SharedStateContext,
+    metric_totals: HashMap<String, u64>,
+}
+
+/// LLM cassettes of the test cases. Recordings start empty once per run and
+/// collect every execution of the case; replays reload the file each time so
+/// a re-recorded cassette is picked up in watch mode.
+#[derive(Clone, Default)]
+struct FixtureCassettes {
+    record: bool,
+    recordings: Arc<Mutex<HashMap<PathBuf, LlmCassette>>>,
+}
+
+impl FixtureCassettes {
+    fn handler(&self, test_case_root: &Path) -> Result<LlmRequestHandler> {
+        let path = test_case_root.join(CASSETTE_FILE_NAME);
+        if !self.record {
+            let cassette = LlmCassette::replay(path).map_err(anyhow::Error::msg)?;
+            return Ok(cassette.handler(None));
+        }
+
+        let cassette = self
+            .recordings
+            .lock()
+            .map_err(|_| anyhow::anyhow!("LLM cassette lock poisoned"))?
+            .entry(path.clone())
+            .or_insert_with(|| LlmCassette::record(path))
+            .clone();
+        Ok(cassette.handler(Some(env_llm_request_handler(LlmUsageContext::new()))))
+    }
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct Command {
     /// Path to the codemod file to test
@@ -95,7 +141,7 @@ pub struct Command {
     #[arg(long)]
     pub watch: bool,
 
-    /// Output format (console, json, terse)
+    /// Output format (console, json, terse, junit, tap)
     #[arg(long, default_value = "console")]
     pub reporter: String,
 
@@ -126,6 +172,36 @@ pub struct Command {
     #[arg(long)]
     pub semantic_workspace: bool,
 
+    /// Re-run the codemod on each expected output and fail if it changes
+    #[arg(long)]
+    pub check_idempotent: bool,
+
+    /// Do not fail tests whose output has syntax errors the input did not
+    #[arg(long)]
+    pub no_syntax_check: bool,
+
+    /// Call the LLM configured by the LLM_* environment variables and record
+    /// its responses in each test's llm-cassette.json instead of replaying them
+    #[arg(long)]
+    pub llm_record: bool,
+
+    /// Run the codemod over a corpus, a directory or a pinned repository
+    /// (<repository>@<commit sha>), and compare its diffs with an approved snapshot
+    #[arg(long, value_name = "SOURCE")]
+    pub corpus: Option<String>,
+
+    /// Approved corpus snapshot (default: <test directory>/__corpus__/<name>.diff)
+    #[arg(long, value_name = "FILE", requires = "corpus")]
+    pub corpus_snapshot: Option<PathBuf>,
+
+    /// Collect line, branch and function coverage of the codemod's sources
+    #[arg(long)]
+    pub coverage: bool,
+
+    /// Directory to write the lcov coverage report to
+    #[arg(long, value_name = "DIR", default_value = "coverage")]
+    pub coverage_dir: PathBuf,
+
     /// Allow fs access
     #[arg(long)]
     pub allow_fs: bool,
@@ -226,8 +302,10 @@ async fn handler_impl(args: &Command) -> Result<()> {
         strictness,
         language: global_config.language.clone(),
         expected_extension: global_config.expected_extension.clone(),
+        check_syntax: !args.no_syntax_check,
+        check_idempotent: args.check_idempotent,
     };
-    let runtime_event_output = if matches!(global_config.reporter, ReporterType::Json) {
+    let runtime_event_output = if global_config.reporter.is_machine_readable() {
         super::RuntimeEventOutput::stderr()
     } else {
         super::RuntimeEventOutput::stdout()
@@ -248,18 +326,57 @@ async fn handler_impl(args: &Command) -> Result<()> {
         None,
         Some(script_base_dir.to_path_buf()),
     );
+    configure_languages_from_working_dir(&script_base_dir)?;
 
     let tsconfig_path = find_tsconfig(&script_base_dir);
     let resolver = Arc::new(OxcResolver::new(script_base_dir, tsconfig_path)?);
 
+    if let Some(corpus) = &args.corpus {
+        return super::corpus::handler(super::corpus::CorpusRun {
+            source: corpus,
+            snapshot_path: args.corpus_snapshot.clone(),
+            test_directory: &test_directory,
+            codemod_path,
+            resolver,
+            language: default_language_str,
+            params: global_config.params.clone(),
+            capabilities,
+            options: &options,
+        })
+        .await;
+    }
+
+    if args.coverage && global_config.watch {
+        anyhow::bail!("--coverage cannot be used in watch mode");
+    }
+    // Modules under the working directory are covered, except dependencies
+    let coverage = args
+        .coverage
+        .then(|| Arc::new(CoverageCollector::new(&current_dir)));
+
     let codemod_path_clone = codemod_path.to_path_buf();
     let base_config_clone = base_config.clone();
     let args_clone = args.clone();
     let current_dir_clone = current_dir.clone();
+    let coverage_clone = coverage.clone();
     let shared_metrics = Arc::new(Mutex::new(HashMap::<PathBuf, SharedMetricsState>::new()));
+    let fixture_run = Arc::new(Mutex::new(FixtureRun::default()));
     let semantic_provider: Option<Arc<dyn SemanticProvider>> =
         Some(Arc::new(LazySemanticProvider::file_scope()));
     let update_snapshots = args.update_snapshots;
+    let cassettes = FixtureCassettes {
+        record: args.llm_record,
+        ..Default::default()
+    };
+    // In watch mode, fixtures whose input and codemod are unchanged since the
+    // previous run are served from the result cache.
+    let result_cache = if global_config.watch {
+        butterflow_core::utils::get_result_cache_dir()
+            .ok()
+            .map(ResultCache::new)
+    } else {
+        None
+    };
     let execution_fn = Box::new(
         move |request: ExecutionRequest, capabilities: Option<HashSet<LlrtSupportedModules>>| {
             let codemod_path = codemod_path_clone.clone();
@@ -273,7 +390,12 @@ async fn handler_impl(args: &Command) -> Result<()> {
             let current_dir = current_dir_clone.clone();
             let semantic_provider = semantic_provider.clone();
             let shared_metrics = shared_metrics.clone();
+            let fixture_run = fixture_run.clone();
+            let settings = request.settings;
             let runtime_event_output = runtime_event_output.clone();
+            let result_cache = result_cache.clone();
+            let cassettes = cassettes.clone();
+            let coverage = coverage_clone.clone();
 
             Box::pin(async move {
                 let logical_input_path = logical_input_path.unwrap_or_else(|| input_path.clone());
@@ -311,7 +433,37 @@ async fn handler_impl(args: &Command) -> Result<()> {
                     .parse()
                     .map_err(|e: String| anyhow::anyhow!("{}", e))?;
 
-                let metrics_context = {
+                // Settings from the fixture's test.yaml
+                let mut params = test_config.params.clone().unwrap_or_default();
+                params.extend(settings.params.clone());
+                let params = (!params.is_empty()).then_some(params);
+                let matrix_values = (!settings.matrix.is_empty()).then(|| settings.matrix.clone());
+                let semantic_workspace = match settings.semantic {
+                    Some(mode) => mode == SemanticMode::Workspace,
+                    None => test_config.semantic_workspace,
+                };
+                // Idempotency reruns leave the fixture's state and metrics alone
+                let shared_state = if request.rerun {
+                    SharedStateContext::with_initial_state(settings.state.clone())
+                } else {
+                    let mut fixture_run = fixture_run
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("fixture run lock poisoned"))?;
+                    if request.starts_case {
+                        clear_step_outputs("jssg").map_err(|e| anyhow::anyhow!("{e}"))?;
+                        *fixture_run = FixtureRun {
+                            shared_state: SharedStateContext::with_initial_state(
+                                settings.state.clone(),
+                            ),
+                            metric_totals: HashMap::new(),
+                        };
+                    }
+                    fixture_run.shared_state.clone()
+                };
+
+                let metrics_context = if request.rerun {
+                    MetricsContext::new()
+                } else {
                     let mut shared_metrics = shared_metrics
                         .lock()
                         .map_err(|_| anyhow::anyhow!("shared metrics lock poisoned"))?;
@@ -325,7 +477,7 @@ async fn handler_impl(args: &Command) -> Result<()> {
                 // For directory snapshot tests with --semantic-workspace,
                 // create a workspace-scoped provider using the temp dir
                 // and pre-index all files (matching jssg run behavior).
-                let semantic_provider = if test_config.semantic_workspace {
+                let semantic_provider = if semantic_workspace {
                     if let Some(ref ws_root) = workspace_root {
                         let provider: Arc<dyn SemanticProvider> =
                             Arc::new(LazySemanticProvider::workspace_scope(ws_root.clone()));
@@ -339,6 +491,14 @@ async fn handler_impl(args: &Command) -> Result<()> {
                             }
                         }
                         Some(provider)
+                    } else if settings.semantic == Some(SemanticMode::Workspace) {
+                        // Single-file fixtures only index their input, not
+                        // the expected output next to it
+                        let provider: Arc<dyn SemanticProvider> = Arc::new(
+                            LazySemanticProvider::workspace_scope(test_case_dir.to_path_buf()),
+                        );
+                        let _ = provider.notify_file_processed(&input_path, &input_code);
+                        Some(provider)
                     } else {
                         semantic_provider
                     }
@@ -350,6 +510,53 @@ async fn handler_impl(args: &Command) -> Result<()> {
                     super::display_path_title(&logical_input_path, Some(&current_dir)),
                 );
 
+                // Directory fixtures run in fresh temporary workspaces and
+                // metrics snapshots need a real execution, so only plain
+                // single-file fixtures are cached.
+                let result_cache_key = result_cache
+                    .as_ref()
+                    .filter(|_| {
+                        !request.rerun
+                            && workspace_root.is_none()
+                            && !semantic_workspace
+                            && settings.state.is_empty()
+                            && !settings.expect.has_checks()
+                            && !test_case_dir.join("metrics.json").exists()
+                            && !cassettes.record
+                            && !request.test_case_root.join(CASSETTE_FILE_NAME).exists()
+                            && !capabilities
+                                .as_ref()
+                                .is_some_and(|capabilities| !capabilities.is_empty())
+                    })
+                    .and_then(|_| CodemodFingerprint::for_script(&codemod_path).ok())
+                    .filter(|fingerprint| fingerprint.cacheable)
+                    .map(|fingerprint| {
+                        ResultCacheKey::new(
+                            &fingerprint,
+                            params.as_ref(),
+                            matrix_values.as_ref(),
+                            language_enum,
+                            &logical_input_path,
+                            &input_code,
+                        )
+                    });
+                if let Some(cached) = result_cache
+                    .as_ref()
+                    .zip(result_cache_key.as_ref())
+                    .and_then(|(cache, key)| cache.get(key))
+                {
+                    let _ = finish_metrics_collection(
+                        shared_metrics.as_ref(),
+                        &metrics_output_path,
+                        None,
+                        true,
+                    );
+                    return Ok(map_execution_result(
+                        cached.into_output().primary,
+                        input_code,
+                    ));
+                }
+
                 let options = JssgExecutionOptions {
                     script_path: &codemod_path,
                     resolver,
@@ -357,23 +564,38 @@ async fn handler_impl(args: &Command) -> Result<()> {
                     file_path: &input_path,
                     content: &input_code,
                     selector_config: None,
-                    params: test_config.params,
-                    matrix_values: None,
+                    params,
+                    matrix_values,
                     capabilities,
                     semantic_provider,
                     metrics_context: Some(metrics_context.clone()),
-                    llm_request_handler: None,
-                    shared_state_context: None,
+                    llm_request_handler: Some(cassettes.handler(&request.test_case_root)?),
+                    shared_state_context: Some(shared_state.clone()),
                     runtime_event_callback: Some(runtime_event_callback),
                     cancellation_flag: None,
                     test_mode: true,
                     dry_run: false,
                     target_directory: &target_directory,
+                    coverage,
                 };
-                let execution_result = execute_codemod_with_quickjs(options)
-                    .await
+                let metric_totals_before = metric_totals(&metrics_context.get_all());
+                let execution_output = execute_codemod_with_quickjs(options).await;
+                if let (Some(cache), Some(key), Ok(output)) = (
+                    result_cache.as_ref(),
+                    result_cache_key.as_ref(),
+                    &execution_output,
+                ) {
+                    if let Some(cached) = CachedResult::from_output(output) {
+                        let _ = cache.insert(key, &cached);
+                    }
+                }
+                let execution_result = execution_output
                     .map(|CodemodOutput { primary, .. }| map_execution_result(primary, input_code))
                     .map_err(anyhow::Error::from);
+                if request.rerun {
+                    runtime_event_output.flush(&runtime_event_buffer);
+                    return execution_result;
+                }
 
                 let pending_snapshot = finish_metrics_collection(
                     shared_metrics.as_ref(),
@@ -391,6 +613,40 @@ async fn handler_impl(args: &Command) -> Result<()> {
                     handle_metrics_snapshot(&snapshot, update_snapshots)?;
                 }
 
+                {
+                    let mut fixture_run = fixture_run
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("fixture run lock poisoned"))?;
+                    // Metrics contexts are shared by the entrypoints of a
+                    // directory, so only this execution's increase is added
+                    for (name, total) in metric_totals(&metrics_context.get_all()) {
+                        let before = metric_totals_before.get(&name).copied().unwrap_or(0);
+                        *fixture_run.metric_totals.entry(name).or_default() +=
+                            total.saturating_sub(before);
+                    }
+
+                    if request.ends_case
+                        && settings.expect.has_checks()
+                        && matches!(execution_result, Ok(TransformationResult::Success(_)))
+                    {
+                        let outputs =
+                            get_step_outputs("jssg").map_err(|e| anyhow::anyhow!("{e}"))?;
+                        let state: HashMap<String, serde_json::Value> = settings
+                            .expect
+                            .state
+                            .iter()
+                            .flatten()
+                            .filter_map(|(key, _)| {
+                                shared_state.get(key).map(|value| (key.clone(), value))
+                            })
+                            .collect();
+                        settings
+                            .expect
+                            .check(&outputs, &state, &fixture_run.metric_totals)
+                            .map_err(|message| anyhow::anyhow!(message))?;
+                    }
+                }
+
                 execution_result
             })
                 as Pin<
@@ -407,11 +663,23 @@ async fn handler_impl(args: &Command) -> Result<()> {
 
     let extensions = get_extensions_for_language(default_language_enum);
 
-    let mut runner = TestRunner::new(options, test_source);
+    let mut runner = TestRunner::new(options, test_source).with_watch_paths(vec![codemod_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .to_path_buf()]);
     let summary = runner
         .run_tests(&extensions, execution_fn, Some(capabilities))
         .await?;
 
+    if let Some(coverage) = &coverage {
+        write_coverage(
+            &coverage.report(),
+            &args.coverage_dir,
+            &current_dir,
+            global_config.reporter.is_machine_readable(),
+        )?;
+    }
+
     if !summary.is_success() {
         std::process::exit(1);
     }
@@ -419,6 +687,39 @@ async fn handler_impl(args: &Command) -> Result<()> {
     Ok(())
 }
 
+/// Write the lcov report and print the coverage summary, on stderr when the
+/// test report itself goes to stdout.
+fn write_coverage(
+    report: &CoverageReport,
+    coverage_dir: &Path,
+    current_dir: &Path,
+    machine_readable: bool,
+) -> Result<()> {
+    std::fs::create_dir_all(coverage_dir)?;
+    let lcov_path = coverage_dir.join("lcov.info");
+    std::fs::write(&lcov_path, report.to_lcov())?;
+
+    let summary = format!(
+        "\nCoverage\n{}\nlcov report written to {}",
+        report.summary(current_dir),
+        lcov_path.display()
+    );
+    if machine_readable {
+        eprintln!("{summary}");
+    } else {
+        println!("{summary}");
+    }
+    Ok(())
+}
+
+/// Total count of every metric, over all cardinalities.
+fn metric_totals(metrics_data: &MetricsData) -> HashMap<String, u64> {
+    metrics_data
+        .iter()
+        .map(|(name, entries)| (name.clone(), entries.iter().map(|entry| entry.count).sum()))
+        .collect()
+}
+
 fn finish_metrics_collection(
     shared_metrics: &Mutex<HashMap<PathBuf, SharedMetricsState>>,
     metrics_output_path: &Path,
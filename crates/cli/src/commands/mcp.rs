@@ -1,11 +1,21 @@
 use crate::feedback;
 use anyhow::Result;
 use clap::Args;
-use codemod_mcp::CodemodMcpServer;
+use codemod_mcp::http::{serve_http, HttpServeOptions, MCP_HTTP_PATH};
+use codemod_mcp::{CodemodMcpServer, PathAllowlist};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
 use rmcp::{transport, ServiceExt};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use tracing_subscriber::{self, EnvFilter};
 
+/// Environment variable read for the HTTP bearer token when `--http-token` is not set
+const HTTP_TOKEN_ENV: &str = "CODEMOD_MCP_TOKEN";
+
+/// Length of the bearer token generated when none is configured.
+const GENERATED_TOKEN_LEN: usize = 32;
+
 #[derive(Args, Debug)]
 pub struct Command {
     /// Write MCP usage events to a file for debugging
@@ -14,6 +24,17 @@ pub struct Command {
     /// Opt in to anonymous Codemod AI and MCP feedback for this OS user
     #[arg(long)]
     allow_feedback: bool,
+    /// Serve over streamable HTTP at this address (e.g. 127.0.0.1:8808) instead of stdio
+    #[arg(long, value_name = "ADDR")]
+    http: Option<SocketAddr>,
+    /// Bearer token HTTP clients must send (defaults to $CODEMOD_MCP_TOKEN, or
+    /// a random token printed at startup)
+    #[arg(long, value_name = "TOKEN", requires = "http")]
+    http_token: Option<String>,
+    /// Only let tools read and run code under this directory. Can be repeated.
+    /// Over HTTP, defaults to the current directory.
+    #[arg(long = "allow-dir", value_name = "DIR")]
+    allow_dirs: Vec<PathBuf>,
 }
 
 impl Command {
@@ -28,13 +49,44 @@ impl Command {
 
         tracing::info!("Starting MCP server");
 
-        let service = CodemodMcpServer::new_with_feedback(
+        let mut server = CodemodMcpServer::new_with_feedback(
             self.usage_log.clone(),
             feedback::anonymous_feedback_client("mcp").unwrap_or(None),
-        )
-        .serve(transport::stdio())
-        .await
-        .inspect_err(|e| {
+        );
+        // A shared HTTP server is always confined, to the current directory
+        // unless told otherwise
+        let allow_dirs = if self.allow_dirs.is_empty() && self.http.is_some() {
+            vec![std::env::current_dir()?]
+        } else {
+            self.allow_dirs.clone()
+        };
+        if !allow_dirs.is_empty() {
+            server = server.with_path_allowlist(PathAllowlist::new(allow_dirs)?);
+        }
+
+        if let Some(addr) = self.http {
+            let configured_token = self
+                .http_token
+                .clone()
+                .or_else(|| std::env::var(HTTP_TOKEN_ENV).ok())
+                .filter(|token| !token.is_empty());
+            // Even on loopback, any local process or user could run codemods
+            // without a token
+            let bearer_token = match configured_token {
+                Some(token) => token,
+                None => {
+                    let token = generate_token();
+                    eprintln!(
+                        "No --http-token or ${HTTP_TOKEN_ENV} set; clients must send: Authorization: Bearer {token}"
+                    );
+                    token
+                }
+            };
+            eprintln!("Serving MCP at http://{addr}{MCP_HTTP_PATH}");
+            return serve_http(server, HttpServeOptions { addr, bearer_token }).await;
+        }
+
+        let service = server.serve(transport::stdio()).await.inspect_err(|e| {
             tracing::error! {"serving error: {:?}", e};
         })?;
 
@@ -42,3 +94,11 @@ impl Command {
         Ok(())
     }
 }
+
+fn generate_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(GENERATED_TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
@@ -1021,6 +1021,7 @@ mod tests {
             documentation: None,
             validation: None,
             capabilities: None,
+            languages: None,
         }
     }
 
@@ -4,7 +4,9 @@ use crate::utils::package_validation::{
     validate_skill_behavior, PackageBehaviorShape,
 };
 use crate::utils::path_safety::resolve_relative_path_within_root;
-use crate::utils::rolldown_bundler::{RolldownBundler, RolldownBundlerConfig};
+use crate::utils::rolldown_bundler::{
+    append_source_mapping_url, source_map_relative_to, RolldownBundler, RolldownBundlerConfig,
+};
 use anyhow::{anyhow, Result};
 use butterflow_core::utils::validate_workflow;
 use butterflow_core::Workflow;
@@ -207,8 +209,14 @@ fn find_js_files_in_workflow(workflow: &Workflow, package_path: &Path) -> Result
     Ok(js_files)
 }
 
-/// Bundle a JavaScript file and return the bundled code
-async fn bundle_js_file(package_path: &Path, js_file: &str) -> Result<String> {
+/// A bundled JS file and its source map, ready to be written into the package
+struct BundledJsFile {
+    code: String,
+    source_map: Option<String>,
+}
+
+/// Bundle a JavaScript file and return the bundled code with its source map
+async fn bundle_js_file(package_path: &Path, js_file: &str) -> Result<BundledJsFile> {
     let js_file_path = resolve_relative_path_within_root(package_path, js_file).ok_or_else(|| {
         anyhow!(
             "Cannot publish: JS file path must be package-relative and stay within the package root: {js_file}"
@@ -221,7 +229,7 @@ async fn bundle_js_file(package_path: &Path, js_file: &str) -> Result<String> {
         entry_path: js_file_path.clone(),
         base_dir: Some(package_path.to_path_buf()),
         output_path: None, // Return code directly, don't write to file
-        source_maps: false,
+        source_maps: true,
         external_modules: supported_runtime_external_modules()
             .into_iter()
             .map(str::to_string)
@@ -244,7 +252,26 @@ async fn bundle_js_file(package_path: &Path, js_file: &str) -> Result<String> {
         js_file,
         bundle_result.code.len()
     );
-    Ok(bundle_result.code)
+
+    // Store the map next to the bundle so runtime errors point at the original sources
+    let Some(source_map) = &bundle_result.source_map else {
+        return Ok(BundledJsFile {
+            code: bundle_result.code,
+            source_map: None,
+        });
+    };
+    let js_dir = js_file_path.parent().unwrap_or(package_path);
+    let map_name = format!(
+        "{}.map",
+        js_file_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default()
+    );
+    Ok(BundledJsFile {
+        code: append_source_mapping_url(&bundle_result.code, &map_name),
+        source_map: Some(source_map_relative_to(source_map, js_dir)?),
+    })
 }
 
 fn validate_package_structure(
@@ -367,13 +394,14 @@ async fn create_package_bundle(
     .to_string();
     let temp_bundle_path = temp_dir.path().join(&bundle_name);
 
-    // Bundle JS files first and prepare replacements
+    // Bundle JS files first and prepare replacements, plus their source maps
     let mut bundled_files = HashMap::new();
     for js_file in js_files_to_bundle {
-        bundled_files.insert(
-            js_file.clone(),
-            bundle_js_file(package_path, js_file).await?,
-        );
+        let bundled = bundle_js_file(package_path, js_file).await?;
+        if let Some(source_map) = bundled.source_map {
+            bundled_files.insert(format!("{js_file}.map"), source_map);
+        }
+        bundled_files.insert(js_file.clone(), bundled.code);
     }
 
     // Create tar.gz archive
@@ -396,14 +424,9 @@ async fn create_package_bundle(
             debug!("Adding file to bundle: {}", relative_path.display());
 
             // Check if this is a JS file that should be replaced with bundled version
-            if let Some(bundled_code) = bundled_files.get(&relative_path_str) {
+            if let Some(bundled_code) = bundled_files.remove(&relative_path_str) {
                 // Add bundled version instead of original
-                let mut header = tar::Header::new_gnu();
-                header.set_path(relative_path)?;
-                header.set_size(bundled_code.len() as u64);
-                header.set_mode(0o644);
-                header.set_cksum();
-                tar.append(&header, bundled_code.as_bytes())?;
+                append_bundled_file(&mut tar, relative_path, &bundled_code)?;
                 info!(
                     "Replaced {} with bundled version ({} bytes)",
                     relative_path_str,
@@ -417,6 +440,13 @@ async fn create_package_bundle(
         }
     }
 
+    // Add generated source maps that have no counterpart in the package
+    for (relative_path, source_map) in &bundled_files {
+        append_bundled_file(&mut tar, Path::new(relative_path), source_map)?;
+        debug!("Added source map to bundle: {relative_path}");
+        file_count += 1;
+    }
+
     info!("Added {file_count} files to bundle");
 
     // Finish the tar archive and flush the gzip encoder
@@ -444,6 +474,20 @@ async fn create_package_bundle(
     Ok(output_path)
 }
 
+fn append_bundled_file<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    relative_path: &Path,
+    contents: &str,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(relative_path)?;
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append(&header, contents.as_bytes())?;
+    Ok(())
+}
+
 fn should_include_file(file_path: &Path, package_root: &Path) -> bool {
     let relative_path = match file_path.strip_prefix(package_root) {
         Ok(path) => path,
@@ -831,6 +875,7 @@ codemod-skill-version: 0.1.0
             documentation: None,
             validation: None,
             capabilities: None,
+            languages: None,
         }
     }
 
@@ -1123,7 +1168,8 @@ export default function transform() {
 
         let bundled = bundle_js_file(temp_dir.path(), "transform.js")
             .await
-            .unwrap();
+            .unwrap()
+            .code;
 
         assert!(bundled.contains("bundled"));
         assert!(!bundled.contains("\"local-helper\""));
@@ -1179,7 +1225,8 @@ export default function transform() {
 
         let bundled = bundle_js_file(temp_dir.path(), "transform.js")
             .await
-            .unwrap();
+            .unwrap()
+            .code;
 
         assert!(bundled.contains("node:util"));
         assert!(bundled.contains("path"));
@@ -1187,4 +1234,33 @@ export default function transform() {
         assert!(bundled.contains("codemod:ast-grep"));
         assert!(bundled.contains("codemod:llm"));
     }
+
+    #[tokio::test]
+    async fn publish_bundle_emits_source_map_relative_to_entry() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("scripts")).unwrap();
+        fs::write(
+            temp_dir.path().join("scripts/helper.ts"),
+            "export function helper(): string { throw new Error(\"boom\"); }\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("scripts/codemod.ts"),
+            "import { helper } from \"./helper\";\nexport default function transform() { return helper(); }\n",
+        )
+        .unwrap();
+
+        let bundled = bundle_js_file(temp_dir.path(), "scripts/codemod.ts")
+            .await
+            .unwrap();
+
+        assert!(bundled
+            .code
+            .ends_with("//# sourceMappingURL=codemod.ts.map\n"));
+        let source_map: serde_json::Value =
+            serde_json::from_str(&bundled.source_map.expect("source map")).unwrap();
+        let sources = source_map["sources"].as_array().unwrap();
+        assert!(sources.iter().any(|source| source == "helper.ts"));
+        assert!(sources.iter().any(|source| source == "codemod.ts"));
+    }
 }
@@ -2,7 +2,7 @@ use crate::commands::run_telemetry::{
     send_completed_event, send_event as send_telemetry_event, send_started_event,
     send_success_events, CodemodRunOutcome, CodemodRunStats, CodemodRunTelemetry,
 };
-use crate::engine::{create_engine, create_registry_client};
+use crate::engine::{apply_result_cache_setting, create_engine, create_registry_client};
 use crate::pro_dry_run::{
     apply_pro_dry_run_execution_settings, notify_pro_dry_run_required, ProDryRunReason,
 };
@@ -12,6 +12,7 @@ use crate::utils::package_validation::{default_workflow_path, select_workflow_pa
 use crate::utils::resolve_capabilities::{
     prompt_capabilities, resolve_capabilities, ResolveCapabilitiesArgs,
 };
+use crate::utils::resolve_languages::configure_manifest_languages;
 use crate::workflow_runner::{run_workflow, workflow_has_manual_steps};
 use crate::TelemetrySenderMutex;
 use crate::CLI_VERSION;
@@ -159,6 +160,9 @@ pub struct Command {
     /// Name of the workflow to run when the package defines multiple workflows
     #[arg(long, value_name = "NAME")]
     workflow: Option<String>,
+    /// Re-execute every file instead of reusing cached js-ast-grep results
+    #[arg(long)]
+    no_cache: bool,
 }
 
 impl Command {
@@ -315,6 +319,8 @@ pub async fn handler(
     let auto_launch_tui =
         should_auto_launch_package_run_tui(args.no_interactive, dry_run, &workflow_definition);
 
+    configure_manifest_languages(codemod_config.as_ref(), &resolved_package.package_dir)?;
+
     let capabilities = resolve_capabilities(
         ResolveCapabilitiesArgs {
             allow_fs: args.allow_fs,
@@ -364,6 +370,8 @@ pub async fn handler(
 
     // Set the package name so it's stored on the WorkflowRun
     engine.set_name(Some(canonical_codemod_name.clone()));
+    apply_result_cache_setting(engine.workflow_run_config_mut(), args.no_cache);
+    apply_result_cache_setting(&mut config, args.no_cache);
     apply_package_run_mode_to_config(engine.workflow_run_config_mut(), auto_launch_tui);
     if auto_launch_tui {
         engine.set_quiet(true);
@@ -887,6 +895,7 @@ mod tests {
             documentation: None,
             validation: None,
             capabilities: None,
+            languages: None,
         }
     }
 
@@ -3,5 +3,6 @@ pub mod list;
 pub mod resume;
 pub mod run;
 pub mod status;
+pub mod test;
 pub mod tui;
 pub mod validate;
@@ -5,6 +5,7 @@ use crate::commands::run_telemetry::{nested_codemod_run_observer, persisted_work
 use crate::engine::create_engine;
 use crate::utils::path_safety::normalize_target_path;
 use crate::utils::resolve_capabilities::{resolve_capabilities, ResolveCapabilitiesArgs};
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
 use crate::workflow_runner::resolve_workflow_source;
 use crate::TelemetrySenderMutex;
 use anyhow::{Context, Result};
@@ -97,6 +98,7 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         None,
         Some(workflow_dir.to_path_buf()),
     );
+    configure_languages_from_working_dir(workflow_dir)?;
     println!(
         "Resuming workflow {} with capabilities: {:?}",
         args.id, capabilities
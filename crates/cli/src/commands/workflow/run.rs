@@ -6,6 +6,7 @@ use crate::utils::path_safety::normalize_target_path;
 use crate::utils::resolve_capabilities::{
     prompt_capabilities, resolve_capabilities, ResolveCapabilitiesArgs,
 };
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
 use crate::{TelemetrySenderMutex, CLI_VERSION};
 use anyhow::{Context, Result};
 use butterflow_core::diff::FileDiff;
@@ -18,7 +19,7 @@ use std::sync::atomic::Ordering;
 
 use crate::commands::run_telemetry::nested_codemod_run_observer;
 use crate::commands::TelemetrySenderExt;
-use crate::engine::{create_engine, create_registry_client};
+use crate::engine::{apply_result_cache_setting, create_engine, create_registry_client};
 use crate::pro_dry_run::{
     apply_pro_dry_run_execution_settings, notify_pro_dry_run_required, ProDryRunReason,
 };
@@ -86,6 +87,9 @@ pub struct Command {
     /// Name of the workflow to run when codemod.yaml defines multiple workflows
     #[arg(long = "workflow-name", value_name = "NAME")]
     workflow_name: Option<String>,
+    /// Re-execute every file instead of reusing cached js-ast-grep results
+    #[arg(long)]
+    no_cache: bool,
 }
 
 fn should_auto_launch_workflow_tui(
@@ -131,6 +135,7 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
         None,
         Some(workflow_dir.to_path_buf()),
     );
+    configure_languages_from_working_dir(workflow_dir)?;
 
     // Build set of capabilities explicitly granted via CLI flags (skip prompting for these)
     let mut cli_granted = std::collections::HashSet::new();
@@ -207,6 +212,8 @@ pub async fn handler(args: &Command, telemetry: TelemetrySenderMutex) -> Result<
     )?;
 
     engine.set_name(Some(workflow_label.clone()));
+    apply_result_cache_setting(engine.workflow_run_config_mut(), args.no_cache);
+    apply_result_cache_setting(&mut config, args.no_cache);
     engine
         .workflow_run_config_mut()
         .execution
@@ -0,0 +1,966 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use butterflow_core::config::{
+    AiStepCallback, AiStepRequest, ManagedGitSettings, PullRequestApprovalCallback,
+    PullRequestCreationRequest, SkillInstallSettings, WorkflowExecutionSettings,
+    WorkflowInteractionSettings, WorkflowOutputSettings, WorkflowRunConfig,
+};
+use butterflow_core::engine::{env_llm_request_handler, Engine};
+use butterflow_core::llm_usage::LlmUsageContext;
+use butterflow_core::utils;
+use butterflow_models::{Task, WorkflowStatus};
+use butterflow_state::mock_adapter::MockStateAdapter;
+use clap::Args;
+use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use codemod_sandbox::llm::cassette::{LlmCassette, CASSETTE_FILE_NAME};
+use codemod_sandbox::llm::{LlmRequest, LlmRequestHandler, LlmResponse};
+use serde::Deserialize;
+use serde_json::Value;
+use testing_utils::{
+    report_results, ReporterType, Strictness, TestOptions, TestResultDetail, TestRunner,
+};
+use walkdir::WalkDir;
+
+use crate::engine::create_registry_client;
+use crate::utils::resolve_capabilities::{resolve_capabilities, ResolveCapabilitiesArgs};
+use crate::utils::resolve_languages::configure_languages_from_working_dir;
+use crate::workflow_runner::resolve_workflow_source_with_name;
+
+/// File names of a fixture's configuration, in order of precedence.
+const FIXTURE_CONFIG_FILES: [&str; 2] = ["test.yaml", "test.yml"];
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Args, Debug)]
+pub struct Command {
+    /// Path to workflow file or directory
+    #[arg(short, long, value_name = "PATH")]
+    workflow: String,
+
+    /// Directory containing the workflow test fixtures (default: tests)
+    test_directory: Option<PathBuf>,
+
+    /// Name of the workflow to run when codemod.yaml defines multiple workflows
+    #[arg(long = "workflow-name", value_name = "NAME")]
+    workflow_name: Option<String>,
+
+    /// Run only fixtures whose name contains the pattern
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Write the resulting file trees to the fixtures' expected directories
+    #[arg(long, short)]
+    update_snapshots: bool,
+
+    /// Comparison strictness for file contents (strict, cst, ast, loose)
+    #[arg(long, default_value = "strict")]
+    strictness: String,
+
+    /// Output format (console, terse, junit, tap)
+    #[arg(long, default_value = "console")]
+    reporter: String,
+
+    /// Timeout for each fixture in seconds
+    #[arg(long, default_value = "120")]
+    timeout: u64,
+
+    /// Call the LLM configured by the LLM_* environment variables and record
+    /// its responses in each fixture's llm-cassette.json instead of replaying them
+    #[arg(long)]
+    llm_record: bool,
+
+    /// Allow fs access
+    #[arg(long)]
+    allow_fs: bool,
+
+    /// Allow fetch access
+    #[arg(long)]
+    allow_fetch: bool,
+
+    /// Allow child process access
+    #[arg(long)]
+    allow_child_process: bool,
+}
+
+/// Contents of a workflow fixture's `test.yaml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowTestConfig {
+    /// Workflow parameters
+    #[serde(default)]
+    params: HashMap<String, Value>,
+    /// Comparison strictness, overriding `--strictness`
+    strictness: Option<String>,
+    /// Canned responses for LLM requests, first match wins. Without them,
+    /// requests are answered from the fixture's `llm-cassette.json`.
+    #[serde(default)]
+    llm: Vec<LlmStub>,
+    #[serde(default)]
+    expect: WorkflowExpectations,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LlmStub {
+    /// Text the prompt must contain; matches every prompt when missing
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    output: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowExpectations {
+    /// Final workflow status (default: completed)
+    status: Option<String>,
+    /// Status of every task of a node, by node id
+    tasks: Option<BTreeMap<String, String>>,
+    /// Workflow state values after the run
+    state: Option<BTreeMap<String, Value>>,
+    /// Pull requests the workflow requested, in any order
+    pull_requests: Option<Vec<ExpectedPullRequest>>,
+    /// Prompts of the AI steps, in any order
+    ai_prompts: Option<Vec<ExpectedAiPrompt>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedPullRequest {
+    node: String,
+    title: Option<String>,
+    body: Option<String>,
+    draft: Option<bool>,
+    base: Option<String>,
+    head: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedAiPrompt {
+    node: String,
+    /// Text the prompt must contain
+    contains: Option<String>,
+}
+
+/// What a workflow run produced, other than files.
+#[derive(Debug, Default)]
+struct WorkflowOutcome {
+    status: Option<WorkflowStatus>,
+    tasks: Vec<Task>,
+    state: HashMap<String, Value>,
+    pull_requests: Vec<PullRequestCreationRequest>,
+    ai_steps: Vec<AiStepRequest>,
+}
+
+/// Run a workflow against fixture repositories and check the results
+pub async fn handler(args: &Command) -> Result<()> {
+    let (workflow_file_path, bundle_path) =
+        resolve_workflow_source_with_name(&args.workflow, args.workflow_name.as_deref())?;
+    let workflow_dir = workflow_file_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    configure_languages_from_working_dir(&workflow_dir)?;
+
+    let capabilities = resolve_capabilities(
+        ResolveCapabilitiesArgs {
+            allow_fs: args.allow_fs,
+            allow_fetch: args.allow_fetch,
+            allow_child_process: args.allow_child_process,
+        },
+        None,
+        Some(workflow_dir),
+    );
+    let strictness: Strictness = args
+        .strictness
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+    let reporter: ReporterType = args
+        .reporter
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+    let options = TestOptions {
+        filter: args.filter.clone(),
+        update_snapshots: args.update_snapshots,
+        verbose: false,
+        parallel: false,
+        max_threads: Some(1),
+        fail_fast: false,
+        watch: false,
+        reporter,
+        timeout: Duration::from_secs(args.timeout),
+        ignore_whitespace: false,
+        context_lines: 3,
+        expect_errors: Vec::new(),
+        strictness,
+        language: None,
+        expected_extension: None,
+        check_syntax: false,
+        check_idempotent: false,
+    };
+
+    let test_directory = args
+        .test_directory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("tests"));
+    let fixtures = discover_fixtures(&test_directory, options.filter.as_deref())?;
+    if fixtures.is_empty() {
+        anyhow::bail!(
+            "No workflow test fixtures found in {}",
+            test_directory.display()
+        );
+    }
+
+    let started = Instant::now();
+    let mut details = Vec::new();
+    for (name, fixture_dir) in fixtures {
+        let fixture_started = Instant::now();
+        let result = run_fixture(
+            &fixture_dir,
+            &workflow_file_path,
+            &bundle_path,
+            &capabilities,
+            &options,
+            args.llm_record,
+        )
+        .await;
+        details.push(TestResultDetail {
+            name,
+            passed: result.is_ok(),
+            error_message: result.as_ref().err().map(|e| format!("{e:#}")),
+            duration: fixture_started.elapsed(),
+            skipped: None,
+            strictness: result.unwrap_or(options.strictness),
+        });
+    }
+
+    let summary = report_results(details, &options, started.elapsed());
+    if !summary.is_success() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Fixture directories under `test_directory`: the ones with an `input/`
+/// directory, by name.
+fn discover_fixtures(
+    test_directory: &Path,
+    filter: Option<&str>,
+) -> Result<Vec<(String, PathBuf)>> {
+    let entries = fs::read_dir(test_directory)
+        .with_context(|| format!("Failed to read test directory {}", test_directory.display()))?;
+
+    let mut fixtures = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.join("input").is_dir() {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if filter.is_some_and(|filter| !name.contains(filter)) {
+            continue;
+        }
+        fixtures.push((name.to_string(), path));
+    }
+    fixtures.sort();
+    Ok(fixtures)
+}
+
+/// Run the workflow on a copy of the fixture's `input/` and check the
+/// results. Returns the strictness the files were compared with.
+async fn run_fixture(
+    fixture_dir: &Path,
+    workflow_file_path: &Path,
+    bundle_path: &Path,
+    capabilities: &HashSet<LlrtSupportedModules>,
+    options: &TestOptions,
+    llm_record: bool,
+) -> Result<Strictness> {
+    let config = load_fixture_config(fixture_dir)?;
+    let mut options = options.clone();
+    if let Some(strictness) = &config.strictness {
+        options.strictness = strictness
+            .parse()
+            .map_err(|e: String| anyhow::anyhow!("Invalid strictness in test.yaml: {e}"))?;
+    }
+
+    // AI steps share the cassette of the LLM requests; with canned
+    // responses they are only recorded
+    let (llm_request_handler, ai_step_cassette) = if !config.llm.is_empty() {
+        (llm_stub_handler(&config.llm), None)
+    } else if llm_record {
+        let cassette = LlmCassette::record(fixture_dir.join(CASSETTE_FILE_NAME));
+        (
+            cassette.handler(Some(env_llm_request_handler(LlmUsageContext::new()))),
+            Some(cassette),
+        )
+    } else {
+        let cassette = LlmCassette::replay(fixture_dir.join(CASSETTE_FILE_NAME))
+            .map_err(anyhow::Error::msg)?;
+        (cassette.handler(None), Some(cassette))
+    };
+
+    let workspace = tempfile::TempDir::new().context("Failed to create temp test workspace")?;
+    copy_tree(&fixture_dir.join("input"), workspace.path())?;
+    init_fixture_repo(workspace.path())?;
+
+    let outcome = run_workflow(
+        workflow_file_path,
+        bundle_path,
+        workspace.path(),
+        capabilities,
+        &config,
+        llm_request_handler,
+        ai_step_cassette,
+        options.timeout,
+    )
+    .await?;
+
+    let mut failures = check_outcome(&config.expect, &outcome);
+
+    let expected_dir = fixture_dir.join("expected");
+    if options.update_snapshots {
+        if expected_dir.exists() {
+            fs::remove_dir_all(&expected_dir)?;
+        }
+        copy_tree(workspace.path(), &expected_dir)?;
+    } else if expected_dir.is_dir() {
+        failures.extend(compare_trees(&expected_dir, workspace.path(), &options)?);
+    }
+
+    if failures.is_empty() {
+        Ok(options.strictness)
+    } else {
+        Err(anyhow::anyhow!(failures.join("\n")))
+    }
+}
+
+fn load_fixture_config(fixture_dir: &Path) -> Result<WorkflowTestConfig> {
+    let Some(path) = FIXTURE_CONFIG_FILES
+        .iter()
+        .map(|name| fixture_dir.join(name))
+        .find(|path| path.is_file())
+    else {
+        return Ok(WorkflowTestConfig::default());
+    };
+
+    let content = fs::read_to_string(&path)?;
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid fixture configuration {}", path.display()))
+}
+
+/// Make `dir` a git repository with the fixture input committed, so managed
+/// git branches and commits in it as it would in a real checkout.
+fn init_fixture_repo(dir: &Path) -> Result<()> {
+    let commands: [&[&str]; 6] = [
+        &["init", "--quiet"],
+        &["config", "user.name", "codemod"],
+        &["config", "user.email", "codemod@localhost"],
+        &["config", "commit.gpgsign", "false"],
+        &["add", "--all"],
+        &[
+            "commit",
+            "--quiet",
+            "--no-verify",
+            "--allow-empty",
+            "-m",
+            "Fixture input",
+        ],
+    ];
+    for args in commands {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_workflow(
+    workflow_file_path: &Path,
+    bundle_path: &Path,
+    target_path: &Path,
+    capabilities: &HashSet<LlrtSupportedModules>,
+    config: &WorkflowTestConfig,
+    llm_request_handler: LlmRequestHandler,
+    ai_step_cassette: Option<LlmCassette>,
+    timeout: Duration,
+) -> Result<WorkflowOutcome> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let workflow = utils::parse_workflow_file(workflow_file_path).with_context(|| {
+        format!(
+            "Failed to parse workflow file: {}",
+            workflow_file_path.display()
+        )
+    })?;
+    let ai_steps = Arc::new(Mutex::new(Vec::new()));
+    let pull_requests = Arc::new(Mutex::new(Vec::new()));
+
+    let run_config = WorkflowRunConfig {
+        execution: WorkflowExecutionSettings {
+            workflow_file_path: workflow_file_path.to_path_buf(),
+            bundle_path: bundle_path.to_path_buf(),
+            target_path: target_path.to_path_buf(),
+            params: config.params.clone(),
+            registry_client: create_registry_client(None)?,
+            capabilities: Some(capabilities.clone()),
+            auto_trigger_manual_steps: true,
+            llm_request_handler: Some(llm_request_handler),
+            ai_step_cassette,
+            ..Default::default()
+        },
+        interaction: WorkflowInteractionSettings {
+            no_interactive: true,
+            ai_step_callback: Some(ai_step_recorder(ai_steps.clone())),
+            pull_request_approval_callback: Some(pull_request_recorder(pull_requests.clone())),
+            ..Default::default()
+        },
+        output: WorkflowOutputSettings {
+            quiet: true,
+            ..Default::default()
+        },
+        // Branches and commits go to the temporary repository. Pull
+        // requests are recorded and deferred, so nothing is pushed.
+        managed_git: ManagedGitSettings::default(),
+        skill_install: SkillInstallSettings {
+            skip_install_skill_steps: true,
+            install_skill_executor: None,
+        },
+    };
+    let engine = Engine::with_state_adapter(Box::new(MockStateAdapter::new()), run_config);
+
+    let timed_out = || anyhow::anyhow!("Workflow timed out after {timeout:?}");
+    let workflow_run_id = tokio::time::timeout_at(
+        deadline,
+        engine.run_workflow(
+            workflow,
+            config.params.clone(),
+            Some(bundle_path.to_path_buf()),
+            Some(capabilities),
+        ),
+    )
+    .await
+    .map_err(|_| timed_out())?
+    .context("Failed to run workflow")?;
+
+    let status = loop {
+        let status = engine.get_workflow_status(workflow_run_id).await?;
+        if !matches!(status, WorkflowStatus::Pending | WorkflowStatus::Running) {
+            break status;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            let _ = engine.cancel_workflow(workflow_run_id).await;
+            return Err(timed_out());
+        }
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+    };
+
+    let ai_steps = ai_steps
+        .lock()
+        .map_err(|_| anyhow::anyhow!("AI step recorder lock poisoned"))?
+        .clone();
+    let pull_requests = pull_requests
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Pull request recorder lock poisoned"))?
+        .clone();
+    Ok(WorkflowOutcome {
+        status: Some(status),
+        tasks: engine.get_tasks(workflow_run_id).await?,
+        state: engine.get_workflow_state(workflow_run_id).await?,
+        pull_requests,
+        ai_steps,
+    })
+}
+
+/// LLM handler answering from the fixture's canned responses.
+fn llm_stub_handler(stubs: &[LlmStub]) -> LlmRequestHandler {
+    let stubs: Vec<(Option<String>, String)> = stubs
+        .iter()
+        .map(|stub| (stub.pattern.clone(), stub.output.clone()))
+        .collect();
+
+    Arc::new(move |request: LlmRequest| {
+        let response = stubs
+            .iter()
+            .find(|(pattern, _)| {
+                pattern
+                    .as_deref()
+                    .is_none_or(|pattern| request.prompt.contains(pattern))
+            })
+            .map(|(_, output)| LlmResponse {
+                output: output.clone(),
+            })
+            .ok_or_else(|| {
+                format!(
+                    "No LLM response in test.yaml matches the prompt: {}",
+                    request.prompt
+                )
+            });
+        Box::pin(async move { response })
+    })
+}
+
+/// AI step handler that records the requests. Without a cassette, it runs
+/// instead of an agent.
+fn ai_step_recorder(requests: Arc<Mutex<Vec<AiStepRequest>>>) -> AiStepCallback {
+    Arc::new(move |request: &AiStepRequest| {
+        requests
+            .lock()
+            .map_err(|_| anyhow::anyhow!("AI step recorder lock poisoned"))?
+            .push(request.clone());
+        Ok(())
+    })
+}
+
+/// Pull request handler that records the requests and defers them, so
+/// branches are never pushed.
+fn pull_request_recorder(
+    requests: Arc<Mutex<Vec<PullRequestCreationRequest>>>,
+) -> PullRequestApprovalCallback {
+    Arc::new(move |request: &PullRequestCreationRequest| {
+        requests
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Pull request recorder lock poisoned"))?
+            .push(request.clone());
+        Ok(false)
+    })
+}
+
+/// Statuses are written like `awaiting_trigger` or `AwaitingTrigger`.
+fn normalize_status(status: &str) -> String {
+    status
+        .chars()
+        .filter(|ch| *ch != '_' && *ch != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Mismatches between the expectations and what the run produced.
+fn check_outcome(expect: &WorkflowExpectations, outcome: &WorkflowOutcome) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    let expected_status = expect.status.as_deref().unwrap_or("completed");
+    let actual_status = outcome
+        .status
+        .map(|status| format!("{status:?}"))
+        .unwrap_or_default();
+    if normalize_status(expected_status) != normalize_status(&actual_status) {
+        failures.push(format!(
+            "workflow status: expected {expected_status}, got {actual_status}"
+        ));
+        // Task errors explain unexpected failures
+        for task in &outcome.tasks {
+            if let Some(error) = &task.error {
+                failures.push(format!("  task {} failed: {error}", task.node_id));
+            }
+        }
+    }
+
+    for (node_id, expected) in expect.tasks.iter().flatten() {
+        let statuses: BTreeSet<String> = outcome
+            .tasks
+            .iter()
+            .filter(|task| &task.node_id == node_id && !task.is_master)
+            .map(|task| format!("{:?}", task.status))
+            .collect();
+        if statuses.is_empty() {
+            failures.push(format!(
+                "tasks of '{node_id}': expected {expected}, but none ran"
+            ));
+        } else if statuses
+            .iter()
+            .any(|status| normalize_status(status) != normalize_status(expected))
+        {
+            let statuses: Vec<_> = statuses.into_iter().collect();
+            failures.push(format!(
+                "tasks of '{node_id}': expected {expected}, got {}",
+                statuses.join(", ")
+            ));
+        }
+    }
+
+    for (key, expected) in expect.state.iter().flatten() {
+        match outcome.state.get(key) {
+            Some(actual) if actual == expected => {}
+            Some(actual) => {
+                failures.push(format!("state '{key}': expected {expected}, got {actual}"))
+            }
+            None => failures.push(format!(
+                "state '{key}': expected {expected}, but it was not set"
+            )),
+        }
+    }
+
+    if let Some(expected) = &expect.pull_requests {
+        failures.extend(match_all(
+            "pull request",
+            expected,
+            &outcome.pull_requests,
+            |expected, actual| {
+                expected.node == actual.node_id
+                    && expected.title.as_ref().is_none_or(|t| *t == actual.title)
+                    && expected
+                        .body
+                        .as_ref()
+                        .is_none_or(|b| Some(b) == actual.body.as_ref())
+                    && expected.draft.is_none_or(|d| d == actual.draft)
+                    && expected
+                        .base
+                        .as_ref()
+                        .is_none_or(|b| Some(b) == actual.base.as_ref())
+                    && expected.head.as_ref().is_none_or(|h| *h == actual.head)
+            },
+            |actual| format!("{} from '{}'", actual.title, actual.node_id),
+        ));
+    }
+
+    if let Some(expected) = &expect.ai_prompts {
+        failures.extend(match_all(
+            "AI step",
+            expected,
+            &outcome.ai_steps,
+            |expected, actual| {
+                expected.node == actual.node_id
+                    && expected
+                        .contains
+                        .as_ref()
+                        .is_none_or(|text| actual.prompt.contains(text))
+            },
+            |actual| format!("'{}': {}", actual.node_id, actual.prompt),
+        ));
+    }
+
+    failures
+}
+
+/// Pair every expected item with a distinct actual one. Reports expected
+/// items without a match and actual items nothing expected.
+fn match_all<E: std::fmt::Debug, A>(
+    kind: &str,
+    expected: &[E],
+    actual: &[A],
+    matches: impl Fn(&E, &A) -> bool,
+    describe: impl Fn(&A) -> String,
+) -> Vec<String> {
+    let mut unmatched: Vec<&A> = actual.iter().collect();
+    let mut failures = Vec::new();
+    for item in expected {
+        match unmatched.iter().position(|actual| matches(item, actual)) {
+            Some(index) => {
+                unmatched.remove(index);
+            }
+            None => failures.push(format!("expected {kind} {item:?} was not produced")),
+        }
+    }
+    for item in unmatched {
+        failures.push(format!("unexpected {kind} {}", describe(item)));
+    }
+    failures
+}
+
+/// Compare the file tree the workflow left with the expected one.
+fn compare_trees(
+    expected_dir: &Path,
+    actual_dir: &Path,
+    options: &TestOptions,
+) -> Result<Vec<String>> {
+    let expected = collect_tree(expected_dir)?;
+    let actual = collect_tree(actual_dir)?;
+    let paths: BTreeSet<&PathBuf> = expected.keys().chain(actual.keys()).collect();
+
+    let mut failures = Vec::new();
+    for path in paths {
+        match (expected.get(path), actual.get(path)) {
+            (Some(expected), Some(actual)) => {
+                if !TestRunner::contents_match(expected, actual, options, Some(path.as_path())) {
+                    failures.push(format!(
+                        "{}: content mismatch\n{}",
+                        path.display(),
+                        TestRunner::generate_diff(expected, actual, options)
+                    ));
+                }
+            }
+            (Some(_), None) => {
+                failures.push(format!("{}: expected file is missing", path.display()))
+            }
+            (None, Some(_)) => failures.push(format!("{}: unexpected file", path.display())),
+            (None, None) => {}
+        }
+    }
+    Ok(failures)
+}
+
+/// Text files under `dir`, by path relative to it. `.git` is skipped.
+fn collect_tree(dir: &Path) -> Result<BTreeMap<PathBuf, String>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir)?.to_path_buf();
+        let bytes = fs::read(entry.path())?;
+        files.insert(relative, String::from_utf8_lossy(&bytes).into_owned());
+    }
+    Ok(files)
+}
+
+/// Copy the files under `from` into `to`. `.git` is skipped.
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+    {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use butterflow_models::TaskStatus;
+    use uuid::Uuid;
+
+    fn task(node_id: &str, status: TaskStatus) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "workflow_run_id": Uuid::new_v4(),
+            "node_id": node_id,
+            "status": status,
+            "is_master": false,
+            "logs": [],
+        }))
+        .expect("valid task")
+    }
+
+    fn pull_request(node_id: &str, title: &str) -> PullRequestCreationRequest {
+        PullRequestCreationRequest {
+            title: title.to_string(),
+            body: None,
+            draft: true,
+            head: "codemod-abc".to_string(),
+            base: None,
+            node_id: node_id.to_string(),
+            node_name: node_id.to_string(),
+            task_id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    #[test]
+    fn outcome_matching_the_expectations_has_no_failures() {
+        let expect: WorkflowExpectations = serde_yaml::from_str(
+            r#"
+status: completed
+tasks: { migrate: completed }
+state: { migrated: 2 }
+pull_requests:
+  - node: migrate
+    title: Migrate
+    draft: true
+"#,
+        )
+        .unwrap();
+        let outcome = WorkflowOutcome {
+            status: Some(WorkflowStatus::Completed),
+            tasks: vec![task("migrate", TaskStatus::Completed)],
+            state: HashMap::from([("migrated".to_string(), serde_json::json!(2))]),
+            pull_requests: vec![pull_request("migrate", "Migrate")],
+            ai_steps: Vec::new(),
+        };
+
+        assert_eq!(check_outcome(&expect, &outcome), Vec::<String>::new());
+    }
+
+    #[test]
+    fn every_mismatch_is_reported() {
+        let expect: WorkflowExpectations = serde_yaml::from_str(
+            r#"
+tasks: { migrate: completed, publish: completed }
+state: { migrated: 2 }
+ai_prompts:
+  - node: review
+    contains: "legacy API"
+"#,
+        )
+        .unwrap();
+        let outcome = WorkflowOutcome {
+            status: Some(WorkflowStatus::Failed),
+            tasks: vec![task("migrate", TaskStatus::Failed)],
+            state: HashMap::new(),
+            pull_requests: vec![pull_request("migrate", "Migrate")],
+            ai_steps: vec![AiStepRequest {
+                prompt: "Review the new API".to_string(),
+                system_prompt: None,
+                node_id: "review".to_string(),
+                node_name: "Review".to_string(),
+                task_id: Uuid::new_v4().to_string(),
+            }],
+        };
+
+        let failures = check_outcome(&expect, &outcome);
+        assert_eq!(
+            failures[0],
+            "workflow status: expected completed, got Failed"
+        );
+        assert!(
+            failures.contains(&"tasks of 'migrate': expected completed, got Failed".to_string())
+        );
+        assert!(
+            failures.contains(&"tasks of 'publish': expected completed, but none ran".to_string())
+        );
+        assert!(failures.contains(&"state 'migrated': expected 2, but it was not set".to_string()));
+        assert!(failures
+            .iter()
+            .any(|failure| failure.starts_with("expected AI step")));
+        assert!(failures.contains(&"unexpected AI step 'review': Review the new API".to_string()));
+        // Pull requests aren't checked unless expected
+        assert!(!failures
+            .iter()
+            .any(|failure| failure.contains("pull request")));
+    }
+
+    #[test]
+    fn llm_stub_answers_with_the_first_matching_response() {
+        let handler = llm_stub_handler(&[
+            LlmStub {
+                pattern: Some("rename".to_string()),
+                output: "newName".to_string(),
+            },
+            LlmStub {
+                pattern: None,
+                output: "fallback".to_string(),
+            },
+        ]);
+        let request = |prompt: &str| LlmRequest {
+            prompt: prompt.to_string(),
+            system_prompt: None,
+            output_schema: None,
+            max_tokens: None,
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let output = |prompt: &str| runtime.block_on(handler(request(prompt))).map(|r| r.output);
+        assert_eq!(output("please rename foo"), Ok("newName".to_string()));
+        assert_eq!(output("anything else"), Ok("fallback".to_string()));
+
+        let strict = llm_stub_handler(&[]);
+        assert!(runtime.block_on(strict(request("hi"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn fixture_records_the_pull_requests_of_nodes_that_changed_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let workflow_path = root.join("workflow.yaml");
+        fs::write(
+            &workflow_path,
+            r#"version: "1"
+nodes:
+  - id: migrate
+    name: Migrate
+    pull_request:
+      title: "Migrate value"
+      draft: true
+    steps:
+      - name: Bump value
+        js-ast-grep:
+          js_file: bump.js
+          language: javascript
+          include: ["**/*.js"]
+  - id: noop
+    name: Noop
+    depends_on: [migrate]
+    pull_request:
+      title: "Nothing to do"
+    steps:
+      - name: Leave files alone
+        js-ast-grep:
+          js_file: noop.js
+          language: javascript
+          include: ["**/*.js"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("bump.js"),
+            "export default function transform(ast) {\n  return ast.root().text().replace(\"value = 1\", \"value = 2\");\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("noop.js"),
+            "export default function transform(ast) {\n  return null;\n}\n",
+        )
+        .unwrap();
+
+        let fixture = root.join("tests/bump");
+        fs::create_dir_all(fixture.join("input")).unwrap();
+        fs::create_dir_all(fixture.join("expected")).unwrap();
+        fs::write(fixture.join("input/app.js"), "const value = 1;\n").unwrap();
+        fs::write(fixture.join("expected/app.js"), "const value = 2;\n").unwrap();
+        // Only the node that changed files asks for a pull request
+        fs::write(
+            fixture.join("test.yaml"),
+            "expect:\n  pull_requests:\n    - node: migrate\n      title: Migrate value\n      draft: true\n",
+        )
+        .unwrap();
+
+        let options = TestOptions {
+            filter: None,
+            update_snapshots: false,
+            verbose: false,
+            parallel: false,
+            max_threads: Some(1),
+            fail_fast: false,
+            watch: false,
+            reporter: ReporterType::Console,
+            timeout: Duration::from_secs(60),
+            ignore_whitespace: false,
+            context_lines: 3,
+            expect_errors: Vec::new(),
+            strictness: Strictness::Strict,
+            language: None,
+            expected_extension: None,
+            check_syntax: false,
+            check_idempotent: false,
+        };
+        let result = run_fixture(
+            &fixture,
+            &workflow_path,
+            root,
+            &HashSet::new(),
+            &options,
+            false,
+        )
+        .await;
+        assert!(result.is_ok(), "{:#}", result.unwrap_err());
+    }
+}
@@ -182,6 +182,15 @@ fn create_shell_command_approval_callback(
     }))
 }
 
+/// Enable the per-file JSSG result cache unless the user opted out.
+pub fn apply_result_cache_setting(cfg: &mut WorkflowRunConfig, no_cache: bool) {
+    cfg.execution.result_cache_dir = if no_cache {
+        None
+    } else {
+        butterflow_core::utils::get_result_cache_dir().ok()
+    };
+}
+
 /// Create an engine based on configuration
 #[allow(clippy::too_many_arguments)]
 pub fn create_engine(
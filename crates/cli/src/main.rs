@@ -136,6 +136,9 @@ enum WorkflowCommands {
 
     /// Browse and interact with workflow runs in the terminal
     Tui(commands::workflow::tui::Command),
+
+    /// Run end-to-end workflow tests against fixture repositories
+    Test(commands::workflow::test::Command),
 }
 
 #[derive(Subcommand, Debug)]
@@ -488,6 +491,9 @@ async fn run_cli() -> Result<()> {
             WorkflowCommands::Tui(args) => {
                 commands::workflow::tui::handler(args, telemetry_sender.clone()).await?;
             }
+            WorkflowCommands::Test(args) => {
+                commands::workflow::test::handler(args).await?;
+            }
         },
         Some(Commands::Jssg(args)) => match &args.command {
             JssgCommands::Bundle(args) => {
@@ -670,6 +676,24 @@ mod tests {
         assert!(parse_result.is_ok());
     }
 
+    #[test]
+    fn parser_accepts_mcp_http_transport() {
+        let parse_result = Cli::try_parse_from([
+            "codemod",
+            "mcp",
+            "--http",
+            "127.0.0.1:8808",
+            "--http-token",
+            "secret",
+            "--allow-dir",
+            ".",
+        ]);
+        assert!(parse_result.is_ok());
+
+        let parse_result = Cli::try_parse_from(["codemod", "mcp", "--http-token", "secret"]);
+        assert!(parse_result.is_err());
+    }
+
     #[test]
     fn parser_accepts_run_with_install_skill_override() {
         let parse_result = Cli::try_parse_from([
@@ -50,6 +50,10 @@ mod tests {
                                 language: None,
                                 capabilities: None,
                                 semantic_analysis: None,
+                                affected_by: None,
+                                check_syntax: None,
+                                check_idempotent: None,
+                                include_components: None,
                             }),
                             env: None,
                             condition: None,
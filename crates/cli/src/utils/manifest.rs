@@ -48,6 +48,8 @@ pub(crate) struct CodemodManifest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) validation: Option<ValidationConfig>,
     pub(crate) capabilities: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) languages: Option<Vec<tree_sitter_loader::LanguageDeclaration>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -392,6 +394,7 @@ mod tests {
             documentation: None,
             validation: None,
             capabilities: None,
+            languages: None,
         }
     }
 
@@ -6,5 +6,6 @@ pub(crate) mod package_validation;
 pub(crate) mod path_safety;
 pub(crate) mod registry_link;
 pub(crate) mod resolve_capabilities;
+pub(crate) mod resolve_languages;
 pub(crate) mod rolldown_bundler;
 pub(crate) mod skill_layout;
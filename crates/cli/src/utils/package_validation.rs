@@ -757,6 +757,7 @@ mod tests {
             documentation: None,
             validation: None,
             capabilities: None,
+            languages: None,
         }
     }
 
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use std::{fs, path::Path};
+
+use crate::utils::{ancestor_search::find_in_ancestors, manifest::CodemodManifest};
+
+/// Registers the tree-sitter grammars a package declares under `languages`
+/// with the parser loader. Grammar paths are relative to `package_dir`.
+pub(crate) fn configure_manifest_languages(
+    manifest: Option<&CodemodManifest>,
+    package_dir: &Path,
+) -> Result<()> {
+    let Some(languages) = manifest.and_then(|manifest| manifest.languages.clone()) else {
+        return Ok(());
+    };
+    tree_sitter_loader::configure(languages, package_dir)
+        .context("Failed to configure languages declared in codemod.yaml")
+}
+
+/// Like [`configure_manifest_languages`], for the codemod.yaml found in the
+/// ancestors of `working_directory`. A missing or unparsable manifest is
+/// ignored, as with capabilities.
+pub(crate) fn configure_languages_from_working_dir(working_directory: &Path) -> Result<()> {
+    let Some(manifest_path) = find_in_ancestors(working_directory, "codemod.yaml") else {
+        return Ok(());
+    };
+    let Some(manifest) = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<CodemodManifest>(&content).ok())
+    else {
+        return Ok(());
+    };
+    let package_dir = manifest_path.parent().unwrap_or(working_directory);
+    configure_manifest_languages(Some(&manifest), package_dir)
+}
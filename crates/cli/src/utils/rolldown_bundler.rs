@@ -1,6 +1,9 @@
 use anyhow::Result;
+use base64::Engine as _;
 use rolldown::{BundleOutput, Bundler, BundlerOptions, InputItem, IsExternal, SourceMapType};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+const SOURCE_MAPPING_URL_PREFIX: &str = "//# sourceMappingURL=";
 
 /// Simple rolldown-based bundler configuration
 #[derive(Debug, Clone)]
@@ -24,6 +27,8 @@ pub struct RolldownBundlerConfig {
 pub struct BundleResult {
     /// Bundled JavaScript code
     pub code: String,
+    /// Source map JSON with absolute `sources` (if source maps are enabled)
+    pub source_map: Option<String>,
     /// Output file path (if written to file)
     #[allow(dead_code)]
     pub output_path: Option<PathBuf>,
@@ -50,6 +55,28 @@ impl RolldownBundler {
             .ok_or_else(|| anyhow::anyhow!("No JavaScript output generated by rolldown"))
     }
 
+    /// Extract the source map emitted next to the JS chunk, resolving its
+    /// `sources` against rolldown's virtual output directory.
+    fn extract_source_map(result: &BundleOutput, base_dir: &Path) -> Result<Option<String>> {
+        let Some(asset) = result
+            .assets
+            .iter()
+            .find(|asset| asset.filename().ends_with(".js.map"))
+        else {
+            return Ok(None);
+        };
+        let output_dir = base_dir.join("dist");
+        let map = rewrite_source_map_sources(asset.content_as_bytes(), |source| {
+            let source = Path::new(source);
+            if source.is_absolute() {
+                normalize_path(source)
+            } else {
+                normalize_path(&output_dir.join(source))
+            }
+        })?;
+        Ok(Some(map))
+    }
+
     /// Bundle the entry file and its dependencies into a single JavaScript file
     pub async fn bundle(&self) -> Result<BundleResult> {
         let base_dir = if let Some(base_dir) = &self.config.base_dir {
@@ -106,20 +133,138 @@ impl RolldownBundler {
             }
         }
 
-        let bundled_code = Self::extract_js_code(&result)?;
+        let bundled_code = strip_source_mapping_url(&Self::extract_js_code(&result)?);
+        let source_map = if self.config.source_maps {
+            Self::extract_source_map(&result, &base_dir)?
+        } else {
+            None
+        };
 
         if let Some(output_path) = &self.config.output_path {
-            std::fs::write(output_path, &bundled_code)
+            let mut code = bundled_code.clone();
+            if let Some(source_map) = &source_map {
+                let map_path = PathBuf::from(format!("{}.map", output_path.display()));
+                let output_dir = output_path.parent().unwrap_or(Path::new(""));
+                let relative_map = source_map_relative_to(source_map, output_dir)?;
+                std::fs::write(&map_path, relative_map)
+                    .map_err(|e| anyhow::anyhow!("Failed to write source map to file: {}", e))?;
+                let map_name = map_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                code = append_source_mapping_url(&code, &map_name);
+            }
+            std::fs::write(output_path, &code)
                 .map_err(|e| anyhow::anyhow!("Failed to write bundle to file: {}", e))?;
         }
 
         Ok(BundleResult {
             code: bundled_code,
+            source_map,
             output_path: self.config.output_path.clone(),
         })
     }
 }
 
+impl BundleResult {
+    /// Bundled code with the source map inlined as a base64 data URL, so the
+    /// runtime can remap stack traces without a sibling `.map` file.
+    pub fn code_with_inline_source_map(&self) -> String {
+        match &self.source_map {
+            Some(source_map) => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(source_map);
+                append_source_mapping_url(
+                    &self.code,
+                    &format!("data:application/json;base64,{encoded}"),
+                )
+            }
+            None => self.code.clone(),
+        }
+    }
+}
+
+/// Rewrite a source map so its `sources` are relative to `dir`, the
+/// directory the map will be stored in.
+pub fn source_map_relative_to(source_map: &str, dir: &Path) -> Result<String> {
+    rewrite_source_map_sources(source_map.as_bytes(), |source| {
+        relative_path(dir, Path::new(source))
+    })
+}
+
+/// Append a `sourceMappingURL` comment to bundled code.
+pub fn append_source_mapping_url(code: &str, url: &str) -> String {
+    let separator = if code.ends_with('\n') { "" } else { "\n" };
+    format!("{code}{separator}{SOURCE_MAPPING_URL_PREFIX}{url}\n")
+}
+
+fn strip_source_mapping_url(code: &str) -> String {
+    code.lines()
+        .filter(|line| !line.trim_start().starts_with(SOURCE_MAPPING_URL_PREFIX))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn rewrite_source_map_sources(
+    source_map: &[u8],
+    rewrite: impl Fn(&str) -> PathBuf,
+) -> Result<String> {
+    let mut map: serde_json::Value = serde_json::from_slice(source_map)
+        .map_err(|e| anyhow::anyhow!("Invalid source map generated by rolldown: {}", e))?;
+    if let Some(object) = map.as_object_mut() {
+        object.remove("sourceRoot");
+        if let Some(sources) = object
+            .get_mut("sources")
+            .and_then(serde_json::Value::as_array_mut)
+        {
+            for source in sources.iter_mut() {
+                if let Some(path) = source.as_str() {
+                    let rewritten = rewrite(path).to_string_lossy().replace('\\', "/");
+                    *source = serde_json::Value::String(rewritten);
+                }
+            }
+        }
+    }
+    Ok(serde_json::to_string(&map)?)
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from_dir = normalize_path(from_dir);
+    let to = normalize_path(to);
+    let from_components = from_dir.components().collect::<Vec<_>>();
+    let to_components = to.components().collect::<Vec<_>>();
+    let common = from_components
+        .iter()
+        .zip(&to_components)
+        .take_while(|(from, to)| from == to)
+        .count();
+    if common == 0 {
+        return to;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..from_components.len() {
+        relative.push("..");
+    }
+    for component in &to_components[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
 fn normalize_rolldown_path(path: &Path) -> PathBuf {
     #[cfg(windows)]
     {
@@ -160,6 +305,27 @@ mod tests {
         assert_eq!(bundler.config.source_maps, config.source_maps);
     }
 
+    #[test]
+    fn source_map_sources_are_rewritten_relative_to_output_dir() {
+        let map = r#"{"version":3,"sources":["/pkg/src/codemod.ts","/pkg/node_modules/helper/index.js"],"sourceRoot":"","mappings":"AAAA"}"#;
+        let rewritten = source_map_relative_to(map, Path::new("/pkg/dist")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rewritten).unwrap();
+
+        assert_eq!(
+            value["sources"],
+            serde_json::json!(["../src/codemod.ts", "../node_modules/helper/index.js"])
+        );
+        assert!(value.get("sourceRoot").is_none());
+    }
+
+    #[test]
+    fn source_mapping_url_is_replaced_not_duplicated() {
+        let code = strip_source_mapping_url("run();\n//# sourceMappingURL=codemod.js.map\n");
+        let code = append_source_mapping_url(&code, "bundle.js.map");
+
+        assert_eq!(code, "run();\n//# sourceMappingURL=bundle.js.map\n");
+    }
+
     #[cfg(windows)]
     #[test]
     fn normalize_rolldown_path_converts_verbatim_unc_paths() {
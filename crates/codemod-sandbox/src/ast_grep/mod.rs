@@ -11,6 +11,9 @@ pub mod wasm_utils;
 #[cfg(feature = "native")]
 pub mod native;
 
+#[cfg(feature = "native")]
+pub(crate) mod scope;
+
 #[cfg(all(
     not(all(feature = "wasm", target_arch = "wasm32")),
     not(feature = "native")
@@ -280,9 +283,22 @@ fn jssg_transform_rjs<'js>(
         let mut changes = file_changes.changes.lock().map_err(|e| {
             Exception::throw_message(&ctx2, &format!("Failed to lock file_changes mutex: {e}"))
         })?;
+        let change_path = std::path::PathBuf::from(&path_to_file);
+        if changes
+            .iter()
+            .any(|c| c.path == change_path && c.base_content.is_some())
+        {
+            return Err(Exception::throw_message(
+                &ctx2,
+                &format!(
+                    "Conflicting changes: '{path_to_file}' is changed by both renameSymbol and jssgTransform"
+                ),
+            ));
+        }
         changes.push(FileChange {
-            path: std::path::PathBuf::from(&path_to_file),
+            path: change_path,
             result: exec_result,
+            base_content: None,
         });
 
         // Return the transformed content string, or null if unmodified
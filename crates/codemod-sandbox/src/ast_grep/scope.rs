@@ -0,0 +1,142 @@
+//! Lexical scopes returned by `node.scope()`.
+//!
+//! A scope is one link of the chain reported by the semantic provider, from
+//! the innermost scope around a node out to the module scope. Lookups walk
+//! the chain; nodes are resolved lazily against the root the scope came from.
+
+use crate::ast_grep::sg_node::{find_node_at_range, SgNodeRjs, SgRootInner, TSDoc};
+use ast_grep_core::NodeMatch;
+use language_core::{ByteRange, ScopeInfo};
+use rquickjs::{class, class::Trace, methods, Ctx, IntoJs, JsLifetime, Object, Result, Value};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+#[derive(Trace, Clone)]
+#[class(rename_all = "camelCase")]
+pub struct SgScopeRjs<'js> {
+    #[qjs(skip_trace)] // Strong reference to keep root alive
+    pub(crate) root: Arc<SgRootInner>,
+    #[qjs(skip_trace)]
+    pub(crate) chain: Arc<Vec<ScopeInfo>>,
+    #[qjs(skip_trace)]
+    pub(crate) index: usize,
+    #[qjs(skip_trace)]
+    pub(crate) _phantom: PhantomData<&'js ()>,
+}
+
+unsafe impl<'js> JsLifetime<'js> for SgScopeRjs<'js> {
+    type Changed<'to> = SgScopeRjs<'to>;
+}
+
+impl<'js> SgScopeRjs<'js> {
+    fn info(&self) -> &ScopeInfo {
+        &self.chain[self.index]
+    }
+
+    /// Scopes from this one out to the module scope.
+    fn visible(&self) -> &[ScopeInfo] {
+        &self.chain[self.index..]
+    }
+
+    fn node_at(&self, ctx: &Ctx<'js>, range: ByteRange) -> Result<Value<'js>> {
+        let root_node = self.root.grep.root();
+        match find_node_at_range(&root_node, range.start as usize, range.end as usize) {
+            Some(node) => {
+                let node_match: NodeMatch<_> = node.into();
+                let static_node_match: NodeMatch<'static, TSDoc> =
+                    unsafe { std::mem::transmute(node_match) };
+                SgNodeRjs {
+                    root: Arc::clone(&self.root),
+                    inner_node: static_node_match,
+                    _phantom: PhantomData,
+                }
+                .into_js(ctx)
+            }
+            None => Ok(Value::new_null(ctx.clone())),
+        }
+    }
+}
+
+#[methods]
+impl<'js> SgScopeRjs<'js> {
+    /// The kind of scope: `module`, `function`, `class` or `block`.
+    pub fn kind(&self) -> String {
+        self.info().kind.as_str().to_string()
+    }
+
+    /// The node that introduces this scope.
+    pub fn node(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        self.node_at(&ctx, self.info().range)
+    }
+
+    /// The enclosing scope, or null for the module scope.
+    pub fn parent(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        if self.index + 1 >= self.chain.len() {
+            return Ok(Value::new_null(ctx));
+        }
+        SgScopeRjs {
+            root: Arc::clone(&self.root),
+            chain: Arc::clone(&self.chain),
+            index: self.index + 1,
+            _phantom: PhantomData,
+        }
+        .into_js(&ctx)
+    }
+
+    /// Names declared directly in this scope, as `{ name, kind, node }`.
+    pub fn bindings(&self, ctx: Ctx<'js>) -> Result<Vec<Object<'js>>> {
+        self.info()
+            .bindings
+            .iter()
+            .map(|binding| {
+                let obj = Object::new(ctx.clone())?;
+                obj.set("name", binding.name.as_str())?;
+                obj.set("kind", binding.kind.as_str())?;
+                obj.set("node", self.node_at(&ctx, binding.range)?)?;
+                Ok(obj)
+            })
+            .collect()
+    }
+
+    /// Whether `name` is declared in this scope or an enclosing one.
+    pub fn has(&self, name: String) -> bool {
+        self.visible().iter().any(|scope| scope.binds(&name))
+    }
+
+    /// Whether `name` is declared directly in this scope.
+    #[qjs(rename = "hasOwn")]
+    pub fn has_own(&self, name: String) -> bool {
+        self.info().binds(&name)
+    }
+
+    /// Names used in this scope that are declared outside it, including
+    /// globals.
+    #[qjs(rename = "freeNames")]
+    pub fn free_names(&self) -> Vec<String> {
+        self.info().free_names.clone()
+    }
+
+    /// A name based on `base` that can be declared in this scope without
+    /// shadowing or being shadowed by any existing binding: `base`, then
+    /// `base1`, `base2`, ...
+    #[qjs(rename = "uniqueName")]
+    pub fn unique_name(&self, base: String) -> String {
+        let info = self.info();
+        let taken: HashSet<&str> = self
+            .visible()
+            .iter()
+            .flat_map(|scope| scope.bindings.iter().map(|binding| binding.name.as_str()))
+            .chain(info.nested_names.iter().map(String::as_str))
+            .chain(info.free_names.iter().map(String::as_str))
+            .collect();
+
+        if !taken.contains(base.as_str()) {
+            return base;
+        }
+        (1..)
+            .map(|suffix| format!("{base}{suffix}"))
+            .find(|candidate| !taken.contains(candidate.as_str()))
+            .expect("infinite candidates")
+    }
+}
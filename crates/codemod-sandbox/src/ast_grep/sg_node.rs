@@ -14,7 +14,8 @@ use ast_grep_language::SupportLang;
 use language_core::SemanticProvider;
 
 use rquickjs::{
-    class, class::Trace, methods, prelude::Opt, Ctx, Exception, IntoJs, JsLifetime, Result, Value,
+    class, class::Trace, methods, prelude::Opt, Class, Ctx, Exception, IntoJs, JsLifetime, Result,
+    Value,
 };
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -39,11 +40,11 @@ use CodemodLang as Lang;
     not(all(feature = "wasm", target_arch = "wasm32")),
     not(feature = "native")
 ))]
-type TSDoc = TSStrDoc<SupportLang>;
+pub(super) type TSDoc = TSStrDoc<SupportLang>;
 #[cfg(feature = "native")]
-type TSDoc = TSStrDoc<CodemodLang>;
+pub(super) type TSDoc = TSStrDoc<CodemodLang>;
 #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
-type TSDoc = WasmDoc;
+pub(super) type TSDoc = WasmDoc;
 
 pub(crate) struct SgRootInner {
     pub(crate) grep: AstGrep<TSDoc>,
@@ -58,6 +59,11 @@ pub(crate) struct SgRootInner {
     /// The current file being processed (for write() validation)
     #[cfg(feature = "native")]
     pub(crate) current_file_path: Option<String>,
+    /// Control-flow graphs of every function in the source, built on the
+    /// first control-flow query
+    #[cfg(feature = "native")]
+    control_flows:
+        std::sync::OnceLock<std::result::Result<Vec<language_core::ControlFlowGraph>, String>>,
 }
 
 #[derive(Trace, Clone)]
@@ -226,9 +232,68 @@ impl<'js> SgRootRjs<'js> {
             Ok(())
         }
     }
+
+    /// Get the absolute paths of the workspace files this file imports.
+    ///
+    /// Returns an empty array unless workspace-scope semantic analysis is
+    /// configured for a language with module resolution.
+    pub fn imports(&self, ctx: Ctx<'js>) -> Result<Vec<String>> {
+        #[cfg(not(feature = "native"))]
+        {
+            let _ = ctx;
+            return Ok(Vec::new());
+        }
+        #[cfg(feature = "native")]
+        {
+            self.import_graph(&ctx, "imports", |provider, path| provider.get_imports(path))
+        }
+    }
+
+    /// Get the absolute paths of the workspace files that import this file
+    /// directly.
+    #[qjs(rename = "importedBy")]
+    pub fn imported_by(&self, ctx: Ctx<'js>) -> Result<Vec<String>> {
+        #[cfg(not(feature = "native"))]
+        {
+            let _ = ctx;
+            return Ok(Vec::new());
+        }
+        #[cfg(feature = "native")]
+        {
+            self.import_graph(&ctx, "importers", |provider, path| {
+                provider.get_importers(path)
+            })
+        }
+    }
 }
 
 impl<'js> SgRootRjs<'js> {
+    /// Run an import graph query for this file and convert the paths for JS.
+    #[cfg(feature = "native")]
+    fn import_graph(
+        &self,
+        ctx: &Ctx<'js>,
+        what: &str,
+        query: impl FnOnce(
+            &dyn SemanticProvider,
+            &std::path::Path,
+        ) -> language_core::SemanticResult<Vec<std::path::PathBuf>>,
+    ) -> Result<Vec<String>> {
+        let (Some(provider), Some(filename)) =
+            (&self.inner.semantic_provider, &self.inner.filename)
+        else {
+            return Ok(Vec::new());
+        };
+        query(provider.as_ref(), std::path::Path::new(filename))
+            .map(|paths| {
+                paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect()
+            })
+            .map_err(|e| Exception::throw_message(ctx, &format!("Failed to get {what}: {e}")))
+    }
+
     /// Get the rename target path, if rename() was called.
     pub fn get_rename_to(&self) -> Option<String> {
         self.inner
@@ -295,6 +360,8 @@ impl<'js> SgRootRjs<'js> {
                     semantic_provider: None,
                     #[cfg(feature = "native")]
                     current_file_path: None,
+                    #[cfg(feature = "native")]
+                    control_flows: Default::default(),
                 }),
                 _phantom: PhantomData,
             })
@@ -319,6 +386,8 @@ impl<'js> SgRootRjs<'js> {
                     semantic_provider: None,
                     #[cfg(feature = "native")]
                     current_file_path: None,
+                    #[cfg(feature = "native")]
+                    control_flows: Default::default(),
                 }),
                 _phantom: PhantomData,
             })
@@ -341,6 +410,8 @@ impl<'js> SgRootRjs<'js> {
                 semantic_provider: None,
                 #[cfg(feature = "native")]
                 current_file_path: None,
+                #[cfg(feature = "native")]
+                control_flows: Default::default(),
             }),
             _phantom: PhantomData,
         })
@@ -364,6 +435,7 @@ impl<'js> SgRootRjs<'js> {
                 rename_to: Mutex::new(None),
                 semantic_provider,
                 current_file_path,
+                control_flows: Default::default(),
             }),
             _phantom: PhantomData,
         })
@@ -416,7 +488,7 @@ unsafe impl<'js> JsLifetime<'js> for SgNodeRjs<'js> {
 
 /// Helper to find the tightest node containing a byte range.
 #[cfg(feature = "native")]
-fn find_node_at_range<'a>(
+pub(super) fn find_node_at_range<'a>(
     root: &'a Node<'a, TSDoc>,
     start: usize,
     end: usize,
@@ -456,6 +528,61 @@ fn find_node_at_range<'a>(
     }
 }
 
+/// Edits renaming `old_name` to `new_name` at each of `ranges`.
+///
+/// A range may cover more than the identifier itself (e.g. a whole import
+/// specifier), so the first identifier inside it with the old name is
+/// renamed. This leaves the local name of aliased imports untouched, and
+/// shorthand properties are expanded so the property key keeps its name.
+#[cfg(feature = "native")]
+fn rename_edits(
+    root: &Node<TSDoc>,
+    ranges: &[language_core::ByteRange],
+    old_name: &str,
+    new_name: &str,
+) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut edits = Vec::new();
+    for range in ranges {
+        let Some(node) = find_node_at_range(root, range.start as usize, range.end as usize) else {
+            continue;
+        };
+        let Some(identifier) = node
+            .dfs()
+            .find(|n| n.is_named() && n.is_leaf() && n.text() == old_name)
+        else {
+            continue;
+        };
+        let text = match identifier.kind().as_ref() {
+            "shorthand_property_identifier" | "shorthand_property_identifier_pattern" => {
+                format!("{old_name}: {new_name}")
+            }
+            _ => new_name.to_string(),
+        };
+        edits.push((identifier.range(), text));
+    }
+    edits.sort_by(|a, b| (a.0.start, a.0.end).cmp(&(b.0.start, b.0.end)));
+    edits.dedup();
+    edits
+}
+
+#[cfg(feature = "native")]
+fn is_identifier_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$')
+        && chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+}
+
+#[cfg(feature = "native")]
+fn is_same_path(a: &std::path::Path, b: &std::path::Path) -> bool {
+    a == b
+        || matches!(
+            (a.canonicalize(), b.canonicalize()),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
 #[methods]
 impl<'js> SgNodeRjs<'js> {
     pub fn text(&self) -> Result<String> {
@@ -969,11 +1096,12 @@ impl<'js> SgNodeRjs<'js> {
                         }
                     } else {
                         // Definition is in a different file, create new root
-                        let lang_str = detect_language_from_path(&def_result.location.file_path);
+                        let (lang_str, source) =
+                            semantic_source(&def_result.location.file_path, &def_result.content);
                         let lang = Lang::from_str(&lang_str).map_err(|e| {
                             Exception::throw_message(&ctx, &format!("Unsupported language: {}", e))
                         })?;
-                        let grep = AstGrep::new(def_result.content.clone(), lang);
+                        let grep = AstGrep::new(source, lang);
 
                         if let Ok(new_root) = SgRootRjs::try_new_with_semantic(
                             grep,
@@ -1095,12 +1223,13 @@ impl<'js> SgNodeRjs<'js> {
                             file_obj.set("nodes", nodes_array)?;
                         } else {
                             // Create new root for different file
-                            let lang_str = detect_language_from_path(&file_refs.file_path);
+                            let (lang_str, source) =
+                                semantic_source(&file_refs.file_path, &file_refs.content);
                             let lang = match Lang::from_str(&lang_str) {
                                 Ok(l) => l,
                                 Err(_) => continue, // Skip files with unsupported languages
                             };
-                            let grep = AstGrep::new(file_refs.content.clone(), lang);
+                            let grep = AstGrep::new(source, lang);
 
                             if let Ok(new_root) = SgRootRjs::try_new_with_semantic(
                                 grep,
@@ -1153,9 +1282,172 @@ impl<'js> SgNodeRjs<'js> {
         }
     }
 
+    /// Rename the symbol at this node's position across the workspace.
+    ///
+    /// Renames the declaration, every reference and the import/export
+    /// specifiers that name it. Returns the edits for this node's file, to be
+    /// applied with `commitEdits()`. Edits for other files are queued as
+    /// secondary changes and applied with the rest of the run; a file that
+    /// changed on disk before they are applied is reported as a conflict and
+    /// left untouched.
+    ///
+    /// Throws if:
+    /// - No semantic provider is configured
+    /// - The symbol has no definition in the workspace
+    /// - The edits conflict with other changes to the same file
+    #[qjs(rename = "renameSymbol")]
+    pub fn rename_symbol(&self, new_name: String, ctx: Ctx<'js>) -> Result<Vec<JsEdit>> {
+        #[cfg(not(feature = "native"))]
+        {
+            let _ = new_name;
+            return Err(Exception::throw_message(
+                &ctx,
+                "renameSymbol() is only available in native mode",
+            ));
+        }
+        #[cfg(feature = "native")]
+        {
+            use crate::sandbox::engine::execution_engine::{
+                validate_path_within_target, JssgFileChanges,
+            };
+            use crate::sandbox::engine::ExecutionModeFlag;
+
+            if !is_identifier_name(&new_name) {
+                return Err(Exception::throw_message(
+                    &ctx,
+                    &format!("renameSymbol(): '{new_name}' is not a valid identifier"),
+                ));
+            }
+            let provider = self.root.semantic_provider.as_ref().ok_or_else(|| {
+                Exception::throw_message(&ctx, "renameSymbol() requires a semantic provider")
+            })?;
+            let file_path = self
+                .root
+                .filename
+                .as_ref()
+                .map(std::path::PathBuf::from)
+                .ok_or_else(|| Exception::throw_message(&ctx, "Cannot rename: file has no path"))?;
+
+            let old_name = self.inner_node.text().to_string();
+            let byte_range = self.inner_node.range();
+            let range =
+                language_core::ByteRange::new(byte_range.start as u32, byte_range.end as u32);
+
+            let definition = match provider.get_definition(
+                &file_path,
+                range,
+                language_core::DefinitionOptions {
+                    resolve_external: true,
+                },
+            ) {
+                Ok(Some(def)) if def.kind != language_core::DefinitionKind::Import => def,
+                Ok(_) => {
+                    return Err(Exception::throw_message(
+                        &ctx,
+                        &format!(
+                            "Cannot rename '{old_name}': no definition found in the workspace"
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    return Err(Exception::throw_message(
+                        &ctx,
+                        &format!("Failed to get definition: {}", e),
+                    ))
+                }
+            };
+            let references = provider
+                .find_references(&definition.location.file_path, definition.location.range)
+                .map_err(|e| {
+                    Exception::throw_message(&ctx, &format!("Failed to find references: {}", e))
+                })?;
+
+            // Group the definition and all references by file
+            let mut files = vec![(
+                definition.location.file_path.clone(),
+                definition.content,
+                vec![definition.location.range],
+            )];
+            for file_refs in references.files {
+                let ranges = file_refs.locations.iter().map(|loc| loc.range);
+                match files
+                    .iter_mut()
+                    .find(|(path, _, _)| is_same_path(path, &file_refs.file_path))
+                {
+                    Some((_, _, existing)) => existing.extend(ranges),
+                    None => files.push((file_refs.file_path, file_refs.content, ranges.collect())),
+                }
+            }
+
+            let queue_other_files = ctx
+                .userdata::<ExecutionModeFlag>()
+                .map(|f| !f.test_mode)
+                .unwrap_or(false); // No flag = in-memory engine → current file only
+            let mut own_edits = Vec::new();
+            let mut queued = Vec::new();
+            for (path, content, ranges) in files {
+                if is_same_path(&path, &file_path) {
+                    let root_node = self.root.grep.root();
+                    own_edits = rename_edits(&root_node, &ranges, &old_name, &new_name);
+                    continue;
+                }
+
+                let (lang_str, source) = semantic_source(&path, &content);
+                let lang = Lang::from_str(&lang_str).map_err(|e| {
+                    Exception::throw_message(&ctx, &format!("Unsupported language: {}", e))
+                })?;
+                let grep = AstGrep::new(source, lang);
+                let edits = rename_edits(&grep.root(), &ranges, &old_name, &new_name);
+                if edits.is_empty() {
+                    continue;
+                }
+
+                if let Some(current) = &self.root.current_file_path {
+                    if is_same_path(&path, std::path::Path::new(current)) {
+                        return Err(Exception::throw_message(
+                            &ctx,
+                            &format!(
+                                "Cannot rename '{old_name}': it is referenced in the file being transformed. Call renameSymbol() on a node from that file instead."
+                            ),
+                        ));
+                    }
+                }
+                if queue_other_files {
+                    validate_path_within_target(&ctx, &path, "renameSymbol()")?;
+                    queued.push((path, content, edits));
+                }
+            }
+
+            if !queued.is_empty() {
+                let file_changes = ctx
+                    .userdata::<JssgFileChanges>()
+                    .map(|guard| guard.clone())
+                    .ok_or_else(|| {
+                        Exception::throw_message(&ctx, "JssgFileChanges not found in userdata")
+                    })?;
+                for (path, content, edits) in queued {
+                    file_changes
+                        .queue_rename(&path, &content, edits)
+                        .map_err(|e| Exception::throw_message(&ctx, &e))?;
+                }
+            }
+
+            Ok(own_edits
+                .into_iter()
+                .map(|(range, inserted_text)| JsEdit {
+                    start_pos: range.start as u32,
+                    end_pos: range.end as u32,
+                    inserted_text,
+                })
+                .collect())
+        }
+    }
+
     /// Get type information for the symbol at this node's position.
     ///
-    /// TODO: Currently, this is not implemented by any of the semantic providers. So it's not publicly available.
+    /// Python types come from ty's inference. JavaScript/TypeScript types come
+    /// from declarations: annotations, literal initializers, function return
+    /// annotations and type aliases, followed across imports.
     ///
     /// Returns null if:
     /// - No semantic provider is configured
@@ -1192,6 +1484,279 @@ impl<'js> SgNodeRjs<'js> {
             }
         }
     }
+
+    /// Get the innermost lexical scope containing this node.
+    ///
+    /// The returned scope lists the names declared in it (`bindings()`),
+    /// answers whether a name is visible (`has()`), reports the names it uses
+    /// from outside (`freeNames()`) and generates names that collide with
+    /// nothing in it (`uniqueName()`). `parent()` walks out to the module.
+    ///
+    /// Returns null if:
+    /// - No semantic provider is configured
+    /// - The provider has no scope information for this file
+    pub fn scope(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        #[cfg(not(feature = "native"))]
+        {
+            return Ok(Value::new_null(ctx));
+        }
+        #[cfg(feature = "native")]
+        {
+            let provider = match &self.root.semantic_provider {
+                Some(p) => p,
+                None => return Ok(Value::new_null(ctx)),
+            };
+
+            let file_path = match &self.root.filename {
+                Some(f) => std::path::PathBuf::from(f),
+                None => return Ok(Value::new_null(ctx)),
+            };
+
+            let byte_range = self.inner_node.range();
+            let range =
+                language_core::ByteRange::new(byte_range.start as u32, byte_range.end as u32);
+
+            match provider.get_scopes(&file_path, range) {
+                Ok(chain) if chain.is_empty() => Ok(Value::new_null(ctx)),
+                Ok(chain) => crate::ast_grep::scope::SgScopeRjs {
+                    root: Arc::clone(&self.root),
+                    chain: Arc::new(chain),
+                    index: 0,
+                    _phantom: PhantomData,
+                }
+                .into_js(&ctx),
+                Err(e) => Err(Exception::throw_message(
+                    &ctx,
+                    &format!("Failed to get scope: {}", e),
+                )),
+            }
+        }
+    }
+
+    /// Whether this node can run at all: false for code after a `return`,
+    /// `throw`, `break` or `continue` on every path.
+    ///
+    /// Throws an error if no semantic provider is configured.
+    #[qjs(rename = "isReachable")]
+    pub fn is_reachable(&self, ctx: Ctx<'js>) -> Result<bool> {
+        #[cfg(not(feature = "native"))]
+        {
+            return Err(Exception::throw_message(
+                &ctx,
+                "isReachable() is only available in native mode",
+            ));
+        }
+        #[cfg(feature = "native")]
+        {
+            let range = self.byte_range();
+            let graph = self.control_flow(&ctx, "isReachable()", range)?;
+            Ok(graph.is_reachable(range))
+        }
+    }
+
+    /// Whether every path from the start of the enclosing function to
+    /// `other` passes through this node. Nodes in different functions never
+    /// dominate each other.
+    ///
+    /// Throws an error if no semantic provider is configured.
+    pub fn dominates(&self, other: Class<'js, SgNodeRjs<'js>>, ctx: Ctx<'js>) -> Result<bool> {
+        #[cfg(not(feature = "native"))]
+        {
+            let _ = other;
+            return Err(Exception::throw_message(
+                &ctx,
+                "dominates() is only available in native mode",
+            ));
+        }
+        #[cfg(feature = "native")]
+        {
+            let Some((graph, range, other_range)) =
+                self.shared_control_flow(&ctx, "dominates()", &other)?
+            else {
+                return Ok(false);
+            };
+            Ok(graph.dominates(range, other_range))
+        }
+    }
+
+    /// Whether every path from `other` to the exit of the enclosing
+    /// function passes through this node, e.g. a `close()` that runs after
+    /// every `open()`.
+    ///
+    /// Throws an error if no semantic provider is configured.
+    #[qjs(rename = "postDominates")]
+    pub fn post_dominates(&self, other: Class<'js, SgNodeRjs<'js>>, ctx: Ctx<'js>) -> Result<bool> {
+        #[cfg(not(feature = "native"))]
+        {
+            let _ = other;
+            return Err(Exception::throw_message(
+                &ctx,
+                "postDominates() is only available in native mode",
+            ));
+        }
+        #[cfg(feature = "native")]
+        {
+            let Some((graph, range, other_range)) =
+                self.shared_control_flow(&ctx, "postDominates()", &other)?
+            else {
+                return Ok(false);
+            };
+            Ok(graph.post_dominates(range, other_range))
+        }
+    }
+
+    /// Get the statements after which control leaves this function (or the
+    /// function enclosing this node): `return` and uncaught `throw`
+    /// statements and the last statement of each path that falls off the
+    /// end of the body. A body that can finish without running any
+    /// statement reports the function node itself.
+    ///
+    /// Throws an error if no semantic provider is configured.
+    #[qjs(rename = "exitPoints")]
+    pub fn exit_points(&self, ctx: Ctx<'js>) -> Result<Vec<SgNodeRjs<'js>>> {
+        #[cfg(not(feature = "native"))]
+        {
+            return Err(Exception::throw_message(
+                &ctx,
+                "exitPoints() is only available in native mode",
+            ));
+        }
+        #[cfg(feature = "native")]
+        {
+            let graph = self.control_flow(&ctx, "exitPoints()", self.byte_range())?;
+            let root_node = self.root.grep.root();
+            Ok(graph
+                .exit_points()
+                .into_iter()
+                .filter_map(|range| {
+                    let node =
+                        find_node_at_range(&root_node, range.start as usize, range.end as usize)?;
+                    let node_match: NodeMatch<_> = node.into();
+                    let static_node_match: NodeMatch<'static, TSDoc> =
+                        unsafe { std::mem::transmute(node_match) };
+                    Some(SgNodeRjs {
+                        root: Arc::clone(&self.root),
+                        inner_node: static_node_match,
+                        _phantom: PhantomData,
+                    })
+                })
+                .collect())
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl<'js> SgNodeRjs<'js> {
+    fn byte_range(&self) -> language_core::ByteRange {
+        let range = self.inner_node.range();
+        language_core::ByteRange::new(range.start as u32, range.end as u32)
+    }
+
+    /// Get the control-flow graph of the function enclosing `range` in this
+    /// node's file. The graphs are built from the root's source once and
+    /// kept for the life of the root.
+    fn control_flow(
+        &self,
+        ctx: &Ctx<'js>,
+        method: &str,
+        range: language_core::ByteRange,
+    ) -> Result<language_core::ControlFlowGraph> {
+        let provider = self.root.semantic_provider.as_ref().ok_or_else(|| {
+            Exception::throw_message(ctx, &format!("{method} requires a semantic provider"))
+        })?;
+        let file_path = self
+            .root
+            .filename
+            .as_ref()
+            .map(std::path::PathBuf::from)
+            .ok_or_else(|| Exception::throw_message(ctx, &format!("{method}: file has no path")))?;
+
+        let graphs = self.root.control_flows.get_or_init(|| {
+            let source = self.root.grep.root().text().to_string();
+            match provider.get_control_flows(&self.control_flow_path(file_path), &source) {
+                Ok(Some(graphs)) => Ok(graphs),
+                Ok(None) => Err("control flow is not available for this language".to_string()),
+                Err(e) => Err(format!("failed to get control flow: {e}")),
+            }
+        });
+        let graphs = graphs
+            .as_ref()
+            .map_err(|message| Exception::throw_message(ctx, &format!("{method}: {message}")))?;
+        language_core::ControlFlowGraph::innermost(graphs, range)
+            .cloned()
+            .ok_or_else(|| {
+                Exception::throw_message(ctx, &format!("{method}: node is outside its file"))
+            })
+    }
+
+    /// Path the root's source is analyzed under. Script regions of Vue,
+    /// Svelte and HTML files are transformed with the rest of the host file
+    /// blanked out, so their source is already a plain module at host
+    /// offsets; naming it after the region's language keeps providers from
+    /// extracting `<script>` blocks from it a second time.
+    fn control_flow_path(&self, file_path: std::path::PathBuf) -> std::path::PathBuf {
+        use ast_grep_language::SupportLang;
+        use language_core::embedded::HostFormat;
+
+        if HostFormat::from_path(&file_path).is_none() {
+            return file_path;
+        }
+        let extension = match get_language(&self.root.grep) {
+            Lang::Static(SupportLang::TypeScript) => "ts",
+            Lang::Static(SupportLang::Tsx) => "tsx",
+            Lang::Static(SupportLang::JavaScript) => "js",
+            Lang::Static(SupportLang::Python) => "py",
+            _ => return file_path,
+        };
+        let mut path = file_path.into_os_string();
+        path.push(".");
+        path.push(extension);
+        path.into()
+    }
+
+    /// Get the graph shared by this node and `other`, with both ranges, or
+    /// `None` when they are in different files or functions.
+    fn shared_control_flow(
+        &self,
+        ctx: &Ctx<'js>,
+        method: &str,
+        other: &Class<'js, SgNodeRjs<'js>>,
+    ) -> Result<
+        Option<(
+            language_core::ControlFlowGraph,
+            language_core::ByteRange,
+            language_core::ByteRange,
+        )>,
+    > {
+        let other = other.borrow();
+        if !Arc::ptr_eq(&self.root, &other.root) {
+            return Ok(None);
+        }
+        let range = self.byte_range();
+        let other_range = other.byte_range();
+        let graph = self.control_flow(ctx, method, range)?;
+        let other_graph = self.control_flow(ctx, method, other_range)?;
+        if graph.range != other_graph.range {
+            return Ok(None);
+        }
+        Ok(Some((graph, range, other_range)))
+    }
+}
+
+/// Language and parse text for a file reached through semantic analysis.
+/// Vue and Svelte components and HTML pages are parsed as their `<script>`
+/// blocks, blanked around so node ranges match the provider's offsets.
+#[cfg(feature = "native")]
+fn semantic_source(path: &std::path::Path, content: &str) -> (String, String) {
+    use language_core::embedded::{self, HostFormat};
+
+    match HostFormat::from_path(path) {
+        Some(format) if format != HostFormat::Markdown => {
+            let (language, masked) = embedded::script_module(format, content);
+            (language.to_string(), masked)
+        }
+        _ => (detect_language_from_path(path), content.to_string()),
+    }
 }
 
 /// Detect language from file path extension.
@@ -0,0 +1,383 @@
+//! Recorded LLM exchanges for deterministic codemod tests.
+//!
+//! A cassette maps a hash of each [`LlmRequest`] to the response the model
+//! gave when it was recorded. Replaying serves those responses without a
+//! network connection and fails on requests that were never recorded, so a
+//! codemod whose prompts changed cannot silently pass against stale answers.
+//!
+//! Workflow `ai` steps are recorded in the same file, keyed by the hash of
+//! their prompt and system prompt. Their answer is the set of files the
+//! agent changed, which replaying writes back instead of running it.
+
+use super::{LlmRequest, LlmRequestHandler, LlmResponse};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// File name of a test fixture's cassette.
+pub const CASSETTE_FILE_NAME: &str = "llm-cassette.json";
+
+/// Bumped whenever the file format or the request hash changes.
+const CASSETTE_FORMAT_VERSION: u32 = 1;
+
+/// Text files an AI step changed, by `/`-separated path relative to the
+/// directory it ran in. Deleted files map to `None`.
+pub type AiStepChanges = BTreeMap<String, Option<String>>;
+
+/// Hash identifying `request` in a cassette.
+pub fn request_hash(request: &LlmRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CASSETTE_FORMAT_VERSION.to_le_bytes());
+    hasher.update(serde_json::to_vec(request).unwrap_or_default());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ai_steps: Vec<AiStepInteraction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Interaction {
+    request_hash: String,
+    /// Kept so recordings can be reviewed; only the hash is matched
+    request: LlmRequest,
+    response: LlmResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AiStepInteraction {
+    request_hash: String,
+    request: LlmRequest,
+    changes: AiStepChanges,
+}
+
+/// Everything recorded in a cassette, by request hash.
+#[derive(Debug, Default)]
+struct Recordings {
+    interactions: BTreeMap<String, Interaction>,
+    ai_steps: BTreeMap<String, AiStepInteraction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Answer from the recorded responses, failing on unknown requests
+    Replay,
+    /// Forward requests to a real client and record what it answers
+    Record,
+}
+
+/// Cassette file backing an [`LlmRequestHandler`]. Clones share recordings.
+#[derive(Clone)]
+pub struct LlmCassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    recordings: Arc<Mutex<Recordings>>,
+}
+
+impl LlmCassette {
+    /// Load the cassette at `path` for replay. A missing file replays as an
+    /// empty cassette.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let recordings = if path.is_file() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read LLM cassette {}: {e}", path.display()))?;
+            let file: CassetteFile = serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid LLM cassette {}: {e}", path.display()))?;
+            if file.version != CASSETTE_FORMAT_VERSION {
+                return Err(format!(
+                    "LLM cassette {} has format version {}, expected {CASSETTE_FORMAT_VERSION}; re-record it with --llm-record",
+                    path.display(),
+                    file.version
+                ));
+            }
+            Recordings {
+                interactions: file
+                    .interactions
+                    .into_iter()
+                    .map(|interaction| (interaction.request_hash.clone(), interaction))
+                    .collect(),
+                ai_steps: file
+                    .ai_steps
+                    .into_iter()
+                    .map(|step| (step.request_hash.clone(), step))
+                    .collect(),
+            }
+        } else {
+            Recordings::default()
+        };
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            recordings: Arc::new(Mutex::new(recordings)),
+        })
+    }
+
+    /// Start a new recording at `path`. Existing recordings are replaced once
+    /// the first response is recorded, so stale requests do not linger.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            recordings: Arc::new(Mutex::new(Recordings::default())),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Number of recorded LLM requests and AI steps.
+    pub fn len(&self) -> usize {
+        self.recordings
+            .lock()
+            .map(|recordings| recordings.interactions.len() + recordings.ai_steps.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Handler serving requests from the cassette. When recording, requests
+    /// go to `upstream` and every response is written to the cassette file
+    /// as soon as it arrives.
+    pub fn handler(&self, upstream: Option<LlmRequestHandler>) -> LlmRequestHandler {
+        let cassette = self.clone();
+        Arc::new(move |request: LlmRequest| {
+            let cassette = cassette.clone();
+            let upstream = upstream.clone();
+            Box::pin(async move {
+                match cassette.mode {
+                    CassetteMode::Replay => cassette.lookup(&request),
+                    CassetteMode::Record => {
+                        let upstream = upstream.ok_or_else(|| {
+                            "Recording LLM responses requires a configured LLM client".to_string()
+                        })?;
+                        let response = upstream(request.clone()).await?;
+                        cassette.insert(request, response.clone())?;
+                        Ok(response)
+                    }
+                }
+            })
+        })
+    }
+
+    /// Files the AI step with `request`'s prompt changed when it was
+    /// recorded.
+    pub fn ai_step_changes(&self, request: &LlmRequest) -> Result<AiStepChanges, String> {
+        let hash = request_hash(request);
+        self.lock()?
+            .ai_steps
+            .get(&hash)
+            .map(|step| step.changes.clone())
+            .ok_or_else(|| {
+                format!(
+                    "No recorded AI step in {} for request {hash} (prompt: {}); re-record it with --llm-record",
+                    self.path.display(),
+                    prompt_excerpt(&request.prompt)
+                )
+            })
+    }
+
+    /// Record the files an AI step changed and write the cassette file.
+    pub fn record_ai_step(
+        &self,
+        request: LlmRequest,
+        changes: AiStepChanges,
+    ) -> Result<(), String> {
+        let mut recordings = self.lock()?;
+        let request_hash = request_hash(&request);
+        recordings.ai_steps.insert(
+            request_hash.clone(),
+            AiStepInteraction {
+                request_hash,
+                request,
+                changes,
+            },
+        );
+        self.write(&recordings)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Recordings>, String> {
+        self.recordings
+            .lock()
+            .map_err(|_| "LLM cassette lock poisoned".to_string())
+    }
+
+    fn lookup(&self, request: &LlmRequest) -> Result<LlmResponse, String> {
+        let hash = request_hash(request);
+        self.lock()?
+            .interactions
+            .get(&hash)
+            .map(|interaction| interaction.response.clone())
+            .ok_or_else(|| {
+                format!(
+                    "No recorded LLM response in {} for request {hash} (prompt: {}); re-record it with --llm-record",
+                    self.path.display(),
+                    prompt_excerpt(&request.prompt)
+                )
+            })
+    }
+
+    fn insert(&self, request: LlmRequest, response: LlmResponse) -> Result<(), String> {
+        let mut recordings = self.lock()?;
+        let request_hash = request_hash(&request);
+        recordings.interactions.insert(
+            request_hash.clone(),
+            Interaction {
+                request_hash,
+                request,
+                response,
+            },
+        );
+        self.write(&recordings)
+    }
+
+    fn write(&self, recordings: &Recordings) -> Result<(), String> {
+        let file = CassetteFile {
+            version: CASSETTE_FORMAT_VERSION,
+            interactions: recordings.interactions.values().cloned().collect(),
+            ai_steps: recordings.ai_steps.values().cloned().collect(),
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize LLM cassette: {e}"))?;
+        fs::write(&self.path, content + "\n")
+            .map_err(|e| format!("Failed to write LLM cassette {}: {e}", self.path.display()))
+    }
+}
+
+fn prompt_excerpt(prompt: &str) -> String {
+    const EXCERPT_LIMIT: usize = 80;
+    let line = prompt.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(EXCERPT_LIMIT) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(prompt: &str) -> LlmRequest {
+        LlmRequest {
+            prompt: prompt.to_string(),
+            system_prompt: None,
+            output_schema: None,
+            max_tokens: None,
+        }
+    }
+
+    fn echo_handler() -> LlmRequestHandler {
+        Arc::new(|request: LlmRequest| {
+            Box::pin(async move {
+                Ok(LlmResponse {
+                    output: format!("echo: {}", request.prompt),
+                })
+            })
+        })
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn recorded_responses_replay_without_a_client() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CASSETTE_FILE_NAME);
+
+        let recorder = LlmCassette::record(&path);
+        let handler = recorder.handler(Some(echo_handler()));
+        let recorded = block_on(handler(request("rename foo"))).unwrap();
+        assert_eq!(recorded.output, "echo: rename foo");
+        assert_eq!(recorder.len(), 1);
+
+        let player = LlmCassette::replay(&path).unwrap();
+        let handler = player.handler(None);
+        assert_eq!(
+            block_on(handler(request("rename foo"))).unwrap().output,
+            "echo: rename foo"
+        );
+        let error = block_on(handler(request("rename bar"))).unwrap_err();
+        assert!(error.contains("No recorded LLM response"), "{error}");
+    }
+
+    #[test]
+    fn ai_steps_replay_their_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CASSETTE_FILE_NAME);
+        let changes: AiStepChanges = [
+            ("src/app.ts".to_string(), Some("export {};\n".to_string())),
+            ("src/old.ts".to_string(), None),
+        ]
+        .into_iter()
+        .collect();
+
+        let recorder = LlmCassette::record(&path);
+        let handler = recorder.handler(Some(echo_handler()));
+        block_on(handler(request("rename foo"))).unwrap();
+        recorder
+            .record_ai_step(request("migrate the app"), changes.clone())
+            .unwrap();
+        assert_eq!(recorder.len(), 2);
+
+        let player = LlmCassette::replay(&path).unwrap();
+        assert_eq!(
+            player.ai_step_changes(&request("migrate the app")).unwrap(),
+            changes
+        );
+        // LLM requests and AI steps with the same prompt are kept apart
+        assert!(player.ai_step_changes(&request("rename foo")).is_err());
+        let error = player
+            .ai_step_changes(&request("migrate the tests"))
+            .unwrap_err();
+        assert!(error.contains("No recorded AI step"), "{error}");
+    }
+
+    #[test]
+    fn any_request_field_changes_the_hash() {
+        let base = request("prompt");
+        let mut with_system = base.clone();
+        with_system.system_prompt = Some("be terse".to_string());
+        let mut with_limit = base.clone();
+        with_limit.max_tokens = Some(10);
+
+        assert_eq!(request_hash(&base), request_hash(&request("prompt")));
+        assert_ne!(request_hash(&base), request_hash(&with_system));
+        assert_ne!(request_hash(&base), request_hash(&with_limit));
+    }
+
+    #[test]
+    fn missing_cassette_replays_as_empty_and_recording_needs_a_client() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CASSETTE_FILE_NAME);
+
+        let player = LlmCassette::replay(&path).unwrap();
+        assert!(player.is_empty());
+        assert!(block_on(player.handler(None)(request("hi"))).is_err());
+
+        let recorder = LlmCassette::record(&path);
+        assert!(block_on(recorder.handler(None)(request("hi"))).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,985 @@
+//! Statement, branch and function coverage for codemod sources.
+//!
+//! With a [`CoverageCollector`] attached, the module loader instruments every
+//! codemod module under the collector's root before QuickJS declares it. The
+//! instrumented code counts hits in a per-module array on the global object,
+//! which [`CoverageCollector::collect`] adds to the collector's totals once an
+//! execution finishes, so one collector can aggregate any number of runs on
+//! any number of threads.
+//!
+//! Counters are placed from positions in the loaded code. TypeScript is
+//! transpiled by stripping types in place, so those positions are already the
+//! author's; bundles are mapped back to their sources through their source
+//! maps when the report is built.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use oxc::allocator::{Allocator, Vec as ArenaVec};
+use oxc::ast::ast::{
+    ArrowFunctionExpression, ConditionalExpression, Declaration, DoWhileStatement,
+    ExportDefaultDeclarationKind, Expression, ForInStatement, ForOfStatement, ForStatement,
+    Function, FunctionBody, IfStatement, LogicalExpression, MethodDefinition, ObjectProperty,
+    Statement, SwitchStatement, VariableDeclarator, WhileStatement,
+};
+use oxc::ast_visit::{walk, Visit};
+use oxc::parser::Parser;
+use oxc::span::{GetSpan, SourceType};
+use oxc::syntax::scope::ScopeFlags;
+use rquickjs::{Ctx, Object};
+
+use crate::utils::source_map::SourceMap;
+
+/// Global object holding the hit counters of every instrumented module.
+pub const COVERAGE_GLOBAL: &str = "__codemodCoverage__";
+/// Module-local binding for the module's own counters.
+const COUNTERS_BINDING: &str = "__codemod_coverage__";
+
+/// What a counter counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CounterKind {
+    Statement,
+    Function {
+        name: String,
+    },
+    /// One arm of an `if`, `switch`, `?:` or `&&`/`||`/`??`
+    Branch {
+        branch: u32,
+        arm: u32,
+    },
+}
+
+/// A counter and the position it was placed at. Lines and columns are
+/// 1-based; branch arms share the position of their branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter {
+    pub kind: CounterKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A module rewritten to count its own execution.
+#[derive(Debug, Clone)]
+pub struct InstrumentedModule {
+    pub code: String,
+    pub counters: Vec<Counter>,
+}
+
+/// Instrument an ES module. `key` names the module's counters in
+/// [`COVERAGE_GLOBAL`].
+///
+/// Counters are inserted as text so that every line of the original code
+/// stays on the same line, and stack traces still point at the right place.
+pub fn instrument(source: &str, key: &str) -> Result<InstrumentedModule, String> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, SourceType::mjs()).parse();
+    if parsed.panicked || !parsed.errors.is_empty() {
+        return Err(parsed
+            .errors
+            .first()
+            .map(|error| error.to_string())
+            .unwrap_or_else(|| "failed to parse module".to_string()));
+    }
+
+    let mut instrumenter = Instrumenter::new(source);
+    // After a hashbang, the header goes on the next line so it is not
+    // commented out
+    let header_offset = parsed
+        .program
+        .hashbang
+        .as_ref()
+        .map(|hashbang| {
+            source[hashbang.span.end as usize..]
+                .find('\n')
+                .map_or(source.len(), |newline| {
+                    hashbang.span.end as usize + newline + 1
+                })
+        })
+        .unwrap_or(0);
+    instrumenter.opener(header_offset as u32, String::new());
+    let header_index = instrumenter.inserts.len() - 1;
+    instrumenter.visit_program(&parsed.program);
+
+    let key = serde_json::to_string(key).map_err(|error| error.to_string())?;
+    instrumenter.inserts[header_index].text = format!(
+        "var {COUNTERS_BINDING} = ((globalThis.{COVERAGE_GLOBAL} ??= {{}})[{key}] ??= new Array({}).fill(0));",
+        instrumenter.counters.len()
+    );
+    Ok(instrumenter.finish())
+}
+
+struct Insert {
+    offset: u32,
+    closing: bool,
+    depth: u32,
+    text: String,
+}
+
+struct Instrumenter<'s> {
+    source: &'s str,
+    line_starts: Vec<usize>,
+    counters: Vec<Counter>,
+    inserts: Vec<Insert>,
+    /// Nesting of the constructs that wrap code, so that text inserted at the
+    /// same offset opens outside-in and closes inside-out
+    depth: u32,
+    branches: u32,
+    /// Name for the next function, from the binding or key it is assigned to
+    pending_name: Option<String>,
+    anonymous: usize,
+}
+
+impl<'s> Instrumenter<'s> {
+    fn new(source: &'s str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+            counters: Vec::new(),
+            inserts: Vec::new(),
+            depth: 0,
+            branches: 0,
+            pending_name: None,
+            anonymous: 0,
+        }
+    }
+
+    /// Add a counter placed at `offset` and return the code that increments it.
+    fn counter(&mut self, kind: CounterKind, offset: u32) -> String {
+        let offset = offset as usize;
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.source[line_start..offset].chars().count() + 1;
+        self.counters.push(Counter { kind, line, column });
+        format!("{COUNTERS_BINDING}[{}]++", self.counters.len() - 1)
+    }
+
+    fn opener(&mut self, offset: u32, text: String) {
+        self.inserts.push(Insert {
+            offset,
+            closing: false,
+            depth: self.depth,
+            text,
+        });
+    }
+
+    fn closer(&mut self, offset: u32, text: String) {
+        self.inserts.push(Insert {
+            offset,
+            closing: true,
+            depth: self.depth,
+            text,
+        });
+    }
+
+    fn next_branch(&mut self) -> u32 {
+        self.branches += 1;
+        self.branches - 1
+    }
+
+    fn function_name(&mut self, own: Option<&str>) -> String {
+        let pending = self.pending_name.take();
+        own.map(str::to_string).or(pending).unwrap_or_else(|| {
+            self.anonymous += 1;
+            format!("(anonymous_{})", self.anonymous)
+        })
+    }
+
+    /// Count a statement that is the body of an `if` or a loop. Blocks get the
+    /// `prefix` counters after their `{`; other statements are wrapped in a
+    /// block so they can be counted.
+    fn single_statement(&mut self, statement: &Statement<'_>, prefix: String) {
+        let span = statement.span();
+        if let Statement::BlockStatement(block) = statement {
+            if !prefix.is_empty() {
+                self.opener(block.span.start + 1, prefix);
+            }
+            return;
+        }
+        let mut text = format!("{{{prefix}");
+        if is_executable(statement) {
+            let increment = self.counter(CounterKind::Statement, span.start);
+            text.push_str(&increment);
+            text.push(';');
+        }
+        self.opener(span.start, text);
+        self.closer(span.end, "}".to_string());
+    }
+
+    /// Wrap an expression in a sequence that first bumps `increment`.
+    fn wrap_expression(&mut self, expression: &Expression<'_>, increment: String) {
+        let span = expression.span();
+        self.opener(span.start, format!("({increment}, "));
+        self.closer(span.end, ")".to_string());
+    }
+
+    fn finish(mut self) -> InstrumentedModule {
+        self.inserts.sort_by(|a, b| {
+            a.offset
+                .cmp(&b.offset)
+                .then(b.closing.cmp(&a.closing))
+                .then_with(|| {
+                    if a.closing {
+                        b.depth.cmp(&a.depth)
+                    } else {
+                        a.depth.cmp(&b.depth)
+                    }
+                })
+        });
+
+        let extra: usize = self.inserts.iter().map(|insert| insert.text.len()).sum();
+        let mut code = String::with_capacity(self.source.len() + extra);
+        let mut position = 0;
+        for insert in &self.inserts {
+            let offset = insert.offset as usize;
+            code.push_str(&self.source[position..offset]);
+            code.push_str(&insert.text);
+            position = offset;
+        }
+        code.push_str(&self.source[position..]);
+
+        InstrumentedModule {
+            code,
+            counters: self.counters,
+        }
+    }
+}
+
+/// Offset of the first statement of a function body, after its directives.
+fn body_start(body: &FunctionBody<'_>) -> u32 {
+    body.directives
+        .last()
+        .map_or(body.span.start + 1, |directive| directive.span.end)
+}
+
+/// Whether a statement does anything when control reaches it. Imports and
+/// function declarations are hoisted, so they are not counted.
+fn is_executable(statement: &Statement<'_>) -> bool {
+    match statement {
+        Statement::FunctionDeclaration(_)
+        | Statement::ImportDeclaration(_)
+        | Statement::ExportAllDeclaration(_)
+        | Statement::EmptyStatement(_) => false,
+        Statement::ExportNamedDeclaration(export) => matches!(
+            &export.declaration,
+            Some(declaration) if !matches!(declaration, Declaration::FunctionDeclaration(_))
+        ),
+        Statement::ExportDefaultDeclaration(export) => !matches!(
+            export.declaration,
+            ExportDefaultDeclarationKind::FunctionDeclaration(_)
+        ),
+        _ => true,
+    }
+}
+
+fn is_function_like(expression: &Expression<'_>) -> bool {
+    matches!(
+        expression,
+        Expression::ArrowFunctionExpression(_) | Expression::FunctionExpression(_)
+    )
+}
+
+impl<'a> Visit<'a> for Instrumenter<'_> {
+    fn visit_statements(&mut self, statements: &ArenaVec<'a, Statement<'a>>) {
+        for statement in statements {
+            if is_executable(statement) {
+                let start = statement.span().start;
+                let increment = self.counter(CounterKind::Statement, start);
+                self.opener(start, format!("{increment};"));
+            }
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_if_statement(&mut self, it: &IfStatement<'a>) {
+        self.depth += 1;
+        let branch = self.next_branch();
+        let consequent = self.counter(CounterKind::Branch { branch, arm: 0 }, it.span.start);
+        self.single_statement(&it.consequent, format!("{consequent};"));
+        let alternate = self.counter(CounterKind::Branch { branch, arm: 1 }, it.span.start);
+        match &it.alternate {
+            Some(statement) => self.single_statement(statement, format!("{alternate};")),
+            None => self.closer(it.span.end, format!(" else {{ {alternate}; }}")),
+        }
+        walk::walk_if_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_switch_statement(&mut self, it: &SwitchStatement<'a>) {
+        self.depth += 1;
+        let branch = self.next_branch();
+        for (arm, case) in it.cases.iter().enumerate() {
+            let increment = self.counter(
+                CounterKind::Branch {
+                    branch,
+                    arm: arm as u32,
+                },
+                it.span.start,
+            );
+            let offset = case
+                .consequent
+                .first()
+                .map_or(case.span.end, |statement| statement.span().start);
+            self.opener(offset, format!("{increment};"));
+        }
+        walk::walk_switch_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_conditional_expression(&mut self, it: &ConditionalExpression<'a>) {
+        self.depth += 1;
+        let branch = self.next_branch();
+        let consequent = self.counter(CounterKind::Branch { branch, arm: 0 }, it.span.start);
+        self.wrap_expression(&it.consequent, consequent);
+        let alternate = self.counter(CounterKind::Branch { branch, arm: 1 }, it.span.start);
+        self.wrap_expression(&it.alternate, alternate);
+        walk::walk_conditional_expression(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_logical_expression(&mut self, it: &LogicalExpression<'a>) {
+        self.depth += 1;
+        let branch = self.next_branch();
+        let left = self.counter(CounterKind::Branch { branch, arm: 0 }, it.span.start);
+        self.wrap_expression(&it.left, left);
+        let right = self.counter(CounterKind::Branch { branch, arm: 1 }, it.span.start);
+        self.wrap_expression(&it.right, right);
+        walk::walk_logical_expression(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_for_statement(&mut self, it: &ForStatement<'a>) {
+        self.depth += 1;
+        self.single_statement(&it.body, String::new());
+        walk::walk_for_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_for_in_statement(&mut self, it: &ForInStatement<'a>) {
+        self.depth += 1;
+        self.single_statement(&it.body, String::new());
+        walk::walk_for_in_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+        self.depth += 1;
+        self.single_statement(&it.body, String::new());
+        walk::walk_for_of_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_while_statement(&mut self, it: &WhileStatement<'a>) {
+        self.depth += 1;
+        self.single_statement(&it.body, String::new());
+        walk::walk_while_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_do_while_statement(&mut self, it: &DoWhileStatement<'a>) {
+        self.depth += 1;
+        self.single_statement(&it.body, String::new());
+        walk::walk_do_while_statement(self, it);
+        self.depth -= 1;
+    }
+
+    fn visit_function(&mut self, it: &Function<'a>, flags: ScopeFlags) {
+        let name = self.function_name(it.id.as_ref().map(|id| id.name.as_str()));
+        let Some(body) = &it.body else {
+            walk::walk_function(self, it, flags);
+            return;
+        };
+        self.depth += 1;
+        let increment = self.counter(CounterKind::Function { name }, it.span.start);
+        self.opener(body_start(body), format!("{increment};"));
+        walk::walk_function(self, it, flags);
+        self.depth -= 1;
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        let name = self.function_name(None);
+        self.depth += 1;
+        let increment = self.counter(CounterKind::Function { name }, it.span.start);
+        match it.get_expression() {
+            // `x => expr` has no statements to put the counter before
+            Some(expression) => {
+                self.wrap_expression(expression, increment);
+                self.visit_formal_parameters(&it.params);
+                self.visit_expression(expression);
+            }
+            None => {
+                self.opener(body_start(&it.body), format!("{increment};"));
+                walk::walk_arrow_function_expression(self, it);
+            }
+        }
+        self.depth -= 1;
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        if it.init.as_ref().is_some_and(is_function_like) {
+            self.pending_name = it.id.get_identifier_name().map(|name| name.to_string());
+        }
+        walk::walk_variable_declarator(self, it);
+    }
+
+    fn visit_object_property(&mut self, it: &ObjectProperty<'a>) {
+        if is_function_like(&it.value) {
+            self.pending_name = it.key.static_name().map(|name| name.to_string());
+        }
+        walk::walk_object_property(self, it);
+    }
+
+    fn visit_method_definition(&mut self, it: &MethodDefinition<'a>) {
+        self.pending_name = it.key.static_name().map(|name| name.to_string());
+        walk::walk_method_definition(self, it);
+    }
+}
+
+struct ModuleCoverage {
+    path: PathBuf,
+    source: String,
+    instrumented: String,
+    counters: Vec<Counter>,
+    hits: Vec<u64>,
+}
+
+/// Collects coverage of the codemod modules under a root directory, across
+/// executions and threads.
+pub struct CoverageCollector {
+    root: PathBuf,
+    modules: Mutex<HashMap<String, ModuleCoverage>>,
+}
+
+impl CoverageCollector {
+    /// Cover the modules under `root`, except those in `node_modules`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            root: root.canonicalize().unwrap_or(root),
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn covers(&self, path: &Path) -> bool {
+        path.canonicalize()
+            .is_ok_and(|path| path.starts_with(&self.root) && !is_dependency(&path))
+    }
+
+    /// Instrumented code for the module at `path`, or `None` when the module
+    /// is not covered or cannot be instrumented.
+    pub fn instrument(&self, path: &Path, code: &str) -> Option<String> {
+        if !self.covers(path) {
+            return None;
+        }
+        let key = path.to_string_lossy().to_string();
+        let mut modules = self.modules.lock().ok()?;
+        if let Some(module) = modules.get(&key).filter(|module| module.source == code) {
+            return Some(module.instrumented.clone());
+        }
+
+        let instrumented = match instrument(code, &key) {
+            Ok(instrumented) => instrumented,
+            Err(error) => {
+                eprintln!(
+                    "Warning: not collecting coverage for {}: {error}",
+                    path.display()
+                );
+                return None;
+            }
+        };
+        modules.insert(
+            key,
+            ModuleCoverage {
+                path: path.to_path_buf(),
+                source: code.to_string(),
+                instrumented: instrumented.code.clone(),
+                hits: vec![0; instrumented.counters.len()],
+                counters: instrumented.counters,
+            },
+        );
+        Some(instrumented.code)
+    }
+
+    /// Add the hits counted in a context to the totals. Call once per
+    /// execution, after it finishes.
+    pub fn collect(&self, ctx: &Ctx<'_>) {
+        let Ok(store) = ctx.globals().get::<_, Object>(COVERAGE_GLOBAL) else {
+            return;
+        };
+        let Ok(mut modules) = self.modules.lock() else {
+            return;
+        };
+        for entry in store.props::<String, Vec<f64>>() {
+            let Ok((key, hits)) = entry else {
+                continue;
+            };
+            if let Some(module) = modules.get_mut(&key) {
+                for (total, hit) in module.hits.iter_mut().zip(hits) {
+                    *total += hit as u64;
+                }
+            }
+        }
+    }
+
+    /// Coverage of the original sources, mapping bundles through their
+    /// source maps.
+    pub fn report(&self) -> CoverageReport {
+        let mut report = CoverageReport::default();
+        let Ok(modules) = self.modules.lock() else {
+            return report;
+        };
+        let mut modules: Vec<_> = modules.values().collect();
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for module in modules {
+            let is_script = matches!(
+                module.path.extension().and_then(|ext| ext.to_str()),
+                Some("js" | "mjs" | "cjs")
+            );
+            let source_map = is_script
+                .then(|| SourceMap::load_for_script(&module.path))
+                .flatten();
+            for (counter, hits) in module.counters.iter().zip(&module.hits) {
+                let (source, line) = match &source_map {
+                    Some(map) => match map.lookup(counter.line, counter.column) {
+                        Some(location) => (location.source, location.line),
+                        None => continue,
+                    },
+                    None => (module.path.clone(), counter.line),
+                };
+                if is_dependency(&source) {
+                    continue;
+                }
+                report
+                    .files
+                    .entry(source)
+                    .or_default()
+                    .add(&counter.kind, line, *hits);
+            }
+        }
+        report
+    }
+}
+
+fn is_dependency(path: &Path) -> bool {
+    path.components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+}
+
+/// Covered and total counts of one metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoverageTotals {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl CoverageTotals {
+    fn count<'a>(hits: impl IntoIterator<Item = &'a u64>) -> Self {
+        hits.into_iter().fold(Self::default(), |totals, hits| Self {
+            covered: totals.covered + usize::from(*hits > 0),
+            total: totals.total + 1,
+        })
+    }
+
+    /// Covered percentage; 100 when there is nothing to cover.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            covered: self.covered + other.covered,
+            total: self.total + other.total,
+        }
+    }
+}
+
+impl std::fmt::Display for CoverageTotals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2}% ({}/{})",
+            self.percent(),
+            self.covered,
+            self.total
+        )
+    }
+}
+
+/// Coverage of one source file.
+#[derive(Debug, Clone, Default)]
+pub struct FileCoverage {
+    /// Hits by line, for lines where a statement starts
+    pub lines: BTreeMap<usize, u64>,
+    /// Hits by line and name
+    pub functions: BTreeMap<(usize, String), u64>,
+    /// Hits of each arm, by line and branch
+    pub branches: BTreeMap<(usize, u32), Vec<u64>>,
+}
+
+impl FileCoverage {
+    fn add(&mut self, kind: &CounterKind, line: usize, hits: u64) {
+        match kind {
+            CounterKind::Statement => {
+                let line_hits = self.lines.entry(line).or_default();
+                *line_hits = (*line_hits).max(hits);
+            }
+            CounterKind::Function { name } => {
+                *self.functions.entry((line, name.clone())).or_default() += hits;
+            }
+            CounterKind::Branch { branch, arm } => {
+                let arms = self.branches.entry((line, *branch)).or_default();
+                let arm = *arm as usize;
+                if arms.len() <= arm {
+                    arms.resize(arm + 1, 0);
+                }
+                arms[arm] += hits;
+            }
+        }
+    }
+
+    pub fn line_totals(&self) -> CoverageTotals {
+        CoverageTotals::count(self.lines.values())
+    }
+
+    pub fn branch_totals(&self) -> CoverageTotals {
+        CoverageTotals::count(self.branches.values().flatten())
+    }
+
+    pub fn function_totals(&self) -> CoverageTotals {
+        CoverageTotals::count(self.functions.values())
+    }
+
+    /// Lines never executed, with consecutive lines merged: `3-5, 9`.
+    pub fn uncovered_lines(&self) -> String {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut in_range = false;
+        for (&line, &hits) in &self.lines {
+            if hits > 0 {
+                in_range = false;
+            } else if let Some(range) = ranges.last_mut().filter(|_| in_range) {
+                // Lines without statements do not end a range
+                range.1 = line;
+            } else {
+                ranges.push((line, line));
+                in_range = true;
+            }
+        }
+        ranges
+            .iter()
+            .map(|(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{start}-{end}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Coverage of every source file that ran, or could have run.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+impl CoverageReport {
+    /// The report in lcov tracefile format.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for (path, file) in &self.files {
+            let _ = writeln!(lcov, "TN:\nSF:{}", path.display());
+            for (line, name) in file.functions.keys() {
+                let _ = writeln!(lcov, "FN:{line},{name}");
+            }
+            for ((_, name), hits) in &file.functions {
+                let _ = writeln!(lcov, "FNDA:{hits},{name}");
+            }
+            let functions = file.function_totals();
+            let _ = writeln!(lcov, "FNF:{}\nFNH:{}", functions.total, functions.covered);
+            for ((line, branch), arms) in &file.branches {
+                for (arm, hits) in arms.iter().enumerate() {
+                    let _ = writeln!(lcov, "BRDA:{line},{branch},{arm},{hits}");
+                }
+            }
+            let branches = file.branch_totals();
+            let _ = writeln!(lcov, "BRF:{}\nBRH:{}", branches.total, branches.covered);
+            for (line, hits) in &file.lines {
+                let _ = writeln!(lcov, "DA:{line},{hits}");
+            }
+            let lines = file.line_totals();
+            let _ = writeln!(lcov, "LF:{}\nLH:{}", lines.total, lines.covered);
+            lcov.push_str("end_of_record\n");
+        }
+        lcov
+    }
+
+    /// A table of per-file and overall coverage, with paths relative to
+    /// `base` where possible.
+    pub fn summary(&self, base: &Path) -> String {
+        let mut rows = vec![[
+            "File".to_string(),
+            "Lines".to_string(),
+            "Branches".to_string(),
+            "Functions".to_string(),
+            "Uncovered lines".to_string(),
+        ]];
+        let mut totals = [CoverageTotals::default(); 3];
+        for (path, file) in &self.files {
+            let file_totals = [
+                file.line_totals(),
+                file.branch_totals(),
+                file.function_totals(),
+            ];
+            for (total, file_total) in totals.iter_mut().zip(file_totals) {
+                *total = total.add(file_total);
+            }
+            rows.push([
+                path.strip_prefix(base)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string(),
+                file_totals[0].to_string(),
+                file_totals[1].to_string(),
+                file_totals[2].to_string(),
+                file.uncovered_lines(),
+            ]);
+        }
+        rows.push([
+            "All files".to_string(),
+            totals[0].to_string(),
+            totals[1].to_string(),
+            totals[2].to_string(),
+            String::new(),
+        ]);
+
+        let mut widths = [0; 5];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut summary = String::new();
+        for row in &rows {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            let _ = writeln!(summary, "{}", line.trim_end());
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(code: &str) -> Vec<u64> {
+        let runtime = rquickjs::Runtime::new().unwrap();
+        let context = rquickjs::Context::full(&runtime).unwrap();
+        context.with(|ctx| {
+            rquickjs::Module::evaluate(ctx.clone(), "test.js", code)
+                .unwrap()
+                .finish::<()>()
+                .unwrap();
+            let store: Object = ctx.globals().get(COVERAGE_GLOBAL).unwrap();
+            store
+                .get::<_, Vec<f64>>("test.js")
+                .unwrap()
+                .into_iter()
+                .map(|hits| hits as u64)
+                .collect()
+        })
+    }
+
+    /// Load `path` through the collector and run it in a fresh context.
+    fn run_collected(collector: &CoverageCollector, path: &Path) {
+        let code = std::fs::read_to_string(path).unwrap();
+        let instrumented = collector.instrument(path, &code).unwrap();
+        let runtime = rquickjs::Runtime::new().unwrap();
+        let context = rquickjs::Context::full(&runtime).unwrap();
+        let name = path.to_string_lossy();
+        context.with(|ctx| {
+            rquickjs::Module::evaluate(ctx.clone(), name.as_ref(), instrumented)
+                .unwrap()
+                .finish::<()>()
+                .unwrap();
+            collector.collect(&ctx);
+        });
+    }
+
+    fn hits_of<'c>(
+        module: &'c InstrumentedModule,
+        hits: &'c [u64],
+        kind: impl Fn(&CounterKind) -> bool + 'c,
+    ) -> impl Iterator<Item = (usize, u64)> + 'c {
+        module
+            .counters
+            .iter()
+            .zip(hits)
+            .filter(move |(counter, _)| kind(&counter.kind))
+            .map(|(counter, hits)| (counter.line, *hits))
+    }
+
+    #[test]
+    fn counts_statements_functions_and_branches() {
+        let source = r#"const values = [];
+function pick(flag) {
+  if (flag) values.push("yes");
+  return flag ? "a" : "b";
+}
+const never = () => values.length;
+pick(true);
+pick(true);
+"#;
+        let module = instrument(source, "test.js").unwrap();
+        let hits = run(&module.code);
+
+        let statements: Vec<_> =
+            hits_of(&module, &hits, |kind| *kind == CounterKind::Statement).collect();
+        assert_eq!(
+            statements,
+            vec![(1, 1), (3, 2), (3, 2), (4, 2), (6, 1), (7, 1), (8, 1)]
+        );
+
+        let functions: Vec<_> = module
+            .counters
+            .iter()
+            .zip(&hits)
+            .filter_map(|(counter, hits)| match &counter.kind {
+                CounterKind::Function { name } => Some((name.as_str(), *hits)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(functions, vec![("pick", 2), ("never", 0)]);
+
+        let branches: Vec<_> = hits_of(&module, &hits, |kind| {
+            matches!(kind, CounterKind::Branch { .. })
+        })
+        .collect();
+        // The missing `else` of line 3 and the `: "b"` of line 4 never ran
+        assert_eq!(branches, vec![(3, 2), (3, 0), (4, 2), (4, 0)]);
+    }
+
+    #[test]
+    fn keeps_lines_and_wraps_single_statement_bodies() {
+        let source = "for (const x of [1, 2]) if (x > 1) a(x)\nelse b(x)\nfunction a() {}\nfunction b() {}\n";
+        let module = instrument(source, "test.js").unwrap();
+        assert_eq!(module.code.lines().count(), source.lines().count());
+        let hits = run(&module.code);
+        let branches: Vec<_> = hits_of(&module, &hits, |kind| {
+            matches!(kind, CounterKind::Branch { .. })
+        })
+        .collect();
+        assert_eq!(branches, vec![(1, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn reports_lcov_and_uncovered_ranges() {
+        let mut file = FileCoverage::default();
+        for (line, hits) in [(1, 1), (2, 0), (3, 0), (5, 2), (6, 0)] {
+            file.add(&CounterKind::Statement, line, hits);
+        }
+        file.add(
+            &CounterKind::Function {
+                name: "main".to_string(),
+            },
+            1,
+            1,
+        );
+        file.add(&CounterKind::Branch { branch: 0, arm: 0 }, 5, 2);
+        file.add(&CounterKind::Branch { branch: 0, arm: 1 }, 5, 0);
+        assert_eq!(file.uncovered_lines(), "2-3, 6");
+
+        let report = CoverageReport {
+            files: BTreeMap::from([(PathBuf::from("/codemod/src/index.ts"), file)]),
+        };
+        let lcov = report.to_lcov();
+        assert!(lcov.contains("SF:/codemod/src/index.ts\nFN:1,main\nFNDA:1,main\nFNF:1\nFNH:1\n"));
+        assert!(lcov.contains("BRDA:5,0,1,0\nBRF:2\nBRH:1\n"));
+        assert!(lcov.contains("LF:5\nLH:2\nend_of_record\n"));
+
+        let summary = report.summary(Path::new("/codemod"));
+        assert!(summary.contains("src/index.ts"));
+        assert!(summary.contains("40.00% (2/5)"));
+    }
+
+    #[test]
+    fn maps_bundled_code_back_to_its_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("bundle.js");
+        std::fs::write(
+            &bundle,
+            "globalThis.a = 1;\n    globalThis.b = 2;\n//# sourceMappingURL=bundle.js.map\n",
+        )
+        .unwrap();
+        // Line 1 column 0 -> src/codemod.ts line 3, line 2 column 4 ->
+        // src/helper.ts line 10
+        std::fs::write(
+            dir.path().join("bundle.js.map"),
+            r#"{"version":3,"sources":["src/codemod.ts","src/helper.ts"],"names":[],"mappings":"AAEE;ICOF"}"#,
+        )
+        .unwrap();
+
+        let collector = CoverageCollector::new(dir.path());
+        run_collected(&collector, &bundle);
+        let report = collector.report();
+
+        let files: Vec<_> = report.files.keys().cloned().collect();
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("src/codemod.ts"),
+                dir.path().join("src/helper.ts")
+            ]
+        );
+        assert_eq!(
+            report.files[&dir.path().join("src/codemod.ts")].lines,
+            BTreeMap::from([(3, 1)])
+        );
+        assert_eq!(
+            report.files[&dir.path().join("src/helper.ts")].lines,
+            BTreeMap::from([(10, 1)])
+        );
+    }
+
+    #[test]
+    fn merges_hits_collected_on_several_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let codemod = dir.path().join("codemod.js");
+        std::fs::write(
+            &codemod,
+            "function transform(flag) {\n  return flag ? 1 : 2;\n}\ntransform(true);\n",
+        )
+        .unwrap();
+
+        let collector = CoverageCollector::new(dir.path());
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| run_collected(&collector, &codemod));
+            }
+        });
+        let report = collector.report();
+
+        let file = &report.files[&codemod];
+        assert_eq!(file.lines, BTreeMap::from([(2, 4), (4, 4)]));
+        assert_eq!(
+            file.functions,
+            BTreeMap::from([((1, "transform".to_string()), 4)])
+        );
+        assert_eq!(file.branches, BTreeMap::from([((2, 0), vec![4, 0])]));
+    }
+}
@@ -0,0 +1,241 @@
+//! Running codemods on code embedded in host files (Vue/Svelte components,
+//! HTML pages, Markdown code fences).
+//!
+//! Each region the codemod's language applies to is transformed on its own.
+//! The transform sees the host file with everything outside the region
+//! blanked out, so node ranges and semantic lookups use host offsets, and
+//! the edited region is spliced back into the host content afterwards.
+
+use super::codemod_lang::CodemodLang;
+use super::execution_engine::{ExecutionResult, ModifiedResult};
+use super::language_data::get_language_from_extension;
+use crate::sandbox::errors::ExecutionError;
+use ast_grep_language::SupportLang;
+use language_core::embedded::{self, EmbeddedRegion, HostFormat};
+use rquickjs::{Ctx, Object, Value};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A region of a host file the codemod runs on.
+pub(crate) struct EmbeddedTarget {
+    pub host: HostFormat,
+    pub region: EmbeddedRegion,
+    pub language: CodemodLang,
+    /// Host content with everything outside the region blanked out.
+    pub source: String,
+}
+
+/// The regions of `file_path` that `language` applies to, or `None` when
+/// the file is not a host file for this codemod and is processed whole.
+pub(crate) fn embedded_targets(
+    file_path: &Path,
+    content: &str,
+    language: CodemodLang,
+) -> Option<Vec<EmbeddedTarget>> {
+    let host = HostFormat::from_path(file_path)?;
+    let native_language = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(get_language_from_extension);
+    if native_language == Some(language) {
+        return None;
+    }
+
+    Some(
+        embedded::extract_regions(host, content)
+            .into_iter()
+            .filter_map(|region| {
+                let region_language = region_language(&region.language, language)?;
+                let source = embedded::mask_outside(content, &[region.range.clone()]);
+                Some(EmbeddedTarget {
+                    host,
+                    region,
+                    language: region_language,
+                    source,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Language to parse a region with, if the codemod applies to it. JavaScript
+/// and TypeScript codemods run on all JavaScript-family regions.
+fn region_language(name: &str, codemod_language: CodemodLang) -> Option<CodemodLang> {
+    let language = match SupportLang::from_str(name) {
+        Ok(lang) => CodemodLang::Static(lang),
+        Err(_) => match codemod_language {
+            CodemodLang::Dynamic(lang) if lang.name().eq_ignore_ascii_case(name) => {
+                codemod_language
+            }
+            _ => return None,
+        },
+    };
+    let compatible = language == codemod_language
+        || (is_javascript_family(language) && is_javascript_family(codemod_language));
+    compatible.then_some(language)
+}
+
+fn is_javascript_family(language: CodemodLang) -> bool {
+    matches!(
+        language,
+        CodemodLang::Static(SupportLang::JavaScript | SupportLang::TypeScript | SupportLang::Tsx)
+    )
+}
+
+/// Expose the region being transformed as `options.embedded`.
+pub(crate) fn set_embedded_option<'js>(
+    ctx: &Ctx<'js>,
+    run_options: &Value<'js>,
+    target: &EmbeddedTarget,
+    index: usize,
+) -> rquickjs::Result<()> {
+    let Some(run_options) = run_options.as_object() else {
+        return Ok(());
+    };
+    let embedded = Object::new(ctx.clone())?;
+    embedded.set("host", target.host.as_str())?;
+    embedded.set("kind", target.region.kind.as_str())?;
+    embedded.set("language", target.region.language.as_str())?;
+    embedded.set("index", index as u32)?;
+    embedded.set("start", target.region.range.start as u32)?;
+    embedded.set("end", target.region.range.end as u32)?;
+    run_options.set("embedded", embedded)
+}
+
+/// Combine per-region results into the result for the host file.
+pub(crate) fn splice_results(
+    file_path: &Path,
+    content: &str,
+    targets: &[EmbeddedTarget],
+    results: Vec<ExecutionResult>,
+) -> Result<ExecutionResult, ExecutionError> {
+    let mut edits = Vec::new();
+    let mut rename_to = None;
+    let mut all_skipped = true;
+
+    for (target, result) in targets.iter().zip(results) {
+        let modified = match result {
+            ExecutionResult::Skipped => continue,
+            ExecutionResult::Unmodified => {
+                all_skipped = false;
+                continue;
+            }
+            ExecutionResult::Modified(modified) => modified,
+        };
+        all_skipped = false;
+        rename_to = modified.rename_to.or(rename_to);
+
+        let range = &target.region.range;
+        let suffix_len = target.source.len() - range.end;
+        let new_source = &modified.content;
+        let in_bounds = new_source.len() >= range.start + suffix_len
+            && new_source.is_char_boundary(range.start)
+            && new_source.is_char_boundary(new_source.len() - suffix_len);
+        if !in_bounds
+            || new_source[..range.start] != target.source[..range.start]
+            || new_source[new_source.len() - suffix_len..] != target.source[range.end..]
+        {
+            return Err(ExecutionError::Runtime {
+                source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
+                    message: format!(
+                        "Transform of the embedded {} block at bytes {}..{} in {} edited outside the block",
+                        target.region.language,
+                        range.start,
+                        range.end,
+                        file_path.display()
+                    ),
+                },
+            });
+        }
+        let replacement = &new_source[range.start..new_source.len() - suffix_len];
+        if replacement != &content[range.clone()] {
+            edits.push((range.clone(), replacement.to_string()));
+        }
+    }
+
+    if edits.is_empty() && rename_to.is_none() {
+        return Ok(if all_skipped {
+            ExecutionResult::Skipped
+        } else {
+            ExecutionResult::Unmodified
+        });
+    }
+
+    let mut new_content = content.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        new_content.replace_range(range, &replacement);
+    }
+    Ok(ExecutionResult::Modified(ModifiedResult {
+        content: new_content,
+        rename_to,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"<template><Child /></template>
+<script setup lang="ts">
+const a = 1;
+</script>
+<style>.a {}</style>
+"#;
+
+    fn typescript() -> CodemodLang {
+        CodemodLang::Static(SupportLang::TypeScript)
+    }
+
+    #[test]
+    fn test_targets_follow_codemod_language() {
+        let path = Path::new("Comp.vue");
+        let targets = embedded_targets(path, COMPONENT, typescript()).unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].source.len(), COMPONENT.len());
+        assert!(targets[0].source.trim_start().starts_with("const a = 1;"));
+
+        let css = CodemodLang::Static(SupportLang::Css);
+        assert_eq!(embedded_targets(path, COMPONENT, css).unwrap().len(), 1);
+
+        let html = CodemodLang::Static(SupportLang::Html);
+        assert!(embedded_targets(Path::new("index.html"), COMPONENT, html).is_none());
+        assert!(embedded_targets(Path::new("a.ts"), COMPONENT, typescript()).is_none());
+    }
+
+    #[test]
+    fn test_splice_results_maps_edits_back() {
+        let path = Path::new("Comp.vue");
+        let targets = embedded_targets(path, COMPONENT, typescript()).unwrap();
+        let edited = targets[0].source.replace("const a = 1;", "let a = 1;");
+
+        let result = splice_results(
+            path,
+            COMPONENT,
+            &targets,
+            vec![ExecutionResult::Modified(ModifiedResult {
+                content: edited,
+                rename_to: None,
+            })],
+        )
+        .unwrap();
+        let ExecutionResult::Modified(modified) = result else {
+            panic!("expected a modified result");
+        };
+        assert_eq!(
+            modified.content,
+            COMPONENT.replace("const a = 1;", "let a = 1;")
+        );
+
+        let outside = format!("x{}", &targets[0].source[1..]);
+        assert!(splice_results(
+            path,
+            COMPONENT,
+            &targets,
+            vec![ExecutionResult::Modified(ModifiedResult {
+                content: outside,
+                rename_to: None,
+            })],
+        )
+        .is_err());
+    }
+}
@@ -1,8 +1,10 @@
 use super::codemod_lang::CodemodLang;
+use super::coverage::CoverageCollector;
 use super::curated_fs::{
     normalize_virtual_absolute_path, CuratedFsConfig, CuratedFsModule, CuratedFsPromisesModule,
     FileFetcher,
 };
+use super::embedded;
 use super::quickjs_adapters::{QuickJSLoader, QuickJSResolver};
 use super::transform_helpers::{
     build_transform_options, process_transform_result, ModificationCheck,
@@ -17,6 +19,7 @@ use crate::sandbox::runtime_module::{
     RuntimeEvent, RuntimeEventCallback, RuntimeEventKind, RuntimeHooksContext, RuntimeModule,
 };
 use crate::utils::quickjs_utils::maybe_promise;
+use crate::utils::source_map::remap_stack_trace;
 use crate::workflow_global::{SharedStateContext, WorkflowGlobalModule};
 use ast_grep_config::RuleConfig;
 use ast_grep_core::matcher::MatcherExt;
@@ -31,7 +34,7 @@ use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// Flag indicating whether execution is in test mode.
@@ -52,7 +55,7 @@ unsafe impl<'js> rquickjs::JsLifetime<'js> for DryRunExecutionFlag {
     type Changed<'to> = DryRunExecutionFlag;
 }
 
-fn install_console_bridge(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+pub(crate) fn install_console_bridge(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
     let console = Object::new(ctx.clone())?;
     console.set("log", Function::new(ctx.clone(), console_log)?)?;
     console.set("info", Function::new(ctx.clone(), console_log)?)?;
@@ -146,11 +149,27 @@ pub enum ExecutionResult {
     Skipped,
 }
 
-/// A file change produced by `jssgTransform` (secondary output)
+/// A file change produced by `jssgTransform` or `renameSymbol` (secondary output)
 #[derive(Debug, Clone)]
 pub struct FileChange {
     pub path: PathBuf,
     pub result: ExecutionResult,
+    /// Content the change was computed from. When set, the change must only
+    /// be applied while the file on disk still has this content.
+    pub base_content: Option<String>,
+}
+
+impl FileChange {
+    /// Whether the file on disk no longer has the content this change was
+    /// computed from, e.g. because another file's transform already rewrote it.
+    pub fn is_stale(&self) -> bool {
+        match &self.base_content {
+            Some(base) => {
+                !std::fs::read_to_string(&self.path).is_ok_and(|current| current == *base)
+            }
+            None => false,
+        }
+    }
 }
 
 /// Output of a codemod execution including both the primary result
@@ -161,12 +180,104 @@ pub struct CodemodOutput {
     pub secondary: Vec<FileChange>,
 }
 
-/// Shared accumulator for file changes produced by `jssgTransform`.
-/// Stored as QuickJS userdata so the JS-facing function can push changes
-/// without touching the filesystem.
+/// Shared accumulator for file changes produced by `jssgTransform` and
+/// `renameSymbol`. Stored as QuickJS userdata so the JS-facing functions can
+/// push changes without touching the filesystem.
 #[derive(Debug, Clone, Default)]
 pub struct JssgFileChanges {
     pub changes: Arc<Mutex<Vec<FileChange>>>,
+    /// Edits queued by `renameSymbol` for files other than the one being
+    /// transformed, keyed by path.
+    pub renames: Arc<Mutex<HashMap<PathBuf, PendingRename>>>,
+}
+
+/// Rename edits queued for one file, as byte ranges into `base_content`.
+#[derive(Debug, Clone)]
+pub struct PendingRename {
+    pub base_content: String,
+    pub edits: Vec<(std::ops::Range<usize>, String)>,
+}
+
+impl JssgFileChanges {
+    /// Queue `renameSymbol` edits for `path` and refresh the secondary change
+    /// for that file. Edits from earlier calls are kept; identical edits are
+    /// merged and overlapping ones are rejected as conflicts.
+    pub fn queue_rename(
+        &self,
+        path: &Path,
+        base_content: &str,
+        edits: Vec<(std::ops::Range<usize>, String)>,
+    ) -> Result<(), String> {
+        let mut renames = self
+            .renames
+            .lock()
+            .map_err(|e| format!("Failed to lock rename edits: {e}"))?;
+        let mut changes = self
+            .changes
+            .lock()
+            .map_err(|e| format!("Failed to lock file changes: {e}"))?;
+
+        if changes
+            .iter()
+            .any(|c| c.path == path && c.base_content.is_none())
+        {
+            return Err(format!(
+                "Conflicting changes: '{}' is changed by both jssgTransform and renameSymbol",
+                path.display()
+            ));
+        }
+
+        let mut merged = match renames.get(path) {
+            Some(pending) if pending.base_content != base_content => {
+                return Err(format!(
+                    "Conflicting changes: '{}' changed between renameSymbol calls",
+                    path.display()
+                ));
+            }
+            Some(pending) => pending.edits.clone(),
+            None => Vec::new(),
+        };
+        merged.extend(edits);
+        merged.sort_by(|a, b| (a.0.start, a.0.end).cmp(&(b.0.start, b.0.end)));
+        merged.dedup();
+
+        let mut content = String::with_capacity(base_content.len());
+        let mut last = 0;
+        for (range, text) in &merged {
+            if range.start < last {
+                return Err(format!(
+                    "Conflicting rename edits in '{}' at byte {}",
+                    path.display(),
+                    range.start
+                ));
+            }
+            content.push_str(&base_content[last..range.start]);
+            content.push_str(text);
+            last = range.end;
+        }
+        content.push_str(&base_content[last..]);
+
+        let result = ExecutionResult::Modified(ModifiedResult {
+            content,
+            rename_to: None,
+        });
+        match changes.iter_mut().find(|c| c.path == path) {
+            Some(change) => change.result = result,
+            None => changes.push(FileChange {
+                path: path.to_path_buf(),
+                result,
+                base_content: Some(base_content.to_string()),
+            }),
+        }
+        renames.insert(
+            path.to_path_buf(),
+            PendingRename {
+                base_content: base_content.to_string(),
+                edits: merged,
+            },
+        );
+        Ok(())
+    }
 }
 
 unsafe impl<'js> rquickjs::JsLifetime<'js> for JssgFileChanges {
@@ -253,10 +364,12 @@ pub struct JssgExecutionOptions<'a, R> {
     /// The target directory the codemod is running against.
     /// Used to validate that `jssgTransform` and `rename()` only access files within this directory.
     pub target_directory: &'a Path,
+    /// Optional collector for coverage of the codemod's own modules
+    pub coverage: Option<Arc<CoverageCollector>>,
 }
 
-struct DryRunDiskFetcher {
-    target_directory: PathBuf,
+pub(crate) struct DryRunDiskFetcher {
+    pub(crate) target_directory: PathBuf,
 }
 
 impl FileFetcher for DryRunDiskFetcher {
@@ -342,36 +455,64 @@ fn seed_dry_run_current_file(
     }
 }
 
-/// Execute a codemod on string content using QuickJS
-/// This is the core execution logic that doesn't touch the filesystem
-pub async fn execute_codemod_with_quickjs<'a, R>(
-    options: JssgExecutionOptions<'a, R>,
-) -> Result<CodemodOutput, ExecutionError>
+/// Settings for [`build_transform_runtime`], shared by the per-file and
+/// `transformProject` entry points.
+pub(crate) struct TransformRuntimeOptions<'a, R> {
+    pub(crate) resolver: Arc<R>,
+    pub(crate) capabilities: Option<HashSet<LlrtSupportedModules>>,
+    pub(crate) params: HashMap<String, serde_json::Value>,
+    pub(crate) matrix_values: Option<HashMap<String, serde_json::Value>>,
+    pub(crate) metrics_context: Option<MetricsContext>,
+    pub(crate) llm_request_handler: Option<LlmRequestHandler>,
+    pub(crate) shared_state_context: Option<SharedStateContext>,
+    pub(crate) runtime_event_callback: Option<RuntimeEventCallback>,
+    pub(crate) cancellation_flag: Option<Arc<AtomicBool>>,
+    pub(crate) coverage: Option<Arc<CoverageCollector>>,
+    pub(crate) test_mode: bool,
+    pub(crate) dry_run: bool,
+    /// Directory `jssgTransform` and `rename()` must stay within
+    pub(crate) target_directory: &'a Path,
+    /// File the dry-run overlay starts with, and its content
+    pub(crate) dry_run_file: Option<(&'a Path, &'a str)>,
+}
+
+/// A QuickJS context with the codemod modules registered and the execution
+/// userdata stored, ready to evaluate an entry script.
+pub(crate) struct TransformRuntime {
+    pub(crate) context: AsyncContext,
+    pub(crate) canonical_target_directory: PathBuf,
+    pub(crate) runtime_hooks_context: RuntimeHooksContext,
+    /// Changes queued by `jssgTransform` and `renameSymbol`
+    pub(crate) jssg_file_changes: JssgFileChanges,
+    /// Kept alive for as long as the context is used
+    pub(crate) _runtime: AsyncRuntime,
+}
+
+/// Create the QuickJS runtime a transform runs in: the modules the
+/// capabilities allow, the curated `fs`, and the userdata the codemod
+/// modules read.
+pub(crate) async fn build_transform_runtime<R>(
+    options: TransformRuntimeOptions<'_, R>,
+) -> Result<TransformRuntime, ExecutionError>
 where
     R: ModuleResolver + 'static,
 {
-    let script_name = options
-        .script_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("main.js");
-
-    let js_code = format!(
-        include_str!("scripts/main_script.js.txt"),
-        script_name = script_name
-    );
-
-    let params: HashMap<String, serde_json::Value> = options.params.unwrap_or_default();
-
     // Initialize QuickJS runtime and context
     let runtime = AsyncRuntime::new().map_err(|e| ExecutionError::Runtime {
         source: crate::sandbox::errors::RuntimeError::InitializationFailed {
             message: format!("Failed to create AsyncRuntime: {e}"),
         },
     })?;
+    // A synchronous loop never yields to the caller's timeout, so canceling
+    // also interrupts the script from QuickJS's periodic interrupt check
+    if let Some(cancellation_flag) = options.cancellation_flag.clone() {
+        runtime
+            .set_interrupt_handler(Some(Box::new(move || {
+                cancellation_flag.load(Ordering::Relaxed)
+            })))
+            .await;
+    }
 
-    // Create AstGrep instance for the SgRootRjs
-    let ast_grep = AstGrep::new(options.content, options.language);
     let canonical_target_directory = options
         .target_directory
         .canonicalize()
@@ -450,7 +591,7 @@ where
     }
 
     let fs_resolver = QuickJSResolver::new(Arc::clone(&options.resolver));
-    let fs_loader = QuickJSLoader;
+    let fs_loader = QuickJSLoader::with_coverage(options.coverage);
 
     // Combine resolvers and loaders
     runtime
@@ -468,21 +609,25 @@ where
             },
         })?;
 
-    // Capture metrics context and shared state context for use inside async block
-    let metrics_context = options.metrics_context.clone();
     let llm_runtime_context = LlmRuntimeContext::new(
         llm_capability_enabled
-            .then(|| options.llm_request_handler.clone())
+            .then_some(options.llm_request_handler)
             .flatten(),
     );
-    let shared_state_context = options.shared_state_context.clone();
-    let runtime_hooks_context = RuntimeHooksContext::new(
-        options.runtime_event_callback.clone(),
-        options.cancellation_flag.clone(),
-    );
+    let runtime_hooks_context =
+        RuntimeHooksContext::new(options.runtime_event_callback, options.cancellation_flag);
+    let jssg_file_changes = JssgFileChanges::default();
     let test_mode = options.test_mode;
+    let dry_run = options.dry_run;
+    let dry_run_file = options.dry_run_file;
+    let target_directory = options.target_directory.to_path_buf();
+    let metrics_context = options.metrics_context;
+    let shared_state_context = options.shared_state_context;
+    let jssg_execution_context = JssgExecutionContext {
+        params: options.params,
+        matrix_values: options.matrix_values,
+    };
 
-    // Execute JavaScript code
     async_with!(context => |ctx| {
         // Store execution mode flag in runtime userdata
         ctx.store_userdata(ExecutionModeFlag { test_mode }).map_err(|e| ExecutionError::Runtime {
@@ -491,14 +636,13 @@ where
             },
         })?;
 
-        ctx.store_userdata(DryRunExecutionFlag(options.dry_run)).map_err(|e| ExecutionError::Runtime {
+        ctx.store_userdata(DryRunExecutionFlag(dry_run)).map_err(|e| ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                 message: format!("Failed to store DryRunExecutionFlag: {:?}", e),
             },
         })?;
 
         // Store shared accumulator for jssgTransform file changes
-        let jssg_file_changes = JssgFileChanges::default();
         ctx.store_userdata(jssg_file_changes.clone()).map_err(|e| ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                 message: format!("Failed to store JssgFileChanges: {:?}", e),
@@ -506,32 +650,29 @@ where
         })?;
 
         // Store jssg execution context so jssgTransform can access params/matrixValues
-        ctx.store_userdata(JssgExecutionContext {
-            params: params.clone(),
-            matrix_values: options.matrix_values.clone(),
-        }).map_err(|e| ExecutionError::Runtime {
+        ctx.store_userdata(jssg_execution_context).map_err(|e| ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                 message: format!("Failed to store JssgExecutionContext: {:?}", e),
             },
         })?;
 
-        // Store target directory in runtime userdata if provided
-        ctx.store_userdata(TargetDirectory(options.target_directory.to_path_buf())).map_err(|e| ExecutionError::Runtime {
+        // Store target directory in runtime userdata
+        ctx.store_userdata(TargetDirectory(target_directory)).map_err(|e| ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                 message: format!("Failed to store TargetDirectory: {:?}", e),
             },
         })?;
 
         // Store metrics context in runtime userdata if provided (must be done inside async_with)
-        if let Some(ref metrics_ctx) = metrics_context {
-            ctx.store_userdata(metrics_ctx.clone()).map_err(|e| ExecutionError::Runtime {
+        if let Some(metrics_ctx) = metrics_context {
+            ctx.store_userdata(metrics_ctx).map_err(|e| ExecutionError::Runtime {
                 source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                     message: format!("Failed to store MetricsContext: {:?}", e),
                 },
             })?;
         }
 
-        ctx.store_userdata(llm_runtime_context.clone()).map_err(|e| ExecutionError::Runtime {
+        ctx.store_userdata(llm_runtime_context).map_err(|e| ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::InitializationFailed {
                 message: format!("Failed to store LlmRuntimeContext: {:?}", e),
             },
@@ -550,16 +691,14 @@ where
             },
         })?;
 
-        if let Some(target_dir) = curated_fs_target.clone() {
-            let cfg = if options.dry_run {
+        if let Some(target_dir) = curated_fs_target {
+            let cfg = if dry_run {
+                // Reads fall through to disk, writes land in memory and are discarded.
                 let target_path = std::path::PathBuf::from(&target_dir);
                 let memory_root: vfs::VfsPath = vfs::MemoryFS::new().into();
-                seed_dry_run_current_file(
-                    &memory_root,
-                    &target_path,
-                    options.file_path,
-                    options.content,
-                );
+                if let Some((file_path, content)) = dry_run_file {
+                    seed_dry_run_current_file(&memory_root, &target_path, file_path, content);
+                }
                 CuratedFsConfig::new(target_dir.clone(), memory_root).with_fetcher(Arc::new(
                     DryRunDiskFetcher {
                         target_directory: target_path,
@@ -591,7 +730,81 @@ where
                 message: format!("Failed to attach console bridge: {e}"),
             },
         })?;
+        Ok::<_, ExecutionError>(())
+    })
+    .await?;
+
+    Ok(TransformRuntime {
+        context,
+        canonical_target_directory,
+        runtime_hooks_context,
+        jssg_file_changes,
+        _runtime: runtime,
+    })
+}
 
+/// Execute a codemod on string content using QuickJS
+/// This is the core execution logic that doesn't touch the filesystem
+pub async fn execute_codemod_with_quickjs<'a, R>(
+    options: JssgExecutionOptions<'a, R>,
+) -> Result<CodemodOutput, ExecutionError>
+where
+    R: ModuleResolver + 'static,
+{
+    let script_name = options
+        .script_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("main.js");
+
+    let js_code = format!(
+        include_str!("scripts/main_script.js.txt"),
+        script_name = script_name
+    );
+
+    let params: HashMap<String, serde_json::Value> = options.params.unwrap_or_default();
+
+    // Host files (components, pages, Markdown) are transformed one embedded
+    // region at a time; everything else is transformed whole.
+    let embedded_targets =
+        embedded::embedded_targets(options.file_path, options.content, options.language);
+    if embedded_targets
+        .as_ref()
+        .is_some_and(|targets| targets.is_empty())
+    {
+        return Ok(CodemodOutput {
+            primary: ExecutionResult::Skipped,
+            secondary: vec![],
+        });
+    }
+
+    let coverage = options.coverage.clone();
+    let TransformRuntime {
+        context,
+        canonical_target_directory,
+        runtime_hooks_context,
+        jssg_file_changes,
+        _runtime,
+    } = build_transform_runtime(TransformRuntimeOptions {
+        resolver: options.resolver,
+        capabilities: options.capabilities,
+        params: params.clone(),
+        matrix_values: options.matrix_values.clone(),
+        metrics_context: options.metrics_context,
+        llm_request_handler: options.llm_request_handler,
+        shared_state_context: options.shared_state_context,
+        runtime_event_callback: options.runtime_event_callback,
+        cancellation_flag: options.cancellation_flag,
+        coverage: coverage.clone(),
+        test_mode: options.test_mode,
+        dry_run: options.dry_run,
+        target_directory: options.target_directory,
+        dry_run_file: Some((options.file_path, options.content)),
+    })
+    .await?;
+
+    // Execute JavaScript code
+    async_with!(context => |ctx| {
         let execution = async {
             let module = Module::declare(ctx.clone(), "__codemod_entry.js", js_code)
                 .catch(&ctx)
@@ -631,59 +844,8 @@ where
                 .to_string_lossy()
                 .to_string();
             let target_directory = canonical_target_directory.clone();
-            let parsed_content =
-                SgRootRjs::try_new_with_semantic(
-                    ast_grep,
-                    Some(file_path_str.clone()),
-                    options.semantic_provider.clone(),
-                    Some(file_path_str), // Pass current file path for write() validation
-                    Some(target_directory.as_path()),
-                ).map_err(|e| ExecutionError::Runtime {
-                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
-                        message: e.to_string(),
-                    },
-                })?;
-
-            // Keep a reference to read rename_to after JS execution
-            let sg_root_inner = Arc::clone(&parsed_content.inner);
-
-            // Calculate matches inside the JS context
-            let matches: Option<Vec<SgNodeRjs<'_>>> = if let Some(selector_config) = &options.selector_config {
-                let root_node = parsed_content.root(ctx.clone()).map_err(|e| ExecutionError::Runtime {
-                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
-                        message: e.to_string(),
-                    },
-                })?;
-                let ast_matches: Vec<_> = root_node.inner_node.dfs()
-                    .filter_map(|node| selector_config.matcher.match_node(node))
-                    .collect();
-
-                if ast_matches.is_empty() {
-                    return Ok(CodemodOutput { primary: ExecutionResult::Skipped, secondary: vec![] });
-                }
-
-                Some(ast_matches.into_iter().map(|node_match| SgNodeRjs {
-                    root: Arc::clone(&parsed_content.inner),
-                    inner_node: node_match,
-                    _phantom: PhantomData,
-                }).collect())
-            } else {
-                None
-            };
-
-            let language_str = options.language.to_string();
             let target_dir_str = target_directory.to_string_lossy().into_owned();
 
-            let run_options_qjs = build_transform_options(
-                &ctx,
-                params,
-                &language_str,
-                options.matrix_values,
-                matches,
-                options.dry_run,
-                &target_dir_str,
-            )?;
-
             let func = namespace
                 .get::<_, Function>("executeCodemod")
                 .catch(&ctx)
@@ -693,20 +855,97 @@ where
                     },
                 })?;
 
-            // Call it and return value.
-            let result_obj_promise = func.call((parsed_content, run_options_qjs)).catch(&ctx).map_err(|e| {
-                map_transform_execution_error(&runtime_hooks_context, e)
-            })?;
-            let result_obj = maybe_promise(result_obj_promise)
-                .await
-                .catch(&ctx)
-                .map_err(|e| map_transform_execution_error(&runtime_hooks_context, e))?;
+            let sources: Vec<(&str, CodemodLang)> = match &embedded_targets {
+                Some(targets) => targets
+                    .iter()
+                    .map(|target| (target.source.as_str(), target.language))
+                    .collect(),
+                None => vec![(options.content, options.language)],
+            };
+            let mut results = Vec::with_capacity(sources.len());
+            for (index, (source, language)) in sources.into_iter().enumerate() {
+                let parsed_content =
+                    SgRootRjs::try_new_with_semantic(
+                        AstGrep::new(source, language),
+                        Some(file_path_str.clone()),
+                        options.semantic_provider.clone(),
+                        Some(file_path_str.clone()), // Pass current file path for write() validation
+                        Some(target_directory.as_path()),
+                    ).map_err(|e| ExecutionError::Runtime {
+                        source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                            message: e.to_string(),
+                        },
+                    })?;
+
+                // Keep a reference to read rename_to after JS execution
+                let sg_root_inner = Arc::clone(&parsed_content.inner);
+
+                // Calculate matches inside the JS context
+                let matches: Option<Vec<SgNodeRjs<'_>>> = if let Some(selector_config) = &options.selector_config {
+                    let root_node = parsed_content.root(ctx.clone()).map_err(|e| ExecutionError::Runtime {
+                        source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                            message: e.to_string(),
+                        },
+                    })?;
+                    let ast_matches: Vec<_> = root_node.inner_node.dfs()
+                        .filter_map(|node| selector_config.matcher.match_node(node))
+                        .collect();
+
+                    if ast_matches.is_empty() {
+                        results.push(ExecutionResult::Skipped);
+                        continue;
+                    }
+
+                    Some(ast_matches.into_iter().map(|node_match| SgNodeRjs {
+                        root: Arc::clone(&parsed_content.inner),
+                        inner_node: node_match,
+                        _phantom: PhantomData,
+                    }).collect())
+                } else {
+                    None
+                };
+
+                let language_str = language.to_string();
+
+                let run_options_qjs = build_transform_options(
+                    &ctx,
+                    params.clone(),
+                    &language_str,
+                    options.matrix_values.clone(),
+                    matches,
+                    options.dry_run,
+                    &target_dir_str,
+                )?;
+                if let Some(target) = embedded_targets.as_ref().map(|targets| &targets[index]) {
+                    embedded::set_embedded_option(&ctx, &run_options_qjs, target, index).map_err(|e| ExecutionError::Runtime {
+                        source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                            message: e.to_string(),
+                        },
+                    })?;
+                }
 
-            let primary = process_transform_result(
-                &result_obj,
-                &sg_root_inner,
-                ModificationCheck::StringEquality { original_content: options.content },
-            )?;
+                // Call it and return value.
+                let result_obj_promise = func.call((parsed_content, run_options_qjs)).catch(&ctx).map_err(|e| {
+                    map_transform_execution_error(&runtime_hooks_context, e)
+                })?;
+                let result_obj = maybe_promise(result_obj_promise)
+                    .await
+                    .catch(&ctx)
+                    .map_err(|e| map_transform_execution_error(&runtime_hooks_context, e))?;
+
+                results.push(process_transform_result(
+                    &result_obj,
+                    &sg_root_inner,
+                    ModificationCheck::StringEquality { original_content: source },
+                )?);
+            }
+
+            let primary = match &embedded_targets {
+                Some(targets) => {
+                    embedded::splice_results(options.file_path, options.content, targets, results)?
+                }
+                None => results.pop().unwrap_or(ExecutionResult::Skipped),
+            };
 
             let secondary = jssg_file_changes.changes.lock()
                 .map(|guard| guard.clone())
@@ -714,12 +953,17 @@ where
 
             Ok(CodemodOutput { primary, secondary })
         };
-        execution.await
+        let output = execution.await;
+        // Failed runs still executed code, so their hits count too
+        if let Some(coverage) = &coverage {
+            coverage.collect(&ctx);
+        }
+        output
     })
     .await
 }
 
-fn map_transform_execution_error(
+pub(crate) fn map_transform_execution_error(
     runtime_hooks_context: &RuntimeHooksContext,
     error: impl std::fmt::Display,
 ) -> ExecutionError {
@@ -728,7 +972,7 @@ fn map_transform_execution_error(
     } else {
         ExecutionError::Runtime {
             source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
-                message: error.to_string(),
+                message: remap_stack_trace(&error.to_string()),
             },
         }
     }
@@ -792,7 +1036,7 @@ where
     built_in_loader = built_in_loader.with_module("codemod:runtime", RuntimeModule);
 
     let fs_resolver = QuickJSResolver::new(Arc::clone(&options.resolver));
-    let fs_loader = QuickJSLoader;
+    let fs_loader = QuickJSLoader::default();
 
     runtime
         .set_loader(
@@ -984,7 +1228,7 @@ where
     }
 
     let fs_resolver = QuickJSResolver::new(Arc::clone(&options.resolver));
-    let fs_loader = QuickJSLoader;
+    let fs_loader = QuickJSLoader::default();
 
     runtime
         .set_loader(
@@ -1220,6 +1464,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1294,6 +1539,7 @@ export default async function transform() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let output = execute_codemod_with_quickjs(options)
@@ -1364,6 +1610,7 @@ export default async function transform() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let error = execute_codemod_with_quickjs(options)
@@ -1439,6 +1686,7 @@ export default function transform(root, options) {
             test_mode: false,
             dry_run: true,
             target_directory: &target_dir,
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options)
@@ -1509,6 +1757,7 @@ export default function transform() {
             test_mode: false,
             dry_run: true,
             target_directory: temp_dir.path(),
+            coverage: None,
         };
 
         execute_codemod_with_quickjs(options)
@@ -1553,6 +1802,7 @@ export default function transform(root, options) {
             test_mode: false,
             dry_run: false,
             target_directory: &target_dir,
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options)
@@ -1616,6 +1866,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1669,6 +1920,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1722,6 +1974,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1767,6 +2020,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1817,6 +2071,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -1928,6 +2183,7 @@ function example() {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -2008,6 +2264,7 @@ export default function transform(root) {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -2056,6 +2313,7 @@ export default function transform(root) {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -2104,6 +2362,7 @@ export default function transform(root) {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -2152,6 +2411,7 @@ export default async function transform(root) {
             test_mode: false,
             dry_run: false,
             target_directory: Path::new("."),
+            coverage: None,
         };
 
         let result = execute_codemod_with_quickjs(options).await;
@@ -2202,4 +2462,101 @@ export default function shard(input) {
             other => panic!("Expected runtime hook error, got: {:?}", other),
         }
     }
+
+    #[tokio::test]
+    async fn test_rename_symbol_updates_declaration_and_references() {
+        let codemod_content = r#"
+export default function transform(root) {
+  const rootNode = root.root();
+  const call = rootNode.find({ rule: { pattern: "greet()" } });
+  const edits = call.field("function").renameSymbol("welcome");
+  return rootNode.commitEdits(edits);
+}
+        "#
+        .trim();
+        let (temp_dir, codemod_path) = setup_test_codemod(codemod_content);
+        let project = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            project.join("greet.js"),
+            "export function greet() {}\nexport const other = greet;\n",
+        )
+        .unwrap();
+        let content = "import { greet } from './greet';\nconst api = { greet };\ngreet();\n";
+        let file_path = project.join("main.js");
+        fs::write(&file_path, content).unwrap();
+
+        let resolver = Arc::new(OxcResolver::new(project.clone(), None).unwrap());
+        let provider: Arc<dyn SemanticProvider> = Arc::new(
+            language_javascript::OxcSemanticProvider::workspace_scope(project.clone()),
+        );
+        let options = JssgExecutionOptions {
+            script_path: &codemod_path,
+            resolver,
+            language: js_lang(),
+            file_path: &file_path,
+            content,
+            selector_config: None,
+            params: None,
+            matrix_values: None,
+            capabilities: None,
+            semantic_provider: Some(provider),
+            metrics_context: None,
+            llm_request_handler: None,
+            shared_state_context: None,
+            runtime_event_callback: None,
+            cancellation_flag: None,
+            test_mode: false,
+            dry_run: false,
+            target_directory: &project,
+            coverage: None,
+        };
+
+        let output = execute_codemod_with_quickjs(options).await.unwrap();
+        match output.primary {
+            ExecutionResult::Modified(modified) => assert_eq!(
+                modified.content,
+                "import { welcome } from './greet';\nconst api = { greet: welcome };\nwelcome();\n"
+            ),
+            other => panic!("Expected modified result, got: {:?}", other),
+        }
+        assert_eq!(output.secondary.len(), 1);
+        let change = &output.secondary[0];
+        assert_eq!(change.path, project.join("greet.js"));
+        assert!(!change.is_stale());
+        match &change.result {
+            ExecutionResult::Modified(modified) => assert_eq!(
+                modified.content,
+                "export function welcome() {}\nexport const other = welcome;\n"
+            ),
+            other => panic!("Expected modified result, got: {:?}", other),
+        }
+
+        fs::write(project.join("greet.js"), "export function greet() {}\n").unwrap();
+        assert!(change.is_stale());
+    }
+
+    #[test]
+    fn test_queue_rename_rejects_overlapping_edits() {
+        let changes = JssgFileChanges::default();
+        let path = Path::new("/repo/a.js");
+        let base = "const foo = 1;\n";
+        changes
+            .queue_rename(path, base, vec![(6..9, "bar".to_string())])
+            .unwrap();
+        changes
+            .queue_rename(path, base, vec![(6..9, "bar".to_string())])
+            .unwrap();
+        assert!(changes
+            .queue_rename(path, base, vec![(6..9, "baz".to_string())])
+            .is_err());
+
+        let queued = changes.changes.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        match &queued[0].result {
+            ExecutionResult::Modified(modified) => {
+                assert_eq!(modified.content, "const bar = 1;\n")
+            }
+            other => panic!("Expected modified result, got: {:?}", other),
+        }
+    }
 }
@@ -8,6 +8,11 @@ const TOML_EXTENSIONS: &[&str] = &[".toml"];
 const XML_EXTENSIONS: &[&str] = &[
     ".xml", ".csproj", ".props", ".targets", ".config", ".resx", ".xaml",
 ];
+/// Component files whose `<script>` blocks JavaScript-family codemods run on.
+/// Not part of the extension map, since the files themselves have no
+/// single language.
+#[cfg(feature = "native")]
+pub const COMPONENT_EXTENSIONS: &[&str] = &[".vue", ".svelte"];
 
 /// Creates a map from CodemodLang to their associated file extensions
 pub fn create_language_extension_map() -> HashMap<CodemodLang, Vec<&'static str>> {
@@ -78,7 +83,19 @@ pub fn get_extensions_for_language(lang: CodemodLang) -> Vec<&'static str> {
     }
 
     let map = create_language_extension_map();
-    map.get(&lang).cloned().unwrap_or_default()
+    let mut extensions = map.get(&lang).cloned().unwrap_or_default();
+    #[cfg(feature = "native")]
+    if matches!(
+        lang,
+        CodemodLang::Static(
+            ast_grep_language::SupportLang::JavaScript
+                | ast_grep_language::SupportLang::TypeScript
+                | ast_grep_language::SupportLang::Tsx
+        )
+    ) {
+        extensions.extend_from_slice(COMPONENT_EXTENSIONS);
+    }
+    extensions
 }
 
 /// Determine language from file extension
@@ -153,6 +170,11 @@ mod tests {
         assert!(js_extensions.contains(&".js"));
         assert!(js_extensions.contains(&".mjs"));
         assert!(js_extensions.contains(&".cjs"));
+        assert!(js_extensions.contains(&".vue"));
+        assert!(js_extensions.contains(&".svelte"));
+        assert!(
+            !get_extensions_for_language(CodemodLang::Static(SupportLang::Rust)).contains(&".vue")
+        );
 
         let toml_extensions = get_extensions_for_language("toml".parse().unwrap());
         assert_eq!(toml_extensions, vec![".toml"]);
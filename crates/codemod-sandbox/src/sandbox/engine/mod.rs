@@ -1,11 +1,20 @@
+pub mod coverage;
 #[cfg(feature = "native")]
 pub mod curated_fs;
+#[cfg(feature = "native")]
+pub(crate) mod embedded;
 pub mod execution_engine;
 #[cfg(feature = "native")]
 pub mod fetching_vfs;
 pub mod in_memory_engine;
+#[cfg(feature = "native")]
+pub mod project_engine;
 pub mod quickjs_adapters;
+#[cfg(feature = "native")]
+pub mod result_cache;
 pub mod selector_engine;
+#[cfg(feature = "native")]
+pub mod syntax_check;
 pub(crate) mod transform_helpers;
 
 #[cfg(feature = "native")]
@@ -13,5 +22,11 @@ pub mod codemod_lang;
 
 pub use execution_engine::*;
 pub use in_memory_engine::*;
+#[cfg(feature = "native")]
+pub use project_engine::*;
+#[cfg(feature = "native")]
+pub use result_cache::*;
 pub use selector_engine::*;
+#[cfg(feature = "native")]
+pub use syntax_check::*;
 pub mod language_data;
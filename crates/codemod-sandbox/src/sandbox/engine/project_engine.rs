@@ -0,0 +1,793 @@
+use super::codemod_lang::CodemodLang;
+use super::execution_engine::{
+    build_transform_runtime, map_transform_execution_error, ExecutionResult, TransformRuntime,
+    TransformRuntimeOptions,
+};
+use super::transform_helpers::build_transform_options;
+use crate::ast_grep::serde::JsValue;
+use crate::ast_grep::sg_node::SgRootRjs;
+use crate::llm::LlmRequestHandler;
+use crate::metrics::MetricsContext;
+use crate::sandbox::errors::ExecutionError;
+use crate::sandbox::resolvers::ModuleResolver;
+use crate::sandbox::runtime_module::RuntimeEventCallback;
+use crate::utils::quickjs_utils::maybe_promise;
+use crate::workflow_global::SharedStateContext;
+use ast_grep_core::AstGrep;
+use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use language_core::SemanticProvider;
+use oxc::allocator::Allocator;
+use oxc::ast::ast::{Declaration, Statement};
+use oxc::parser::Parser;
+use oxc::span::SourceType;
+use rquickjs::class::Trace;
+use rquickjs::prelude::Opt;
+use rquickjs::{async_with, Ctx, Exception, JsLifetime, Result};
+use rquickjs::{CatchResultExt, FromJs, Function, Module, Value};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// A single change returned from `transformProject`.
+///
+/// Paths are absolute and have been validated to stay within the target
+/// directory. Existence preconditions were checked when the batch was built,
+/// so callers only need to apply the edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectEdit {
+    /// Replace the content of an existing file.
+    Edit { path: PathBuf, content: String },
+    /// Create a file that does not exist yet.
+    Create { path: PathBuf, content: String },
+    /// Move a file, optionally replacing its content.
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+        content: Option<String>,
+    },
+    /// Remove an existing file.
+    Delete { path: PathBuf },
+}
+
+impl ProjectEdit {
+    /// Paths touched by this edit, used for conflict detection.
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            ProjectEdit::Edit { path, .. }
+            | ProjectEdit::Create { path, .. }
+            | ProjectEdit::Delete { path } => vec![path.as_path()],
+            ProjectEdit::Rename { from, to, .. } => vec![from.as_path(), to.as_path()],
+        }
+    }
+}
+
+/// Output of a `transformProject` execution.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectOutput {
+    pub edits: Vec<ProjectEdit>,
+}
+
+/// Edit shape accepted from JavaScript before path resolution.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum RawProjectEdit {
+    Edit {
+        path: String,
+        content: String,
+    },
+    Create {
+        path: String,
+        content: String,
+    },
+    Rename {
+        from: String,
+        to: String,
+        #[serde(default)]
+        content: Option<String>,
+    },
+    Delete {
+        path: String,
+    },
+}
+
+/// Resolve a path returned by a project codemod against the target directory.
+/// Relative paths are joined onto the target. Files that don't exist yet are
+/// resolved through their closest existing ancestor, so a symlinked directory
+/// cannot lead a write outside the target; `..` below that ancestor is
+/// rejected since where it leads is unknown.
+pub fn resolve_project_path(
+    target_directory: &Path,
+    raw: &str,
+) -> std::result::Result<PathBuf, String> {
+    if raw.is_empty() {
+        return Err("project edit paths must be non-empty".to_string());
+    }
+
+    let outside = || {
+        format!(
+            "transformProject() path '{}' is outside the target directory '{}'",
+            raw,
+            target_directory.display()
+        )
+    };
+    let canonical_target = target_directory
+        .canonicalize()
+        .unwrap_or_else(|_| target_directory.to_path_buf());
+    let canonical_path =
+        resolve_through_existing_ancestor(&target_directory.join(raw)).ok_or_else(outside)?;
+    if !canonical_path.starts_with(&canonical_target) {
+        return Err(outside());
+    }
+
+    Ok(canonical_path)
+}
+
+/// Canonical form of `path`: its closest existing ancestor resolved through
+/// symlinks, with the rest appended. `None` when the rest contains `..`.
+fn resolve_through_existing_ancestor(path: &Path) -> Option<PathBuf> {
+    if let Ok(canonical) = path.canonicalize() {
+        return Some(canonical);
+    }
+    let file_name = path.file_name()?;
+    Some(resolve_through_existing_ancestor(path.parent()?)?.join(file_name))
+}
+
+/// Turn the raw JS result into validated edits.
+///
+/// Rejects batches where more than one edit touches the same path, edits to
+/// files that don't exist, and creates/renames onto files that do.
+fn build_project_edits(
+    raw_edits: Vec<RawProjectEdit>,
+    target_directory: &Path,
+) -> std::result::Result<Vec<ProjectEdit>, String> {
+    let mut edits = Vec::with_capacity(raw_edits.len());
+    for raw in raw_edits {
+        let edit = match raw {
+            RawProjectEdit::Edit { path, content } => {
+                let path = resolve_project_path(target_directory, &path)?;
+                if !path.is_file() {
+                    return Err(format!(
+                        "Cannot edit '{}': file does not exist",
+                        path.display()
+                    ));
+                }
+                ProjectEdit::Edit { path, content }
+            }
+            RawProjectEdit::Create { path, content } => {
+                let path = resolve_project_path(target_directory, &path)?;
+                if path.exists() {
+                    return Err(format!(
+                        "Cannot create '{}': file already exists",
+                        path.display()
+                    ));
+                }
+                ProjectEdit::Create { path, content }
+            }
+            RawProjectEdit::Rename { from, to, content } => {
+                let from = resolve_project_path(target_directory, &from)?;
+                let to = resolve_project_path(target_directory, &to)?;
+                if !from.is_file() {
+                    return Err(format!(
+                        "Cannot rename '{}': file does not exist",
+                        from.display()
+                    ));
+                }
+                if to.exists() {
+                    return Err(format!(
+                        "Cannot rename '{}' to '{}': destination already exists",
+                        from.display(),
+                        to.display()
+                    ));
+                }
+                ProjectEdit::Rename { from, to, content }
+            }
+            RawProjectEdit::Delete { path } => {
+                let path = resolve_project_path(target_directory, &path)?;
+                if !path.is_file() {
+                    return Err(format!(
+                        "Cannot delete '{}': file does not exist",
+                        path.display()
+                    ));
+                }
+                ProjectEdit::Delete { path }
+            }
+        };
+        edits.push(edit);
+    }
+
+    let mut touched = HashSet::new();
+    for edit in &edits {
+        for path in edit.paths() {
+            if !touched.insert(path.to_path_buf()) {
+                return Err(format!(
+                    "Conflicting project edits: '{}' is changed more than once",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    Ok(edits)
+}
+
+/// Project handle passed to `transformProject(project, options)`.
+#[derive(Clone)]
+#[rquickjs::class]
+pub struct ProjectRjs {
+    target_directory: PathBuf,
+    language: CodemodLang,
+    files: Arc<Vec<PathBuf>>,
+    semantic_provider: Option<Arc<dyn SemanticProvider>>,
+}
+
+impl Trace<'_> for ProjectRjs {
+    fn trace<'a>(&self, _tracer: rquickjs::class::Tracer<'a, '_>) {
+        // No JavaScript values to trace
+    }
+}
+
+unsafe impl<'js> JsLifetime<'js> for ProjectRjs {
+    type Changed<'to> = ProjectRjs;
+}
+
+impl ProjectRjs {
+    fn resolve(&self, ctx: &Ctx<'_>, path: &str) -> Result<PathBuf> {
+        resolve_project_path(&self.target_directory, path)
+            .map_err(|e| Exception::throw_message(ctx, &e))
+    }
+}
+
+#[rquickjs::methods]
+impl ProjectRjs {
+    /// Absolute path of the directory the codemod runs against.
+    #[qjs(get, rename = "root")]
+    pub fn root(&self) -> String {
+        self.target_directory.to_string_lossy().into_owned()
+    }
+
+    /// Language the step was configured with.
+    #[qjs(get, rename = "language")]
+    pub fn language(&self) -> String {
+        self.language.to_string()
+    }
+
+    /// Absolute paths of the files selected by the step's include/exclude globs.
+    #[qjs(rename = "files")]
+    pub fn files(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Whether a file exists inside the target directory.
+    #[qjs(rename = "exists")]
+    pub fn exists<'js>(&self, ctx: Ctx<'js>, path: String) -> Result<bool> {
+        Ok(self.resolve(&ctx, &path)?.is_file())
+    }
+
+    /// Read a file inside the target directory.
+    #[qjs(rename = "readFile")]
+    pub fn read_file<'js>(&self, ctx: Ctx<'js>, path: String) -> Result<String> {
+        let resolved = self.resolve(&ctx, &path)?;
+        std::fs::read_to_string(&resolved).map_err(|e| {
+            Exception::throw_message(&ctx, &format!("Failed to read file '{path}': {e}"))
+        })
+    }
+
+    /// Files that import any of `paths`, directly or transitively, excluding
+    /// `paths` themselves. Needs workspace-scope semantic analysis; returns
+    /// an empty array otherwise.
+    #[qjs(rename = "dependentsOf")]
+    pub fn dependents_of<'js>(&self, ctx: Ctx<'js>, paths: Value<'js>) -> Result<Vec<String>> {
+        let raw_paths = match paths.as_string() {
+            Some(path) => vec![path.to_string()?],
+            None => Vec::<String>::from_js(&ctx, paths)?,
+        };
+        let Some(provider) = &self.semantic_provider else {
+            return Ok(Vec::new());
+        };
+        let resolved = raw_paths
+            .iter()
+            .map(|path| self.resolve(&ctx, path))
+            .collect::<Result<Vec<_>>>()?;
+        let dependents = provider.get_dependents(&resolved).map_err(|e| {
+            Exception::throw_message(&ctx, &format!("Failed to get dependents: {e}"))
+        })?;
+        Ok(dependents
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// Parse a file on demand. Defaults to the step language; the returned
+    /// root shares the step's semantic provider so `definition()` and
+    /// `references()` work across the project.
+    #[qjs(rename = "parseFile")]
+    pub fn parse_file<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        path: String,
+        language: Opt<String>,
+    ) -> Result<SgRootRjs<'js>> {
+        let resolved = self.resolve(&ctx, &path)?;
+        let language = match language.0 {
+            Some(language) => CodemodLang::from_str(&language)
+                .map_err(|e| Exception::throw_message(&ctx, &format!("Language error: {e}")))?,
+            None => self.language,
+        };
+        let content = std::fs::read_to_string(&resolved).map_err(|e| {
+            Exception::throw_message(&ctx, &format!("Failed to read file '{path}': {e}"))
+        })?;
+        let filename = resolved.to_string_lossy().into_owned();
+        SgRootRjs::try_new_with_semantic(
+            AstGrep::new(content, language),
+            Some(filename),
+            self.semantic_provider.clone(),
+            None,
+            Some(self.target_directory.as_path()),
+        )
+        .map_err(|e| Exception::throw_message(&ctx, &format!("Failed to parse: {e}")))
+    }
+}
+
+/// Options for executing a whole-project codemod
+pub struct ProjectExecutionOptions<'a, R> {
+    pub script_path: &'a Path,
+    pub resolver: Arc<R>,
+    pub language: CodemodLang,
+    /// Files selected for the run, exposed through `project.files()`
+    pub files: Vec<PathBuf>,
+    pub params: Option<HashMap<String, serde_json::Value>>,
+    pub matrix_values: Option<HashMap<String, serde_json::Value>>,
+    pub capabilities: Option<HashSet<LlrtSupportedModules>>,
+    /// Optional semantic provider shared by every root returned from `project.parseFile()`
+    pub semantic_provider: Option<Arc<dyn SemanticProvider>>,
+    /// Optional metrics context for tracking metrics across execution
+    pub metrics_context: Option<MetricsContext>,
+    /// Optional engine-owned LLM request handler exposed through codemod:llm
+    pub llm_request_handler: Option<LlmRequestHandler>,
+    /// Optional shared state context for cross-thread state communication
+    pub shared_state_context: Option<SharedStateContext>,
+    /// Optional runtime event callback for codemod:runtime hook emissions
+    pub runtime_event_callback: Option<RuntimeEventCallback>,
+    /// Optional cancellation flag exposed to codemod:runtime.isCanceled()
+    pub cancellation_flag: Option<Arc<AtomicBool>>,
+    /// Whether this is a dry-run execution (passed to codemod via options.dryRun)
+    pub dry_run: bool,
+    /// The target directory the codemod is running against. Every edit must stay within it.
+    pub target_directory: &'a Path,
+}
+
+/// Whether the module source may export `transformProject`, judged from its
+/// top-level export statements so per-file codemods skip the extra module
+/// evaluation. `export * from` can't be resolved statically and counts as a
+/// maybe, as does a source that fails to parse; `execute_project_codemod_with_quickjs`
+/// still checks the evaluated module.
+pub fn may_export_transform_project(path: &Path, source: &str) -> bool {
+    const EXPORT_NAME: &str = "transformProject";
+
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(path).unwrap_or_else(|_| SourceType::mjs());
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    if parsed.panicked {
+        return true;
+    }
+
+    parsed.program.body.iter().any(|statement| match statement {
+        Statement::ExportNamedDeclaration(export) => {
+            let declared = match &export.declaration {
+                Some(Declaration::FunctionDeclaration(function)) => function
+                    .id
+                    .as_ref()
+                    .is_some_and(|id| id.name == EXPORT_NAME),
+                Some(Declaration::VariableDeclaration(variables)) => {
+                    variables.declarations.iter().any(|declarator| {
+                        declarator
+                            .id
+                            .get_identifier_name()
+                            .is_some_and(|name| name == EXPORT_NAME)
+                    })
+                }
+                _ => false,
+            };
+            declared
+                || export
+                    .specifiers
+                    .iter()
+                    .any(|specifier| specifier.exported.name() == EXPORT_NAME)
+        }
+        Statement::ExportAllDeclaration(export) => export
+            .exported
+            .as_ref()
+            .is_none_or(|exported| exported.name() == EXPORT_NAME),
+        _ => false,
+    })
+}
+
+/// Execute a codemod's `transformProject` export using QuickJS.
+///
+/// Returns `Ok(None)` when the module doesn't export `transformProject`, so
+/// callers can fall back to per-file execution. Nothing is written to disk;
+/// the returned edits have been validated and are applied by the caller.
+pub async fn execute_project_codemod_with_quickjs<'a, R>(
+    options: ProjectExecutionOptions<'a, R>,
+) -> std::result::Result<Option<ProjectOutput>, ExecutionError>
+where
+    R: ModuleResolver + 'static,
+{
+    let script_name = options
+        .script_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("main.js");
+
+    let js_code = format!(
+        include_str!("scripts/project_script.js.txt"),
+        script_name = script_name
+    );
+
+    let params: HashMap<String, serde_json::Value> = options.params.unwrap_or_default();
+
+    let canonical_target_directory = options
+        .target_directory
+        .canonicalize()
+        .unwrap_or_else(|_| options.target_directory.to_path_buf());
+    let TransformRuntime {
+        context,
+        runtime_hooks_context,
+        jssg_file_changes,
+        _runtime,
+        ..
+    } = build_transform_runtime(TransformRuntimeOptions {
+        resolver: options.resolver,
+        capabilities: options.capabilities,
+        params: params.clone(),
+        matrix_values: options.matrix_values.clone(),
+        metrics_context: options.metrics_context,
+        llm_request_handler: options.llm_request_handler,
+        shared_state_context: options.shared_state_context,
+        runtime_event_callback: options.runtime_event_callback,
+        cancellation_flag: options.cancellation_flag,
+        coverage: None,
+        test_mode: false,
+        dry_run: options.dry_run,
+        target_directory: &canonical_target_directory,
+        dry_run_file: None,
+    })
+    .await?;
+
+    let project = ProjectRjs {
+        target_directory: canonical_target_directory.clone(),
+        language: options.language,
+        files: Arc::new(options.files),
+        semantic_provider: options.semantic_provider.clone(),
+    };
+
+    async_with!(context => |ctx| {
+        let execution = async {
+            let module = Module::declare(ctx.clone(), "__codemod_project_entry.js", js_code)
+                .catch(&ctx)
+                .map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                        message: format!("Failed to declare module: {e}"),
+                    },
+                })?;
+
+            let (evaluated, eval_value) = module
+                .eval()
+                .catch(&ctx)
+                .map_err(|e| map_transform_execution_error(&runtime_hooks_context, e))?;
+
+            maybe_promise(eval_value.into())
+                .await
+                .catch(&ctx)
+                .map_err(|e| map_transform_execution_error(&runtime_hooks_context, e))?;
+
+            while ctx.execute_pending_job() {}
+
+            let namespace = evaluated
+                .namespace()
+                .catch(&ctx)
+                .map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                        message: e.to_string(),
+                    },
+                })?;
+
+            let has_transform_project = namespace
+                .get::<_, Function>("hasTransformProject")
+                .and_then(|func| func.call::<_, bool>(()))
+                .catch(&ctx)
+                .map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                        message: e.to_string(),
+                    },
+                })?;
+            if !has_transform_project {
+                return Ok(None);
+            }
+
+            let language_str = options.language.to_string();
+            let target_dir_str = canonical_target_directory.to_string_lossy().into_owned();
+            let run_options_qjs = build_transform_options(
+                &ctx,
+                params,
+                &language_str,
+                options.matrix_values,
+                None,
+                options.dry_run,
+                &target_dir_str,
+            )?;
+
+            let func = namespace
+                .get::<_, Function>("executeProject")
+                .catch(&ctx)
+                .map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                        message: e.to_string(),
+                    },
+                })?;
+
+            let result_obj_promise = func.call((project, run_options_qjs)).catch(&ctx).map_err(|e| {
+                map_transform_execution_error(&runtime_hooks_context, e)
+            })?;
+            let result_obj = maybe_promise(result_obj_promise)
+                .await
+                .catch(&ctx)
+                .map_err(|e| map_transform_execution_error(&runtime_hooks_context, e))?;
+
+            let raw_edits: Vec<RawProjectEdit> = if result_obj.is_null() || result_obj.is_undefined() {
+                Vec::new()
+            } else if result_obj.is_array() {
+                let js_value = JsValue::from_js(&ctx, result_obj).map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
+                        message: format!("Failed to convert JS value: {e}"),
+                    },
+                })?;
+                serde_json::from_value(js_value.0).map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
+                        message: format!("Invalid transformProject edit: {e}"),
+                    },
+                })?
+            } else {
+                let type_name = result_obj.type_name();
+                return Err(ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
+                        message: format!("transformProject must return an array of edits or null/undefined. Received {type_name}"),
+                    },
+                });
+            };
+
+            let mut edits = build_project_edits(raw_edits, &canonical_target_directory)
+                .map_err(|message| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::ExecutionFailed { message },
+                })?;
+
+            let secondary = jssg_file_changes.changes.lock()
+                .map(|guard| guard.clone())
+                .unwrap_or_default();
+            let mut secondary_edits = Vec::new();
+            for change in secondary {
+                let ExecutionResult::Modified(modified) = change.result else {
+                    continue;
+                };
+                secondary_edits.push(match modified.rename_to {
+                    Some(to) => RawProjectEdit::Rename {
+                        from: change.path.to_string_lossy().into_owned(),
+                        to: to.to_string_lossy().into_owned(),
+                        content: Some(modified.content),
+                    },
+                    None => RawProjectEdit::Edit {
+                        path: change.path.to_string_lossy().into_owned(),
+                        content: modified.content,
+                    },
+                });
+            }
+            if !secondary_edits.is_empty() {
+                edits.extend(
+                    build_project_edits(secondary_edits, &canonical_target_directory)
+                        .map_err(|message| ExecutionError::Runtime {
+                            source: crate::sandbox::errors::RuntimeError::ExecutionFailed { message },
+                        })?,
+                );
+                let mut touched = HashSet::new();
+                for path in edits.iter().flat_map(ProjectEdit::paths) {
+                    if !touched.insert(path.to_path_buf()) {
+                        return Err(ExecutionError::Runtime {
+                            source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
+                                message: format!(
+                                    "Conflicting project edits: '{}' is changed by both transformProject and jssgTransform",
+                                    path.display()
+                                ),
+                            },
+                        });
+                    }
+                }
+            }
+
+            Ok(Some(ProjectOutput { edits }))
+        };
+        execution.await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::resolvers::oxc_resolver::OxcResolver;
+    use tempfile::tempdir;
+
+    #[test]
+    fn may_export_transform_project_reads_module_exports() {
+        let path = Path::new("codemod.ts");
+        for source in [
+            "export async function transformProject(root) { return []; }",
+            "export const transformProject = async () => [];",
+            "async function run() { return []; }\nexport { run as transformProject };",
+            "export * from './project';",
+        ] {
+            assert!(may_export_transform_project(path, source), "{source}");
+        }
+        for source in [
+            "// transformProject is not used here\nexport default function transform() {}",
+            "const transformProject = 1;\nexport default function transform() {}",
+            "export { transformProject as other } from './project';",
+        ] {
+            assert!(!may_export_transform_project(path, source), "{source}");
+        }
+    }
+
+    async fn run_project(
+        dir: &Path,
+        codemod: &str,
+        files: Vec<PathBuf>,
+    ) -> std::result::Result<Option<ProjectOutput>, ExecutionError> {
+        let codemod_dir = tempdir().unwrap();
+        let script_path = codemod_dir.path().join("codemod.js");
+        std::fs::write(&script_path, codemod).unwrap();
+        let resolver = Arc::new(OxcResolver::new(codemod_dir.path().to_path_buf(), None).unwrap());
+
+        execute_project_codemod_with_quickjs(ProjectExecutionOptions {
+            script_path: &script_path,
+            resolver,
+            language: "typescript".parse().unwrap(),
+            files,
+            params: None,
+            matrix_values: None,
+            capabilities: None,
+            semantic_provider: None,
+            metrics_context: None,
+            llm_request_handler: None,
+            shared_state_context: None,
+            runtime_event_callback: None,
+            cancellation_flag: None,
+            dry_run: false,
+            target_directory: dir,
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn returns_none_without_transform_project_export() {
+        let dir = tempdir().unwrap();
+        let output = run_project(
+            dir.path(),
+            "export default function transform() { return null; }",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        assert!(output.is_none());
+    }
+
+    #[tokio::test]
+    async fn collects_edits_from_transform_project() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.ts");
+        let b = dir.path().join("b.ts");
+        std::fs::write(&a, "export const value = 1;\n").unwrap();
+        std::fs::write(&b, "import { value } from './a';\n").unwrap();
+
+        let output = run_project(
+            dir.path(),
+            r#"
+            export async function transformProject(project) {
+                const edits = [];
+                for (const file of project.files()) {
+                    const root = project.parseFile(file);
+                    const node = root.root().find({ rule: { pattern: "value" } });
+                    if (node) {
+                        edits.push({ type: "edit", path: file, content: root.root().commitEdits([node.replace("renamed")]) });
+                    }
+                }
+                edits.push({ type: "create", path: "c.ts", content: "export {};\n" });
+                edits.push({ type: "delete", path: "b.ts" });
+                return edits;
+            }
+            "#,
+            vec![a.clone()],
+        )
+        .await
+        .unwrap()
+        .expect("transformProject should run");
+
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            output.edits,
+            vec![
+                ProjectEdit::Edit {
+                    path: root.join("a.ts"),
+                    content: "export const renamed = 1;\n".to_string(),
+                },
+                ProjectEdit::Create {
+                    path: root.join("c.ts"),
+                    content: "export {};\n".to_string(),
+                },
+                ProjectEdit::Delete {
+                    path: root.join("b.ts"),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_conflicting_project_edits() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.ts"), "a").unwrap();
+
+        let error = run_project(
+            dir.path(),
+            r#"
+            export function transformProject() {
+                return [
+                    { type: "edit", path: "a.ts", content: "b" },
+                    { type: "delete", path: "a.ts" },
+                ];
+            }
+            "#,
+            vec![],
+        )
+        .await
+        .expect_err("conflicting edits should fail");
+
+        assert!(error.to_string().contains("Conflicting project edits"));
+    }
+
+    #[test]
+    fn resolve_project_path_rejects_paths_outside_target() {
+        let dir = tempdir().unwrap();
+        let error = resolve_project_path(dir.path(), "../outside.ts").unwrap_err();
+        assert!(error.contains("outside the target directory"));
+        // `..` is only followed through directories that exist
+        assert!(resolve_project_path(dir.path(), "nested/../inside.ts").is_err());
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        assert_eq!(
+            resolve_project_path(dir.path(), "nested/../inside.ts").unwrap(),
+            dir.path().canonicalize().unwrap().join("inside.ts")
+        );
+        assert_eq!(
+            resolve_project_path(dir.path(), "new/dir/file.ts").unwrap(),
+            dir.path().canonicalize().unwrap().join("new/dir/file.ts")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_project_path_follows_symlinks_of_missing_files() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempdir().unwrap();
+        let outside = tempdir().unwrap();
+        symlink(outside.path(), dir.path().join("link")).unwrap();
+
+        let error = resolve_project_path(dir.path(), "link/nested/new.ts").unwrap_err();
+        assert!(error.contains("outside the target directory"));
+        assert!(resolve_project_path(dir.path(), "link/new.ts").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::coverage::CoverageCollector;
 use crate::sandbox::resolvers::ModuleResolver;
 use crate::utils::transpiler;
 use rquickjs::{
@@ -28,7 +29,17 @@ impl<R: ModuleResolver> Resolver for QuickJSResolver<R> {
 }
 
 /// QuickJS-compatible loader adapter
-pub struct QuickJSLoader;
+#[derive(Default)]
+pub struct QuickJSLoader {
+    coverage: Option<Arc<CoverageCollector>>,
+}
+
+impl QuickJSLoader {
+    /// Loader that instruments the modules covered by `coverage`, if any.
+    pub fn with_coverage(coverage: Option<Arc<CoverageCollector>>) -> Self {
+        Self { coverage }
+    }
+}
 
 impl Loader for QuickJSLoader {
     fn load<'js>(&mut self, ctx: &Ctx<'js>, name: &str) -> Result<Module<'js, module::Declared>> {
@@ -44,15 +55,25 @@ impl Loader for QuickJSLoader {
                 .unwrap_or_else(|| std::ffi::OsStr::new("anon.ts"))
                 .to_string_lossy();
 
-            if needs_transpilation {
-                let transpiled_bytes = transpiler::transpile(source, file_name.to_string())
-                    .map_err(|err| {
-                        Error::new_loading_message(name, format!("Transpilation failed: {err}"))
-                    })?;
-                Module::declare(ctx.clone(), name, transpiled_bytes.as_slice())
+            let code = if needs_transpilation {
+                transpiler::transpile(source, file_name.to_string()).map_err(|err| {
+                    Error::new_loading_message(name, format!("Transpilation failed: {err}"))
+                })?
             } else {
-                Module::declare(ctx.clone(), name, source.as_bytes())
-            }
+                source.into_bytes()
+            };
+
+            // Type stripping keeps positions, so coverage of transpiled code
+            // is coverage of the TypeScript source
+            let code = match &self.coverage {
+                Some(coverage) => std::str::from_utf8(&code)
+                    .ok()
+                    .and_then(|code| coverage.instrument(path, code))
+                    .map(String::into_bytes)
+                    .unwrap_or(code),
+                None => code,
+            };
+            Module::declare(ctx.clone(), name, code)
         } else {
             Err(Error::new_loading(name))
         }
@@ -0,0 +1,581 @@
+//! Persistent, content-addressed cache of per-file JSSG results.
+//!
+//! Entries are keyed on the codemod fingerprint, the params/matrix values, the
+//! file path, the file content hash and the language, so a hit means the same
+//! codemod already ran on exactly this input. Only self-contained results are
+//! cached: runs that produced secondary `jssgTransform` changes always execute.
+
+use super::codemod_lang::CodemodLang;
+use super::execution_engine::{CodemodOutput, ExecutionResult, ModifiedResult};
+use super::selector_engine::canonical_values_key;
+use crate::sandbox::resolvers::{ModuleResolver, OxcResolver};
+use language_core::{DiskStore, DiskStoreStats};
+use oxc::allocator::Allocator;
+use oxc::ast::ast::{
+    Argument, CallExpression, ExportAllDeclaration, ExportNamedDeclaration, Expression,
+    IdentifierReference, ImportDeclaration, ImportExpression,
+};
+use oxc::ast_visit::{walk, Visit};
+use oxc::parser::Parser;
+use oxc::span::SourceType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Bumped whenever the on-disk entry format or the key derivation changes.
+const RESULT_CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Extensions parsed for further imports while walking the module graph.
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx"];
+
+/// Runtime module giving codemods access to an LLM.
+const LLM_MODULE: &str = "codemod:llm";
+
+/// Runtime modules whose side effects or external inputs are lost when a file
+/// is served from the cache. Codemods importing them are never cached.
+const UNCACHEABLE_MODULES: &[&str] = &[
+    LLM_MODULE,
+    "codemod:metrics",
+    "codemod:workflow",
+    "fetch",
+    "fs",
+    "fs/promises",
+    "node:fs",
+    "node:fs/promises",
+];
+
+/// Globals with the same problem as [`UNCACHEABLE_MODULES`].
+const UNCACHEABLE_GLOBALS: &[&str] = &["fetch"];
+
+/// Identity of a codemod's sources, used as part of every cache key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodemodFingerprint {
+    /// SHA-256 over the codemod sources and the engine version
+    pub hash: String,
+    /// False when the codemod relies on side effects a cache hit would skip
+    pub cacheable: bool,
+    /// True when the codemod imports the LLM module, or loads modules
+    /// dynamically and so might
+    pub may_call_llm: bool,
+}
+
+impl CodemodFingerprint {
+    /// Fingerprint the module graph reachable from `script_path`. Imports are
+    /// followed with the same resolver the runtime uses, so edits to local
+    /// helpers and installed packages the codemod loads invalidate the cache,
+    /// while unrelated files next to the script don't.
+    pub fn for_script(script_path: &Path) -> io::Result<Self> {
+        let script_dir = script_path.parent().unwrap_or(Path::new("."));
+        let resolver = OxcResolver::new(script_dir.to_path_buf(), None)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let graph = ModuleGraph::collect(script_path, &resolver)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for (path, content) in &graph.modules {
+            let relative = path.strip_prefix(script_dir).unwrap_or(path);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(content);
+            hasher.update([0]);
+        }
+        for specifier in &graph.builtins {
+            hasher.update(specifier.as_bytes());
+            hasher.update([0]);
+        }
+
+        Ok(Self {
+            hash: format!("{:x}", hasher.finalize()),
+            cacheable: graph.cacheable,
+            may_call_llm: graph.may_call_llm,
+        })
+    }
+}
+
+/// Modules reachable from a codemod entry point.
+struct ModuleGraph {
+    /// Resolved module files and their contents, ordered by path
+    modules: BTreeMap<PathBuf, Vec<u8>>,
+    /// Specifiers that don't resolve to a file, such as `codemod:ast-grep`
+    builtins: BTreeSet<String>,
+    cacheable: bool,
+    may_call_llm: bool,
+}
+
+impl ModuleGraph {
+    fn collect(entry: &Path, resolver: &OxcResolver) -> io::Result<Self> {
+        let mut graph = Self {
+            modules: BTreeMap::new(),
+            builtins: BTreeSet::new(),
+            cacheable: true,
+            may_call_llm: false,
+        };
+        let mut pending = vec![entry.to_path_buf()];
+        while let Some(path) = pending.pop() {
+            if graph.modules.contains_key(&path) {
+                continue;
+            }
+            let content = fs::read(&path)?;
+            let is_script = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SCRIPT_EXTENSIONS.contains(&ext));
+            if is_script {
+                let source = String::from_utf8_lossy(&content);
+                let imports = ModuleImports::scan(&path, &source);
+                if imports.dynamic || imports.uncacheable_global {
+                    graph.cacheable = false;
+                }
+                if imports.dynamic {
+                    graph.may_call_llm = true;
+                }
+                let base = path.to_string_lossy();
+                for specifier in imports.specifiers {
+                    if UNCACHEABLE_MODULES.contains(&specifier.as_str()) {
+                        graph.cacheable = false;
+                    }
+                    if specifier == LLM_MODULE {
+                        graph.may_call_llm = true;
+                    }
+                    let resolved = if specifier.contains(':') {
+                        None
+                    } else {
+                        resolver.resolve(&base, &specifier).ok()
+                    };
+                    match resolved {
+                        Some(resolved) => pending.push(PathBuf::from(resolved)),
+                        None => {
+                            graph.builtins.insert(specifier);
+                        }
+                    }
+                }
+            }
+            graph.modules.insert(path, content);
+        }
+        Ok(graph)
+    }
+}
+
+/// Import specifiers and cache-relevant globals found in one module.
+#[derive(Default)]
+struct ModuleImports {
+    specifiers: Vec<String>,
+    /// An `import()` or `require()` whose specifier isn't a string literal
+    dynamic: bool,
+    uncacheable_global: bool,
+}
+
+impl ModuleImports {
+    fn scan(path: &Path, source: &str) -> Self {
+        let allocator = Allocator::default();
+        let source_type = SourceType::from_path(path).unwrap_or_else(|_| SourceType::mjs());
+        let parsed = Parser::new(&allocator, source, source_type).parse();
+        let mut imports = Self::default();
+        imports.visit_program(&parsed.program);
+        imports
+    }
+
+    fn add_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::StringLiteral(literal) => self.specifiers.push(literal.value.to_string()),
+            _ => self.dynamic = true,
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ModuleImports {
+    fn visit_import_declaration(&mut self, it: &ImportDeclaration<'a>) {
+        self.specifiers.push(it.source.value.to_string());
+    }
+
+    fn visit_export_named_declaration(&mut self, it: &ExportNamedDeclaration<'a>) {
+        if let Some(source) = &it.source {
+            self.specifiers.push(source.value.to_string());
+        }
+        walk::walk_export_named_declaration(self, it);
+    }
+
+    fn visit_export_all_declaration(&mut self, it: &ExportAllDeclaration<'a>) {
+        self.specifiers.push(it.source.value.to_string());
+    }
+
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        self.add_expression(&it.source);
+        walk::walk_import_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        if it.callee.is_specific_id("require") {
+            match it.arguments.first().and_then(Argument::as_expression) {
+                Some(argument) => self.add_expression(argument),
+                None => self.dynamic = true,
+            }
+        }
+        walk::walk_call_expression(self, it);
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        if UNCACHEABLE_GLOBALS.contains(&it.name.as_str()) {
+            self.uncacheable_global = true;
+        }
+    }
+}
+
+/// Hex-encoded SHA-256 cache key for one file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey(String);
+
+impl ResultCacheKey {
+    pub fn new(
+        fingerprint: &CodemodFingerprint,
+        params: Option<&HashMap<String, serde_json::Value>>,
+        matrix_values: Option<&HashMap<String, serde_json::Value>>,
+        language: CodemodLang,
+        file_path: &Path,
+        content: &str,
+    ) -> Self {
+        let content_hash = Sha256::digest(content.as_bytes());
+
+        let mut hasher = Sha256::new();
+        for part in [
+            RESULT_CACHE_FORMAT_VERSION.to_string(),
+            fingerprint.hash.clone(),
+            canonical_values_key(params),
+            canonical_values_key(matrix_values),
+            language.to_string(),
+            // The path is part of the key because transforms can read
+            // `root.filename()` and renames are recorded as absolute paths.
+            file_path.to_string_lossy().to_string(),
+            format!("{content_hash:x}"),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        Self(format!("{:x}", hasher.finalize()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A cached per-file outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum CachedResult {
+    Unmodified,
+    Skipped,
+    #[serde(rename_all = "camelCase")]
+    Modified {
+        content: String,
+        rename_to: Option<PathBuf>,
+    },
+}
+
+impl CachedResult {
+    /// Convert an execution output into a cacheable result. Returns `None`
+    /// when the output touched other files through `jssgTransform`.
+    pub fn from_output(output: &CodemodOutput) -> Option<Self> {
+        if !output.secondary.is_empty() {
+            return None;
+        }
+        Some(match &output.primary {
+            ExecutionResult::Unmodified => Self::Unmodified,
+            ExecutionResult::Skipped => Self::Skipped,
+            ExecutionResult::Modified(modified) => Self::Modified {
+                content: modified.content.clone(),
+                rename_to: modified.rename_to.clone(),
+            },
+        })
+    }
+
+    pub fn into_output(self) -> CodemodOutput {
+        let primary = match self {
+            Self::Unmodified => ExecutionResult::Unmodified,
+            Self::Skipped => ExecutionResult::Skipped,
+            Self::Modified { content, rename_to } => {
+                ExecutionResult::Modified(ModifiedResult { content, rename_to })
+            }
+        };
+        CodemodOutput {
+            primary,
+            secondary: Vec::new(),
+        }
+    }
+}
+
+/// Entry count and size of a result cache directory.
+pub type ResultCacheStats = DiskStoreStats;
+
+/// On-disk result cache. Entries live at `<root>/<key[..2]>/<key[2..]>.json`;
+/// reads refresh the entry's modification time so pruning by age keeps
+/// entries that are still being hit.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    entries: DiskStore,
+}
+
+impl ResultCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            entries: DiskStore::new(root, RESULT_CACHE_FORMAT_VERSION),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.entries.root()
+    }
+
+    /// Look up a cached result. Unreadable or outdated entries are misses.
+    pub fn get(&self, key: &ResultCacheKey) -> Option<CachedResult> {
+        self.entries.get(key.as_str())
+    }
+
+    /// Store a result.
+    pub fn insert(&self, key: &ResultCacheKey, result: &CachedResult) -> io::Result<()> {
+        self.entries.insert(key.as_str(), result)
+    }
+
+    pub fn stats(&self) -> io::Result<ResultCacheStats> {
+        self.entries.stats()
+    }
+
+    /// Remove entries that have not been written or hit within `max_age`.
+    /// With `dry_run` nothing is deleted; the returned stats describe what
+    /// would be removed.
+    pub fn prune(&self, max_age: Duration, dry_run: bool) -> io::Result<ResultCacheStats> {
+        self.entries.prune(max_age, dry_run)
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        self.entries.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn fingerprint(hash: &str) -> CodemodFingerprint {
+        CodemodFingerprint {
+            hash: hash.to_string(),
+            cacheable: true,
+            may_call_llm: false,
+        }
+    }
+
+    fn key(fingerprint: &CodemodFingerprint, content: &str) -> ResultCacheKey {
+        ResultCacheKey::new(
+            fingerprint,
+            None,
+            None,
+            "typescript".parse().unwrap(),
+            Path::new("/repo/src/index.ts"),
+            content,
+        )
+    }
+
+    #[test]
+    fn key_changes_with_codemod_params_and_content() {
+        let base = key(&fingerprint("a"), "const a = 1;");
+        assert_eq!(base, key(&fingerprint("a"), "const a = 1;"));
+        assert_ne!(base, key(&fingerprint("b"), "const a = 1;"));
+        assert_ne!(base, key(&fingerprint("a"), "const a = 2;"));
+
+        let params = HashMap::from([("mode".to_string(), serde_json::json!("strict"))]);
+        let with_params = ResultCacheKey::new(
+            &fingerprint("a"),
+            Some(&params),
+            None,
+            "typescript".parse().unwrap(),
+            Path::new("/repo/src/index.ts"),
+            "const a = 1;",
+        );
+        assert_ne!(base, with_params);
+    }
+
+    #[test]
+    fn round_trips_results_and_prunes() {
+        let dir = tempdir().unwrap();
+        let cache = ResultCache::new(dir.path().join("results"));
+        let unmodified = key(&fingerprint("a"), "one");
+        let modified = key(&fingerprint("a"), "two");
+
+        assert_eq!(cache.get(&unmodified), None);
+        cache
+            .insert(&unmodified, &CachedResult::Unmodified)
+            .unwrap();
+        cache
+            .insert(
+                &modified,
+                &CachedResult::Modified {
+                    content: "three".to_string(),
+                    rename_to: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(cache.get(&unmodified), Some(CachedResult::Unmodified));
+        assert!(matches!(
+            cache.get(&modified).map(CachedResult::into_output),
+            Some(CodemodOutput {
+                primary: ExecutionResult::Modified(ModifiedResult { ref content, .. }),
+                ..
+            }) if content == "three"
+        ));
+        assert_eq!(cache.stats().unwrap().entries, 2);
+
+        let would_prune = cache.prune(Duration::ZERO, true).unwrap();
+        assert_eq!(would_prune.entries, 2);
+        assert_eq!(cache.stats().unwrap().entries, 2);
+
+        assert_eq!(
+            cache
+                .prune(Duration::from_secs(3600), false)
+                .unwrap()
+                .entries,
+            0
+        );
+        cache.prune(Duration::ZERO, false).unwrap();
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+
+    #[test]
+    fn fingerprint_tracks_helpers_and_uncacheable_modules() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("codemod.ts");
+        fs::write(&script, "import { helper } from './helper';").unwrap();
+        fs::write(dir.path().join("helper.ts"), "export const helper = 1;").unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/dep")).unwrap();
+        fs::write(dir.path().join("node_modules/dep/index.js"), "1").unwrap();
+
+        let first = CodemodFingerprint::for_script(&script).unwrap();
+        assert!(first.cacheable);
+
+        fs::write(dir.path().join("node_modules/dep/index.js"), "2").unwrap();
+        assert_eq!(first, CodemodFingerprint::for_script(&script).unwrap());
+
+        fs::write(
+            dir.path().join("helper.ts"),
+            "import { useMetricAtom } from 'codemod:metrics';",
+        )
+        .unwrap();
+        let second = CodemodFingerprint::for_script(&script).unwrap();
+        assert_ne!(first.hash, second.hash);
+        assert!(!second.cacheable);
+    }
+
+    #[test]
+    fn secondary_changes_are_not_cacheable() {
+        let output = CodemodOutput {
+            primary: ExecutionResult::Unmodified,
+            secondary: vec![super::super::execution_engine::FileChange {
+                path: PathBuf::from("/repo/other.ts"),
+                result: ExecutionResult::Unmodified,
+                base_content: None,
+            }],
+        };
+        assert_eq!(CachedResult::from_output(&output), None);
+    }
+
+    #[test]
+    fn fingerprint_only_covers_reachable_modules() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("codemod.ts");
+        fs::write(&script, "import { helper } from './lib/helper';").unwrap();
+        fs::create_dir_all(dir.path().join("lib")).unwrap();
+        fs::write(
+            dir.path().join("lib/helper.ts"),
+            "export { data } from './data.json';",
+        )
+        .unwrap();
+        fs::write(dir.path().join("lib/data.json"), "{}").unwrap();
+        fs::write(dir.path().join("unrelated.ts"), "export const a = 1;").unwrap();
+
+        let first = CodemodFingerprint::for_script(&script).unwrap();
+        fs::write(dir.path().join("unrelated.ts"), "export const a = 2;").unwrap();
+        assert_eq!(first, CodemodFingerprint::for_script(&script).unwrap());
+
+        fs::write(dir.path().join("lib/data.json"), "{\"a\": 1}").unwrap();
+        assert_ne!(first, CodemodFingerprint::for_script(&script).unwrap());
+    }
+
+    #[test]
+    fn llm_fetch_and_dynamic_imports_are_not_cacheable() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("codemod.ts");
+        for source in [
+            "import { llm } from 'codemod:llm';",
+            "const response = await fetch('https://example.com');",
+            "import { fetch } from 'fetch';",
+            "const name = 'helper';\nawait import(`./${name}`);",
+        ] {
+            fs::write(&script, source).unwrap();
+            assert!(
+                !CodemodFingerprint::for_script(&script).unwrap().cacheable,
+                "{source}"
+            );
+        }
+
+        fs::write(&script, "import { parse } from 'codemod:ast-grep';").unwrap();
+        assert!(CodemodFingerprint::for_script(&script).unwrap().cacheable);
+    }
+
+    #[test]
+    fn fs_imports_are_not_cacheable() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("codemod.ts");
+        for source in [
+            "import { readFileSync } from 'fs';",
+            "import { readFile } from 'fs/promises';",
+            "import { writeFileSync } from 'node:fs';",
+            "import { writeFile } from 'node:fs/promises';",
+        ] {
+            fs::write(&script, source).unwrap();
+            assert!(
+                !CodemodFingerprint::for_script(&script).unwrap().cacheable,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn llm_imports_and_dynamic_imports_may_call_llm() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("codemod.ts");
+        fs::write(
+            dir.path().join("helper.ts"),
+            "import { llm } from 'codemod:llm';",
+        )
+        .unwrap();
+        for source in [
+            "import { llm } from 'codemod:llm';",
+            "import './helper';",
+            "const name = 'helper';\nawait import(`./${name}`);",
+        ] {
+            fs::write(&script, source).unwrap();
+            assert!(
+                CodemodFingerprint::for_script(&script)
+                    .unwrap()
+                    .may_call_llm,
+                "{source}"
+            );
+        }
+
+        fs::write(
+            &script,
+            "const response = await fetch('https://example.com');",
+        )
+        .unwrap();
+        assert!(
+            !CodemodFingerprint::for_script(&script)
+                .unwrap()
+                .may_call_llm
+        );
+    }
+}
@@ -7,15 +7,17 @@ use crate::metrics::{MetricsContext, MetricsModule};
 use crate::sandbox::errors::ExecutionError;
 use crate::sandbox::resolvers::ModuleResolver;
 use crate::utils::quickjs_utils::maybe_promise;
+use crate::utils::source_map::remap_stack_trace;
 use ast_grep_config::{RuleConfig, SerializableRuleConfig};
 use codemod_llrt_capabilities::module_builder::LlrtModuleBuilder;
 use codemod_llrt_capabilities::types::LlrtSupportedModules;
 use rquickjs::{async_with, AsyncContext, AsyncRuntime};
 use rquickjs::{CatchResultExt, Function, Module};
 use rquickjs::{FromJs, IntoJs};
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use crate::ast_grep::serde::JsValue;
 use crate::workflow_global::WorkflowGlobalModule;
@@ -31,6 +33,82 @@ pub struct SelectorEngineOptions<'a, R> {
     /// backed by `vfs::PhysicalFS` at disk root `/`, with reads/writes
     /// prefix-checked against this path.
     pub target_directory: Option<&'a Path>,
+    /// Resolved params exposed to `getSelector({ params })`.
+    pub params: Option<HashMap<String, serde_json::Value>>,
+    /// Matrix values exposed to `getSelector({ matrixValues })`.
+    pub matrix_values: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Identifies a selector extracted for one version of a codemod script,
+/// language and parameterization. Params and matrix values are part of the
+/// key because `getSelector` may choose its rule based on them, and the
+/// script's content hash so that editing it invalidates the entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectorCacheKey {
+    script_path: PathBuf,
+    script_hash: String,
+    language: String,
+    params: String,
+    matrix_values: String,
+}
+
+impl SelectorCacheKey {
+    pub fn new(
+        script_path: &Path,
+        language: CodemodLang,
+        params: Option<&HashMap<String, serde_json::Value>>,
+        matrix_values: Option<&HashMap<String, serde_json::Value>>,
+    ) -> Self {
+        // An unreadable script fails to load anyway, so it needs no hash
+        let script_hash = std::fs::read(script_path)
+            .map(|content| format!("{:x}", Sha256::digest(content)))
+            .unwrap_or_default();
+        Self {
+            script_path: script_path.to_path_buf(),
+            script_hash,
+            language: language.to_string(),
+            params: canonical_values_key(params),
+            matrix_values: canonical_values_key(matrix_values),
+        }
+    }
+}
+
+/// Serialize a value map with sorted keys so equal maps produce equal keys
+/// regardless of `HashMap` iteration order.
+pub(crate) fn canonical_values_key(values: Option<&HashMap<String, serde_json::Value>>) -> String {
+    values
+        .map(|values| {
+            let sorted: BTreeMap<&String, &serde_json::Value> = values.iter().collect();
+            serde_json::to_string(&sorted).unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// In-process cache of extracted selectors, shared across the tasks of a run
+/// so matrix tasks with identical params don't re-evaluate `getSelector`.
+/// A cached `None` records that the codemod does not export a selector.
+#[derive(Clone, Default)]
+pub struct SelectorCache {
+    entries: Arc<Mutex<HashMap<SelectorCacheKey, Option<Arc<RuleConfig<CodemodLang>>>>>>,
+}
+
+impl SelectorCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &SelectorCacheKey) -> Option<Option<Arc<RuleConfig<CodemodLang>>>> {
+        self.entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.get(key).cloned())
+    }
+
+    pub fn insert(&self, key: SelectorCacheKey, selector: Option<Arc<RuleConfig<CodemodLang>>>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, selector);
+        }
+    }
 }
 
 /// Extract a selector from a codemod module using QuickJS
@@ -52,8 +130,18 @@ where
         script_name = script_name
     );
 
-    // TODO: Add params to the codemod
-    let params: HashMap<String, String> = HashMap::new();
+    let params = options
+        .params
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| (k, JsValue(v)))
+        .collect::<HashMap<String, JsValue>>();
+    let matrix_values = options.matrix_values.map(|values| {
+        values
+            .into_iter()
+            .map(|(k, v)| (k, JsValue(v)))
+            .collect::<HashMap<String, JsValue>>()
+    });
 
     // Initialize QuickJS runtime and context
     let runtime = AsyncRuntime::new().map_err(|e| ExecutionError::Runtime {
@@ -131,7 +219,7 @@ where
     }
 
     let fs_resolver = QuickJSResolver::new(Arc::clone(&options.resolver));
-    let fs_loader = QuickJSLoader;
+    let fs_loader = QuickJSLoader::default();
 
     // Combine resolvers and loaders
     runtime
@@ -204,6 +292,14 @@ where
                     },
                 })?;
 
+            ctx.globals()
+                .set("CODEMOD_MATRIX_VALUES", matrix_values)
+                .map_err(|e| ExecutionError::Runtime {
+                    source: crate::sandbox::errors::RuntimeError::InitializationFailed {
+                        message: format!("Failed to set matrix values global variable: {e}"),
+                    },
+                })?;
+
             ctx.globals()
                 .set("CODEMOD_LANGUAGE", options.language.to_string())
                 .map_err(|e| ExecutionError::Runtime {
@@ -218,7 +314,7 @@ where
                 .catch(&ctx)
                 .map_err(|e| ExecutionError::Runtime {
                     source: crate::sandbox::errors::RuntimeError::InitializationFailed {
-                        message: e.to_string(),
+                        message: remap_stack_trace(&e.to_string()),
                     },
                 })?;
             while ctx.execute_pending_job() {}
@@ -246,7 +342,7 @@ where
             let result_obj_promise = func.call(()).catch(&ctx).map_err(|e| {
                 ExecutionError::Runtime {
                     source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
-                        message: e.to_string(),
+                        message: remap_stack_trace(&e.to_string()),
                     },
                 }
             })?;
@@ -255,7 +351,7 @@ where
                 .catch(&ctx)
                 .map_err(|e| ExecutionError::Runtime {
                     source: crate::sandbox::errors::RuntimeError::ExecutionFailed {
-                        message: e.to_string(),
+                        message: remap_stack_trace(&e.to_string()),
                     },
                 })?;
 
@@ -331,6 +427,8 @@ mod tests {
             resolver,
             capabilities: None,
             target_directory: Some(dir.path()),
+            params: None,
+            matrix_values: None,
         })
         .await;
 
@@ -366,6 +464,8 @@ mod tests {
             resolver,
             capabilities: None,
             target_directory: Some(dir.path()),
+            params: None,
+            matrix_values: None,
         })
         .await
         .unwrap();
@@ -403,6 +503,8 @@ mod tests {
             resolver,
             capabilities: None,
             target_directory: Some(dir.path()),
+            params: None,
+            matrix_values: None,
         })
         .await
         .unwrap();
@@ -412,4 +514,93 @@ mod tests {
             "selector extraction should support top-level metrics imports"
         );
     }
+
+    #[tokio::test]
+    async fn selector_extraction_passes_params_and_matrix_values_to_get_selector() {
+        let dir = tempdir().unwrap();
+        let script_path = dir.path().join("codemod.js");
+        std::fs::write(
+            &script_path,
+            r#"
+            export const getSelector = ({ params, matrixValues }) => {
+                if (params.api !== "legacyFetch" || matrixValues.team !== "web") {
+                    throw new Error("unexpected selector options");
+                }
+                return { rule: { pattern: `${params.api}($$$ARGS)` } };
+            };
+            export default function codemod() {}
+            "#,
+        )
+        .unwrap();
+
+        let resolver = Arc::new(OxcResolver::new(dir.path().to_path_buf(), None).unwrap());
+        let result = extract_selector_with_quickjs(SelectorEngineOptions {
+            script_path: &script_path,
+            language: "typescript".parse::<CodemodLang>().unwrap(),
+            resolver,
+            capabilities: None,
+            target_directory: Some(dir.path()),
+            params: Some(HashMap::from([(
+                "api".to_string(),
+                serde_json::json!("legacyFetch"),
+            )])),
+            matrix_values: Some(HashMap::from([(
+                "team".to_string(),
+                serde_json::json!("web"),
+            )])),
+        })
+        .await
+        .unwrap();
+
+        assert!(
+            result.is_some(),
+            "params and matrix values should reach getSelector"
+        );
+    }
+
+    #[test]
+    fn selector_cache_key_depends_on_params_but_not_their_order() {
+        let script_path = Path::new("/codemods/codemod.js");
+        let language = "typescript".parse::<CodemodLang>().unwrap();
+        let mut first = HashMap::new();
+        first.insert("a".to_string(), serde_json::json!(1));
+        first.insert("b".to_string(), serde_json::json!("two"));
+        let mut second = HashMap::new();
+        second.insert("b".to_string(), serde_json::json!("two"));
+        second.insert("a".to_string(), serde_json::json!(1));
+        let other = HashMap::from([("a".to_string(), serde_json::json!(2))]);
+
+        assert_eq!(
+            SelectorCacheKey::new(script_path, language, Some(&first), None),
+            SelectorCacheKey::new(script_path, language, Some(&second), None)
+        );
+        assert_ne!(
+            SelectorCacheKey::new(script_path, language, Some(&first), None),
+            SelectorCacheKey::new(script_path, language, Some(&other), None)
+        );
+        assert_ne!(
+            SelectorCacheKey::new(script_path, language, Some(&first), None),
+            SelectorCacheKey::new(script_path, language, Some(&first), Some(&other))
+        );
+    }
+
+    #[test]
+    fn selector_cache_key_changes_when_the_script_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let script_path = dir.path().join("codemod.js");
+        let language = "typescript".parse::<CodemodLang>().unwrap();
+
+        std::fs::write(&script_path, "export const getSelector = () => null;").unwrap();
+        let before = SelectorCacheKey::new(&script_path, language, None, None);
+        assert_eq!(
+            before,
+            SelectorCacheKey::new(&script_path, language, None, None)
+        );
+
+        std::fs::write(&script_path, "export const getSelector = () => undefined;").unwrap();
+        assert_ne!(
+            before,
+            SelectorCacheKey::new(&script_path, language, None, None)
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! Parse checks for transformed code.
+//!
+//! A codemod that emits code tree-sitter cannot parse leaves `ERROR` nodes,
+//! or `MISSING` nodes the parser inserted to recover, in the output tree.
+//! Only issues the transform introduced are reported: code that was already
+//! broken before the codemod ran is not blamed on it.
+
+use super::codemod_lang::CodemodLang;
+use super::language_data::get_language_from_extension;
+use ast_grep_core::tree_sitter::LanguageExt;
+use std::fmt;
+use std::path::Path;
+
+/// Issues listed in a summary before the rest are elided.
+const SUMMARY_LIMIT: usize = 3;
+
+/// Characters of an unparsable region shown in a summary.
+const SNIPPET_LIMIT: usize = 40;
+
+/// A region of source the parser could not make sense of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxIssue {
+    /// 1-based line the issue starts on
+    pub line: usize,
+    pub kind: SyntaxIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxIssueKind {
+    /// Text that does not fit the grammar
+    Unexpected(String),
+    /// A node the parser had to insert, such as a closing bracket
+    Missing(String),
+}
+
+impl fmt::Display for SyntaxIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SyntaxIssueKind::Unexpected(text) => {
+                write!(f, "line {}: unexpected `{text}`", self.line)
+            }
+            SyntaxIssueKind::Missing(kind) => write!(f, "line {}: missing `{kind}`", self.line),
+        }
+    }
+}
+
+/// `ERROR` and `MISSING` nodes in `source` parsed as `language`. Errors
+/// nested in another error are not reported separately.
+pub fn syntax_issues(language: CodemodLang, source: &str) -> Vec<SyntaxIssue> {
+    let root = language.ast_grep(source);
+    let mut issues = Vec::new();
+    let mut error_end = 0;
+    for node in root.root().dfs() {
+        let range = node.range();
+        if node.is_missing() {
+            issues.push(SyntaxIssue {
+                line: node.start_pos().line() + 1,
+                kind: SyntaxIssueKind::Missing(node.kind().to_string()),
+            });
+        } else if node.is_error() && range.start >= error_end {
+            error_end = range.end;
+            issues.push(SyntaxIssue {
+                line: node.start_pos().line() + 1,
+                kind: SyntaxIssueKind::Unexpected(snippet(&node.text())),
+            });
+        }
+    }
+    issues
+}
+
+/// Issues in `modified` when `original` parsed cleanly. Returns nothing
+/// when the original was already broken.
+pub fn introduced_syntax_issues(
+    language: CodemodLang,
+    original: &str,
+    modified: &str,
+) -> Vec<SyntaxIssue> {
+    if !syntax_issues(language, original).is_empty() {
+        return Vec::new();
+    }
+    syntax_issues(language, modified)
+}
+
+/// Language to check a change to `change_path` with. The file the codemod
+/// ran on uses the step's `language`; other files a codemod writes are
+/// detected from their extension, and are not checked when it is unknown.
+pub fn syntax_check_language(
+    file_path: &Path,
+    language: CodemodLang,
+    change_path: &Path,
+) -> Option<CodemodLang> {
+    if change_path == file_path {
+        return Some(language);
+    }
+    change_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(get_language_from_extension)
+}
+
+/// One-line summary of `issues`, for error messages.
+pub fn summarize_syntax_issues(issues: &[SyntaxIssue]) -> String {
+    let mut summary = issues
+        .iter()
+        .take(SUMMARY_LIMIT)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    if issues.len() > SUMMARY_LIMIT {
+        summary.push_str(&format!(" (and {} more)", issues.len() - SUMMARY_LIMIT));
+    }
+    summary
+}
+
+fn snippet(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(SNIPPET_LIMIT) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typescript() -> CodemodLang {
+        "typescript".parse().unwrap()
+    }
+
+    #[test]
+    fn valid_code_has_no_issues() {
+        assert!(syntax_issues(typescript(), "const a = f(1);\n").is_empty());
+    }
+
+    #[test]
+    fn reports_unparsable_and_missing_code() {
+        let issues = syntax_issues(typescript(), "const a = f(1;\nconst = 2;\n");
+        assert!(!issues.is_empty());
+        assert!(issues.iter().all(|issue| issue.line >= 1));
+        assert!(summarize_syntax_issues(&issues).starts_with("line "));
+    }
+
+    #[test]
+    fn only_issues_introduced_by_the_transform_are_reported() {
+        let broken = "const a = f(1;\n";
+        assert!(!introduced_syntax_issues(typescript(), "const a = f(1);\n", broken).is_empty());
+        assert!(introduced_syntax_issues(typescript(), broken, broken).is_empty());
+    }
+
+    #[test]
+    fn secondary_files_are_checked_in_their_own_language() {
+        let file_path = Path::new("src/app.ts");
+        let json = syntax_check_language(file_path, typescript(), Path::new("package.json"))
+            .expect("json is a known language");
+        // Valid JSON is not valid TypeScript on its own
+        assert!(syntax_issues(json, "{\"a\": 1}\n").is_empty());
+        assert!(syntax_check_language(file_path, typescript(), Path::new("README")).is_none());
+        assert!(syntax_check_language(file_path, typescript(), file_path).is_some());
+    }
+
+    #[test]
+    fn summary_elides_issues_past_the_limit() {
+        let issues: Vec<_> = (1..=5)
+            .map(|line| SyntaxIssue {
+                line,
+                kind: SyntaxIssueKind::Missing(";".to_string()),
+            })
+            .collect();
+        assert_eq!(
+            summarize_syntax_issues(&issues),
+            "line 1: missing `;`; line 2: missing `;`; line 3: missing `;` (and 2 more)"
+        );
+    }
+}
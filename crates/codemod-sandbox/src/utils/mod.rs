@@ -1,5 +1,6 @@
 pub mod project_discovery;
 pub mod quickjs_utils;
+pub mod source_map;
 pub mod transpiler;
 
 #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
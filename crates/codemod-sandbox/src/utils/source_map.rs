@@ -0,0 +1,256 @@
+//! Source map support for remapping QuickJS stack traces.
+//!
+//! Bundled codemods carry a `//# sourceMappingURL=` comment pointing at a
+//! sibling `.map` file or an inline base64 data URL. When a codemod throws,
+//! frames that point into such a bundle are rewritten to the original source
+//! file, line and column so errors point at the author's TypeScript.
+
+use base64::Engine as _;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+const SOURCE_MAPPING_URL_PREFIX: &str = "//# sourceMappingURL=";
+const INLINE_SOURCE_MAP_PREFIX: &str = "data:application/json;base64,";
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    generated_column: u32,
+    source: u32,
+    original_line: u32,
+    original_column: u32,
+}
+
+/// A location in an original source file. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginalLocation {
+    pub source: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A decoded version 3 source map.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    sources: Vec<PathBuf>,
+    lines: Vec<Vec<Segment>>,
+}
+
+impl SourceMap {
+    /// Parse a source map. Relative `sources` are resolved against
+    /// `base_dir`, the directory the map was loaded from.
+    pub fn parse(json: &str, base_dir: &Path) -> Option<Self> {
+        let map = oxc_sourcemap::SourceMap::from_json_string(json).ok()?;
+        let source_root = Path::new(map.get_source_root().unwrap_or_default());
+        let sources = (0..)
+            .map_while(|id| map.get_source(id))
+            .map(|source| {
+                let source = source_root.join(source);
+                if source.is_absolute() {
+                    normalize(&source)
+                } else {
+                    normalize(&base_dir.join(source))
+                }
+            })
+            .collect();
+
+        // Tokens are ordered by generated position; group them per line so
+        // lookups can binary search the columns.
+        let mut lines: Vec<Vec<Segment>> = Vec::new();
+        for token in map.get_tokens() {
+            let Some(source) = token.get_source_id() else {
+                continue;
+            };
+            let line = token.get_dst_line() as usize;
+            if lines.len() <= line {
+                lines.resize_with(line + 1, Vec::new);
+            }
+            lines[line].push(Segment {
+                generated_column: token.get_dst_col(),
+                source,
+                original_line: token.get_src_line(),
+                original_column: token.get_src_col(),
+            });
+        }
+
+        Some(Self { sources, lines })
+    }
+
+    /// Load the map referenced by a script's `sourceMappingURL` comment.
+    pub fn load_for_script(script_path: &Path) -> Option<Self> {
+        let script = std::fs::read_to_string(script_path).ok()?;
+        let url = script
+            .lines()
+            .rev()
+            .take(5)
+            .find_map(|line| line.trim().strip_prefix(SOURCE_MAPPING_URL_PREFIX))?
+            .trim();
+        let script_dir = script_path.parent().unwrap_or(Path::new("."));
+
+        if let Some(encoded) = url.strip_prefix(INLINE_SOURCE_MAP_PREFIX) {
+            let json = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())?;
+            return Self::parse(&json, script_dir);
+        }
+
+        let map_path = script_dir.join(url);
+        let json = std::fs::read_to_string(&map_path).ok()?;
+        Self::parse(&json, map_path.parent().unwrap_or(script_dir))
+    }
+
+    /// Find the original location of a generated 1-based line and column.
+    pub fn lookup(&self, line: usize, column: usize) -> Option<OriginalLocation> {
+        let segments = self.lines.get(line.checked_sub(1)?)?;
+        let column = u32::try_from(column.checked_sub(1)?).ok()?;
+        let index = segments.partition_point(|segment| segment.generated_column <= column);
+        let segment = segments[..index].last().or_else(|| segments.first())?;
+        Some(OriginalLocation {
+            source: self.sources.get(segment.source as usize)?.clone(),
+            line: segment.original_line as usize + 1,
+            column: segment.original_column as usize + 1,
+        })
+    }
+}
+
+/// Rewrite `path:line:column` stack frame locations that point into bundles
+/// with source maps. Frames without a map are left untouched.
+pub fn remap_stack_trace(text: &str) -> String {
+    let mut maps: HashMap<String, Option<SourceMap>> = HashMap::new();
+    text.lines()
+        .map(|line| remap_stack_line(line, &mut maps))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn remap_stack_line(line: &str, maps: &mut HashMap<String, Option<SourceMap>>) -> String {
+    let trimmed = line.trim_start();
+    let location_range = if let (Some(start), Some(end)) = (line.rfind('('), line.rfind(')')) {
+        (start < end).then_some(start + 1..end)
+    } else {
+        trimmed
+            .strip_prefix("at ")
+            .map(|location| line.len() - location.len()..line.len())
+    };
+    let Some(range) = location_range else {
+        return line.to_string();
+    };
+
+    let location = &line[range.clone()];
+    let Some((path, generated_line, generated_column)) = parse_location(location) else {
+        return line.to_string();
+    };
+    let map = maps
+        .entry(path.to_string())
+        .or_insert_with(|| SourceMap::load_for_script(Path::new(path)));
+    let Some(original) = map
+        .as_ref()
+        .and_then(|map| map.lookup(generated_line, generated_column))
+    else {
+        return line.to_string();
+    };
+
+    format!(
+        "{}{}:{}:{}{}",
+        &line[..range.start],
+        original.source.display(),
+        original.line,
+        original.column,
+        &line[range.end..]
+    )
+}
+
+fn parse_location(location: &str) -> Option<(&str, usize, usize)> {
+    let (path_and_line, column) = location.rsplit_once(':')?;
+    let (path, line) = path_and_line.rsplit_once(':')?;
+    let line = line.parse().ok()?;
+    let column = column.parse().ok()?;
+    (!path.is_empty()).then_some((path, line, column))
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // Maps `bundle.js` line 1 col 0 -> `src/codemod.ts` line 3 col 2 and
+    // line 2 col 4 -> `src/helper.ts` line 10 col 0.
+    const MAP: &str = r#"{"version":3,"sources":["src/codemod.ts","src/helper.ts"],"names":[],"mappings":"AAEE;ICOF"}"#;
+
+    #[test]
+    fn looks_up_original_locations() {
+        let map = SourceMap::parse(MAP, Path::new("/pkg")).unwrap();
+        assert_eq!(
+            map.lookup(1, 1),
+            Some(OriginalLocation {
+                source: PathBuf::from("/pkg/src/codemod.ts"),
+                line: 3,
+                column: 3,
+            })
+        );
+        assert_eq!(
+            map.lookup(2, 10),
+            Some(OriginalLocation {
+                source: PathBuf::from("/pkg/src/helper.ts"),
+                line: 10,
+                column: 1,
+            })
+        );
+        assert_eq!(map.lookup(3, 1), None);
+    }
+
+    #[test]
+    fn remaps_frames_that_point_into_bundles() {
+        let dir = tempdir().unwrap();
+        let bundle = dir.path().join("bundle.js");
+        std::fs::write(
+            &bundle,
+            "throw new Error('boom');\n  fail();\n//# sourceMappingURL=bundle.js.map\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("bundle.js.map"), MAP).unwrap();
+
+        let trace = format!(
+            "Error: boom\n    at transform ({}:2:5)\n    at <anonymous> (__codemod_entry.js:4:12)",
+            bundle.display()
+        );
+        let remapped = remap_stack_trace(&trace);
+        let helper = dir.path().join("src/helper.ts");
+        assert_eq!(
+            remapped,
+            format!(
+                "Error: boom\n    at transform ({}:10:1)\n    at <anonymous> (__codemod_entry.js:4:12)",
+                helper.display()
+            )
+        );
+    }
+
+    #[test]
+    fn loads_inline_source_maps() {
+        let dir = tempdir().unwrap();
+        let bundle = dir.path().join("bundle.js");
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, MAP);
+        std::fs::write(
+            &bundle,
+            format!("throw 1;\n//# sourceMappingURL=data:application/json;base64,{encoded}\n"),
+        )
+        .unwrap();
+
+        let map = SourceMap::load_for_script(&bundle).unwrap();
+        assert_eq!(map.lookup(1, 1).map(|location| location.line), Some(3));
+    }
+}
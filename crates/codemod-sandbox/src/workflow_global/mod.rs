@@ -59,6 +59,15 @@ pub fn get_step_outputs(
     }
 }
 
+/// Remove all outputs of a step, so a new run starts without them.
+pub fn clear_step_outputs(step_id: &str) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut store = STEP_OUTPUTS_STORE
+        .lock()
+        .map_err(|e| format!("Failed to lock STEP_OUTPUTS_STORE: {}", e))?;
+    store.remove(step_id);
+    Ok(())
+}
+
 /// Get or set step output atomically
 /// If the output exists, returns it. If not, sets it to the provided value and returns it.
 pub fn get_or_set_step_output(
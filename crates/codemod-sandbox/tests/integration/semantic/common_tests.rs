@@ -30,3 +30,12 @@ jssg_test! {
     target: "input.js",
     no_provider: true,
 }
+
+jssg_test! {
+    name: test_scope_without_provider,
+    language: CodemodLang::Static(SupportLang::JavaScript),
+    codemod: "no_provider_scope.js",
+    fixture_dir: "common/without_provider",
+    target: "input.js",
+    no_provider: true,
+}
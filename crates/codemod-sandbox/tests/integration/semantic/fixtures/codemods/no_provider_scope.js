@@ -0,0 +1,15 @@
+export default function transform(root) {
+  const node = root.root().find({ rule: { pattern: "x" } });
+  if (!node) {
+    throw new Error("Expected to find 'x' node");
+  }
+
+  const scope = node.scope();
+
+  // Should return null when no provider is configured
+  if (scope !== null) {
+    throw new Error("Expected null when no semantic provider is configured");
+  }
+
+  return null;
+}
@@ -0,0 +1,37 @@
+export default function transform(root) {
+  const rootNode = root.root();
+  const find = (pattern) => {
+    const node = rootNode.find({ rule: { pattern } });
+    if (!node) {
+      throw new Error("Expected to find '" + pattern + "'");
+    }
+    return node;
+  };
+
+  const open = find("open(path)");
+  const write = find("handle.write(item)");
+  const close = find("handle.close()");
+
+  if (!open.dominates(write) || write.dominates(close)) {
+    throw new Error("Expected open() to dominate write() but write() not to dominate close()");
+  }
+  // `finally` runs on every path, including the early return
+  if (!close.postDominates(open) || !close.postDominates(write)) {
+    throw new Error("Expected close() in finally to post-dominate open() and write()");
+  }
+
+  const copy = rootNode.find({ rule: { kind: "function_definition", regex: "^def copy" } });
+  const exits = copy.exitPoints().map((node) => node.text());
+  if (exits.join("|") !== "handle.close()") {
+    throw new Error("Unexpected exit points: " + exits.join("|"));
+  }
+
+  if (find("cleanup()").isReachable()) {
+    throw new Error("Expected cleanup() after raise to be unreachable");
+  }
+  if (!find("report()").isReachable()) {
+    throw new Error("Expected report() to be reachable");
+  }
+
+  return null;
+}
@@ -0,0 +1,44 @@
+export default function transform(root) {
+  const call = root.root().find({ rule: { pattern: "open(path).read(limit)" } });
+  if (!call) {
+    throw new Error("Expected to find 'open(path).read(limit)'");
+  }
+
+  const scope = call.scope();
+  if (scope === null) {
+    throw new Error("Expected a scope for the call");
+  }
+  if (scope.kind() !== "function") {
+    throw new Error("Expected the method scope, got " + scope.kind());
+  }
+
+  const names = scope.bindings().map((binding) => binding.name).join(",");
+  if (names !== "self,path,data") {
+    throw new Error("Expected bindings [self,path,data], got [" + names + "]");
+  }
+
+  // Class attributes are not visible from methods, so `limit` is the global
+  const free = scope.freeNames().join(",");
+  if (free !== "limit,open") {
+    throw new Error("Expected free names [limit,open], got [" + free + "]");
+  }
+
+  const cls = scope.parent();
+  if (cls.kind() !== "class" || !cls.hasOwn("limit") || !cls.hasOwn("read")) {
+    throw new Error("Expected the parent scope to be the class body");
+  }
+
+  if (!scope.has("os") || scope.has("missing")) {
+    throw new Error("Expected module imports to be visible from the method");
+  }
+
+  // `line` is bound in the nested comprehension
+  if (scope.uniqueName("line") !== "line1") {
+    throw new Error("Expected uniqueName('line') to be 'line1', got " + scope.uniqueName("line"));
+  }
+  if (scope.uniqueName("data") !== "data1") {
+    throw new Error("Expected uniqueName('data') to be 'data1'");
+  }
+
+  return null;
+}
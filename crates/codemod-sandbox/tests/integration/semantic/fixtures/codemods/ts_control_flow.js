@@ -0,0 +1,45 @@
+export default function transform(root) {
+  const rootNode = root.root();
+  const find = (pattern) => {
+    const node = rootNode.find({ rule: { pattern } });
+    if (!node) {
+      throw new Error("Expected to find '" + pattern + "'");
+    }
+    return node;
+  };
+
+  const open = find("open(path)");
+  const write = find("handle.write(item)");
+  const close = find("handle.close()");
+
+  if (!open.dominates(close) || !open.dominates(write)) {
+    throw new Error("Expected open() to dominate write() and close()");
+  }
+  if (write.dominates(close)) {
+    throw new Error("Expected write() not to dominate close()");
+  }
+  // The early return skips close()
+  if (close.postDominates(open)) {
+    throw new Error("Expected close() not to post-dominate open()");
+  }
+
+  const copy = rootNode.find({ rule: { pattern: "function copy($$$) { $$$ }" } });
+  const exits = copy.exitPoints().map((node) => node.text());
+  if (exits.join("|") !== "return;|handle.close();") {
+    throw new Error("Unexpected exit points: " + exits.join("|"));
+  }
+
+  if (find("cleanup()").isReachable()) {
+    throw new Error("Expected cleanup() after throw to be unreachable");
+  }
+  if (!find("report()").isReachable()) {
+    throw new Error("Expected report() to be reachable");
+  }
+
+  // Nodes in different functions never dominate each other
+  if (open.dominates(find("report()"))) {
+    throw new Error("Expected no dominance across functions");
+  }
+
+  return null;
+}
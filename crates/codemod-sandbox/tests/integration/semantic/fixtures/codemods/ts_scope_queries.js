@@ -0,0 +1,61 @@
+export default function transform(root) {
+  const call = root.root().find({ rule: { pattern: "format(item, value)" } });
+  if (!call) {
+    throw new Error("Expected to find 'format(item, value)'");
+  }
+
+  const scope = call.scope();
+  if (scope === null) {
+    throw new Error("Expected a scope for the call");
+  }
+  if (scope.kind() !== "function") {
+    throw new Error("Expected the arrow function scope, got " + scope.kind());
+  }
+
+  const names = scope.bindings().map((binding) => binding.name);
+  if (names.join(",") !== "item") {
+    throw new Error("Expected bindings [item], got [" + names.join(",") + "]");
+  }
+  if (scope.bindings()[0].node.text() !== "item") {
+    throw new Error("Expected binding node to be the parameter");
+  }
+
+  // Visible through enclosing scopes, but not declared here
+  if (!scope.has("items") || !scope.has("format") || scope.hasOwn("items")) {
+    throw new Error("Expected items and format to be visible from the arrow function");
+  }
+  if (scope.has("missing")) {
+    throw new Error("Expected 'missing' not to be declared");
+  }
+
+  const free = scope.freeNames().join(",");
+  if (free !== "format,value") {
+    throw new Error("Expected free names [format,value], got [" + free + "]");
+  }
+
+  const render = scope.parent();
+  if (render.kind() !== "function" || !render.hasOwn("result")) {
+    throw new Error("Expected the parent scope to be render()");
+  }
+  if (!render.node().text().startsWith("function render")) {
+    throw new Error("Expected the parent scope node to be the function declaration");
+  }
+
+  const module = render.parent();
+  if (module.kind() !== "module" || module.parent() !== null) {
+    throw new Error("Expected the outermost scope to be the module");
+  }
+
+  // `item` is declared in a nested scope and `value` is already taken
+  if (render.uniqueName("item") !== "item1") {
+    throw new Error("Expected uniqueName('item') to be 'item1', got " + render.uniqueName("item"));
+  }
+  if (render.uniqueName("value") !== "value1") {
+    throw new Error("Expected uniqueName('value') to be 'value1'");
+  }
+  if (render.uniqueName("helper") !== "helper") {
+    throw new Error("Expected uniqueName('helper') to be unchanged");
+  }
+
+  return null;
+}
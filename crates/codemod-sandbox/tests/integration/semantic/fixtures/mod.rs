@@ -244,6 +244,7 @@ pub async fn run_test(config: TestConfig<'_>) -> Result<Option<String>, String>
         test_mode: false,
         dry_run: false,
         target_directory: target_path.parent().unwrap_or(target_path.as_path()),
+        coverage: None,
     };
 
     let result = execute_codemod_with_quickjs(options).await;
@@ -0,0 +1,16 @@
+def copy(path, items):
+    handle = open(path)
+    try:
+        for item in items:
+            if not item:
+                return
+            handle.write(item)
+    finally:
+        handle.close()
+
+
+def finished(flag):
+    if flag:
+        raise RuntimeError("done")
+        cleanup()
+    report()
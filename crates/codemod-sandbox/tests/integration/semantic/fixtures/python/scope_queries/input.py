@@ -0,0 +1,11 @@
+import os
+
+limit = 10
+
+
+class Reader:
+    limit = 5
+
+    def read(self, path):
+        data = open(path).read(limit)
+        return [line for line in data.splitlines() if line]
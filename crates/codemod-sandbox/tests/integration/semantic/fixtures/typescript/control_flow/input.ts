@@ -0,0 +1,18 @@
+function copy(path: string, items: string[]) {
+  const handle = open(path);
+  for (const item of items) {
+    if (!item) {
+      return;
+    }
+    handle.write(item);
+  }
+  handle.close();
+}
+
+function finished(flag: boolean) {
+  if (flag) {
+    throw new Error("done");
+    cleanup();
+  }
+  report();
+}
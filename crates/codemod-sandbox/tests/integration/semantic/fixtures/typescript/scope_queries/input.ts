@@ -0,0 +1,8 @@
+import { format } from "./format";
+
+const value = 1;
+
+function render(items: string[]) {
+  const result = items.map((item) => format(item, value));
+  return result;
+}
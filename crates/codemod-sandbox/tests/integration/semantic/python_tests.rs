@@ -31,6 +31,22 @@ jssg_test! {
     target: "input.py",
 }
 
+jssg_test! {
+    name: test_scope_queries,
+    language: CodemodLang::Static(SupportLang::Python),
+    codemod: "py_scope_queries.js",
+    fixture_dir: "python/scope_queries",
+    target: "input.py",
+}
+
+jssg_test! {
+    name: test_control_flow_queries,
+    language: CodemodLang::Static(SupportLang::Python),
+    codemod: "py_control_flow.js",
+    fixture_dir: "python/control_flow",
+    target: "input.py",
+}
+
 // =============================================================================
 // Cross-file (Workspace Scope) Tests
 // =============================================================================
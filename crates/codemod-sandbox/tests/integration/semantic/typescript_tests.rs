@@ -54,3 +54,19 @@ jssg_test! {
     preprocess: ["main.ts"],
     scope: workspace,
 }
+
+jssg_test! {
+    name: test_scope_queries,
+    language: CodemodLang::Static(SupportLang::TypeScript),
+    codemod: "ts_scope_queries.js",
+    fixture_dir: "typescript/scope_queries",
+    target: "input.ts",
+}
+
+jssg_test! {
+    name: test_control_flow_queries,
+    language: CodemodLang::Static(SupportLang::TypeScript),
+    codemod: "ts_control_flow.js",
+    fixture_dir: "typescript/control_flow",
+    target: "input.ts",
+}
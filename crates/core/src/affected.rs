@@ -0,0 +1,196 @@
+//! Affected-file targeting for `js-ast-grep` runs.
+//!
+//! `affected_by` entries name the modules that changed, either directly as
+//! paths relative to the target or as git ranges (`main..HEAD`). The run is
+//! then limited to those files and every file that transitively imports them.
+
+use crate::utils::with_semantic_index;
+use crate::{Error, Result};
+use language_core::{ProviderMode, SemanticProvider};
+use semantic_factory::{LazySemanticProvider, SemanticConfig};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files selected by `affected_by`.
+#[derive(Debug)]
+pub struct AffectedSelection {
+    /// The candidate files that are affected, in their original order
+    pub files: Vec<PathBuf>,
+    /// Number of changed files the selection was computed from
+    pub changed: usize,
+}
+
+/// Limit `files` to those affected by the `affected_by` entries in `specs`:
+/// the changed files plus everything that transitively imports them.
+///
+/// `provider` is reused when it is workspace-scoped; otherwise `target_path`
+/// is indexed with a workspace provider backed by the shared semantic index.
+pub fn select_affected(
+    target_path: &Path,
+    specs: &[String],
+    files: Vec<PathBuf>,
+    provider: Option<&dyn SemanticProvider>,
+) -> Result<AffectedSelection> {
+    let changed = resolve_changed_files(target_path, specs)?;
+    let affected = match provider {
+        Some(provider) if provider.mode() == ProviderMode::WorkspaceScope => {
+            affected_files(provider, &changed)?
+        }
+        _ => affected_files(
+            &LazySemanticProvider::new(with_semantic_index(SemanticConfig::workspace_scope(
+                target_path.to_path_buf(),
+            ))),
+            &changed,
+        )?,
+    };
+    Ok(AffectedSelection {
+        files: restrict_to_affected(files, &affected),
+        changed: changed.len(),
+    })
+}
+
+/// Whether an `affected_by` entry is a git range rather than a path.
+///
+/// A `..` that forms a whole path component (`../shared/api.ts`) is a parent
+/// directory, not a range.
+pub fn is_git_range(spec: &str) -> bool {
+    !spec.starts_with('-')
+        && spec.contains("..")
+        && !spec.split(['/', '\\']).any(|part| part == "..")
+}
+
+/// Expand `affected_by` entries into the list of changed files.
+///
+/// Git ranges are expanded with `git diff --name-only` run in `target_path`;
+/// other entries are resolved relative to `target_path`. Entries starting
+/// with `-` are rejected so they can never reach git as options.
+pub fn resolve_changed_files(target_path: &Path, specs: &[String]) -> Result<Vec<PathBuf>> {
+    let mut changed = Vec::new();
+    for spec in specs {
+        if spec.starts_with('-') {
+            return Err(Error::Other(format!(
+                "Invalid affected_by entry '{spec}': entries must not start with '-'"
+            )));
+        }
+        if is_git_range(spec) {
+            changed.extend(git_diff_files(target_path, spec)?);
+        } else {
+            changed.push(target_path.join(spec));
+        }
+    }
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+/// The changed files together with all of their transitive importers.
+fn affected_files(
+    provider: &dyn SemanticProvider,
+    changed: &[PathBuf],
+) -> Result<HashSet<PathBuf>> {
+    let dependents = provider
+        .get_dependents(changed)
+        .map_err(|e| Error::Other(format!("Failed to compute dependents: {e}")))?;
+    Ok(changed
+        .iter()
+        .cloned()
+        .chain(dependents)
+        .map(|path| normalize(&path))
+        .collect())
+}
+
+/// Keep only the files in `files` that are part of `affected`.
+fn restrict_to_affected(files: Vec<PathBuf>, affected: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    files
+        .into_iter()
+        .filter(|file| affected.contains(&normalize(file)))
+        .collect()
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn git_diff_files(target_path: &Path, range: &str) -> Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--name-only",
+            "--relative",
+            "--end-of-options",
+            range,
+        ])
+        .current_dir(target_path)
+        .output()
+        .map_err(|e| Error::Other(format!("Failed to run git diff for '{range}': {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "git diff --name-only {range} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| target_path.join(line))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semantic_factory::LazySemanticProvider;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_git_range_detection() {
+        assert!(is_git_range("main..HEAD"));
+        assert!(is_git_range("origin/main...feature"));
+        assert!(!is_git_range("src/index.ts"));
+        assert!(!is_git_range("../shared/api.ts"));
+        assert!(!is_git_range("--output=/tmp/x..y"));
+    }
+
+    #[test]
+    fn test_option_like_entries_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        for spec in ["--output=/tmp/pwned..HEAD", "-p"] {
+            let err = resolve_changed_files(dir.path(), &[spec.to_string()]).unwrap_err();
+            assert!(err.to_string().contains("must not start with '-'"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_affected_files_follow_importers() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(root.join("api.ts"), "export const fetchUser = () => 1;\n").unwrap();
+        fs::write(
+            root.join("service.ts"),
+            "import { fetchUser } from './api';\nexport const load = fetchUser;\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("page.ts"),
+            "import { load } from './service';\nload();\n",
+        )
+        .unwrap();
+        fs::write(root.join("other.ts"), "export const x = 1;\n").unwrap();
+
+        let provider = LazySemanticProvider::workspace_scope(root.to_path_buf());
+        let changed = resolve_changed_files(root, &["api.ts".to_string()]).unwrap();
+        let affected = affected_files(&provider, &changed).unwrap();
+
+        let files = ["api.ts", "other.ts", "page.ts", "service.ts"]
+            .iter()
+            .map(|name| root.join(name))
+            .collect();
+        let names: Vec<_> = restrict_to_affected(files, &affected)
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["api.ts", "page.ts", "service.ts"]);
+    }
+}
@@ -8,6 +8,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use butterflow_models::step::UseInstallSkill;
 use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use codemod_sandbox::llm::{cassette::LlmCassette, LlmRequestHandler};
 use thiserror::Error;
 
 use crate::{
@@ -111,6 +112,18 @@ pub struct PullRequestCreationRequest {
 pub type PullRequestApprovalCallback =
     Arc<dyn Fn(&PullRequestCreationRequest) -> Result<bool, anyhow::Error> + Send + Sync>;
 
+#[derive(Clone, Debug)]
+pub struct AiStepRequest {
+    pub prompt: String,
+    pub system_prompt: Option<String>,
+    pub node_id: String,
+    pub node_name: String,
+    pub task_id: String,
+}
+
+/// Callback that runs AI steps instead of a coding agent or the built-in AI.
+pub type AiStepCallback = Arc<dyn Fn(&AiStepRequest) -> Result<(), anyhow::Error> + Send + Sync>;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DirtyGitApprovalKind {
     UncommittedChanges,
@@ -171,6 +184,14 @@ pub struct WorkflowExecutionSettings {
     pub skip_state_writes: bool,
     /// Flatten matrix tasks to a single task per node (used by pro codemod dry-run)
     pub flatten_matrix_tasks: bool,
+    /// Directory of the per-file JSSG result cache; `None` disables caching
+    pub result_cache_dir: Option<PathBuf>,
+    /// Handler for LLM requests from JSSG steps, replacing the one configured
+    /// through `LLM_*` environment variables (used by workflow tests)
+    pub llm_request_handler: Option<LlmRequestHandler>,
+    /// Cassette AI steps replay their file changes from, or record them to
+    /// by running the built-in AI (used by workflow tests)
+    pub ai_step_cassette: Option<LlmCassette>,
 }
 
 impl Default for WorkflowExecutionSettings {
@@ -192,6 +213,9 @@ impl Default for WorkflowExecutionSettings {
             skip_shard_steps: false,
             skip_state_writes: false,
             flatten_matrix_tasks: false,
+            result_cache_dir: None,
+            llm_request_handler: None,
+            ai_step_cassette: None,
         }
     }
 }
@@ -211,6 +235,9 @@ pub struct WorkflowInteractionSettings {
     pub pull_request_approval_callback: Option<PullRequestApprovalCallback>,
     /// Optional interactive approval callback for proceeding on dirty/untracked git targets
     pub dirty_git_approval_callback: Option<DirtyGitApprovalCallback>,
+    /// Optional handler for AI steps, used instead of agents (used by workflow
+    /// tests). With an `ai_step_cassette`, it only observes the steps.
+    pub ai_step_callback: Option<AiStepCallback>,
 }
 
 #[derive(Clone)]
@@ -5,7 +5,7 @@ use futures_util::FutureExt;
 use serde::Deserialize;
 use std::any::Any;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::future::Future;
 use std::hash::{Hash, Hasher};
@@ -27,8 +27,8 @@ use crate::ai_handoff::{
     find_agent_executable, resolve_agent_name, DetectionConfidence,
 };
 use crate::config::{
-    CapabilitiesSecurityCallback, InstallSkillExecutionRequest, InstallSkillExecutor,
-    ShellCommandExecutionRequest, WorkflowRunConfig,
+    AiStepRequest, CapabilitiesSecurityCallback, InstallSkillExecutionRequest,
+    InstallSkillExecutor, ShellCommandExecutionRequest, WorkflowRunConfig,
 };
 use crate::execution::{CodemodExecutionConfig, ProgressCallback};
 use crate::execution_stats::ExecutionStats;
@@ -48,8 +48,9 @@ use crate::task_state_service::TaskStateService;
 use crate::utils::validate_workflow;
 use crate::workflow_runtime::{publish_event, WorkflowEvent};
 use chrono::Utc;
-use codemod_sandbox::llm::{LlmRequestHandler, LlmResponse};
-use codemod_sandbox::sandbox::engine::CodemodOutput;
+use codemod_sandbox::llm::cassette::{AiStepChanges, CassetteMode, LlmCassette};
+use codemod_sandbox::llm::{LlmRequest, LlmRequestHandler, LlmResponse};
+use codemod_sandbox::sandbox::engine::{CodemodOutput, SelectorCache};
 use codemod_sandbox::sandbox::runtime_module::{
     RuntimeEvent, RuntimeEventKind, RuntimeFailure, RuntimeFailureKind,
 };
@@ -612,6 +613,11 @@ pub struct Engine {
     /// (today: the js-ast-grep file loop). `cancel_workflow` flips every entry
     /// so the step can short-circuit without polling the state backend.
     step_cancel_signals: Arc<std::sync::Mutex<HashMap<Uuid, Arc<AtomicBool>>>>,
+
+    /// Selectors extracted from js-ast-grep codemods, keyed by script,
+    /// language, params and matrix values so tasks sharing a
+    /// parameterization evaluate `getSelector` once.
+    selector_cache: SelectorCache,
 }
 
 /// Represents a codemod dependency chain for cycle detection
@@ -627,6 +633,55 @@ impl Default for Engine {
     }
 }
 
+/// LLM client configured from the `LLM_PROVIDER`, `LLM_MODEL`,
+/// `LLM_BASE_URL` and `LLM_API_KEY` environment variables. Requests are
+/// recorded in `usage_context`.
+pub fn env_llm_request_handler(usage_context: LlmUsageContext) -> LlmRequestHandler {
+    let provider = std::env::var("LLM_PROVIDER")
+        .unwrap_or_else(|_| "openai".to_string())
+        .trim()
+        .to_ascii_lowercase();
+    let model = std::env::var("LLM_MODEL").unwrap_or_default();
+    let endpoint = std::env::var("LLM_BASE_URL").unwrap_or_else(|_| match provider.as_str() {
+        "anthropic" => "https://api.anthropic.com".to_string(),
+        _ => "https://api.openai.com/v1".to_string(),
+    });
+    let api_key = std::env::var("LLM_API_KEY").unwrap_or_default();
+
+    Arc::new(move |request| {
+        let usage_context = usage_context.clone();
+        let provider = provider.clone();
+        let model = model.clone();
+        let endpoint = endpoint.clone();
+        let api_key = api_key.clone();
+
+        Box::pin(async move {
+            if api_key.trim().is_empty() {
+                usage_context.record_error(&provider, &model);
+                return Err("Engine LLM client requires LLM_API_KEY".to_string());
+            }
+            if model.trim().is_empty() {
+                usage_context.record_error(&provider, &model);
+                return Err("Engine LLM client requires LLM_MODEL".to_string());
+            }
+
+            let result = generate_llm(GenerateRequest {
+                endpoint,
+                api_key,
+                model: model.clone(),
+                provider: provider.clone(),
+                system_prompt: request.system_prompt,
+                prompt: request.prompt,
+                output_schema: request.output_schema,
+                max_tokens: request.max_tokens,
+            })
+            .await;
+
+            record_llm_result(&usage_context, &provider, &model, result)
+        })
+    })
+}
+
 fn record_llm_result(
     usage_context: &LlmUsageContext,
     configured_provider: &str,
@@ -767,6 +822,83 @@ fn configured_ai_agent_candidates<'a>(
         .collect()
 }
 
+/// Text files under `root` by `/`-separated relative path, skipping `.git`.
+/// Files that are not UTF-8 are left out of AI step recordings.
+fn snapshot_text_files(root: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+    {
+        let entry = entry.map_err(|e| Error::StepExecution(e.to_string()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let relative: Vec<_> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        files.insert(relative.join("/"), content);
+    }
+    Ok(files)
+}
+
+/// Files that differ between two snapshots of the same directory.
+fn diff_snapshots(
+    before: &BTreeMap<String, String>,
+    after: BTreeMap<String, String>,
+) -> AiStepChanges {
+    let mut changes: AiStepChanges = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .map(|path| (path.clone(), None))
+        .collect();
+    for (path, content) in after {
+        if before.get(&path) != Some(&content) {
+            changes.insert(path, Some(content));
+        }
+    }
+    changes
+}
+
+/// Write the files an AI step changed back into `root`.
+fn apply_ai_step_changes(root: &Path, changes: &AiStepChanges) -> Result<()> {
+    for (path, content) in changes {
+        let relative = Path::new(path);
+        if relative
+            .components()
+            .any(|component| !matches!(component, std::path::Component::Normal(_)))
+        {
+            return Err(Error::StepExecution(format!(
+                "Recorded AI step changes a path outside the target directory: {path}"
+            )));
+        }
+        let path = root.join(relative);
+        match content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, content)?;
+            }
+            None => {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 impl Engine {
     async fn launch_agent(
         &self,
@@ -1086,6 +1218,7 @@ impl Engine {
                 .with_text_log_fallthrough(true),
             output_heartbeat_callbacks: Arc::new(std::sync::Mutex::new(HashMap::new())),
             step_cancel_signals: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            selector_cache: SelectorCache::new(),
         }
     }
 
@@ -1113,6 +1246,7 @@ impl Engine {
             structured_logger,
             output_heartbeat_callbacks: Arc::new(std::sync::Mutex::new(HashMap::new())),
             step_cancel_signals: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            selector_cache: SelectorCache::new(),
         }
     }
 
@@ -1125,51 +1259,22 @@ impl Engine {
         &self.workflow_run_config
     }
 
-    pub(crate) fn llm_request_handler(&self) -> LlmRequestHandler {
-        let usage_context = self.llm_usage_context.clone();
-        let provider = std::env::var("LLM_PROVIDER")
-            .unwrap_or_else(|_| "openai".to_string())
-            .trim()
-            .to_ascii_lowercase();
-        let model = std::env::var("LLM_MODEL").unwrap_or_default();
-        let endpoint = std::env::var("LLM_BASE_URL").unwrap_or_else(|_| match provider.as_str() {
-            "anthropic" => "https://api.anthropic.com".to_string(),
-            _ => "https://api.openai.com/v1".to_string(),
-        });
-        let api_key = std::env::var("LLM_API_KEY").unwrap_or_default();
-
-        Arc::new(move |request| {
-            let usage_context = usage_context.clone();
-            let provider = provider.clone();
-            let model = model.clone();
-            let endpoint = endpoint.clone();
-            let api_key = api_key.clone();
-
-            Box::pin(async move {
-                if api_key.trim().is_empty() {
-                    usage_context.record_error(&provider, &model);
-                    return Err("Engine LLM client requires LLM_API_KEY".to_string());
-                }
-                if model.trim().is_empty() {
-                    usage_context.record_error(&provider, &model);
-                    return Err("Engine LLM client requires LLM_MODEL".to_string());
-                }
-
-                let result = generate_llm(GenerateRequest {
-                    endpoint,
-                    api_key,
-                    model: model.clone(),
-                    provider: provider.clone(),
-                    system_prompt: request.system_prompt,
-                    prompt: request.prompt,
-                    output_schema: request.output_schema,
-                    max_tokens: request.max_tokens,
-                })
-                .await;
+    /// LLM handler for JSSG steps: the configured override, or the built-in
+    /// client when the step may use the network.
+    pub(crate) fn llm_request_handler_for(
+        &self,
+        capabilities: Option<&HashSet<LlrtSupportedModules>>,
+    ) -> Option<LlmRequestHandler> {
+        if let Some(handler) = &self.workflow_run_config.execution.llm_request_handler {
+            return Some(handler.clone());
+        }
+        capabilities
+            .is_some_and(|capabilities| capabilities.contains(&LlrtSupportedModules::Fetch))
+            .then(|| self.llm_request_handler())
+    }
 
-                record_llm_result(&usage_context, &provider, &model, result)
-            })
-        })
+    pub(crate) fn llm_request_handler(&self) -> LlmRequestHandler {
+        env_llm_request_handler(self.llm_usage_context.clone())
     }
 
     pub(crate) fn state_adapter(&self) -> Arc<Mutex<Box<dyn StateAdapter>>> {
@@ -1184,6 +1289,10 @@ impl Engine {
         Arc::clone(&self.file_writer)
     }
 
+    pub(crate) fn selector_cache(&self) -> &SelectorCache {
+        &self.selector_cache
+    }
+
     /// Create a new engine with a custom state adapter
     pub fn with_state_adapter(
         state_adapter: Box<dyn StateAdapter>,
@@ -1210,6 +1319,7 @@ impl Engine {
             structured_logger,
             output_heartbeat_callbacks: Arc::new(std::sync::Mutex::new(HashMap::new())),
             step_cancel_signals: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            selector_cache: SelectorCache::new(),
         }
     }
 
@@ -1944,6 +2054,18 @@ impl Engine {
             .await
     }
 
+    /// Get the shared state of a workflow run
+    pub async fn get_workflow_state(
+        &self,
+        workflow_run_id: Uuid,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        self.state_adapter
+            .lock()
+            .await
+            .get_state(workflow_run_id)
+            .await
+    }
+
     /// List workflow runs
     pub async fn list_workflow_runs(&self, limit: usize) -> Result<Vec<WorkflowRun>> {
         self.state_adapter
@@ -2867,6 +2989,7 @@ impl Engine {
                     languages: Some(languages.iter().map(|l| l.to_string()).collect()),
                     threads: ast_grep.max_threads,
                     capabilities: None,
+                    include_components: true,
                 };
 
                 // Clone variables needed in the closure
@@ -3127,7 +3250,7 @@ impl Engine {
         &self,
         ai_config: &UseAI,
         _step_env: &Option<HashMap<String, String>>,
-        _node: &Node,
+        node: &Node,
         task: &Task,
         params: &HashMap<String, serde_json::Value>,
         state: &HashMap<String, serde_json::Value>,
@@ -3146,6 +3269,26 @@ impl Engine {
 
         debug!("Executing AI agent step with prompt: {}", resolved_prompt);
 
+        let cassette = self.workflow_run_config.execution.ai_step_cassette.as_ref();
+        if let Some(callback) = &self.workflow_run_config.interaction.ai_step_callback {
+            callback(&AiStepRequest {
+                prompt: resolved_prompt.clone(),
+                system_prompt: ai_config.system_prompt.clone(),
+                node_id: node.id.clone(),
+                node_name: node.name.clone(),
+                task_id: task.id.to_string(),
+            })
+            .map_err(|error| Error::StepExecution(error.to_string()))?;
+            if cassette.is_none() {
+                return Ok(());
+            }
+        }
+        if let Some(cassette) = cassette {
+            return self
+                .execute_ai_step_with_cassette(cassette, ai_config, resolved_prompt, logger)
+                .await;
+        }
+
         // 1. Check explicitly configured agents before inspecting the parent
         // process. `--agent` has priority over LLM_AGENT so CLI intent is stable
         // even when the command is run inside another coding agent.
@@ -3304,7 +3447,66 @@ impl Engine {
             handoff_detection.confidence.as_str(),
             detected_agent
         );
+        self.execute_builtin_ai_step(ai_config, resolved_prompt, logger)
+            .await
+    }
+
+    /// Replay the file changes a cassette recorded for an AI step, or run
+    /// the built-in AI and record the files it changed.
+    async fn execute_ai_step_with_cassette(
+        &self,
+        cassette: &LlmCassette,
+        ai_config: &UseAI,
+        resolved_prompt: String,
+        logger: &StructuredLogger,
+    ) -> Result<()> {
+        let target_path = &self.workflow_run_config.execution.target_path;
+        let request = LlmRequest {
+            prompt: resolved_prompt.clone(),
+            system_prompt: ai_config.system_prompt.clone(),
+            output_schema: None,
+            max_tokens: None,
+        };
+
+        match cassette.mode() {
+            CassetteMode::Replay => {
+                let changes = cassette
+                    .ai_step_changes(&request)
+                    .map_err(Error::StepExecution)?;
+                apply_ai_step_changes(target_path, &changes)?;
+                slog!(
+                    logger,
+                    info,
+                    "Replayed AI step from {} ({} files changed)",
+                    cassette.path().display(),
+                    changes.len()
+                );
+                Ok(())
+            }
+            CassetteMode::Record => {
+                if ai_config.api_key.is_none() && std::env::var("LLM_API_KEY").is_err() {
+                    return Err(Error::StepExecution(
+                        "Recording AI steps requires LLM_API_KEY or the step's api_key".to_string(),
+                    ));
+                }
+                let before = snapshot_text_files(target_path)?;
+                self.execute_builtin_ai_step(ai_config, resolved_prompt, logger)
+                    .await?;
+                let after = snapshot_text_files(target_path)?;
+                cassette
+                    .record_ai_step(request, diff_snapshots(&before, after))
+                    .map_err(Error::StepExecution)
+            }
+        }
+    }
 
+    /// Run an AI step with the built-in agent and the `LLM_*` configuration.
+    async fn execute_builtin_ai_step(
+        &self,
+        ai_config: &UseAI,
+        resolved_prompt: String,
+        logger: &StructuredLogger,
+    ) -> Result<()> {
         // Configure LLM settings - check for API key from config or environment
         let api_key = match ai_config
             .api_key
@@ -4346,6 +4548,7 @@ impl Clone for Engine {
             structured_logger: self.structured_logger.clone(),
             output_heartbeat_callbacks: Arc::clone(&self.output_heartbeat_callbacks),
             step_cancel_signals: Arc::clone(&self.step_cancel_signals),
+            selector_cache: self.selector_cache.clone(),
         }
     }
 }
@@ -4427,6 +4630,42 @@ mod tests {
         assert!(registry_nested_codemod_run(&resolved_package(None), &dependency_chain).is_none());
     }
 
+    #[test]
+    fn ai_step_changes_replay_onto_a_fresh_copy() {
+        let recorded = tempfile::tempdir().expect("recorded dir");
+        std::fs::create_dir_all(recorded.path().join("src")).unwrap();
+        std::fs::create_dir_all(recorded.path().join(".git")).unwrap();
+        std::fs::write(recorded.path().join("src/keep.ts"), "keep\n").unwrap();
+        std::fs::write(recorded.path().join("src/edit.ts"), "old\n").unwrap();
+        std::fs::write(recorded.path().join("remove.ts"), "gone\n").unwrap();
+        std::fs::write(recorded.path().join(".git/HEAD"), "ref\n").unwrap();
+        let replayed = tempfile::tempdir().expect("replayed dir");
+        for path in ["src/keep.ts", "src/edit.ts", "remove.ts"] {
+            std::fs::create_dir_all(replayed.path().join(path).parent().unwrap()).unwrap();
+            std::fs::copy(recorded.path().join(path), replayed.path().join(path)).unwrap();
+        }
+
+        let before = snapshot_text_files(recorded.path()).unwrap();
+        assert!(!before.contains_key(".git/HEAD"));
+        std::fs::write(recorded.path().join("src/edit.ts"), "new\n").unwrap();
+        std::fs::write(recorded.path().join("src/added.ts"), "added\n").unwrap();
+        std::fs::remove_file(recorded.path().join("remove.ts")).unwrap();
+        let changes = diff_snapshots(&before, snapshot_text_files(recorded.path()).unwrap());
+        assert_eq!(
+            changes.keys().collect::<Vec<_>>(),
+            ["remove.ts", "src/added.ts", "src/edit.ts"]
+        );
+
+        apply_ai_step_changes(replayed.path(), &changes).unwrap();
+        assert_eq!(
+            snapshot_text_files(replayed.path()).unwrap(),
+            snapshot_text_files(recorded.path()).unwrap()
+        );
+
+        let escaping: AiStepChanges = [("../outside.ts".to_string(), None)].into();
+        assert!(apply_ai_step_changes(replayed.path(), &escaping).is_err());
+    }
+
     #[test]
     fn nested_manifest_capabilities_merge_with_parent_capabilities() {
         let package_dir = tempfile::tempdir().expect("package dir");
@@ -4840,6 +5079,10 @@ export default function transform(ast) {
                     semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                         SemanticAnalysisMode::File,
                     )),
+                    affected_by: None,
+                    check_syntax: None,
+                    check_idempotent: None,
+                    include_components: None,
                 },
                 None,
                 None,
@@ -4871,6 +5114,105 @@ export default function transform(ast) {
         );
     }
 
+    #[tokio::test]
+    async fn js_ast_grep_reuses_cached_results_for_unchanged_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+        std::fs::create_dir_all(temp_path.join("codemod")).unwrap();
+        std::fs::create_dir_all(temp_path.join("src")).unwrap();
+        std::fs::write(
+            temp_path.join("codemod/codemod.js"),
+            r#"
+export default function transform(ast) {
+  return ast.root().text().replace("value = 1", "value = 2");
+}
+"#,
+        )
+        .unwrap();
+        let source_path = temp_path.join("src/app.js");
+        std::fs::write(&source_path, "const value = 1;\n").unwrap();
+
+        let cache_dir = temp_path.join("result-cache");
+        let config = WorkflowRunConfig {
+            execution: crate::config::WorkflowExecutionSettings {
+                bundle_path: temp_path.to_path_buf(),
+                target_path: temp_path.to_path_buf(),
+                result_cache_dir: Some(cache_dir.clone()),
+                ..WorkflowRunConfig::default().execution
+            },
+            ..WorkflowRunConfig::default()
+        };
+        let engine = Engine::with_state_adapter(Box::new(MockStateAdapter::new()), config);
+        let step = UseJSAstGrep {
+            js_file: "codemod/codemod.js".to_string(),
+            base_path: Some("src".to_string()),
+            include: Some(vec!["**/*.js".to_string()]),
+            exclude: None,
+            max_threads: None,
+            dry_run: None,
+            language: Some("javascript".to_string()),
+            capabilities: None,
+            semantic_analysis: None,
+            affected_by: None,
+            check_syntax: None,
+            check_idempotent: None,
+            include_components: None,
+        };
+        let capabilities_data = CapabilitiesData {
+            capabilities: None,
+            capabilities_security_callback: None,
+        };
+        let run_step = || {
+            engine.execute_js_ast_grep_step(
+                "test-node".to_string(),
+                None,
+                "test-step".to_string(),
+                "test-step".to_string(),
+                None,
+                None,
+                &step,
+                None,
+                None,
+                &capabilities_data,
+                &None,
+                None,
+                None,
+                &StructuredLogger::default(),
+                None,
+                None,
+                None,
+            )
+        };
+
+        run_step().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&source_path).unwrap(),
+            "const value = 2;\n"
+        );
+        let cache = codemod_sandbox::sandbox::engine::ResultCache::new(&cache_dir);
+        assert_eq!(cache.stats().unwrap().entries, 1);
+
+        // Rewrite the stored entry so a hit is observable, then restore the
+        // original input and run again.
+        let entry = walkdir::WalkDir::new(&cache_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .unwrap();
+        std::fs::write(
+            entry.path(),
+            r#"{"version":1,"result":{"status":"modified","content":"const cached = true;\n","renameTo":null}}"#,
+        )
+        .unwrap();
+        std::fs::write(&source_path, "const value = 1;\n").unwrap();
+
+        run_step().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&source_path).unwrap(),
+            "const cached = true;\n"
+        );
+    }
+
     #[test]
     #[serial]
     fn managed_git_mode_is_enabled_for_local_pull_request_nodes() {
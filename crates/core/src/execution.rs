@@ -1,5 +1,7 @@
 use codemod_llrt_capabilities::types::LlrtSupportedModules;
-use codemod_sandbox::sandbox::engine::language_data::get_extensions_for_language;
+use codemod_sandbox::sandbox::engine::language_data::{
+    get_extensions_for_language, COMPONENT_EXTENSIONS,
+};
 use ignore::{
     overrides::{Override, OverrideBuilder},
     WalkBuilder, WalkState,
@@ -68,6 +70,9 @@ pub struct CodemodExecutionConfig {
     pub threads: Option<usize>,
     /// Capabilities
     pub capabilities: Option<HashSet<LlrtSupportedModules>>,
+    /// Walk `.vue` and `.svelte` files for JavaScript and TypeScript when no
+    /// include globs are set
+    pub include_components: bool,
 }
 
 impl CodemodExecutionConfig {
@@ -343,7 +348,12 @@ impl CodemodExecutionConfig {
                 .is_some_and(|langs| !langs.is_empty())
         {
             for language in self.languages.as_ref().unwrap() {
-                for extension in get_extensions_for_language(language.parse().unwrap()) {
+                for extension in get_extensions_for_language(language.parse().unwrap())
+                    .into_iter()
+                    .filter(|extension| {
+                        self.include_components || !COMPONENT_EXTENSIONS.contains(extension)
+                    })
+                {
                     builder
                         .add(format!("**/*{extension}").as_str())
                         .map_err(|e| format!("Failed to add language pattern: {e}"))?;
@@ -424,6 +434,7 @@ mod tests {
             languages: None,
             threads: Some(1),
             capabilities: Some(HashSet::new()),
+            include_components: true,
         };
 
         let mut files = config.collect_files();
@@ -433,6 +444,39 @@ mod tests {
         fs::remove_dir_all(root).unwrap();
     }
 
+    #[test]
+    fn collect_files_skips_components_when_opted_out() {
+        let root = temp_dir();
+        let script = root.join("a.ts");
+        let component = root.join("b.vue");
+        fs::write(&script, "a").unwrap();
+        fs::write(&component, "<script>b</script>").unwrap();
+
+        let mut config = CodemodExecutionConfig {
+            pre_run_callback: None,
+            progress_callback: Arc::new(None),
+            target_path: Some(root.clone()),
+            base_path: None,
+            include_globs: None,
+            explicit_files: None,
+            exclude_globs: None,
+            dry_run: false,
+            languages: Some(vec!["typescript".to_string()]),
+            threads: Some(1),
+            capabilities: Some(HashSet::new()),
+            include_components: true,
+        };
+
+        let mut files = config.collect_files();
+        files.sort();
+        assert_eq!(files, vec![script.clone(), component]);
+
+        config.include_components = false;
+        assert_eq!(config.collect_files(), vec![script]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
     #[test]
     fn execute_with_explicit_include_files_processes_only_targeted_files() {
         let root = temp_dir();
@@ -457,6 +501,7 @@ mod tests {
             languages: None,
             threads: Some(1),
             capabilities: Some(HashSet::new()),
+            include_components: true,
         };
 
         let seen = Arc::new(Mutex::new(Vec::new()));
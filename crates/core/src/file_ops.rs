@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use codemod_sandbox::sandbox::engine::ProjectEdit;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 
 /// File write operation for batched async I/O
@@ -50,3 +52,251 @@ impl AsyncFileWriter {
             .map_err(|_| std::io::Error::other("File write operation canceled"))?
     }
 }
+
+/// Undo record for a single applied project edit.
+enum ProjectEditUndo {
+    Restore {
+        path: PathBuf,
+        content: Vec<u8>,
+    },
+    Remove {
+        path: PathBuf,
+    },
+    /// A directory created for a new file
+    RemoveDir {
+        path: PathBuf,
+    },
+}
+
+/// Apply a batch of `transformProject` edits all-or-nothing.
+///
+/// Edits are applied in order. If any edit fails, every edit applied so far
+/// is rolled back before the error is returned, leaving the tree as it was.
+pub fn apply_project_edits(edits: &[ProjectEdit]) -> std::io::Result<()> {
+    let mut undo_log = Vec::with_capacity(edits.len());
+    for edit in edits {
+        if let Err(error) = apply_project_edit(edit, &mut undo_log) {
+            rollback_project_edits(undo_log);
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+fn apply_project_edit(
+    edit: &ProjectEdit,
+    undo_log: &mut Vec<ProjectEditUndo>,
+) -> std::io::Result<()> {
+    match edit {
+        ProjectEdit::Edit { path, content } => {
+            let original = std::fs::read(path)?;
+            // Logged first, so a write that fails partway is restored too
+            undo_log.push(ProjectEditUndo::Restore {
+                path: path.clone(),
+                content: original,
+            });
+            std::fs::write(path, content)?;
+        }
+        ProjectEdit::Create { path, content } => {
+            if let Some(parent) = path.parent() {
+                create_missing_dirs(parent, undo_log)?;
+            }
+            write_new_file(path, content.as_bytes())?;
+            undo_log.push(ProjectEditUndo::Remove { path: path.clone() });
+        }
+        ProjectEdit::Rename { from, to, content } => {
+            let original = std::fs::read(from)?;
+            if let Some(parent) = to.parent() {
+                create_missing_dirs(parent, undo_log)?;
+            }
+            match content {
+                Some(content) => write_new_file(to, content.as_bytes())?,
+                None => write_new_file(to, &original)?,
+            }
+            undo_log.push(ProjectEditUndo::Remove { path: to.clone() });
+            std::fs::remove_file(from)?;
+            undo_log.push(ProjectEditUndo::Restore {
+                path: from.clone(),
+                content: original,
+            });
+        }
+        ProjectEdit::Delete { path } => {
+            let original = std::fs::read(path)?;
+            std::fs::remove_file(path)?;
+            undo_log.push(ProjectEditUndo::Restore {
+                path: path.clone(),
+                content: original,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Create `dir` and its missing ancestors, logging each one created so a
+/// rollback removes it again.
+fn create_missing_dirs(dir: &Path, undo_log: &mut Vec<ProjectEditUndo>) -> std::io::Result<()> {
+    let mut missing = dir
+        .ancestors()
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .collect::<Vec<_>>();
+    missing.reverse();
+    for dir in missing {
+        match std::fs::create_dir(dir) {
+            Ok(()) => undo_log.push(ProjectEditUndo::RemoveDir {
+                path: dir.to_path_buf(),
+            }),
+            // Created concurrently; not ours to remove
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+/// Write `content` to a file that must not exist yet.
+///
+/// The file is opened with `create_new`, so a file that appeared after the
+/// edit was validated fails the batch instead of being overwritten and then
+/// removed by a rollback. A file this call created is removed again if the
+/// write fails.
+fn write_new_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(content).inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })
+}
+
+fn rollback_project_edits(undo_log: Vec<ProjectEditUndo>) {
+    for undo in undo_log.into_iter().rev() {
+        let _ = match undo {
+            ProjectEditUndo::Restore { path, content } => std::fs::write(path, content),
+            ProjectEditUndo::Remove { path } => std::fs::remove_file(path),
+            // Only removes the directory if the rollback emptied it
+            ProjectEditUndo::RemoveDir { path } => std::fs::remove_dir(path),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn apply_project_edits_applies_every_edit_kind() {
+        let dir = tempdir().unwrap();
+        let edited = dir.path().join("edited.ts");
+        let renamed = dir.path().join("renamed.ts");
+        let deleted = dir.path().join("deleted.ts");
+        std::fs::write(&edited, "old").unwrap();
+        std::fs::write(&renamed, "move me").unwrap();
+        std::fs::write(&deleted, "bye").unwrap();
+
+        apply_project_edits(&[
+            ProjectEdit::Edit {
+                path: edited.clone(),
+                content: "new".to_string(),
+            },
+            ProjectEdit::Create {
+                path: dir.path().join("nested/created.ts"),
+                content: "created".to_string(),
+            },
+            ProjectEdit::Rename {
+                from: renamed.clone(),
+                to: dir.path().join("moved.ts"),
+                content: None,
+            },
+            ProjectEdit::Delete {
+                path: deleted.clone(),
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "new");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("nested/created.ts")).unwrap(),
+            "created"
+        );
+        assert!(!renamed.exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("moved.ts")).unwrap(),
+            "move me"
+        );
+        assert!(!deleted.exists());
+    }
+
+    #[test]
+    fn apply_project_edits_rolls_back_on_failure() {
+        let dir = tempdir().unwrap();
+        let edited = dir.path().join("edited.ts");
+        std::fs::write(&edited, "old").unwrap();
+
+        let result = apply_project_edits(&[
+            ProjectEdit::Edit {
+                path: edited.clone(),
+                content: "new".to_string(),
+            },
+            ProjectEdit::Create {
+                path: dir.path().join("created.ts"),
+                content: "created".to_string(),
+            },
+            ProjectEdit::Delete {
+                path: dir.path().join("missing.ts"),
+            },
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "old");
+        assert!(!dir.path().join("created.ts").exists());
+    }
+
+    #[test]
+    fn apply_project_edits_removes_created_directories_on_rollback() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("existing")).unwrap();
+
+        let result = apply_project_edits(&[
+            ProjectEdit::Create {
+                path: dir.path().join("existing/new/deeper/created.ts"),
+                content: "created".to_string(),
+            },
+            ProjectEdit::Delete {
+                path: dir.path().join("missing.ts"),
+            },
+        ]);
+
+        assert!(result.is_err());
+        assert!(dir.path().join("existing").is_dir());
+        assert!(!dir.path().join("existing/new").exists());
+    }
+
+    #[test]
+    fn apply_project_edits_never_overwrites_existing_files_on_create() {
+        let dir = tempdir().unwrap();
+        let existing = dir.path().join("existing.ts");
+        let edited = dir.path().join("edited.ts");
+        std::fs::write(&existing, "keep me").unwrap();
+        std::fs::write(&edited, "old").unwrap();
+
+        let result = apply_project_edits(&[
+            ProjectEdit::Edit {
+                path: edited.clone(),
+                content: "new".to_string(),
+            },
+            ProjectEdit::Create {
+                path: existing.clone(),
+                content: "clobbered".to_string(),
+            },
+        ]);
+
+        assert_eq!(
+            result.unwrap_err().kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "keep me");
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "old");
+    }
+}
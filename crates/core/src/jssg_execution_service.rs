@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -9,16 +9,19 @@ use std::{
 
 use ast_grep_config::RuleConfig;
 use butterflow_models::{
+    resolve_string_list,
     step::{SemanticAnalysisConfig, SemanticAnalysisMode, UseJSAstGrep},
     DiffOperation, FieldDiff, Result, StateDiff, TaskExpressionContext,
 };
 use chrono::Utc;
-use codemod_llrt_capabilities::types::LlrtSupportedModules;
 use codemod_sandbox::sandbox::{
     engine::{
-        codemod_lang::CodemodLang, execution_engine::execute_codemod_with_quickjs,
-        extract_selector_with_quickjs, CodemodOutput, ExecutionResult, JssgExecutionOptions,
-        SelectorEngineOptions,
+        codemod_lang::CodemodLang, execute_project_codemod_with_quickjs,
+        execution_engine::execute_codemod_with_quickjs, extract_selector_with_quickjs,
+        introduced_syntax_issues, may_export_transform_project, summarize_syntax_issues,
+        syntax_check_language, CachedResult, CodemodFingerprint, CodemodOutput, ExecutionResult,
+        JssgExecutionOptions, ModifiedResult, ProjectEdit, ProjectExecutionOptions, ResultCache,
+        ResultCacheKey, SelectorCacheKey, SelectorEngineOptions,
     },
     errors::{ExecutionError as SandboxExecutionError, RuntimeError as SandboxRuntimeError},
     resolvers::OxcResolver,
@@ -27,8 +30,8 @@ use codemod_sandbox::sandbox::{
 use codemod_sandbox::{
     utils::project_discovery::find_tsconfig, MetricsContext, SharedStateContext,
 };
-use language_core::SemanticProvider;
-use semantic_factory::LazySemanticProvider;
+use language_core::{ProviderMode, SemanticProvider};
+use semantic_factory::{LazySemanticProvider, SemanticConfig};
 use tokio::sync::{mpsc, Notify};
 use uuid::Uuid;
 
@@ -42,6 +45,7 @@ use crate::{
         resolve_optional_glob_list, CapabilitiesData, Engine, StepPhase, StepProgressState,
     },
     execution::{CodemodExecutionConfig, PreRunCallback},
+    file_ops::apply_project_edits,
     progress_output::{
         append_buffered_diagnostic, append_buffered_log, flush_buffered_execution_output,
         BufferedExecutionOutput,
@@ -228,6 +232,7 @@ impl<'a> JssgExecutionService<'a> {
                 .unwrap_or("typescript".to_string())]),
             threads: request.js_ast_grep.max_threads,
             capabilities: effective_capabilities.clone(),
+            include_components: request.js_ast_grep.include_components.unwrap_or(true),
         };
 
         if let Some(pre_run_callback) = &config.pre_run_callback {
@@ -246,35 +251,73 @@ impl<'a> JssgExecutionService<'a> {
             })?
         };
 
-        let selector_config = match extract_selector_with_quickjs(SelectorEngineOptions {
-            script_path: &js_file_path,
+        let selector_cache_key = SelectorCacheKey::new(
+            &js_file_path,
             language,
-            resolver: Arc::clone(&resolver),
-            capabilities: effective_capabilities,
-            target_directory: Some(&target_path),
-        })
-        .await
-        {
-            Ok(selector_config) => selector_config,
-            Err(e) => {
-                if Self::is_runtime_initialization_failure(&e) {
-                    return Err(Error::StepExecution(format!(
-                        "Failed to initialize js-ast-grep codemod: {e}"
-                    )));
-                }
+            request.params.as_ref(),
+            request.matrix_input.as_ref(),
+        );
+        let selector_config =
+            if let Some(cached) = self.engine.selector_cache().get(&selector_cache_key) {
+                cached
+            } else {
+                match extract_selector_with_quickjs(SelectorEngineOptions {
+                    script_path: &js_file_path,
+                    language,
+                    resolver: Arc::clone(&resolver),
+                    capabilities: effective_capabilities,
+                    target_directory: Some(&target_path),
+                    params: request.params.clone(),
+                    matrix_values: request.matrix_input.clone(),
+                })
+                .await
+                {
+                    Ok(selector_config) => {
+                        let selector_config: Option<Arc<RuleConfig<CodemodLang>>> =
+                            selector_config.map(Arc::from);
+                        self.engine
+                            .selector_cache()
+                            .insert(selector_cache_key, selector_config.clone());
+                        selector_config
+                    }
+                    Err(e) => {
+                        if Self::is_runtime_initialization_failure(&e) {
+                            return Err(Error::StepExecution(format!(
+                                "Failed to initialize js-ast-grep codemod: {e}"
+                            )));
+                        }
 
-                let message = format!("Failed to extract js-ast-grep selector: {e}");
-                if let Some(task_id) = task_log_task_id {
-                    let _ = self.engine.append_task_log(task_id, &message).await;
+                        let message = format!("Failed to extract js-ast-grep selector: {e}");
+                        if let Some(task_id) = task_log_task_id {
+                            let _ = self.engine.append_task_log(task_id, &message).await;
+                        }
+                        slog!(request.logger, warn, "{}", message);
+                        None
+                    }
                 }
-                slog!(request.logger, warn, "{}", message);
-                None
-            }
-        };
+            };
 
         let semantic_provider = self
-            .build_semantic_provider(request.js_ast_grep, &target_path)
+            .build_semantic_provider(request.js_ast_grep, &target_path, effective_bundle_path)
+            .await?;
+        if let Some(affected_by) = &request.js_ast_grep.affected_by {
+            let affected_by = resolve_string_list(
+                affected_by,
+                resolved_params_ref,
+                resolved_state_ref,
+                request.matrix_input.as_ref(),
+                None,
+                request.task_expr_ctx,
+            )?;
+            self.restrict_to_affected(
+                &mut config,
+                &target_path,
+                &affected_by,
+                semantic_provider.as_ref(),
+                task_log_task_id,
+            )
             .await?;
+        }
         self.pre_index_workspace_semantics(
             semantic_provider.as_ref(),
             &config,
@@ -283,6 +326,25 @@ impl<'a> JssgExecutionService<'a> {
         )
         .await;
 
+        if Self::may_export_transform_project(&js_file_path) {
+            let handled = self
+                .execute_project(
+                    &request,
+                    task_log_task_id,
+                    metrics_context.clone(),
+                    &js_file_path,
+                    &target_path,
+                    Arc::clone(&resolver),
+                    language,
+                    semantic_provider.clone(),
+                    &config,
+                )
+                .await?;
+            if handled {
+                return Ok(());
+            }
+        }
+
         self.execute_runtime(
             request,
             task_log_task_id,
@@ -291,7 +353,7 @@ impl<'a> JssgExecutionService<'a> {
             target_path,
             resolver,
             language,
-            selector_config.map(Arc::from),
+            selector_config,
             semantic_provider,
             config,
         )
@@ -302,34 +364,83 @@ impl<'a> JssgExecutionService<'a> {
         &self,
         js_ast_grep: &UseJSAstGrep,
         target_path: &Path,
+        bundle_path: &Path,
     ) -> Result<Option<Arc<dyn SemanticProvider>>> {
-        Ok(match &js_ast_grep.semantic_analysis {
+        let config = match &js_ast_grep.semantic_analysis {
             Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)) => {
-                Some(Arc::new(LazySemanticProvider::file_scope()))
+                SemanticConfig::file_scope()
             }
-            Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::Workspace)) => Some(Arc::new(
-                LazySemanticProvider::workspace_scope(target_path.to_path_buf()),
-            )),
-            Some(SemanticAnalysisConfig::Detailed(detailed)) => match detailed.mode {
-                SemanticAnalysisMode::File => Some(Arc::new(LazySemanticProvider::file_scope())),
-                SemanticAnalysisMode::Workspace => {
-                    let root = detailed
-                        .root
-                        .as_ref()
-                        .map(|root| {
-                            crate::utils::resolve_workflow_path_within_root(
-                                target_path,
-                                root,
-                                "js-ast-grep.semantic_analysis.root",
-                            )
-                        })
-                        .transpose()?
-                        .unwrap_or_else(|| target_path.to_path_buf());
-                    Some(Arc::new(LazySemanticProvider::workspace_scope(root)))
+            Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::Workspace)) => {
+                SemanticConfig::workspace_scope(target_path.to_path_buf())
+            }
+            Some(SemanticAnalysisConfig::Detailed(detailed)) => {
+                let config = match detailed.mode {
+                    SemanticAnalysisMode::File => SemanticConfig::file_scope(),
+                    SemanticAnalysisMode::Workspace => {
+                        let root = detailed
+                            .root
+                            .as_ref()
+                            .map(|root| {
+                                crate::utils::resolve_workflow_path_within_root(
+                                    target_path,
+                                    root,
+                                    "js-ast-grep.semantic_analysis.root",
+                                )
+                            })
+                            .transpose()?
+                            .unwrap_or_else(|| target_path.to_path_buf());
+                        SemanticConfig::workspace_scope(root)
+                    }
+                };
+                match &detailed.queries {
+                    Some(queries) => {
+                        config.with_query_dir(crate::utils::resolve_workflow_path_within_root(
+                            bundle_path,
+                            queries,
+                            "js-ast-grep.semantic_analysis.queries",
+                        )?)
+                    }
+                    None => config,
                 }
-            },
-            None => None,
-        })
+            }
+            None => return Ok(None),
+        };
+        Ok(Some(Arc::new(LazySemanticProvider::new(
+            crate::utils::with_semantic_index(config),
+        ))))
+    }
+
+    /// Limit `config` to the files affected by `affected_by`: the changed
+    /// files plus everything that transitively imports them.
+    async fn restrict_to_affected(
+        &self,
+        config: &mut CodemodExecutionConfig,
+        target_path: &Path,
+        affected_by: &[String],
+        provider: Option<&Arc<dyn SemanticProvider>>,
+        task_log_task_id: Option<Uuid>,
+    ) -> Result<()> {
+        let selection = crate::affected::select_affected(
+            target_path,
+            affected_by,
+            config.collect_files(),
+            provider.map(|provider| provider.as_ref()),
+        )?;
+        if let Some(task_id) = task_log_task_id {
+            let _ = self
+                .engine
+                .append_task_log(
+                    task_id,
+                    format!(
+                        "Limiting run to {} file(s) affected by {} changed file(s)",
+                        selection.files.len(),
+                        selection.changed
+                    ),
+                )
+                .await;
+        }
+        config.explicit_files = Some(selection.files);
+        Ok(())
     }
 
     async fn pre_index_workspace_semantics(
@@ -342,7 +453,7 @@ impl<'a> JssgExecutionService<'a> {
         let Some(provider) = provider else {
             return;
         };
-        if provider.mode() != language_core::ProviderMode::WorkspaceScope {
+        if provider.mode() != ProviderMode::WorkspaceScope {
             return;
         }
 
@@ -384,6 +495,282 @@ impl<'a> JssgExecutionService<'a> {
         }
     }
 
+    /// Cheap pre-check so per-file codemods don't pay for an extra module
+    /// evaluation: only modules whose exports may include `transformProject`.
+    fn may_export_transform_project(js_file_path: &Path) -> bool {
+        std::fs::read_to_string(js_file_path)
+            .map(|source| may_export_transform_project(js_file_path, &source))
+            .unwrap_or(false)
+    }
+
+    /// Run the codemod's `transformProject` export, if it has one, and apply
+    /// the returned edits as a single batch. Returns `false` when the codemod
+    /// only exports a per-file transform.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_project(
+        &self,
+        request: &JssgExecutionRequest<'_>,
+        task_log_task_id: Option<Uuid>,
+        metrics_context: MetricsContext,
+        js_file_path: &Path,
+        target_path: &Path,
+        resolver: Arc<OxcResolver>,
+        language: CodemodLang,
+        semantic_provider: Option<Arc<dyn SemanticProvider>>,
+        config: &CodemodExecutionConfig,
+    ) -> Result<bool> {
+        let shared_state_context = if let Some(state) = request.initial_state {
+            SharedStateContext::with_initial_state(state.clone())
+        } else {
+            SharedStateContext::new()
+        };
+        let llm_request_handler = self
+            .engine
+            .llm_request_handler_for(config.capabilities.as_ref());
+        let logger = request.logger.clone();
+        let workflow_run_id = request.workflow_run_id;
+        let runtime_event_callback: RuntimeEventCallback = Arc::new(move |event| {
+            let Some(message) = format_runtime_event_log(&event) else {
+                return;
+            };
+            match event.kind {
+                RuntimeEventKind::Warn => {
+                    slog!(logger, warn, "{}", message);
+                }
+                _ => {
+                    slog!(logger, info, "{}", message);
+                }
+            }
+            if let (Some(task_id), Some(run_id)) = (task_log_task_id, workflow_run_id) {
+                publish_event(
+                    run_id,
+                    WorkflowEvent::TaskLogAppended {
+                        workflow_run_id: run_id,
+                        task_id,
+                        line: message,
+                        at: Utc::now(),
+                    },
+                );
+            }
+        });
+
+        let files: Vec<PathBuf> = config
+            .collect_files()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+        let cancellation_flag = Arc::new(AtomicBool::new(false));
+        if let Some(task_id) = task_log_task_id {
+            self.engine
+                .register_step_cancel_signal(task_id, Arc::clone(&cancellation_flag));
+        }
+
+        std::env::set_var("CODEMOD_STEP_ID", &request.step_id);
+        let output = execute_project_codemod_with_quickjs(ProjectExecutionOptions {
+            script_path: js_file_path,
+            resolver,
+            language,
+            files,
+            params: request.params.clone(),
+            matrix_values: request.matrix_input.clone(),
+            capabilities: config.capabilities.clone(),
+            semantic_provider: semantic_provider.clone(),
+            metrics_context: Some(metrics_context),
+            llm_request_handler,
+            shared_state_context: Some(shared_state_context.clone()),
+            runtime_event_callback: Some(runtime_event_callback),
+            cancellation_flag: Some(Arc::clone(&cancellation_flag)),
+            dry_run: config.dry_run,
+            target_directory: target_path,
+        })
+        .await;
+
+        if let Some(task_id) = task_log_task_id {
+            self.engine.unregister_step_cancel_signal(task_id);
+        }
+        if cancellation_flag.load(Ordering::Acquire) {
+            return Err(Error::Runtime("Canceled by user".to_string()));
+        }
+
+        let output = match output {
+            Ok(Some(output)) => output,
+            Ok(None) => return Ok(false),
+            Err(SandboxExecutionError::RuntimeHook { source }) => {
+                return Err(Error::StepExecution(format_runtime_failure_message(
+                    &source,
+                )));
+            }
+            Err(e) => {
+                let message = format!("Failed to execute transformProject: {e}");
+                if let Some(task_id) = task_log_task_id {
+                    let _ = self.engine.append_task_log(task_id, &message).await;
+                }
+                self.engine
+                    .execution_stats
+                    .files_with_errors
+                    .fetch_add(1, Ordering::Relaxed);
+                return Err(Error::StepExecution(message));
+            }
+        };
+
+        self.report_project_edits(request, &output.edits);
+
+        if config.dry_run {
+            slog!(
+                request.logger,
+                debug,
+                "Would apply {} project edit(s) (dry run)",
+                output.edits.len()
+            );
+        } else {
+            apply_project_edits(&output.edits).map_err(|e| {
+                Error::StepExecution(format!(
+                    "Failed to apply transformProject edits, all changes were rolled back: {e}"
+                ))
+            })?;
+
+            for edit in &output.edits {
+                let written = match edit {
+                    ProjectEdit::Edit { path, content } | ProjectEdit::Create { path, content } => {
+                        Some((path, content.as_str()))
+                    }
+                    ProjectEdit::Rename {
+                        to,
+                        content: Some(content),
+                        ..
+                    } => Some((to, content.as_str())),
+                    ProjectEdit::Rename { .. } | ProjectEdit::Delete { .. } => None,
+                };
+                if let (Some((path, content)), Some(provider)) = (written, &semantic_provider) {
+                    let _ = provider.notify_file_processed(path, content);
+                }
+            }
+        }
+
+        if let Some(collector) = &request.modified_files_collector {
+            let mut collector = collector
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            for edit in &output.edits {
+                collector.extend(edit.paths().into_iter().map(Path::to_path_buf));
+            }
+        }
+        // A file touched by several edits counts once; a rename counts as its
+        // source file.
+        let modified_files: HashSet<&Path> = output
+            .edits
+            .iter()
+            .filter_map(|edit| edit.paths().first().copied())
+            .collect();
+        self.engine
+            .execution_stats
+            .files_modified
+            .fetch_add(modified_files.len(), Ordering::Relaxed);
+
+        self.persist_shared_state(workflow_run_id, &shared_state_context, config.dry_run)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Feed project edits through the same dry-run callback used for per-file
+    /// changes so diffs and reports include them. Renames are reported as a
+    /// removal of the old path and an addition at the new one.
+    fn report_project_edits(&self, request: &JssgExecutionRequest<'_>, edits: &[ProjectEdit]) {
+        let Some(callback) = &self.engine.workflow_run_config().output.dry_run_callback else {
+            return;
+        };
+        let report = |file_path: &Path, original_content: String, new_content: String| {
+            callback(DryRunChange {
+                file_path: file_path.to_path_buf(),
+                original_content,
+                new_content,
+                step_id: Some(request.step_id.clone()),
+                step_name: Some(request.step_name.clone()),
+                parent_step_id: request.report_step_id.clone(),
+                parent_step_name: request.report_step_name.clone(),
+            });
+        };
+        for edit in edits {
+            match edit {
+                ProjectEdit::Edit { path, content } => report(
+                    path,
+                    std::fs::read_to_string(path).unwrap_or_default(),
+                    content.clone(),
+                ),
+                ProjectEdit::Create { path, content } => {
+                    report(path, String::new(), content.clone())
+                }
+                ProjectEdit::Rename { from, to, content } => {
+                    let original = std::fs::read_to_string(from).unwrap_or_default();
+                    report(from, original.clone(), String::new());
+                    report(to, String::new(), content.clone().unwrap_or(original));
+                }
+                ProjectEdit::Delete { path } => report(
+                    path,
+                    std::fs::read_to_string(path).unwrap_or_default(),
+                    String::new(),
+                ),
+            }
+        }
+    }
+
+    async fn persist_shared_state(
+        &self,
+        workflow_run_id: Option<Uuid>,
+        shared_state_context: &SharedStateContext,
+        dry_run: bool,
+    ) -> Result<()> {
+        let Some(wf_run_id) = workflow_run_id else {
+            return Ok(());
+        };
+        if self
+            .engine
+            .workflow_run_config()
+            .execution
+            .skip_state_writes
+            || dry_run
+        {
+            return Ok(());
+        }
+
+        let persistable = shared_state_context.get_persistable();
+        let removals = shared_state_context.get_removals();
+        if persistable.is_empty() && removals.is_empty() {
+            return Ok(());
+        }
+
+        let mut fields = HashMap::new();
+        for (key, value) in persistable {
+            fields.insert(
+                key,
+                FieldDiff {
+                    operation: DiffOperation::Update,
+                    value: Some(value),
+                },
+            );
+        }
+        for key in removals {
+            fields.insert(
+                key,
+                FieldDiff {
+                    operation: DiffOperation::Remove,
+                    value: None,
+                },
+            );
+        }
+
+        self.engine
+            .state_adapter()
+            .lock()
+            .await
+            .apply_state_diff(&StateDiff {
+                workflow_run_id: wf_run_id,
+                fields,
+            })
+            .await
+    }
+
     fn signal_progress(progress_tx: &mpsc::UnboundedSender<()>) {
         let _ = progress_tx.send(());
     }
@@ -419,6 +806,79 @@ impl<'a> JssgExecutionService<'a> {
             .any(|line| line.starts_with("at ") && line.contains(script_path.as_ref()))
     }
 
+    /// Open the per-file result cache when this step allows it. Steps with
+    /// capabilities or a semantic provider depend on more than the file being
+    /// transformed (types and references come from the rest of the
+    /// workspace), so they always execute.
+    fn result_cache_for(
+        &self,
+        js_file_path: &Path,
+        config: &CodemodExecutionConfig,
+        semantic_provider: Option<&Arc<dyn SemanticProvider>>,
+        logger: &StructuredLogger,
+    ) -> Option<(ResultCache, CodemodFingerprint)> {
+        let cache_dir = self
+            .engine
+            .workflow_run_config()
+            .execution
+            .result_cache_dir
+            .as_ref()?;
+        if config
+            .capabilities
+            .as_ref()
+            .is_some_and(|capabilities| !capabilities.is_empty())
+            || semantic_provider.is_some()
+        {
+            return None;
+        }
+
+        match CodemodFingerprint::for_script(js_file_path) {
+            Ok(fingerprint) if fingerprint.cacheable => {
+                Some((ResultCache::new(cache_dir), fingerprint))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                slog!(
+                    logger,
+                    debug,
+                    "Result cache disabled, failed to fingerprint {}: {}",
+                    js_file_path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Whether the step's check_idempotent guard has to be skipped for this
+    /// codemod. The guard's second run goes without an LLM handler, so it
+    /// doesn't pay for another call, and codemods that may call the LLM
+    /// can't be rerun faithfully.
+    fn skips_idempotency_check(js_file_path: &Path, logger: &StructuredLogger) -> bool {
+        match CodemodFingerprint::for_script(js_file_path) {
+            Ok(fingerprint) if fingerprint.may_call_llm => {
+                slog!(
+                    logger,
+                    warn,
+                    "Skipping check_idempotent for {}: the codemod may call the LLM, which a second run can't repeat",
+                    js_file_path.display()
+                );
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                slog!(
+                    logger,
+                    debug,
+                    "Failed to inspect {} for LLM imports: {}",
+                    js_file_path.display(),
+                    e
+                );
+                false
+            }
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn execute_runtime(
         &self,
@@ -456,11 +916,9 @@ impl<'a> JssgExecutionService<'a> {
             SharedStateContext::new()
         };
         let metrics_context_clone = metrics_context.clone();
-        let llm_request_handler = config
-            .capabilities
-            .as_ref()
-            .is_some_and(|capabilities| capabilities.contains(&LlrtSupportedModules::Fetch))
-            .then(|| self.engine.llm_request_handler());
+        let llm_request_handler = self
+            .engine
+            .llm_request_handler_for(config.capabilities.as_ref());
         let shared_state_context_clone = shared_state_context.clone();
         let logger = request.logger.clone();
         let modified_files_collector_clone = request.modified_files_collector.clone();
@@ -474,6 +932,12 @@ impl<'a> JssgExecutionService<'a> {
         let idle_notify = Arc::new(Notify::new());
         let idle_failure_message = Arc::new(std::sync::Mutex::new(None::<String>));
         let has_selector = selector_config.is_some();
+        let result_cache = self.result_cache_for(
+            &js_file_path,
+            &config,
+            semantic_provider.as_ref(),
+            request.logger,
+        );
         let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<()>();
 
         let deferred_deletions: Arc<std::sync::Mutex<Vec<PathBuf>>> =
@@ -578,6 +1042,9 @@ impl<'a> JssgExecutionService<'a> {
         let params = request.params;
         let matrix_input = request.matrix_input;
         let workflow_run_id = request.workflow_run_id;
+        let check_syntax = request.js_ast_grep.check_syntax.unwrap_or(false);
+        let check_idempotent = request.js_ast_grep.check_idempotent.unwrap_or(false)
+            && !Self::skips_idempotency_check(&js_file_path, request.logger);
         let buffered_execution_output_for_closure = Arc::clone(&buffered_execution_output);
         let attempted_file_count_for_closure = Arc::clone(&attempted_file_count);
         let succeeded_file_count_for_closure = Arc::clone(&succeeded_file_count);
@@ -763,64 +1230,110 @@ impl<'a> JssgExecutionService<'a> {
                                 }
                             }
                         });
-                    let execution_result = block_on_runtime_handle(&runtime_handle, async {
-                        let local = tokio::task::LocalSet::new();
-                        let file_path_owned = file_path.to_path_buf();
-                        let content_owned = content.clone();
-                        let js_file_path_owned = js_file_path_clone.clone();
-                        let resolver_owned = resolver_clone.clone();
-                        let selector_config_owned = selector_config.clone();
-                        let params_owned = params.clone();
-                        let matrix_input_owned = matrix_input.clone();
-                        let capabilities_owned = config.capabilities.clone();
-                        let semantic_provider_owned = semantic_provider.clone();
-                        let metrics_context_owned = metrics_context_clone.clone();
-                        let llm_request_handler_owned = llm_request_handler.clone();
-                        let shared_state_context_owned = shared_state_context_clone.clone();
-                        let target_path_owned = target_path.clone();
-                        let idle_timed_out = Arc::clone(&idle_timed_out_for_closure);
-                        let idle_notify = Arc::clone(&idle_notify_for_closure);
-                        let idle_failure_message = Arc::clone(&idle_failure_message_for_closure);
-
-                        local
-                            .run_until(async move {
-                                let execution_task = tokio::task::spawn_local(async move {
-                                    execute_codemod_with_quickjs(JssgExecutionOptions {
-                                        script_path: &js_file_path_owned,
-                                        resolver: resolver_owned,
-                                        language,
-                                        file_path: &file_path_owned,
-                                        content: &content_owned,
-                                        selector_config: selector_config_owned,
-                                        params: params_owned,
-                                        matrix_values: matrix_input_owned,
-                                        capabilities: capabilities_owned,
-                                        semantic_provider: semantic_provider_owned,
-                                        metrics_context: Some(metrics_context_owned),
-                                        llm_request_handler: llm_request_handler_owned,
-                                        shared_state_context: Some(shared_state_context_owned),
-                                        runtime_event_callback: Some(runtime_event_callback),
-                                        cancellation_flag: Some(cancellation_flag_for_execution),
-                                        test_mode: false,
-                                        dry_run,
-                                        target_directory: &target_path_owned,
-                                    })
+                    let result_cache_key = result_cache.as_ref().map(|(_, fingerprint)| {
+                        ResultCacheKey::new(
+                            fingerprint,
+                            params.as_ref(),
+                            matrix_input.as_ref(),
+                            language,
+                            file_path,
+                            &content,
+                        )
+                    });
+                    let cached_output = result_cache
+                        .as_ref()
+                        .zip(result_cache_key.as_ref())
+                        .and_then(|((cache, _), key)| cache.get(key))
+                        .map(CachedResult::into_output);
+                    let cache_hit = cached_output.is_some();
+                    if cache_hit {
+                        slog!(logger, debug, "Using cached result for {}", relative_path);
+                    }
+                    let execution_result = if let Some(output) = cached_output {
+                        Ok(Ok(output))
+                    } else {
+                        block_on_runtime_handle(&runtime_handle, async {
+                            let local = tokio::task::LocalSet::new();
+                            let file_path_owned = file_path.to_path_buf();
+                            let content_owned = content.clone();
+                            let js_file_path_owned = js_file_path_clone.clone();
+                            let resolver_owned = resolver_clone.clone();
+                            let selector_config_owned = selector_config.clone();
+                            let params_owned = params.clone();
+                            let matrix_input_owned = matrix_input.clone();
+                            let capabilities_owned = config.capabilities.clone();
+                            let semantic_provider_owned = semantic_provider.clone();
+                            let metrics_context_owned = metrics_context_clone.clone();
+                            let llm_request_handler_owned = llm_request_handler.clone();
+                            let shared_state_context_owned = shared_state_context_clone.clone();
+                            let target_path_owned = target_path.clone();
+                            let idle_timed_out = Arc::clone(&idle_timed_out_for_closure);
+                            let idle_notify = Arc::clone(&idle_notify_for_closure);
+                            let idle_failure_message =
+                                Arc::clone(&idle_failure_message_for_closure);
+
+                            local
+                                .run_until(async move {
+                                    let execution_task = tokio::task::spawn_local(async move {
+                                        execute_codemod_with_quickjs(JssgExecutionOptions {
+                                            script_path: &js_file_path_owned,
+                                            resolver: resolver_owned,
+                                            language,
+                                            file_path: &file_path_owned,
+                                            content: &content_owned,
+                                            selector_config: selector_config_owned,
+                                            params: params_owned,
+                                            matrix_values: matrix_input_owned,
+                                            capabilities: capabilities_owned,
+                                            semantic_provider: semantic_provider_owned,
+                                            metrics_context: Some(metrics_context_owned),
+                                            llm_request_handler: llm_request_handler_owned,
+                                            shared_state_context: Some(shared_state_context_owned),
+                                            runtime_event_callback: Some(runtime_event_callback),
+                                            cancellation_flag: Some(
+                                                cancellation_flag_for_execution,
+                                            ),
+                                            test_mode: false,
+                                            dry_run,
+                                            target_directory: &target_path_owned,
+                                            coverage: None,
+                                        })
+                                        .await
+                                    });
+
+                                    await_js_ast_grep_execution_task(
+                                        execution_task,
+                                        idle_timed_out,
+                                        idle_notify,
+                                        idle_failure_message,
+                                        progress_state_for_execution,
+                                        idle_timeout,
+                                        &relative_path_for_execution,
+                                    )
                                     .await
-                                });
-
-                                await_js_ast_grep_execution_task(
-                                    execution_task,
-                                    idle_timed_out,
-                                    idle_notify,
-                                    idle_failure_message,
-                                    progress_state_for_execution,
-                                    idle_timeout,
-                                    &relative_path_for_execution,
-                                )
+                                })
                                 .await
-                            })
-                            .await
-                    });
+                        })
+                    };
+
+                    if let (Some((cache, _)), Some(key), false, Ok(Ok(output))) = (
+                        result_cache.as_ref(),
+                        result_cache_key.as_ref(),
+                        cache_hit,
+                        &execution_result,
+                    ) {
+                        if let Some(cached) = CachedResult::from_output(output) {
+                            if let Err(e) = cache.insert(key, &cached) {
+                                slog!(
+                                    logger,
+                                    debug,
+                                    "Failed to cache result for {}: {}",
+                                    relative_path,
+                                    e
+                                );
+                            }
+                        }
+                    }
 
                     if canceled_flag_for_closure.load(Ordering::Acquire) {
                         finish_unit_progress(
@@ -834,9 +1347,117 @@ impl<'a> JssgExecutionService<'a> {
                     match execution_result {
                         Ok(Ok(CodemodOutput { primary, secondary })) => {
                             succeeded_file_count_for_closure.fetch_add(1, Ordering::Relaxed);
+                            // Why the step's check_syntax or check_idempotent
+                            // guard refuses a change, if it does
+                            let guard_violation = |change_path: &Path,
+                                                   modified: &ModifiedResult|
+                             -> Option<String> {
+                                let syntax_language = check_syntax
+                                    .then(|| {
+                                        syntax_check_language(file_path, language, change_path)
+                                    })
+                                    .flatten();
+                                if let Some(syntax_language) = syntax_language {
+                                    let original = if change_path == file_path {
+                                        content.clone()
+                                    } else {
+                                        std::fs::read_to_string(change_path).unwrap_or_default()
+                                    };
+                                    let issues = introduced_syntax_issues(
+                                        syntax_language,
+                                        &original,
+                                        &modified.content,
+                                    );
+                                    if !issues.is_empty() {
+                                        return Some(format!(
+                                            "the output no longer parses: {}",
+                                            summarize_syntax_issues(&issues)
+                                        ));
+                                    }
+                                }
+                                if !check_idempotent || change_path != file_path {
+                                    return None;
+                                }
+
+                                // Semantic queries of the rerun see its input rather
+                                // than the file on disk. Files processed concurrently
+                                // see it too until the overlay is removed.
+                                if let Some(provider) = &semantic_provider {
+                                    let _ = provider
+                                        .set_file_overlay(file_path, Some(&modified.content));
+                                }
+                                // Reruns must not count metrics, touch shared state or
+                                // pay for another LLM call
+                                let rerun = block_on_runtime_handle(&runtime_handle, async {
+                                    let file_path_owned = file_path.to_path_buf();
+                                    let content_owned = modified.content.clone();
+                                    let js_file_path_owned = js_file_path_clone.clone();
+                                    let resolver_owned = resolver_clone.clone();
+                                    let selector_config_owned = selector_config.clone();
+                                    let params_owned = params.clone();
+                                    let matrix_input_owned = matrix_input.clone();
+                                    let capabilities_owned = config.capabilities.clone();
+                                    let semantic_provider_owned = semantic_provider.clone();
+                                    let target_path_owned = target_path.clone();
+                                    tokio::task::LocalSet::new()
+                                        .run_until(async move {
+                                            execute_codemod_with_quickjs(JssgExecutionOptions {
+                                                script_path: &js_file_path_owned,
+                                                resolver: resolver_owned,
+                                                language,
+                                                file_path: &file_path_owned,
+                                                content: &content_owned,
+                                                selector_config: selector_config_owned,
+                                                params: params_owned,
+                                                matrix_values: matrix_input_owned,
+                                                capabilities: capabilities_owned,
+                                                semantic_provider: semantic_provider_owned,
+                                                metrics_context: None,
+                                                llm_request_handler: None,
+                                                shared_state_context: None,
+                                                runtime_event_callback: None,
+                                                cancellation_flag: None,
+                                                test_mode: false,
+                                                dry_run: true,
+                                                target_directory: &target_path_owned,
+                                                coverage: None,
+                                            })
+                                            .await
+                                        })
+                                        .await
+                                });
+                                if let Some(provider) = &semantic_provider {
+                                    let _ = provider.set_file_overlay(file_path, None);
+                                }
+                                match rerun.map(|output| output.primary) {
+                                    Ok(ExecutionResult::Modified(again))
+                                        if again.content != modified.content =>
+                                    {
+                                        Some("a second run changes the output again".to_string())
+                                    }
+                                    Ok(_) => None,
+                                    Err(e) => Some(format!("a second run failed: {e}")),
+                                }
+                            };
                             let apply_change =
                                 |change_path: &Path, result: &ExecutionResult| match result {
                                     ExecutionResult::Modified(ref modified) => {
+                                        if let Some(reason) =
+                                            guard_violation(change_path, modified)
+                                        {
+                                            slog!(
+                                                logger,
+                                                error,
+                                                "Refusing to write {}: {}",
+                                                change_path.display(),
+                                                reason
+                                            );
+                                            engine
+                                                .execution_stats
+                                                .files_with_errors
+                                                .fetch_add(1, Ordering::Relaxed);
+                                            return;
+                                        }
                                         let write_path =
                                             modified.rename_to.as_deref().unwrap_or(change_path);
                                         if config.dry_run {
@@ -1001,6 +1622,19 @@ impl<'a> JssgExecutionService<'a> {
                             }
 
                             for change in &secondary {
+                                if change.is_stale() {
+                                    slog!(
+                                        logger,
+                                        error,
+                                        "Skipping change to {}: file was modified after the change was computed",
+                                        change.path.display()
+                                    );
+                                    engine
+                                        .execution_stats
+                                        .files_with_errors
+                                        .fetch_add(1, Ordering::Relaxed);
+                                    continue;
+                                }
                                 apply_change(&change.path, &change.result);
                             }
 
@@ -1251,50 +1885,8 @@ impl<'a> JssgExecutionService<'a> {
             }
         }
 
-        if let Some(wf_run_id) = workflow_run_id {
-            if !self
-                .engine
-                .workflow_run_config()
-                .execution
-                .skip_state_writes
-                && !config.dry_run
-            {
-                let persistable = shared_state_context.get_persistable();
-                let removals = shared_state_context.get_removals();
-
-                if !persistable.is_empty() || !removals.is_empty() {
-                    let mut fields = HashMap::new();
-                    for (key, value) in persistable {
-                        fields.insert(
-                            key,
-                            FieldDiff {
-                                operation: DiffOperation::Update,
-                                value: Some(value),
-                            },
-                        );
-                    }
-                    for key in removals {
-                        fields.insert(
-                            key,
-                            FieldDiff {
-                                operation: DiffOperation::Remove,
-                                value: None,
-                            },
-                        );
-                    }
-
-                    self.engine
-                        .state_adapter()
-                        .lock()
-                        .await
-                        .apply_state_diff(&StateDiff {
-                            workflow_run_id: wf_run_id,
-                            fields,
-                        })
-                        .await?;
-                }
-            }
-        }
+        self.persist_shared_state(workflow_run_id, &shared_state_context, config.dry_run)
+            .await?;
 
         Ok(())
     }
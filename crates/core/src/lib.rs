@@ -1,3 +1,4 @@
+pub mod affected;
 pub(crate) mod ai_agent_stream;
 pub mod ai_handoff;
 pub mod config;
@@ -16,6 +17,7 @@ pub(crate) mod progress_output;
 pub mod registry;
 pub mod registry_link;
 pub mod report;
+pub mod sarif;
 pub mod shard;
 pub(crate) mod step_executor;
 pub mod structured_log;
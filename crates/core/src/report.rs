@@ -334,7 +334,7 @@ fn normalize_optional_step_value(value: Option<String>) -> Option<String> {
     })
 }
 
-fn normalize_report_diff_path(diff_path: &Path, target_path: &Path) -> String {
+pub(crate) fn normalize_report_diff_path(diff_path: &Path, target_path: &Path) -> String {
     if let Ok(relative) = diff_path.strip_prefix(target_path) {
         return relative.display().to_string();
     }
@@ -0,0 +1,312 @@
+//! SARIF output for dry-run findings.
+//!
+//! Each contiguous run of changed lines in a dry-run diff becomes one SARIF
+//! result, located at the lines the codemod would replace and carrying the
+//! replacement as a fix, so code-scanning UIs can show where a codemod would
+//! change code.
+
+use serde_json::{json, Value};
+use std::path::Path;
+use url::Url;
+
+use crate::diff::FileDiff;
+use crate::report::normalize_report_diff_path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A contiguous run of changed lines within a diff hunk.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ChangeRun {
+    /// First line of the original file the change touches
+    start_line: usize,
+    deleted: Vec<String>,
+    inserted: Vec<String>,
+}
+
+/// Build a SARIF 2.1.0 log for the changes in `diffs`. Paths are reported
+/// relative to `target_path`, or as absolute `file:` URIs when they lie
+/// outside it, and every result uses `rule_id` (usually the codemod name) as
+/// its rule.
+pub fn sarif_report(
+    diffs: &[FileDiff],
+    target_path: &Path,
+    rule_id: &str,
+    tool_version: &str,
+) -> Value {
+    let results: Vec<Value> = diffs
+        .iter()
+        .flat_map(|diff| {
+            let artifact = artifact_location(Path::new(&diff.path), target_path);
+            change_runs(&diff.diff_text)
+                .into_iter()
+                .map(move |run| sarif_result(&artifact, rule_id, &run))
+        })
+        .collect();
+    // Relative targets have no file URI; their results stay relative
+    let base_ids = Url::from_directory_path(target_path)
+        .map(|root| json!({ "%SRCROOT%": { "uri": root.as_str() } }))
+        .unwrap_or_else(|()| json!({}));
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codemod",
+                    "version": tool_version,
+                    "rules": [{
+                        "id": rule_id,
+                        "shortDescription": { "text": format!("Code changed by {rule_id}") },
+                    }],
+                },
+            },
+            "originalUriBaseIds": base_ids,
+            "results": results,
+        }],
+    })
+}
+
+/// Where a changed file lives: relative to `%SRCROOT%`, or an absolute file
+/// URI with no base for files outside the target.
+fn artifact_location(diff_path: &Path, target_path: &Path) -> Value {
+    let uri = normalize_report_diff_path(diff_path, target_path);
+    if Path::new(&uri).is_absolute() {
+        if let Ok(url) = Url::from_file_path(&uri) {
+            return json!({ "uri": url.as_str() });
+        }
+    }
+    json!({ "uri": uri, "uriBaseId": "%SRCROOT%" })
+}
+
+fn sarif_result(artifact: &Value, rule_id: &str, run: &ChangeRun) -> Value {
+    let start_line = run.start_line.max(1);
+    // Pure insertions replace an empty region at the start of the line
+    let (region, deleted_region) = if run.deleted.is_empty() {
+        let region = json!({ "startLine": start_line, "startColumn": 1, "endColumn": 1 });
+        (region.clone(), region)
+    } else {
+        let end_line = start_line + run.deleted.len() - 1;
+        (
+            json!({ "startLine": start_line, "endLine": end_line }),
+            json!({
+                "startLine": start_line,
+                "startColumn": 1,
+                "endLine": end_line + 1,
+                "endColumn": 1,
+            }),
+        )
+    };
+
+    let text = match (run.deleted.len(), run.inserted.len()) {
+        (0, added) => format!("{rule_id} would insert {added} line(s)"),
+        (removed, 0) => format!("{rule_id} would remove {removed} line(s)"),
+        (removed, added) => {
+            format!("{rule_id} would replace {removed} line(s) with {added} line(s)")
+        }
+    };
+    let diff: String = run
+        .deleted
+        .iter()
+        .map(|line| format!("-{line}\n"))
+        .chain(run.inserted.iter().map(|line| format!("+{line}\n")))
+        .collect();
+    let inserted: String = run
+        .inserted
+        .iter()
+        .map(|line| format!("{line}\n"))
+        .collect();
+    json!({
+        "ruleId": rule_id,
+        "level": "warning",
+        "message": {
+            "text": text,
+            "markdown": format!("{text}:\n\n```diff\n{diff}```"),
+        },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": artifact,
+                "region": region,
+            },
+        }],
+        "fixes": [{
+            "description": { "text": format!("Apply {rule_id}") },
+            "artifactChanges": [{
+                "artifactLocation": artifact,
+                "replacements": [{
+                    "deletedRegion": deleted_region,
+                    "insertedContent": { "text": inserted },
+                }],
+            }],
+        }],
+    })
+}
+
+/// Split a plain unified diff into runs of changed lines, numbered by their
+/// position in the original file.
+fn change_runs(diff_text: &str) -> Vec<ChangeRun> {
+    let mut runs = Vec::new();
+    let mut current: Option<ChangeRun> = None;
+    let mut old_line = 0;
+    let mut in_hunk = false;
+
+    for line in diff_text.lines() {
+        if let Some(header) = line.strip_prefix("@@ -") {
+            in_hunk = true;
+            runs.extend(current.take());
+            old_line = header
+                .split([',', ' '])
+                .next()
+                .and_then(|start| start.parse::<usize>().ok())
+                .unwrap_or(1);
+            // Hunks of empty originals start at line 0
+            old_line = old_line.max(1);
+        } else if !in_hunk {
+            // File headers
+            continue;
+        } else if let Some(removed) = line.strip_prefix('-') {
+            let run = current.get_or_insert_with(|| ChangeRun {
+                start_line: old_line,
+                ..ChangeRun::default()
+            });
+            run.deleted.push(removed.to_string());
+            old_line += 1;
+        } else if let Some(added) = line.strip_prefix('+') {
+            current
+                .get_or_insert_with(|| ChangeRun {
+                    start_line: old_line,
+                    ..ChangeRun::default()
+                })
+                .inserted
+                .push(added.to_string());
+        } else if line.starts_with(' ') {
+            runs.extend(current.take());
+            old_line += 1;
+        } else {
+            // "... (diff truncated)"
+            break;
+        }
+    }
+    runs.extend(current);
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{generate_unified_diff, DiffConfig, DiffMetadata};
+    use std::path::PathBuf;
+
+    fn plain_diff(path: &str, original: &str, modified: &str) -> FileDiff {
+        let config = DiffConfig {
+            color: false,
+            ..Default::default()
+        };
+        generate_unified_diff(
+            &PathBuf::from(path),
+            original,
+            modified,
+            &config,
+            DiffMetadata::default(),
+        )
+    }
+
+    #[test]
+    fn change_runs_are_numbered_by_original_lines() {
+        let diff = plain_diff(
+            "a.ts",
+            "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n",
+            "one\n2\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\n",
+        );
+
+        let runs = change_runs(&diff.diff_text);
+        assert_eq!(
+            runs,
+            vec![
+                ChangeRun {
+                    start_line: 2,
+                    deleted: vec!["two".to_string()],
+                    inserted: vec!["2".to_string()],
+                },
+                ChangeRun {
+                    start_line: 11,
+                    deleted: vec![],
+                    inserted: vec!["eleven".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sarif_report_has_a_result_with_a_fix_per_change() {
+        let diff = plain_diff(
+            "/repo/src/app.ts",
+            "const a = 1;\nvar b = 2;\nvar c = 3;\n",
+            "const a = 1;\nlet b = 2;\nlet c = 3;\n",
+        );
+
+        let log = sarif_report(&[diff], Path::new("/repo"), "var-to-let", "1.0.0");
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "var-to-let");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(
+            result["message"]["text"],
+            "var-to-let would replace 2 line(s) with 2 line(s)"
+        );
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/app.ts");
+        assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["endLine"], 3);
+
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["endLine"], 4);
+        assert_eq!(
+            replacement["insertedContent"]["text"],
+            "let b = 2;\nlet c = 3;\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sarif_report_uses_file_uris_for_the_root_and_files_outside_it() {
+        let inside = plain_diff("/my repo/a.ts", "var a;\n", "let a;\n");
+        let outside = plain_diff("/generated/b#1.ts", "var b;\n", "let b;\n");
+
+        let log = sarif_report(
+            &[inside, outside],
+            Path::new("/my repo"),
+            "var-to-let",
+            "1.0.0",
+        );
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///my%20repo/"
+        );
+        let artifacts: Vec<_> = run["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| &result["locations"][0]["physicalLocation"]["artifactLocation"])
+            .collect();
+        assert_eq!(
+            artifacts,
+            vec![
+                &json!({ "uri": "a.ts", "uriBaseId": "%SRCROOT%" }),
+                &json!({ "uri": "file:///generated/b%231.ts" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn sarif_report_without_changes_has_no_results() {
+        let diff = plain_diff("/repo/a.ts", "same\n", "same\n");
+        let log = sarif_report(&[diff], Path::new("/repo"), "noop", "1.0.0");
+        assert_eq!(log["runs"][0]["results"], json!([]));
+    }
+}
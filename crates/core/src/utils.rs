@@ -4,6 +4,7 @@ use std::fs;
 use std::path::{Component, Path, PathBuf};
 
 use butterflow_models::step::{SemanticAnalysisConfig, SemanticAnalysisMode, StepAction};
+use semantic_factory::SemanticConfig;
 use serde_yaml;
 
 use butterflow_models::{Error, Node, Result, Workflow};
@@ -190,6 +191,12 @@ pub fn validate_workflow(workflow: &Workflow, package_path: &Path) -> Result<()>
                             )?;
                         }
                     }
+                    if let Some(queries) = &detailed.queries {
+                        validate_workflow_relative_path(
+                            queries,
+                            "js-ast-grep.semantic_analysis.queries",
+                        )?;
+                    }
                 }
 
                 let js_file_path = package_path.join(js_step.js_file.trim());
@@ -445,6 +452,34 @@ pub fn get_cache_dir() -> Result<PathBuf> {
     Ok(cache_dir)
 }
 
+/// Directory of the persistent per-file JSSG result cache, next to the
+/// package cache.
+pub fn get_result_cache_dir() -> Result<PathBuf> {
+    let home_dir = dirs::data_dir()
+        .ok_or_else(|| Error::Other("Could not find home directory".to_string()))?;
+    Ok(home_dir.join("codemod").join("cache").join("results"))
+}
+
+/// Directory of the persistent workspace semantic index, shared by every
+/// run and matrix task on this machine.
+pub fn get_semantic_index_dir() -> Result<PathBuf> {
+    let home_dir = dirs::data_dir()
+        .ok_or_else(|| Error::Other("Could not find home directory".to_string()))?;
+    Ok(home_dir
+        .join("codemod")
+        .join("cache")
+        .join("semantic-index"))
+}
+
+/// Back `config` with the persistent semantic index when its directory is
+/// known, so runs and matrix tasks share one workspace index.
+pub fn with_semantic_index(config: SemanticConfig) -> SemanticConfig {
+    match get_semantic_index_dir() {
+        Ok(dir) => config.with_index_dir(dir),
+        Err(_) => config,
+    }
+}
+
 pub fn generate_execution_id() -> String {
     let execution_id: [u8; 20] = rand::rng().random();
     base64::Engine::encode(
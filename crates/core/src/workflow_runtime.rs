@@ -809,6 +809,7 @@ mod tests {
             languages: None,
             threads: None,
             capabilities: Some(requested.clone()),
+            include_components: true,
         };
 
         let pending_for_thread = Arc::clone(&pending);
@@ -880,6 +881,7 @@ mod tests {
             languages: None,
             threads: None,
             capabilities: Some(requested),
+            include_components: true,
         };
 
         let first = {
@@ -953,6 +955,7 @@ mod tests {
             languages: None,
             threads: None,
             capabilities: Some(requested),
+            include_components: true,
         };
 
         let threads: Vec<_> = (0..24)
@@ -1024,6 +1027,7 @@ mod tests {
             languages: None,
             threads: None,
             capabilities: Some(approved),
+            include_components: true,
         };
 
         callback(&config).expect("preapproved capabilities should not prompt");
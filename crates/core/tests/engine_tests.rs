@@ -970,6 +970,10 @@ fn create_manual_matrix_git_js_ast_grep_workflow() -> Workflow {
                         semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                             SemanticAnalysisMode::File,
                         )),
+                        affected_by: None,
+                        check_syntax: None,
+                        check_idempotent: None,
+                        include_components: None,
                     }),
                     env: None,
                     condition: None,
@@ -1078,6 +1082,10 @@ fn create_manual_matrix_real_debarrel_workspace_workflow() -> Workflow {
                         semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                             SemanticAnalysisMode::Workspace,
                         )),
+                        affected_by: None,
+                        check_syntax: None,
+                        check_idempotent: None,
+                        include_components: None,
                     }),
                     env: None,
                     condition: None,
@@ -1179,6 +1187,10 @@ fn create_many_shard_real_debarrel_workspace_workflow(shard_count: usize) -> Wor
                         semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                             SemanticAnalysisMode::Workspace,
                         )),
+                        affected_by: None,
+                        check_syntax: None,
+                        check_idempotent: None,
+                        include_components: None,
                     }),
                     env: None,
                     condition: None,
@@ -4889,6 +4901,10 @@ function helper() {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -4957,6 +4973,10 @@ export default function transform(root) {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5061,6 +5081,10 @@ interface ApiResponse {
                 language: Some("typescript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5139,6 +5163,10 @@ function main() {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5202,6 +5230,10 @@ export default function (
                 language: Some("typescript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5295,6 +5327,10 @@ export default function transform(ast) {{
                 language: Some("typescript".to_string()),
                 capabilities: Some(vec!["fs".to_string()]),
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5369,6 +5405,10 @@ export default function transform(ast) {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5439,6 +5479,10 @@ export default function transform() {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5524,6 +5568,10 @@ var count = 0;
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5580,6 +5628,10 @@ async fn test_execute_js_ast_grep_step_nonexistent_js_file() {
                 language: None,
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -5665,6 +5717,10 @@ export default function transform(root) {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             Some(matrix),
@@ -5795,6 +5851,10 @@ export default function transform(root) {
                     semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                         SemanticAnalysisMode::File,
                     )),
+                    affected_by: None,
+                    check_syntax: None,
+                    check_idempotent: None,
+                    include_components: None,
                 }),
                 env: None,
                 condition: None,
@@ -5941,6 +6001,10 @@ export default function transform(root) {
                     semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                         SemanticAnalysisMode::File,
                     )),
+                    affected_by: None,
+                    check_syntax: None,
+                    check_idempotent: None,
+                    include_components: None,
                 }),
                 env: None,
                 condition: None,
@@ -6055,6 +6119,10 @@ export default function transform(root) {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             Some(matrix),
@@ -6152,6 +6220,10 @@ build/
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -6194,6 +6266,10 @@ build/
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -6266,6 +6342,10 @@ export default function transform(ast) {
                 language: Some("javascript".to_string()),
                 capabilities: None,
                 semantic_analysis: Some(SemanticAnalysisConfig::Mode(SemanticAnalysisMode::File)),
+                affected_by: None,
+                check_syntax: None,
+                check_idempotent: None,
+                include_components: None,
             },
             None,
             None,
@@ -6328,6 +6408,10 @@ fn create_js_ast_grep_workflow() -> Workflow {
                     semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                         SemanticAnalysisMode::File,
                     )),
+                    affected_by: None,
+                    check_syntax: None,
+                    check_idempotent: None,
+                    include_components: None,
                 }),
                 env: None,
                 condition: None,
@@ -1029,6 +1029,10 @@ fix:
                         semantic_analysis: Some(SemanticAnalysisConfig::Mode(
                             SemanticAnalysisMode::File,
                         )),
+                        affected_by: None,
+                        check_syntax: None,
+                        check_idempotent: None,
+                        include_components: None,
                     }),
                     env: None,
                     condition: None,
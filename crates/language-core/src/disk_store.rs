@@ -0,0 +1,196 @@
+//! Versioned JSON entries on disk, addressed by hex keys.
+//!
+//! Shared by the semantic [`IndexStore`](crate::IndexStore) and the JSSG
+//! result cache, which derive their own keys and wrap the store with typed
+//! entries. Entries live at `<root>/<key[..2]>/<key[2..]>.json`; reads
+//! refresh the entry's modification time so pruning by age keeps entries
+//! that are still being hit.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[derive(Serialize, Deserialize)]
+struct StoredEntry<T> {
+    version: u32,
+    value: T,
+}
+
+/// Entry count and size of a store directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskStoreStats {
+    pub entries: usize,
+    pub total_size: u64,
+}
+
+/// On-disk store of JSON entries written with one format version.
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    root: PathBuf,
+    version: u32,
+}
+
+impl DiskStore {
+    /// Open the store under `root`. Entries written with another `version`
+    /// are misses.
+    pub fn new(root: impl Into<PathBuf>, version: u32) -> Self {
+        Self {
+            root: root.into(),
+            version,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let (shard, rest) = key.split_at(2.min(key.len()));
+        self.root.join(shard).join(format!("{rest}.json"))
+    }
+
+    /// Look up an entry. Unreadable or outdated entries are misses.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let path = self.entry_path(key);
+        let bytes = fs::read(&path).ok()?;
+        let entry: StoredEntry<T> = serde_json::from_slice(&bytes).ok()?;
+        if entry.version != self.version {
+            return None;
+        }
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry.value)
+    }
+
+    /// Store an entry. Writes go through a temporary file so concurrent
+    /// processes never observe a partial entry.
+    pub fn insert<T: Serialize>(&self, key: &str, value: &T) -> io::Result<()> {
+        let path = self.entry_path(key);
+        let parent = path.parent().unwrap_or(&self.root);
+        fs::create_dir_all(parent)?;
+        let bytes = serde_json::to_vec(&StoredEntry {
+            version: self.version,
+            value,
+        })
+        .map_err(io::Error::other)?;
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = parent.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+
+    /// Entry count and size of everything under `root`, including stores
+    /// nested in subdirectories.
+    pub fn stats(&self) -> io::Result<DiskStoreStats> {
+        let mut stats = DiskStoreStats::default();
+        for_each_entry(&self.root, &mut |_, metadata| {
+            stats.entries += 1;
+            stats.total_size += metadata.len();
+            Ok(())
+        })?;
+        Ok(stats)
+    }
+
+    /// Remove entries under `root` that have not been written or read within
+    /// `max_age`. With `dry_run` nothing is deleted; the returned stats
+    /// describe what would be removed.
+    pub fn prune(&self, max_age: Duration, dry_run: bool) -> io::Result<DiskStoreStats> {
+        let cutoff = SystemTime::now()
+            .checked_sub(max_age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut pruned = DiskStoreStats::default();
+        for_each_entry(&self.root, &mut |path, metadata| {
+            if metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH) < cutoff {
+                if !dry_run {
+                    fs::remove_file(path)?;
+                }
+                pruned.entries += 1;
+                pruned.total_size += metadata.len();
+            }
+            Ok(())
+        })?;
+        Ok(pruned)
+    }
+
+    /// Remove every entry under `root`.
+    pub fn clear(&self) -> io::Result<()> {
+        if self.root.exists() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// Visit every `.json` entry under `dir`, at any depth.
+fn for_each_entry(
+    dir: &Path,
+    visit: &mut impl FnMut(&Path, &fs::Metadata) -> io::Result<()>,
+) -> io::Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            for_each_entry(&path, visit)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            visit(&path, &entry.metadata()?)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_entries_round_trip_per_version() {
+        let dir = tempdir().unwrap();
+        let store = DiskStore::new(dir.path(), 1);
+
+        assert_eq!(store.get::<Vec<u32>>("abcdef"), None);
+        store.insert("abcdef", &vec![1u32, 2]).unwrap();
+        assert_eq!(store.get::<Vec<u32>>("abcdef"), Some(vec![1, 2]));
+        assert!(dir.path().join("ab/cdef.json").is_file());
+        assert_eq!(
+            DiskStore::new(dir.path(), 2).get::<Vec<u32>>("abcdef"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stats_and_prune_cover_nested_stores() {
+        let dir = tempdir().unwrap();
+        DiskStore::new(dir.path(), 1).insert("aa11", &1u32).unwrap();
+        DiskStore::new(dir.path().join("namespace"), 1)
+            .insert("bb22", &2u32)
+            .unwrap();
+        fs::write(dir.path().join("aa/.11.json.1.tmp"), "partial").unwrap();
+
+        let store = DiskStore::new(dir.path(), 1);
+        assert_eq!(store.stats().unwrap().entries, 2);
+        assert_eq!(
+            store
+                .prune(Duration::from_secs(3600), false)
+                .unwrap()
+                .entries,
+            0
+        );
+        assert_eq!(store.prune(Duration::ZERO, true).unwrap().entries, 2);
+        assert_eq!(store.stats().unwrap().entries, 2);
+        store.prune(Duration::ZERO, false).unwrap();
+        assert_eq!(store.stats().unwrap().entries, 0);
+
+        store.clear().unwrap();
+        assert!(!dir.path().exists());
+    }
+}
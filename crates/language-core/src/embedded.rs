@@ -0,0 +1,438 @@
+//! Code embedded in host files: `<script>` and `<style>` blocks of Vue and
+//! Svelte single-file components and HTML pages, and fenced code blocks in
+//! Markdown.
+//!
+//! Regions are located with the tree-sitter grammar of the host format, so
+//! the codemod engine and the semantic providers agree on the same regions
+//! and handle comments, quoting and nesting the way the host language does.
+//!
+//! Consumers analyze a region through [`mask_outside`]: everything outside
+//! the region is blanked to spaces, which keeps byte offsets, lines and
+//! columns identical to the host file.
+
+use ast_grep_core::tree_sitter::LanguageExt;
+use ast_grep_language::SupportLang;
+use std::ops::Range;
+use std::path::Path;
+use tree_sitter::{Node, Parser, Tree};
+
+/// File formats that embed code in other languages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HostFormat {
+    Vue,
+    Svelte,
+    Html,
+    Markdown,
+}
+
+impl HostFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "vue" => Some(Self::Vue),
+            "svelte" => Some(Self::Svelte),
+            "html" | "htm" => Some(Self::Html),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Vue => "vue",
+            Self::Svelte => "svelte",
+            Self::Html => "html",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+/// What kind of host construct a region comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    /// Content of a `<script>` element.
+    Script,
+    /// Content of a `<style>` element.
+    Style,
+    /// Body of a fenced Markdown code block.
+    CodeFence,
+}
+
+impl RegionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Style => "style",
+            Self::CodeFence => "fence",
+        }
+    }
+}
+
+/// A block of code embedded in a host file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedRegion {
+    /// Byte range of the block content in the host file.
+    pub range: Range<usize>,
+    /// Language of the content, e.g. `typescript` or `css`. Code fences
+    /// keep the first word of their info string (`ts`, `python`).
+    pub language: String,
+    pub kind: RegionKind,
+}
+
+/// Find the embedded regions of `content`, in document order.
+pub fn extract_regions(format: HostFormat, content: &str) -> Vec<EmbeddedRegion> {
+    match format {
+        HostFormat::Vue | HostFormat::Svelte | HostFormat::Html => html_regions(content),
+        HostFormat::Markdown => markdown_regions(content),
+    }
+}
+
+/// The `<script>` regions of a component or page that hold JavaScript or
+/// TypeScript, i.e. the parts a module analyzer should see.
+pub fn script_regions(format: HostFormat, content: &str) -> Vec<EmbeddedRegion> {
+    if format == HostFormat::Markdown {
+        return Vec::new();
+    }
+    extract_regions(format, content)
+        .into_iter()
+        .filter(|region| region.kind == RegionKind::Script)
+        .collect()
+}
+
+/// The module formed by the `<script>` blocks of a component or page: the
+/// language to analyze it as (`javascript`, `typescript` or `tsx`) and the
+/// host content masked to the scripts.
+pub fn script_module(format: HostFormat, content: &str) -> (&'static str, String) {
+    let regions = script_regions(format, content);
+    let language = if regions.iter().any(|region| region.language == "tsx") {
+        "tsx"
+    } else if regions.iter().any(|region| region.language == "typescript") {
+        "typescript"
+    } else {
+        "javascript"
+    };
+    let ranges: Vec<_> = regions.into_iter().map(|region| region.range).collect();
+    (language, mask_outside(content, &ranges))
+}
+
+/// Copy of `content` with every byte outside `ranges` replaced by a space.
+/// Line breaks are kept so positions still line up with the host file.
+pub fn mask_outside(content: &str, ranges: &[Range<usize>]) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut offset = 0;
+    let mut ranges: Vec<_> = ranges.to_vec();
+    ranges.sort_by_key(|range| range.start);
+    for range in ranges {
+        let start = range.start.max(offset).min(content.len());
+        let end = range.end.min(content.len());
+        if start >= end {
+            continue;
+        }
+        push_blank(&mut masked, &content[offset..start]);
+        masked.push_str(&content[start..end]);
+        offset = end;
+    }
+    push_blank(&mut masked, &content[offset..]);
+    masked
+}
+
+fn push_blank(out: &mut String, text: &str) {
+    for byte in text.bytes() {
+        out.push(match byte {
+            b'\n' | b'\r' => byte as char,
+            _ => ' ',
+        });
+    }
+}
+
+fn parse(language: &tree_sitter::Language, content: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    parser.parse(content, None)
+}
+
+/// `<script>` and `<style>` elements, located with the HTML grammar
+/// ast-grep uses for HTML injections. The grammar also parses Vue and
+/// Svelte components, whose templates are HTML with extra text syntax.
+fn html_regions(content: &str) -> Vec<EmbeddedRegion> {
+    let Some(tree) = parse(&SupportLang::Html.get_ts_language(), content) else {
+        return Vec::new();
+    };
+    let mut regions = Vec::new();
+    collect_html_regions(tree.root_node(), content, &mut regions);
+    regions
+}
+
+fn collect_html_regions(node: Node, content: &str, regions: &mut Vec<EmbeddedRegion>) {
+    let kind = match node.kind() {
+        "script_element" => RegionKind::Script,
+        "style_element" => RegionKind::Style,
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                collect_html_regions(child, content, regions);
+            }
+            return;
+        }
+    };
+
+    let mut cursor = node.walk();
+    let children: Vec<_> = node.named_children(&mut cursor).collect();
+    let Some(start_tag) = children.iter().find(|child| child.kind() == "start_tag") else {
+        return;
+    };
+    // An element without content has no `raw_text` child
+    let range = children
+        .iter()
+        .find(|child| child.kind() == "raw_text")
+        .map_or(start_tag.end_byte()..start_tag.end_byte(), |text| {
+            text.byte_range()
+        });
+    if let Some(language) = region_language(kind, &tag_attributes(*start_tag, content)) {
+        regions.push(EmbeddedRegion {
+            range,
+            language,
+            kind,
+        });
+    }
+}
+
+/// Attribute names (lowercased) and unquoted values of a start tag.
+fn tag_attributes(start_tag: Node, content: &str) -> Vec<(String, String)> {
+    let text = |node: Node| content[node.byte_range()].to_string();
+    let mut cursor = start_tag.walk();
+    start_tag
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "attribute")
+        .filter_map(|attribute| {
+            let mut cursor = attribute.walk();
+            let mut name = None;
+            let mut value = String::new();
+            for child in attribute.named_children(&mut cursor) {
+                match child.kind() {
+                    "attribute_name" => name = Some(text(child).to_ascii_lowercase()),
+                    "attribute_value" => value = text(child),
+                    "quoted_attribute_value" => {
+                        let mut cursor = child.walk();
+                        value = child
+                            .named_children(&mut cursor)
+                            .find(|inner| inner.kind() == "attribute_value")
+                            .map(text)
+                            .unwrap_or_default();
+                    }
+                    _ => {}
+                }
+            }
+            Some((name?, value))
+        })
+        .collect()
+}
+
+fn region_language(kind: RegionKind, attributes: &[(String, String)]) -> Option<String> {
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_ascii_lowercase())
+    };
+
+    match kind {
+        RegionKind::Script => {
+            if attribute("src").is_some() {
+                return None;
+            }
+            // `type="text/template"`, `application/json` etc. are data, not code
+            if let Some(script_type) = attribute("type") {
+                if !matches!(
+                    script_type.as_str(),
+                    "" | "module"
+                        | "text/javascript"
+                        | "application/javascript"
+                        | "text/typescript"
+                        | "application/typescript"
+                ) {
+                    return None;
+                }
+                if script_type.ends_with("typescript") {
+                    return Some("typescript".to_string());
+                }
+            }
+            Some(
+                match attribute("lang").as_deref() {
+                    Some("ts" | "typescript") => "typescript",
+                    Some("tsx") => "tsx",
+                    _ => "javascript",
+                }
+                .to_string(),
+            )
+        }
+        RegionKind::Style => Some(
+            match attribute("lang").as_deref() {
+                Some(lang @ ("scss" | "less" | "sass" | "stylus")) => lang,
+                _ => "css",
+            }
+            .to_string(),
+        ),
+        RegionKind::CodeFence => None,
+    }
+}
+
+/// Fenced code blocks with an info string, located with the Markdown block
+/// grammar. The region spans the lines between the fences.
+fn markdown_regions(content: &str) -> Vec<EmbeddedRegion> {
+    let Some(tree) = parse(&tree_sitter_md::LANGUAGE.into(), content) else {
+        return Vec::new();
+    };
+    let mut regions = Vec::new();
+    collect_code_fences(tree.root_node(), content, &mut regions);
+    regions
+}
+
+fn collect_code_fences(node: Node, content: &str, regions: &mut Vec<EmbeddedRegion>) {
+    if node.kind() != "fenced_code_block" {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            collect_code_fences(child, content, regions);
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    let children: Vec<_> = node.named_children(&mut cursor).collect();
+    let Some(info) = children.iter().find(|child| child.kind() == "info_string") else {
+        return;
+    };
+    let language = content[info.byte_range()]
+        .split(|c: char| c.is_ascii_whitespace() || c == '{' || c == ',')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if language.is_empty() {
+        return;
+    }
+
+    // The body starts on the line after the opening fence and ends where
+    // the closing fence's line starts, or with the block when unclosed
+    let line_end = |offset: usize| {
+        content[offset..]
+            .find('\n')
+            .map_or(content.len(), |newline| offset + newline + 1)
+    };
+    let line_start = |offset: usize| {
+        content[..offset]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1)
+    };
+    let start = line_end(info.end_byte());
+    let end = children
+        .iter()
+        .filter(|child| child.kind() == "fenced_code_block_delimiter")
+        .nth(1)
+        .map_or(node.end_byte(), |fence| line_start(fence.start_byte()))
+        .max(start);
+    regions.push(EmbeddedRegion {
+        range: start..end,
+        language,
+        kind: RegionKind::CodeFence,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(content: &'a str, regions: &[EmbeddedRegion]) -> Vec<(&'a str, String)> {
+        regions
+            .iter()
+            .map(|region| (&content[region.range.clone()], region.language.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_vue_component_regions() {
+        let content = r#"<template>
+  <div>{{ count }}</div>
+</template>
+
+<!-- <script>ignored()</script> -->
+<script setup lang="ts">
+const count = ref<number>(0);
+</script>
+
+<script src="./external.js"></script>
+
+<style scoped lang="scss">
+.a { color: red; }
+</style>
+"#;
+        let regions = extract_regions(HostFormat::Vue, content);
+        assert_eq!(
+            texts(content, &regions),
+            vec![
+                (
+                    "\nconst count = ref<number>(0);\n",
+                    "typescript".to_string()
+                ),
+                ("\n.a { color: red; }\n", "scss".to_string()),
+            ]
+        );
+        assert_eq!(script_regions(HostFormat::Vue, content).len(), 1);
+    }
+
+    #[test]
+    fn test_html_script_types() {
+        let content = "<script type=\"module\">import a from './a.js';</script>\
+<script type=\"application/json\">{}</script>\
+<SCRIPT>var b = 1;</SCRIPT>";
+        let regions = extract_regions(HostFormat::Html, content);
+        assert_eq!(
+            texts(content, &regions),
+            vec![
+                ("import a from './a.js';", "javascript".to_string()),
+                ("var b = 1;", "javascript".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_attribute_forms() {
+        let content = "<svelte:options immutable />\n\
+<script context=module lang=ts data-x=\"a > b\"></script>\n\
+<p title='</script>'>text</p>\n\
+<style>p { color: red; }</style>\n";
+        let regions = extract_regions(HostFormat::Svelte, content);
+        assert_eq!(
+            texts(content, &regions),
+            vec![
+                ("", "typescript".to_string()),
+                ("p { color: red; }", "css".to_string()),
+            ]
+        );
+        assert_eq!(regions[0].kind, RegionKind::Script);
+        assert_eq!(regions[1].kind, RegionKind::Style);
+    }
+
+    #[test]
+    fn test_markdown_fences() {
+        let content = "# Title\n\n```ts title=\"a.ts\"\nconst a: number = 1;\n```\n\n~~~\nplain\n~~~\n\n````js\n```\nnested\n````\n";
+        let regions = extract_regions(HostFormat::Markdown, content);
+        assert_eq!(
+            texts(content, &regions),
+            vec![
+                ("const a: number = 1;\n", "ts".to_string()),
+                ("```\nnested\n", "js".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mask_outside_preserves_positions() {
+        let content = "<script>\nlet é = 1;\n</script>\n<p>é</p>\n";
+        let regions = extract_regions(HostFormat::Html, content);
+        let masked = mask_outside(content, &[regions[0].range.clone()]);
+        assert_eq!(masked.len(), content.len());
+        assert_eq!(&masked[regions[0].range.clone()], "\nlet é = 1;\n");
+        assert_eq!(masked.lines().count(), content.lines().count());
+        assert!(masked[regions[0].range.end..].trim().is_empty());
+    }
+}
@@ -23,8 +23,10 @@
 //! file.create_file()?.write_all(b"const x = 1;")?;
 //! ```
 
+use std::collections::HashMap;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 // Re-export core vfs types
 pub use vfs::{MemoryFS, PhysicalFS, VfsError, VfsPath, VfsResult};
@@ -83,6 +85,44 @@ pub fn memory_fs() -> VfsPath {
     MemoryFS::new().into()
 }
 
+/// In-memory file contents that take precedence over the filesystem.
+///
+/// Providers consult it before reading a file, so callers can analyze content
+/// that hasn't been written yet, such as a codemod's output.
+#[derive(Debug, Default)]
+pub struct ContentOverlay {
+    files: RwLock<HashMap<PathBuf, String>>,
+}
+
+impl ContentOverlay {
+    /// Create an empty overlay.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The overlaid content of `path`, if any.
+    pub fn get(&self, path: &Path) -> Option<String> {
+        self.files
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(path)
+            .cloned()
+    }
+
+    /// Overlay `path` with `content`, or remove its overlay with `None`.
+    pub fn set(&self, path: PathBuf, content: Option<&str>) {
+        let mut files = self.files.write().unwrap_or_else(|e| e.into_inner());
+        match content {
+            Some(content) => {
+                files.insert(path, content.to_string());
+            }
+            None => {
+                files.remove(&path);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +181,17 @@ mod tests {
         let result = read_to_string(&root.join("nonexistent.txt").unwrap());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_content_overlay_set_and_clear() {
+        let overlay = ContentOverlay::new();
+        let path = PathBuf::from("/app/main.ts");
+        assert_eq!(overlay.get(&path), None);
+
+        overlay.set(path.clone(), Some("const y = 2;"));
+        assert_eq!(overlay.get(&path).as_deref(), Some("const y = 2;"));
+
+        overlay.set(path.clone(), None);
+        assert_eq!(overlay.get(&path), None);
+    }
 }
@@ -0,0 +1,597 @@
+//! Per-function control-flow graphs.
+//!
+//! Providers build a graph for the body of one function (or the top level
+//! of a module), either with [`FlowBuilder`] or by translating their
+//! parser's own graph, recording the byte range of every statement and
+//! branch condition as an item of a basic block. Queries map node ranges to
+//! the smallest item containing them, so reachability and dominance work
+//! for any node inside a statement.
+//!
+//! Exceptions are modelled for explicit `throw`/`raise` statements and for
+//! every statement inside a `try` block; calls outside `try` are assumed
+//! not to throw.
+
+use crate::ByteRange;
+
+/// Index of the entry block of every graph.
+pub const ENTRY: usize = 0;
+/// Index of the exit block of every graph. Returns, uncaught throws and
+/// falling off the end of the body all lead here.
+pub const EXIT: usize = 1;
+
+/// A basic block: items that always run in sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FlowBlock {
+    /// Statements and conditions in execution order
+    pub items: Vec<ByteRange>,
+    /// Blocks control can pass to after this one
+    pub successors: Vec<usize>,
+}
+
+/// The control-flow graph of a single function body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+    /// Byte range of the function (or the whole module)
+    pub range: ByteRange,
+    /// Basic blocks, starting with [`ENTRY`] and [`EXIT`]
+    pub blocks: Vec<FlowBlock>,
+}
+
+impl ControlFlowGraph {
+    /// The graph of the innermost function containing `range` among the
+    /// graphs of a whole file, listed outermost first as providers build
+    /// them. Later graphs win ties, so a function spanning the whole file
+    /// is preferred over the module.
+    pub fn innermost(graphs: &[ControlFlowGraph], range: ByteRange) -> Option<&ControlFlowGraph> {
+        graphs
+            .iter()
+            .rev()
+            .filter(|graph| graph.range.start <= range.start && graph.range.end >= range.end)
+            .min_by_key(|graph| graph.range.end - graph.range.start)
+    }
+
+    /// Find the block and item index of the smallest item containing `range`.
+    pub fn locate(&self, range: ByteRange) -> Option<(usize, usize)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(block, b)| {
+                b.items
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, item)| (block, index, *item))
+            })
+            .filter(|(_, _, item)| item.start <= range.start && item.end >= range.end)
+            .min_by_key(|(_, _, item)| item.end - item.start)
+            .map(|(block, index, _)| (block, index))
+    }
+
+    /// Whether the code at `range` can run at all.
+    ///
+    /// Ranges outside every item (e.g. the function's parameters) are
+    /// reachable.
+    pub fn is_reachable(&self, range: ByteRange) -> bool {
+        match self.locate(range) {
+            Some((block, _)) => self.reachable()[block],
+            None => true,
+        }
+    }
+
+    /// Whether every path from the function entry to `b` passes through `a`.
+    pub fn dominates(&self, a: ByteRange, b: ByteRange) -> bool {
+        let (Some((a_block, a_index)), Some((b_block, b_index))) = (self.locate(a), self.locate(b))
+        else {
+            return false;
+        };
+        if !self.reachable()[b_block] {
+            return false;
+        }
+        if a_block == b_block {
+            return a_index <= b_index;
+        }
+        self.dominator_sets(false)[b_block][a_block]
+    }
+
+    /// Whether every path from `b` to the function exit passes through `a`.
+    pub fn post_dominates(&self, a: ByteRange, b: ByteRange) -> bool {
+        let (Some((a_block, a_index)), Some((b_block, b_index))) = (self.locate(a), self.locate(b))
+        else {
+            return false;
+        };
+        if !self.reachable()[b_block] {
+            return false;
+        }
+        if a_block == b_block {
+            return a_index >= b_index;
+        }
+        self.dominator_sets(true)[b_block][a_block]
+    }
+
+    /// The items after which control leaves the function: `return` and
+    /// uncaught `throw` statements, and the last item of every path that
+    /// falls off the end of the body. A body that can finish without
+    /// running any item reports the function's own range.
+    pub fn exit_points(&self) -> Vec<ByteRange> {
+        let predecessors = self.predecessors();
+        let reachable = self.reachable();
+
+        let mut points = Vec::new();
+        let mut visited = vec![false; self.blocks.len()];
+        let mut stack: Vec<usize> = predecessors[EXIT].clone();
+        while let Some(block) = stack.pop() {
+            if visited[block] || !reachable[block] {
+                continue;
+            }
+            visited[block] = true;
+            match self.blocks[block].items.last() {
+                Some(item) => points.push(*item),
+                None if block == ENTRY => points.push(self.range),
+                // Empty join blocks: the exit is the last item before them
+                None => stack.extend(&predecessors[block]),
+            }
+        }
+        points.sort_by_key(|range| (range.start, range.end));
+        points.dedup();
+        points
+    }
+
+    fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![Vec::new(); self.blocks.len()];
+        for (block, b) in self.blocks.iter().enumerate() {
+            for &successor in &b.successors {
+                predecessors[successor].push(block);
+            }
+        }
+        predecessors
+    }
+
+    /// Blocks reachable from the entry.
+    fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![ENTRY];
+        while let Some(block) = stack.pop() {
+            if std::mem::replace(&mut reachable[block], true) {
+                continue;
+            }
+            stack.extend(&self.blocks[block].successors);
+        }
+        reachable
+    }
+
+    /// `sets[b][a]` is true when `a` dominates `b`, or post-dominates it when
+    /// `reverse` is set. Blocks that can't be reached from the root have
+    /// empty sets.
+    fn dominator_sets(&self, reverse: bool) -> Vec<Vec<bool>> {
+        let count = self.blocks.len();
+        let (root, incoming) = if reverse {
+            let outgoing: Vec<Vec<usize>> = self
+                .blocks
+                .iter()
+                .map(|block| block.successors.clone())
+                .collect();
+            (EXIT, outgoing)
+        } else {
+            (ENTRY, self.predecessors())
+        };
+
+        // Only blocks connected to the root take part
+        let mut connected = vec![false; count];
+        let mut stack = vec![root];
+        let forward = if reverse {
+            self.predecessors()
+        } else {
+            self.blocks
+                .iter()
+                .map(|block| block.successors.clone())
+                .collect()
+        };
+        while let Some(block) = stack.pop() {
+            if std::mem::replace(&mut connected[block], true) {
+                continue;
+            }
+            stack.extend(&forward[block]);
+        }
+
+        let mut sets: Vec<Vec<bool>> = (0..count)
+            .map(|block| {
+                if block == root {
+                    (0..count).map(|other| other == root).collect()
+                } else {
+                    connected.clone()
+                }
+            })
+            .collect();
+        for (block, set) in sets.iter_mut().enumerate() {
+            if !connected[block] {
+                set.iter_mut().for_each(|value| *value = false);
+            }
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in 0..count {
+                if block == root || !connected[block] {
+                    continue;
+                }
+                let mut set = vec![true; count];
+                for &other in incoming[block].iter().filter(|&&other| connected[other]) {
+                    for (value, &dominates) in set.iter_mut().zip(&sets[other]) {
+                        *value &= dominates;
+                    }
+                }
+                set[block] = true;
+                if set != sets[block] {
+                    sets[block] = set;
+                    changed = true;
+                }
+            }
+        }
+        sets
+    }
+}
+
+/// A `break`/`continue` target.
+#[derive(Debug)]
+struct JumpTarget {
+    label: Option<String>,
+    break_to: usize,
+    continue_to: Option<usize>,
+    /// Loops and `switch` take unlabeled `break`; labeled blocks don't
+    unlabeled_break: bool,
+}
+
+/// Builds a [`ControlFlowGraph`] while walking a function body.
+#[derive(Debug)]
+pub struct FlowBuilder {
+    range: ByteRange,
+    blocks: Vec<FlowBlock>,
+    current: usize,
+    jump_targets: Vec<JumpTarget>,
+    handlers: Vec<usize>,
+    /// Enclosing `finally` blocks, and whether a `return` went through them
+    finalizers: Vec<(usize, bool)>,
+}
+
+impl FlowBuilder {
+    /// Start a graph for the function (or module) spanning `range`.
+    pub fn new(range: ByteRange) -> Self {
+        Self {
+            range,
+            blocks: vec![FlowBlock::default(), FlowBlock::default()],
+            current: ENTRY,
+            jump_targets: Vec::new(),
+            handlers: Vec::new(),
+            finalizers: Vec::new(),
+        }
+    }
+
+    /// Record a statement or condition in the current block.
+    pub fn item(&mut self, range: ByteRange) {
+        self.blocks[self.current].items.push(range);
+    }
+
+    /// Create a new, unconnected block.
+    pub fn block(&mut self) -> usize {
+        self.blocks.push(FlowBlock::default());
+        self.blocks.len() - 1
+    }
+
+    /// The block items are currently added to.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Add an edge between two blocks.
+    pub fn edge(&mut self, from: usize, to: usize) {
+        let successors = &mut self.blocks[from].successors;
+        if !successors.contains(&to) {
+            successors.push(to);
+        }
+    }
+
+    /// Continue in `block` without connecting it to the current block.
+    pub fn switch_to(&mut self, block: usize) {
+        self.current = block;
+    }
+
+    /// Flow from the current block into `block` and continue there.
+    pub fn goto(&mut self, block: usize) {
+        self.edge(self.current, block);
+        self.current = block;
+    }
+
+    /// Leave the function (`return`), through the innermost `finally`
+    /// block if there is one. Code after it is unreachable.
+    pub fn exit(&mut self) {
+        let target = match self.finalizers.last_mut() {
+            Some((block, used)) => {
+                *used = true;
+                *block
+            }
+            None => EXIT,
+        };
+        self.edge(self.current, target);
+        self.current = self.block();
+    }
+
+    /// Continue a `return` that went through a `finally` block: add an edge
+    /// from the current block to the next `finally` block or the exit.
+    pub fn exit_edge(&mut self) {
+        let target = match self.finalizers.last_mut() {
+            Some((block, used)) => {
+                *used = true;
+                *block
+            }
+            None => EXIT,
+        };
+        self.edge(self.current, target);
+    }
+
+    /// Add an edge from the current block to the innermost exception
+    /// handler, or the exit outside any `try`.
+    pub fn throw_edge(&mut self) {
+        let target = self.handlers.last().copied().unwrap_or(EXIT);
+        self.edge(self.current, target);
+    }
+
+    /// End the current block after an item that may raise. Inside a `try`
+    /// block it gets an edge to the innermost handler and the code after it
+    /// starts a new block; elsewhere nothing changes.
+    pub fn may_throw(&mut self) {
+        if self.handlers.is_empty() {
+            return;
+        }
+        self.throw_edge();
+        let next = self.block();
+        self.goto(next);
+    }
+
+    /// Raise an exception (`throw`/`raise`). Code after it is unreachable.
+    pub fn throw(&mut self) {
+        self.throw_edge();
+        self.current = self.block();
+    }
+
+    /// Route exceptions raised from now on to `block`.
+    pub fn push_handler(&mut self, block: usize) {
+        self.handlers.push(block);
+    }
+
+    pub fn pop_handler(&mut self) {
+        self.handlers.pop();
+    }
+
+    /// Route `return` from now on through the `finally` block `block`.
+    pub fn push_finalizer(&mut self, block: usize) {
+        self.finalizers.push((block, false));
+    }
+
+    /// Stop routing `return` through the innermost `finally` block.
+    /// Returns whether any `return` went through it.
+    pub fn pop_finalizer(&mut self) -> bool {
+        self.finalizers.pop().is_some_and(|(_, used)| used)
+    }
+
+    /// Register a loop (with a `continue` target) or a `switch`/labeled
+    /// statement (without one).
+    pub fn push_jump_target(
+        &mut self,
+        label: Option<String>,
+        break_to: usize,
+        continue_to: Option<usize>,
+        unlabeled_break: bool,
+    ) {
+        self.jump_targets.push(JumpTarget {
+            label,
+            break_to,
+            continue_to,
+            unlabeled_break,
+        });
+    }
+
+    pub fn pop_jump_target(&mut self) {
+        self.jump_targets.pop();
+    }
+
+    /// Jump out of the innermost loop or `switch`, or the statement with
+    /// `label`.
+    pub fn break_to(&mut self, label: Option<&str>) {
+        let target = self
+            .jump_targets
+            .iter()
+            .rev()
+            .find(|target| match label {
+                Some(label) => target.label.as_deref() == Some(label),
+                None => target.unlabeled_break,
+            })
+            .map(|target| target.break_to);
+        if let Some(target) = target {
+            self.edge(self.current, target);
+        }
+        self.current = self.block();
+    }
+
+    /// Jump to the next iteration of the innermost loop, or the loop with
+    /// `label`.
+    pub fn continue_to(&mut self, label: Option<&str>) {
+        let target = self
+            .jump_targets
+            .iter()
+            .rev()
+            .filter(|target| target.continue_to.is_some())
+            .find(|target| label.map_or(true, |label| target.label.as_deref() == Some(label)))
+            .and_then(|target| target.continue_to);
+        if let Some(target) = target {
+            self.edge(self.current, target);
+        }
+        self.current = self.block();
+    }
+
+    /// Finish the graph; the end of the body falls through to the exit.
+    pub fn finish(mut self) -> ControlFlowGraph {
+        self.edge(self.current, EXIT);
+        ControlFlowGraph {
+            range: self.range,
+            blocks: self.blocks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(start: u32) -> ByteRange {
+        ByteRange::new(start, start + 1)
+    }
+
+    /// `a; if (c) { b; return; } d;`
+    fn if_return() -> ControlFlowGraph {
+        let mut builder = FlowBuilder::new(ByteRange::new(0, 100));
+        builder.item(r(10)); // a
+        builder.item(r(20)); // c
+        let test = builder.current();
+        let then = builder.block();
+        builder.edge(test, then);
+        builder.switch_to(then);
+        builder.item(r(30)); // b
+        builder.item(r(40)); // return
+        builder.exit();
+        builder.item(r(45)); // dead code after return
+        let after = builder.block();
+        builder.edge(builder.current(), after);
+        builder.edge(test, after);
+        builder.switch_to(after);
+        builder.item(r(50)); // d
+        builder.finish()
+    }
+
+    #[test]
+    fn innermost_graph_contains_the_range() {
+        let graph = |start, end| ControlFlowGraph {
+            range: ByteRange::new(start, end),
+            blocks: Vec::new(),
+        };
+        let graphs = [graph(0, 100), graph(0, 100), graph(10, 60), graph(20, 30)];
+
+        let innermost = |range| ControlFlowGraph::innermost(&graphs, range).map(|g| g.range);
+        assert_eq!(innermost(r(25)), Some(ByteRange::new(20, 30)));
+        assert_eq!(innermost(r(40)), Some(ByteRange::new(10, 60)));
+        assert!(std::ptr::eq(
+            ControlFlowGraph::innermost(&graphs, r(80)).unwrap(),
+            &graphs[1]
+        ));
+        assert_eq!(innermost(ByteRange::new(90, 120)), None);
+    }
+
+    #[test]
+    fn reachability() {
+        let graph = if_return();
+        assert!(graph.is_reachable(r(30)));
+        assert!(graph.is_reachable(r(50)));
+        assert!(!graph.is_reachable(r(45)));
+        // Not inside any item
+        assert!(graph.is_reachable(r(90)));
+    }
+
+    #[test]
+    fn dominance() {
+        let graph = if_return();
+        assert!(graph.dominates(r(10), r(50)));
+        assert!(graph.dominates(r(20), r(30)));
+        assert!(!graph.dominates(r(30), r(50)));
+        assert!(!graph.dominates(r(50), r(10)));
+        assert!(!graph.dominates(r(10), r(45)));
+    }
+
+    #[test]
+    fn post_dominance() {
+        let graph = if_return();
+        assert!(graph.post_dominates(r(20), r(10)));
+        assert!(graph.post_dominates(r(40), r(30)));
+        // `d` is skipped by the early return
+        assert!(!graph.post_dominates(r(50), r(10)));
+    }
+
+    #[test]
+    fn exit_points() {
+        let graph = if_return();
+        assert_eq!(graph.exit_points(), vec![r(40), r(50)]);
+
+        let empty = FlowBuilder::new(ByteRange::new(0, 10)).finish();
+        assert_eq!(empty.exit_points(), vec![ByteRange::new(0, 10)]);
+    }
+
+    #[test]
+    fn return_through_finally() {
+        // try { if (c) return; a; } finally { f; } b;
+        let mut builder = FlowBuilder::new(ByteRange::new(0, 100));
+        let after = builder.block();
+        let finally = builder.block();
+        builder.push_finalizer(finally);
+        builder.item(r(10)); // c
+        let test = builder.current();
+        let then = builder.block();
+        builder.edge(test, then);
+        builder.switch_to(then);
+        builder.item(r(20)); // return
+        builder.exit();
+        let rest = builder.block();
+        builder.edge(test, rest);
+        builder.switch_to(rest);
+        builder.item(r(30)); // a
+        builder.goto(finally);
+        assert!(builder.pop_finalizer());
+        builder.item(r(40)); // f
+        builder.exit_edge();
+        builder.goto(after);
+        builder.item(r(50)); // b
+        let graph = builder.finish();
+
+        assert!(graph.post_dominates(r(40), r(10)));
+        assert!(graph.post_dominates(r(40), r(20)));
+        assert!(!graph.post_dominates(r(50), r(10)));
+        assert_eq!(graph.exit_points(), vec![r(40), r(50)]);
+    }
+
+    #[test]
+    fn loops_and_labels() {
+        // outer: while (c) { while (d) { break outer; } e; }
+        let mut builder = FlowBuilder::new(ByteRange::new(0, 100));
+        let header = builder.block();
+        builder.goto(header);
+        builder.item(r(10)); // c
+        let after = builder.block();
+        let body = builder.block();
+        builder.edge(header, body);
+        builder.edge(header, after);
+        builder.push_jump_target(Some("outer".to_string()), after, Some(header), true);
+        builder.switch_to(body);
+
+        let inner_header = builder.block();
+        builder.goto(inner_header);
+        builder.item(r(20)); // d
+        let inner_after = builder.block();
+        let inner_body = builder.block();
+        builder.edge(inner_header, inner_body);
+        builder.edge(inner_header, inner_after);
+        builder.push_jump_target(None, inner_after, Some(inner_header), true);
+        builder.switch_to(inner_body);
+        builder.item(r(30)); // break outer
+        builder.break_to(Some("outer"));
+        builder.edge(builder.current(), inner_header);
+        builder.pop_jump_target();
+        builder.switch_to(inner_after);
+        builder.item(r(40)); // e
+        builder.edge(builder.current(), header);
+        builder.pop_jump_target();
+        builder.switch_to(after);
+        let graph = builder.finish();
+
+        assert!(graph.dominates(r(10), r(40)));
+        assert!(graph.is_reachable(r(40)));
+        assert!(!graph.post_dominates(r(40), r(20)));
+        assert!(graph.post_dominates(r(10), r(40)));
+    }
+}
@@ -0,0 +1,255 @@
+//! Persistent, content-addressed store for per-file index entries.
+//!
+//! Workspace providers parse every file of the workspace before answering
+//! their first cross-file query. The store lets them reuse the per-file
+//! results of earlier processes: entries are keyed on the provider's
+//! namespace, a fingerprint of the workspace's config files, the file
+//! extension and the SHA-256 of the file content. Paths are not part of the
+//! key, so matrix tasks running in separate worktrees of the same repository
+//! share entries.
+
+use crate::disk_store::{DiskStore, DiskStoreStats};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Bumped whenever the on-disk entry layout changes.
+const INDEX_STORE_FORMAT_VERSION: u32 = 1;
+
+/// Entry count and size of an index store directory.
+pub type IndexStoreStats = DiskStoreStats;
+
+/// On-disk index store. Entries live at
+/// `<root>/<namespace>/<key[..2]>/<key[2..]>.json`.
+#[derive(Debug, Clone)]
+pub struct IndexStore {
+    root: PathBuf,
+    namespace: String,
+    config_fingerprint: String,
+    entries: DiskStore,
+}
+
+impl IndexStore {
+    /// Open the store under `root` for one provider.
+    ///
+    /// `namespace` should change whenever the provider's entry type or
+    /// analysis changes (e.g. `javascript-v1`). `config_fingerprint` comes
+    /// from [`IndexStore::config_fingerprint`].
+    pub fn new(
+        root: impl Into<PathBuf>,
+        namespace: impl Into<String>,
+        config_fingerprint: impl Into<String>,
+    ) -> Self {
+        let root = root.into();
+        let namespace = namespace.into();
+        Self {
+            entries: DiskStore::new(root.join(&namespace), INDEX_STORE_FORMAT_VERSION),
+            root,
+            namespace,
+            config_fingerprint: config_fingerprint.into(),
+        }
+    }
+
+    /// Directory all namespaces are stored under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Hash the config files anywhere under `workspace_root` whose names
+    /// match `is_config_file` (e.g. `tsconfig.json`, `pyproject.toml`).
+    /// Editing, adding or removing one of them changes every key, so stale
+    /// entries are never read.
+    pub fn config_fingerprint(
+        workspace_root: &Path,
+        is_config_file: impl Fn(&str) -> bool,
+    ) -> String {
+        let configs = Self::find_config_files(workspace_root, is_config_file);
+        Self::files_fingerprint(workspace_root, &configs)
+    }
+
+    /// Config files anywhere under `workspace_root` whose names match
+    /// `is_config_file`, sorted. Dependency directories (`node_modules`,
+    /// `__pycache__`) and hidden directories are not searched.
+    pub fn find_config_files(
+        workspace_root: &Path,
+        is_config_file: impl Fn(&str) -> bool,
+    ) -> Vec<PathBuf> {
+        let mut configs = Vec::new();
+        let mut pending = vec![workspace_root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    if !(name.starts_with('.') || name == "node_modules" || name == "__pycache__") {
+                        pending.push(entry.path());
+                    }
+                } else if file_type.is_file() && is_config_file(&name) {
+                    configs.push(entry.path());
+                }
+            }
+        }
+        configs.sort();
+        configs
+    }
+
+    /// Hash the paths (relative to `workspace_root` where possible) and
+    /// contents of `files`. Unreadable files hash as empty, so a config that
+    /// appears later still changes the fingerprint.
+    pub fn files_fingerprint(workspace_root: &Path, files: &[PathBuf]) -> String {
+        let mut files: Vec<&PathBuf> = files.iter().collect();
+        files.sort();
+        files.dedup();
+
+        let mut hasher = Sha256::new();
+        for file in files {
+            let name = file.strip_prefix(workspace_root).unwrap_or(file);
+            hasher.update(name.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(fs::read(file).unwrap_or_default());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn key(&self, file_path: &Path, content: &str) -> String {
+        let content_hash = Sha256::digest(content.as_bytes());
+        let extension = file_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut hasher = Sha256::new();
+        for part in [
+            INDEX_STORE_FORMAT_VERSION.to_string(),
+            self.namespace.clone(),
+            self.config_fingerprint.clone(),
+            extension,
+            format!("{content_hash:x}"),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Look up the entry for a file with this content. Unreadable or
+    /// outdated entries are misses.
+    pub fn get<T: DeserializeOwned>(&self, file_path: &Path, content: &str) -> Option<T> {
+        self.entries.get(&self.key(file_path, content))
+    }
+
+    /// Store the entry for a file with this content.
+    pub fn insert<T: Serialize>(
+        &self,
+        file_path: &Path,
+        content: &str,
+        value: &T,
+    ) -> io::Result<()> {
+        self.entries.insert(&self.key(file_path, content), value)
+    }
+
+    /// Entry count and size across all namespaces under `root`.
+    pub fn stats(root: &Path) -> io::Result<IndexStoreStats> {
+        DiskStore::new(root, INDEX_STORE_FORMAT_VERSION).stats()
+    }
+
+    /// Remove entries under `root` that have not been written or read within
+    /// `max_age`. With `dry_run` nothing is deleted.
+    pub fn prune(root: &Path, max_age: Duration, dry_run: bool) -> io::Result<IndexStoreStats> {
+        DiskStore::new(root, INDEX_STORE_FORMAT_VERSION).prune(max_age, dry_run)
+    }
+
+    /// Remove every entry under `root`.
+    pub fn clear(root: &Path) -> io::Result<()> {
+        DiskStore::new(root, INDEX_STORE_FORMAT_VERSION).clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_entries_are_keyed_on_content() {
+        let dir = tempdir().unwrap();
+        let store = IndexStore::new(dir.path(), "test-v1", "config");
+        let path = Path::new("/workspace/a.ts");
+
+        assert_eq!(store.get::<Vec<u32>>(path, "let a = 1;"), None);
+        store.insert(path, "let a = 1;", &vec![1u32, 2]).unwrap();
+        assert_eq!(store.get::<Vec<u32>>(path, "let a = 1;"), Some(vec![1, 2]));
+        // Same content in another worktree hits; different content misses
+        assert_eq!(
+            store.get::<Vec<u32>>(Path::new("/worktree/a.ts"), "let a = 1;"),
+            Some(vec![1, 2])
+        );
+        assert_eq!(store.get::<Vec<u32>>(path, "let a = 2;"), None);
+
+        let stats = IndexStore::stats(dir.path()).unwrap();
+        assert_eq!(stats.entries, 1);
+        IndexStore::clear(dir.path()).unwrap();
+        assert_eq!(store.get::<Vec<u32>>(path, "let a = 1;"), None);
+    }
+
+    #[test]
+    fn test_config_changes_invalidate_entries() {
+        let workspace = tempdir().unwrap();
+        let cache = tempdir().unwrap();
+        let is_config = |name: &str| name.starts_with("tsconfig") && name.ends_with(".json");
+        let path = Path::new("a.ts");
+
+        fs::write(workspace.path().join("tsconfig.json"), "{}").unwrap();
+        let before = IndexStore::config_fingerprint(workspace.path(), is_config);
+        IndexStore::new(cache.path(), "test-v1", before.clone())
+            .insert(path, "x", &1u32)
+            .unwrap();
+
+        fs::write(workspace.path().join("index.ts"), "export {}").unwrap();
+        assert_eq!(
+            IndexStore::config_fingerprint(workspace.path(), is_config),
+            before
+        );
+
+        fs::write(
+            workspace.path().join("tsconfig.json"),
+            r#"{"compilerOptions":{"baseUrl":"."}}"#,
+        )
+        .unwrap();
+        let after = IndexStore::config_fingerprint(workspace.path(), is_config);
+        assert_ne!(after, before);
+        assert_eq!(
+            IndexStore::new(cache.path(), "test-v1", after).get::<u32>(path, "x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nested_configs_are_fingerprinted() {
+        let workspace = tempdir().unwrap();
+        let is_config = |name: &str| name == "pyproject.toml";
+        let nested = workspace.path().join("packages/api");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(workspace.path().join("node_modules/dep")).unwrap();
+        fs::write(nested.join("pyproject.toml"), "[project]").unwrap();
+        fs::write(workspace.path().join("node_modules/dep/pyproject.toml"), "").unwrap();
+
+        assert_eq!(
+            IndexStore::find_config_files(workspace.path(), is_config),
+            vec![nested.join("pyproject.toml")]
+        );
+        let before = IndexStore::config_fingerprint(workspace.path(), is_config);
+        fs::write(nested.join("pyproject.toml"), "[project]\nname = \"api\"").unwrap();
+        assert_ne!(
+            IndexStore::config_fingerprint(workspace.path(), is_config),
+            before
+        );
+    }
+}
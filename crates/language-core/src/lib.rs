@@ -3,16 +3,23 @@
 //! This crate provides the foundational abstractions for symbol indexing
 //! and semantic analysis across different programming languages.
 
+mod disk_store;
+pub mod embedded;
 mod error;
 pub mod filesystem;
+pub mod flow;
+mod index_store;
 mod noop;
 mod provider;
 mod types;
 
+pub use disk_store::{DiskStore, DiskStoreStats};
 pub use error::{SemanticError, SemanticResult};
+pub use flow::ControlFlowGraph;
+pub use index_store::{IndexStore, IndexStoreStats};
 pub use noop::NoopSemanticProvider;
 pub use provider::{ProviderMode, SemanticProvider};
 pub use types::{
     ByteRange, DefinitionKind, DefinitionOptions, DefinitionResult, FileReferences, Position,
-    ReferencesResult, SymbolKind, SymbolLocation,
+    ReferencesResult, ScopeBinding, ScopeInfo, ScopeKind, SymbolKind, SymbolLocation,
 };
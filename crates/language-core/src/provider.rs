@@ -1,7 +1,11 @@
 //! Core trait for semantic analysis providers.
 
-use crate::{ByteRange, DefinitionOptions, DefinitionResult, ReferencesResult, SemanticResult};
-use std::path::Path;
+use crate::{
+    ByteRange, ControlFlowGraph, DefinitionOptions, DefinitionResult, ReferencesResult, ScopeInfo,
+    SemanticResult,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// Provider mode determines the analysis strategy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -97,6 +101,51 @@ pub trait SemanticProvider: Send + Sync {
     /// * `Err(e)` - An error occurred during lookup
     fn get_type(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Option<String>>;
 
+    /// Get the lexical scopes enclosing the given byte range, innermost
+    /// first and ending with the module scope.
+    ///
+    /// Providers without scope information return an empty list.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the file
+    /// * `range` - Byte range of a node in the file
+    fn get_scopes(&self, _file_path: &Path, _range: ByteRange) -> SemanticResult<Vec<ScopeInfo>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the control-flow graph of the innermost function containing the
+    /// given byte range, or of the module's top level outside any function.
+    ///
+    /// Providers without control-flow information return `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the file
+    /// * `range` - Byte range of a node in the file
+    fn get_control_flow(
+        &self,
+        _file_path: &Path,
+        _range: ByteRange,
+    ) -> SemanticResult<Option<ControlFlowGraph>> {
+        Ok(None)
+    }
+
+    /// Get the control-flow graphs of the module's top level and of every
+    /// function in `content`, the source of `file_path`, outermost first.
+    /// Lets callers that hold a file's source build
+    /// all of its graphs once; [`ControlFlowGraph::innermost`] picks the one
+    /// [`get_control_flow`](Self::get_control_flow) would return.
+    ///
+    /// Providers without control-flow information return `None`.
+    fn get_control_flows(
+        &self,
+        _file_path: &Path,
+        _content: &str,
+    ) -> SemanticResult<Option<Vec<ControlFlowGraph>>> {
+        Ok(None)
+    }
+
     /// Notify the provider that a file has been processed.
     ///
     /// This is called after each file is processed during codemod execution.
@@ -109,6 +158,68 @@ pub trait SemanticProvider: Send + Sync {
     /// * `content` - The source content of the file
     fn notify_file_processed(&self, file_path: &Path, content: &str) -> SemanticResult<()>;
 
+    /// Answer queries about `file_path` from `content` instead of the file on
+    /// disk, until called again with `None`.
+    ///
+    /// Used to analyze output that hasn't been written yet. Cached symbols
+    /// for the file follow the content queries see. Providers that don't read
+    /// files keep the default no-op.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the overlaid file
+    /// * `content` - The content to serve, or `None` to read the file again
+    fn set_file_overlay(&self, _file_path: &Path, _content: Option<&str>) -> SemanticResult<()> {
+        Ok(())
+    }
+
+    /// Get the workspace files a file imports.
+    ///
+    /// Only imports that resolve to files in the workspace are returned;
+    /// external packages are skipped. Providers without module resolution
+    /// (and file-scope providers) return an empty list.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the importing file
+    fn get_imports(&self, _file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the workspace files that import a file directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the imported file
+    fn get_importers(&self, _file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the workspace files that import any of the given files, directly
+    /// or transitively. The given files themselves are not included.
+    ///
+    /// The default implementation walks [`get_importers`](Self::get_importers);
+    /// providers that build the whole import graph at once should override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_paths` - Paths to the changed files
+    fn get_dependents(&self, file_paths: &[PathBuf]) -> SemanticResult<Vec<PathBuf>> {
+        let mut seen: HashSet<PathBuf> = file_paths.iter().cloned().collect();
+        let mut queue = file_paths.to_vec();
+        let mut dependents = Vec::new();
+        while let Some(path) = queue.pop() {
+            for importer in self.get_importers(&path)? {
+                if seen.insert(importer.clone()) {
+                    dependents.push(importer.clone());
+                    queue.push(importer);
+                }
+            }
+        }
+        dependents.sort();
+        Ok(dependents)
+    }
+
     /// Check if this provider supports the given language.
     ///
     /// # Arguments
@@ -279,6 +279,66 @@ impl DefinitionResult {
     }
 }
 
+/// The kind of a lexical scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScopeKind {
+    /// The top level of a file
+    Module,
+    /// A function, method, arrow function or lambda body
+    Function,
+    /// A class body
+    Class,
+    /// A block or other nested scope (loops, `catch`, comprehensions)
+    Block,
+}
+
+impl ScopeKind {
+    /// Returns a human-readable name for the scope kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScopeKind::Module => "module",
+            ScopeKind::Function => "function",
+            ScopeKind::Class => "class",
+            ScopeKind::Block => "block",
+        }
+    }
+}
+
+/// A name declared in a scope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeBinding {
+    /// The declared name
+    pub name: String,
+    /// The kind of symbol the name is bound to
+    pub kind: SymbolKind,
+    /// Byte range of the declaring identifier
+    pub range: ByteRange,
+}
+
+/// A lexical scope and the names declared and used in it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeInfo {
+    /// The kind of scope
+    pub kind: ScopeKind,
+    /// Byte range of the node that introduces the scope
+    pub range: ByteRange,
+    /// Names declared directly in this scope
+    pub bindings: Vec<ScopeBinding>,
+    /// Names declared in scopes nested inside this one, sorted
+    pub nested_names: Vec<String>,
+    /// Names used inside this scope (including nested scopes) that are not
+    /// declared in it: bindings of enclosing scopes and globals, sorted
+    pub free_names: Vec<String>,
+}
+
+impl ScopeInfo {
+    /// Whether `name` is declared directly in this scope.
+    pub fn binds(&self, name: &str) -> bool {
+        self.bindings.iter().any(|binding| binding.name == name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,12 +1,14 @@
 //! Accurate mode implementation for workspace-wide lazy indexing.
 
-use crate::cache::SymbolCache;
+use crate::cache::{ExportedSymbol, FileSymbols, ImportedSymbol, Symbol, SymbolCache};
 use crate::error::JsSemanticError;
-use crate::oxc_adapter::{find_symbol_at_range, parse_and_analyze};
+use crate::oxc_adapter::{find_symbol_at_range, is_module_file, parse_and_analyze};
+use crate::scope::scopes_at;
+use crate::type_info::type_at;
 use crate::vfs_fs::VfsFileSystem;
 use language_core::{
     filesystem, ByteRange, DefinitionKind, DefinitionOptions, DefinitionResult, FileReferences,
-    ReferencesResult, SemanticResult, SymbolLocation,
+    IndexStore, ReferencesResult, ScopeInfo, SemanticResult, SymbolKind, SymbolLocation,
 };
 use oxc_resolver::{
     Resolution, ResolveError, ResolveOptions, Resolver, ResolverGeneric, TsconfigDiscovery,
@@ -95,6 +97,8 @@ pub struct AccurateAnalyzer {
     fs_root: VfsPath,
     /// Strategy for discovering workspace files during bulk indexing.
     walker: WorkspaceWalker,
+    /// On-disk store of parsed files shared with other processes
+    index_store: Option<IndexStore>,
 }
 
 impl AccurateAnalyzer {
@@ -146,9 +150,48 @@ impl AccurateAnalyzer {
             indexing_in_progress: RwLock::new(HashSet::new()),
             fs_root,
             walker,
+            index_store: None,
         }
     }
 
+    /// Persist parsed files under `dir` and reuse entries written by
+    /// earlier runs for files whose content is unchanged. Entries are
+    /// invalidated when any `tsconfig`/`jsconfig`/`package.json` in the
+    /// workspace changes, or any config they `extends`.
+    pub fn with_index_dir(mut self, dir: &Path) -> Self {
+        let mut configs = IndexStore::find_config_files(&self.workspace_root, is_config_file);
+        configs.extend(extended_tsconfigs(&configs));
+        let fingerprint = IndexStore::files_fingerprint(&self.workspace_root, &configs);
+        self.index_store = Some(IndexStore::new(
+            dir,
+            format!("javascript-{}", env!("CARGO_PKG_VERSION")),
+            fingerprint,
+        ));
+        self
+    }
+
+    /// Parse `content`, reusing the persisted entry for identical content.
+    fn analyze(&self, file_path: &Path, content: &str) -> Result<FileSymbols, JsSemanticError> {
+        if let Some(symbols) = self
+            .index_store
+            .as_ref()
+            .and_then(|store| store.get(file_path, content))
+        {
+            return Ok(symbols);
+        }
+        let file_symbols = parse_and_analyze(file_path, content)?;
+        if let Some(store) = &self.index_store {
+            if let Err(e) = store.insert(file_path, content, &file_symbols) {
+                log::debug!(
+                    "Failed to persist index entry for {}: {}",
+                    file_path.display(),
+                    e
+                );
+            }
+        }
+        Ok(file_symbols)
+    }
+
     /// Build the real-disk resolver pair. Keeps the historical behavior:
     /// tsconfig is discovered on real disk via `PathBuf::exists`, and the
     /// fallback strips tsconfig entirely so relative imports still
@@ -260,11 +303,11 @@ impl AccurateAnalyzer {
         // Read and parse the file using the virtual filesystem
         let content = self.read_file(&canonical)?;
 
-        let file_symbols = parse_and_analyze(&canonical, &content)?;
+        let file_symbols = self.analyze(&canonical, &content)?;
 
         // Index imported files
-        for import in &file_symbols.imports {
-            if let Ok(resolved) = self.resolve_module(&import.module_specifier, &canonical) {
+        for specifier in &file_symbols.module_requests {
+            if let Ok(resolved) = self.resolve_module(specifier, &canonical) {
                 // Recursively index the imported file
                 let _ = self.ensure_indexed(&resolved);
             }
@@ -343,7 +386,7 @@ impl AccurateAnalyzer {
             return Ok(());
         }
 
-        let file_symbols = parse_and_analyze(file_path, content)?;
+        let file_symbols = self.analyze(file_path, content)?;
         self.cache
             .insert(canonical.clone(), file_symbols, content.to_string());
         self.indexed_files.write().insert(canonical);
@@ -400,7 +443,7 @@ impl AccurateAnalyzer {
                     SymbolLocation::new(
                         canonical,
                         import.range,
-                        language_core::SymbolKind::Import,
+                        SymbolKind::Import,
                         import.local_name.clone(),
                     ),
                     content.to_string(),
@@ -423,7 +466,7 @@ impl AccurateAnalyzer {
                         SymbolLocation::new(
                             canonical,
                             import.range,
-                            language_core::SymbolKind::Import,
+                            SymbolKind::Import,
                             import.local_name.clone(),
                         ),
                         content.to_string(),
@@ -433,6 +476,23 @@ impl AccurateAnalyzer {
             }
         }
 
+        // Check if this is a member of a namespace import (`ns.name`)
+        if let Some(access) = file_symbols.find_member_access_at(range) {
+            let namespace_import = file_symbols
+                .find_symbol_by_id(access.object_symbol_id)
+                .and_then(|binding| file_symbols.find_import_for_symbol(binding))
+                .filter(|import| import.is_namespace && options.resolve_external);
+            let definition = namespace_import.and_then(|import| {
+                let module_path = self
+                    .resolve_module(&import.module_specifier, &canonical)
+                    .ok()?;
+                self.resolve_export(&module_path, &access.property, &mut HashSet::new())
+            });
+            if definition.is_some() {
+                return Ok(definition);
+            }
+        }
+
         // Check if this is a direct symbol
         if let Some(symbol) = find_symbol_at_range(&file_symbols, range) {
             return Ok(Some(DefinitionResult::new(
@@ -475,86 +535,27 @@ impl AccurateAnalyzer {
         if let Some(sym) = symbol {
             // Find all local references (excluding the definition itself)
             for reference in file_symbols.find_references_to(sym.symbol_id) {
-                files_map
-                    .entry(canonical.clone())
-                    .or_default()
-                    .push(SymbolLocation::new(
-                        canonical.clone(),
-                        reference.range,
-                        sym.kind,
-                        sym.name.clone(),
-                    ));
+                push_location(
+                    &mut files_map,
+                    &canonical,
+                    reference.range,
+                    reference_kind(sym.kind, reference.is_type),
+                    &sym.name,
+                );
             }
 
-            // Check if this symbol is exported
-            let is_exported = file_symbols
+            // Names this file exports the symbol under
+            let export_names: Vec<String> = file_symbols
                 .exports
                 .iter()
-                .any(|e| e.local_symbol_id == Some(sym.symbol_id));
+                .filter(|e| e.local_symbol_id == Some(sym.symbol_id))
+                .map(|e| e.name.clone())
+                .collect();
 
-            if is_exported {
+            if !export_names.is_empty() {
                 // Index all files in workspace that might import this
                 self.index_workspace_files()?;
-
-                // Search all indexed files for imports of this module
-                for cached_path in self.cache.files() {
-                    if cached_path == canonical {
-                        continue;
-                    }
-
-                    if let Some((other_symbols, _)) = self.cache.get(&cached_path) {
-                        // Check imports from this file
-                        for import in &other_symbols.imports {
-                            // Try to resolve the import
-                            if let Ok(resolved) =
-                                self.resolve_module(&import.module_specifier, &cached_path)
-                            {
-                                if resolved == canonical {
-                                    // This file imports from our file
-                                    let matches_name = import
-                                        .imported_name
-                                        .as_ref()
-                                        .map(|n| n == &sym.name)
-                                        .unwrap_or(false)
-                                        || import.local_name == sym.name;
-
-                                    if matches_name {
-                                        files_map.entry(cached_path.clone()).or_default().push(
-                                            SymbolLocation::new(
-                                                cached_path.clone(),
-                                                import.range,
-                                                language_core::SymbolKind::Import,
-                                                import.local_name.clone(),
-                                            ),
-                                        );
-
-                                        // Also find references to this import in the file
-                                        let import_symbol = other_symbols
-                                            .symbols
-                                            .iter()
-                                            .find(|s| s.name == import.local_name);
-
-                                        if let Some(imp_sym) = import_symbol {
-                                            for ref_in_other in
-                                                other_symbols.find_references_to(imp_sym.symbol_id)
-                                            {
-                                                files_map
-                                                    .entry(cached_path.clone())
-                                                    .or_default()
-                                                    .push(SymbolLocation::new(
-                                                        cached_path.clone(),
-                                                        ref_in_other.range,
-                                                        sym.kind,
-                                                        import.local_name.clone(),
-                                                    ));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+                self.collect_cross_file_references(&canonical, sym, export_names, &mut files_map);
             }
         }
 
@@ -568,6 +569,8 @@ impl AccurateAnalyzer {
                 self.cache.get(&path).map(|(_, c)| c).unwrap_or_default()
             };
 
+            let mut locations = locations;
+            locations.sort_by_key(|location| (location.range.start, location.range.end));
             result.add_file(FileReferences::new(path, file_content, locations));
         }
 
@@ -596,11 +599,8 @@ impl AccurateAnalyzer {
 
         for entry in walker.flatten() {
             let path = entry.path();
-            if path.is_file() {
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs") {
-                    let _ = self.ensure_indexed(path);
-                }
+            if path.is_file() && is_module_file(path) {
+                let _ = self.ensure_indexed(path);
             }
         }
 
@@ -644,14 +644,6 @@ impl AccurateAnalyzer {
             Err(_) => return Ok(None), // External module, can't resolve
         };
 
-        // Ensure the target file is indexed
-        let _ = self.ensure_indexed(&resolved_path);
-
-        let (file_symbols, file_content) = match self.cache.get(&resolved_path) {
-            Some(entry) => entry,
-            None => return Ok(None),
-        };
-
         // Find the exported symbol
         let export_name = if is_default {
             "default"
@@ -659,50 +651,348 @@ impl AccurateAnalyzer {
             imported_name.unwrap_or(local_name)
         };
 
-        if let Some(export) = file_symbols.find_export_by_name(export_name) {
-            if let Some(local_id) = export.local_symbol_id {
-                if let Some(symbol) = file_symbols.find_symbol_by_id(local_id) {
-                    return Ok(Some(DefinitionResult::new(
+        Ok(self.resolve_export(&resolved_path, export_name, &mut HashSet::new()))
+    }
+
+    /// Find the definition a module exports under `name`, following
+    /// re-exports (`export { a } from`, `export * from` and imported
+    /// bindings exported again) through barrel files. Falls back to the
+    /// export statement when the chain leaves the workspace.
+    fn resolve_export(
+        &self,
+        module_path: &Path,
+        name: &str,
+        visited: &mut HashSet<(PathBuf, String)>,
+    ) -> Option<DefinitionResult> {
+        if !visited.insert((module_path.to_path_buf(), name.to_string())) {
+            return None;
+        }
+
+        // Ensure the target file is indexed
+        let _ = self.ensure_indexed(module_path);
+        let (file_symbols, file_content) = self.cache.get(module_path)?;
+
+        if let Some(export) = file_symbols.find_export_by_name(name) {
+            if let Some(symbol) = export
+                .local_symbol_id
+                .and_then(|local_id| file_symbols.find_symbol_by_id(local_id))
+            {
+                // `import { a } from './a'; export { a };`
+                let forwarded = file_symbols
+                    .find_import_for_symbol(symbol)
+                    .filter(|import| !import.is_namespace)
+                    .and_then(|import| {
+                        let target = self
+                            .resolve_module(&import.module_specifier, module_path)
+                            .ok()?;
+                        let imported = if import.is_default {
+                            "default"
+                        } else {
+                            import
+                                .imported_name
+                                .as_deref()
+                                .unwrap_or(&import.local_name)
+                        };
+                        self.resolve_export(&target, imported, visited)
+                    });
+                return Some(forwarded.unwrap_or_else(|| {
+                    DefinitionResult::new(
                         SymbolLocation::new(
-                            resolved_path,
+                            module_path.to_path_buf(),
                             symbol.range,
                             symbol.kind,
                             symbol.name.clone(),
                         ),
-                        file_content,
+                        file_content.clone(),
                         DefinitionKind::External,
-                    )));
+                    )
+                }));
+            }
+            if let (Some(source), Some(imported)) = (&export.re_export_from, &export.imported_name)
+            {
+                if let Some(def) = self
+                    .resolve_module(source, module_path)
+                    .ok()
+                    .and_then(|target| self.resolve_export(&target, imported, visited))
+                {
+                    return Some(def);
                 }
             }
-            return Ok(Some(DefinitionResult::new(
+            return Some(DefinitionResult::new(
                 SymbolLocation::new(
-                    resolved_path,
+                    module_path.to_path_buf(),
                     export.range,
-                    language_core::SymbolKind::Export,
+                    SymbolKind::Export,
                     export.name.clone(),
                 ),
                 file_content,
                 DefinitionKind::External,
-            )));
+            ));
         }
 
-        // Check for default export
-        if is_default {
-            if let Some(export) = file_symbols.get_default_export() {
-                return Ok(Some(DefinitionResult::new(
-                    SymbolLocation::new(
-                        resolved_path,
-                        export.range,
-                        language_core::SymbolKind::Export,
-                        "default".to_string(),
-                    ),
-                    file_content,
-                    DefinitionKind::External,
-                )));
+        // `export * from` forwards every name except `default`
+        if name == "default" {
+            return None;
+        }
+        file_symbols
+            .exports
+            .iter()
+            .filter(|e| e.name == "*")
+            .filter_map(|e| e.re_export_from.as_ref())
+            .find_map(|source| {
+                let target = self.resolve_module(source, module_path).ok()?;
+                self.resolve_export(&target, name, visited)
+            })
+    }
+
+    /// Find the imports, re-export specifiers and namespace member accesses
+    /// in other workspace files that reach `symbol`, following re-export
+    /// chains through barrel files. `export_names` are the names
+    /// `file_path` exports the symbol under.
+    fn collect_cross_file_references(
+        &self,
+        file_path: &Path,
+        symbol: &Symbol,
+        export_names: Vec<String>,
+        files_map: &mut HashMap<PathBuf, Vec<SymbolLocation>>,
+    ) {
+        // Map each module to the imports and re-exports that point at it
+        let mut modules: HashMap<PathBuf, FileSymbols> = HashMap::new();
+        let mut importers: HashMap<PathBuf, Vec<(PathBuf, ImportedSymbol)>> = HashMap::new();
+        let mut re_exporters: HashMap<PathBuf, Vec<(PathBuf, ExportedSymbol)>> = HashMap::new();
+        for path in self.cache.files() {
+            let Some((file_symbols, _)) = self.cache.get(&path) else {
+                continue;
+            };
+            if path != file_path {
+                for import in &file_symbols.imports {
+                    if let Ok(target) = self.resolve_module(&import.module_specifier, &path) {
+                        importers
+                            .entry(target)
+                            .or_default()
+                            .push((path.clone(), import.clone()));
+                    }
+                }
+            }
+            for export in &file_symbols.exports {
+                let Some(source) = &export.re_export_from else {
+                    continue;
+                };
+                if let Ok(target) = self.resolve_module(source, &path) {
+                    re_exporters
+                        .entry(target)
+                        .or_default()
+                        .push((path.clone(), export.clone()));
+                }
             }
+            modules.insert(path, file_symbols);
         }
 
-        Ok(None)
+        // Walk every (module, export name) that reaches the symbol
+        let mut seen: HashSet<(PathBuf, String, ExportTarget)> = HashSet::new();
+        let mut queue: Vec<(PathBuf, String, ExportTarget)> = export_names
+            .into_iter()
+            .map(|name| (file_path.to_path_buf(), name, ExportTarget::Symbol))
+            .collect();
+        // Namespace bindings (file, binding symbol ID, module it stands for)
+        let mut namespaces: HashSet<(PathBuf, u32, PathBuf)> = HashSet::new();
+
+        while let Some((module, name, target)) = queue.pop() {
+            if !seen.insert((module.clone(), name.clone(), target.clone())) {
+                continue;
+            }
+
+            for (path, export) in re_exporters.get(&module).into_iter().flatten() {
+                if export.is_namespace {
+                    // `export * as ns from`
+                    if target == ExportTarget::Symbol {
+                        let namespace = ExportTarget::Namespace(module.clone());
+                        queue.push((path.clone(), export.name.clone(), namespace));
+                    }
+                } else if export.name == "*" {
+                    // `export * from` does not forward default exports
+                    if name != "default" {
+                        queue.push((path.clone(), name.clone(), target.clone()));
+                    }
+                } else if export.imported_name.as_deref() == Some(name.as_str()) {
+                    // `export { name as alias } from`
+                    if let (ExportTarget::Symbol, Some(range)) = (&target, export.imported_range) {
+                        push_location(files_map, path, range, symbol.kind, &name);
+                    }
+                    queue.push((path.clone(), export.name.clone(), target.clone()));
+                }
+            }
+
+            for (path, import) in importers.get(&module).into_iter().flatten() {
+                let Some(other_symbols) = modules.get(path) else {
+                    continue;
+                };
+                let Some(binding) = other_symbols.find_import_binding(import) else {
+                    continue;
+                };
+                let binding_target = if import.is_namespace {
+                    // `import * as ns from`
+                    if target != ExportTarget::Symbol {
+                        continue;
+                    }
+                    ExportTarget::Namespace(module.clone())
+                } else {
+                    let imported = if import.is_default {
+                        "default"
+                    } else {
+                        import
+                            .imported_name
+                            .as_deref()
+                            .unwrap_or(&import.local_name)
+                    };
+                    if imported != name {
+                        continue;
+                    }
+                    target.clone()
+                };
+
+                match &binding_target {
+                    ExportTarget::Symbol => {
+                        push_location(
+                            files_map,
+                            path,
+                            import.range,
+                            SymbolKind::Import,
+                            &import.local_name,
+                        );
+                        for reference in other_symbols.find_references_to(binding.symbol_id) {
+                            push_location(
+                                files_map,
+                                path,
+                                reference.range,
+                                reference_kind(symbol.kind, reference.is_type),
+                                &import.local_name,
+                            );
+                        }
+                    }
+                    ExportTarget::Namespace(source) => {
+                        namespaces.insert((path.clone(), binding.symbol_id, source.clone()));
+                    }
+                }
+
+                // `import { a } from './a'; export { a };`
+                for export in other_symbols
+                    .exports
+                    .iter()
+                    .filter(|e| e.local_symbol_id == Some(binding.symbol_id))
+                {
+                    queue.push((path.clone(), export.name.clone(), binding_target.clone()));
+                }
+            }
+        }
+
+        // Member accesses on namespace bindings (`ns.name`, `ns.Name`)
+        for (path, binding_id, module) in namespaces {
+            let names: HashSet<&str> = seen
+                .iter()
+                .filter(|(m, _, t)| *m == module && *t == ExportTarget::Symbol)
+                .map(|(_, name, _)| name.as_str())
+                .collect();
+            let Some(other_symbols) = modules.get(&path) else {
+                continue;
+            };
+            for access in other_symbols.member_accesses.iter().filter(|access| {
+                access.object_symbol_id == binding_id && names.contains(access.property.as_str())
+            }) {
+                push_location(
+                    files_map,
+                    &path,
+                    access.range,
+                    reference_kind(symbol.kind, access.is_type),
+                    &access.property,
+                );
+            }
+        }
+    }
+
+    /// Workspace files a file depends on through its import and re-export
+    /// declarations. Unresolvable specifiers (external packages) and files
+    /// inside `node_modules` are skipped.
+    pub fn get_imports(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        let canonical = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        self.ensure_indexed(&canonical)?;
+
+        Ok(match self.cache.get(&canonical) {
+            Some((file_symbols, _)) => self.resolve_dependencies(&canonical, &file_symbols),
+            None => Vec::new(),
+        })
+    }
+
+    /// Workspace files that import or re-export from a file directly.
+    pub fn get_importers(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        let canonical = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        Ok(self
+            .dependency_graph()?
+            .remove(&canonical)
+            .unwrap_or_default())
+    }
+
+    /// Workspace files that transitively import any of `file_paths`,
+    /// excluding `file_paths` themselves, sorted by path.
+    pub fn get_dependents(&self, file_paths: &[PathBuf]) -> SemanticResult<Vec<PathBuf>> {
+        let importers = self.dependency_graph()?;
+        let roots: HashSet<PathBuf> = file_paths
+            .iter()
+            .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+            .collect();
+
+        let mut seen = roots.clone();
+        let mut queue: Vec<PathBuf> = roots.into_iter().collect();
+        let mut dependents = Vec::new();
+        while let Some(path) = queue.pop() {
+            for importer in importers.get(&path).into_iter().flatten() {
+                if seen.insert(importer.clone()) {
+                    dependents.push(importer.clone());
+                    queue.push(importer.clone());
+                }
+            }
+        }
+        dependents.sort();
+        Ok(dependents)
+    }
+
+    /// Index the whole workspace and map each file to the files importing it.
+    fn dependency_graph(&self) -> SemanticResult<HashMap<PathBuf, Vec<PathBuf>>> {
+        self.index_workspace_files()?;
+
+        let mut importers: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        let mut files = self.cache.files();
+        files.sort();
+        for path in files {
+            if let Some((file_symbols, _)) = self.cache.get(&path) {
+                for dependency in self.resolve_dependencies(&path, &file_symbols) {
+                    importers.entry(dependency).or_default().push(path.clone());
+                }
+            }
+        }
+        Ok(importers)
+    }
+
+    /// Resolve a file's module requests to workspace files, deduplicated and
+    /// in source order.
+    fn resolve_dependencies(&self, file_path: &Path, file_symbols: &FileSymbols) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        file_symbols
+            .module_requests
+            .iter()
+            .filter_map(|specifier| self.resolve_module(specifier, file_path).ok())
+            .filter(|resolved| {
+                resolved.starts_with(&self.workspace_root)
+                    && !resolved
+                        .components()
+                        .any(|component| component.as_os_str() == "node_modules")
+            })
+            .filter(|resolved| seen.insert(resolved.clone()))
+            .collect()
     }
 
     /// Get the symbol cache (for testing/debugging).
@@ -717,17 +1007,67 @@ impl AccurateAnalyzer {
         self.indexing_in_progress.write().clear();
     }
 
-    /// Gets the type of a symbol at the given byte range.
-    /// Type inference is not yet fully implemented, returns None.
-    #[allow(dead_code)]
+    /// Gets the declared type of the node at the given byte range,
+    /// following imports across the workspace.
     pub fn get_type(
         &self,
-        _file_path: &Path,
-        _content: &str,
-        _range: ByteRange,
+        file_path: &Path,
+        content: &str,
+        range: ByteRange,
     ) -> SemanticResult<Option<String>> {
-        // TODO: Implement type inference using oxc's type system
-        Ok(None)
+        self.process_file(file_path, content)?;
+
+        let canonical = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        let (file_symbols, _) =
+            self.cache
+                .get(&canonical)
+                .ok_or_else(|| JsSemanticError::FileNotCached {
+                    path: canonical.clone(),
+                })?;
+
+        let resolve_import = |import: &ImportedSymbol, from_path: &Path| {
+            let resolved_path = self
+                .resolve_module(&import.module_specifier, from_path)
+                .ok()?;
+            let _ = self.ensure_indexed(&resolved_path);
+            let canonical = resolved_path
+                .canonicalize()
+                .unwrap_or_else(|_| resolved_path.clone());
+            let (symbols, _) = self.cache.get(&canonical)?;
+            Some((canonical, symbols))
+        };
+
+        Ok(type_at(
+            &canonical,
+            &file_symbols,
+            content,
+            range,
+            &resolve_import,
+        ))
+    }
+
+    /// Gets the scopes enclosing the given byte range, innermost first.
+    pub fn get_scopes(
+        &self,
+        file_path: &Path,
+        content: &str,
+        range: ByteRange,
+    ) -> SemanticResult<Vec<ScopeInfo>> {
+        self.process_file(file_path, content)?;
+
+        let canonical = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        let (file_symbols, _) =
+            self.cache
+                .get(&canonical)
+                .ok_or_else(|| JsSemanticError::FileNotCached {
+                    path: canonical.clone(),
+                })?;
+
+        Ok(scopes_at(&file_symbols, range))
     }
 }
 
@@ -740,6 +1080,165 @@ impl std::fmt::Debug for AccurateAnalyzer {
     }
 }
 
+/// What an export name stands for while following re-export chains.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ExportTarget {
+    /// The symbol whose references are being collected
+    Symbol,
+    /// The namespace object of a module that exports the symbol
+    Namespace(PathBuf),
+}
+
+fn push_location(
+    files_map: &mut HashMap<PathBuf, Vec<SymbolLocation>>,
+    path: &Path,
+    range: ByteRange,
+    kind: SymbolKind,
+    name: &str,
+) {
+    files_map
+        .entry(path.to_path_buf())
+        .or_default()
+        .push(SymbolLocation::new(
+            path.to_path_buf(),
+            range,
+            kind,
+            name.to_string(),
+        ));
+}
+
+/// Kind reported for a reference: `Type` in type positions, otherwise the
+/// kind of the referenced symbol.
+fn reference_kind(kind: SymbolKind, is_type: bool) -> SymbolKind {
+    if is_type {
+        SymbolKind::Type
+    } else {
+        kind
+    }
+}
+
+/// Workspace files whose content affects module resolution.
+fn is_config_file(name: &str) -> bool {
+    name == "package.json"
+        || ((name.starts_with("tsconfig") || name.starts_with("jsconfig"))
+            && name.ends_with(".json"))
+}
+
+/// Configs reached through the `extends` chains of the tsconfig/jsconfig
+/// files in `configs`, which may live outside the workspace (a shared base
+/// config in a parent directory or a package in `node_modules`).
+fn extended_tsconfigs(configs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut seen: HashSet<PathBuf> = configs.iter().cloned().collect();
+    let mut pending: Vec<PathBuf> = configs
+        .iter()
+        .filter(|path| path.file_name().is_some_and(|name| name != "package.json"))
+        .cloned()
+        .collect();
+    let mut extended = Vec::new();
+    while let Some(config) = pending.pop() {
+        let Some(json) = std::fs::read_to_string(&config).ok().and_then(|content| {
+            serde_json::from_str::<serde_json::Value>(&strip_jsonc(&content)).ok()
+        }) else {
+            continue;
+        };
+        let specifiers = match json.get("extends") {
+            Some(serde_json::Value::String(specifier)) => vec![specifier.as_str()],
+            Some(serde_json::Value::Array(specifiers)) => specifiers
+                .iter()
+                .filter_map(|value| value.as_str())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let config_dir = config.parent().unwrap_or(Path::new("."));
+        for specifier in specifiers {
+            let Some(path) = resolve_tsconfig_extends(config_dir, specifier) else {
+                continue;
+            };
+            if seen.insert(path.clone()) {
+                extended.push(path.clone());
+                pending.push(path);
+            }
+        }
+    }
+    extended
+}
+
+/// Resolve an `extends` specifier the way TypeScript does: relative and
+/// absolute paths against the config's directory, package names through the
+/// nearest `node_modules`. A `.json` extension is optional.
+fn resolve_tsconfig_extends(config_dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| -> Option<PathBuf> {
+        if path.is_file() {
+            return Some(path);
+        }
+        let mut json = path.clone().into_os_string();
+        json.push(".json");
+        let json = PathBuf::from(json);
+        if json.is_file() {
+            return Some(json);
+        }
+        let nested = path.join("tsconfig.json");
+        nested.is_file().then_some(nested)
+    };
+
+    if specifier.starts_with('.') || Path::new(specifier).is_absolute() {
+        return with_json(config_dir.join(specifier));
+    }
+    config_dir
+        .ancestors()
+        .find_map(|dir| with_json(dir.join("node_modules").join(specifier)))
+}
+
+/// Drop comments and trailing commas from a tsconfig so it parses as JSON.
+fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(ch) = chars.next() {
+        if in_string {
+            output.push(ch);
+            match ch {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (ch, chars.peek().copied()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(ch);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for next in chars.by_ref() {
+                    if previous == Some('*') && next == '/' {
+                        break;
+                    }
+                    previous = Some(next);
+                }
+            }
+            (',', _) => {
+                let rest = chars.clone().find(|next| !next.is_whitespace());
+                if !matches!(rest, Some('}' | ']')) {
+                    output.push(ch);
+                }
+            }
+            _ => output.push(ch),
+        }
+    }
+    output
+}
+
 /// Shared `oxc_resolver` settings used by both the physical- and
 /// VFS-backed resolver pairs. Kept in one place so the two variants
 /// behave identically for everything except filesystem access.
@@ -851,11 +1350,7 @@ fn walk_vfs_tree(start: &VfsPath, prefix: &str, on_file: &mut dyn FnMut(&Path))
             }
             VfsFileType::File => {
                 let path_str = entry.as_str();
-                let ext = Path::new(path_str)
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("");
-                if matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs") {
+                if is_module_file(Path::new(path_str)) {
                     // Map the VFS path ("/app/src/foo.ts" or "src/foo.ts")
                     // back to the absolute form that matches the analyzer's
                     // cache keys. If the VFS already gave us the absolute
@@ -1106,6 +1601,306 @@ console.log(x);
         }
     }
 
+    #[test]
+    fn test_accurate_import_graph() {
+        let workspace = create_test_workspace();
+        let root = workspace.path().canonicalize().unwrap();
+        fs::write(root.join("index.ts"), "export * from './utils';\n").unwrap();
+        fs::write(
+            root.join("app.ts"),
+            "import { add } from './index';\nimport 'lodash';\nadd(1, 2);\n",
+        )
+        .unwrap();
+        fs::write(root.join("setup.ts"), "import './app';\n").unwrap();
+        fs::write(root.join("unrelated.ts"), "export const x = 1;\n").unwrap();
+
+        let analyzer = AccurateAnalyzer::new(root.clone());
+
+        assert_eq!(
+            analyzer.get_imports(&root.join("app.ts")).unwrap(),
+            vec![root.join("index.ts")]
+        );
+        assert_eq!(
+            analyzer.get_importers(&root.join("utils.ts")).unwrap(),
+            vec![root.join("index.ts"), root.join("main.ts")]
+        );
+        assert_eq!(
+            analyzer.get_dependents(&[root.join("utils.ts")]).unwrap(),
+            vec![
+                root.join("app.ts"),
+                root.join("index.ts"),
+                root.join("main.ts"),
+                root.join("setup.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accurate_persisted_index() {
+        let index_dir = TempDir::new().unwrap();
+        let first = create_test_workspace();
+        let analyzer =
+            AccurateAnalyzer::new(first.path().to_path_buf()).with_index_dir(index_dir.path());
+        analyzer.index_workspace_files().unwrap();
+        let stats = IndexStore::stats(index_dir.path()).unwrap();
+        assert_eq!(stats.entries, 2);
+
+        // A second checkout of the same sources is served from the store
+        let second = create_test_workspace();
+        let analyzer =
+            AccurateAnalyzer::new(second.path().to_path_buf()).with_index_dir(index_dir.path());
+        let root = second.path().canonicalize().unwrap();
+        assert_eq!(
+            analyzer.get_importers(&root.join("utils.ts")).unwrap(),
+            vec![root.join("main.ts")]
+        );
+        assert_eq!(IndexStore::stats(index_dir.path()).unwrap(), stats);
+
+        // Changing tsconfig.json invalidates every entry
+        fs::write(second.path().join("tsconfig.json"), "{}").unwrap();
+        let analyzer =
+            AccurateAnalyzer::new(second.path().to_path_buf()).with_index_dir(index_dir.path());
+        analyzer.index_workspace_files().unwrap();
+        assert_eq!(IndexStore::stats(index_dir.path()).unwrap().entries, 4);
+    }
+
+    #[test]
+    fn test_index_fingerprint_covers_nested_and_extended_configs() {
+        let index_dir = TempDir::new().unwrap();
+        let workspace = create_test_workspace();
+        let root = workspace.path();
+        fs::create_dir_all(root.join("packages/app")).unwrap();
+        fs::create_dir_all(root.join("node_modules/@acme/tsconfig")).unwrap();
+        fs::write(
+            root.join("packages/app/tsconfig.json"),
+            r#"{
+                // shared settings
+                "extends": ["@acme/tsconfig/base", "../../tsconfig.base"],
+            }"#,
+        )
+        .unwrap();
+        fs::write(root.join("node_modules/@acme/tsconfig/base.json"), "{}").unwrap();
+        fs::write(root.join("tsconfig.base.json"), "{}").unwrap();
+
+        let entries = |analyzer: AccurateAnalyzer| {
+            analyzer.index_workspace_files().unwrap();
+            IndexStore::stats(index_dir.path()).unwrap().entries
+        };
+        let analyze = || AccurateAnalyzer::new(root.to_path_buf()).with_index_dir(index_dir.path());
+        let initial = entries(analyze());
+        assert_eq!(entries(analyze()), initial);
+
+        fs::write(root.join("packages/app/tsconfig.json"), "{}").unwrap();
+        let after_nested = entries(analyze());
+        assert!(after_nested > initial);
+
+        fs::write(
+            root.join("packages/app/tsconfig.json"),
+            r#"{"extends": "@acme/tsconfig/base"}"#,
+        )
+        .unwrap();
+        let with_extends = entries(analyze());
+        fs::write(
+            root.join("node_modules/@acme/tsconfig/base.json"),
+            r#"{"compilerOptions":{"strict":true}}"#,
+        )
+        .unwrap();
+        assert!(entries(analyze()) > with_extends);
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        assert_eq!(
+            strip_jsonc("{\n  // c\n  \"a\": \"//x\", /* b */ \"b\": [1,],\n}"),
+            "{\n  \n  \"a\": \"//x\",  \"b\": [1]\n}"
+        );
+    }
+
+    /// A monorepo where `apps/web` consumes `@acme/ui` through barrel files:
+    /// `index.ts` re-exports `components/index.ts`, which re-exports
+    /// `button.ts`, plus an aliased and a namespace re-export.
+    fn create_barrel_monorepo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": { "@acme/ui": ["./packages/ui/src/index.ts"] }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let ui = root.join("packages/ui/src");
+        fs::create_dir_all(ui.join("components")).unwrap();
+        fs::write(
+            ui.join("button.ts"),
+            "export interface ButtonProps { label: string }\n\
+             export function Button(props: ButtonProps) { return props.label; }\n",
+        )
+        .unwrap();
+        fs::write(
+            ui.join("components/index.ts"),
+            "export * from '../button';\n",
+        )
+        .unwrap();
+        fs::write(
+            ui.join("index.ts"),
+            "export * from './components';\n\
+             export { Button as UiButton } from './button';\n\
+             export * as ui from './button';\n",
+        )
+        .unwrap();
+
+        let web = root.join("apps/web/src");
+        fs::create_dir_all(&web).unwrap();
+        fs::write(
+            web.join("page.ts"),
+            "import { Button, type ButtonProps } from '@acme/ui';\n\
+             import * as Ui from '@acme/ui';\n\
+             import { ui, UiButton } from '@acme/ui';\n\
+             const props: ButtonProps = { label: 'a' };\n\
+             let other: Ui.ButtonProps = props;\n\
+             Button(props);\n\
+             Ui.Button(other);\n\
+             ui.Button(props);\n\
+             UiButton(props);\n",
+        )
+        .unwrap();
+
+        dir
+    }
+
+    /// `(file name, referenced text, kind)` for every location, sorted.
+    fn reference_texts(references: &ReferencesResult) -> Vec<(String, String, &'static str)> {
+        let mut texts: Vec<_> = references
+            .files
+            .iter()
+            .flat_map(|file| {
+                let name = file.file_path.file_name().unwrap().to_string_lossy();
+                file.locations.iter().map(move |location| {
+                    let range = location.range.start as usize..location.range.end as usize;
+                    (
+                        name.to_string(),
+                        file.content[range].to_string(),
+                        location.kind.as_str(),
+                    )
+                })
+            })
+            .collect();
+        texts.sort();
+        texts
+    }
+
+    /// Range of the `nth` occurrence of `word` in `content`.
+    fn word_range(content: &str, word: &str, nth: usize) -> ByteRange {
+        let start = content.match_indices(word).nth(nth).unwrap().0 as u32;
+        ByteRange::new(start, start + word.len() as u32)
+    }
+
+    #[test]
+    fn test_accurate_find_references_through_barrels() {
+        let workspace = create_barrel_monorepo();
+        let root = workspace.path().canonicalize().unwrap();
+        let analyzer = AccurateAnalyzer::new(root.clone());
+
+        let button_path = root.join("packages/ui/src/button.ts");
+        let content = fs::read_to_string(&button_path).unwrap();
+        let references = analyzer
+            .find_references(&button_path, &content, word_range(&content, "Button", 1))
+            .unwrap();
+
+        let reference =
+            |file: &str, text: &str, kind: &'static str| (file.to_string(), text.to_string(), kind);
+        assert_eq!(
+            reference_texts(&references),
+            vec![
+                // export { Button as UiButton } from './button'
+                reference("index.ts", "Button", "function"),
+                // Button(props), Ui.Button(other), ui.Button(props)
+                reference("page.ts", "Button", "function"),
+                reference("page.ts", "Button", "function"),
+                reference("page.ts", "Button", "function"),
+                reference("page.ts", "Button", "import"),
+                reference("page.ts", "UiButton", "function"),
+                reference("page.ts", "UiButton", "import"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accurate_find_references_type_positions() {
+        let workspace = create_barrel_monorepo();
+        let root = workspace.path().canonicalize().unwrap();
+        let analyzer = AccurateAnalyzer::new(root.clone());
+
+        let button_path = root.join("packages/ui/src/button.ts");
+        let content = fs::read_to_string(&button_path).unwrap();
+        let references = analyzer
+            .find_references(
+                &button_path,
+                &content,
+                word_range(&content, "ButtonProps", 0),
+            )
+            .unwrap();
+
+        let texts = reference_texts(&references);
+        let types: Vec<_> = texts
+            .iter()
+            .filter(|(_, _, kind)| *kind == "type")
+            .map(|(file, text, _)| (file.as_str(), text.as_str()))
+            .collect();
+        // props: ButtonProps (button.ts), ButtonProps and Ui.ButtonProps (page.ts)
+        assert_eq!(
+            types,
+            vec![
+                ("button.ts", "ButtonProps"),
+                ("page.ts", "ButtonProps"),
+                ("page.ts", "ButtonProps"),
+            ]
+        );
+        assert_eq!(
+            texts
+                .iter()
+                .filter(|(file, text, kind)| file == "page.ts"
+                    && text.ends_with("ButtonProps")
+                    && *kind == "import")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_accurate_get_definition_through_barrels() {
+        let workspace = create_barrel_monorepo();
+        let root = workspace.path().canonicalize().unwrap();
+        let analyzer = AccurateAnalyzer::new(root.clone());
+        let button_path = root.join("packages/ui/src/button.ts");
+
+        let page_path = root.join("apps/web/src/page.ts");
+        let content = fs::read_to_string(&page_path).unwrap();
+        let options = DefinitionOptions {
+            resolve_external: true,
+        };
+        // import { Button }, import { UiButton }, Ui.Button
+        let member = word_range(&content, "Ui.Button(", 0);
+        for range in [
+            word_range(&content, "Button", 0),
+            word_range(&content, "UiButton", 0),
+            ByteRange::new(member.start + 3, member.end - 1),
+        ] {
+            let definition = analyzer
+                .get_definition(&page_path, &content, range, options)
+                .unwrap()
+                .expect("definition through barrels");
+            assert_eq!(definition.location.file_path, button_path);
+            assert_eq!(definition.location.name, "Button");
+            assert_eq!(definition.kind, DefinitionKind::External);
+        }
+    }
+
     #[test]
     fn test_accurate_get_type() {
         let workspace = create_test_workspace();
@@ -1122,9 +1917,37 @@ console.log(x);
             ByteRange::new(pi_pos as u32, (pi_pos + 2) as u32),
         );
 
-        // Type inference not yet implemented, should return None
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_none());
+        assert_eq!(result.unwrap().as_deref(), Some("number"));
+    }
+
+    #[test]
+    fn test_accurate_get_type_follows_imports() {
+        let workspace = create_test_workspace();
+        let analyzer = AccurateAnalyzer::new(workspace.path().to_path_buf());
+
+        let main_path = workspace.path().join("main.ts");
+        let content = fs::read_to_string(&main_path).unwrap();
+
+        let call = "add(1, 2)";
+        let call_pos = content.find(call).unwrap();
+        let call_type = analyzer
+            .get_type(
+                &main_path,
+                &content,
+                ByteRange::new(call_pos as u32, (call_pos + call.len()) as u32),
+            )
+            .unwrap();
+        assert_eq!(call_type.as_deref(), Some("number"));
+
+        let pi_pos = content.rfind("PI").unwrap();
+        let pi_type = analyzer
+            .get_type(
+                &main_path,
+                &content,
+                ByteRange::new(pi_pos as u32, (pi_pos + 2) as u32),
+            )
+            .unwrap();
+        assert_eq!(pi_type.as_deref(), Some("number"));
     }
 
     #[test]
@@ -1,12 +1,13 @@
 //! Symbol cache for incremental indexing.
 
-use language_core::{ByteRange, SymbolKind};
+use language_core::{ByteRange, ScopeKind, SymbolKind};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Information about a symbol defined in a file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     /// The name of the symbol
     pub name: String,
@@ -19,10 +20,15 @@ pub struct Symbol {
     /// Scope ID this symbol belongs to
     #[allow(dead_code)]
     pub scope_id: u32,
+    /// Type stated by the declaration: an annotation, a literal initializer,
+    /// a function signature or the target of a type alias
+    pub declared_type: Option<String>,
+    /// Annotated return type, for functions
+    pub return_type: Option<String>,
 }
 
 /// Information about an imported symbol.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportedSymbol {
     /// Local name (how it's used in this file)
     pub local_name: String,
@@ -35,12 +41,11 @@ pub struct ImportedSymbol {
     /// Whether this is a default import
     pub is_default: bool,
     /// Whether this is a namespace import (import * as X)
-    #[allow(dead_code)]
     pub is_namespace: bool,
 }
 
 /// Information about an exported symbol.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportedSymbol {
     /// Name of the export
     pub name: String,
@@ -51,12 +56,18 @@ pub struct ExportedSymbol {
     /// Whether this is the default export
     pub is_default: bool,
     /// Whether this is a re-export (export { x } from './other')
-    #[allow(dead_code)]
     pub re_export_from: Option<String>,
+    /// For re-exports, the name exported by the source module
+    /// (`x` in `export { x as y } from './other'`)
+    pub imported_name: Option<String>,
+    /// Byte range of `imported_name` in the export specifier
+    pub imported_range: Option<ByteRange>,
+    /// Whether this re-exports a whole module (`export * as ns from './other'`)
+    pub is_namespace: bool,
 }
 
 /// Information about a reference to a symbol.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolReference {
     /// The symbol ID being referenced
     pub symbol_id: u32,
@@ -65,10 +76,48 @@ pub struct SymbolReference {
     /// Whether this is a write reference
     #[allow(dead_code)]
     pub is_write: bool,
+    /// Whether the reference is in a type position
+    pub is_type: bool,
+}
+
+/// A property access on an imported binding (`ns.name` or, in type
+/// positions, `ns.Name`), used to follow namespace imports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberAccess {
+    /// The imported symbol the property is read from
+    pub object_symbol_id: u32,
+    /// The property name
+    pub property: String,
+    /// Byte range of the property name
+    pub range: ByteRange,
+    /// Whether the access is in a type position
+    pub is_type: bool,
+}
+
+/// A lexical scope in a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scope {
+    /// Unique scope ID (from oxc)
+    pub scope_id: u32,
+    /// Enclosing scope, `None` for the module scope
+    pub parent_id: Option<u32>,
+    /// The kind of scope
+    pub kind: ScopeKind,
+    /// Byte range of the node that introduces the scope
+    pub range: ByteRange,
+}
+
+/// A reference to a name with no declaration in the file (a global).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedReference {
+    /// The referenced name
+    pub name: String,
+    /// Byte range of the reference
+    pub range: ByteRange,
 }
 
 /// Cached symbols for a single file.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileSymbols {
     /// All symbols defined in this file
     pub symbols: Vec<Symbol>,
@@ -78,6 +127,15 @@ pub struct FileSymbols {
     pub exports: Vec<ExportedSymbol>,
     /// All references in this file
     pub references: Vec<SymbolReference>,
+    /// Property accesses on imported bindings
+    pub member_accesses: Vec<MemberAccess>,
+    /// All scopes in this file, outer scopes before the scopes they contain
+    pub scopes: Vec<Scope>,
+    /// References to names not declared in this file
+    pub unresolved_references: Vec<UnresolvedReference>,
+    /// Module specifiers this file depends on: static imports (including
+    /// side-effect imports) and re-exports, in source order
+    pub module_requests: Vec<String>,
     /// Source content hash (for invalidation)
     #[allow(dead_code)]
     pub content_hash: u64,
@@ -117,6 +175,31 @@ impl FileSymbols {
             .collect()
     }
 
+    /// Find the property access on an imported binding at the given range.
+    pub fn find_member_access_at(&self, range: ByteRange) -> Option<&MemberAccess> {
+        self.member_accesses
+            .iter()
+            .find(|m| m.range.start <= range.start && m.range.end >= range.end)
+    }
+
+    /// Find the local binding created by an import.
+    pub fn find_import_binding(&self, import: &ImportedSymbol) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| {
+            s.name == import.local_name
+                && import.range.start <= s.range.start
+                && import.range.end >= s.range.end
+        })
+    }
+
+    /// Find the import that binds a symbol.
+    pub fn find_import_for_symbol(&self, symbol: &Symbol) -> Option<&ImportedSymbol> {
+        self.imports.iter().find(|i| {
+            i.local_name == symbol.name
+                && i.range.start <= symbol.range.start
+                && i.range.end >= symbol.range.end
+        })
+    }
+
     /// Find an export by name.
     pub fn find_export_by_name(&self, name: &str) -> Option<&ExportedSymbol> {
         self.exports.iter().find(|e| e.name == name)
@@ -235,6 +318,8 @@ mod tests {
                 range: ByteRange::new(0, 10),
                 symbol_id: 1,
                 scope_id: 0,
+                declared_type: None,
+                return_type: None,
             }],
             ..Default::default()
         };
@@ -258,6 +343,8 @@ mod tests {
                     range: ByteRange::new(0, 100),
                     symbol_id: 1,
                     scope_id: 0,
+                    declared_type: None,
+                    return_type: None,
                 },
                 Symbol {
                     name: "inner".to_string(),
@@ -265,6 +352,8 @@ mod tests {
                     range: ByteRange::new(20, 40),
                     symbol_id: 2,
                     scope_id: 1,
+                    declared_type: None,
+                    return_type: None,
                 },
             ],
             ..Default::default()
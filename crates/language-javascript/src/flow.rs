@@ -0,0 +1,437 @@
+//! Control-flow graphs for `isReachable()`, `dominates()` and `exitPoints()`.
+//!
+//! The graph is oxc's control-flow graph restricted to a single function
+//! body: nested functions and classes are single items of the enclosing
+//! graph, and get their own graph when queried directly. Each oxc basic
+//! block becomes a [`FlowBlock`] whose items are the statements and
+//! conditions of its instructions.
+//!
+//! Inside a `try` block every statement may throw to the `catch` and
+//! `finally` blocks, so calls there are treated as throwing. Elsewhere only
+//! explicit `throw` statements leave the function exceptionally.
+
+use crate::error::JsSemanticError;
+use crate::oxc_adapter::{module_source, span_to_byte_range};
+use language_core::flow::{FlowBlock, ENTRY, EXIT};
+use language_core::{ByteRange, ControlFlowGraph};
+use oxc::allocator::Allocator;
+use oxc::ast::ast::Expression;
+use oxc::ast::AstKind;
+use oxc::cfg::graph::visit::EdgeRef;
+use oxc::cfg::graph::Direction;
+use oxc::cfg::{BlockNodeId, EdgeType, ErrorEdgeKind, InstructionKind};
+use oxc::parser::Parser;
+use oxc::semantic::{AstNodes, SemanticBuilder};
+use oxc::span::{GetSpan, Span};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Build the control-flow graph of the innermost function containing
+/// `range`, or of the top level of the module.
+pub fn control_flow_at(
+    file_path: &Path,
+    content: &str,
+    range: ByteRange,
+) -> Result<ControlFlowGraph, JsSemanticError> {
+    with_cfg(file_path, content, |cfg, nodes| {
+        let contains = |span: Span| span.start <= range.start && span.end >= range.end;
+        let function = functions(nodes)
+            .filter(|(span, _)| contains(*span))
+            .min_by_key(|(span, _)| span.size());
+
+        let (graph_range, entry) = match function {
+            Some((span, entry)) => (span_to_byte_range(span), entry),
+            None => module_entry(nodes, content)?,
+        };
+        Ok(translate(cfg, nodes, entry, graph_range))
+    })
+}
+
+/// Build the control-flow graphs of the top level of the module and of
+/// every function in it, outermost first.
+pub fn control_flows(
+    file_path: &Path,
+    content: &str,
+) -> Result<Vec<ControlFlowGraph>, JsSemanticError> {
+    with_cfg(file_path, content, |cfg, nodes| {
+        let (module_range, module) = module_entry(nodes, content)?;
+        let mut graphs = vec![translate(cfg, nodes, module, module_range)];
+        graphs.extend(
+            functions(nodes)
+                .map(|(span, entry)| translate(cfg, nodes, entry, span_to_byte_range(span))),
+        );
+        Ok(graphs)
+    })
+}
+
+/// Parse `content` and run `f` on its control-flow graph and AST nodes.
+fn with_cfg<T>(
+    file_path: &Path,
+    content: &str,
+    f: impl FnOnce(&oxc::cfg::ControlFlowGraph, &AstNodes) -> Result<T, JsSemanticError>,
+) -> Result<T, JsSemanticError> {
+    let allocator = Allocator::default();
+    let (source_type, module_content) = module_source(file_path, content);
+    let parser_return = Parser::new(&allocator, module_content.as_ref(), source_type).parse();
+    if !parser_return.errors.is_empty() {
+        let error_messages: Vec<String> =
+            parser_return.errors.iter().map(|e| e.to_string()).collect();
+        return Err(JsSemanticError::ParseError {
+            path: file_path.to_path_buf(),
+            message: error_messages.join("; "),
+        });
+    }
+    let program = parser_return.program;
+    let semantic = SemanticBuilder::new()
+        .with_cfg(true)
+        .build(&program)
+        .semantic;
+    let cfg = semantic
+        .cfg()
+        .ok_or_else(|| JsSemanticError::Internal("control-flow graph was not built".to_string()))?;
+    f(cfg, semantic.nodes())
+}
+
+/// Functions with a body in document order, with their spans and entry
+/// blocks.
+fn functions<'a>(nodes: &'a AstNodes) -> impl Iterator<Item = (Span, BlockNodeId)> + 'a {
+    nodes.iter().filter_map(|node| {
+        let span = match node.kind() {
+            AstKind::Function(function) if function.body.is_some() => function.span,
+            AstKind::ArrowFunctionExpression(arrow) => arrow.span,
+            _ => return None,
+        };
+        // Method nodes include the key, which the function itself doesn't
+        let span = match nodes.parent_kind(node.id()) {
+            AstKind::MethodDefinition(method) => method.span,
+            AstKind::ObjectProperty(property)
+                if property.method
+                    && matches!(&property.value, Expression::FunctionExpression(_)) =>
+            {
+                property.span
+            }
+            _ => span,
+        };
+        // Functions are entered after their basic block is created, so the
+        // node's block is the function's entry
+        Some((span, nodes.cfg_id(node.id())))
+    })
+}
+
+/// Range and entry block of the module's top level.
+fn module_entry(
+    nodes: &AstNodes,
+    content: &str,
+) -> Result<(ByteRange, BlockNodeId), JsSemanticError> {
+    let program = nodes
+        .iter()
+        .find(|node| matches!(node.kind(), AstKind::Program(_)))
+        .ok_or_else(|| JsSemanticError::Internal("program node missing".to_string()))?;
+    Ok((
+        ByteRange::new(0, content.len() as u32),
+        nodes.cfg_id(program.id()),
+    ))
+}
+
+/// How a basic block leaves the function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Return,
+    Throw,
+}
+
+/// Copy the blocks of `cfg` reachable from `entry` without entering nested
+/// functions into a [`ControlFlowGraph`].
+fn translate(
+    cfg: &oxc::cfg::ControlFlowGraph,
+    nodes: &AstNodes,
+    entry: BlockNodeId,
+    range: ByteRange,
+) -> ControlFlowGraph {
+    let mut blocks = vec![FlowBlock::default(), FlowBlock::default()];
+    let mut index: HashMap<BlockNodeId, usize> = HashMap::new();
+    let mut pending = Vec::new();
+    let mut block_of =
+        |oxc_block: BlockNodeId, blocks: &mut Vec<FlowBlock>, pending: &mut Vec<BlockNodeId>| {
+            *index.entry(oxc_block).or_insert_with(|| {
+                blocks.push(FlowBlock::default());
+                pending.push(oxc_block);
+                blocks.len() - 1
+            })
+        };
+    let first = block_of(entry, &mut blocks, &mut pending);
+    blocks[ENTRY].successors.push(first);
+
+    while let Some(oxc_block) = pending.pop() {
+        let block = block_of(oxc_block, &mut blocks, &mut pending);
+
+        let mut exit = None;
+        let mut dead = None;
+        for instruction in cfg.basic_block(oxc_block).instructions() {
+            // Anything after a `return` or `throw` in the same block is dead
+            let target = match (exit, dead) {
+                (None, _) => block,
+                (Some(_), Some(dead)) => dead,
+                (Some(_), None) => {
+                    blocks.push(FlowBlock::default());
+                    *dead.insert(blocks.len() - 1)
+                }
+            };
+            if let Some(node_id) = instruction.node_id {
+                blocks[target]
+                    .items
+                    .push(span_to_byte_range(nodes.kind(node_id).span()));
+            }
+            if exit.is_none() {
+                exit = match instruction.kind {
+                    InstructionKind::Return(_) | InstructionKind::ImplicitReturn => {
+                        Some(Exit::Return)
+                    }
+                    InstructionKind::Throw => Some(Exit::Throw),
+                    _ => None,
+                };
+            }
+        }
+
+        let mut normal = Vec::new();
+        let mut handler = None;
+        let mut finalizer = None;
+        let mut dead_code = Vec::new();
+        for edge in cfg.graph.edges_directed(oxc_block, Direction::Outgoing) {
+            match edge.weight() {
+                EdgeType::Normal | EdgeType::Jump | EdgeType::Backedge | EdgeType::Join => {
+                    normal.push(edge.target());
+                }
+                EdgeType::Error(ErrorEdgeKind::Explicit) => handler = Some(edge.target()),
+                EdgeType::Finalize => finalizer = Some(edge.target()),
+                // Code after a jump is kept, but nothing leads to it
+                EdgeType::Unreachable => dead_code.push(edge.target()),
+                // Implicit error edges lead out of the function from every
+                // block; calls outside `try` are assumed not to throw
+                EdgeType::Error(ErrorEdgeKind::Implicit) | EdgeType::NewFunction => {}
+            }
+        }
+
+        // oxc only links whole blocks to the `catch` and `finally` blocks;
+        // give every item inside `try` its own block so each may throw
+        let mut last = block;
+        if let Some(target) = handler.or(finalizer) {
+            let exceptional = block_of(target, &mut blocks, &mut pending);
+            let mut items = std::mem::take(&mut blocks[block].items).into_iter();
+            blocks[block].items.extend(items.next());
+            for item in items {
+                push_successor(&mut blocks[last], exceptional);
+                blocks.push(FlowBlock {
+                    items: vec![item],
+                    successors: Vec::new(),
+                });
+                let next = blocks.len() - 1;
+                push_successor(&mut blocks[last], next);
+                last = next;
+            }
+        }
+
+        let targets: Vec<BlockNodeId> = match exit {
+            None => normal.into_iter().chain(handler).chain(finalizer).collect(),
+            // A `return` runs the enclosing `finally` block first; its
+            // argument may still throw to a `catch`
+            Some(Exit::Return) => handler.into_iter().chain(finalizer).collect(),
+            Some(Exit::Throw) => handler.or(finalizer).into_iter().collect(),
+        };
+        let leaves = match exit {
+            None => targets.is_empty(),
+            Some(Exit::Return) => finalizer.is_none(),
+            Some(Exit::Throw) => handler.is_none() && finalizer.is_none(),
+        };
+        for target in targets {
+            let successor = block_of(target, &mut blocks, &mut pending);
+            push_successor(&mut blocks[last], successor);
+        }
+        if leaves {
+            push_successor(&mut blocks[last], EXIT);
+        }
+        for target in dead_code {
+            block_of(target, &mut blocks, &mut pending);
+        }
+    }
+
+    // Declarations have no instruction of their own
+    for node in nodes.iter() {
+        let oxc_block = match node.kind() {
+            // A function's node is in its own graph; the declaration sits in
+            // the block the function is entered from
+            AstKind::Function(function) if function.is_declaration() => cfg
+                .graph
+                .edges_directed(nodes.cfg_id(node.id()), Direction::Incoming)
+                .find(|edge| *edge.weight() == EdgeType::NewFunction)
+                .map(|edge| edge.source()),
+            AstKind::Class(class) if class.is_declaration() => Some(nodes.cfg_id(node.id())),
+            _ => None,
+        };
+        if let Some(&block) = oxc_block.and_then(|oxc_block| index.get(&oxc_block)) {
+            blocks[block]
+                .items
+                .push(span_to_byte_range(node.kind().span()));
+        }
+    }
+    for block in &mut blocks {
+        block
+            .items
+            .sort_by_key(|item| (item.start, std::cmp::Reverse(item.end)));
+    }
+
+    ControlFlowGraph { range, blocks }
+}
+
+fn push_successor(block: &mut FlowBlock, successor: usize) {
+    if !block.successors.contains(&successor) {
+        block.successors.push(successor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range_of(content: &str, needle: &str) -> ByteRange {
+        let start = content.find(needle).unwrap() as u32;
+        ByteRange::new(start, start + needle.len() as u32)
+    }
+
+    fn graph(content: &str, needle: &str) -> ControlFlowGraph {
+        control_flow_at(Path::new("test.ts"), content, range_of(content, needle)).unwrap()
+    }
+
+    #[test]
+    fn unreachable_after_return_and_throw() {
+        let content = "function f(a) {\n  if (a) {\n    return 1;\n    dead();\n  }\n  throw new Error();\n  alsoDead();\n}\n";
+        let graph = graph(content, "dead()");
+
+        assert_eq!(
+            graph.range,
+            range_of(content, &content[..content.len() - 1])
+        );
+        assert!(!graph.is_reachable(range_of(content, "dead()")));
+        assert!(!graph.is_reachable(range_of(content, "alsoDead()")));
+        assert!(graph.is_reachable(range_of(content, "throw new Error()")));
+    }
+
+    #[test]
+    fn dominance_of_open_and_close() {
+        let content = "function f(path) {\n  const handle = open(path);\n  if (path) {\n    handle.write();\n  }\n  handle.close();\n}\n";
+        let graph = graph(content, "open(path)");
+        let open = range_of(content, "open(path)");
+        let write = range_of(content, "handle.write()");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.dominates(open, close));
+        assert!(graph.dominates(open, write));
+        assert!(!graph.dominates(write, close));
+        assert!(graph.post_dominates(close, open));
+        assert!(graph.post_dominates(close, write));
+        assert!(!graph.post_dominates(write, open));
+    }
+
+    #[test]
+    fn early_exits_skip_cleanup() {
+        let content = "function f(items) {\n  const handle = open();\n  for (const item of items) {\n    if (!item) return;\n    if (item.skip) continue;\n    handle.write(item);\n  }\n  handle.close();\n}\n";
+        let graph = graph(content, "open()");
+        let open = range_of(content, "open()");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.dominates(open, close));
+        assert!(!graph.post_dominates(close, open));
+        assert_eq!(
+            graph.exit_points(),
+            vec![
+                range_of(content, "return;"),
+                range_of(content, "handle.close();")
+            ]
+        );
+    }
+
+    #[test]
+    fn try_finally_runs_on_every_path() {
+        let content = "function f() {\n  const handle = open();\n  try {\n    if (handle.bad) throw new Error();\n    if (handle.empty) return;\n    handle.write();\n  } finally {\n    handle.close();\n  }\n}\n";
+        let graph = graph(content, "open()");
+        let open = range_of(content, "open()");
+        let write = range_of(content, "handle.write()");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.post_dominates(close, open));
+        assert!(graph.post_dominates(close, write));
+        assert!(!graph.dominates(write, close));
+        // `return` inside `try` still runs the `finally` block
+        assert!(graph.post_dominates(close, range_of(content, "return;")));
+        assert_eq!(
+            graph.exit_points(),
+            vec![range_of(content, "handle.close();")]
+        );
+    }
+
+    #[test]
+    fn calls_inside_try_may_throw_to_catch() {
+        let content = "function f() {\n  try {\n    first();\n    second();\n  } catch (e) {\n    recover();\n  }\n  done();\n}\n";
+        let graph = graph(content, "first()");
+        let second = range_of(content, "second()");
+        let recover = range_of(content, "recover()");
+        let done = range_of(content, "done()");
+
+        assert!(graph.is_reachable(recover));
+        // `first()` may throw before `second()` runs
+        assert!(!graph.post_dominates(second, range_of(content, "first()")));
+        assert!(!graph.dominates(second, recover));
+        assert!(graph.post_dominates(done, recover));
+    }
+
+    #[test]
+    fn nested_functions_have_their_own_graph() {
+        let content = "function outer() {\n  return () => {\n    inner();\n  };\n}\ntopLevel();\n";
+        let inner = graph(content, "inner()");
+        assert_eq!(inner.exit_points(), vec![range_of(content, "inner();")]);
+
+        let module = graph(content, "topLevel()");
+        assert_eq!(module.range, ByteRange::new(0, content.len() as u32));
+        assert!(module.dominates(
+            range_of(content, "function outer"),
+            range_of(content, "topLevel()")
+        ));
+    }
+
+    #[test]
+    fn switch_fallthrough_and_labels() {
+        let content = "function f(x) {\n  start();\n  outer: for (;;) {\n    switch (x) {\n      case 1:\n        one();\n      case 2:\n        two();\n        break;\n      default:\n        break outer;\n    }\n    loopEnd();\n  }\n  after();\n}\n";
+        let graph = graph(content, "one()");
+        let start = range_of(content, "start()");
+        let one = range_of(content, "one()");
+        let two = range_of(content, "two()");
+        let loop_end = range_of(content, "loopEnd()");
+        let after = range_of(content, "after()");
+
+        // Only `break outer` leaves the infinite loop
+        assert!(graph.is_reachable(after));
+        assert!(graph.dominates(start, after));
+        assert!(!graph.dominates(one, two));
+        assert!(!graph.dominates(one, loop_end));
+        // `case 1` falls through to `case 2`, whose `break` only leaves the switch
+        assert!(graph.post_dominates(two, one));
+        assert!(graph.post_dominates(loop_end, two));
+    }
+
+    #[test]
+    fn file_graphs_match_the_graph_at_each_range() {
+        let content = "topLevel();\nclass A {\n  method() {\n    inMethod();\n  }\n}\nfunction outer() {\n  const inner = () => {\n    inInner();\n  };\n  inOuter();\n}\n";
+        let graphs = control_flows(Path::new("test.ts"), content).unwrap();
+        assert_eq!(graphs.len(), 4);
+        assert_eq!(graphs[0].range, ByteRange::new(0, content.len() as u32));
+
+        for needle in ["topLevel()", "inMethod()", "inInner()", "inOuter()"] {
+            let range = range_of(content, needle);
+            assert_eq!(
+                ControlFlowGraph::innermost(&graphs, range),
+                Some(&graph(content, needle)),
+                "{needle}"
+            );
+        }
+    }
+}
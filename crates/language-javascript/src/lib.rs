@@ -7,7 +7,9 @@
 //!
 //! - Symbol definition lookup (go-to-definition)
 //! - Reference finding (find-all-references)
-//! - Type information extraction
+//! - Declared type lookup (annotations, literals, signatures and type aliases)
+//! - Scope and binding lookup
+//! - Per-function control-flow graphs
 //! - Two analysis modes: FileScope (single-file) and WorkspaceScope (workspace-wide)
 //!
 //! # Example
@@ -40,9 +42,12 @@
 mod accurate;
 mod cache;
 mod error;
+mod flow;
 mod lightweight;
 mod oxc_adapter;
 mod provider;
+mod scope;
+mod type_info;
 mod vfs_fs;
 
 pub use accurate::WorkspaceWalker;
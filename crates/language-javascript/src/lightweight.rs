@@ -1,14 +1,16 @@
 //! Lightweight mode implementation for incremental per-file analysis.
 
-use crate::cache::SymbolCache;
+use crate::cache::{FileSymbols, ImportedSymbol, SymbolCache};
 use crate::error::JsSemanticError;
 use crate::oxc_adapter::{find_symbol_at_range, parse_and_analyze};
+use crate::scope::scopes_at;
+use crate::type_info::type_at;
 use language_core::{
     ByteRange, DefinitionKind, DefinitionOptions, DefinitionResult, FileReferences,
-    ReferencesResult, SemanticResult, SymbolLocation,
+    ReferencesResult, ScopeInfo, SemanticResult, SymbolLocation,
 };
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Lightweight semantic analyzer that builds symbol cache incrementally.
 #[derive(Debug, Default)]
@@ -229,6 +231,95 @@ impl LightweightAnalyzer {
         Ok(result)
     }
 
+    /// Get the declared type of the node at the given range.
+    ///
+    /// In lightweight mode, imports are only followed into files that have
+    /// already been processed.
+    pub fn get_type(
+        &self,
+        file_path: &Path,
+        content: &str,
+        range: ByteRange,
+    ) -> SemanticResult<Option<String>> {
+        if !self.cache.contains(file_path) {
+            self.process_file(file_path, content)?;
+        }
+
+        let (file_symbols, _) =
+            self.cache
+                .get(file_path)
+                .ok_or_else(|| JsSemanticError::FileNotCached {
+                    path: file_path.to_path_buf(),
+                })?;
+
+        let resolve_import = |import: &ImportedSymbol, from_path: &Path| {
+            self.find_cached_module(&import.module_specifier, from_path)
+        };
+
+        Ok(type_at(
+            file_path,
+            &file_symbols,
+            content,
+            range,
+            &resolve_import,
+        ))
+    }
+
+    /// Gets the scopes enclosing the given byte range, innermost first.
+    pub fn get_scopes(
+        &self,
+        file_path: &Path,
+        content: &str,
+        range: ByteRange,
+    ) -> SemanticResult<Vec<ScopeInfo>> {
+        if !self.cache.contains(file_path) {
+            self.process_file(file_path, content)?;
+        }
+
+        let (file_symbols, _) =
+            self.cache
+                .get(file_path)
+                .ok_or_else(|| JsSemanticError::FileNotCached {
+                    path: file_path.to_path_buf(),
+                })?;
+
+        Ok(scopes_at(&file_symbols, range))
+    }
+
+    /// Find a relative import's target among the cached files.
+    fn find_cached_module(
+        &self,
+        module_specifier: &str,
+        from_path: &Path,
+    ) -> Option<(PathBuf, FileSymbols)> {
+        if !module_specifier.starts_with('.') {
+            return None;
+        }
+        let from_dir = from_path.parent().unwrap_or(Path::new(""));
+
+        [
+            "",
+            ".ts",
+            ".tsx",
+            ".js",
+            ".jsx",
+            "/index.ts",
+            "/index.tsx",
+            "/index.js",
+        ]
+        .iter()
+        .find_map(|ext| {
+            let potential_path = from_dir.join(format!(
+                "{}{}",
+                module_specifier.trim_start_matches("./"),
+                ext
+            ));
+            self.cache
+                .get(&potential_path)
+                .map(|(symbols, _)| (potential_path, symbols))
+        })
+    }
+
     /// Try to resolve an import to its definition.
     /// Returns `DefinitionKind::External` for successfully resolved cross-file definitions.
     fn resolve_import_definition(
@@ -372,6 +463,30 @@ const y = x + 2;"#;
         assert_eq!(def.kind, DefinitionKind::Local);
     }
 
+    #[test]
+    fn test_lightweight_get_type_follows_cached_imports() {
+        let analyzer = LightweightAnalyzer::new();
+
+        analyzer
+            .process_file(
+                Path::new("src/config.ts"),
+                "export type Options = Record<string, boolean>;\nexport function load(): Options { return {}; }",
+            )
+            .unwrap();
+
+        let content = "import { load } from './config';\nconst options = load();";
+        let call_pos = content.rfind("load()").unwrap() as u32;
+        let result = analyzer
+            .get_type(
+                Path::new("src/main.ts"),
+                content,
+                ByteRange::new(call_pos, call_pos + 6),
+            )
+            .unwrap();
+
+        assert_eq!(result.as_deref(), Some("Record<string, boolean>"));
+    }
+
     #[test]
     fn test_lightweight_find_references() {
         let analyzer = LightweightAnalyzer::new();
@@ -1,26 +1,33 @@
 //! OXC integration for parsing and semantic analysis.
 
 use crate::cache::{
-    hash_content, ExportedSymbol, FileSymbols, ImportedSymbol, Symbol, SymbolReference,
+    hash_content, ExportedSymbol, FileSymbols, ImportedSymbol, MemberAccess, Scope, Symbol,
+    SymbolReference, UnresolvedReference,
 };
 use crate::error::JsSemanticError;
-use language_core::{ByteRange, SymbolKind};
+use language_core::embedded::{self, HostFormat};
+use language_core::{ByteRange, ScopeKind, SymbolKind};
 use oxc::allocator::Allocator;
 use oxc::ast::ast::{
-    Declaration, ExportDefaultDeclarationKind, ExportNamedDeclaration, ImportDeclaration,
-    ImportDeclarationSpecifier, ModuleDeclaration,
+    ArrayExpressionElement, Declaration, ExportDefaultDeclarationKind, ExportNamedDeclaration,
+    Expression, FormalParameters, ImportDeclaration, ImportDeclarationSpecifier, ModuleDeclaration,
+    TSType, TSTypeAnnotation, TSTypeName,
 };
+use oxc::ast::AstKind;
 use oxc::parser::Parser;
-use oxc::semantic::{SemanticBuilder, SymbolFlags};
-use oxc::span::{SourceType, Span};
+use oxc::semantic::{Semantic, SemanticBuilder, SymbolFlags, SymbolId};
+use oxc::span::{GetSpan, SourceType, Span};
+use std::borrow::Cow;
 use std::path::Path;
 
 /// Parse a JavaScript/TypeScript file and extract semantic information.
 pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols, JsSemanticError> {
     let allocator = Allocator::default();
+    let content_hash = hash_content(content);
 
     // Determine source type from file extension
-    let source_type = SourceType::from_path(file_path).unwrap_or_default();
+    let (source_type, module_content) = module_source(file_path, content);
+    let content = module_content.as_ref();
 
     // Parse the source
     let parser_return = Parser::new(&allocator, content, source_type).parse();
@@ -55,7 +62,7 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
     let scoping = semantic.scoping();
 
     let mut file_symbols = FileSymbols {
-        content_hash: hash_content(content),
+        content_hash,
         ..Default::default()
     };
 
@@ -67,6 +74,7 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
         let scope_id = scoping.symbol_scope_id(symbol_id);
 
         let kind = symbol_flags_to_kind(flags);
+        let (declared_type, return_type) = declared_symbol_types(&semantic, symbol_id, content);
 
         file_symbols.symbols.push(Symbol {
             name,
@@ -74,6 +82,8 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
             range: span_to_byte_range(span),
             symbol_id: symbol_id.index() as u32,
             scope_id: scope_id.index() as u32,
+            declared_type,
+            return_type,
         });
 
         // Extract references for this symbol
@@ -85,14 +95,83 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
                 symbol_id: symbol_id.index() as u32,
                 range: span_to_byte_range(ref_span),
                 is_write: reference.flags().is_write(),
+                is_type: reference.flags().is_type(),
             });
         }
     }
 
+    // Extract the scope tree and references to undeclared names
+    for scope_id in scoping.scope_descendants_from_root() {
+        let node_kind = semantic.nodes().kind(scoping.get_node_id(scope_id));
+        let kind = match node_kind {
+            AstKind::Program(_) => ScopeKind::Module,
+            AstKind::Function(_) | AstKind::ArrowFunctionExpression(_) => ScopeKind::Function,
+            AstKind::Class(_) => ScopeKind::Class,
+            _ => ScopeKind::Block,
+        };
+        file_symbols.scopes.push(Scope {
+            scope_id: scope_id.index() as u32,
+            parent_id: scoping
+                .scope_parent_id(scope_id)
+                .map(|parent_id| parent_id.index() as u32),
+            kind,
+            range: span_to_byte_range(node_kind.span()),
+        });
+    }
+    for (name, reference_ids) in scoping.root_unresolved_references().iter() {
+        for reference_id in reference_ids {
+            let reference = scoping.get_reference(*reference_id);
+            file_symbols
+                .unresolved_references
+                .push(UnresolvedReference {
+                    name: name.to_string(),
+                    range: span_to_byte_range(semantic.reference_span(reference)),
+                });
+        }
+    }
+
+    // Record property accesses on imported bindings so namespace imports
+    // (`import * as ns` then `ns.name`) can be followed across files
+    for node in semantic.nodes().iter() {
+        let (object, property, is_type) = match node.kind() {
+            AstKind::StaticMemberExpression(member) => match &member.object {
+                Expression::Identifier(object) => (object, &member.property, false),
+                _ => continue,
+            },
+            AstKind::TSQualifiedName(qualified) => match &qualified.left {
+                TSTypeName::IdentifierReference(object) => (object, &qualified.right, true),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let Some(symbol_id) = object
+            .reference_id
+            .get()
+            .and_then(|reference_id| scoping.get_reference(reference_id).symbol_id())
+        else {
+            continue;
+        };
+        if !scoping
+            .symbol_flags(symbol_id)
+            .contains(SymbolFlags::Import)
+        {
+            continue;
+        }
+        file_symbols.member_accesses.push(MemberAccess {
+            object_symbol_id: symbol_id.index() as u32,
+            property: property.name.to_string(),
+            range: span_to_byte_range(property.span),
+            is_type,
+        });
+    }
+
     // Extract imports and exports from the AST
     for stmt in &program.body {
         // Use as_module_declaration() to check if this is a module declaration
         if let Some(module_decl) = stmt.as_module_declaration() {
+            if let Some(source) = module_decl.source() {
+                file_symbols.module_requests.push(source.value.to_string());
+            }
             match module_decl {
                 ModuleDeclaration::ImportDeclaration(import_decl) => {
                     extract_imports(import_decl, &mut file_symbols);
@@ -102,33 +181,49 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
                 }
                 ModuleDeclaration::ExportDefaultDeclaration(export_decl) => {
                     let range = span_to_byte_range(export_decl.span);
-                    let local_symbol_id = match &export_decl.declaration {
-                        ExportDefaultDeclarationKind::Identifier(id) => {
-                            // Find the symbol ID for this identifier
-                            file_symbols
-                                .symbols
-                                .iter()
-                                .find(|s| s.name == id.name.as_str())
-                                .map(|s| s.symbol_id)
+                    let local_name = match &export_decl.declaration {
+                        ExportDefaultDeclarationKind::Identifier(id) => Some(&id.name),
+                        ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                            func.id.as_ref().map(|id| &id.name)
+                        }
+                        ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                            class.id.as_ref().map(|id| &id.name)
                         }
                         _ => None,
                     };
+                    // Find the symbol ID for the exported binding
+                    let local_symbol_id = local_name.and_then(|name| {
+                        file_symbols
+                            .symbols
+                            .iter()
+                            .find(|s| s.name == name.as_str())
+                            .map(|s| s.symbol_id)
+                    });
                     file_symbols.exports.push(ExportedSymbol {
                         name: "default".to_string(),
                         local_symbol_id,
                         range,
                         is_default: true,
                         re_export_from: None,
+                        imported_name: None,
+                        imported_range: None,
+                        is_namespace: false,
                     });
                 }
                 ModuleDeclaration::ExportAllDeclaration(export_all) => {
                     let range = span_to_byte_range(export_all.span);
+                    // `export * as ns from` exports the module under `ns`
+                    let namespace = export_all.exported.as_ref().map(|name| name.name());
+                    let is_namespace = namespace.is_some();
                     file_symbols.exports.push(ExportedSymbol {
-                        name: "*".to_string(),
+                        name: namespace.map_or_else(|| "*".to_string(), |name| name.to_string()),
                         local_symbol_id: None,
                         range,
                         is_default: false,
                         re_export_from: Some(export_all.source.value.to_string()),
+                        imported_name: None,
+                        imported_range: None,
+                        is_namespace,
                     });
                 }
                 _ => {}
@@ -139,11 +234,228 @@ pub fn parse_and_analyze(file_path: &Path, content: &str) -> Result<FileSymbols,
     Ok(file_symbols)
 }
 
+/// Source type and module text for a file. Vue and Svelte components and
+/// HTML pages are analyzed as the module formed by their `<script>` blocks,
+/// with the rest of the file blanked out so offsets match the host file.
+pub(crate) fn module_source<'a>(file_path: &Path, content: &'a str) -> (SourceType, Cow<'a, str>) {
+    let Some(format) = HostFormat::from_path(file_path) else {
+        return (
+            SourceType::from_path(file_path).unwrap_or_default(),
+            Cow::Borrowed(content),
+        );
+    };
+
+    let (language, masked) = embedded::script_module(format, content);
+    let source_type = match language {
+        "tsx" => SourceType::tsx(),
+        "typescript" => SourceType::ts(),
+        _ => SourceType::jsx(),
+    };
+    (source_type, Cow::Owned(masked))
+}
+
+/// Whether the workspace index should include this file.
+pub(crate) fn is_module_file(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    matches!(
+        ext,
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "vue" | "svelte"
+    )
+}
+
 /// Convert OXC Span to ByteRange.
 pub fn span_to_byte_range(span: Span) -> ByteRange {
     ByteRange::new(span.start, span.end)
 }
 
+/// Extract the declared type and return type of a symbol from its declaration.
+///
+/// oxc has no type checker, so this only reports what the source states:
+/// annotations, literal initializers, function signatures and type alias targets.
+fn declared_symbol_types(
+    semantic: &Semantic,
+    symbol_id: SymbolId,
+    content: &str,
+) -> (Option<String>, Option<String>) {
+    let declaration = semantic.scoping().symbol_declaration(symbol_id);
+    match semantic.nodes().get_node(declaration).kind() {
+        AstKind::VariableDeclarator(declarator) => {
+            // Destructured bindings would need the type of the whole pattern
+            if declarator.id.get_identifier_name().is_none() {
+                return (None, None);
+            }
+            if let Some(annotation) = &declarator.id.type_annotation {
+                return (Some(annotation_text(annotation, content)), None);
+            }
+            declarator
+                .init
+                .as_ref()
+                .map(|init| expression_types(init, content))
+                .unwrap_or_default()
+        }
+        AstKind::FormalParameter(parameter) => (
+            parameter
+                .pattern
+                .type_annotation
+                .as_ref()
+                .map(|annotation| annotation_text(annotation, content)),
+            None,
+        ),
+        AstKind::Function(function) => {
+            let return_type = function
+                .return_type
+                .as_ref()
+                .map(|annotation| annotation_text(annotation, content));
+            (
+                Some(function_signature(
+                    &function.params,
+                    return_type.as_deref(),
+                    content,
+                )),
+                return_type,
+            )
+        }
+        AstKind::Class(class) => (
+            class.id.as_ref().map(|id| format!("typeof {}", id.name)),
+            None,
+        ),
+        AstKind::TSTypeAliasDeclaration(alias) => {
+            (Some(type_text(&alias.type_annotation, content)), None)
+        }
+        AstKind::TSInterfaceDeclaration(interface) => (Some(interface.id.name.to_string()), None),
+        AstKind::TSEnumDeclaration(enum_decl) => {
+            (Some(format!("typeof {}", enum_decl.id.name)), None)
+        }
+        _ => (None, None),
+    }
+}
+
+/// Infer the declared type and return type of an initializer expression.
+fn expression_types(expression: &Expression, content: &str) -> (Option<String>, Option<String>) {
+    match expression {
+        Expression::ArrowFunctionExpression(arrow) => {
+            let return_type = arrow
+                .return_type
+                .as_ref()
+                .map(|annotation| annotation_text(annotation, content));
+            (
+                Some(function_signature(
+                    &arrow.params,
+                    return_type.as_deref(),
+                    content,
+                )),
+                return_type,
+            )
+        }
+        Expression::FunctionExpression(function) => {
+            let return_type = function
+                .return_type
+                .as_ref()
+                .map(|annotation| annotation_text(annotation, content));
+            (
+                Some(function_signature(
+                    &function.params,
+                    return_type.as_deref(),
+                    content,
+                )),
+                return_type,
+            )
+        }
+        _ => (expression_type(expression, content), None),
+    }
+}
+
+/// Infer the type of a non-function expression from its syntax.
+fn expression_type(expression: &Expression, content: &str) -> Option<String> {
+    let type_name = match expression {
+        Expression::StringLiteral(_) | Expression::TemplateLiteral(_) => "string",
+        Expression::NumericLiteral(_) => "number",
+        Expression::BigIntLiteral(_) => "bigint",
+        Expression::BooleanLiteral(_) => "boolean",
+        Expression::NullLiteral(_) => "null",
+        Expression::RegExpLiteral(_) => "RegExp",
+        Expression::ObjectExpression(_) => "object",
+        Expression::Identifier(identifier) if identifier.name == "undefined" => "undefined",
+        Expression::ArrayExpression(array) => {
+            let element_types = array
+                .elements
+                .iter()
+                .map(|element| match element {
+                    ArrayExpressionElement::SpreadElement(_)
+                    | ArrayExpressionElement::Elision(_) => None,
+                    _ => element
+                        .as_expression()
+                        .and_then(|element| expression_type(element, content)),
+                })
+                .collect::<Option<Vec<_>>>();
+            return Some(match element_types {
+                Some(types) if !types.is_empty() && types.iter().all(|t| *t == types[0]) => {
+                    format!("{}[]", types[0])
+                }
+                _ => "unknown[]".to_string(),
+            });
+        }
+        Expression::NewExpression(new_expression) => {
+            let callee = new_expression.callee.span().source_text(content);
+            return Some(match &new_expression.type_arguments {
+                Some(arguments) => format!("{callee}{}", arguments.span.source_text(content)),
+                None => callee.to_string(),
+            });
+        }
+        Expression::TSAsExpression(as_expression) => {
+            return cast_type(
+                &as_expression.type_annotation,
+                &as_expression.expression,
+                content,
+            );
+        }
+        Expression::TSSatisfiesExpression(satisfies) => {
+            return Some(type_text(&satisfies.type_annotation, content));
+        }
+        Expression::TSTypeAssertion(assertion) => {
+            return cast_type(&assertion.type_annotation, &assertion.expression, content);
+        }
+        Expression::ParenthesizedExpression(parenthesized) => {
+            return expression_type(&parenthesized.expression, content);
+        }
+        _ => return None,
+    };
+    Some(type_name.to_string())
+}
+
+/// The type of `expression as T`, looking through `as const`.
+fn cast_type(type_annotation: &TSType, expression: &Expression, content: &str) -> Option<String> {
+    let text = type_text(type_annotation, content);
+    if text == "const" {
+        expression_type(expression, content)
+    } else {
+        Some(text)
+    }
+}
+
+/// Render a function signature such as `(a: string) => number`.
+fn function_signature(
+    params: &FormalParameters,
+    return_type: Option<&str>,
+    content: &str,
+) -> String {
+    let params = params.span.source_text(content);
+    let params = if params.starts_with('(') {
+        params.to_string()
+    } else {
+        format!("({params})")
+    };
+    format!("{params} => {}", return_type.unwrap_or("unknown"))
+}
+
+fn annotation_text(annotation: &TSTypeAnnotation, content: &str) -> String {
+    type_text(&annotation.type_annotation, content)
+}
+
+fn type_text(ts_type: &TSType, content: &str) -> String {
+    ts_type.span().source_text(content).to_string()
+}
+
 /// Convert OXC SymbolFlags to SymbolKind.
 fn symbol_flags_to_kind(flags: SymbolFlags) -> SymbolKind {
     if flags.contains(SymbolFlags::Function) {
@@ -225,11 +537,24 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
         let name = spec.exported.name().to_string();
         let local_name = spec.local.name().to_string();
 
-        let local_symbol_id = file_symbols
-            .symbols
-            .iter()
-            .find(|s| s.name == local_name)
-            .map(|s| s.symbol_id);
+        // Re-exported names refer to the source module, not a local binding
+        let local_symbol_id = if re_export_from.is_some() {
+            None
+        } else {
+            file_symbols
+                .symbols
+                .iter()
+                .find(|s| s.name == local_name)
+                .map(|s| s.symbol_id)
+        };
+        let (imported_name, imported_range) = if re_export_from.is_some() {
+            (
+                Some(local_name),
+                Some(span_to_byte_range(spec.local.span())),
+            )
+        } else {
+            (None, None)
+        };
 
         file_symbols.exports.push(ExportedSymbol {
             name,
@@ -237,6 +562,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
             range,
             is_default: false,
             re_export_from: re_export_from.clone(),
+            imported_name,
+            imported_range,
+            is_namespace: false,
         });
     }
 
@@ -259,6 +587,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                             range,
                             is_default: false,
                             re_export_from: None,
+                            imported_name: None,
+                            imported_range: None,
+                            is_namespace: false,
                         });
                     }
                 }
@@ -278,6 +609,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                         range,
                         is_default: false,
                         re_export_from: None,
+                        imported_name: None,
+                        imported_range: None,
+                        is_namespace: false,
                     });
                 }
             }
@@ -296,6 +630,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                         range,
                         is_default: false,
                         re_export_from: None,
+                        imported_name: None,
+                        imported_range: None,
+                        is_namespace: false,
                     });
                 }
             }
@@ -313,6 +650,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                     range,
                     is_default: false,
                     re_export_from: None,
+                    imported_name: None,
+                    imported_range: None,
+                    is_namespace: false,
                 });
             }
             Declaration::TSInterfaceDeclaration(interface_decl) => {
@@ -329,6 +669,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                     range,
                     is_default: false,
                     re_export_from: None,
+                    imported_name: None,
+                    imported_range: None,
+                    is_namespace: false,
                 });
             }
             Declaration::TSEnumDeclaration(enum_decl) => {
@@ -345,6 +688,9 @@ fn extract_named_exports(export_decl: &ExportNamedDeclaration, file_symbols: &mu
                     range,
                     is_default: false,
                     re_export_from: None,
+                    imported_name: None,
+                    imported_range: None,
+                    is_namespace: false,
                 });
             }
             _ => {}
@@ -476,6 +822,48 @@ console.log(x, y);
         assert!(!x_refs.is_empty());
     }
 
+    #[test]
+    fn test_parse_declared_types() {
+        let content = r#"
+type Cache = Map<string, number>;
+const name: string = "codemod";
+let count = 1;
+const cache = new Map<string, number>();
+const ids = [1, 2, 3];
+function load(path: string): Promise<Cache> {
+    return fetch(path);
+}
+const parse = (input: string): number => Number(input);
+        "#;
+
+        let symbols = parse_and_analyze(Path::new("test.ts"), content).unwrap();
+        let declared = |name: &str| {
+            symbols
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .and_then(|s| s.declared_type.clone())
+        };
+
+        assert_eq!(declared("Cache").as_deref(), Some("Map<string, number>"));
+        assert_eq!(declared("name").as_deref(), Some("string"));
+        assert_eq!(declared("count").as_deref(), Some("number"));
+        assert_eq!(declared("cache").as_deref(), Some("Map<string, number>"));
+        assert_eq!(declared("ids").as_deref(), Some("number[]"));
+        assert_eq!(declared("path").as_deref(), Some("string"));
+        assert_eq!(
+            declared("load").as_deref(),
+            Some("(path: string) => Promise<Cache>")
+        );
+        assert_eq!(
+            declared("parse").as_deref(),
+            Some("(input: string) => number")
+        );
+
+        let load = symbols.symbols.iter().find(|s| s.name == "load").unwrap();
+        assert_eq!(load.return_type.as_deref(), Some("Promise<Cache>"));
+    }
+
     #[test]
     fn test_parse_error_handling() {
         let content = r#"
@@ -1,10 +1,11 @@
 //! Main OXC semantic provider implementation.
 
 use crate::accurate::{AccurateAnalyzer, WorkspaceWalker};
+use crate::flow::{control_flow_at, control_flows};
 use crate::lightweight::LightweightAnalyzer;
 use language_core::{
-    filesystem, ByteRange, DefinitionOptions, DefinitionResult, ProviderMode, ReferencesResult,
-    SemanticProvider, SemanticResult,
+    filesystem, filesystem::ContentOverlay, ByteRange, ControlFlowGraph, DefinitionOptions,
+    DefinitionResult, ProviderMode, ReferencesResult, ScopeInfo, SemanticProvider, SemanticResult,
 };
 use std::path::{Path, PathBuf};
 use vfs::VfsPath;
@@ -37,6 +38,8 @@ pub struct OxcSemanticProvider {
     /// Physical root path for converting absolute paths to relative paths.
     /// Only used when fs_root is PhysicalFS.
     physical_root: Option<PathBuf>,
+    /// Contents served instead of the filesystem, by canonical path
+    overlay: ContentOverlay,
 }
 
 impl OxcSemanticProvider {
@@ -55,6 +58,7 @@ impl OxcSemanticProvider {
             analyzer: AnalyzerKind::FileScope(LightweightAnalyzer::new()),
             fs_root: filesystem::physical_path(&cwd),
             physical_root: Some(cwd),
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -72,6 +76,7 @@ impl OxcSemanticProvider {
             analyzer: AnalyzerKind::FileScope(LightweightAnalyzer::new()),
             fs_root,
             physical_root: None, // MemoryFS or other VFS - paths are virtual
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -96,6 +101,7 @@ impl OxcSemanticProvider {
             )),
             fs_root,
             physical_root: Some(canonical_root),
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -113,6 +119,7 @@ impl OxcSemanticProvider {
             )),
             fs_root,
             physical_root: None, // Custom VFS - paths handled by VFS implementation
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -132,6 +139,7 @@ impl OxcSemanticProvider {
             )),
             fs_root,
             physical_root: Some(canonical_root),
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -152,9 +160,19 @@ impl OxcSemanticProvider {
             )),
             fs_root,
             physical_root: None,
+            overlay: ContentOverlay::new(),
         }
     }
 
+    /// Persist the workspace index under `dir` so later runs only re-parse
+    /// files whose content changed. No-op in file scope.
+    pub fn with_index_dir(mut self, dir: &Path) -> Self {
+        if let AnalyzerKind::WorkspaceScope(analyzer) = self.analyzer {
+            self.analyzer = AnalyzerKind::WorkspaceScope(analyzer.with_index_dir(dir));
+        }
+        self
+    }
+
     /// Clear all cached data.
     pub fn clear_cache(&self) {
         match &self.analyzer {
@@ -177,6 +195,9 @@ impl OxcSemanticProvider {
         let canonical_file = file_path
             .canonicalize()
             .unwrap_or_else(|_| file_path.to_path_buf());
+        if let Some(content) = self.overlay.get(&canonical_file) {
+            return Ok(content);
+        }
 
         // For PhysicalFS, try to convert to a path relative to the physical root.
         // If the file is outside the root, read it directly using its absolute path.
@@ -274,11 +295,44 @@ impl SemanticProvider for OxcSemanticProvider {
         }
     }
 
-    fn get_type(&self, _file_path: &Path, _range: ByteRange) -> SemanticResult<Option<String>> {
-        // Type inference is more complex and would require TypeScript's type checker
-        // For now, return None (type info not available)
-        // Future: could integrate with oxc's type inference or use TypeScript server
-        Ok(None)
+    fn get_type(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Option<String>> {
+        // Without a type checker, types come from declarations: annotations,
+        // literal initializers, function signatures and type aliases
+        let content = self.read_file(file_path)?;
+
+        match &self.analyzer {
+            AnalyzerKind::FileScope(analyzer) => analyzer.get_type(file_path, &content, range),
+            AnalyzerKind::WorkspaceScope(analyzer) => analyzer.get_type(file_path, &content, range),
+        }
+    }
+
+    fn get_scopes(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Vec<ScopeInfo>> {
+        let content = self.read_file(file_path)?;
+
+        match &self.analyzer {
+            AnalyzerKind::FileScope(analyzer) => analyzer.get_scopes(file_path, &content, range),
+            AnalyzerKind::WorkspaceScope(analyzer) => {
+                analyzer.get_scopes(file_path, &content, range)
+            }
+        }
+    }
+
+    fn get_control_flow(
+        &self,
+        file_path: &Path,
+        range: ByteRange,
+    ) -> SemanticResult<Option<ControlFlowGraph>> {
+        // Control flow is syntactic, so both modes build it from the file alone
+        let content = self.read_file(file_path)?;
+        Ok(Some(control_flow_at(file_path, &content, range)?))
+    }
+
+    fn get_control_flows(
+        &self,
+        file_path: &Path,
+        content: &str,
+    ) -> SemanticResult<Option<Vec<ControlFlowGraph>>> {
+        Ok(Some(control_flows(file_path, content)?))
     }
 
     fn notify_file_processed(&self, file_path: &Path, content: &str) -> SemanticResult<()> {
@@ -288,6 +342,38 @@ impl SemanticProvider for OxcSemanticProvider {
         }
     }
 
+    fn get_imports(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        match &self.analyzer {
+            // Module resolution needs a workspace root
+            AnalyzerKind::FileScope(_) => Ok(Vec::new()),
+            AnalyzerKind::WorkspaceScope(analyzer) => analyzer.get_imports(file_path),
+        }
+    }
+
+    fn get_importers(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        match &self.analyzer {
+            AnalyzerKind::FileScope(_) => Ok(Vec::new()),
+            AnalyzerKind::WorkspaceScope(analyzer) => analyzer.get_importers(file_path),
+        }
+    }
+
+    fn get_dependents(&self, file_paths: &[PathBuf]) -> SemanticResult<Vec<PathBuf>> {
+        match &self.analyzer {
+            AnalyzerKind::FileScope(_) => Ok(Vec::new()),
+            AnalyzerKind::WorkspaceScope(analyzer) => analyzer.get_dependents(file_paths),
+        }
+    }
+
+    fn set_file_overlay(&self, file_path: &Path, content: Option<&str>) -> SemanticResult<()> {
+        let canonical_file = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        self.overlay.set(canonical_file, content);
+        // Re-index so cached symbols match the content queries now read
+        let content = self.read_file(file_path)?;
+        self.notify_file_processed(file_path, &content)
+    }
+
     fn supports_language(&self, lang: &str) -> bool {
         matches!(
             lang.to_lowercase().as_str(),
@@ -469,18 +555,106 @@ console.log(x);"#;
     }
 
     #[test]
-    fn test_provider_get_type_returns_none() {
+    fn test_provider_get_type() {
         let provider = OxcSemanticProvider::file_scope();
         let dir = TempDir::new().unwrap();
         let file_path = dir.path().join("test.ts");
-        fs::write(&file_path, "const x = 1;").unwrap();
+        fs::write(&file_path, "const x = 1;\nlet y: Set<string>;").unwrap();
 
-        // Type info is not yet implemented
         let result = provider.get_type(&file_path, ByteRange::new(6, 7));
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap().as_deref(), Some("number"));
+
+        let result = provider.get_type(&file_path, ByteRange::new(17, 18));
+        assert_eq!(result.unwrap().as_deref(), Some("Set<string>"));
+
+        // Nodes without a declared type report nothing
+        fs::write(&file_path, "const z = compute();").unwrap();
+        provider.clear_cache();
+        let result = provider.get_type(&file_path, ByteRange::new(6, 7));
         assert!(result.unwrap().is_none());
     }
 
+    #[test]
+    fn test_provider_get_scopes() {
+        let provider = OxcSemanticProvider::file_scope();
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.ts");
+        fs::write(&file_path, "const x = 1;\nfunction f(a) { return a + x; }").unwrap();
+
+        let scopes = provider
+            .get_scopes(&file_path, ByteRange::new(36, 41))
+            .unwrap();
+        assert_eq!(scopes.len(), 2);
+        assert_eq!(scopes[0].kind, language_core::ScopeKind::Function);
+        assert!(scopes[0].binds("a"));
+        assert_eq!(scopes[0].free_names, vec!["x"]);
+        assert!(scopes[1].binds("f"));
+        assert!(scopes[1].binds("x"));
+    }
+
+    #[test]
+    fn test_provider_get_control_flow() {
+        let provider = OxcSemanticProvider::file_scope();
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.ts");
+        let content = "function f() {\n  return 1;\n  dead();\n}\n";
+        fs::write(&file_path, content).unwrap();
+
+        let start = content.find("dead()").unwrap() as u32;
+        let dead = ByteRange::new(start, start + 6);
+        let graph = provider
+            .get_control_flow(&file_path, dead)
+            .unwrap()
+            .unwrap();
+        assert_eq!(graph.range, ByteRange::new(0, content.len() as u32 - 1));
+        assert!(!graph.is_reachable(dead));
+    }
+
+    #[test]
+    fn test_provider_file_overlay() {
+        let provider = OxcSemanticProvider::file_scope();
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.ts");
+        fs::write(&file_path, "const x = 1;").unwrap();
+        assert_eq!(
+            provider
+                .get_type(&file_path, ByteRange::new(6, 7))
+                .unwrap()
+                .as_deref(),
+            Some("number")
+        );
+
+        // Queries and cached symbols follow the overlay, not the file
+        provider
+            .set_file_overlay(&file_path, Some("const x = \"one\";"))
+            .unwrap();
+        assert_eq!(
+            provider
+                .get_type(&file_path, ByteRange::new(6, 7))
+                .unwrap()
+                .as_deref(),
+            Some("string")
+        );
+        let definition = provider
+            .get_definition(
+                &file_path,
+                ByteRange::new(6, 7),
+                DefinitionOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(definition.content, "const x = \"one\";");
+
+        provider.set_file_overlay(&file_path, None).unwrap();
+        assert_eq!(
+            provider
+                .get_type(&file_path, ByteRange::new(6, 7))
+                .unwrap()
+                .as_deref(),
+            Some("number")
+        );
+    }
+
     // VFS (Virtual FileSystem) tests
 
     #[test]
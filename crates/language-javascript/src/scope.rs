@@ -0,0 +1,168 @@
+//! Scope and binding lookup for `scope()`.
+//!
+//! Scopes come from the scope tree extracted by [`crate::oxc_adapter`]. This
+//! module finds the innermost scope around a node range and describes it and
+//! each enclosing scope: the names declared in it, the names declared in
+//! scopes nested inside it and the names it uses from outside.
+
+use crate::cache::{FileSymbols, Scope};
+use language_core::{ByteRange, ScopeBinding, ScopeInfo};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Get the scopes enclosing `range`, innermost first and ending with the
+/// module scope.
+pub fn scopes_at(file_symbols: &FileSymbols, range: ByteRange) -> Vec<ScopeInfo> {
+    let scopes: HashMap<u32, &Scope> = file_symbols
+        .scopes
+        .iter()
+        .map(|scope| (scope.scope_id, scope))
+        .collect();
+
+    // Scopes are ordered outer to inner, so the last containing scope of
+    // the smallest size is the innermost one
+    let Some(innermost) = file_symbols
+        .scopes
+        .iter()
+        .filter(|scope| scope.range.start <= range.start && scope.range.end >= range.end)
+        .min_by_key(|scope| {
+            (
+                scope.range.end - scope.range.start,
+                u32::MAX - scope.scope_id,
+            )
+        })
+    else {
+        return Vec::new();
+    };
+
+    let mut chain = Vec::new();
+    let mut current = Some(innermost);
+    while let Some(scope) = current {
+        chain.push(describe(file_symbols, &scopes, scope));
+        current = scope.parent_id.and_then(|id| scopes.get(&id).copied());
+    }
+    chain
+}
+
+/// Build the [`ScopeInfo`] for a single scope.
+fn describe(file_symbols: &FileSymbols, scopes: &HashMap<u32, &Scope>, scope: &Scope) -> ScopeInfo {
+    let within = |scope_id: u32| is_within(scopes, scope_id, scope.scope_id);
+
+    let mut bindings: Vec<ScopeBinding> = file_symbols
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.scope_id == scope.scope_id)
+        .map(|symbol| ScopeBinding {
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            range: symbol.range,
+        })
+        .collect();
+    bindings.sort_by_key(|binding| binding.range.start);
+
+    let nested_names: BTreeSet<String> = file_symbols
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.scope_id != scope.scope_id && within(symbol.scope_id))
+        .map(|symbol| symbol.name.clone())
+        .collect();
+
+    let contains =
+        |range: &ByteRange| range.start >= scope.range.start && range.end <= scope.range.end;
+    let mut outside: HashSet<u32> = HashSet::new();
+    let mut free_names: BTreeSet<String> = BTreeSet::new();
+    for reference in &file_symbols.references {
+        if !contains(&reference.range) || outside.contains(&reference.symbol_id) {
+            continue;
+        }
+        if let Some(symbol) = file_symbols.find_symbol_by_id(reference.symbol_id) {
+            if !within(symbol.scope_id) {
+                outside.insert(symbol.symbol_id);
+                free_names.insert(symbol.name.clone());
+            }
+        }
+    }
+    free_names.extend(
+        file_symbols
+            .unresolved_references
+            .iter()
+            .filter(|reference| contains(&reference.range))
+            .map(|reference| reference.name.clone()),
+    );
+
+    ScopeInfo {
+        kind: scope.kind,
+        range: scope.range,
+        bindings,
+        nested_names: nested_names.into_iter().collect(),
+        free_names: free_names.into_iter().collect(),
+    }
+}
+
+/// Whether `scope_id` is `ancestor_id` or nested inside it.
+fn is_within(scopes: &HashMap<u32, &Scope>, scope_id: u32, ancestor_id: u32) -> bool {
+    let mut current = Some(scope_id);
+    while let Some(id) = current {
+        if id == ancestor_id {
+            return true;
+        }
+        current = scopes.get(&id).and_then(|scope| scope.parent_id);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oxc_adapter::parse_and_analyze;
+    use language_core::{ScopeKind, SymbolKind};
+    use std::path::Path;
+
+    fn scopes_of(content: &str, needle: &str) -> Vec<ScopeInfo> {
+        let symbols = parse_and_analyze(Path::new("test.ts"), content).unwrap();
+        let start = content.find(needle).unwrap() as u32;
+        scopes_at(&symbols, ByteRange::new(start, start + needle.len() as u32))
+    }
+
+    fn names(scope: &ScopeInfo) -> Vec<&str> {
+        scope.bindings.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    #[test]
+    fn reports_chain_from_innermost_to_module() {
+        let content = "import { a } from './a';\nconst top = 1;\nfunction run(arg) {\n  const local = arg + top;\n  if (local) {\n    let inner = 2;\n    log(inner);\n  }\n}\n";
+        let chain = scopes_of(content, "log(inner)");
+
+        let kinds: Vec<ScopeKind> = chain.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ScopeKind::Block, ScopeKind::Function, ScopeKind::Module]
+        );
+        assert_eq!(names(&chain[0]), vec!["inner"]);
+        assert_eq!(names(&chain[1]), vec!["arg", "local"]);
+        assert_eq!(names(&chain[2]), vec!["a", "top", "run"]);
+        assert_eq!(chain[2].bindings[0].kind, SymbolKind::Import);
+    }
+
+    #[test]
+    fn reports_nested_and_free_names() {
+        let content = "const top = 1;\nfunction run(arg) {\n  const local = arg + top;\n  return () => { const deep = local; console.log(deep); };\n}\n";
+        let chain = scopes_of(content, "const local");
+        let function = &chain[0];
+
+        assert_eq!(function.kind, ScopeKind::Function);
+        assert_eq!(function.nested_names, vec!["deep"]);
+        assert_eq!(function.free_names, vec!["console", "top"]);
+        assert!(function.binds("local"));
+        assert!(!function.binds("top"));
+
+        let module = chain.last().unwrap();
+        assert_eq!(module.free_names, vec!["console"]);
+        assert_eq!(module.nested_names, vec!["arg", "deep", "local"]);
+    }
+
+    #[test]
+    fn reports_nothing_outside_any_scope() {
+        let symbols = parse_and_analyze(Path::new("test.ts"), "const x = 1;").unwrap();
+        assert!(scopes_at(&symbols, ByteRange::new(100, 110)).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Declared-type lookup for `typeInfo()`.
+//!
+//! Types come from the symbol information extracted by [`crate::oxc_adapter`].
+//! This module maps a node range to the symbol it names, follows imports into
+//! the files they resolve to and expands type aliases to their targets.
+
+use crate::cache::{FileSymbols, ImportedSymbol, Symbol};
+use language_core::{ByteRange, SymbolKind};
+use std::path::{Path, PathBuf};
+
+/// Maximum number of imports and aliases followed for a single lookup.
+const MAX_RESOLUTION_DEPTH: usize = 8;
+
+/// Resolves an import to the file it points at and that file's symbols.
+pub type ImportResolver<'a> = dyn Fn(&ImportedSymbol, &Path) -> Option<(PathBuf, FileSymbols)> + 'a;
+
+/// Get the type of the node at `range`.
+///
+/// - An identifier reports the declared type of the symbol it refers to.
+/// - A call `f(...)` reports the annotated return type of `f`.
+/// - A literal reports its primitive type.
+pub fn type_at(
+    file_path: &Path,
+    file_symbols: &FileSymbols,
+    content: &str,
+    range: ByteRange,
+    resolve_import: &ImportResolver,
+) -> Option<String> {
+    let resolver = TypeResolver { resolve_import };
+
+    if let Some(reference) = file_symbols
+        .references
+        .iter()
+        .find(|r| r.range.start <= range.start && r.range.end >= range.end)
+    {
+        let symbol = file_symbols.find_symbol_by_id(reference.symbol_id)?;
+        return resolver.symbol_type(file_path, file_symbols, symbol, 0);
+    }
+
+    if let Some(reference) = file_symbols
+        .references
+        .iter()
+        .find(|r| r.range.start == range.start && r.range.end < range.end)
+    {
+        let suffix = content.get(reference.range.end as usize..range.end as usize)?;
+        if is_call_suffix(suffix) {
+            let symbol = file_symbols.find_symbol_by_id(reference.symbol_id)?;
+            return resolver.return_type(file_path, file_symbols, symbol, 0);
+        }
+        return None;
+    }
+
+    if let Some(symbol) = file_symbols.find_symbol_at(range) {
+        return resolver.symbol_type(file_path, file_symbols, symbol, 0);
+    }
+
+    literal_type(
+        content
+            .get(range.start as usize..range.end as usize)?
+            .trim(),
+    )
+}
+
+struct TypeResolver<'r, 'a> {
+    resolve_import: &'r ImportResolver<'a>,
+}
+
+impl TypeResolver<'_, '_> {
+    fn symbol_type(
+        &self,
+        file_path: &Path,
+        file_symbols: &FileSymbols,
+        symbol: &Symbol,
+        depth: usize,
+    ) -> Option<String> {
+        if depth > MAX_RESOLUTION_DEPTH {
+            return None;
+        }
+        if symbol.kind == SymbolKind::Import {
+            let (target_path, target_symbols, target) =
+                self.imported_symbol(file_path, file_symbols, &symbol.name)?;
+            return self.symbol_type(&target_path, &target_symbols, &target, depth + 1);
+        }
+
+        let declared = symbol.declared_type.as_deref()?;
+        Some(self.expand_alias(file_path, file_symbols, declared, depth + 1))
+    }
+
+    fn return_type(
+        &self,
+        file_path: &Path,
+        file_symbols: &FileSymbols,
+        symbol: &Symbol,
+        depth: usize,
+    ) -> Option<String> {
+        if depth > MAX_RESOLUTION_DEPTH {
+            return None;
+        }
+        if symbol.kind == SymbolKind::Import {
+            let (target_path, target_symbols, target) =
+                self.imported_symbol(file_path, file_symbols, &symbol.name)?;
+            return self.return_type(&target_path, &target_symbols, &target, depth + 1);
+        }
+
+        let return_type = symbol.return_type.as_deref()?;
+        Some(self.expand_alias(file_path, file_symbols, return_type, depth + 1))
+    }
+
+    /// Replace a bare type alias name with the type it aliases, following
+    /// imports of the alias into other files.
+    fn expand_alias(
+        &self,
+        file_path: &Path,
+        file_symbols: &FileSymbols,
+        type_text: &str,
+        depth: usize,
+    ) -> String {
+        if depth > MAX_RESOLUTION_DEPTH || !is_identifier(type_text) {
+            return type_text.to_string();
+        }
+
+        if let Some(alias) = file_symbols
+            .symbols
+            .iter()
+            .find(|s| s.kind == SymbolKind::Type && s.name == type_text)
+        {
+            return match &alias.declared_type {
+                Some(target) => self.expand_alias(file_path, file_symbols, target, depth + 1),
+                None => type_text.to_string(),
+            };
+        }
+
+        if let Some((target_path, target_symbols, target)) =
+            self.imported_symbol(file_path, file_symbols, type_text)
+        {
+            if target.kind == SymbolKind::Type {
+                if let Some(aliased) = &target.declared_type {
+                    return self.expand_alias(&target_path, &target_symbols, aliased, depth + 1);
+                }
+            }
+        }
+
+        type_text.to_string()
+    }
+
+    /// Find the symbol an import binding refers to in the imported file.
+    fn imported_symbol(
+        &self,
+        file_path: &Path,
+        file_symbols: &FileSymbols,
+        local_name: &str,
+    ) -> Option<(PathBuf, FileSymbols, Symbol)> {
+        let import = file_symbols
+            .imports
+            .iter()
+            .find(|i| i.local_name == local_name && !i.is_namespace)?;
+        let (target_path, target_symbols) = (self.resolve_import)(import, file_path)?;
+
+        let export = if import.is_default {
+            target_symbols.get_default_export()?
+        } else {
+            target_symbols
+                .find_export_by_name(import.imported_name.as_deref().unwrap_or(local_name))?
+        };
+        let target = target_symbols
+            .find_symbol_by_id(export.local_symbol_id?)?
+            .clone();
+        Some((target_path, target_symbols, target))
+    }
+}
+
+/// Whether `suffix` (the text after a callee) is a single call's arguments,
+/// optionally preceded by type arguments: `(...)`, `<T>(...)` or `?.(...)`.
+fn is_call_suffix(suffix: &str) -> bool {
+    let mut rest = suffix.trim_start();
+    rest = rest.strip_prefix("?.").unwrap_or(rest);
+    if rest.starts_with('<') {
+        match closing_index(rest, '<', '>') {
+            Some(end) => rest = rest[end + 1..].trim_start(),
+            None => return false,
+        }
+    }
+    rest.starts_with('(') && closing_index(rest, '(', ')') == Some(rest.len() - 1)
+}
+
+/// Index of the delimiter closing the one that opens `text`.
+fn closing_index(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, ch) in text.char_indices() {
+        if ch == open {
+            depth += 1;
+        } else if ch == close {
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$')
+        && chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+}
+
+fn literal_type(text: &str) -> Option<String> {
+    let type_name = match text {
+        "true" | "false" => "boolean",
+        "null" => "null",
+        "undefined" => "undefined",
+        _ if text.starts_with(['"', '\'', '`']) => "string",
+        _ if text.ends_with('n') && text[..text.len() - 1].parse::<u128>().is_ok() => "bigint",
+        _ if text.parse::<f64>().is_ok() => "number",
+        _ => return None,
+    };
+    Some(type_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oxc_adapter::parse_and_analyze;
+
+    fn no_imports(_: &ImportedSymbol, _: &Path) -> Option<(PathBuf, FileSymbols)> {
+        None
+    }
+
+    fn type_of(content: &str, needle: &str, occurrence: usize) -> Option<String> {
+        let path = Path::new("test.ts");
+        let symbols = parse_and_analyze(path, content).unwrap();
+        let start = content.match_indices(needle).nth(occurrence).unwrap().0 as u32;
+        let range = ByteRange::new(start, start + needle.len() as u32);
+        type_at(path, &symbols, content, range, &no_imports)
+    }
+
+    #[test]
+    fn reports_declared_type_of_references() {
+        let content = "const names: string[] = [];\nnames.push('a');\n";
+        assert_eq!(type_of(content, "names", 1).as_deref(), Some("string[]"));
+    }
+
+    #[test]
+    fn expands_local_type_aliases() {
+        let content = "type Cache = Map<string, number>;\nlet cache: Cache;\ncache.get('a');\n";
+        assert_eq!(
+            type_of(content, "cache", 1).as_deref(),
+            Some("Map<string, number>")
+        );
+    }
+
+    #[test]
+    fn reports_return_type_of_calls() {
+        let content = "function load(): Promise<string> { return fetch(); }\nload();\n";
+        assert_eq!(
+            type_of(content, "load()", 1).as_deref(),
+            Some("Promise<string>")
+        );
+        assert_eq!(
+            type_of(
+                "function f(): number { return 1; }\nf().toFixed();\n",
+                "f().toFixed()",
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_literal_types() {
+        assert_eq!(
+            type_of("call(\"x\", 42);\n", "\"x\"", 0).as_deref(),
+            Some("string")
+        );
+        assert_eq!(
+            type_of("call(\"x\", 42);\n", "42", 0).as_deref(),
+            Some("number")
+        );
+    }
+
+    #[test]
+    fn follows_imported_type_aliases() {
+        let types_path = PathBuf::from("types.ts");
+        let types =
+            parse_and_analyze(&types_path, "export type Cache = Map<string, number>;\n").unwrap();
+        let content = "import { Cache } from './types';\nconst cache: Cache = new Map();\n";
+        let path = Path::new("main.ts");
+        let symbols = parse_and_analyze(path, content).unwrap();
+        let resolve = |import: &ImportedSymbol, _: &Path| {
+            (import.module_specifier == "./types").then(|| (types_path.clone(), types.clone()))
+        };
+
+        let start = content.find("cache").unwrap() as u32;
+        let result = type_at(
+            path,
+            &symbols,
+            content,
+            ByteRange::new(start, start + 5),
+            &resolve,
+        );
+        assert_eq!(result.as_deref(), Some("Map<string, number>"));
+    }
+}
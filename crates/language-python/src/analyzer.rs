@@ -4,24 +4,41 @@
 //! to Ruff's ty_ide crate, which provides battle-tested goto-definition
 //! and find-references functionality.
 
-use crate::db::create_db_with_files;
+use crate::db::{create_db_with_files, PythonSemanticDb};
 use crate::error::{PySemanticError, PySemanticResult};
+use crate::imports::{parse_imports, resolve_imports, ImportStatement};
 use language_core::{
-    ByteRange, DefinitionKind, DefinitionOptions, DefinitionResult, FileReferences,
+    ByteRange, DefinitionKind, DefinitionOptions, DefinitionResult, FileReferences, IndexStore,
     ReferencesResult, SymbolKind, SymbolLocation,
 };
 use parking_lot::RwLock;
+use ruff_db::files::File;
 use ruff_db::source::source_text;
 use ruff_text_size::TextSize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use ty_ide::{goto_definition, goto_references};
+use ty_ide::{goto_definition, goto_references, hover, MarkupKind};
 
 /// Convert ruff's TextRange to our ByteRange.
 fn text_range_to_byte_range(range: ruff_text_size::TextRange) -> ByteRange {
     ByteRange::new(range.start().to_u32(), range.end().to_u32())
 }
 
+/// Infer the type at `offset` using ty's hover.
+///
+/// Hover renders the inferred type first, followed by docstrings separated by
+/// a horizontal rule. Only the type block is returned.
+fn inferred_type(db: &PythonSemanticDb, file: File, offset: TextSize) -> Option<String> {
+    let hover = hover(db, file, offset)?;
+    let rendered = hover.value.display(db, MarkupKind::PlainText).to_string();
+    let type_block = rendered
+        .split("\n---")
+        .next()
+        .map(str::trim)
+        .filter(|block| !block.is_empty())?;
+    Some(type_block.to_string())
+}
+
 /// File-scope analyzer using ty_ide.
 ///
 /// This analyzer creates a fresh Salsa database for each operation,
@@ -169,6 +186,30 @@ impl FileScopeAnalyzer {
         Ok(result)
     }
 
+    /// Get the inferred type of the expression starting at the given range.
+    pub fn get_type(
+        &self,
+        path: &Path,
+        content: &str,
+        range: ByteRange,
+    ) -> PySemanticResult<Option<String>> {
+        let workspace_root = path.parent().unwrap_or(path);
+
+        let mut file_contents = HashMap::new();
+        file_contents.insert(path.to_path_buf(), content.to_string());
+
+        let db = create_db_with_files(workspace_root, &file_contents)
+            .map_err(|e| PySemanticError::Other(e.to_string()))?;
+
+        let file = db
+            .get_file(path)
+            .ok_or_else(|| PySemanticError::FileNotCached {
+                path: path.to_path_buf(),
+            })?;
+
+        Ok(inferred_type(&db, file, TextSize::from(range.start)))
+    }
+
     /// Get cache stats (always empty since we don't cache).
     pub fn cache(&self) -> CacheStats {
         CacheStats { len: 0 }
@@ -183,6 +224,10 @@ pub struct WorkspaceScopeAnalyzer {
     workspace_root: PathBuf,
     /// Cached file contents for cross-file analysis.
     file_contents: RwLock<HashMap<PathBuf, String>>,
+    /// Import statements of each cached file.
+    file_imports: RwLock<HashMap<PathBuf, Vec<ImportStatement>>>,
+    /// On-disk store of extracted imports shared with other processes
+    index_store: Option<IndexStore>,
 }
 
 impl WorkspaceScopeAnalyzer {
@@ -191,7 +236,115 @@ impl WorkspaceScopeAnalyzer {
         Self {
             workspace_root,
             file_contents: RwLock::new(HashMap::new()),
+            file_imports: RwLock::new(HashMap::new()),
+            index_store: None,
+        }
+    }
+
+    /// Persist the imports extracted from each file under `dir` and reuse
+    /// entries written by earlier runs for files whose content is unchanged.
+    /// Entries are invalidated when any `pyproject.toml` in the workspace
+    /// changes.
+    pub fn with_index_dir(mut self, dir: &Path) -> Self {
+        let fingerprint =
+            IndexStore::config_fingerprint(&self.workspace_root, |name| name == "pyproject.toml");
+        self.index_store = Some(IndexStore::new(
+            dir,
+            format!("python-{}", env!("CARGO_PKG_VERSION")),
+            fingerprint,
+        ));
+        self
+    }
+
+    /// Import statements of `content`, reusing the persisted entry for
+    /// identical content. Files that fail to parse have no imports.
+    fn imports_of(&self, path: &Path, content: &str) -> Vec<ImportStatement> {
+        if let Some(imports) = self
+            .index_store
+            .as_ref()
+            .and_then(|store| store.get(path, content))
+        {
+            return imports;
+        }
+        let Ok(imports) = parse_imports(path, content) else {
+            return Vec::new();
+        };
+        if let Some(store) = &self.index_store {
+            if let Err(e) = store.insert(path, content, &imports) {
+                log::debug!(
+                    "Failed to persist index entry for {}: {}",
+                    path.display(),
+                    e
+                );
+            }
         }
+        imports
+    }
+
+    /// The cached files `path` imports, directly or transitively, together
+    /// with `path` itself at `content`. Definitions and types only depend on
+    /// these files, so queries build their database from this subset.
+    fn reachable_contents(&self, path: &Path, content: &str) -> HashMap<PathBuf, String> {
+        let file_contents = self.file_contents.read();
+        let file_imports = self.file_imports.read();
+        let known_files: HashSet<PathBuf> = file_contents.keys().cloned().collect();
+
+        let mut reachable = HashMap::from([(path.to_path_buf(), content.to_string())]);
+        let mut pending = resolve_imports(
+            &self.workspace_root,
+            path,
+            &self.imports_of(path, content),
+            &known_files,
+        );
+        while let Some(file) = pending.pop() {
+            if reachable.contains_key(&file) {
+                continue;
+            }
+            if let Some(imports) = file_imports.get(&file) {
+                pending.extend(resolve_imports(
+                    &self.workspace_root,
+                    &file,
+                    imports,
+                    &known_files,
+                ));
+            }
+            if let Some(content) = file_contents.get(&file) {
+                reachable.insert(file, content.clone());
+            }
+        }
+        reachable
+    }
+
+    /// The cached files `path` imports directly.
+    pub fn get_imports(&self, path: &Path) -> Vec<PathBuf> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let known_files: HashSet<PathBuf> = self.file_contents.read().keys().cloned().collect();
+        let file_imports = self.file_imports.read();
+        let Some(imports) = file_imports.get(&canonical) else {
+            return Vec::new();
+        };
+        let mut resolved = resolve_imports(&self.workspace_root, &canonical, imports, &known_files);
+        resolved.retain(|file| *file != canonical);
+        resolved
+    }
+
+    /// The cached files that import `path` directly.
+    pub fn get_importers(&self, path: &Path) -> Vec<PathBuf> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let known_files: HashSet<PathBuf> = self.file_contents.read().keys().cloned().collect();
+        let mut importers: Vec<PathBuf> = self
+            .file_imports
+            .read()
+            .iter()
+            .filter(|(importer, imports)| {
+                **importer != canonical
+                    && resolve_imports(&self.workspace_root, importer, imports, &known_files)
+                        .contains(&canonical)
+            })
+            .map(|(importer, _)| importer.clone())
+            .collect();
+        importers.sort();
+        importers
     }
 
     /// Get the workspace root.
@@ -203,12 +356,15 @@ impl WorkspaceScopeAnalyzer {
     /// Clear the cache.
     pub fn clear(&self) {
         self.file_contents.write().clear();
+        self.file_imports.write().clear();
     }
 
-    /// Process a file (store content for cross-file analysis).
+    /// Process a file (store content and imports for cross-file analysis).
     pub fn process_file(&self, path: &Path, content: &str) -> PySemanticResult<()> {
         // Canonicalize the path to handle symlinks (e.g., /var -> /private/var on macOS)
         let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let imports = self.imports_of(&canonical, content);
+        self.file_imports.write().insert(canonical.clone(), imports);
         self.file_contents
             .write()
             .insert(canonical, content.to_string());
@@ -226,9 +382,8 @@ impl WorkspaceScopeAnalyzer {
         // Canonicalize the path to handle symlinks (e.g., /var -> /private/var on macOS)
         let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
-        // add current file to contents
-        let mut file_contents = self.file_contents.read().clone();
-        file_contents.insert(canonical.clone(), content.to_string());
+        // Definitions only depend on the file and what it imports
+        let file_contents = self.reachable_contents(&canonical, content);
 
         let db = create_db_with_files(&self.workspace_root, &file_contents)
             .map_err(|e| PySemanticError::Other(e.to_string()))?;
@@ -333,6 +488,30 @@ impl WorkspaceScopeAnalyzer {
         Ok(result)
     }
 
+    /// Get the inferred type of the expression starting at the given range,
+    /// resolving imported symbols against the cached workspace files.
+    pub fn get_type(
+        &self,
+        path: &Path,
+        content: &str,
+        range: ByteRange,
+    ) -> PySemanticResult<Option<String>> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        let file_contents = self.reachable_contents(&canonical, content);
+
+        let db = create_db_with_files(&self.workspace_root, &file_contents)
+            .map_err(|e| PySemanticError::Other(e.to_string()))?;
+
+        let file = db
+            .get_file(&canonical)
+            .ok_or_else(|| PySemanticError::FileNotCached {
+                path: canonical.clone(),
+            })?;
+
+        Ok(inferred_type(&db, file, TextSize::from(range.start)))
+    }
+
     /// Get cache stats.
     pub fn cache(&self) -> CacheStats {
         CacheStats {
@@ -393,6 +572,103 @@ y = x + 2
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_file_scope_analyzer_get_type() {
+        let analyzer = FileScopeAnalyzer::new();
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.py");
+
+        let content = "config = {\"debug\": True}\nvalue = config.get(\"debug\")\n";
+        fs::write(&file_path, content).unwrap();
+
+        let offset = content.rfind("config").unwrap() as u32;
+        let result = analyzer
+            .get_type(&file_path, content, ByteRange::new(offset, offset + 6))
+            .unwrap();
+        assert!(result.unwrap().starts_with("dict["));
+    }
+
+    #[test]
+    fn test_workspace_scope_analyzer_get_type_across_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let analyzer = WorkspaceScopeAnalyzer::new(root.clone());
+
+        let utils_path = root.join("utils.py");
+        let utils_content = "def load() -> list[int]:\n    return [1]\n";
+        fs::write(&utils_path, utils_content).unwrap();
+        analyzer.process_file(&utils_path, utils_content).unwrap();
+
+        let main_path = root.join("main.py");
+        let main_content = "from utils import load\nitems = load()\n";
+        fs::write(&main_path, main_content).unwrap();
+
+        let offset = main_content.find("items").unwrap() as u32;
+        let result = analyzer
+            .get_type(&main_path, main_content, ByteRange::new(offset, offset + 5))
+            .unwrap();
+        assert_eq!(result.as_deref(), Some("list[int]"));
+    }
+
+    #[test]
+    fn test_workspace_scope_analyzer_persists_imports() {
+        let dir = TempDir::new().unwrap();
+        let index_dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        let files = [
+            ("pkg/__init__.py", ""),
+            ("pkg/models.py", "class User: ...\n"),
+            ("pkg/service.py", "from .models import User\n"),
+            ("main.py", "from pkg.service import User\n"),
+            ("other.py", "import os\n"),
+        ];
+        for (path, content) in files {
+            fs::write(root.join(path), content).unwrap();
+        }
+        let index = |analyzer: &WorkspaceScopeAnalyzer| {
+            for (path, content) in files {
+                analyzer.process_file(&root.join(path), content).unwrap();
+            }
+        };
+
+        let analyzer = WorkspaceScopeAnalyzer::new(root.clone()).with_index_dir(index_dir.path());
+        index(&analyzer);
+        let stats = IndexStore::stats(index_dir.path()).unwrap();
+        assert_eq!(stats.entries, files.len());
+        assert_eq!(
+            analyzer.get_importers(&root.join("pkg/models.py")),
+            vec![root.join("pkg/service.py")]
+        );
+        assert_eq!(
+            analyzer.get_imports(&root.join("main.py")),
+            vec![root.join("pkg/__init__.py"), root.join("pkg/service.py")]
+        );
+        let reachable = analyzer.reachable_contents(&root.join("main.py"), files[3].1);
+        let mut reachable: Vec<_> = reachable.into_keys().collect();
+        reachable.sort();
+        assert_eq!(
+            reachable,
+            vec![
+                root.join("main.py"),
+                root.join("pkg/__init__.py"),
+                root.join("pkg/models.py"),
+                root.join("pkg/service.py"),
+            ]
+        );
+
+        // A second process reuses the entries; editing pyproject.toml
+        // invalidates them
+        index(&WorkspaceScopeAnalyzer::new(root.clone()).with_index_dir(index_dir.path()));
+        assert_eq!(IndexStore::stats(index_dir.path()).unwrap(), stats);
+        fs::write(root.join("pyproject.toml"), "[project]\nname = \"app\"\n").unwrap();
+        index(&WorkspaceScopeAnalyzer::new(root.clone()).with_index_dir(index_dir.path()));
+        assert_eq!(
+            IndexStore::stats(index_dir.path()).unwrap().entries,
+            files.len() * 2
+        );
+    }
+
     #[test]
     fn test_workspace_scope_analyzer_new() {
         let dir = TempDir::new().unwrap();
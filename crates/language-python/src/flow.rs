@@ -0,0 +1,457 @@
+//! Control-flow graphs for `isReachable()`, `dominates()` and `exitPoints()`.
+//!
+//! The graph covers the statements of a single `def` body: nested functions
+//! and classes are single items of the enclosing graph, and get their own
+//! graph when queried directly. Any simple statement inside a `try` block
+//! may raise to its handlers. `with` blocks run their body in sequence;
+//! `__exit__` suppressing exceptions is not modelled.
+
+use crate::error::PySemanticError;
+use language_core::flow::FlowBuilder;
+use language_core::{ByteRange, ControlFlowGraph};
+use ruff_python_ast::visitor::{walk_stmt, Visitor};
+use ruff_python_ast::{ExceptHandler, Expr, Pattern, Stmt, StmtFunctionDef, StmtTry};
+use ruff_text_size::{Ranged, TextRange};
+use std::path::Path;
+
+/// Build the control-flow graph of the innermost function containing
+/// `range`, or of the top level of the module.
+pub fn control_flow_at(
+    file_path: &Path,
+    content: &str,
+    range: ByteRange,
+) -> Result<ControlFlowGraph, PySemanticError> {
+    let parsed = parse(file_path, content)?;
+    // Nested definitions are listed after the ones containing them
+    let innermost = functions(parsed.suite())
+        .into_iter()
+        .rev()
+        .find(|function| {
+            let function = to_byte_range(function.range());
+            function.start <= range.start && function.end >= range.end
+        });
+    Ok(match innermost {
+        Some(function) => build(to_byte_range(function.range()), &function.body),
+        None => build(ByteRange::new(0, content.len() as u32), parsed.suite()),
+    })
+}
+
+/// Build the control-flow graphs of the top level of the module and of
+/// every function in it, outermost first.
+pub fn control_flows(
+    file_path: &Path,
+    content: &str,
+) -> Result<Vec<ControlFlowGraph>, PySemanticError> {
+    let parsed = parse(file_path, content)?;
+    let mut graphs = vec![build(
+        ByteRange::new(0, content.len() as u32),
+        parsed.suite(),
+    )];
+    graphs.extend(
+        functions(parsed.suite())
+            .into_iter()
+            .map(|function| build(to_byte_range(function.range()), &function.body)),
+    );
+    Ok(graphs)
+}
+
+fn parse(
+    file_path: &Path,
+    content: &str,
+) -> Result<ruff_python_parser::Parsed<ruff_python_ast::ModModule>, PySemanticError> {
+    ruff_python_parser::parse_module(content).map_err(|err| PySemanticError::ParseError {
+        path: file_path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+fn build(range: ByteRange, body: &[Stmt]) -> ControlFlowGraph {
+    let mut walker = FlowWalker {
+        builder: FlowBuilder::new(range),
+    };
+    walker.statements(body);
+    walker.builder.finish()
+}
+
+/// Every `def` in `suite`, each before the ones nested in it.
+fn functions(suite: &[Stmt]) -> Vec<&StmtFunctionDef> {
+    let mut collector = FunctionCollector {
+        functions: Vec::new(),
+    };
+    for stmt in suite {
+        collector.visit_stmt(stmt);
+    }
+    collector.functions
+}
+
+struct FunctionCollector<'a> {
+    functions: Vec<&'a StmtFunctionDef>,
+}
+
+impl<'a> Visitor<'a> for FunctionCollector<'a> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Stmt::FunctionDef(function) = stmt {
+            self.functions.push(function);
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+/// Walks statements into a [`FlowBuilder`].
+struct FlowWalker {
+    builder: FlowBuilder,
+}
+
+impl FlowWalker {
+    fn item(&mut self, range: TextRange) {
+        self.builder.item(to_byte_range(range));
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::If(stmt) => {
+                self.item(stmt.test.range());
+                let mut test = self.builder.current();
+                let after = self.builder.block();
+                self.branch(test, &stmt.body, after);
+
+                let mut falls_through = true;
+                for clause in &stmt.elif_else_clauses {
+                    let next = self.builder.block();
+                    self.builder.edge(test, next);
+                    self.builder.switch_to(next);
+                    match &clause.test {
+                        Some(condition) => {
+                            self.item(condition.range());
+                            test = next;
+                            self.branch(test, &clause.body, after);
+                        }
+                        None => {
+                            self.statements(&clause.body);
+                            self.builder.edge(self.builder.current(), after);
+                            falls_through = false;
+                        }
+                    }
+                }
+                if falls_through {
+                    self.builder.edge(test, after);
+                }
+                self.builder.switch_to(after);
+            }
+            Stmt::While(stmt) => {
+                let header = self.builder.block();
+                self.builder.goto(header);
+                self.item(stmt.test.range());
+                let exits =
+                    !matches!(stmt.test.as_ref(), Expr::BooleanLiteral(literal) if literal.value);
+                self.loop_body(header, &stmt.body, &stmt.orelse, exits);
+            }
+            Stmt::For(stmt) => {
+                self.item(stmt.iter.range());
+                let header = self.builder.block();
+                self.builder.goto(header);
+                self.item(stmt.target.range());
+                self.loop_body(header, &stmt.body, &stmt.orelse, true);
+            }
+            Stmt::Try(stmt) => self.try_statement(stmt),
+            Stmt::With(stmt) => {
+                for item in &stmt.items {
+                    self.item(item.range());
+                }
+                self.statements(&stmt.body);
+            }
+            Stmt::Match(stmt) => {
+                self.item(stmt.subject.range());
+                let dispatch = self.builder.current();
+                let after = self.builder.block();
+
+                let mut exhaustive = false;
+                for case in &stmt.cases {
+                    let block = self.builder.block();
+                    self.builder.edge(dispatch, block);
+                    self.builder.switch_to(block);
+                    self.item(case.pattern.range());
+                    if let Some(guard) = &case.guard {
+                        self.item(guard.range());
+                    }
+                    self.statements(&case.body);
+                    self.builder.edge(self.builder.current(), after);
+
+                    // `case _:` and bare captures match anything
+                    let catch_all = match &case.pattern {
+                        Pattern::MatchAs(pattern) => pattern.pattern.is_none(),
+                        _ => false,
+                    };
+                    exhaustive |= catch_all && case.guard.is_none();
+                }
+                if !exhaustive {
+                    self.builder.edge(dispatch, after);
+                }
+                self.builder.switch_to(after);
+            }
+            Stmt::Return(stmt) => {
+                self.item(stmt.range());
+                self.builder.exit();
+            }
+            Stmt::Raise(stmt) => {
+                self.item(stmt.range());
+                self.builder.throw();
+            }
+            Stmt::Break(stmt) => {
+                self.item(stmt.range());
+                self.builder.break_to(None);
+            }
+            Stmt::Continue(stmt) => {
+                self.item(stmt.range());
+                self.builder.continue_to(None);
+            }
+            other => {
+                self.item(other.range());
+                self.builder.may_throw();
+            }
+        }
+    }
+
+    /// Walk `body` as the branch taken when the condition in `test` holds.
+    fn branch(&mut self, test: usize, body: &[Stmt], after: usize) {
+        let block = self.builder.block();
+        self.builder.edge(test, block);
+        self.builder.switch_to(block);
+        self.statements(body);
+        self.builder.edge(self.builder.current(), after);
+    }
+
+    /// Walk a loop entered from `header`. The `else` clause runs when the
+    /// loop ends without `break`; `exits` is false for `while True`.
+    fn loop_body(&mut self, header: usize, body: &[Stmt], orelse: &[Stmt], exits: bool) {
+        let after = self.builder.block();
+        let body_block = self.builder.block();
+        self.builder.edge(header, body_block);
+
+        self.builder
+            .push_jump_target(None, after, Some(header), true);
+        self.builder.switch_to(body_block);
+        self.statements(body);
+        self.builder.edge(self.builder.current(), header);
+        self.builder.pop_jump_target();
+
+        if exits {
+            let orelse_block = self.builder.block();
+            self.builder.edge(header, orelse_block);
+            self.builder.switch_to(orelse_block);
+            self.statements(orelse);
+            self.builder.edge(self.builder.current(), after);
+        }
+        self.builder.switch_to(after);
+    }
+
+    fn try_statement(&mut self, stmt: &StmtTry) {
+        let after = self.builder.block();
+        let finally = (!stmt.finalbody.is_empty()).then(|| self.builder.block());
+        let dispatch = (!stmt.handlers.is_empty()).then(|| self.builder.block());
+        let normal = finally.unwrap_or(after);
+        if let Some(finally) = finally {
+            self.builder.push_finalizer(finally);
+        }
+
+        // The `try` body can raise before any of its statements completes
+        let block = self.builder.block();
+        self.builder.goto(block);
+        let throw_target = dispatch.or(finally);
+        if let Some(target) = throw_target {
+            self.builder.edge(block, target);
+            self.builder.push_handler(target);
+        }
+        self.statements(&stmt.body);
+        if throw_target.is_some() {
+            self.builder.pop_handler();
+        }
+
+        // Exceptions from `else` and the handlers go through `finally`
+        if let Some(finally) = finally {
+            self.builder.push_handler(finally);
+        }
+        self.statements(&stmt.orelse);
+        self.builder.edge(self.builder.current(), normal);
+
+        if let Some(dispatch) = dispatch {
+            for handler in &stmt.handlers {
+                let ExceptHandler::ExceptHandler(handler) = handler;
+                let block = self.builder.block();
+                self.builder.edge(dispatch, block);
+                self.builder.switch_to(block);
+                if let Some(type_) = &handler.type_ {
+                    self.item(type_.range());
+                }
+                self.statements(&handler.body);
+                self.builder.edge(self.builder.current(), normal);
+            }
+            // Exceptions no handler matches keep propagating
+            self.builder.switch_to(dispatch);
+            self.builder.throw_edge();
+        }
+        if let Some(finally) = finally {
+            self.builder.pop_handler();
+            let returned = self.builder.pop_finalizer();
+
+            self.builder.switch_to(finally);
+            self.statements(&stmt.finalbody);
+            self.builder.throw_edge();
+            if returned {
+                self.builder.exit_edge();
+            }
+            self.builder.edge(self.builder.current(), after);
+        }
+        self.builder.switch_to(after);
+    }
+}
+
+fn to_byte_range(range: TextRange) -> ByteRange {
+    ByteRange::new(range.start().to_u32(), range.end().to_u32())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range_of(content: &str, needle: &str) -> ByteRange {
+        let start = content.find(needle).unwrap() as u32;
+        ByteRange::new(start, start + needle.len() as u32)
+    }
+
+    fn graph(content: &str, needle: &str) -> ControlFlowGraph {
+        control_flow_at(Path::new("test.py"), content, range_of(content, needle)).unwrap()
+    }
+
+    #[test]
+    fn unreachable_after_return_and_raise() {
+        let content = "def f(a):\n    if a:\n        return 1\n        dead()\n    raise ValueError()\n    also_dead()\n";
+        let graph = graph(content, "dead()");
+
+        assert_eq!(graph.range, ByteRange::new(0, content.len() as u32 - 1));
+        assert!(!graph.is_reachable(range_of(content, "dead()")));
+        assert!(!graph.is_reachable(range_of(content, "also_dead()")));
+        assert!(graph.is_reachable(range_of(content, "raise ValueError()")));
+    }
+
+    #[test]
+    fn dominance_of_open_and_close() {
+        let content = "def f(path):\n    handle = open(path)\n    for line in handle:\n        if not line:\n            return\n        if line == 'skip':\n            continue\n        print(line)\n    handle.close()\n";
+        let graph = graph(content, "open(path)");
+        let open = range_of(content, "open(path)");
+        let print = range_of(content, "print(line)");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.dominates(open, close));
+        assert!(graph.dominates(open, print));
+        assert!(!graph.dominates(print, close));
+        assert!(!graph.post_dominates(close, open));
+        assert_eq!(
+            graph.exit_points(),
+            vec![range_of(content, "return"), close]
+        );
+    }
+
+    #[test]
+    fn try_finally_runs_on_every_path() {
+        let content = "def f():\n    handle = open('a')\n    try:\n        if handle.bad:\n            raise ValueError()\n        handle.write()\n    except ValueError:\n        log()\n    finally:\n        handle.close()\n";
+        let graph = graph(content, "open('a')");
+        let open = range_of(content, "open('a')");
+        let write = range_of(content, "handle.write()");
+        let log = range_of(content, "log()");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.post_dominates(close, open));
+        assert!(graph.post_dominates(close, log));
+        assert!(!graph.dominates(write, log));
+        assert!(graph.is_reachable(log));
+    }
+
+    #[test]
+    fn calls_inside_try_may_raise_to_except() {
+        let content = "def f():\n    try:\n        first()\n        second()\n    except ValueError:\n        recover()\n    done()\n";
+        let graph = graph(content, "first()");
+        let first = range_of(content, "first()");
+        let second = range_of(content, "second()");
+        let recover = range_of(content, "recover()");
+
+        // `first()` may raise before `second()` runs
+        assert!(!graph.post_dominates(second, first));
+        assert!(!graph.dominates(second, recover));
+        assert!(graph.post_dominates(range_of(content, "done()"), recover));
+        // Exceptions `except ValueError` doesn't match leave the function;
+        // outside `try`, `done()` is assumed not to raise
+        assert_eq!(
+            graph.exit_points(),
+            vec![first, second, range_of(content, "done()")]
+        );
+    }
+
+    #[test]
+    fn return_runs_finally() {
+        let content = "def f():\n    handle = open('a')\n    try:\n        if handle.empty:\n            return\n        handle.write()\n    finally:\n        handle.close()\n    after()\n";
+        let graph = graph(content, "open('a')");
+        let close = range_of(content, "handle.close()");
+
+        assert!(graph.post_dominates(close, range_of(content, "open('a')")));
+        assert!(graph.post_dominates(close, range_of(content, "return")));
+        assert!(!graph.post_dominates(range_of(content, "after()"), range_of(content, "return")));
+        assert_eq!(
+            graph.exit_points(),
+            vec![close, range_of(content, "after()")]
+        );
+    }
+
+    #[test]
+    fn loops_with_else_and_while_true() {
+        let content = "def f(items):\n    for item in items:\n        if item:\n            break\n    else:\n        missing()\n    while True:\n        if poll():\n            break\n    done()\n";
+        let graph = graph(content, "missing()");
+        let missing = range_of(content, "missing()");
+        let done = range_of(content, "done()");
+
+        assert!(graph.is_reachable(missing));
+        assert!(!graph.dominates(missing, done));
+        // `while True` is only left through `break`
+        assert!(graph.post_dominates(range_of(content, "poll()"), missing));
+        assert_eq!(graph.exit_points(), vec![done]);
+    }
+
+    #[test]
+    fn nested_functions_have_their_own_graph() {
+        let content = "class A:\n    def method(self):\n        def inner():\n            work()\n        return inner\n\ntop_level()\n";
+        let inner = graph(content, "work()");
+        assert_eq!(inner.exit_points(), vec![range_of(content, "work()")]);
+
+        let method = graph(content, "return inner");
+        assert_eq!(
+            method.exit_points(),
+            vec![range_of(content, "return inner")]
+        );
+
+        let module = graph(content, "top_level()");
+        assert_eq!(module.range, ByteRange::new(0, content.len() as u32));
+    }
+
+    #[test]
+    fn file_graphs_match_the_graph_at_each_range() {
+        let content = "top_level()\nclass A:\n    def method(self):\n        in_method()\ndef outer():\n    def inner():\n        in_inner()\n    in_outer()\n";
+        let graphs = control_flows(Path::new("test.py"), content).unwrap();
+        assert_eq!(graphs.len(), 4);
+        assert_eq!(graphs[0].range, ByteRange::new(0, content.len() as u32));
+
+        for needle in ["top_level()", "in_method()", "in_inner()", "in_outer()"] {
+            let range = range_of(content, needle);
+            assert_eq!(
+                ControlFlowGraph::innermost(&graphs, range),
+                Some(&graph(content, needle)),
+                "{needle}"
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Import graph of a Python workspace.
+//!
+//! Every workspace query builds a fresh ty database, so the analyzer keeps
+//! the database small by only loading the files a query can reach. The
+//! import statements of each file are extracted once, persisted in the
+//! index store (they only depend on the file content) and resolved against
+//! the workspace's files on demand.
+
+use crate::error::PySemanticError;
+use ruff_python_ast::statement_visitor::{walk_stmt, StatementVisitor};
+use ruff_python_ast::Stmt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// One `import` or `from ... import` statement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportStatement {
+    /// Number of leading dots of a relative import
+    pub level: u32,
+    /// Dotted module path, absent for `from . import x`
+    pub module: Option<String>,
+    /// Names imported by `from ... import`, which may be submodules
+    pub names: Vec<String>,
+}
+
+/// Extract the import statements of a module, including ones nested in
+/// functions, classes and conditionals.
+pub fn parse_imports(
+    file_path: &Path,
+    content: &str,
+) -> Result<Vec<ImportStatement>, PySemanticError> {
+    let parsed =
+        ruff_python_parser::parse_module(content).map_err(|err| PySemanticError::ParseError {
+            path: file_path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+    let mut collector = ImportCollector::default();
+    collector.visit_body(parsed.suite());
+    Ok(collector.imports)
+}
+
+#[derive(Default)]
+struct ImportCollector {
+    imports: Vec<ImportStatement>,
+}
+
+impl<'a> StatementVisitor<'a> for ImportCollector {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Import(import) => {
+                for alias in &import.names {
+                    self.imports.push(ImportStatement {
+                        level: 0,
+                        module: Some(alias.name.as_str().to_string()),
+                        names: Vec::new(),
+                    });
+                }
+            }
+            Stmt::ImportFrom(import) => self.imports.push(ImportStatement {
+                level: import.level,
+                module: import
+                    .module
+                    .as_ref()
+                    .map(|module| module.as_str().to_string()),
+                names: import
+                    .names
+                    .iter()
+                    .map(|alias| alias.name.as_str().to_string())
+                    .filter(|name| name != "*")
+                    .collect(),
+            }),
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
+
+/// Resolve `imports` of `importer` to the workspace files in `known_files`.
+///
+/// Absolute imports are resolved from `workspace_root`, the only search path
+/// the ty database is configured with. Every package `__init__.py` on the way
+/// is included, since importing a submodule executes them.
+pub fn resolve_imports(
+    workspace_root: &Path,
+    importer: &Path,
+    imports: &[ImportStatement],
+    known_files: &HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut resolved = Vec::new();
+    for import in imports {
+        let base = if import.level == 0 {
+            workspace_root.to_path_buf()
+        } else {
+            let mut base = importer.parent().unwrap_or(workspace_root).to_path_buf();
+            for _ in 1..import.level {
+                if !base.pop() {
+                    break;
+                }
+            }
+            base
+        };
+
+        let mut package = base;
+        for part in import.module.iter().flat_map(|module| module.split('.')) {
+            package.push(part);
+            for candidate in [package.join("__init__.py"), package.with_extension("py")] {
+                if known_files.contains(&candidate) {
+                    resolved.push(candidate);
+                }
+            }
+        }
+        if import.level > 0 && import.module.is_none() {
+            let init = package.join("__init__.py");
+            if known_files.contains(&init) {
+                resolved.push(init);
+            }
+        }
+        for name in &import.names {
+            let submodule = package.join(name);
+            for candidate in [
+                submodule.join("__init__.py"),
+                submodule.with_extension("py"),
+            ] {
+                if known_files.contains(&candidate) {
+                    resolved.push(candidate);
+                }
+            }
+        }
+    }
+    resolved.sort();
+    resolved.dedup();
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve_imports() {
+        let root = Path::new("/ws");
+        let content = "\
+import os
+import pkg.models as models
+from . import sibling
+from .helpers import format_name
+from ..shared import *
+
+def load():
+    from pkg import service
+";
+        let importer = root.join("pkg/api/views.py");
+        let imports = parse_imports(&importer, content).unwrap();
+        assert_eq!(imports.len(), 6);
+        assert_eq!(
+            imports[3],
+            ImportStatement {
+                level: 1,
+                module: Some("helpers".to_string()),
+                names: vec!["format_name".to_string()],
+            }
+        );
+
+        let known_files: HashSet<PathBuf> = [
+            "pkg/__init__.py",
+            "pkg/models.py",
+            "pkg/service.py",
+            "pkg/api/sibling.py",
+            "pkg/api/helpers.py",
+            "pkg/shared/__init__.py",
+            "pkg/unused.py",
+        ]
+        .iter()
+        .map(|path| root.join(path))
+        .collect();
+        assert_eq!(
+            resolve_imports(root, &importer, &imports, &known_files),
+            [
+                "pkg/__init__.py",
+                "pkg/api/helpers.py",
+                "pkg/api/sibling.py",
+                "pkg/models.py",
+                "pkg/service.py",
+                "pkg/shared/__init__.py",
+            ]
+            .iter()
+            .map(|path| root.join(path))
+            .collect::<Vec<_>>()
+        );
+    }
+}
@@ -8,6 +8,8 @@
 //!
 //! - Symbol definition lookup (go-to-definition)
 //! - Reference finding (find-all-references)
+//! - Scope and binding lookup
+//! - Per-function control-flow graphs
 //! - Two analysis modes: FileScope (single-file) and WorkspaceScope (workspace-wide)
 //!
 //! # Example
@@ -40,7 +42,10 @@
 mod analyzer;
 mod db;
 mod error;
+mod flow;
+mod imports;
 mod provider;
+mod scope;
 
 pub use error::PySemanticError;
 pub use provider::RuffSemanticProvider;
@@ -1,9 +1,11 @@
 //! Main Ruff semantic provider implementation for Python.
 
 use crate::analyzer::{FileScopeAnalyzer, WorkspaceScopeAnalyzer};
+use crate::flow::{control_flow_at, control_flows};
+use crate::scope::scopes_at;
 use language_core::{
-    filesystem, ByteRange, DefinitionOptions, DefinitionResult, ProviderMode, ReferencesResult,
-    SemanticProvider, SemanticResult,
+    filesystem, filesystem::ContentOverlay, ByteRange, ControlFlowGraph, DefinitionOptions,
+    DefinitionResult, ProviderMode, ReferencesResult, ScopeInfo, SemanticProvider, SemanticResult,
 };
 use std::path::{Path, PathBuf};
 use vfs::VfsPath;
@@ -36,6 +38,8 @@ pub struct RuffSemanticProvider {
     /// Physical root path for converting absolute paths to relative paths.
     /// Only used when fs_root is PhysicalFS.
     physical_root: Option<PathBuf>,
+    /// Contents served instead of the filesystem, by canonical path
+    overlay: ContentOverlay,
 }
 
 impl RuffSemanticProvider {
@@ -54,6 +58,7 @@ impl RuffSemanticProvider {
             analyzer: AnalyzerKind::FileScope(FileScopeAnalyzer::new()),
             fs_root: filesystem::physical_path(&cwd),
             physical_root: Some(cwd),
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -71,6 +76,7 @@ impl RuffSemanticProvider {
             analyzer: AnalyzerKind::FileScope(FileScopeAnalyzer::new()),
             fs_root,
             physical_root: None, // MemoryFS or other VFS - paths are virtual
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -94,6 +100,7 @@ impl RuffSemanticProvider {
             )),
             fs_root,
             physical_root: Some(canonical_root),
+            overlay: ContentOverlay::new(),
         }
     }
 
@@ -108,9 +115,19 @@ impl RuffSemanticProvider {
             analyzer: AnalyzerKind::WorkspaceScope(WorkspaceScopeAnalyzer::new(workspace_root)),
             fs_root,
             physical_root: None, // Custom VFS - paths handled by VFS implementation
+            overlay: ContentOverlay::new(),
         }
     }
 
+    /// Persist the imports of workspace files under `dir` so later runs only
+    /// re-parse files whose content changed. No-op in file scope.
+    pub fn with_index_dir(mut self, dir: &Path) -> Self {
+        if let AnalyzerKind::WorkspaceScope(analyzer) = self.analyzer {
+            self.analyzer = AnalyzerKind::WorkspaceScope(analyzer.with_index_dir(dir));
+        }
+        self
+    }
+
     /// Clear all cached data.
     pub fn clear_cache(&self) {
         match &self.analyzer {
@@ -134,6 +151,9 @@ impl RuffSemanticProvider {
         let canonical_file = file_path
             .canonicalize()
             .unwrap_or_else(|_| file_path.to_path_buf());
+        if let Some(content) = self.overlay.get(&canonical_file) {
+            return Ok(content);
+        }
 
         // For PhysicalFS, try to convert to a path relative to the physical root.
         // If the file is outside the root, read it directly using its absolute path.
@@ -236,10 +256,60 @@ impl SemanticProvider for RuffSemanticProvider {
         }
     }
 
-    fn get_type(&self, _file_path: &Path, _range: ByteRange) -> SemanticResult<Option<String>> {
-        // Type inference is available through ty_ide but not exposed yet
-        // Could be added in the future using ty_ide::hover
-        Ok(None)
+    fn get_type(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Option<String>> {
+        let content = self.read_file(file_path)?;
+
+        match &self.analyzer {
+            AnalyzerKind::FileScope(analyzer) => analyzer
+                .get_type(file_path, &content, range)
+                .map_err(Into::into),
+            AnalyzerKind::WorkspaceScope(analyzer) => analyzer
+                .get_type(file_path, &content, range)
+                .map_err(Into::into),
+        }
+    }
+
+    fn get_scopes(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Vec<ScopeInfo>> {
+        // Scopes only depend on the file itself, so both modes share the
+        // same syntactic analysis
+        let content = self.read_file(file_path)?;
+        scopes_at(file_path, &content, range).map_err(Into::into)
+    }
+
+    fn get_control_flow(
+        &self,
+        file_path: &Path,
+        range: ByteRange,
+    ) -> SemanticResult<Option<ControlFlowGraph>> {
+        let content = self.read_file(file_path)?;
+        control_flow_at(file_path, &content, range)
+            .map(Some)
+            .map_err(Into::into)
+    }
+
+    fn get_control_flows(
+        &self,
+        file_path: &Path,
+        content: &str,
+    ) -> SemanticResult<Option<Vec<ControlFlowGraph>>> {
+        control_flows(file_path, content)
+            .map(Some)
+            .map_err(Into::into)
+    }
+
+    fn get_imports(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        match &self.analyzer {
+            // Module resolution needs a workspace root
+            AnalyzerKind::FileScope(_) => Ok(Vec::new()),
+            AnalyzerKind::WorkspaceScope(analyzer) => Ok(analyzer.get_imports(file_path)),
+        }
+    }
+
+    fn get_importers(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        match &self.analyzer {
+            AnalyzerKind::FileScope(_) => Ok(Vec::new()),
+            AnalyzerKind::WorkspaceScope(analyzer) => Ok(analyzer.get_importers(file_path)),
+        }
     }
 
     fn notify_file_processed(&self, file_path: &Path, content: &str) -> SemanticResult<()> {
@@ -253,6 +323,16 @@ impl SemanticProvider for RuffSemanticProvider {
         }
     }
 
+    fn set_file_overlay(&self, file_path: &Path, content: Option<&str>) -> SemanticResult<()> {
+        let canonical_file = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        self.overlay.set(canonical_file, content);
+        // Re-index so cached symbols match the content queries now read
+        let content = self.read_file(file_path)?;
+        self.notify_file_processed(file_path, &content)
+    }
+
     fn supports_language(&self, lang: &str) -> bool {
         matches!(lang.to_lowercase().as_str(), "python" | "py")
     }
@@ -345,6 +425,43 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_provider_get_scopes() {
+        let provider = RuffSemanticProvider::file_scope();
+
+        let content = "x = 1\n\ndef f(a):\n    return a + x\n";
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.py");
+        fs::write(&file_path, content).unwrap();
+
+        let start = content.find("a + x").unwrap() as u32;
+        let scopes = provider
+            .get_scopes(&file_path, ByteRange::new(start, start + 5))
+            .unwrap();
+        assert_eq!(scopes.len(), 2);
+        assert!(scopes[0].binds("a"));
+        assert_eq!(scopes[0].free_names, vec!["x"]);
+        assert!(scopes[1].binds("f"));
+    }
+
+    #[test]
+    fn test_provider_get_control_flow() {
+        let provider = RuffSemanticProvider::file_scope();
+
+        let content = "def f():\n    return 1\n    dead()\n";
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.py");
+        fs::write(&file_path, content).unwrap();
+
+        let start = content.find("dead()").unwrap() as u32;
+        let dead = ByteRange::new(start, start + 6);
+        let graph = provider
+            .get_control_flow(&file_path, dead)
+            .unwrap()
+            .unwrap();
+        assert!(!graph.is_reachable(dead));
+    }
+
     #[test]
     fn test_provider_clear_cache() {
         let provider = RuffSemanticProvider::file_scope();
@@ -0,0 +1,520 @@
+//! Scope and binding lookup for `scope()`.
+//!
+//! ty answers questions about single names; codemods that introduce or
+//! rename bindings need the whole scope. ty_python_semantic builds exactly
+//! this as its semantic index, but `semantic_index()`, the place tables and
+//! the use-def maps are crate-private, and the public `SemanticModel` only
+//! answers per-node queries (types, definitions, completions) without scope
+//! ranges or binding sites. Until the index is exposed, this module builds
+//! the scope tree from the ruff AST and resolves every name load with
+//! Python's rules: class bodies are skipped by nested functions, `global`
+//! and `nonlocal` redirect bindings, and walrus targets inside
+//! comprehensions bind in the enclosing function.
+
+use crate::error::PySemanticError;
+use language_core::{ByteRange, ScopeBinding, ScopeInfo, ScopeKind, SymbolKind};
+use ruff_python_ast::visitor::{walk_except_handler, walk_expr, walk_pattern, walk_stmt, Visitor};
+use ruff_python_ast::{
+    Comprehension, ExceptHandler, Expr, ExprContext, Identifier, Parameters, Pattern, Stmt,
+};
+use ruff_text_size::{Ranged, TextRange};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+/// Get the scopes enclosing `range` in a Python module, innermost first and
+/// ending with the module scope.
+pub fn scopes_at(
+    file_path: &Path,
+    content: &str,
+    range: ByteRange,
+) -> Result<Vec<ScopeInfo>, PySemanticError> {
+    let parsed =
+        ruff_python_parser::parse_module(content).map_err(|err| PySemanticError::ParseError {
+            path: file_path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+
+    let mut builder = ScopeBuilder::default();
+    builder.push(ScopeKind::Module, ByteRange::new(0, content.len() as u32));
+    for stmt in parsed.suite() {
+        builder.visit_stmt(stmt);
+    }
+    builder.stack.pop();
+
+    Ok(builder.finish().chain_at(range))
+}
+
+/// A scope under construction.
+#[derive(Debug)]
+struct PyScope {
+    parent: Option<usize>,
+    kind: ScopeKind,
+    range: ByteRange,
+    bindings: Vec<ScopeBinding>,
+    globals: HashSet<String>,
+    nonlocals: HashSet<String>,
+}
+
+/// A name load and the scope it occurs in.
+#[derive(Debug)]
+struct NameLoad {
+    name: String,
+    scope: usize,
+}
+
+/// Walks a module and records scopes, bindings and name loads.
+#[derive(Debug, Default)]
+struct ScopeBuilder {
+    scopes: Vec<PyScope>,
+    stack: Vec<usize>,
+    loads: Vec<NameLoad>,
+}
+
+impl ScopeBuilder {
+    fn current(&self) -> usize {
+        *self.stack.last().expect("module scope is always present")
+    }
+
+    fn push(&mut self, kind: ScopeKind, range: ByteRange) {
+        self.scopes.push(PyScope {
+            parent: self.stack.last().copied(),
+            kind,
+            range,
+            bindings: Vec::new(),
+            globals: HashSet::new(),
+            nonlocals: HashSet::new(),
+        });
+        self.stack.push(self.scopes.len() - 1);
+    }
+
+    /// Bind `name` in `scope`, honouring `global` and `nonlocal`
+    /// declarations. Rebinding keeps the first declaration.
+    fn bind_in(&mut self, scope: usize, name: &str, kind: SymbolKind, range: TextRange) {
+        let target = if self.scopes[scope].globals.contains(name) {
+            0
+        } else if self.scopes[scope].nonlocals.contains(name) {
+            return;
+        } else {
+            scope
+        };
+        let bindings = &mut self.scopes[target].bindings;
+        if bindings.iter().any(|binding| binding.name == name) {
+            return;
+        }
+        bindings.push(ScopeBinding {
+            name: name.to_string(),
+            kind,
+            range: to_byte_range(range),
+        });
+    }
+
+    fn bind(&mut self, name: &str, kind: SymbolKind, range: TextRange) {
+        self.bind_in(self.current(), name, kind, range);
+    }
+
+    fn bind_identifier(&mut self, identifier: &Identifier, kind: SymbolKind) {
+        self.bind(identifier.as_str(), kind, identifier.range());
+    }
+
+    /// Visit default values and annotations, which are evaluated in the
+    /// scope that defines the function.
+    fn visit_parameter_defaults(&mut self, parameters: &Parameters) {
+        for parameter in parameters.iter() {
+            if let Some(default) = parameter.default() {
+                self.visit_expr(default);
+            }
+            if let Some(annotation) = parameter.annotation() {
+                self.visit_expr(annotation);
+            }
+        }
+    }
+
+    fn bind_parameters(&mut self, parameters: &Parameters) {
+        for parameter in parameters.iter() {
+            self.bind_identifier(parameter.name(), SymbolKind::Parameter);
+        }
+    }
+
+    /// Visit a comprehension. The first iterable is evaluated in the
+    /// enclosing scope; everything else runs in the comprehension's own scope.
+    fn visit_comprehension_scope(
+        &mut self,
+        range: TextRange,
+        generators: &[Comprehension],
+        elements: &[&Expr],
+    ) {
+        if let Some(first) = generators.first() {
+            self.visit_expr(&first.iter);
+        }
+        self.push(ScopeKind::Block, to_byte_range(range));
+        for (index, generator) in generators.iter().enumerate() {
+            if index > 0 {
+                self.visit_expr(&generator.iter);
+            }
+            self.visit_expr(&generator.target);
+            for condition in &generator.ifs {
+                self.visit_expr(condition);
+            }
+        }
+        for element in elements {
+            self.visit_expr(element);
+        }
+        self.stack.pop();
+    }
+
+    /// Resolve name loads and freeze the scope tree.
+    fn finish(self) -> ScopeTree {
+        let resolved = self
+            .loads
+            .iter()
+            .map(|load| {
+                let resolved = self.resolve(&load.name, load.scope);
+                (load.name.clone(), load.scope, resolved)
+            })
+            .collect();
+        ScopeTree {
+            scopes: self.scopes,
+            loads: resolved,
+        }
+    }
+
+    /// Find the scope a load of `name` in `scope` refers to. Class scopes
+    /// are only visible to code directly in the class body.
+    fn resolve(&self, name: &str, scope: usize) -> Option<usize> {
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let candidate = &self.scopes[id];
+            if candidate.globals.contains(name) {
+                return self.scopes[0].binds(name).then_some(0);
+            }
+            if (id == scope || candidate.kind != ScopeKind::Class) && candidate.binds(name) {
+                return Some(id);
+            }
+            current = candidate.parent;
+        }
+        None
+    }
+}
+
+impl PyScope {
+    fn binds(&self, name: &str) -> bool {
+        self.bindings.iter().any(|binding| binding.name == name)
+    }
+}
+
+impl<'a> Visitor<'a> for ScopeBuilder {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(function) => {
+                for decorator in &function.decorator_list {
+                    self.visit_decorator(decorator);
+                }
+                self.visit_parameter_defaults(&function.parameters);
+                if let Some(returns) = &function.returns {
+                    self.visit_expr(returns);
+                }
+                let kind = if self.scopes[self.current()].kind == ScopeKind::Class {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                };
+                self.bind_identifier(&function.name, kind);
+
+                self.push(ScopeKind::Function, to_byte_range(function.range()));
+                self.bind_parameters(&function.parameters);
+                self.visit_body(&function.body);
+                self.stack.pop();
+            }
+            Stmt::ClassDef(class) => {
+                for decorator in &class.decorator_list {
+                    self.visit_decorator(decorator);
+                }
+                if let Some(arguments) = &class.arguments {
+                    self.visit_arguments(arguments);
+                }
+                self.bind_identifier(&class.name, SymbolKind::Class);
+
+                self.push(ScopeKind::Class, to_byte_range(class.range()));
+                self.visit_body(&class.body);
+                self.stack.pop();
+            }
+            Stmt::Import(import) => {
+                for alias in &import.names {
+                    match &alias.asname {
+                        Some(asname) => self.bind_identifier(asname, SymbolKind::Import),
+                        None => {
+                            // `import a.b` binds `a`
+                            let name = alias.name.as_str();
+                            let head = name.split('.').next().unwrap_or(name);
+                            self.bind(head, SymbolKind::Import, alias.name.range());
+                        }
+                    }
+                }
+            }
+            Stmt::ImportFrom(import) => {
+                for alias in &import.names {
+                    match &alias.asname {
+                        Some(asname) => self.bind_identifier(asname, SymbolKind::Import),
+                        None if alias.name.as_str() == "*" => {}
+                        None => self.bind_identifier(&alias.name, SymbolKind::Import),
+                    }
+                }
+            }
+            Stmt::Global(global) => {
+                let current = self.current();
+                for name in &global.names {
+                    self.scopes[current]
+                        .globals
+                        .insert(name.as_str().to_string());
+                }
+            }
+            Stmt::Nonlocal(nonlocal) => {
+                let current = self.current();
+                for name in &nonlocal.names {
+                    self.scopes[current]
+                        .nonlocals
+                        .insert(name.as_str().to_string());
+                }
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) => match name.ctx {
+                ExprContext::Load => self.loads.push(NameLoad {
+                    name: name.id.to_string(),
+                    scope: self.current(),
+                }),
+                ExprContext::Store | ExprContext::Del => {
+                    self.bind(name.id.as_str(), SymbolKind::Variable, name.range());
+                }
+                ExprContext::Invalid => {}
+            },
+            Expr::Named(named) => {
+                self.visit_expr(&named.value);
+                // Walrus targets leak out of comprehensions into the
+                // enclosing function or module
+                if let Expr::Name(target) = named.target.as_ref() {
+                    let scope = self
+                        .stack
+                        .iter()
+                        .rev()
+                        .copied()
+                        .find(|&id| self.scopes[id].kind != ScopeKind::Block)
+                        .unwrap_or(0);
+                    self.bind_in(
+                        scope,
+                        target.id.as_str(),
+                        SymbolKind::Variable,
+                        target.range(),
+                    );
+                }
+            }
+            Expr::Lambda(lambda) => {
+                if let Some(parameters) = &lambda.parameters {
+                    self.visit_parameter_defaults(parameters);
+                }
+                self.push(ScopeKind::Function, to_byte_range(lambda.range()));
+                if let Some(parameters) = &lambda.parameters {
+                    self.bind_parameters(parameters);
+                }
+                self.visit_expr(&lambda.body);
+                self.stack.pop();
+            }
+            Expr::ListComp(comp) => {
+                self.visit_comprehension_scope(comp.range(), &comp.generators, &[&*comp.elt]);
+            }
+            Expr::SetComp(comp) => {
+                self.visit_comprehension_scope(comp.range(), &comp.generators, &[&*comp.elt]);
+            }
+            Expr::Generator(generator) => {
+                self.visit_comprehension_scope(
+                    generator.range(),
+                    &generator.generators,
+                    &[&*generator.elt],
+                );
+            }
+            Expr::DictComp(comp) => {
+                self.visit_comprehension_scope(
+                    comp.range(),
+                    &comp.generators,
+                    &[&*comp.key, &*comp.value],
+                );
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_except_handler(&mut self, except_handler: &'a ExceptHandler) {
+        let ExceptHandler::ExceptHandler(handler) = except_handler;
+        if let Some(name) = &handler.name {
+            self.bind_identifier(name, SymbolKind::Variable);
+        }
+        walk_except_handler(self, except_handler);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'a Pattern) {
+        let captured = match pattern {
+            Pattern::MatchAs(pattern) => pattern.name.as_ref(),
+            Pattern::MatchStar(pattern) => pattern.name.as_ref(),
+            Pattern::MatchMapping(pattern) => pattern.rest.as_ref(),
+            _ => None,
+        };
+        if let Some(name) = captured {
+            self.bind_identifier(name, SymbolKind::Variable);
+        }
+        walk_pattern(self, pattern);
+    }
+}
+
+/// The finished scope tree of a module with every name load resolved.
+struct ScopeTree {
+    scopes: Vec<PyScope>,
+    /// Name, scope the load occurs in and the scope it resolves to
+    loads: Vec<(String, usize, Option<usize>)>,
+}
+
+impl ScopeTree {
+    fn chain_at(&self, range: ByteRange) -> Vec<ScopeInfo> {
+        // Nested scopes are created after the scopes containing them, so
+        // the last of the smallest containing scopes is the innermost
+        let Some(innermost) = self
+            .scopes
+            .iter()
+            .enumerate()
+            .filter(|(_, scope)| scope.range.start <= range.start && scope.range.end >= range.end)
+            .min_by_key(|(id, scope)| (scope.range.end - scope.range.start, usize::MAX - id))
+            .map(|(id, _)| id)
+        else {
+            return Vec::new();
+        };
+
+        let mut chain = Vec::new();
+        let mut current = Some(innermost);
+        while let Some(id) = current {
+            chain.push(self.describe(id));
+            current = self.scopes[id].parent;
+        }
+        chain
+    }
+
+    fn describe(&self, id: usize) -> ScopeInfo {
+        let scope = &self.scopes[id];
+
+        let nested_names: BTreeSet<String> = self
+            .scopes
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != id && self.is_within(*other, id))
+            .flat_map(|(_, other)| other.bindings.iter().map(|binding| binding.name.clone()))
+            .collect();
+
+        let free_names: BTreeSet<String> = self
+            .loads
+            .iter()
+            .filter(|(_, scope, resolved)| {
+                self.is_within(*scope, id) && !resolved.is_some_and(|r| self.is_within(r, id))
+            })
+            .map(|(name, _, _)| name.clone())
+            .collect();
+
+        ScopeInfo {
+            kind: scope.kind,
+            range: scope.range,
+            bindings: scope.bindings.clone(),
+            nested_names: nested_names.into_iter().collect(),
+            free_names: free_names.into_iter().collect(),
+        }
+    }
+
+    /// Whether scope `id` is `ancestor` or nested inside it.
+    fn is_within(&self, id: usize, ancestor: usize) -> bool {
+        let mut current = Some(id);
+        while let Some(scope) = current {
+            if scope == ancestor {
+                return true;
+            }
+            current = self.scopes[scope].parent;
+        }
+        false
+    }
+}
+
+fn to_byte_range(range: TextRange) -> ByteRange {
+    ByteRange::new(range.start().to_u32(), range.end().to_u32())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes_of(content: &str, needle: &str) -> Vec<ScopeInfo> {
+        let start = content.find(needle).unwrap() as u32;
+        let range = ByteRange::new(start, start + needle.len() as u32);
+        scopes_at(Path::new("test.py"), content, range).unwrap()
+    }
+
+    fn names(scope: &ScopeInfo) -> Vec<&str> {
+        scope.bindings.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    #[test]
+    fn reports_chain_from_innermost_to_module() {
+        let content = "import os.path\nfrom typing import List as L\n\nclass Loader:\n    size = 1\n\n    def load(self, path, *rest):\n        data = read(path)\n        return data\n";
+        let chain = scopes_of(content, "read(path)");
+
+        let kinds: Vec<ScopeKind> = chain.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ScopeKind::Function, ScopeKind::Class, ScopeKind::Module]
+        );
+        assert_eq!(names(&chain[0]), vec!["self", "path", "rest", "data"]);
+        assert_eq!(chain[0].bindings[0].kind, SymbolKind::Parameter);
+        assert_eq!(names(&chain[1]), vec!["size", "load"]);
+        assert_eq!(chain[1].bindings[1].kind, SymbolKind::Method);
+        assert_eq!(names(&chain[2]), vec!["os", "L", "Loader"]);
+    }
+
+    #[test]
+    fn class_bindings_are_not_visible_to_methods() {
+        let content = "size = 0\n\nclass Box:\n    size = 1\n\n    def grow(self):\n        return size + 1\n";
+        let chain = scopes_of(content, "size + 1");
+
+        // `size` resolves to the module binding, so it is free in the class
+        assert_eq!(chain[0].free_names, vec!["size"]);
+        assert_eq!(chain[1].free_names, vec!["size"]);
+        assert!(chain[2].free_names.is_empty());
+    }
+
+    #[test]
+    fn comprehensions_and_walrus() {
+        let content =
+            "def f(items):\n    total = [y for x in items if (y := x * 2)]\n    return total, y\n";
+        let chain = scopes_of(content, "x * 2");
+
+        assert_eq!(chain[0].kind, ScopeKind::Block);
+        assert_eq!(names(&chain[0]), vec!["x"]);
+        assert_eq!(chain[0].free_names, vec!["y"]);
+        assert_eq!(names(&chain[1]), vec!["items", "y", "total"]);
+        assert_eq!(chain[1].nested_names, vec!["x"]);
+    }
+
+    #[test]
+    fn global_and_builtin_names() {
+        let content = "count = 0\n\ndef bump():\n    global count\n    count = len([]) + 1\n";
+        let chain = scopes_of(content, "len([])");
+
+        assert!(chain[0].bindings.is_empty());
+        assert_eq!(chain[0].free_names, vec!["len"]);
+        assert_eq!(names(&chain[1]), vec!["count", "bump"]);
+        assert_eq!(chain[1].free_names, vec!["len"]);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let result = scopes_at(Path::new("test.py"), "def (", ByteRange::new(0, 1));
+        assert!(result.is_err());
+    }
+}
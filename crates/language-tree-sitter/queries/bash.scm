@@ -0,0 +1,15 @@
+; Scopes
+
+(function_definition) @local.scope
+
+; Definitions
+
+(function_definition name: (word) @local.definition.function)
+(variable_assignment name: (variable_name) @local.definition.var)
+(for_statement variable: (variable_name) @local.definition.var)
+(declaration_command (variable_name) @local.definition.var)
+
+; References
+
+(variable_name) @local.reference
+(command_name (word) @local.reference)
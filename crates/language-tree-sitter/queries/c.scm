@@ -0,0 +1,39 @@
+; Scopes
+
+(function_definition) @local.scope
+(compound_statement) @local.scope
+(for_statement) @local.scope
+
+; Definitions
+
+(function_definition declarator: (function_declarator declarator: (identifier) @local.definition.function))
+(function_definition declarator: (pointer_declarator declarator: (function_declarator declarator: (identifier) @local.definition.function)))
+(declaration declarator: (function_declarator declarator: (identifier) @local.definition.function))
+(declaration declarator: (pointer_declarator declarator: (function_declarator declarator: (identifier) @local.definition.function)))
+(parameter_declaration declarator: (identifier) @local.definition.parameter)
+(parameter_declaration declarator: (pointer_declarator declarator: (identifier) @local.definition.parameter))
+(parameter_declaration declarator: (array_declarator declarator: (identifier) @local.definition.parameter))
+(declaration declarator: (identifier) @local.definition.var)
+(declaration declarator: (pointer_declarator declarator: (identifier) @local.definition.var))
+(declaration declarator: (array_declarator declarator: (identifier) @local.definition.var))
+(init_declarator declarator: (identifier) @local.definition.var)
+(init_declarator declarator: (pointer_declarator declarator: (identifier) @local.definition.var))
+(init_declarator declarator: (array_declarator declarator: (identifier) @local.definition.var))
+(type_definition declarator: (type_identifier) @local.definition.type)
+(type_definition declarator: (pointer_declarator declarator: (type_identifier) @local.definition.type))
+(struct_specifier name: (type_identifier) @local.definition.type body: (_))
+(union_specifier name: (type_identifier) @local.definition.type body: (_))
+(enum_specifier name: (type_identifier) @local.definition.enum body: (_))
+(enumerator name: (identifier) @local.definition.constant)
+(field_declaration declarator: (field_identifier) @local.definition.field)
+(field_declaration declarator: (pointer_declarator declarator: (field_identifier) @local.definition.field))
+(preproc_def name: (identifier) @local.definition.macro)
+(preproc_function_def name: (identifier) @local.definition.macro)
+(labeled_statement label: (statement_identifier) @local.definition)
+
+; References
+
+(identifier) @local.reference
+(type_identifier) @local.reference
+(statement_identifier) @local.reference
+(field_expression field: (field_identifier) @local.reference)
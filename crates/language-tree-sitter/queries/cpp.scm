@@ -0,0 +1,75 @@
+; C definitions and references also apply to C++.
+
+; Scopes
+
+(function_definition) @local.scope
+(compound_statement) @local.scope
+(for_statement) @local.scope
+
+; Definitions
+
+(function_definition declarator: (function_declarator declarator: (identifier) @local.definition.function))
+(function_definition declarator: (pointer_declarator declarator: (function_declarator declarator: (identifier) @local.definition.function)))
+(declaration declarator: (function_declarator declarator: (identifier) @local.definition.function))
+(declaration declarator: (pointer_declarator declarator: (function_declarator declarator: (identifier) @local.definition.function)))
+(parameter_declaration declarator: (identifier) @local.definition.parameter)
+(parameter_declaration declarator: (pointer_declarator declarator: (identifier) @local.definition.parameter))
+(parameter_declaration declarator: (array_declarator declarator: (identifier) @local.definition.parameter))
+(declaration declarator: (identifier) @local.definition.var)
+(declaration declarator: (pointer_declarator declarator: (identifier) @local.definition.var))
+(declaration declarator: (array_declarator declarator: (identifier) @local.definition.var))
+(init_declarator declarator: (identifier) @local.definition.var)
+(init_declarator declarator: (pointer_declarator declarator: (identifier) @local.definition.var))
+(init_declarator declarator: (array_declarator declarator: (identifier) @local.definition.var))
+(type_definition declarator: (type_identifier) @local.definition.type)
+(type_definition declarator: (pointer_declarator declarator: (type_identifier) @local.definition.type))
+(struct_specifier name: (type_identifier) @local.definition.type body: (_))
+(union_specifier name: (type_identifier) @local.definition.type body: (_))
+(enum_specifier name: (type_identifier) @local.definition.enum body: (_))
+(enumerator name: (identifier) @local.definition.constant)
+(field_declaration declarator: (field_identifier) @local.definition.field)
+(field_declaration declarator: (pointer_declarator declarator: (field_identifier) @local.definition.field))
+(preproc_def name: (identifier) @local.definition.macro)
+(preproc_function_def name: (identifier) @local.definition.macro)
+(labeled_statement label: (statement_identifier) @local.definition)
+
+; References
+
+(identifier) @local.reference
+(type_identifier) @local.reference
+(statement_identifier) @local.reference
+(field_expression field: (field_identifier) @local.reference)
+
+; C++ scopes
+
+(lambda_expression) @local.scope
+(for_range_loop) @local.scope
+(namespace_definition) @local.scope
+(field_declaration_list) @local.scope
+(catch_clause) @local.scope
+
+; C++ definitions
+
+(function_definition declarator: (function_declarator declarator: (field_identifier) @local.definition.method))
+(function_definition declarator: (function_declarator declarator: (qualified_identifier name: (identifier) @local.definition.function)))
+(function_definition declarator: (reference_declarator (function_declarator declarator: (identifier) @local.definition.function)))
+(field_declaration declarator: (function_declarator declarator: (field_identifier) @local.definition.method))
+(parameter_declaration declarator: (reference_declarator (identifier) @local.definition.parameter))
+(optional_parameter_declaration declarator: (identifier) @local.definition.parameter)
+(optional_parameter_declaration declarator: (reference_declarator (identifier) @local.definition.parameter))
+(init_declarator declarator: (reference_declarator (identifier) @local.definition.var))
+(for_range_loop declarator: (identifier) @local.definition.var)
+(for_range_loop declarator: (reference_declarator (identifier) @local.definition.var))
+(class_specifier name: (type_identifier) @local.definition.class body: (_))
+(struct_specifier name: (type_identifier) @local.definition.class body: (_))
+(namespace_definition name: (namespace_identifier) @local.definition.namespace)
+(alias_declaration name: (type_identifier) @local.definition.type)
+(type_parameter_declaration (type_identifier) @local.definition.type)
+(using_declaration (identifier) @local.definition.import)
+(using_declaration (qualified_identifier name: (identifier) @local.definition.import))
+(lambda_capture_specifier (identifier) @local.reference)
+
+; C++ references
+
+(namespace_identifier) @local.reference
+(qualified_identifier name: (identifier) @local.reference)
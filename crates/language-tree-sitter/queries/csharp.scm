@@ -0,0 +1,45 @@
+; Scopes
+
+(declaration_list) @local.scope
+(method_declaration) @local.scope
+(constructor_declaration) @local.scope
+(local_function_statement) @local.scope
+(lambda_expression) @local.scope
+(anonymous_method_expression) @local.scope
+(accessor_declaration) @local.scope
+(block) @local.scope
+(for_statement) @local.scope
+(foreach_statement) @local.scope
+(using_statement) @local.scope
+(catch_clause) @local.scope
+(switch_section) @local.scope
+
+; Definitions
+
+(class_declaration name: (identifier) @local.definition.class)
+(struct_declaration name: (identifier) @local.definition.class)
+(record_declaration name: (identifier) @local.definition.class)
+(interface_declaration name: (identifier) @local.definition.interface)
+(enum_declaration name: (identifier) @local.definition.enum)
+(enum_member_declaration name: (identifier) @local.definition.constant)
+(delegate_declaration name: (identifier) @local.definition.type)
+(namespace_declaration name: (identifier) @local.definition.namespace)
+(method_declaration name: (identifier) @local.definition.method)
+(local_function_statement name: (identifier) @local.definition.function)
+(property_declaration name: (identifier) @local.definition.field)
+(event_declaration name: (identifier) @local.definition.field)
+(field_declaration (variable_declaration (variable_declarator . (identifier) @local.definition.field)))
+(local_declaration_statement (variable_declaration (variable_declarator . (identifier) @local.definition.var)))
+(using_statement (variable_declaration (variable_declarator . (identifier) @local.definition.var)))
+(for_statement (variable_declaration (variable_declarator . (identifier) @local.definition.var)))
+(parameter name: (identifier) @local.definition.parameter)
+(implicit_parameter) @local.definition.parameter
+(type_parameter name: (identifier) @local.definition.type)
+(foreach_statement left: (identifier) @local.definition.var)
+(catch_declaration name: (identifier) @local.definition.var)
+(declaration_expression name: (identifier) @local.definition.var)
+(using_directive name: (identifier) @local.definition.import)
+
+; References
+
+(identifier) @local.reference
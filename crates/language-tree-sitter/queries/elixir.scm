@@ -0,0 +1,38 @@
+; Scopes
+
+((call target: (identifier) @_keyword) @local.scope
+  (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop" "defmodule" "for" "with"))
+(stab_clause) @local.scope
+(anonymous_function) @local.scope
+
+; Definitions
+
+((call
+  target: (identifier) @_keyword
+  (arguments [
+    (call target: (identifier) @local.definition.function)
+    (binary_operator left: (call target: (identifier) @local.definition.function))
+    (identifier) @local.definition.function
+  ]))
+  (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop")
+  (#set! definition.function.scope "parent"))
+
+((call
+  target: (identifier) @_keyword
+  (arguments (call target: (identifier) (arguments (identifier) @local.definition.parameter))))
+  (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop"))
+
+((call
+  target: (identifier) @_keyword
+  (arguments (alias) @local.definition.namespace))
+  (#eq? @_keyword "defmodule")
+  (#set! definition.namespace.scope "parent"))
+
+(stab_clause left: (arguments (identifier) @local.definition.parameter))
+(binary_operator operator: "=" left: (identifier) @local.definition.var)
+(binary_operator operator: "<-" left: (identifier) @local.definition.var)
+
+; References
+
+(identifier) @local.reference
+(alias) @local.reference
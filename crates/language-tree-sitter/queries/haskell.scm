@@ -0,0 +1,30 @@
+; Scopes
+
+(function) @local.scope
+(bind) @local.scope
+(lambda) @local.scope
+(let_in) @local.scope
+(alternative) @local.scope
+(do) @local.scope
+
+; Definitions
+
+(signature name: (variable) @local.definition.function)
+(function name: (variable) @local.definition.function)
+(bind name: (variable) @local.definition.var)
+(function patterns: (patterns (variable) @local.definition.parameter))
+(lambda patterns: (patterns (variable) @local.definition.parameter))
+(alternative pattern: (variable) @local.definition.var)
+(bind_statement pattern: (variable) @local.definition.var)
+(data_type name: (name) @local.definition.type)
+(newtype name: (name) @local.definition.type)
+(type_synomym name: (name) @local.definition.type)
+(class name: (name) @local.definition.interface)
+(data_constructor (prefix name: (constructor) @local.definition.constant))
+(import names: (import_list (import_name (variable) @local.definition.import)))
+
+; References
+
+(variable) @local.reference
+(name) @local.reference
+(constructor) @local.reference
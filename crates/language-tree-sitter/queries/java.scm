@@ -0,0 +1,42 @@
+; Scopes
+
+(class_body) @local.scope
+(interface_body) @local.scope
+(enum_body) @local.scope
+(record_declaration) @local.scope
+(method_declaration) @local.scope
+(constructor_declaration) @local.scope
+(lambda_expression) @local.scope
+(block) @local.scope
+(for_statement) @local.scope
+(enhanced_for_statement) @local.scope
+(catch_clause) @local.scope
+(try_with_resources_statement) @local.scope
+(switch_block_statement_group) @local.scope
+
+; Definitions
+
+(class_declaration name: (identifier) @local.definition.class)
+(record_declaration name: (identifier) @local.definition.class)
+(interface_declaration name: (identifier) @local.definition.interface)
+(annotation_type_declaration name: (identifier) @local.definition.interface)
+(enum_declaration name: (identifier) @local.definition.enum)
+(enum_constant name: (identifier) @local.definition.constant)
+(method_declaration name: (identifier) @local.definition.method)
+(formal_parameter name: (identifier) @local.definition.parameter)
+(spread_parameter (variable_declarator name: (identifier) @local.definition.parameter))
+(catch_formal_parameter name: (identifier) @local.definition.parameter)
+(lambda_expression parameters: (identifier) @local.definition.parameter)
+(inferred_parameters (identifier) @local.definition.parameter)
+(type_parameter (type_identifier) @local.definition.type)
+(local_variable_declaration declarator: (variable_declarator name: (identifier) @local.definition.var))
+(field_declaration declarator: (variable_declarator name: (identifier) @local.definition.field))
+(constant_declaration declarator: (variable_declarator name: (identifier) @local.definition.constant))
+(enhanced_for_statement name: (identifier) @local.definition.var)
+(resource name: (identifier) @local.definition.var)
+(import_declaration (scoped_identifier name: (identifier) @local.definition.import))
+
+; References
+
+(identifier) @local.reference
+(type_identifier) @local.reference
@@ -0,0 +1,30 @@
+; Scopes
+
+(class_body) @local.scope
+(function_declaration) @local.scope
+(anonymous_function) @local.scope
+(lambda_literal) @local.scope
+(statements) @local.scope
+(for_statement) @local.scope
+(catch_block) @local.scope
+
+; Definitions
+
+(class_declaration (type_identifier) @local.definition.class)
+(object_declaration (type_identifier) @local.definition.class)
+(type_alias (type_identifier) @local.definition.type)
+(function_declaration (simple_identifier) @local.definition.function)
+(parameter (simple_identifier) @local.definition.parameter)
+(class_parameter (simple_identifier) @local.definition.field)
+(lambda_parameters (variable_declaration (simple_identifier) @local.definition.parameter))
+(property_declaration (variable_declaration (simple_identifier) @local.definition.var))
+(for_statement (variable_declaration (simple_identifier) @local.definition.var))
+(catch_block (simple_identifier) @local.definition.var)
+(enum_entry (simple_identifier) @local.definition.constant)
+(type_parameter (type_identifier) @local.definition.type)
+(import_header (identifier (simple_identifier) @local.definition.import .))
+
+; References
+
+(simple_identifier) @local.reference
+(type_identifier) @local.reference
@@ -0,0 +1,26 @@
+; Scopes
+
+(chunk) @local.scope
+(function_declaration) @local.scope
+(function_definition) @local.scope
+(do_statement) @local.scope
+(while_statement) @local.scope
+(repeat_statement) @local.scope
+(if_statement) @local.scope
+(for_statement) @local.scope
+
+; Definitions
+
+(function_declaration name: (identifier) @local.definition.function)
+(function_declaration name: (dot_index_expression field: (identifier) @local.definition.function))
+(function_declaration name: (method_index_expression method: (identifier) @local.definition.method))
+(parameters (identifier) @local.definition.parameter)
+(variable_declaration (assignment_statement (variable_list name: (identifier) @local.definition.var)))
+(variable_declaration (variable_list name: (identifier) @local.definition.var))
+(assignment_statement (variable_list name: (identifier) @local.definition.var))
+(for_generic_clause (variable_list name: (identifier) @local.definition.var))
+(for_numeric_clause name: (identifier) @local.definition.var)
+
+; References
+
+(identifier) @local.reference
@@ -0,0 +1,43 @@
+; Scopes
+
+(function_definition) @local.scope
+(method_declaration) @local.scope
+(anonymous_function) @local.scope
+(arrow_function) @local.scope
+(class_declaration) @local.scope
+(interface_declaration) @local.scope
+(trait_declaration) @local.scope
+(catch_clause) @local.scope
+
+; Definitions
+
+(function_definition name: (name) @local.definition.function)
+(method_declaration name: (name) @local.definition.method)
+(class_declaration name: (name) @local.definition.class)
+(interface_declaration name: (name) @local.definition.interface)
+(trait_declaration name: (name) @local.definition.class)
+(enum_declaration name: (name) @local.definition.enum)
+(const_element (name) @local.definition.constant)
+(simple_parameter name: (variable_name) @local.definition.parameter)
+(variadic_parameter name: (variable_name) @local.definition.parameter)
+(property_promotion_parameter name: (variable_name) @local.definition.parameter)
+(assignment_expression left: (variable_name) @local.definition.var)
+(foreach_statement (pair (variable_name) @local.definition.var))
+(foreach_statement (variable_name) (variable_name) @local.definition.var)
+(catch_clause name: (variable_name) @local.definition.var)
+(anonymous_function_use_clause (variable_name) @local.definition.var)
+(namespace_use_clause (qualified_name (name) @local.definition.import))
+(namespace_use_clause (name) @local.definition.import)
+
+; References
+
+(variable_name) @local.reference
+(function_call_expression function: (name) @local.reference)
+(function_call_expression function: (qualified_name (name) @local.reference))
+(object_creation_expression (name) @local.reference)
+(object_creation_expression (qualified_name (name) @local.reference))
+(scoped_call_expression scope: (name) @local.reference)
+(class_constant_access_expression . (name) @local.reference)
+(base_clause (name) @local.reference)
+(class_interface_clause (name) @local.reference)
+(named_type (name) @local.reference)
@@ -0,0 +1,35 @@
+; Scopes
+
+(method) @local.scope
+(singleton_method) @local.scope
+(class) @local.scope
+(module) @local.scope
+(singleton_class) @local.scope
+(block) @local.scope
+(do_block) @local.scope
+(lambda) @local.scope
+
+; Definitions
+
+(method name: (identifier) @local.definition.method)
+(singleton_method name: (identifier) @local.definition.method)
+(class name: (constant) @local.definition.class)
+(module name: (constant) @local.definition.namespace)
+(method_parameters (identifier) @local.definition.parameter)
+(lambda_parameters (identifier) @local.definition.parameter)
+(block_parameters (identifier) @local.definition.parameter)
+(optional_parameter name: (identifier) @local.definition.parameter)
+(keyword_parameter name: (identifier) @local.definition.parameter)
+(splat_parameter name: (identifier) @local.definition.parameter)
+(hash_splat_parameter name: (identifier) @local.definition.parameter)
+(block_parameter name: (identifier) @local.definition.parameter)
+(assignment left: (identifier) @local.definition.var)
+(assignment left: (constant) @local.definition.constant)
+(operator_assignment left: (identifier) @local.definition.var)
+(left_assignment_list (identifier) @local.definition.var)
+(for pattern: (identifier) @local.definition.var)
+
+; References
+
+(identifier) @local.reference
+(constant) @local.reference
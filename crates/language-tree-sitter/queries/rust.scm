@@ -0,0 +1,56 @@
+; Scopes
+
+(function_item) @local.scope
+(closure_expression) @local.scope
+(block) @local.scope
+(impl_item) @local.scope
+(trait_item) @local.scope
+(mod_item) @local.scope
+(match_arm) @local.scope
+(for_expression) @local.scope
+(if_expression) @local.scope
+(while_expression) @local.scope
+
+; Definitions
+
+(function_item name: (identifier) @local.definition.function)
+(function_signature_item name: (identifier) @local.definition.function)
+(parameter pattern: (identifier) @local.definition.parameter)
+(parameter pattern: (mut_pattern (identifier) @local.definition.parameter))
+(closure_parameters (identifier) @local.definition.parameter)
+(let_declaration pattern: (identifier) @local.definition.var)
+(let_declaration pattern: (mut_pattern (identifier) @local.definition.var))
+(let_declaration pattern: (tuple_pattern (identifier) @local.definition.var))
+(let_declaration pattern: (tuple_struct_pattern type: (_) (identifier) @local.definition.var))
+(let_condition pattern: (identifier) @local.definition.var)
+(let_condition pattern: (tuple_struct_pattern type: (_) (identifier) @local.definition.var))
+(tuple_struct_pattern type: (_) (identifier) @local.definition.var)
+(tuple_pattern (identifier) @local.definition.var)
+(field_pattern name: (shorthand_field_identifier) @local.definition.var)
+(for_expression pattern: (identifier) @local.definition.var)
+(match_pattern (identifier) @local.definition.var)
+(const_item name: (identifier) @local.definition.constant)
+(static_item name: (identifier) @local.definition.constant)
+(struct_item name: (type_identifier) @local.definition.type)
+(union_item name: (type_identifier) @local.definition.type)
+(enum_item name: (type_identifier) @local.definition.enum)
+(trait_item name: (type_identifier) @local.definition.interface)
+(type_item name: (type_identifier) @local.definition.type)
+(associated_type name: (type_identifier) @local.definition.type)
+(type_parameter name: (type_identifier) @local.definition.type)
+(mod_item name: (identifier) @local.definition.namespace)
+(macro_definition name: (identifier) @local.definition.macro)
+(field_declaration name: (field_identifier) @local.definition.field)
+(enum_variant name: (identifier) @local.definition.constant)
+(use_declaration argument: (identifier) @local.definition.import)
+(use_declaration argument: (scoped_identifier name: (identifier) @local.definition.import))
+(use_as_clause alias: (identifier) @local.definition.import)
+(use_list (identifier) @local.definition.import)
+(use_list (scoped_identifier name: (identifier) @local.definition.import))
+
+; References
+
+(identifier) @local.reference
+(type_identifier) @local.reference
+(shorthand_field_identifier) @local.reference
+(field_expression field: (field_identifier) @local.reference)
@@ -0,0 +1,34 @@
+; Scopes
+
+(template_body) @local.scope
+(function_definition) @local.scope
+(lambda_expression) @local.scope
+(block) @local.scope
+(for_expression) @local.scope
+(case_clause) @local.scope
+
+; Definitions
+
+(class_definition name: (identifier) @local.definition.class)
+(object_definition name: (identifier) @local.definition.class)
+(trait_definition name: (identifier) @local.definition.interface)
+(enum_definition name: (identifier) @local.definition.enum)
+(type_definition name: (type_identifier) @local.definition.type)
+(function_definition name: (identifier) @local.definition.function)
+(function_declaration name: (identifier) @local.definition.method)
+(parameter name: (identifier) @local.definition.parameter)
+(class_parameter name: (identifier) @local.definition.field)
+(bindings (binding name: (identifier) @local.definition.parameter))
+(lambda_expression parameters: (identifier) @local.definition.parameter)
+(val_definition pattern: (identifier) @local.definition.var)
+(var_definition pattern: (identifier) @local.definition.var)
+(enumerator (identifier) @local.definition.var)
+(case_clause pattern: (identifier) @local.definition.var)
+(typed_pattern pattern: (identifier) @local.definition.var)
+(type_parameters name: (identifier) @local.definition.type)
+(import_declaration path: (identifier) @local.definition.import .)
+
+; References
+
+(identifier) @local.reference
+(type_identifier) @local.reference
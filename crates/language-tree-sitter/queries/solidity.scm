@@ -0,0 +1,30 @@
+; Scopes
+
+(contract_body) @local.scope
+(function_definition) @local.scope
+(modifier_definition) @local.scope
+(constructor_definition) @local.scope
+(block_statement) @local.scope
+(for_statement) @local.scope
+
+; Definitions
+
+(contract_declaration name: (identifier) @local.definition.class)
+(interface_declaration name: (identifier) @local.definition.interface)
+(library_declaration name: (identifier) @local.definition.namespace)
+(struct_declaration name: (identifier) @local.definition.type)
+(enum_declaration name: (identifier) @local.definition.enum)
+(enum_value) @local.definition.constant
+(event_definition name: (identifier) @local.definition.type)
+(error_declaration name: (identifier) @local.definition.type)
+(function_definition name: (identifier) @local.definition.function)
+(modifier_definition name: (identifier) @local.definition.function)
+(state_variable_declaration name: (identifier) @local.definition.field)
+(struct_member name: (identifier) @local.definition.field)
+(parameter name: (identifier) @local.definition.parameter)
+(variable_declaration name: (identifier) @local.definition.var)
+(import_directive alias: (identifier) @local.definition.import)
+
+; References
+
+(identifier) @local.reference
@@ -0,0 +1,30 @@
+; Scopes
+
+(class_body) @local.scope
+(protocol_body) @local.scope
+(enum_class_body) @local.scope
+(function_declaration) @local.scope
+(init_declaration) @local.scope
+(lambda_literal) @local.scope
+(statements) @local.scope
+(for_statement) @local.scope
+(catch_block) @local.scope
+
+; Definitions
+
+(class_declaration name: (type_identifier) @local.definition.class)
+(protocol_declaration name: (type_identifier) @local.definition.interface)
+(typealias_declaration name: (type_identifier) @local.definition.type)
+(function_declaration name: (simple_identifier) @local.definition.function)
+(protocol_function_declaration name: (simple_identifier) @local.definition.method)
+(parameter name: (simple_identifier) @local.definition.parameter)
+(lambda_parameter name: (simple_identifier) @local.definition.parameter)
+(property_declaration name: (pattern bound_identifier: (simple_identifier) @local.definition.var))
+(for_statement item: (pattern bound_identifier: (simple_identifier) @local.definition.var))
+(enum_entry name: (simple_identifier) @local.definition.constant)
+(type_parameter (type_identifier) @local.definition.type)
+
+; References
+
+(simple_identifier) @local.reference
+(type_identifier) @local.reference
@@ -0,0 +1,366 @@
+//! Per-file scope, definition and reference index built from a locals query.
+
+use crate::query::{CaptureRole, LocalsQuery};
+use language_core::{ByteRange, SymbolKind};
+use std::ops::Range;
+use tree_sitter::{Parser, StreamingIterator, Tree};
+
+/// A scope in a file. Scope `0` is the file itself.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub range: Range<usize>,
+    pub parent: Option<usize>,
+    /// Ids of the syntax nodes that opened this scope.
+    node_ids: Vec<usize>,
+}
+
+/// A named definition.
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    pub range: Range<usize>,
+    pub kind: SymbolKind,
+    pub scope: usize,
+    pub is_import: bool,
+}
+
+/// An identifier that refers to a definition.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub range: Range<usize>,
+    pub scope: usize,
+}
+
+/// The symbol found at a byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolAt {
+    Definition(usize),
+    Reference(usize),
+}
+
+/// Scopes, definitions and references of one file, in source order.
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    pub scopes: Vec<Scope>,
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+}
+
+impl FileIndex {
+    /// Parse `content` and index it with `query`.
+    pub fn build(
+        language: &tree_sitter::Language,
+        query: &LocalsQuery,
+        content: &str,
+    ) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(language).ok()?;
+        let tree = parser.parse(content, None)?;
+        Some(Self::from_tree(&tree, query, content))
+    }
+
+    fn from_tree(tree: &Tree, query: &LocalsQuery, content: &str) -> Self {
+        let root = tree.root_node();
+        let mut scope_nodes = Vec::new();
+        let mut definitions = Vec::new();
+        let mut references = Vec::new();
+
+        let mut cursor = tree_sitter::QueryCursor::new();
+        let mut matches = cursor.matches(&query.query, root, content.as_bytes());
+        while let Some(query_match) = matches.next() {
+            let in_parent_scope = query.defines_in_parent_scope(query_match.pattern_index);
+            for capture in query_match.captures {
+                let node = capture.node;
+                match query.roles[capture.index as usize] {
+                    Some(CaptureRole::Scope) => scope_nodes.push((node.byte_range(), node.id())),
+                    Some(CaptureRole::Definition { kind, is_import }) => definitions.push((
+                        node.byte_range(),
+                        declaration_owner(node),
+                        in_parent_scope,
+                        kind,
+                        is_import,
+                    )),
+                    Some(CaptureRole::Reference) => references.push(node.byte_range()),
+                    None => {}
+                }
+            }
+        }
+
+        let mut index = Self {
+            scopes: build_scopes(root.byte_range(), scope_nodes),
+            ..Self::default()
+        };
+
+        definitions.sort_by_key(|(range, ..)| (range.start, range.end));
+        definitions.dedup_by(|a, b| a.0 == b.0);
+        for (range, owner_id, in_parent_scope, kind, is_import) in definitions {
+            let mut scope = index.innermost_scope(&range);
+            // The name of a function, type or module belongs to the scope the
+            // declaration appears in, not to the scope the declaration opens.
+            let names_own_scope = !matches!(kind, SymbolKind::Variable | SymbolKind::Parameter)
+                && owner_id.is_some_and(|id| index.scopes[scope].node_ids.contains(&id));
+            if in_parent_scope || names_own_scope {
+                scope = index.scopes[scope].parent.unwrap_or(0);
+            }
+            index.definitions.push(Definition {
+                name: content[range.clone()].to_string(),
+                range,
+                kind,
+                scope,
+                is_import,
+            });
+        }
+
+        references.sort_by_key(|range| (range.start, range.end));
+        references.dedup();
+        for range in references {
+            if index.definitions.iter().any(|d| d.range == range) {
+                continue;
+            }
+            let scope = index.innermost_scope(&range);
+            index.references.push(Reference {
+                name: content[range.clone()].to_string(),
+                range,
+                scope,
+            });
+        }
+
+        index
+    }
+
+    /// The innermost scope containing `range`.
+    fn innermost_scope(&self, range: &Range<usize>) -> usize {
+        self.scopes
+            .iter()
+            .enumerate()
+            .filter(|(_, scope)| scope.range.start <= range.start && scope.range.end >= range.end)
+            .min_by_key(|(_, scope)| scope.range.len())
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// The definition or reference at `range`. An exact match wins, otherwise
+    /// the innermost symbol containing the range is returned.
+    pub fn symbol_at(&self, range: ByteRange) -> Option<SymbolAt> {
+        let (start, end) = (range.start as usize, range.end as usize);
+        let contains = |r: &Range<usize>| r.start <= start && r.end >= end;
+
+        self.definitions
+            .iter()
+            .position(|d| d.range == (start..end))
+            .map(SymbolAt::Definition)
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .position(|r| r.range == (start..end))
+                    .map(SymbolAt::Reference)
+            })
+            .or_else(|| {
+                self.definitions
+                    .iter()
+                    .position(|d| contains(&d.range))
+                    .map(SymbolAt::Definition)
+            })
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .position(|r| contains(&r.range))
+                    .map(SymbolAt::Reference)
+            })
+    }
+
+    /// Resolve a reference to the definition it refers to within this file.
+    ///
+    /// Scopes are searched from the innermost outwards. Within a scope the
+    /// closest definition before the reference wins; a definition after the
+    /// reference is used only when there is none before it (e.g. calls to
+    /// functions declared further down).
+    pub fn resolve(&self, reference: usize) -> Option<usize> {
+        let reference = &self.references[reference];
+        let mut scope = Some(reference.scope);
+        while let Some(current) = scope {
+            let candidates: Vec<usize> = self
+                .definitions
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.scope == current && d.name == reference.name)
+                .map(|(index, _)| index)
+                .collect();
+            if let Some(&first) = candidates.first() {
+                return Some(
+                    candidates
+                        .iter()
+                        .rev()
+                        .copied()
+                        .find(|&index| self.definitions[index].range.start <= reference.range.start)
+                        .unwrap_or(first),
+                );
+            }
+            scope = self.scopes[current].parent;
+        }
+        None
+    }
+
+    /// The definition a symbol refers to: itself for a definition, the
+    /// resolved definition for a reference.
+    pub fn definition_of(&self, symbol: SymbolAt) -> Option<usize> {
+        match symbol {
+            SymbolAt::Definition(index) => Some(index),
+            SymbolAt::Reference(index) => self.resolve(index),
+        }
+    }
+
+    /// References in this file that resolve to `definition`.
+    pub fn references_to(&self, definition: usize) -> impl Iterator<Item = &Reference> + '_ {
+        self.references
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| self.resolve(*index) == Some(definition))
+            .map(|(_, reference)| reference)
+    }
+
+    /// Non-import definitions named `name` at the top level of the file.
+    pub fn top_level_definition(&self, name: &str) -> Option<usize> {
+        self.definitions
+            .iter()
+            .position(|d| d.scope == 0 && !d.is_import && d.name == name)
+    }
+
+    /// References and import definitions in this file that may point at a
+    /// top-level definition named `name` in another file: unresolved
+    /// references, and imports of that name with their uses.
+    pub fn external_uses(&self, name: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        for (index, definition) in self.definitions.iter().enumerate() {
+            if definition.is_import && definition.name == name {
+                ranges.push(definition.range.clone());
+                ranges.extend(self.references_to(index).map(|r| r.range.clone()));
+            }
+        }
+        for (index, reference) in self.references.iter().enumerate() {
+            if reference.name == name && self.resolve(index).is_none() {
+                ranges.push(reference.range.clone());
+            }
+        }
+        ranges.sort_by_key(|range| (range.start, range.end));
+        ranges
+    }
+}
+
+/// The declaration a definition name belongs to: its parent, looking through
+/// C-style declarators (`int *name(...)`) and qualified names (`Foo::name`).
+fn declaration_owner(node: tree_sitter::Node) -> Option<usize> {
+    let mut current = node.parent()?;
+    while current.kind().ends_with("declarator") || current.kind() == "qualified_identifier" {
+        current = current.parent()?;
+    }
+    Some(current.id())
+}
+
+/// Sort scope nodes into a tree rooted at the whole file. Nodes with the same
+/// range (e.g. a function and its body) share one scope.
+fn build_scopes(file_range: Range<usize>, mut nodes: Vec<(Range<usize>, usize)>) -> Vec<Scope> {
+    nodes.sort_by(|a, b| a.0.start.cmp(&b.0.start).then(b.0.end.cmp(&a.0.end)));
+
+    let mut scopes = vec![Scope {
+        range: file_range,
+        parent: None,
+        node_ids: Vec::new(),
+    }];
+    let mut stack = vec![0usize];
+    for (range, node_id) in nodes {
+        if let Some(existing) = scopes.iter_mut().rev().find(|s| s.range == range) {
+            existing.node_ids.push(node_id);
+            continue;
+        }
+        while let Some(&top) = stack.last() {
+            if scopes[top].range.start <= range.start && scopes[top].range.end >= range.end {
+                break;
+            }
+            stack.pop();
+        }
+        let parent = stack.last().copied().unwrap_or(0);
+        scopes.push(Scope {
+            range,
+            parent: Some(parent),
+            node_ids: vec![node_id],
+        });
+        stack.push(scopes.len() - 1);
+    }
+    scopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages;
+
+    fn index(language: &str, content: &str) -> FileIndex {
+        let spec = languages::for_name(language).unwrap();
+        let ts_language = spec.ts_language();
+        let query = LocalsQuery::compile(&ts_language, spec.name, spec.query).unwrap();
+        FileIndex::build(&ts_language, &query, content).unwrap()
+    }
+
+    fn reference_at(index: &FileIndex, content: &str, needle: &str, occurrence: usize) -> usize {
+        let start = content.match_indices(needle).nth(occurrence).unwrap().0;
+        match index.symbol_at(ByteRange::new(start as u32, (start + needle.len()) as u32)) {
+            Some(SymbolAt::Reference(reference)) => reference,
+            other => panic!("expected a reference to {needle}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_go_resolves_shadowed_locals() {
+        let content = "package main\n\nfunc run(x int) int {\n\tif true {\n\t\tx := 2\n\t\treturn x\n\t}\n\treturn x\n}\n";
+        let index = index("go", content);
+
+        let inner = reference_at(&index, content, "x", 2);
+        let inner_def = index.resolve(inner).unwrap();
+        assert_eq!(
+            index.definitions[inner_def].range.start,
+            content.find("x :=").unwrap()
+        );
+
+        let outer = reference_at(&index, content, "x", 3);
+        let outer_def = index.resolve(outer).unwrap();
+        assert_eq!(index.definitions[outer_def].kind, SymbolKind::Parameter);
+    }
+
+    #[test]
+    fn test_go_function_names_are_top_level() {
+        let content = "package main\n\nfunc main() {\n\thelper()\n}\n\nfunc helper() {}\n";
+        let index = index("go", content);
+        let call = reference_at(&index, content, "helper", 0);
+        let definition = index.resolve(call).unwrap();
+        assert_eq!(index.definitions[definition].scope, 0);
+        assert_eq!(index.top_level_definition("helper"), Some(definition));
+    }
+
+    #[test]
+    fn test_java_resolves_fields_and_locals() {
+        let content = "class Counter {\n  int total;\n  void add(int amount) {\n    int next = total + amount;\n    total = next;\n  }\n}\n";
+        let index = index("java", content);
+
+        let amount = reference_at(&index, content, "amount", 1);
+        let definition = index.resolve(amount).unwrap();
+        assert_eq!(index.definitions[definition].kind, SymbolKind::Parameter);
+
+        let next = reference_at(&index, content, "next", 1);
+        assert!(index.resolve(next).is_some());
+    }
+
+    #[test]
+    fn test_rust_resolves_let_bindings() {
+        let content = "fn main() {\n    let value = 1;\n    println!(\"{}\", value);\n    consume(value);\n}\n";
+        let index = index("rust", content);
+        let usage = reference_at(&index, content, "value", 2);
+        let definition = index.resolve(usage).unwrap();
+        assert_eq!(
+            index.definitions[definition].range.start,
+            content.find("value").unwrap()
+        );
+        assert_eq!(index.references_to(definition).count(), 2);
+    }
+}
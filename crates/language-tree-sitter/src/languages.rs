@@ -0,0 +1,180 @@
+//! Languages covered by the tree-sitter provider and their built-in queries.
+
+use ast_grep_core::tree_sitter::LanguageExt;
+use ast_grep_language::SupportLang;
+use std::path::Path;
+
+/// A language the provider can analyze.
+#[derive(Debug)]
+pub struct LanguageSpec {
+    /// Canonical name, also used for query override files (`<name>.scm`).
+    pub name: &'static str,
+    /// Other identifiers accepted for this language.
+    pub aliases: &'static [&'static str],
+    /// File extensions (without the leading dot).
+    pub extensions: &'static [&'static str],
+    /// Bundled grammar.
+    pub lang: SupportLang,
+    /// Built-in locals query.
+    pub query: &'static str,
+}
+
+impl LanguageSpec {
+    /// The tree-sitter grammar for this language.
+    pub fn ts_language(&self) -> tree_sitter::Language {
+        self.lang.get_ts_language()
+    }
+}
+
+/// All languages with a built-in locals query.
+///
+/// JavaScript, TypeScript and Python are not listed: they have dedicated
+/// providers with real module resolution.
+pub static LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec {
+        name: "go",
+        aliases: &["golang"],
+        extensions: &["go"],
+        lang: SupportLang::Go,
+        query: include_str!("../queries/go.scm"),
+    },
+    LanguageSpec {
+        name: "rust",
+        aliases: &["rs"],
+        extensions: &["rs"],
+        lang: SupportLang::Rust,
+        query: include_str!("../queries/rust.scm"),
+    },
+    LanguageSpec {
+        name: "java",
+        aliases: &[],
+        extensions: &["java"],
+        lang: SupportLang::Java,
+        query: include_str!("../queries/java.scm"),
+    },
+    LanguageSpec {
+        name: "c",
+        aliases: &[],
+        extensions: &["c", "h"],
+        lang: SupportLang::C,
+        query: include_str!("../queries/c.scm"),
+    },
+    LanguageSpec {
+        name: "cpp",
+        aliases: &["c++", "cc", "cxx"],
+        extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        lang: SupportLang::Cpp,
+        query: include_str!("../queries/cpp.scm"),
+    },
+    LanguageSpec {
+        name: "csharp",
+        aliases: &["c#", "cs"],
+        extensions: &["cs"],
+        lang: SupportLang::CSharp,
+        query: include_str!("../queries/csharp.scm"),
+    },
+    LanguageSpec {
+        name: "ruby",
+        aliases: &["rb"],
+        extensions: &["rb"],
+        lang: SupportLang::Ruby,
+        query: include_str!("../queries/ruby.scm"),
+    },
+    LanguageSpec {
+        name: "php",
+        aliases: &[],
+        extensions: &["php"],
+        lang: SupportLang::Php,
+        query: include_str!("../queries/php.scm"),
+    },
+    LanguageSpec {
+        name: "kotlin",
+        aliases: &["kt"],
+        extensions: &["kt", "kts"],
+        lang: SupportLang::Kotlin,
+        query: include_str!("../queries/kotlin.scm"),
+    },
+    LanguageSpec {
+        name: "swift",
+        aliases: &[],
+        extensions: &["swift"],
+        lang: SupportLang::Swift,
+        query: include_str!("../queries/swift.scm"),
+    },
+    LanguageSpec {
+        name: "scala",
+        aliases: &[],
+        extensions: &["scala", "sc"],
+        lang: SupportLang::Scala,
+        query: include_str!("../queries/scala.scm"),
+    },
+    LanguageSpec {
+        name: "haskell",
+        aliases: &["hs"],
+        extensions: &["hs"],
+        lang: SupportLang::Haskell,
+        query: include_str!("../queries/haskell.scm"),
+    },
+    LanguageSpec {
+        name: "elixir",
+        aliases: &["ex"],
+        extensions: &["ex", "exs"],
+        lang: SupportLang::Elixir,
+        query: include_str!("../queries/elixir.scm"),
+    },
+    LanguageSpec {
+        name: "lua",
+        aliases: &[],
+        extensions: &["lua"],
+        lang: SupportLang::Lua,
+        query: include_str!("../queries/lua.scm"),
+    },
+    LanguageSpec {
+        name: "bash",
+        aliases: &["sh"],
+        extensions: &["sh", "bash"],
+        lang: SupportLang::Bash,
+        query: include_str!("../queries/bash.scm"),
+    },
+    LanguageSpec {
+        name: "solidity",
+        aliases: &["sol"],
+        extensions: &["sol"],
+        lang: SupportLang::Solidity,
+        query: include_str!("../queries/solidity.scm"),
+    },
+];
+
+/// Look up a language by name or alias (case-insensitive).
+pub fn for_name(name: &str) -> Option<&'static LanguageSpec> {
+    let name = name.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name.as_str()))
+}
+
+/// Look up a language by file extension.
+pub fn for_path(path: &Path) -> Option<&'static LanguageSpec> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|spec| spec.extensions.contains(&ext.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_name_and_path() {
+        assert_eq!(for_name("Go").map(|s| s.name), Some("go"));
+        assert_eq!(for_name("c++").map(|s| s.name), Some("cpp"));
+        assert_eq!(
+            for_path(Path::new("src/Main.java")).map(|s| s.name),
+            Some("java")
+        );
+        assert_eq!(for_path(Path::new("lib.rs")).map(|s| s.name), Some("rust"));
+        assert!(for_name("typescript").is_none());
+        assert!(for_path(Path::new("index.ts")).is_none());
+    }
+}
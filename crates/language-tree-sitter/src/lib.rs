@@ -0,0 +1,41 @@
+//! Generic semantic analysis provider built on tree-sitter locals queries.
+//!
+//! This crate provides best-effort semantic analysis for languages that do
+//! not have a dedicated provider (Go, Rust, Java, C/C++, C#, Ruby, PHP,
+//! Kotlin, Swift, Scala, Haskell, Elixir, Lua, Bash and Solidity). Each
+//! language ships a `locals.scm`-style query describing its scopes,
+//! definitions, references and imports.
+//!
+//! # Features
+//!
+//! - Symbol definition lookup through lexical scopes
+//! - Reference finding within a file, and by name across the workspace
+//! - Two analysis modes: FileScope (single-file) and WorkspaceScope (workspace-wide)
+//! - Per-language query overrides loaded from a directory
+//!
+//! # Example
+//!
+//! ```no_run
+//! use language_tree_sitter::TreeSitterSemanticProvider;
+//! use language_core::{SemanticProvider, ByteRange, DefinitionOptions};
+//! use std::path::{Path, PathBuf};
+//!
+//! let provider = TreeSitterSemanticProvider::workspace_scope(PathBuf::from("."))
+//!     .with_query_dir(PathBuf::from("queries"));
+//!
+//! let definition = provider.get_definition(
+//!     Path::new("main.go"),
+//!     ByteRange::new(42, 45),
+//!     DefinitionOptions::default()
+//! ).unwrap();
+//! ```
+
+mod index;
+mod languages;
+mod provider;
+mod query;
+
+pub use provider::TreeSitterSemanticProvider;
+
+// Re-export core types for convenience
+pub use language_core::{ByteRange, ProviderMode, SemanticProvider, SymbolKind, SymbolLocation};
@@ -0,0 +1,630 @@
+//! Semantic provider built on tree-sitter locals queries.
+
+use crate::index::{FileIndex, SymbolAt};
+use crate::languages::{self, LanguageSpec};
+use crate::query::LocalsQuery;
+use language_core::{
+    filesystem, filesystem::ContentOverlay, ByteRange, DefinitionKind, DefinitionOptions,
+    DefinitionResult, FileReferences, ProviderMode, ReferencesResult, SemanticError,
+    SemanticProvider, SemanticResult, SymbolLocation,
+};
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use vfs::{VfsFileType, VfsPath};
+
+/// A parsed and indexed file.
+struct IndexedFile {
+    language: &'static LanguageSpec,
+    content: String,
+    index: FileIndex,
+}
+
+/// Semantic analysis provider for languages without a dedicated provider.
+///
+/// Symbols are resolved with tree-sitter locals queries (scopes, definitions,
+/// references and imports), so results are syntactic rather than type-aware:
+///
+/// - **FileScope**: references are resolved through the scopes of a single
+///   file.
+/// - **WorkspaceScope**: additionally, imports and unresolved references are
+///   matched by name against top-level definitions in other files of the same
+///   language, and references to top-level definitions are searched for across
+///   the workspace. This is best-effort: there is no module resolution.
+///
+/// Built-in queries can be replaced per language with
+/// [`with_query_dir`](Self::with_query_dir).
+pub struct TreeSitterSemanticProvider {
+    mode: ProviderMode,
+    /// Virtual filesystem root for file operations
+    fs_root: VfsPath,
+    /// Physical root path for converting absolute paths to relative paths.
+    /// Only used when fs_root is PhysicalFS.
+    physical_root: Option<PathBuf>,
+    /// Directory with `<language>.scm` files overriding the built-in queries.
+    query_dir: Option<PathBuf>,
+    /// Compiled queries by language name. `None` if the query failed to
+    /// compile.
+    queries: RwLock<HashMap<&'static str, Option<Arc<LocalsQuery>>>>,
+    /// Contents served instead of the filesystem, by cache key.
+    overlay: ContentOverlay,
+    /// Indexed files by cache key.
+    files: RwLock<HashMap<PathBuf, Arc<IndexedFile>>>,
+    /// Languages whose workspace files have been indexed.
+    indexed_languages: RwLock<HashSet<&'static str>>,
+}
+
+impl TreeSitterSemanticProvider {
+    /// Create a file-scope provider for single-file analysis.
+    ///
+    /// Uses the real filesystem (PhysicalFS) with the current directory as root.
+    pub fn file_scope() -> Self {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        Self::new(
+            ProviderMode::FileScope,
+            filesystem::physical_path(&cwd),
+            Some(cwd),
+        )
+    }
+
+    /// Create a file-scope provider with a custom virtual filesystem.
+    pub fn file_scope_with_fs(fs_root: VfsPath) -> Self {
+        Self::new(ProviderMode::FileScope, fs_root, None)
+    }
+
+    /// Create a workspace-scope provider for workspace-wide analysis.
+    ///
+    /// Uses the real filesystem (PhysicalFS) with the workspace root. Files
+    /// are discovered with `.gitignore` honored and hidden files skipped.
+    pub fn workspace_scope(workspace_root: PathBuf) -> Self {
+        // Canonicalize workspace root to handle symlinks (e.g., /var -> /private/var on macOS)
+        let canonical_root = workspace_root
+            .canonicalize()
+            .unwrap_or_else(|_| workspace_root.clone());
+        Self::new(
+            ProviderMode::WorkspaceScope,
+            filesystem::physical_path(&canonical_root),
+            Some(canonical_root),
+        )
+    }
+
+    /// Create a workspace-scope provider with a custom virtual filesystem.
+    ///
+    /// Files are discovered by walking `fs_root`.
+    pub fn workspace_scope_with_fs(fs_root: VfsPath) -> Self {
+        Self::new(ProviderMode::WorkspaceScope, fs_root, None)
+    }
+
+    fn new(mode: ProviderMode, fs_root: VfsPath, physical_root: Option<PathBuf>) -> Self {
+        Self {
+            mode,
+            fs_root,
+            physical_root,
+            query_dir: None,
+            overlay: ContentOverlay::new(),
+            queries: RwLock::new(HashMap::new()),
+            files: RwLock::new(HashMap::new()),
+            indexed_languages: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Load query overrides from `dir`.
+    ///
+    /// A file named `<language>.scm` (e.g. `go.scm`, `cpp.scm`) replaces the
+    /// built-in query for that language. Languages without a file keep the
+    /// built-in query.
+    pub fn with_query_dir(mut self, dir: PathBuf) -> Self {
+        self.query_dir = Some(dir);
+        self
+    }
+
+    /// Whether `lang` is one of the languages this provider has queries for.
+    pub fn is_supported_language(lang: &str) -> bool {
+        languages::for_name(lang).is_some()
+    }
+
+    /// The name of the language a file is analyzed as, from its extension.
+    pub fn detect_language(file_path: &Path) -> Option<&'static str> {
+        languages::for_path(file_path).map(|language| language.name)
+    }
+
+    /// Clear all cached data.
+    pub fn clear_cache(&self) {
+        self.files.write().clear();
+        self.indexed_languages.write().clear();
+    }
+
+    /// Get the number of cached files.
+    pub fn cached_file_count(&self) -> usize {
+        self.files.read().len()
+    }
+
+    /// Get the virtual filesystem root used by this provider.
+    pub fn fs_root(&self) -> &VfsPath {
+        &self.fs_root
+    }
+
+    fn query_for(&self, language: &'static LanguageSpec) -> Option<Arc<LocalsQuery>> {
+        if let Some(query) = self.queries.read().get(language.name) {
+            return query.clone();
+        }
+
+        let override_source = self.query_dir.as_ref().and_then(|dir| {
+            let path = dir.join(format!("{}.scm", language.name));
+            match std::fs::read_to_string(&path) {
+                Ok(source) => Some(source),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    log::warn!("Failed to read query file {}: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+        let source = override_source.as_deref().unwrap_or(language.query);
+        let query =
+            LocalsQuery::compile(&language.ts_language(), language.name, source).map(Arc::new);
+        self.queries.write().insert(language.name, query.clone());
+        query
+    }
+
+    /// The key a file is cached under. Physical paths are canonicalized,
+    /// virtual paths are made relative to the VFS root.
+    fn cache_key(&self, file_path: &Path) -> PathBuf {
+        if self.physical_root.is_some() {
+            file_path
+                .canonicalize()
+                .unwrap_or_else(|_| file_path.to_path_buf())
+        } else {
+            PathBuf::from(file_path.to_string_lossy().trim_start_matches('/'))
+        }
+    }
+
+    /// Read file content using the virtual filesystem.
+    fn read_file(&self, key: &Path) -> SemanticResult<String> {
+        if let Some(content) = self.overlay.get(key) {
+            return Ok(content);
+        }
+        let file_read_error = |message: String| SemanticError::FileRead {
+            path: key.to_path_buf(),
+            message,
+        };
+
+        let vfs_path = match &self.physical_root {
+            // Files outside the root are read through a VFS rooted at their
+            // parent directory.
+            Some(root) => match key.strip_prefix(root) {
+                Ok(relative) => self.fs_root.join(&*relative.to_string_lossy()),
+                Err(_) => {
+                    let parent = key.parent().unwrap_or(Path::new("/"));
+                    let file_name = key
+                        .file_name()
+                        .ok_or_else(|| file_read_error("Invalid file path".to_string()))?;
+                    filesystem::physical_path(parent).join(&*file_name.to_string_lossy())
+                }
+            },
+            None => self.fs_root.join(&*key.to_string_lossy()),
+        }
+        .map_err(|e| file_read_error(e.to_string()))?;
+
+        filesystem::read_to_string(&vfs_path).map_err(|e| file_read_error(e.to_string()))
+    }
+
+    /// Index `content` under `key` and cache the result.
+    fn index_content(
+        &self,
+        key: PathBuf,
+        language: &'static LanguageSpec,
+        content: String,
+    ) -> Option<Arc<IndexedFile>> {
+        let query = self.query_for(language)?;
+        let index = FileIndex::build(&language.ts_language(), &query, &content)?;
+        let file = Arc::new(IndexedFile {
+            language,
+            content,
+            index,
+        });
+        self.files.write().insert(key, file.clone());
+        Some(file)
+    }
+
+    /// The indexed file at `key`, reading and indexing it if needed.
+    fn indexed(&self, key: &Path) -> SemanticResult<Option<Arc<IndexedFile>>> {
+        if let Some(file) = self.files.read().get(key) {
+            return Ok(Some(file.clone()));
+        }
+        let Some(language) = languages::for_path(key) else {
+            return Ok(None);
+        };
+        let content = self.read_file(key)?;
+        Ok(self.index_content(key.to_path_buf(), language, content))
+    }
+
+    /// Index every workspace file of `language` once.
+    fn ensure_workspace_indexed(&self, language: &'static LanguageSpec) {
+        if self.mode != ProviderMode::WorkspaceScope
+            || !self.indexed_languages.write().insert(language.name)
+        {
+            return;
+        }
+
+        let mut paths = Vec::new();
+        match &self.physical_root {
+            Some(root) => {
+                let walker = ignore::WalkBuilder::new(root)
+                    .hidden(true)
+                    .git_ignore(true)
+                    .git_exclude(true)
+                    .build();
+                for entry in walker.flatten() {
+                    if entry.path().is_file() {
+                        paths.push(entry.path().to_path_buf());
+                    }
+                }
+            }
+            None => walk_vfs_files(&self.fs_root, &mut paths),
+        }
+
+        for path in paths {
+            if languages::for_path(&path).is_some_and(|l| l.name == language.name) {
+                let key = self.cache_key(&path);
+                if let Err(e) = self.indexed(&key) {
+                    log::debug!("Skipping {} while indexing workspace: {}", key.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Other indexed files of the same language, sorted by path.
+    fn other_files(
+        &self,
+        key: &Path,
+        language: &'static LanguageSpec,
+    ) -> Vec<(PathBuf, Arc<IndexedFile>)> {
+        let mut files: Vec<_> = self
+            .files
+            .read()
+            .iter()
+            .filter(|(path, file)| path.as_path() != key && file.language.name == language.name)
+            .map(|(path, file)| (path.clone(), file.clone()))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    }
+
+    /// Find a top-level definition named `name` in another file.
+    fn find_external_definition(
+        &self,
+        key: &Path,
+        language: &'static LanguageSpec,
+        name: &str,
+    ) -> Option<(PathBuf, Arc<IndexedFile>, usize)> {
+        self.ensure_workspace_indexed(language);
+        self.other_files(key, language)
+            .into_iter()
+            .find_map(|(path, file)| {
+                let definition = file.index.top_level_definition(name)?;
+                Some((path, file, definition))
+            })
+    }
+
+    /// The definition a symbol at `range` refers to, following imports and
+    /// unresolved references into other files in workspace scope.
+    fn resolve_symbol(
+        &self,
+        key: &Path,
+        file: Arc<IndexedFile>,
+        range: ByteRange,
+        resolve_external: bool,
+    ) -> Option<(PathBuf, Arc<IndexedFile>, usize, DefinitionKind)> {
+        let symbol = file.index.symbol_at(range)?;
+        let local = file.index.definition_of(symbol);
+        if let Some(index) = local {
+            if !file.index.definitions[index].is_import {
+                return Some((key.to_path_buf(), file, index, DefinitionKind::Local));
+            }
+        }
+
+        if resolve_external && self.mode == ProviderMode::WorkspaceScope {
+            let name = match symbol {
+                SymbolAt::Definition(index) => &file.index.definitions[index].name,
+                SymbolAt::Reference(index) => &file.index.references[index].name,
+            };
+            if let Some((path, other, index)) =
+                self.find_external_definition(key, file.language, name)
+            {
+                return Some((path, other, index, DefinitionKind::External));
+            }
+        }
+
+        local.map(|index| (key.to_path_buf(), file, index, DefinitionKind::Import))
+    }
+}
+
+impl std::fmt::Debug for TreeSitterSemanticProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            ProviderMode::FileScope => write!(f, "TreeSitterSemanticProvider::FileScope"),
+            ProviderMode::WorkspaceScope => {
+                write!(f, "TreeSitterSemanticProvider::WorkspaceScope")
+            }
+        }
+    }
+}
+
+impl SemanticProvider for TreeSitterSemanticProvider {
+    fn get_definition(
+        &self,
+        file_path: &Path,
+        range: ByteRange,
+        options: DefinitionOptions,
+    ) -> SemanticResult<Option<DefinitionResult>> {
+        let key = self.cache_key(file_path);
+        let Some(file) = self.indexed(&key)? else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .resolve_symbol(&key, file, range, options.resolve_external)
+            .map(|(path, file, index, kind)| {
+                let definition = &file.index.definitions[index];
+                DefinitionResult::new(
+                    SymbolLocation::new(
+                        path,
+                        to_byte_range(&definition.range),
+                        definition.kind,
+                        definition.name.clone(),
+                    ),
+                    file.content.clone(),
+                    kind,
+                )
+            }))
+    }
+
+    fn find_references(
+        &self,
+        file_path: &Path,
+        range: ByteRange,
+    ) -> SemanticResult<ReferencesResult> {
+        let mut result = ReferencesResult::new();
+        let key = self.cache_key(file_path);
+        let Some(file) = self.indexed(&key)? else {
+            return Ok(result);
+        };
+        let Some((def_path, def_file, def_index, _)) = self.resolve_symbol(&key, file, range, true)
+        else {
+            return Ok(result);
+        };
+
+        let definition = &def_file.index.definitions[def_index];
+        let location = |path: &Path, range: &std::ops::Range<usize>| {
+            SymbolLocation::new(
+                path.to_path_buf(),
+                to_byte_range(range),
+                definition.kind,
+                definition.name.clone(),
+            )
+        };
+
+        let locations: Vec<_> = def_file
+            .index
+            .references_to(def_index)
+            .map(|reference| location(&def_path, &reference.range))
+            .collect();
+        if !locations.is_empty() {
+            result.add_file(FileReferences::new(
+                def_path.clone(),
+                def_file.content.clone(),
+                locations,
+            ));
+        }
+
+        // Top-level definitions may be used from other files
+        if self.mode == ProviderMode::WorkspaceScope
+            && definition.scope == 0
+            && !definition.is_import
+        {
+            self.ensure_workspace_indexed(def_file.language);
+            for (path, other) in self.other_files(&def_path, def_file.language) {
+                let locations: Vec<_> = other
+                    .index
+                    .external_uses(&definition.name)
+                    .iter()
+                    .map(|range| location(&path, range))
+                    .collect();
+                if !locations.is_empty() {
+                    result.add_file(FileReferences::new(path, other.content.clone(), locations));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn get_type(&self, _file_path: &Path, _range: ByteRange) -> SemanticResult<Option<String>> {
+        // Locals queries carry no type information
+        Ok(None)
+    }
+
+    fn notify_file_processed(&self, file_path: &Path, content: &str) -> SemanticResult<()> {
+        if let Some(language) = languages::for_path(file_path) {
+            self.index_content(self.cache_key(file_path), language, content.to_string());
+        }
+        Ok(())
+    }
+
+    fn set_file_overlay(&self, file_path: &Path, content: Option<&str>) -> SemanticResult<()> {
+        let key = self.cache_key(file_path);
+        self.overlay.set(key.clone(), content);
+        if let Some(language) = languages::for_path(file_path) {
+            let content = self.read_file(&key)?;
+            self.index_content(key, language, content);
+        }
+        Ok(())
+    }
+
+    fn supports_language(&self, lang: &str) -> bool {
+        Self::is_supported_language(lang)
+    }
+
+    fn mode(&self) -> ProviderMode {
+        self.mode
+    }
+}
+
+fn to_byte_range(range: &std::ops::Range<usize>) -> ByteRange {
+    ByteRange::new(range.start as u32, range.end as u32)
+}
+
+/// Collect every file under `entry`, as paths relative to the VFS root.
+fn walk_vfs_files(entry: &VfsPath, paths: &mut Vec<PathBuf>) {
+    let Ok(meta) = entry.metadata() else {
+        return;
+    };
+    match meta.file_type {
+        VfsFileType::Directory => {
+            let Ok(children) = entry.read_dir() else {
+                return;
+            };
+            for child in children {
+                walk_vfs_files(&child, paths);
+            }
+        }
+        VfsFileType::File => paths.push(PathBuf::from(entry.as_str().trim_start_matches('/'))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn range_of(content: &str, needle: &str) -> ByteRange {
+        let start = content.find(needle).unwrap() as u32;
+        ByteRange::new(start, start + needle.len() as u32)
+    }
+
+    #[test]
+    fn test_supports_language() {
+        let provider = TreeSitterSemanticProvider::file_scope();
+        assert!(provider.supports_language("go"));
+        assert!(provider.supports_language("Java"));
+        assert!(provider.supports_language("rust"));
+        assert!(!provider.supports_language("typescript"));
+        assert!(!provider.supports_language("python"));
+    }
+
+    #[test]
+    fn test_file_scope_definition_and_references() {
+        let dir = TempDir::new().unwrap();
+        let content = "package main\n\nfunc helper() int { return 1 }\n\nfunc main() {\n\tx := helper()\n\tprintln(x, helper())\n}\n";
+        let file = dir.path().join("main.go");
+        fs::write(&file, content).unwrap();
+
+        let provider = TreeSitterSemanticProvider::file_scope();
+        let call = content.find("helper()\n").unwrap() as u32;
+        let definition = provider
+            .get_definition(
+                &file,
+                ByteRange::new(call, call + 6),
+                DefinitionOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(definition.kind, DefinitionKind::Local);
+        assert_eq!(definition.location.range, range_of(content, "helper"));
+
+        let references = provider
+            .find_references(&file, range_of(content, "helper"))
+            .unwrap();
+        assert_eq!(references.total_count(), 2);
+    }
+
+    #[test]
+    fn test_workspace_scope_resolves_across_files() {
+        let dir = TempDir::new().unwrap();
+        let util = "package main\n\nfunc Add(a, b int) int { return a + b }\n";
+        let main = "package main\n\nfunc main() {\n\tprintln(Add(1, 2))\n}\n";
+        fs::write(dir.path().join("util.go"), util).unwrap();
+        fs::write(dir.path().join("main.go"), main).unwrap();
+
+        let provider = TreeSitterSemanticProvider::workspace_scope(dir.path().to_path_buf());
+        let main_path = dir.path().join("main.go");
+
+        let definition = provider
+            .get_definition(
+                &main_path,
+                range_of(main, "Add"),
+                DefinitionOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(definition.kind, DefinitionKind::External);
+        assert!(definition.location.file_path.ends_with("util.go"));
+        assert_eq!(definition.location.range, range_of(util, "Add"));
+
+        let references = provider
+            .find_references(&dir.path().join("util.go"), range_of(util, "Add"))
+            .unwrap();
+        assert_eq!(references.total_count(), 1);
+        assert!(references.files[0].file_path.ends_with("main.go"));
+    }
+
+    #[test]
+    fn test_query_dir_overrides_builtin_query() {
+        let dir = TempDir::new().unwrap();
+        let queries = dir.path().join("queries");
+        fs::create_dir(&queries).unwrap();
+        // Only parameters are definitions; locals stay unresolved
+        fs::write(
+            queries.join("go.scm"),
+            "(parameter_declaration name: (identifier) @local.definition.parameter)\n(identifier) @local.reference\n",
+        )
+        .unwrap();
+        let content = "package main\n\nfunc f() {\n\tx := 1\n\tprintln(x)\n}\n";
+        let file = dir.path().join("main.go");
+        fs::write(&file, content).unwrap();
+
+        let provider = TreeSitterSemanticProvider::file_scope().with_query_dir(queries);
+        let usage = content.rfind('x').unwrap() as u32;
+        let definition = provider
+            .get_definition(
+                &file,
+                ByteRange::new(usage, usage + 1),
+                DefinitionOptions::default(),
+            )
+            .unwrap();
+        assert!(definition.is_none());
+    }
+
+    #[test]
+    fn test_workspace_scope_with_memory_fs() {
+        use std::io::Write;
+
+        let fs_root = filesystem::memory_fs();
+        let util = "package util\n\nfunc Add(a, b int) int { return a + b }\n";
+        let main = "package main\n\nfunc main() {\n\tprintln(Add(1, 2))\n}\n";
+        for (name, content) in [("util.go", util), ("main.go", main)] {
+            fs_root
+                .join(name)
+                .unwrap()
+                .create_file()
+                .unwrap()
+                .write_all(content.as_bytes())
+                .unwrap();
+        }
+
+        let provider = TreeSitterSemanticProvider::workspace_scope_with_fs(fs_root);
+        let definition = provider
+            .get_definition(
+                Path::new("main.go"),
+                range_of(main, "Add"),
+                DefinitionOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(definition.kind, DefinitionKind::External);
+        assert_eq!(definition.location.file_path, PathBuf::from("util.go"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Compilation of locals queries.
+//!
+//! Queries follow the tree-sitter `locals.scm` conventions:
+//!
+//! - `@local.scope` marks a node that opens a scope.
+//! - `@local.definition` (optionally suffixed with a kind, e.g.
+//!   `@local.definition.function`) marks the name of a definition.
+//!   `@local.definition.import` marks a name brought in by an import.
+//! - `@local.reference` marks an identifier that refers to a definition.
+//!
+//! Captures without the `local.` prefix (as used by nvim-treesitter) are
+//! accepted too. A pattern can place its definitions in the enclosing scope's
+//! parent with `(#set! definition.<kind>.scope "parent")`.
+
+use language_core::SymbolKind;
+use tree_sitter::{Language, Query};
+
+/// What a capture contributes to the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureRole {
+    Scope,
+    Definition { kind: SymbolKind, is_import: bool },
+    Reference,
+}
+
+/// A compiled locals query together with the role of each capture.
+pub struct LocalsQuery {
+    pub query: Query,
+    pub roles: Vec<Option<CaptureRole>>,
+}
+
+impl std::fmt::Debug for LocalsQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalsQuery")
+            .field("patterns", &self.query.pattern_count())
+            .finish()
+    }
+}
+
+impl LocalsQuery {
+    /// Whether definitions captured by `pattern` belong to the parent of the
+    /// scope they appear in, as requested with
+    /// `(#set! definition.<kind>.scope "parent")`.
+    pub fn defines_in_parent_scope(&self, pattern: usize) -> bool {
+        self.query
+            .property_settings(pattern)
+            .iter()
+            .any(|property| {
+                property.key.ends_with("scope") && property.value.as_deref() == Some("parent")
+            })
+    }
+
+    /// Compile `source` for `language`.
+    ///
+    /// Patterns are compiled one by one and patterns the grammar rejects
+    /// (e.g. a node type that does not exist in this grammar version) are
+    /// skipped with a warning, so one stale pattern does not disable
+    /// resolution for the whole language.
+    pub fn compile(language: &Language, language_name: &str, source: &str) -> Option<Self> {
+        let mut valid = Vec::new();
+        for pattern in split_patterns(source) {
+            match Query::new(language, pattern) {
+                Ok(_) => valid.push(pattern),
+                Err(e) => log::warn!(
+                    "Skipping invalid {} locals query pattern: {}",
+                    language_name,
+                    e
+                ),
+            }
+        }
+        if valid.is_empty() {
+            return None;
+        }
+
+        let query = match Query::new(language, &valid.join("\n")) {
+            Ok(query) => query,
+            Err(e) => {
+                log::warn!("Failed to compile {} locals query: {}", language_name, e);
+                return None;
+            }
+        };
+        let roles = query
+            .capture_names()
+            .iter()
+            .map(|name| capture_role(name))
+            .collect();
+        Some(Self { query, roles })
+    }
+}
+
+fn capture_role(name: &str) -> Option<CaptureRole> {
+    let name = name.strip_prefix("local.").unwrap_or(name);
+    if name == "scope" {
+        return Some(CaptureRole::Scope);
+    }
+    if name == "reference" {
+        return Some(CaptureRole::Reference);
+    }
+    let suffix = name.strip_prefix("definition")?;
+    let kind = suffix.strip_prefix('.').unwrap_or(suffix);
+    Some(CaptureRole::Definition {
+        kind: definition_kind(kind),
+        is_import: kind == "import",
+    })
+}
+
+fn definition_kind(kind: &str) -> SymbolKind {
+    match kind {
+        "var" | "variable" => SymbolKind::Variable,
+        "function" => SymbolKind::Function,
+        "method" => SymbolKind::Method,
+        "parameter" => SymbolKind::Parameter,
+        "field" | "property" => SymbolKind::Property,
+        "type" | "associated" => SymbolKind::Type,
+        "class" => SymbolKind::Class,
+        "interface" => SymbolKind::Interface,
+        "enum" => SymbolKind::Enum,
+        "namespace" => SymbolKind::Namespace,
+        "constant" | "macro" => SymbolKind::Constant,
+        "import" => SymbolKind::Import,
+        _ => SymbolKind::Unknown,
+    }
+}
+
+/// Split a query source into its top-level patterns.
+///
+/// A pattern starts at each `(`, `[` or `"` at nesting depth zero and runs
+/// until the next one, so trailing captures, quantifiers and predicates stay
+/// with the pattern they belong to. Comments are dropped.
+fn split_patterns(source: &str) -> Vec<&str> {
+    let mut patterns = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    let mut end = 0;
+    let mut chars = source.char_indices();
+
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            ';' => {
+                // Comment: skip to end of line
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                if depth == 0 {
+                    if let Some(begin) = start.replace(index) {
+                        patterns.push(source[begin..end].trim());
+                    }
+                }
+                let mut escaped = false;
+                for (i, c) in chars.by_ref() {
+                    end = i + c.len_utf8();
+                    if escaped {
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '(' | '[' => {
+                if depth == 0 {
+                    if let Some(begin) = start.replace(index) {
+                        patterns.push(source[begin..end].trim());
+                    }
+                }
+                depth += 1;
+            }
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if !ch.is_whitespace() {
+            end = index + ch.len_utf8();
+        }
+    }
+    if let Some(begin) = start {
+        patterns.push(source[begin..end].trim());
+    }
+    patterns.retain(|pattern| !pattern.is_empty());
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_patterns() {
+        let source = r#"
+; scopes
+(block) @local.scope
+[(function_declaration) (method_declaration)] @local.scope
+
+(identifier) @local.reference ; trailing comment
+((identifier) @local.definition.var
+  (#eq? @local.definition.var "x"))
+"#;
+        assert_eq!(
+            split_patterns(source),
+            vec![
+                "(block) @local.scope",
+                "[(function_declaration) (method_declaration)] @local.scope",
+                "(identifier) @local.reference",
+                "((identifier) @local.definition.var\n  (#eq? @local.definition.var \"x\"))",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_roles() {
+        assert_eq!(capture_role("local.scope"), Some(CaptureRole::Scope));
+        assert_eq!(capture_role("reference"), Some(CaptureRole::Reference));
+        assert_eq!(
+            capture_role("local.definition.function"),
+            Some(CaptureRole::Definition {
+                kind: SymbolKind::Function,
+                is_import: false
+            })
+        );
+        assert_eq!(
+            capture_role("local.definition.import"),
+            Some(CaptureRole::Definition {
+                kind: SymbolKind::Import,
+                is_import: true
+            })
+        );
+        assert_eq!(capture_role("name"), None);
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_skipped() {
+        let language = crate::languages::for_name("go").unwrap().ts_language();
+        let query = LocalsQuery::compile(
+            &language,
+            "go",
+            "(not_a_go_node) @local.scope\n(identifier) @local.reference",
+        )
+        .unwrap();
+        assert_eq!(query.query.pattern_count(), 1);
+        assert_eq!(query.roles, vec![Some(CaptureRole::Reference)]);
+    }
+
+    #[test]
+    fn test_builtin_queries_have_no_invalid_patterns() {
+        for spec in crate::languages::LANGUAGES {
+            let language = spec.ts_language();
+            let rejected: Vec<String> = split_patterns(spec.query)
+                .into_iter()
+                .filter_map(|pattern| {
+                    Query::new(&language, pattern)
+                        .err()
+                        .map(|e| format!("{pattern}: {e}"))
+                })
+                .collect();
+            assert!(
+                rejected.is_empty(),
+                "{} locals query has patterns the grammar rejects:\n{}",
+                spec.name,
+                rejected.join("\n")
+            );
+        }
+    }
+}
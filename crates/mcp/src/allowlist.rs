@@ -0,0 +1,111 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directories tools may read code from and run code in.
+///
+/// Paths are resolved through symlinks before they are checked, so a link
+/// inside an allowed directory cannot point tools outside of it.
+#[derive(Clone, Debug)]
+pub struct PathAllowlist {
+    roots: Vec<PathBuf>,
+}
+
+impl PathAllowlist {
+    /// Allow the given directories. Each must exist.
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> io::Result<Self> {
+        let roots = roots
+            .into_iter()
+            .map(|root| {
+                root.canonicalize().map_err(|error| {
+                    io::Error::new(
+                        error.kind(),
+                        format!("Cannot allow '{}': {error}", root.display()),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self { roots })
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Check that `path`, relative to the current directory, is inside an
+    /// allowed directory. Paths that do not exist yet are checked through
+    /// their closest existing ancestor.
+    pub fn check(&self, path: &Path) -> Result<(), String> {
+        let outside = || {
+            format!(
+                "Path '{}' is outside the allowed directories",
+                path.display()
+            )
+        };
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()
+                .map_err(|error| format!("Failed to read the current directory: {error}"))?
+                .join(path)
+        };
+        let resolved = resolve(&absolute).ok_or_else(outside)?;
+        if self.roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(())
+        } else {
+            Err(outside())
+        }
+    }
+}
+
+/// Canonical form of `path`, resolving the closest existing ancestor and
+/// appending the rest. `None` when the rest contains `..`.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    if let Ok(canonical) = path.canonicalize() {
+        return Some(canonical);
+    }
+    let file_name = path.file_name()?;
+    Some(resolve(path.parent()?)?.join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_must_be_inside_an_allowed_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed");
+        std::fs::create_dir_all(allowed.join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("other")).unwrap();
+        let allowlist = PathAllowlist::new([allowed.clone()]).unwrap();
+
+        assert!(allowlist.check(&allowed).is_ok());
+        assert!(allowlist.check(&allowed.join("src")).is_ok());
+        assert!(allowlist.check(&allowed.join("src/new.ts")).is_ok());
+        assert!(allowlist.check(&dir.path().join("other")).is_err());
+        assert!(allowlist.check(&allowed.join("../other")).is_err());
+        assert!(allowlist
+            .check(&allowed.join("missing/../../other"))
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_checked_by_their_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed");
+        let other = dir.path().join("other");
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        std::os::unix::fs::symlink(&other, allowed.join("link")).unwrap();
+        let allowlist = PathAllowlist::new([allowed.clone()]).unwrap();
+
+        assert!(allowlist.check(&allowed.join("link")).is_err());
+    }
+
+    #[test]
+    fn allowed_directories_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(PathAllowlist::new([dir.path().join("missing")]).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+use butterflow_core::diff::{generate_unified_diff, DiffConfig, DiffMetadata};
+use butterflow_core::utils::parse_workflow_file;
+use butterflow_models::step::StepAction;
+use codemod_sandbox::metrics::MetricEntry;
+use codemod_sandbox::sandbox::engine::{
+    execute_codemod_with_quickjs, CodemodOutput, ExecutionResult, JssgExecutionOptions,
+};
+use codemod_sandbox::sandbox::resolvers::OxcResolver;
+use codemod_sandbox::utils::project_discovery::find_tsconfig;
+use codemod_sandbox::{CodemodLang, MetricsContext};
+use rmcp::{handler::server::wrapper::Parameters, model::*, schemars, tool, ErrorData as McpError};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use super::package_validation::workflow_path_for_package;
+use super::pattern_search::{relative_path, target_files};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DryRunCodemodRequest {
+    /// Path to a JSSG codemod file, or to a codemod package whose workflow has
+    /// a `js-ast-grep` step
+    pub codemod: String,
+    /// File or directory to run the codemod on. Nothing is written.
+    pub path: String,
+    /// The programming language (default: the package step's language; required
+    /// for codemod files)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Globs of files to run on (default: the package step's include globs, or
+    /// every file with the language's extensions)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs of files to skip (default: the package step's exclude globs)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Parameters passed to the codemod as `options.params`
+    #[serde(default)]
+    pub params: Option<HashMap<String, serde_json::Value>>,
+    /// Maximum number of changed files to return diffs for (default: 50)
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Maximum number of lines in each file's diff (default: 200)
+    #[serde(default = "default_max_diff_lines")]
+    pub max_diff_lines: usize,
+    /// Stop running after this many seconds (default: 60)
+    #[serde(default = "default_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_max_files() -> usize {
+    50
+}
+
+fn default_max_diff_lines() -> usize {
+    200
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileChangeDiff {
+    /// Path relative to the target path
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<String>,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff of the change
+    pub diff: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DryRunCodemodResponse {
+    /// The codemod script that ran
+    pub codemod: String,
+    pub language: String,
+    pub files_processed: usize,
+    pub files_changed: usize,
+    pub changes: Vec<FileChangeDiff>,
+    pub errors: Vec<FileError>,
+    /// Metrics recorded with `codemod:metrics`, by metric name
+    pub metrics: BTreeMap<String, Vec<MetricEntry>>,
+    /// `max_files` was reached; other files may have changed or failed too
+    pub truncated: bool,
+    /// `timeout_seconds` elapsed before every file was processed
+    pub timed_out: bool,
+}
+
+/// The script to run and the defaults taken from a package's workflow step.
+#[derive(Debug)]
+struct ResolvedCodemod {
+    script: PathBuf,
+    language: Option<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+/// Resolve a codemod file as is, and a package to the first `js-ast-grep`
+/// step of its workflow.
+fn resolve_codemod(codemod: &Path) -> Result<ResolvedCodemod, String> {
+    if codemod.is_file() {
+        return Ok(ResolvedCodemod {
+            script: codemod.to_path_buf(),
+            language: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        });
+    }
+    if !codemod.is_dir() {
+        return Err(format!("Codemod not found: {}", codemod.display()));
+    }
+
+    let workflow_path = workflow_path_for_package(codemod).path;
+    let workflow = parse_workflow_file(&workflow_path).map_err(|error| {
+        format!(
+            "Failed to parse workflow {}: {error}",
+            workflow_path.display()
+        )
+    })?;
+    let step = workflow
+        .nodes
+        .iter()
+        .flat_map(|node| &node.steps)
+        .find_map(|step| match &step.action {
+            StepAction::JSAstGrep(js_ast_grep) => Some(js_ast_grep),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            format!(
+                "Workflow {} has no js-ast-grep step",
+                workflow_path.display()
+            )
+        })?;
+
+    let js_file = Path::new(&step.js_file);
+    if js_file.is_absolute()
+        || js_file
+            .components()
+            .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Err(format!(
+            "js-ast-grep.js_file must stay within the package root: {}",
+            step.js_file
+        ));
+    }
+    Ok(ResolvedCodemod {
+        script: codemod.join(js_file),
+        language: step.language.clone(),
+        include: step.include.clone().unwrap_or_default(),
+        exclude: step.exclude.clone().unwrap_or_default(),
+    })
+}
+
+#[derive(Clone)]
+pub struct CodemodDryRunHandler;
+
+impl CodemodDryRunHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    #[tool(
+        description = "Run a JSSG codemod file or package on a path without writing anything and return the unified diffs and metrics"
+    )]
+    pub async fn dry_run_codemod(
+        &self,
+        Parameters(request): Parameters<DryRunCodemodRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        // Spawn a blocking task to handle the QuickJS execution
+        let response = tokio::task::spawn_blocking(move || {
+            tokio::runtime::Handle::current().block_on(async move { dry_run(request).await })
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {e}"), None))?
+        .map_err(|e| McpError::invalid_params(e, None))?;
+
+        let content = serde_json::to_string_pretty(&response).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize response: {e}"), None)
+        })?;
+        Ok(CallToolResult::success(vec![Content::text(content)]))
+    }
+}
+
+impl Default for CodemodDryRunHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn dry_run(request: DryRunCodemodRequest) -> Result<DryRunCodemodResponse, String> {
+    let deadline = Instant::now() + Duration::from_secs(request.timeout_seconds);
+    let resolved = resolve_codemod(Path::new(&request.codemod))?;
+    if !resolved.script.is_file() {
+        return Err(format!(
+            "Codemod script not found: {}",
+            resolved.script.display()
+        ));
+    }
+    let language_name = request
+        .language
+        .clone()
+        .or_else(|| resolved.language.clone())
+        .ok_or("`language` is required for codemod files")?;
+    let language: CodemodLang = language_name
+        .parse()
+        .map_err(|error| format!("Unsupported language '{language_name}': {error}"))?;
+
+    let root = PathBuf::from(&request.path);
+    if !root.exists() {
+        return Err(format!("Path not found: {}", request.path));
+    }
+    let target_directory = if root.is_dir() {
+        root.clone()
+    } else {
+        root.parent().unwrap_or(Path::new(".")).to_path_buf()
+    };
+    let include = if request.include.is_empty() {
+        &resolved.include
+    } else {
+        &request.include
+    };
+    let exclude = if request.exclude.is_empty() {
+        &resolved.exclude
+    } else {
+        &request.exclude
+    };
+    let mut files = target_files(&root, &language_name, include, exclude);
+    files.sort();
+
+    let script_base_dir = resolved
+        .script
+        .parent()
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    let tsconfig_path = find_tsconfig(&script_base_dir);
+    let resolver = Arc::new(
+        OxcResolver::new(script_base_dir, tsconfig_path)
+            .map_err(|e| format!("Failed to create resolver: {e}"))?,
+    );
+    let metrics_context = MetricsContext::new();
+    let diff_config = DiffConfig {
+        context_lines: 3,
+        color: false,
+        max_lines_per_file: request.max_diff_lines,
+    };
+
+    let mut response = DryRunCodemodResponse {
+        codemod: resolved.script.display().to_string(),
+        language: language_name.clone(),
+        files_processed: 0,
+        files_changed: 0,
+        changes: Vec::new(),
+        errors: Vec::new(),
+        metrics: BTreeMap::new(),
+        truncated: false,
+        timed_out: false,
+    };
+    // The flag lets codemods that poll `isCanceled()` stop early, and
+    // interrupts scripts stuck in a loop that never yields to the timeout
+    let cancellation_flag = Arc::new(AtomicBool::new(false));
+    let _watchdog = Watchdog::start(deadline, cancellation_flag.clone());
+    for file_path in &files {
+        if response.changes.len() + response.errors.len() >= request.max_files {
+            response.truncated = true;
+            break;
+        }
+        let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+            response.timed_out = true;
+            break;
+        };
+        // Binary and non-UTF-8 files cannot be transformed
+        let Ok(content) = std::fs::read_to_string(file_path) else {
+            continue;
+        };
+        response.files_processed += 1;
+
+        let execution = execute_codemod_with_quickjs(JssgExecutionOptions {
+            script_path: &resolved.script,
+            resolver: resolver.clone(),
+            language,
+            file_path,
+            content: &content,
+            selector_config: None,
+            params: request.params.clone(),
+            matrix_values: None,
+            capabilities: None,
+            semantic_provider: None,
+            metrics_context: Some(metrics_context.clone()),
+            llm_request_handler: None,
+            shared_state_context: None,
+            runtime_event_callback: None,
+            cancellation_flag: Some(cancellation_flag.clone()),
+            test_mode: false,
+            dry_run: true,
+            target_directory: &target_directory,
+            coverage: None,
+        });
+        let output = match tokio::time::timeout(remaining, execution).await {
+            Ok(output) => output,
+            Err(_) => {
+                cancellation_flag.store(true, Ordering::Relaxed);
+                response.timed_out = true;
+                break;
+            }
+        };
+
+        match output {
+            Ok(CodemodOutput { primary, secondary }) => {
+                let changes = std::iter::once((file_path.clone(), primary, Some(content))).chain(
+                    secondary
+                        .into_iter()
+                        .map(|change| (change.path, change.result, change.base_content)),
+                );
+                let mut changed = false;
+                for (path, result, base_content) in changes {
+                    let ExecutionResult::Modified(modified) = result else {
+                        continue;
+                    };
+                    let original = base_content
+                        .or_else(|| std::fs::read_to_string(&path).ok())
+                        .unwrap_or_default();
+                    if original == modified.content && modified.rename_to.is_none() {
+                        continue;
+                    }
+                    let relative = relative_path(&target_directory, &path);
+                    let diff = generate_unified_diff(
+                        Path::new(&relative),
+                        &original,
+                        &modified.content,
+                        &diff_config,
+                        DiffMetadata::default(),
+                    );
+                    response.changes.push(FileChangeDiff {
+                        path: relative,
+                        renamed_to: modified
+                            .rename_to
+                            .map(|rename_to| relative_path(&target_directory, &rename_to)),
+                        additions: diff.additions,
+                        deletions: diff.deletions,
+                        diff: diff.diff_text,
+                    });
+                    changed = true;
+                }
+                if changed {
+                    response.files_changed += 1;
+                }
+            }
+            Err(_) if cancellation_flag.load(Ordering::Relaxed) => {
+                response.timed_out = true;
+                break;
+            }
+            Err(error) => response.errors.push(FileError {
+                path: relative_path(&target_directory, file_path),
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    response.metrics = metrics_context.get_all().into_iter().collect();
+    Ok(response)
+}
+
+/// Sets a cancellation flag at a deadline from its own thread, since a
+/// script running on the current one may never yield. Stops when dropped.
+struct Watchdog {
+    _stop: mpsc::Sender<()>,
+}
+
+impl Watchdog {
+    fn start(deadline: Instant, flag: Arc<AtomicBool>) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(timeout) {
+                flag.store(true, Ordering::Relaxed);
+            }
+        });
+        Self { _stop: stop }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEMOD: &str = r#"
+import { useMetricAtom } from "codemod:metrics";
+
+const replaced = useMetricAtom("replaced");
+
+export default function transform(root) {
+  const rootNode = root.root();
+  const edits = rootNode
+    .findAll({ rule: { pattern: "console.log($ARG)" } })
+    .map((node) => {
+      replaced.increment();
+      return node.replace(`logger.log(${node.getMatch("ARG").text()})`);
+    });
+  return rootNode.commitEdits(edits);
+}
+"#;
+
+    fn request(codemod: &Path, path: &Path) -> DryRunCodemodRequest {
+        DryRunCodemodRequest {
+            codemod: codemod.to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            language: None,
+            include: vec![],
+            exclude: vec![],
+            params: None,
+            max_files: default_max_files(),
+            max_diff_lines: default_max_diff_lines(),
+            timeout_seconds: default_timeout(),
+        }
+    }
+
+    /// A package with a js-ast-grep step and a target with two files, one of
+    /// which the codemod changes.
+    fn fixture() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("package");
+        std::fs::create_dir_all(package.join("scripts")).unwrap();
+        std::fs::write(package.join("scripts/codemod.js"), CODEMOD).unwrap();
+        std::fs::write(
+            package.join("workflow.yaml"),
+            r#"version: "1"
+nodes:
+  - id: apply
+    name: Apply
+    type: automatic
+    steps:
+      - name: Transform
+        js-ast-grep:
+          js_file: scripts/codemod.js
+          language: javascript
+"#,
+        )
+        .unwrap();
+
+        let target = dir.path().join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(target.join("a.js"), "console.log(1);\nconsole.log(2);\n").unwrap();
+        std::fs::write(target.join("b.js"), "const b = 1;\n").unwrap();
+        (dir, package, target)
+    }
+
+    #[test]
+    fn packages_resolve_to_their_js_ast_grep_step() {
+        let (_dir, package, _target) = fixture();
+        let resolved = resolve_codemod(&package).unwrap();
+        assert_eq!(resolved.script, package.join("scripts/codemod.js"));
+        assert_eq!(resolved.language.as_deref(), Some("javascript"));
+
+        let file = resolve_codemod(&package.join("scripts/codemod.js")).unwrap();
+        assert_eq!(file.language, None);
+        assert!(resolve_codemod(&package.join("missing")).is_err());
+    }
+
+    #[tokio::test]
+    async fn dry_run_returns_diffs_and_metrics_without_writing() {
+        let (_dir, package, target) = fixture();
+        let response = dry_run(request(&package, &target)).await.unwrap();
+
+        assert_eq!(response.files_processed, 2);
+        assert_eq!(response.files_changed, 1);
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let change = &response.changes[0];
+        assert_eq!(change.path, "a.js");
+        assert_eq!((change.additions, change.deletions), (2, 2));
+        assert!(change.diff.contains("+logger.log(1);"));
+        assert_eq!(response.metrics["replaced"][0].count, 2);
+        assert_eq!(
+            std::fs::read_to_string(target.join("a.js")).unwrap(),
+            "console.log(1);\nconsole.log(2);\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn scripts_that_never_yield_are_interrupted_at_the_timeout() {
+        let (_dir, package, target) = fixture();
+        let script = package.join("scripts/spin.js");
+        std::fs::write(
+            &script,
+            "export default function transform(root) {\n  while (true) {}\n}\n",
+        )
+        .unwrap();
+
+        let started = Instant::now();
+        let response = dry_run(DryRunCodemodRequest {
+            language: Some("javascript".to_string()),
+            timeout_seconds: 1,
+            ..request(&script, &target)
+        })
+        .await
+        .unwrap();
+        assert!(response.timed_out);
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert!(started.elapsed() < Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn codemod_files_need_a_language() {
+        let (_dir, package, target) = fixture();
+        let script = package.join("scripts/codemod.js");
+        assert!(dry_run(request(&script, &target)).await.is_err());
+
+        let response = dry_run(DryRunCodemodRequest {
+            language: Some("javascript".to_string()),
+            max_files: 1,
+            ..request(&script, &target)
+        })
+        .await
+        .unwrap();
+        assert!(response.truncated);
+        assert_eq!(response.changes.len(), 1);
+    }
+}
@@ -18,8 +18,8 @@ use codemod_sandbox::{
     utils::project_discovery::find_tsconfig,
 };
 use testing_utils::{
-    map_execution_result, ExecutionRequest, ReporterType, TestOptions, TestRunner, TestSource,
-    TransformationResult, TransformationTestCase,
+    map_execution_result, render_report, ExecutionRequest, ReporterType, TestOptions, TestRunner,
+    TestSource, TransformationResult, TransformationTestCase,
 };
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -162,6 +162,14 @@ pub struct RunJssgTestRequest {
     /// - "loose": Loose AST comparison (ignores formatting and ordering of unordered nodes)
     #[serde(default)]
     pub strictness: Option<String>,
+    /// Also return the results as a report (default: none):
+    /// - "junit": JUnit XML
+    /// - "tap": TAP version 13
+    ///
+    /// Reports include each test's duration, the strictness level and the
+    /// diff for failed tests.
+    #[serde(default)]
+    pub reporter: Option<String>,
 }
 
 fn default_timeout() -> u64 {
@@ -179,11 +187,17 @@ pub struct TestResult {
 #[serde(tag = "success")]
 pub enum RunJssgTestResponse {
     #[serde(rename = "true")]
-    Success { message: String },
+    Success {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        report: Option<String>,
+    },
     #[serde(rename = "false")]
     Failure {
         message: String,
         test_results: Vec<TestResult>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        report: Option<String>,
     },
 }
 
@@ -246,6 +260,7 @@ impl JssgTestHandler {
             return Ok(RunJssgTestResponse::Failure {
                 message: format!("Codemod file not found: {}", request.codemod_file),
                 test_results: vec![],
+                report: None,
             });
         }
 
@@ -269,6 +284,12 @@ impl JssgTestHandler {
             .unwrap_or("strict")
             .parse()
             .map_err(|e: String| -> Box<dyn std::error::Error + Send + Sync> { e.into() })?;
+        let reporter: Option<ReporterType> = request
+            .reporter
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e: String| -> Box<dyn std::error::Error + Send + Sync> { e.into() })?;
 
         let test_options = TestOptions {
             filter: None,
@@ -286,6 +307,8 @@ impl JssgTestHandler {
             strictness,
             language: Some(language_str),
             expected_extension: None,
+            check_syntax: true,
+            check_idempotent: false,
         };
 
         // Create execution function
@@ -323,6 +346,7 @@ impl JssgTestHandler {
                         test_mode: true,
                         dry_run: false,
                         target_directory: &target_directory,
+                        coverage: None,
                     };
                     let CodemodOutput { primary, .. } =
                         execute_codemod_with_quickjs(options).await?;
@@ -356,10 +380,15 @@ impl JssgTestHandler {
         .map_err(|e| format!("Task join error: {e}"))?
         .map_err(|e| format!("Test execution error: {e}"))?;
 
+        let report = reporter
+            .as_ref()
+            .and_then(|reporter| render_report(reporter, &summary, strictness));
+
         // Convert summary to response
         if summary.is_success() {
             Ok(RunJssgTestResponse::Success {
                 message: format!("All {} tests passed! 🎉", summary.total),
+                report,
             })
         } else {
             // Create test results from detailed summary
@@ -384,7 +413,9 @@ impl JssgTestHandler {
                     .enumerate()
                     .map(|(index, detail)| TestResult {
                         success: detail.passed,
-                        message: if detail.passed {
+                        message: if detail.skipped.is_some() {
+                            format!("{} skipped", detail.name)
+                        } else if detail.passed {
                             format!("{} passed", detail.name)
                         } else {
                             detail
@@ -403,6 +434,7 @@ impl JssgTestHandler {
                     summary.failed, summary.total, summary.passed
                 ),
                 test_results,
+                report,
             })
         }
     }
@@ -456,4 +488,25 @@ mod tests {
             other => panic!("expected adhoc test case, got {other:?}"),
         }
     }
+
+    #[test]
+    fn response_includes_report_only_when_requested() {
+        let without_report = serde_json::to_value(RunJssgTestResponse::Success {
+            message: "All 1 tests passed! 🎉".to_string(),
+            report: None,
+        })
+        .expect("expected response to serialize");
+        assert_eq!(
+            without_report,
+            json!({ "success": "true", "message": "All 1 tests passed! 🎉" })
+        );
+
+        let with_report = serde_json::to_value(RunJssgTestResponse::Failure {
+            message: "1 of 1 tests failed. 0 tests passed.".to_string(),
+            test_results: vec![],
+            report: Some("TAP version 13".to_string()),
+        })
+        .expect("expected response to serialize");
+        assert_eq!(with_report["report"], "TAP version 13");
+    }
 }
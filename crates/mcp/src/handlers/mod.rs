@@ -1,9 +1,15 @@
 pub mod ast_dump;
+pub mod codemod_dry_run;
 pub mod jssg_test;
 pub mod node_types;
 pub mod package_validation;
+pub mod pattern_search;
+pub mod workflow_runs;
 
 pub use ast_dump::AstDumpHandler;
+pub use codemod_dry_run::CodemodDryRunHandler;
 pub use jssg_test::JssgTestHandler;
 pub use node_types::NodeTypesHandler;
 pub use package_validation::PackageValidationHandler;
+pub use pattern_search::PatternSearchHandler;
+pub use workflow_runs::WorkflowRunsHandler;
@@ -136,8 +136,8 @@ struct ValidationRequirements {
 }
 
 #[derive(Debug)]
-struct WorkflowPathResolution {
-    path: PathBuf,
+pub(crate) struct WorkflowPathResolution {
+    pub(crate) path: PathBuf,
     codemod_yaml_invalid: bool,
     workflow_path_invalid: bool,
 }
@@ -556,7 +556,7 @@ fn infer_validation_package_kind(
     ValidationPackageKind::Unknown
 }
 
-fn workflow_path_for_package(package_root: &Path) -> WorkflowPathResolution {
+pub(crate) fn workflow_path_for_package(package_root: &Path) -> WorkflowPathResolution {
     let manifest_path = package_root.join("codemod.yaml");
     let Some(content) = read_file_if_exists(&manifest_path) else {
         return WorkflowPathResolution {
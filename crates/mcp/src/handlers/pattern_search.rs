@@ -0,0 +1,449 @@
+use ast_grep_config::{RuleConfig, SerializableRuleConfig};
+use ast_grep_core::meta_var::{MetaVarEnv, MetaVariable};
+use ast_grep_core::tree_sitter::StrDoc;
+use ast_grep_core::{AstGrep, Matcher, Node, Pattern};
+use butterflow_core::execution::CodemodExecutionConfig;
+use codemod_sandbox::CodemodLang;
+use rmcp::{handler::server::wrapper::Parameters, model::*, schemars, tool, ErrorData as McpError};
+use serde::Serialize;
+use serde_yaml::Value as YamlValue;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+type SgDoc = StrDoc<CodemodLang>;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SearchPatternRequest {
+    /// File or directory to search
+    pub path: String,
+    /// The programming language (e.g., "typescript", "tsx", "python", "rust")
+    pub language: String,
+    /// ast-grep pattern such as `console.log($$$ARGS)`. Exactly one of `pattern`
+    /// and `rule` is required.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// ast-grep rule as YAML. Either a full rule config (`rule`, `constraints`,
+    /// `utils`) or just the rule object (`kind: call_expression`, `has: ...`).
+    /// The config's `language` is replaced by `language`.
+    #[serde(default)]
+    pub rule: Option<String>,
+    /// Globs of files to search (default: every file with the language's extensions)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs of files to skip
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Maximum number of matches to return (default: 100)
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Stop searching after this many seconds (default: 30)
+    #[serde(default = "default_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_max_results() -> usize {
+    100
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+/// Position in a file. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct MatchPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct PatternMatch {
+    /// Path relative to the searched path
+    pub file: String,
+    pub start: MatchPosition,
+    pub end: MatchPosition,
+    /// Source text of the matched node
+    pub text: String,
+    /// Text captured by each metavariable, keyed by name without the `$`
+    pub captures: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchPatternResponse {
+    pub matches: Vec<PatternMatch>,
+    pub files_searched: usize,
+    /// Files with at least one match, including matches past `max_results`
+    /// in the file that hit the limit
+    pub files_matched: usize,
+    /// `max_results` was reached; more matches may exist
+    pub truncated: bool,
+    /// `timeout_seconds` elapsed before every file was searched
+    pub timed_out: bool,
+}
+
+/// What to look for: a pattern, or a rule with its own constraints.
+enum Query {
+    Pattern(Pattern),
+    Rule(Box<RuleConfig<CodemodLang>>),
+}
+
+impl Query {
+    fn parse(request: &SearchPatternRequest, language: CodemodLang) -> Result<Self, String> {
+        match (&request.pattern, &request.rule) {
+            (Some(pattern), None) => Pattern::try_new(pattern, language)
+                .map(Query::Pattern)
+                .map_err(|error| format!("Invalid pattern: {error}")),
+            (None, Some(rule)) => parse_rule(rule, &request.language).map(Query::Rule),
+            _ => Err("Exactly one of `pattern` and `rule` is required".to_string()),
+        }
+    }
+
+    fn find_all<'r>(
+        &self,
+        root: &'r AstGrep<SgDoc>,
+        mut f: impl FnMut(Node<'r, SgDoc>, &MetaVarEnv<'r, SgDoc>) -> bool,
+    ) {
+        fn visit<'r, M: Matcher>(
+            root: &'r AstGrep<SgDoc>,
+            matcher: &M,
+            f: &mut impl FnMut(Node<'r, SgDoc>, &MetaVarEnv<'r, SgDoc>) -> bool,
+        ) {
+            for node_match in root.root().find_all(matcher) {
+                if !f(node_match.get_node().clone(), node_match.get_env()) {
+                    break;
+                }
+            }
+        }
+        match self {
+            Query::Pattern(pattern) => visit(root, pattern, &mut f),
+            Query::Rule(rule) => visit(root, &rule.matcher, &mut f),
+        }
+    }
+}
+
+/// Parse a YAML rule, wrapping a bare rule object in a config, defaulting
+/// `id` and setting `language`.
+fn parse_rule(yaml: &str, language: &str) -> Result<Box<RuleConfig<CodemodLang>>, String> {
+    let value: YamlValue =
+        serde_yaml::from_str(yaml).map_err(|error| format!("Invalid rule YAML: {error}"))?;
+    let YamlValue::Mapping(mut config) = value else {
+        return Err("Rule YAML must be a mapping".to_string());
+    };
+    if !config.contains_key("rule") {
+        config = [(YamlValue::from("rule"), YamlValue::Mapping(config))]
+            .into_iter()
+            .collect();
+    }
+    if !config.contains_key("id") {
+        config.insert(YamlValue::from("id"), YamlValue::from("search-pattern"));
+    }
+    // Files are parsed with the requested language, so the rule must use it too
+    config.insert(YamlValue::from("language"), YamlValue::from(language));
+
+    let serializable: SerializableRuleConfig<CodemodLang> =
+        serde_yaml::from_value(YamlValue::Mapping(config))
+            .map_err(|error| format!("Invalid rule: {error}"))?;
+    RuleConfig::try_from(serializable, &Default::default())
+        .map(Box::new)
+        .map_err(|error| format!("Invalid rule: {error}"))
+}
+
+#[derive(Clone)]
+pub struct PatternSearchHandler;
+
+impl PatternSearchHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    #[tool(
+        description = "Search files for an ast-grep pattern or YAML rule and return the matches with their metavariable captures"
+    )]
+    pub async fn search_pattern(
+        &self,
+        Parameters(request): Parameters<SearchPatternRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if !Path::new(&request.path).exists() {
+            return Err(McpError::invalid_params(
+                format!("Path not found: {}", request.path),
+                None,
+            ));
+        }
+
+        // Parsing every file is CPU bound, so keep it off the async workers
+        let response = tokio::task::spawn_blocking(move || {
+            let language: CodemodLang = request
+                .language
+                .parse()
+                .map_err(|error| format!("Unsupported language '{}': {error}", request.language))?;
+            let query = Query::parse(&request, language)?;
+            Ok::<_, String>(search(&request, language, &query))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {e}"), None))?
+        .map_err(|error| McpError::invalid_params(error, None))?;
+
+        let content = serde_json::to_string_pretty(&response).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize response: {e}"), None)
+        })?;
+        Ok(CallToolResult::success(vec![Content::text(content)]))
+    }
+}
+
+impl Default for PatternSearchHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn search(
+    request: &SearchPatternRequest,
+    language: CodemodLang,
+    query: &Query,
+) -> SearchPatternResponse {
+    let root = PathBuf::from(&request.path);
+    let deadline = Instant::now() + Duration::from_secs(request.timeout_seconds);
+    let mut files = target_files(&root, &request.language, &request.include, &request.exclude);
+    files.sort();
+
+    let mut response = SearchPatternResponse {
+        matches: Vec::new(),
+        files_searched: 0,
+        files_matched: 0,
+        truncated: false,
+        timed_out: false,
+    };
+    for file in files {
+        if response.truncated {
+            break;
+        }
+        if Instant::now() >= deadline {
+            response.timed_out = true;
+            break;
+        }
+        // Binary and non-UTF-8 files cannot be parsed
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        response.files_searched += 1;
+
+        let ast = AstGrep::new(content.as_str(), language);
+        let relative = relative_path(&root, &file);
+        let mut matched = false;
+        query.find_all(&ast, |node, env| {
+            matched = true;
+            if response.matches.len() >= request.max_results {
+                response.truncated = true;
+                return false;
+            }
+            response
+                .matches
+                .push(pattern_match(&relative, &content, &node, env));
+            true
+        });
+        if matched {
+            response.files_matched += 1;
+        }
+    }
+    response
+}
+
+/// Files under `root` the search or dry run covers. Without include globs,
+/// only files with the language's extensions are picked.
+pub(crate) fn target_files(
+    root: &Path,
+    language: &str,
+    include: &[String],
+    exclude: &[String],
+) -> Vec<PathBuf> {
+    CodemodExecutionConfig {
+        pre_run_callback: None,
+        progress_callback: Arc::new(None),
+        target_path: Some(root.to_path_buf()),
+        base_path: None,
+        include_globs: (!include.is_empty()).then(|| include.to_vec()),
+        explicit_files: None,
+        exclude_globs: (!exclude.is_empty()).then(|| exclude.to_vec()),
+        dry_run: true,
+        languages: Some(vec![language.to_string()]),
+        threads: None,
+        capabilities: None,
+        include_components: true,
+    }
+    .collect_files()
+}
+
+/// `path` relative to `root` with `/` separators. A file searched directly
+/// keeps its name.
+pub(crate) fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = if relative.as_os_str().is_empty() {
+        path.file_name().map(Path::new).unwrap_or(path)
+    } else {
+        relative
+    };
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+fn pattern_match(
+    file: &str,
+    content: &str,
+    node: &Node<SgDoc>,
+    env: &MetaVarEnv<SgDoc>,
+) -> PatternMatch {
+    let start = node.start_pos();
+    let end = node.end_pos();
+    PatternMatch {
+        file: file.to_string(),
+        start: MatchPosition {
+            line: start.line() + 1,
+            column: start.column(node) + 1,
+        },
+        end: MatchPosition {
+            line: end.line() + 1,
+            column: end.column(node) + 1,
+        },
+        text: node.text().to_string(),
+        captures: captures(content, env),
+    }
+}
+
+fn captures(content: &str, env: &MetaVarEnv<SgDoc>) -> BTreeMap<String, String> {
+    let mut captures = BTreeMap::new();
+    for variable in env.get_matched_variables() {
+        match variable {
+            MetaVariable::Capture(name, _) => {
+                if let Some(node) = env.get_match(&name) {
+                    captures.insert(name, node.text().to_string());
+                }
+            }
+            MetaVariable::MultiCapture(name) => {
+                let nodes = env.get_multiple_matches(&name);
+                // The source between the first and last node keeps the separators
+                let text = match (nodes.first(), nodes.last()) {
+                    (Some(first), Some(last)) => content
+                        .get(first.range().start..last.range().end)
+                        .unwrap_or_default()
+                        .to_string(),
+                    _ => String::new(),
+                };
+                captures.insert(name, text);
+            }
+            _ => {}
+        }
+    }
+    captures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &Path) -> SearchPatternRequest {
+        SearchPatternRequest {
+            path: path.to_string_lossy().to_string(),
+            language: "typescript".to_string(),
+            pattern: None,
+            rule: None,
+            include: vec![],
+            exclude: vec![],
+            max_results: default_max_results(),
+            timeout_seconds: default_timeout(),
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/vendor")).unwrap();
+        std::fs::write(
+            dir.path().join("src/a.ts"),
+            "console.log(1, 2);\nconst x = 1;\nconsole.log(x);\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/vendor/b.ts"),
+            "console.log('vendor');\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "console.log(1)\n").unwrap();
+        dir
+    }
+
+    fn run(request: SearchPatternRequest) -> SearchPatternResponse {
+        let language = request.language.parse().unwrap();
+        let query = Query::parse(&request, language).unwrap();
+        search(&request, language, &query)
+    }
+
+    #[test]
+    fn pattern_matches_report_positions_and_captures() {
+        let dir = fixture();
+        let response = run(SearchPatternRequest {
+            pattern: Some("console.log($$$ARGS)".to_string()),
+            exclude: vec!["**/vendor/**".to_string()],
+            ..request(dir.path())
+        });
+
+        assert_eq!(response.files_searched, 1);
+        assert_eq!(response.matches.len(), 2);
+        let first = &response.matches[0];
+        assert_eq!(first.file, "src/a.ts");
+        assert_eq!(first.start, MatchPosition { line: 1, column: 1 });
+        assert_eq!(
+            first.end,
+            MatchPosition {
+                line: 1,
+                column: 18
+            }
+        );
+        assert_eq!(first.captures["ARGS"], "1, 2");
+        assert_eq!(response.matches[1].start.line, 3);
+        assert!(!response.truncated);
+    }
+
+    #[test]
+    fn bare_rules_get_an_id_and_the_request_language() {
+        let dir = fixture();
+        let response = run(SearchPatternRequest {
+            rule: Some(
+                "pattern: console.log($ARG)\nhas:\n  kind: identifier\n  stopBy: end\n".to_string(),
+            ),
+            ..request(dir.path())
+        });
+
+        let texts: Vec<_> = response.matches.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["console.log(x)"]);
+        assert_eq!(response.matches[0].captures["ARG"], "x");
+    }
+
+    #[test]
+    fn results_are_capped_at_max_results() {
+        let dir = fixture();
+        let response = run(SearchPatternRequest {
+            pattern: Some("console.log($$$ARGS)".to_string()),
+            max_results: 1,
+            ..request(dir.path())
+        });
+
+        assert_eq!(response.matches.len(), 1);
+        assert!(response.truncated);
+    }
+
+    #[test]
+    fn queries_need_exactly_one_of_pattern_and_rule() {
+        let dir = fixture();
+        let language = "typescript".parse().unwrap();
+        assert!(Query::parse(&request(dir.path()), language).is_err());
+        assert!(Query::parse(
+            &SearchPatternRequest {
+                pattern: Some("a".to_string()),
+                rule: Some("kind: identifier".to_string()),
+                ..request(dir.path())
+            },
+            language
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,1102 @@
+use butterflow_core::config::{
+    SkillInstallSettings, WorkflowExecutionSettings, WorkflowInteractionSettings, WorkflowRunConfig,
+};
+use butterflow_core::engine::Engine;
+use butterflow_core::utils::parse_workflow_file;
+use butterflow_core::workflow_runtime::{
+    WorkflowCommand, WorkflowEvent, WorkflowSession, WorkflowSessionHandle, WorkflowSnapshot,
+};
+use butterflow_models::{Task, TaskStatus, WorkflowRun, WorkflowStatus};
+use butterflow_state::cloud_adapter::CloudStateAdapter;
+use chrono::{DateTime, Utc};
+use codemod_llrt_capabilities::types::LlrtSupportedModules;
+use rmcp::{
+    handler::server::wrapper::Parameters, model::*, schemars, tool, ErrorData as McpError, Peer,
+    RoleServer,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+use uuid::Uuid;
+
+use super::package_validation::workflow_path_for_package;
+
+const EVENTS_URI_PREFIX: &str = "workflow-run://";
+const EVENTS_URI_SUFFIX: &str = "/events";
+/// Events kept per run; older ones are dropped first.
+const MAX_BUFFERED_EVENTS: usize = 1000;
+/// Finished runs kept tracked, so their events stay readable; the ones that
+/// finished first are dropped first.
+const MAX_FINISHED_RUNS: usize = 20;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct StartWorkflowRunRequest {
+    /// Path to a workflow file, or to a codemod package directory whose
+    /// `codemod.yaml` names the workflow
+    pub workflow: String,
+    /// Directory to run the workflow against (default: the current directory)
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Workflow parameters
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    /// Report changes without writing them (default: false, or true on
+    /// servers restricted to allowed directories). The run's summary
+    /// reports the effective value.
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+    /// Runtime modules the run may use without approval, such as `fs`,
+    /// `fetch` or `child_process`. Refused by servers restricted to allowed
+    /// directories.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ListWorkflowRunsRequest {
+    /// Maximum number of runs to return, newest first (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetWorkflowRunRequest {
+    /// Workflow run ID
+    pub id: String,
+    /// Only return these tasks (default: every task)
+    #[serde(default)]
+    pub task_ids: Vec<String>,
+    /// Maximum number of log lines returned per task, latest first
+    /// (default: 50)
+    #[serde(default = "default_max_log_lines")]
+    pub max_log_lines: usize,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct TriggerWorkflowTasksRequest {
+    /// Workflow run ID
+    pub id: String,
+    /// Tasks to trigger; exactly one of task_ids and all is required
+    #[serde(default)]
+    pub task_ids: Vec<String>,
+    /// Trigger every task awaiting a manual trigger
+    #[serde(default)]
+    pub all: bool,
+    /// Report changes without writing them (default: as for
+    /// `start_workflow_run`). Only used for runs this server did not start;
+    /// other runs keep the setting they were started with.
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CancelWorkflowRunRequest {
+    /// Workflow run ID
+    pub id: String,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+fn default_max_log_lines() -> usize {
+    50
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowRunSummary {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub status: WorkflowStatus,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<PathBuf>,
+    /// Subscribable event stream; only runs this server started or
+    /// triggered have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events_uri: Option<String>,
+    /// Whether the run reports changes instead of writing them; only known
+    /// for runs this server started or triggered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskSummary {
+    pub id: Uuid,
+    pub node_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    pub status: TaskStatus,
+    /// Matrix tasks are grouped under a master task
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_master: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_task_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matrix_values: Option<HashMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    /// Earlier log lines left out by `max_log_lines`
+    #[serde(skip_serializing_if = "is_zero")]
+    pub logs_omitted: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowRunDetails {
+    pub run: WorkflowRunSummary,
+    pub tasks: Vec<TaskSummary>,
+    /// Tasks waiting for `trigger_workflow_tasks`
+    pub awaiting_trigger: Vec<Uuid>,
+}
+
+/// Serialized events of one run, numbered so clients can read only what is
+/// new.
+#[derive(Default)]
+struct EventLog {
+    next_seq: u64,
+    events: VecDeque<Value>,
+    /// When the run last reached a final status
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl EventLog {
+    fn push(&mut self, mut event: Value) {
+        event["seq"] = json!(self.next_seq);
+        self.next_seq += 1;
+        self.events.push_back(event);
+        if self.events.len() > MAX_BUFFERED_EVENTS {
+            self.events.pop_front();
+        }
+    }
+}
+
+/// A run this server started or triggered. Holding the session keeps its
+/// events flowing into `events`.
+struct TrackedRun {
+    session: WorkflowSession,
+    dry_run: bool,
+    events: Arc<Mutex<EventLog>>,
+    pump: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TrackedRun {
+    fn drop(&mut self) {
+        self.pump.abort();
+    }
+}
+
+/// Resource subscribers by run, keyed by client session.
+type Subscribers = Arc<Mutex<HashMap<Uuid, HashMap<String, Peer<RoleServer>>>>>;
+
+#[derive(Clone, Default)]
+pub struct WorkflowRunsHandler {
+    runs: Arc<Mutex<HashMap<Uuid, TrackedRun>>>,
+    subscribers: Subscribers,
+}
+
+impl WorkflowRunsHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[tool(
+        description = "Start a workflow run from a workflow file or codemod package and return its tasks"
+    )]
+    pub async fn start_workflow_run(
+        &self,
+        Parameters(request): Parameters<StartWorkflowRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let (workflow_file_path, bundle_path) = resolve_workflow(Path::new(&request.workflow))
+            .map_err(|error| McpError::invalid_params(error, None))?;
+        let workflow = parse_workflow_file(&workflow_file_path).map_err(|error| {
+            McpError::invalid_params(
+                format!(
+                    "Failed to parse workflow {}: {error}",
+                    workflow_file_path.display()
+                ),
+                None,
+            )
+        })?;
+        let target = request.target.as_deref().unwrap_or(".");
+        let target_path = Path::new(target).canonicalize().map_err(|error| {
+            McpError::invalid_params(format!("Invalid target '{target}': {error}"), None)
+        })?;
+        let capabilities = request
+            .capabilities
+            .iter()
+            .map(|capability| capability.parse::<LlrtSupportedModules>())
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(|error| McpError::invalid_params(error, None))?;
+        let capabilities = (!capabilities.is_empty()).then_some(capabilities);
+
+        let dry_run = request.dry_run.unwrap_or(false);
+        let engine = new_engine(run_config(
+            workflow_file_path,
+            bundle_path.clone(),
+            target_path,
+            request.params.clone(),
+            dry_run,
+            capabilities.clone(),
+        ));
+        let session = WorkflowSession::start_workflow(
+            engine,
+            workflow,
+            request.params,
+            Some(bundle_path),
+            capabilities.as_ref(),
+        )
+        .await
+        .map_err(|error| {
+            McpError::internal_error(format!("Failed to start workflow: {error}"), None)
+        })?;
+        let handle = self.track(session, dry_run).await?;
+        self.run_details(&handle, &[], default_max_log_lines())
+            .await
+    }
+
+    #[tool(description = "List recent workflow runs, newest first")]
+    pub async fn list_workflow_runs(
+        &self,
+        parameters: Parameters<ListWorkflowRunsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let Parameters(request) = parameters;
+        let runs = list_runs(request.limit).await?;
+        json_result(
+            &runs
+                .iter()
+                .map(|run| self.summarize_run(run))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// List the recent runs `keep` accepts, newest first. Every persisted
+    /// run is loaded, since any number of them may be filtered out.
+    pub async fn list_workflow_runs_where(
+        &self,
+        Parameters(request): Parameters<ListWorkflowRunsRequest>,
+        keep: impl Fn(&WorkflowRun) -> bool,
+    ) -> Result<CallToolResult, McpError> {
+        let runs = list_runs(usize::MAX).await?;
+        let runs = runs
+            .iter()
+            .filter(|run| keep(run))
+            .take(request.limit)
+            .map(|run| self.summarize_run(run))
+            .collect::<Vec<_>>();
+        json_result(&runs)
+    }
+
+    #[tool(description = "Get a workflow run's status and its tasks' statuses and logs")]
+    pub async fn get_workflow_run(
+        &self,
+        Parameters(request): Parameters<GetWorkflowRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let workflow_run_id = parse_id(&request.id)?;
+        let task_ids = request
+            .task_ids
+            .iter()
+            .map(|id| parse_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let snapshot = match self.tracked_handle(workflow_run_id) {
+            Some(handle) => handle.load_snapshot().await,
+            None => load_snapshot(&new_engine(WorkflowRunConfig::default()), workflow_run_id)
+                .await
+                .map_err(anyhow::Error::from),
+        }
+        .map_err(|error| run_not_found(workflow_run_id, error))?;
+        json_result(&self.details(snapshot, &task_ids, request.max_log_lines))
+    }
+
+    #[tool(description = "Trigger manual tasks of a workflow run")]
+    pub async fn trigger_workflow_tasks(
+        &self,
+        Parameters(request): Parameters<TriggerWorkflowTasksRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let workflow_run_id = parse_id(&request.id)?;
+        let task_ids = request
+            .task_ids
+            .iter()
+            .map(|id| parse_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if request.all == !task_ids.is_empty() {
+            return Err(McpError::invalid_params(
+                "Pass either task_ids or all: true",
+                None,
+            ));
+        }
+
+        let handle = match self.tracked_handle(workflow_run_id) {
+            Some(handle) => handle,
+            None => {
+                self.attach(workflow_run_id, request.dry_run.unwrap_or(false))
+                    .await?
+            }
+        };
+        let command = if request.all {
+            WorkflowCommand::TriggerAll
+        } else {
+            WorkflowCommand::TriggerTasks { task_ids }
+        };
+        handle.send(command).await.map_err(|error| {
+            McpError::invalid_params(format!("Failed to trigger tasks: {error}"), None)
+        })?;
+        self.run_details(&handle, &[], default_max_log_lines())
+            .await
+    }
+
+    #[tool(description = "Cancel a workflow run")]
+    pub async fn cancel_workflow_run(
+        &self,
+        Parameters(request): Parameters<CancelWorkflowRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let workflow_run_id = parse_id(&request.id)?;
+        let snapshot = match self.tracked_handle(workflow_run_id) {
+            Some(handle) => {
+                handle
+                    .send(WorkflowCommand::CancelWorkflow)
+                    .await
+                    .map_err(|error| run_not_found(workflow_run_id, error))?;
+                handle.load_snapshot().await
+            }
+            None => {
+                let engine = new_engine(WorkflowRunConfig::default());
+                engine
+                    .cancel_workflow(workflow_run_id)
+                    .await
+                    .map_err(|error| run_not_found(workflow_run_id, error))?;
+                load_snapshot(&engine, workflow_run_id)
+                    .await
+                    .map_err(anyhow::Error::from)
+            }
+        }
+        .map_err(|error| run_not_found(workflow_run_id, error))?;
+        json_result(&self.details(snapshot, &[], default_max_log_lines()))
+    }
+
+    /// Directories a run reads code from and runs code in: the persisted
+    /// run's bundle and target.
+    pub async fn run_paths(&self, id: &str) -> Result<Vec<PathBuf>, McpError> {
+        let workflow_run_id = parse_id(id)?;
+        let workflow_run = new_engine(WorkflowRunConfig::default())
+            .get_workflow_run(workflow_run_id)
+            .await
+            .map_err(|error| run_not_found(workflow_run_id, error))?;
+        Ok(Self::paths_of(&workflow_run))
+    }
+
+    /// The bundle and target directories of `workflow_run`.
+    pub fn paths_of(workflow_run: &WorkflowRun) -> Vec<PathBuf> {
+        workflow_run
+            .bundle_path
+            .iter()
+            .chain(&workflow_run.target_path)
+            .cloned()
+            .collect()
+    }
+
+    /// Event stream resources of the tracked runs, as `(uri, name)`.
+    pub fn event_resources(&self) -> Vec<(String, String)> {
+        let mut ids = self
+            .runs
+            .lock()
+            .unwrap()
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| (events_uri(id), format!("workflow-run-{id}-events")))
+            .collect()
+    }
+
+    pub fn is_events_uri(uri: &str) -> bool {
+        uri.starts_with(EVENTS_URI_PREFIX)
+    }
+
+    /// Read `workflow-run://<id>/events`, or only the events numbered `after`
+    /// and up with `?after=<seq>`.
+    pub fn read_events(&self, uri: &str) -> Result<String, McpError> {
+        let (workflow_run_id, after) = parse_events_uri(uri).ok_or_else(|| not_found(uri))?;
+        let events = self
+            .runs
+            .lock()
+            .unwrap()
+            .get(&workflow_run_id)
+            .map(|run| Arc::clone(&run.events))
+            .ok_or_else(|| not_found(uri))?;
+        let events = events.lock().unwrap();
+        let content = json!({
+            "workflow_run_id": workflow_run_id,
+            "next_seq": events.next_seq,
+            "events": events
+                .events
+                .iter()
+                .filter(|event| event["seq"].as_u64().is_some_and(|seq| seq >= after))
+                .collect::<Vec<_>>(),
+        });
+        serde_json::to_string_pretty(&content).map_err(|error| {
+            McpError::internal_error(format!("Failed to serialize events: {error}"), None)
+        })
+    }
+
+    /// Notify `peer` whenever the run's event stream grows. `subscriber`
+    /// identifies the client session so it can unsubscribe again.
+    pub fn subscribe(
+        &self,
+        uri: &str,
+        subscriber: String,
+        peer: Peer<RoleServer>,
+    ) -> Result<(), McpError> {
+        let (workflow_run_id, _) = parse_events_uri(uri).ok_or_else(|| not_found(uri))?;
+        if !self.runs.lock().unwrap().contains_key(&workflow_run_id) {
+            return Err(not_found(uri));
+        }
+        self.subscribers
+            .lock()
+            .unwrap()
+            .entry(workflow_run_id)
+            .or_default()
+            .insert(subscriber, peer);
+        Ok(())
+    }
+
+    pub fn unsubscribe(&self, uri: &str, subscriber: &str) {
+        let Some((workflow_run_id, _)) = parse_events_uri(uri) else {
+            return;
+        };
+        if let Some(peers) = self.subscribers.lock().unwrap().get_mut(&workflow_run_id) {
+            peers.remove(subscriber);
+        }
+    }
+
+    fn tracked_handle(&self, workflow_run_id: Uuid) -> Option<WorkflowSessionHandle> {
+        self.runs
+            .lock()
+            .unwrap()
+            .get(&workflow_run_id)
+            .map(|run| run.session.handle())
+    }
+
+    /// Attach to a run started elsewhere, with an engine configured from
+    /// the persisted run.
+    async fn attach(
+        &self,
+        workflow_run_id: Uuid,
+        dry_run: bool,
+    ) -> Result<WorkflowSessionHandle, McpError> {
+        let workflow_run = new_engine(WorkflowRunConfig::default())
+            .get_workflow_run(workflow_run_id)
+            .await
+            .map_err(|error| run_not_found(workflow_run_id, error))?;
+        let bundle_path = workflow_run.bundle_path.unwrap_or_default();
+        let engine = new_engine(run_config(
+            bundle_path.clone(),
+            bundle_path,
+            workflow_run
+                .target_path
+                .unwrap_or_else(|| PathBuf::from(".")),
+            workflow_run.params,
+            dry_run,
+            workflow_run.capabilities,
+        ));
+        self.track(WorkflowSession::attach(engine, workflow_run_id), dry_run)
+            .await
+    }
+
+    /// Keep `session` alive and record its events, starting from a snapshot
+    /// of the run since earlier events were not observed. Stops tracking the
+    /// finished runs beyond `MAX_FINISHED_RUNS`.
+    async fn track(
+        &self,
+        session: WorkflowSession,
+        dry_run: bool,
+    ) -> Result<WorkflowSessionHandle, McpError> {
+        let handle = session.handle();
+        let workflow_run_id = handle.workflow_run_id();
+        let receiver = handle.subscribe();
+        let snapshot = handle
+            .load_snapshot()
+            .await
+            .map_err(|error| run_not_found(workflow_run_id, error))?;
+
+        let mut log = EventLog::default();
+        if is_finished(snapshot.workflow_run.status) {
+            log.finished_at = Some(snapshot.workflow_run.ended_at.unwrap_or_else(Utc::now));
+        }
+        log.push(json!({
+            "type": "workflow_started",
+            "status": snapshot.workflow_run.status,
+            "at": snapshot.workflow_run.started_at,
+        }));
+        for task in &snapshot.tasks {
+            log.push(task_event("task_created", task, Utc::now()));
+        }
+        let events = Arc::new(Mutex::new(log));
+        let pump = tokio::spawn(pump_events(
+            handle.clone(),
+            receiver,
+            Arc::clone(&events),
+            Arc::clone(&self.subscribers),
+        ));
+
+        let evicted = {
+            let mut runs = self.runs.lock().unwrap();
+            // A concurrent call may have attached first; keep that session
+            if let Some(existing) = runs.get(&workflow_run_id) {
+                pump.abort();
+                return Ok(existing.session.handle());
+            }
+            runs.insert(
+                workflow_run_id,
+                TrackedRun {
+                    session,
+                    dry_run,
+                    events,
+                    pump,
+                },
+            );
+            let finished = runs
+                .iter()
+                .filter(|(id, _)| **id != workflow_run_id)
+                .filter_map(|(id, run)| Some((run.events.lock().unwrap().finished_at?, *id)))
+                .collect();
+            runs_to_evict(finished)
+                .into_iter()
+                .filter_map(|id| runs.remove(&id).map(|run| (id, run)))
+                .collect::<Vec<_>>()
+        };
+        let mut subscribers = self.subscribers.lock().unwrap();
+        for (id, _) in &evicted {
+            subscribers.remove(id);
+        }
+        Ok(handle)
+    }
+
+    async fn run_details(
+        &self,
+        handle: &WorkflowSessionHandle,
+        task_ids: &[Uuid],
+        max_log_lines: usize,
+    ) -> Result<CallToolResult, McpError> {
+        let snapshot = handle
+            .load_snapshot()
+            .await
+            .map_err(|error| run_not_found(handle.workflow_run_id(), error))?;
+        json_result(&self.details(snapshot, task_ids, max_log_lines))
+    }
+
+    fn details(
+        &self,
+        snapshot: WorkflowSnapshot,
+        task_ids: &[Uuid],
+        max_log_lines: usize,
+    ) -> WorkflowRunDetails {
+        let WorkflowSnapshot {
+            workflow_run,
+            tasks,
+        } = snapshot;
+        let awaiting_trigger = tasks
+            .iter()
+            .filter(|task| !task.is_master && task.status == TaskStatus::AwaitingTrigger)
+            .map(|task| task.id)
+            .collect();
+        let tasks = tasks
+            .into_iter()
+            .filter(|task| task_ids.is_empty() || task_ids.contains(&task.id))
+            .map(|task| summarize_task(&workflow_run, task, max_log_lines))
+            .collect();
+        WorkflowRunDetails {
+            run: self.summarize_run(&workflow_run),
+            tasks,
+            awaiting_trigger,
+        }
+    }
+
+    fn summarize_run(&self, workflow_run: &WorkflowRun) -> WorkflowRunSummary {
+        let dry_run = self
+            .runs
+            .lock()
+            .unwrap()
+            .get(&workflow_run.id)
+            .map(|run| run.dry_run);
+        WorkflowRunSummary {
+            id: workflow_run.id,
+            name: workflow_run.name.clone().or_else(|| {
+                workflow_run
+                    .workflow
+                    .nodes
+                    .first()
+                    .map(|node| node.name.clone())
+            }),
+            status: workflow_run.status,
+            started_at: workflow_run.started_at,
+            ended_at: workflow_run.ended_at,
+            target_path: workflow_run.target_path.clone(),
+            events_uri: dry_run.map(|_| events_uri(workflow_run.id)),
+            dry_run,
+        }
+    }
+}
+
+/// The `limit` most recent persisted runs, newest first.
+async fn list_runs(limit: usize) -> Result<Vec<WorkflowRun>, McpError> {
+    new_engine(WorkflowRunConfig::default())
+        .list_workflow_runs(limit)
+        .await
+        .map_err(|error| {
+            McpError::internal_error(format!("Failed to list workflow runs: {error}"), None)
+        })
+}
+
+/// Engine over the same state backend as the `codemod workflow` commands.
+fn new_engine(config: WorkflowRunConfig) -> Engine {
+    if let (Ok(backend), Ok(endpoint)) = (
+        std::env::var("BUTTERFLOW_STATE_BACKEND"),
+        std::env::var("BUTTERFLOW_API_ENDPOINT"),
+    ) {
+        if backend == "cloud" {
+            let auth_token = std::env::var("BUTTERFLOW_API_AUTH_TOKEN").unwrap_or_default();
+            let state_adapter = Box::new(CloudStateAdapter::new(endpoint, auth_token));
+            return Engine::with_state_adapter(state_adapter, config);
+        }
+    }
+    Engine::with_workflow_run_config(config)
+}
+
+fn run_config(
+    workflow_file_path: PathBuf,
+    bundle_path: PathBuf,
+    target_path: PathBuf,
+    params: HashMap<String, Value>,
+    dry_run: bool,
+    capabilities: Option<HashSet<LlrtSupportedModules>>,
+) -> WorkflowRunConfig {
+    WorkflowRunConfig {
+        execution: WorkflowExecutionSettings {
+            workflow_file_path,
+            bundle_path,
+            target_path,
+            params,
+            dry_run,
+            capabilities,
+            ..Default::default()
+        },
+        interaction: WorkflowInteractionSettings {
+            no_interactive: true,
+            ..Default::default()
+        },
+        skill_install: SkillInstallSettings {
+            skip_install_skill_steps: true,
+            install_skill_executor: None,
+        },
+        ..WorkflowRunConfig::default()
+    }
+}
+
+/// Resolve a workflow file as is, and a package directory through its
+/// `codemod.yaml`. Returns the workflow file and the bundle directory.
+fn resolve_workflow(workflow: &Path) -> Result<(PathBuf, PathBuf), String> {
+    let canonicalize = |path: &Path| {
+        path.canonicalize()
+            .map_err(|error| format!("Workflow not found: {}: {error}", path.display()))
+    };
+    if workflow.is_dir() {
+        let bundle_path = canonicalize(workflow)?;
+        let workflow_file_path = canonicalize(&workflow_path_for_package(&bundle_path).path)?;
+        Ok((workflow_file_path, bundle_path))
+    } else {
+        let workflow_file_path = canonicalize(workflow)?;
+        let bundle_path = workflow_file_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Ok((workflow_file_path, bundle_path))
+    }
+}
+
+async fn load_snapshot(
+    engine: &Engine,
+    workflow_run_id: Uuid,
+) -> butterflow_models::Result<WorkflowSnapshot> {
+    Ok(WorkflowSnapshot {
+        workflow_run: engine.get_workflow_run(workflow_run_id).await?,
+        tasks: engine.get_tasks(workflow_run_id).await?,
+    })
+}
+
+fn summarize_task(workflow_run: &WorkflowRun, task: Task, max_log_lines: usize) -> TaskSummary {
+    let logs_omitted = task.logs.len().saturating_sub(max_log_lines);
+    TaskSummary {
+        id: task.id,
+        node_name: workflow_run
+            .workflow
+            .nodes
+            .iter()
+            .find(|node| node.id == task.node_id)
+            .map(|node| node.name.clone()),
+        node_id: task.node_id,
+        status: task.status,
+        is_master: task.is_master,
+        master_task_id: task.master_task_id,
+        matrix_values: task.matrix_values,
+        started_at: task.started_at,
+        ended_at: task.ended_at,
+        error: task.error,
+        logs: task.logs.into_iter().skip(logs_omitted).collect(),
+        logs_omitted,
+    }
+}
+
+/// Record a session's events and tell subscribers, once per batch of events
+/// that arrived together.
+async fn pump_events(
+    handle: WorkflowSessionHandle,
+    mut receiver: broadcast::Receiver<WorkflowEvent>,
+    events: Arc<Mutex<EventLog>>,
+    subscribers: Subscribers,
+) {
+    let workflow_run_id = handle.workflow_run_id();
+    loop {
+        match receiver.recv().await {
+            Ok(event) => record_event(&handle, &events, &event),
+            Err(RecvError::Lagged(skipped)) => record_skipped(&events, skipped),
+            Err(RecvError::Closed) => return,
+        }
+        loop {
+            match receiver.try_recv() {
+                Ok(event) => record_event(&handle, &events, &event),
+                Err(TryRecvError::Lagged(skipped)) => record_skipped(&events, skipped),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        notify_subscribers(&subscribers, workflow_run_id).await;
+    }
+}
+
+fn record_event(handle: &WorkflowSessionHandle, events: &Mutex<EventLog>, event: &WorkflowEvent) {
+    // Nobody can answer prompts over MCP, so runs behave as with
+    // `--no-interactive`: anything that needs approval is declined
+    if let Some(command) = decline(event) {
+        if let Err(error) = handle.dispatch(command) {
+            tracing::warn!("Failed to decline workflow prompt: {error}");
+        }
+    }
+    let mut events = events.lock().unwrap();
+    if let WorkflowEvent::WorkflowStatusChanged { status, at, .. } = event {
+        events.finished_at = is_finished(*status).then_some(*at);
+    }
+    events.push(event_json(event));
+}
+
+fn is_finished(status: WorkflowStatus) -> bool {
+    matches!(
+        status,
+        WorkflowStatus::Completed | WorkflowStatus::Failed | WorkflowStatus::Canceled
+    )
+}
+
+/// The finished runs beyond `MAX_FINISHED_RUNS`, given when each finished.
+fn runs_to_evict(mut finished: Vec<(DateTime<Utc>, Uuid)>) -> Vec<Uuid> {
+    finished.sort();
+    let excess = finished.len().saturating_sub(MAX_FINISHED_RUNS);
+    finished
+        .into_iter()
+        .take(excess)
+        .map(|(_, id)| id)
+        .collect()
+}
+
+fn record_skipped(events: &Mutex<EventLog>, skipped: u64) {
+    events
+        .lock()
+        .unwrap()
+        .push(json!({ "type": "events_skipped", "count": skipped }));
+}
+
+async fn notify_subscribers(subscribers: &Subscribers, workflow_run_id: Uuid) {
+    let peers = subscribers
+        .lock()
+        .unwrap()
+        .get(&workflow_run_id)
+        .map(|peers| {
+            peers
+                .iter()
+                .map(|(subscriber, peer)| (subscriber.clone(), peer.clone()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for (subscriber, peer) in peers {
+        let notified = peer
+            .notify_resource_updated(ResourceUpdatedNotificationParam {
+                uri: events_uri(workflow_run_id),
+            })
+            .await;
+        if notified.is_err() {
+            // The client is gone
+            if let Some(peers) = subscribers.lock().unwrap().get_mut(&workflow_run_id) {
+                peers.remove(&subscriber);
+            }
+        }
+    }
+}
+
+fn decline(event: &WorkflowEvent) -> Option<WorkflowCommand> {
+    match event {
+        WorkflowEvent::ShellApprovalRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondShellApproval {
+                request_id: *request_id,
+                approved: false,
+            })
+        }
+        WorkflowEvent::PullRequestApprovalRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondPullRequestApproval {
+                request_id: *request_id,
+                approved: false,
+            })
+        }
+        WorkflowEvent::CapabilitiesApprovalRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondCapabilitiesApproval {
+                request_id: *request_id,
+                approved: false,
+            })
+        }
+        WorkflowEvent::DirtyGitApprovalRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondDirtyGitApproval {
+                request_id: *request_id,
+                approved: false,
+            })
+        }
+        WorkflowEvent::AgentSelectionRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondAgentSelection {
+                request_id: *request_id,
+                selection: None,
+            })
+        }
+        WorkflowEvent::SelectionRequested { request_id, .. } => {
+            Some(WorkflowCommand::RespondSelection {
+                request_id: *request_id,
+                selection: None,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn event_json(event: &WorkflowEvent) -> Value {
+    let declined = |kind: &str, request_id: &Uuid, at: &DateTime<Utc>| json!({ "type": "prompt_declined", "kind": kind, "request_id": request_id, "at": at });
+    match event {
+        WorkflowEvent::WorkflowStarted { workflow_run, at } => {
+            json!({ "type": "workflow_started", "status": workflow_run.status, "at": at })
+        }
+        WorkflowEvent::WorkflowStatusChanged { status, at, .. } => {
+            json!({ "type": "workflow_status_changed", "status": status, "at": at })
+        }
+        WorkflowEvent::TaskCreated { task, at } => task_event("task_created", task, *at),
+        WorkflowEvent::TaskUpdated { task, at } => task_event("task_updated", task, *at),
+        WorkflowEvent::TaskLogAppended {
+            task_id, line, at, ..
+        } => json!({ "type": "task_log", "task_id": task_id, "line": line, "at": at }),
+        WorkflowEvent::TaskProgressUpdated {
+            task_id,
+            processed_files,
+            total_files,
+            current_file,
+            at,
+            ..
+        } => json!({
+            "type": "task_progress",
+            "task_id": task_id,
+            "processed_files": processed_files,
+            "total_files": total_files,
+            "current_file": current_file,
+            "at": at,
+        }),
+        WorkflowEvent::ShellApprovalRequested { request_id, at, .. } => {
+            declined("shell_command", request_id, at)
+        }
+        WorkflowEvent::PullRequestApprovalRequested { request_id, at, .. } => {
+            declined("pull_request", request_id, at)
+        }
+        WorkflowEvent::CapabilitiesApprovalRequested {
+            request_id,
+            modules,
+            at,
+        } => json!({
+            "type": "prompt_declined",
+            "kind": "capabilities",
+            "request_id": request_id,
+            "modules": modules,
+            "at": at,
+        }),
+        WorkflowEvent::DirtyGitApprovalRequested { request_id, at, .. } => {
+            declined("dirty_git", request_id, at)
+        }
+        WorkflowEvent::AgentSelectionRequested { request_id, at, .. } => {
+            declined("agent_selection", request_id, at)
+        }
+        WorkflowEvent::SelectionRequested { request_id, at, .. } => {
+            declined("selection", request_id, at)
+        }
+    }
+}
+
+fn task_event(kind: &str, task: &Task, at: DateTime<Utc>) -> Value {
+    json!({
+        "type": kind,
+        "task_id": task.id,
+        "node_id": task.node_id,
+        "status": task.status,
+        "error": task.error,
+        "at": at,
+    })
+}
+
+fn events_uri(workflow_run_id: Uuid) -> String {
+    format!("{EVENTS_URI_PREFIX}{workflow_run_id}{EVENTS_URI_SUFFIX}")
+}
+
+/// The run ID and `after` sequence number of an events URI.
+fn parse_events_uri(uri: &str) -> Option<(Uuid, u64)> {
+    let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let workflow_run_id = path
+        .strip_prefix(EVENTS_URI_PREFIX)?
+        .strip_suffix(EVENTS_URI_SUFFIX)?
+        .parse()
+        .ok()?;
+    let after = match query {
+        "" => 0,
+        query => query.strip_prefix("after=")?.parse().ok()?,
+    };
+    Some((workflow_run_id, after))
+}
+
+fn parse_id(id: &str) -> Result<Uuid, McpError> {
+    id.trim()
+        .parse()
+        .map_err(|_| McpError::invalid_params(format!("Invalid ID '{id}'"), None))
+}
+
+fn run_not_found(workflow_run_id: Uuid, error: impl std::fmt::Display) -> McpError {
+    McpError::invalid_params(format!("Workflow run {workflow_run_id}: {error}"), None)
+}
+
+fn not_found(uri: &str) -> McpError {
+    McpError::resource_not_found("resource_not_found", Some(json!({ "uri": uri })))
+}
+
+fn json_result(value: &impl Serialize) -> Result<CallToolResult, McpError> {
+    let content = serde_json::to_string_pretty(value).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize response: {e}"), None)
+    })?;
+    Ok(CallToolResult::success(vec![Content::text(content)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_uris_round_trip() {
+        let id = Uuid::new_v4();
+        assert_eq!(parse_events_uri(&events_uri(id)), Some((id, 0)));
+        assert_eq!(
+            parse_events_uri(&format!("{}?after=12", events_uri(id))),
+            Some((id, 12))
+        );
+        assert_eq!(
+            parse_events_uri(&format!("{}?before=12", events_uri(id))),
+            None
+        );
+        assert_eq!(parse_events_uri("workflow-run://not-an-id/events"), None);
+    }
+
+    #[test]
+    fn event_log_keeps_the_latest_events() {
+        let mut log = EventLog::default();
+        for _ in 0..MAX_BUFFERED_EVENTS + 5 {
+            log.push(json!({ "type": "task_log" }));
+        }
+        assert_eq!(log.events.len(), MAX_BUFFERED_EVENTS);
+        assert_eq!(log.events.front().unwrap()["seq"], 5);
+        assert_eq!(log.next_seq, (MAX_BUFFERED_EVENTS + 5) as u64);
+    }
+
+    #[test]
+    fn runs_that_finished_first_are_evicted_first() {
+        let start = Utc::now();
+        let finished = (0..MAX_FINISHED_RUNS + 2)
+            .rev()
+            .map(|minutes| {
+                (
+                    start + chrono::Duration::minutes(minutes as i64),
+                    Uuid::new_v4(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let oldest = finished[finished.len() - 2..]
+            .iter()
+            .rev()
+            .map(|(_, id)| *id)
+            .collect::<Vec<_>>();
+        assert_eq!(runs_to_evict(finished.clone()), oldest);
+        assert!(runs_to_evict(finished[..MAX_FINISHED_RUNS].to_vec()).is_empty());
+    }
+
+    #[test]
+    fn prompts_are_declined() {
+        let request_id = Uuid::new_v4();
+        let event = WorkflowEvent::CapabilitiesApprovalRequested {
+            request_id,
+            modules: vec![LlrtSupportedModules::Fetch],
+            at: Utc::now(),
+        };
+        assert!(matches!(
+            decline(&event),
+            Some(WorkflowCommand::RespondCapabilitiesApproval {
+                request_id: id,
+                approved: false,
+            }) if id == request_id
+        ));
+        let json = event_json(&event);
+        assert_eq!(json["type"], "prompt_declined");
+        assert_eq!(json["kind"], "capabilities");
+
+        let log = WorkflowEvent::TaskLogAppended {
+            workflow_run_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            line: "done".to_string(),
+            at: Utc::now(),
+        };
+        assert!(decline(&log).is_none());
+        assert_eq!(event_json(&log)["line"], "done");
+    }
+
+    #[test]
+    fn workflows_resolve_from_files_and_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let workflow = dir.path().join("workflow.yaml");
+        std::fs::write(&workflow, "version: \"1\"\nnodes: []\n").unwrap();
+        let bundle = dir.path().canonicalize().unwrap();
+
+        let (file, root) = resolve_workflow(&workflow).unwrap();
+        assert_eq!((file, root), (bundle.join("workflow.yaml"), bundle.clone()));
+        let (file, root) = resolve_workflow(dir.path()).unwrap();
+        assert_eq!((file, root), (bundle.join("workflow.yaml"), bundle));
+        assert!(resolve_workflow(&dir.path().join("missing.yaml")).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use rmcp::transport::streamable_http_server::{
+    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::CodemodMcpServer;
+
+/// Path the MCP endpoint is served under.
+pub const MCP_HTTP_PATH: &str = "/mcp";
+
+/// Host names a server bound to a loopback address may be reached as.
+const LOOPBACK_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// Options for serving over streamable HTTP.
+#[derive(Clone, Debug)]
+pub struct HttpServeOptions {
+    pub addr: SocketAddr,
+    /// Token clients must send as `Authorization: Bearer <token>`
+    pub bearer_token: String,
+}
+
+/// Serve `server` over rmcp's streamable HTTP transport until Ctrl-C.
+///
+/// Every client session gets its own clone of `server`, so tools behave as
+/// they do over stdio. Requests must carry the bearer token, and requests a
+/// browser sends from another site are refused, so web pages cannot drive
+/// the server through DNS rebinding.
+pub async fn serve_http(server: CodemodMcpServer, options: HttpServeOptions) -> anyhow::Result<()> {
+    let service = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+    // Layers run outside in, so the origin is checked before the token
+    let router = axum::Router::new()
+        .nest_service(MCP_HTTP_PATH, service)
+        .layer(middleware::from_fn_with_state(
+            Arc::<str>::from(options.bearer_token),
+            require_bearer_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            options.addr.ip().is_loopback(),
+            require_local_origin,
+        ));
+
+    let listener = tokio::net::TcpListener::bind(options.addr).await?;
+    tracing::info!(
+        "Serving MCP over HTTP at http://{}{MCP_HTTP_PATH}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+async fn require_local_origin(
+    State(loopback): State<bool>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_allowed_origin(request.headers(), loopback) {
+        next.run(request).await
+    } else {
+        (StatusCode::FORBIDDEN, "Host or Origin not allowed").into_response()
+    }
+}
+
+/// Whether a request may come from where its `Host` and `Origin` headers say.
+///
+/// A loopback server only answers to loopback host names, which a rebound
+/// DNS name is not. Browsers send `Origin` on cross-site requests; it must
+/// name the server itself. Clients other than browsers send no `Origin`.
+fn is_allowed_origin(headers: &HeaderMap, loopback: bool) -> bool {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(host_name);
+    if loopback && !host.is_some_and(|host| LOOPBACK_HOSTS.contains(&host)) {
+        return false;
+    }
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    origin
+        .to_str()
+        .ok()
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, authority)| host_name(authority))
+        .is_some_and(|origin| {
+            if loopback {
+                LOOPBACK_HOSTS.contains(&origin)
+            } else {
+                host.is_some_and(|host| host.eq_ignore_ascii_case(origin))
+            }
+        })
+}
+
+/// `authority` without its port.
+fn host_name(authority: &str) -> &str {
+    if authority.starts_with('[') {
+        // IPv6 literals keep their brackets
+        return authority
+            .find(']')
+            .map_or(authority, |end| &authority[..=end]);
+    }
+    authority
+        .rsplit_once(':')
+        .map_or(authority, |(host, _)| host)
+}
+
+async fn require_bearer_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_authorized(request.headers(), &token) {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Missing or invalid bearer token",
+        )
+            .into_response()
+    }
+}
+
+fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let (scheme, credentials) = value.split_once(' ')?;
+            scheme
+                .eq_ignore_ascii_case("bearer")
+                .then(|| credentials.trim())
+        })
+        .is_some_and(|credentials| constant_time_eq(credentials.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early on the first differing byte, so response
+/// times do not reveal how much of a guessed token is right.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn request_headers(host: &str, origin: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_str(host).unwrap());
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, HeaderValue::from_str(origin).unwrap());
+        }
+        headers
+    }
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn bearer_tokens_must_match_exactly() {
+        assert!(is_authorized(&headers("Bearer secret"), "secret"));
+        assert!(is_authorized(&headers("bearer secret"), "secret"));
+        assert!(!is_authorized(&headers("Bearer secret2"), "secret"));
+        assert!(!is_authorized(&headers("Bearer secre"), "secret"));
+        assert!(!is_authorized(&headers("Basic secret"), "secret"));
+        assert!(!is_authorized(&HeaderMap::new(), "secret"));
+    }
+
+    #[test]
+    fn loopback_servers_refuse_other_hosts_and_origins() {
+        for host in [
+            "127.0.0.1:8808",
+            "localhost:8808",
+            "[::1]:8808",
+            "localhost",
+        ] {
+            assert!(
+                is_allowed_origin(&request_headers(host, None), true),
+                "{host}"
+            );
+        }
+        assert!(is_allowed_origin(
+            &request_headers("127.0.0.1:8808", Some("http://localhost:3000")),
+            true
+        ));
+        // A rebound DNS name resolves to loopback but keeps its Host
+        assert!(!is_allowed_origin(
+            &request_headers("attacker.example:8808", None),
+            true
+        ));
+        assert!(!is_allowed_origin(
+            &request_headers("127.0.0.1:8808", Some("https://attacker.example")),
+            true
+        ));
+        assert!(!is_allowed_origin(
+            &request_headers("127.0.0.1:8808", Some("null")),
+            true
+        ));
+        assert!(!is_allowed_origin(&HeaderMap::new(), true));
+    }
+
+    #[test]
+    fn other_servers_only_accept_their_own_origin() {
+        assert!(is_allowed_origin(
+            &request_headers("mcp.internal:8808", None),
+            false
+        ));
+        assert!(is_allowed_origin(
+            &request_headers("mcp.internal:8808", Some("http://mcp.internal:8808")),
+            false
+        ));
+        assert!(!is_allowed_origin(
+            &request_headers("mcp.internal:8808", Some("https://attacker.example")),
+            false
+        ));
+    }
+}
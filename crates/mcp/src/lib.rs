@@ -15,8 +15,15 @@ use std::sync::OnceLock;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::OnceCell;
 
+mod allowlist;
 mod handlers;
-use handlers::{AstDumpHandler, JssgTestHandler, NodeTypesHandler, PackageValidationHandler};
+pub mod http;
+
+pub use allowlist::PathAllowlist;
+use handlers::{
+    AstDumpHandler, CodemodDryRunHandler, JssgTestHandler, NodeTypesHandler,
+    PackageValidationHandler, PatternSearchHandler, WorkflowRunsHandler,
+};
 
 const PUBLIC_DOCS_TIMEOUT_SECS: u64 = 10;
 const PUBLIC_DOCS_INITIAL_WAIT_MILLIS: u64 = 1000;
@@ -197,7 +204,8 @@ fn tool_infos() -> Vec<CliToolInfo> {
                     "codemod_file": { "type": "string" },
                     "tests": { "type": "array" },
                     "timeout_seconds": { "type": "integer", "minimum": 1 },
-                    "strictness": { "type": "string" }
+                    "strictness": { "type": "string" },
+                    "reporter": { "type": "string", "enum": ["junit", "tap"] }
                 },
                 "additionalProperties": true
             }),
@@ -216,6 +224,113 @@ fn tool_infos() -> Vec<CliToolInfo> {
                 "additionalProperties": false
             }),
         },
+        CliToolInfo {
+            name: "search_pattern",
+            description: "Search files for an ast-grep pattern or YAML rule and return the matches with their metavariable captures",
+            input_schema: json!({
+                "type": "object",
+                "required": ["path", "language"],
+                "properties": {
+                    "path": { "type": "string", "description": "File or directory to search" },
+                    "language": { "type": "string" },
+                    "pattern": { "type": "string", "description": "ast-grep pattern; exactly one of pattern and rule is required" },
+                    "rule": { "type": "string", "description": "ast-grep rule or rule config as YAML" },
+                    "include": { "type": "array", "items": { "type": "string" } },
+                    "exclude": { "type": "array", "items": { "type": "string" } },
+                    "max_results": { "type": "integer", "minimum": 1 },
+                    "timeout_seconds": { "type": "integer", "minimum": 1 }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "dry_run_codemod",
+            description: "Run a jssg codemod file or package on a path without writing anything and return the unified diffs and metrics",
+            input_schema: json!({
+                "type": "object",
+                "required": ["codemod", "path"],
+                "properties": {
+                    "codemod": { "type": "string", "description": "jssg codemod file or codemod package directory" },
+                    "path": { "type": "string", "description": "File or directory to run the codemod on" },
+                    "language": { "type": "string" },
+                    "include": { "type": "array", "items": { "type": "string" } },
+                    "exclude": { "type": "array", "items": { "type": "string" } },
+                    "params": { "type": "object" },
+                    "max_files": { "type": "integer", "minimum": 1 },
+                    "max_diff_lines": { "type": "integer", "minimum": 1 },
+                    "timeout_seconds": { "type": "integer", "minimum": 1 }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "start_workflow_run",
+            description: "Start a workflow run from a workflow file or codemod package and return its tasks",
+            input_schema: json!({
+                "type": "object",
+                "required": ["workflow"],
+                "properties": {
+                    "workflow": { "type": "string", "description": "Workflow file or codemod package directory" },
+                    "target": { "type": "string", "description": "Directory to run the workflow against" },
+                    "params": { "type": "object" },
+                    "dry_run": { "type": "boolean" },
+                    "capabilities": { "type": "array", "items": { "type": "string" } }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "list_workflow_runs",
+            description: "List recent workflow runs, newest first",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "limit": { "type": "integer", "minimum": 1 }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "get_workflow_run",
+            description: "Get a workflow run's status and its tasks' statuses and logs",
+            input_schema: json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "task_ids": { "type": "array", "items": { "type": "string" } },
+                    "max_log_lines": { "type": "integer", "minimum": 0 }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "trigger_workflow_tasks",
+            description: "Trigger manual tasks of a workflow run",
+            input_schema: json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "task_ids": { "type": "array", "items": { "type": "string" } },
+                    "all": { "type": "boolean" },
+                    "dry_run": { "type": "boolean" }
+                },
+                "additionalProperties": false
+            }),
+        },
+        CliToolInfo {
+            name: "cancel_workflow_run",
+            description: "Cancel a workflow run",
+            input_schema: json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": { "type": "string" }
+                },
+                "additionalProperties": false
+            }),
+        },
         CliToolInfo {
             name: "get_jssg_instructions",
             description: "Deprecated compatibility alias for the jssg-instructions resource",
@@ -986,6 +1101,10 @@ pub struct CodemodMcpServer {
     node_types_handler: NodeTypesHandler,
     jssg_test_handler: JssgTestHandler,
     package_validation_handler: PackageValidationHandler,
+    pattern_search_handler: PatternSearchHandler,
+    codemod_dry_run_handler: CodemodDryRunHandler,
+    workflow_runs_handler: WorkflowRunsHandler,
+    path_allowlist: Option<PathAllowlist>,
     usage_log_path: Option<PathBuf>,
     anonymous_feedback: Option<AnonymousFeedbackClient>,
     tool_router: ToolRouter<CodemodMcpServer>,
@@ -1011,12 +1130,56 @@ impl CodemodMcpServer {
             node_types_handler: NodeTypesHandler::new(),
             jssg_test_handler: JssgTestHandler::new(),
             package_validation_handler: PackageValidationHandler::new(),
+            pattern_search_handler: PatternSearchHandler::new(),
+            codemod_dry_run_handler: CodemodDryRunHandler::new(),
+            workflow_runs_handler: WorkflowRunsHandler::new(),
+            path_allowlist: None,
             usage_log_path,
             anonymous_feedback,
             tool_router: Self::tool_router(),
         }
     }
 
+    /// Restrict tools that take paths to the allowlisted directories.
+    pub fn with_path_allowlist(mut self, allowlist: PathAllowlist) -> Self {
+        self.path_allowlist = Some(allowlist);
+        self
+    }
+
+    /// Reject tool calls naming paths outside the allowlist, if there is one.
+    fn check_paths<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Result<(), McpError> {
+        let Some(allowlist) = &self.path_allowlist else {
+            return Ok(());
+        };
+        for path in paths {
+            allowlist
+                .check(std::path::Path::new(path))
+                .map_err(|error| McpError::invalid_params(error, None))?;
+        }
+        Ok(())
+    }
+
+    /// Reject tool calls on a persisted workflow run whose bundle or target
+    /// is outside the allowlist, if there is one. Runs that recorded neither
+    /// are rejected too.
+    async fn check_run_paths(&self, id: &str) -> Result<(), McpError> {
+        if self.path_allowlist.is_none() {
+            return Ok(());
+        }
+        let paths = self.workflow_runs_handler.run_paths(id).await?;
+        if paths.is_empty() {
+            return Err(McpError::invalid_params(
+                format!("Workflow run {id} has no recorded directories to check"),
+                None,
+            ));
+        }
+        let paths = paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>();
+        self.check_paths(paths.iter().map(|path| path.as_ref()))
+    }
+
     pub fn cli_tools(&self) -> Vec<CliToolInfo> {
         tool_infos()
     }
@@ -1105,6 +1268,69 @@ impl CodemodMcpServer {
                     .map_err(|error| anyhow::anyhow!("{error:?}"))?;
                 Ok(call_tool_result_text(result))
             }
+            "search_pattern" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .pattern_search_handler
+                    .search_pattern(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "dry_run_codemod" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .codemod_dry_run_handler
+                    .dry_run_codemod(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "start_workflow_run" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .workflow_runs_handler
+                    .start_workflow_run(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "list_workflow_runs" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .workflow_runs_handler
+                    .list_workflow_runs(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "get_workflow_run" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .workflow_runs_handler
+                    .get_workflow_run(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "trigger_workflow_tasks" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .workflow_runs_handler
+                    .trigger_workflow_tasks(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
+            "cancel_workflow_run" => {
+                let request = serde_json::from_value(arguments)?;
+                let result = self
+                    .workflow_runs_handler
+                    .cancel_workflow_run(rmcp::handler::server::wrapper::Parameters(request))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("{error:?}"))?;
+                Ok(call_tool_result_text(result))
+            }
             "get_jssg_instructions" => self.read_resource_text("jssg://instructions").await,
             "get_jssg_gotchas" => self.read_resource_text("jssg-gotchas://instructions").await,
             "get_ast_grep_gotchas" => {
@@ -1186,6 +1412,18 @@ impl CodemodMcpServer {
             .map(|resource| {
                 self._create_resource_text(resource.uri, resource.name, resource.description)
             })
+            .chain(
+                self.workflow_runs_handler
+                    .event_resources()
+                    .into_iter()
+                    .map(|(uri, name)| {
+                        self._create_resource_text(
+                            &uri,
+                            &name,
+                            Some("Events of a workflow run started or triggered over MCP. Subscribe to be notified of new events, and append ?after=<seq> to read only those."),
+                        )
+                    }),
+            )
             .collect()
     }
 
@@ -1320,6 +1558,9 @@ impl CodemodMcpServer {
             "codemod-maintainer-monorepo://instructions" => {
                 Ok(local_codemod_maintainer_monorepo_docs_bundle().to_string())
             }
+            uri if WorkflowRunsHandler::is_events_uri(uri) => {
+                self.workflow_runs_handler.read_events(uri)
+            }
             _ => Err(McpError::resource_not_found(
                 "resource_not_found",
                 Some(json!({ "uri": uri })),
@@ -1375,6 +1616,16 @@ impl CodemodMcpServer {
         params: rmcp::handler::server::wrapper::Parameters<handlers::jssg_test::RunJssgTestRequest>,
     ) -> Result<CallToolResult, McpError> {
         self.log_usage("tool:run_jssg_tests");
+        let request = &params.0;
+        self.check_paths(std::iter::once(request.codemod_file.as_str()).chain(
+            request.tests.iter().flat_map(|test| match test {
+                handlers::jssg_test::TestCase::Adhoc { .. } => vec![],
+                handlers::jssg_test::TestCase::FileSystem {
+                    input_file,
+                    expected_output_file,
+                } => vec![input_file.as_str(), expected_output_file.as_str()],
+            }),
+        ))?;
         self.jssg_test_handler.run_jssg_tests(params).await
     }
 
@@ -1388,11 +1639,141 @@ impl CodemodMcpServer {
         >,
     ) -> Result<CallToolResult, McpError> {
         self.log_usage("tool:validate_codemod_package");
+        // Without a path the package in the current directory is validated
+        self.check_paths([params
+            .0
+            .package_path
+            .as_deref()
+            .filter(|path| !path.trim().is_empty())
+            .unwrap_or(".")])?;
         self.package_validation_handler
             .validate_codemod_package(params)
             .await
     }
 
+    #[tool(
+        description = "Search files for an ast-grep pattern or YAML rule and return the matches with their metavariable captures. Use this to check where a pattern or rule matches before writing a codemod."
+    )]
+    async fn search_pattern(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::pattern_search::SearchPatternRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:search_pattern");
+        self.check_paths([params.0.path.as_str()])?;
+        self.pattern_search_handler.search_pattern(params).await
+    }
+
+    #[tool(
+        description = "Run a jssg codemod file or package on a path without writing anything and return the unified diffs and metrics"
+    )]
+    async fn dry_run_codemod(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::codemod_dry_run::DryRunCodemodRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:dry_run_codemod");
+        self.check_paths([params.0.codemod.as_str(), params.0.path.as_str()])?;
+        self.codemod_dry_run_handler.dry_run_codemod(params).await
+    }
+
+    #[tool(
+        description = "Start a workflow run from a workflow file or codemod package and return its tasks. The run continues in the background; follow it with get_workflow_run or by subscribing to its events_uri resource. Prompts such as shell command approvals are declined. On servers restricted to allowed directories, dry_run defaults to true and capabilities are refused; the response reports the effective dry_run."
+    )]
+    async fn start_workflow_run(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::workflow_runs::StartWorkflowRunRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:start_workflow_run");
+        let mut params = params;
+        self.check_paths([
+            params.0.workflow.as_str(),
+            params.0.target.as_deref().unwrap_or("."),
+        ])?;
+        if self.path_allowlist.is_some() {
+            // Capabilities let scripts reach outside the allowed directories
+            if !params.0.capabilities.is_empty() {
+                return Err(McpError::invalid_params(
+                    "capabilities are not allowed on servers restricted to allowed directories: \
+                     fs, fetch and child_process give scripts access beyond them. Run the \
+                     workflow over stdio without --allow-dir to grant capabilities",
+                    None,
+                ));
+            }
+            // Reported back as the run's dry_run, so the default is never hidden
+            params.0.dry_run.get_or_insert(true);
+        }
+        self.workflow_runs_handler.start_workflow_run(params).await
+    }
+
+    #[tool(description = "List recent workflow runs, newest first")]
+    async fn list_workflow_runs(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::workflow_runs::ListWorkflowRunsRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:list_workflow_runs");
+        let Some(allowlist) = &self.path_allowlist else {
+            return self.workflow_runs_handler.list_workflow_runs(params).await;
+        };
+        self.workflow_runs_handler
+            .list_workflow_runs_where(params, |run| {
+                let paths = WorkflowRunsHandler::paths_of(run);
+                !paths.is_empty() && paths.iter().all(|path| allowlist.check(path).is_ok())
+            })
+            .await
+    }
+
+    #[tool(description = "Get a workflow run's status and its tasks' statuses and logs")]
+    async fn get_workflow_run(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::workflow_runs::GetWorkflowRunRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:get_workflow_run");
+        self.check_run_paths(&params.0.id).await?;
+        self.workflow_runs_handler.get_workflow_run(params).await
+    }
+
+    #[tool(
+        description = "Trigger manual tasks of a workflow run, by ID or all tasks awaiting a trigger. On servers restricted to allowed directories, dry_run defaults to true; the response reports the effective dry_run."
+    )]
+    async fn trigger_workflow_tasks(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::workflow_runs::TriggerWorkflowTasksRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:trigger_workflow_tasks");
+        let mut params = params;
+        // Triggered tasks run the persisted workflow against its target
+        self.check_run_paths(&params.0.id).await?;
+        if self.path_allowlist.is_some() {
+            params.0.dry_run.get_or_insert(true);
+        }
+        self.workflow_runs_handler
+            .trigger_workflow_tasks(params)
+            .await
+    }
+
+    #[tool(description = "Cancel a workflow run")]
+    async fn cancel_workflow_run(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<
+            handlers::workflow_runs::CancelWorkflowRunRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        self.log_usage("tool:cancel_workflow_run");
+        self.check_run_paths(&params.0.id).await?;
+        self.workflow_runs_handler.cancel_workflow_run(params).await
+    }
+
     #[tool(
         description = "Deprecated compatibility alias for jssg instructions. Prefer the jssg-instructions resource."
     )]
@@ -1581,6 +1962,18 @@ impl CodemodMcpServer {
     }
 }
 
+/// Identifies the client session a request came from: its `Mcp-Session-Id`
+/// over HTTP, and the one client over stdio.
+fn subscriber_key(context: &RequestContext<RoleServer>) -> String {
+    context
+        .extensions
+        .get::<axum::http::request::Parts>()
+        .and_then(|parts| parts.headers.get("mcp-session-id"))
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
 #[tool_handler]
 impl ServerHandler for CodemodMcpServer {
     fn get_info(&self) -> ServerInfo {
@@ -1589,9 +1982,10 @@ impl ServerHandler for CodemodMcpServer {
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_resources_subscribe()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("This server provides AST dumping, tree-sitter node types, JSSG test execution, and Codemod package validation. Available tools: dump_ast, get_node_types, run_jssg_tests, validate_codemod_package. Available resources: jssg-instructions, jssg-gotchas, ast-grep-gotchas, jssg-utils-instructions, jssg-runtime-capabilities-instructions, codemod-cli-instructions, sharding-instructions, codemod-troubleshooting-instructions, codemod-creation-workflow-instructions, codemod-maintainer-monorepo-instructions. For codemod authoring, read codemod-creation-workflow-instructions first, then read jssg-gotchas and ast-grep-gotchas before writing source-transform code. If registry search finds no exact existing package, run direct codemod init immediately; in non-interactive flows, pass only user- or task-provided metadata flags and rely on CLI defaults/auth-derived author handling for the rest. Call validate_codemod_package before you stop work on a codemod package. Use dump_ast when pattern shape is unclear. If symbol origin matters, use semantic analysis and binding-aware checks. If you discover a Codemod platform gap, ask the user for explicit consent before submitting anonymous feedback. With consent, run `codemod ai feedback --category <category> --message <short message>` using categories like jssg, workflow, ai-docs, mcp, cli, registry, package-validation, or other. Do not include source code, secrets, auth tokens, private repository paths, user identity, or long transcripts.".to_string()),
+            instructions: Some("This server provides AST dumping, tree-sitter node types, pattern search, JSSG test execution, codemod dry runs, Codemod package validation, and workflow runs. Available tools: dump_ast, get_node_types, search_pattern, run_jssg_tests, dry_run_codemod, validate_codemod_package, start_workflow_run, list_workflow_runs, get_workflow_run, trigger_workflow_tasks, cancel_workflow_run. Available resources: jssg-instructions, jssg-gotchas, ast-grep-gotchas, jssg-utils-instructions, jssg-runtime-capabilities-instructions, codemod-cli-instructions, sharding-instructions, codemod-troubleshooting-instructions, codemod-creation-workflow-instructions, codemod-maintainer-monorepo-instructions, plus a subscribable workflow-run://<id>/events resource for each workflow run started or triggered here. For codemod authoring, read codemod-creation-workflow-instructions first, then read jssg-gotchas and ast-grep-gotchas before writing source-transform code. If registry search finds no exact existing package, run direct codemod init immediately; in non-interactive flows, pass only user- or task-provided metadata flags and rely on CLI defaults/auth-derived author handling for the rest. Call validate_codemod_package before you stop work on a codemod package. Use dump_ast when pattern shape is unclear, search_pattern to see where a pattern or rule matches, and dry_run_codemod to review a codemod's diffs on real code. If symbol origin matters, use semantic analysis and binding-aware checks. If you discover a Codemod platform gap, ask the user for explicit consent before submitting anonymous feedback. With consent, run `codemod ai feedback --category <category> --message <short message>` using categories like jssg, workflow, ai-docs, mcp, cli, registry, package-validation, or other. Do not include source code, secrets, auth tokens, private repository paths, user identity, or long transcripts.".to_string()),
         }
     }
 
@@ -1628,6 +2022,25 @@ impl ServerHandler for CodemodMcpServer {
         })
     }
 
+    async fn subscribe(
+        &self,
+        SubscribeRequestParam { uri }: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.workflow_runs_handler
+            .subscribe(&uri, subscriber_key(&context), context.peer)
+    }
+
+    async fn unsubscribe(
+        &self,
+        UnsubscribeRequestParam { uri }: UnsubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.workflow_runs_handler
+            .unsubscribe(&uri, &subscriber_key(&context));
+        Ok(())
+    }
+
     async fn list_resource_templates(
         &self,
         _request: Option<PaginatedRequestParam>,
@@ -1831,6 +2244,15 @@ mod tests {
         assert!(info.instructions.is_some());
         let instructions = info.instructions.as_deref().unwrap_or_default();
         assert!(instructions.contains("validate_codemod_package"));
+        assert!(instructions.contains("search_pattern"));
+        assert!(instructions.contains("dry_run_codemod"));
+        assert!(instructions.contains("start_workflow_run"));
+        assert!(instructions.contains("trigger_workflow_tasks"));
+        assert!(info
+            .capabilities
+            .resources
+            .as_ref()
+            .is_some_and(|resources| resources.subscribe == Some(true)));
         assert!(!instructions.contains("scaffold_codemod_package"));
         assert!(instructions.contains("jssg-gotchas"));
         assert!(instructions.contains("codemod-creation-workflow-instructions"));
@@ -1839,6 +2261,140 @@ mod tests {
         assert!(instructions.contains("anonymous feedback"));
     }
 
+    #[tokio::test]
+    async fn untracked_workflow_run_events_are_not_found() {
+        let server = CodemodMcpServer::default();
+        let uri = format!("workflow-run://{}/events", uuid::Uuid::new_v4());
+        assert!(server.resource_content(&uri).await.is_err());
+        assert!(server
+            .resources()
+            .iter()
+            .all(|resource| !resource.uri.starts_with("workflow-run://")));
+    }
+
+    #[tokio::test]
+    async fn allowlisted_server_rejects_paths_outside_the_allowlist() {
+        let allowed = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let server = CodemodMcpServer::default().with_path_allowlist(
+            PathAllowlist::new([allowed.path().to_path_buf()]).expect("expected allowlist"),
+        );
+        let search = |path: &std::path::Path| {
+            rmcp::handler::server::wrapper::Parameters(
+                serde_json::from_value(json!({
+                    "path": path,
+                    "language": "typescript",
+                    "pattern": "console.log($A)"
+                }))
+                .expect("expected request"),
+            )
+        };
+
+        assert!(server.search_pattern(search(allowed.path())).await.is_ok());
+        let error = server
+            .search_pattern(search(other.path()))
+            .await
+            .expect_err("expected path outside the allowlist to be rejected");
+        assert!(error.message.contains("outside the allowed directories"));
+    }
+
+    #[tokio::test]
+    async fn allowlisted_server_refuses_workflow_capabilities() {
+        let allowed = tempfile::tempdir().unwrap();
+        let server = CodemodMcpServer::default().with_path_allowlist(
+            PathAllowlist::new([allowed.path().to_path_buf()]).expect("expected allowlist"),
+        );
+        let workflow = allowed.path().join("workflow.yaml");
+        std::fs::write(&workflow, "version: \"1\"\nnodes: []\n").unwrap();
+
+        let error = server
+            .start_workflow_run(rmcp::handler::server::wrapper::Parameters(
+                serde_json::from_value(json!({
+                    "workflow": workflow,
+                    "target": allowed.path(),
+                    "capabilities": ["fs"]
+                }))
+                .expect("expected request"),
+            ))
+            .await
+            .expect_err("expected capabilities to be refused");
+        assert!(error.message.contains("capabilities are not allowed"));
+    }
+
+    #[tokio::test]
+    async fn started_workflow_run_reports_dry_run_and_streams_events() {
+        let allowed = tempfile::tempdir().unwrap();
+        let server = CodemodMcpServer::default().with_path_allowlist(
+            PathAllowlist::new([allowed.path().to_path_buf()]).expect("expected allowlist"),
+        );
+        let workflow = allowed.path().join("workflow.yaml");
+        std::fs::write(
+            &workflow,
+            "version: \"1\"\nnodes:\n  - id: review\n    name: Review\n    trigger:\n      type: manual\n    steps:\n      - name: Review\n        run: echo reviewed\n",
+        )
+        .unwrap();
+        let text = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
+
+        let started = text(
+            server
+                .start_workflow_run(rmcp::handler::server::wrapper::Parameters(
+                    serde_json::from_value(json!({
+                        "workflow": workflow,
+                        "target": allowed.path()
+                    }))
+                    .expect("expected request"),
+                ))
+                .await
+                .expect("expected workflow run to start"),
+        );
+        assert_eq!(started["run"]["dry_run"], json!(true));
+        let id = started["run"]["id"].as_str().unwrap().to_string();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+        loop {
+            let details = text(
+                server
+                    .get_workflow_run(rmcp::handler::server::wrapper::Parameters(
+                        serde_json::from_value(json!({ "id": id })).expect("expected request"),
+                    ))
+                    .await
+                    .expect("expected workflow run"),
+            );
+            assert_eq!(details["run"]["id"], json!(id));
+            assert_eq!(details["run"]["dry_run"], json!(true));
+            if !details["awaiting_trigger"].as_array().unwrap().is_empty() {
+                break;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "expected the manual task to await a trigger"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        let events: serde_json::Value = serde_json::from_str(
+            &server
+                .resource_content(&format!("workflow-run://{id}/events"))
+                .await
+                .expect("expected events of a started run"),
+        )
+        .unwrap();
+        let events = events["events"].as_array().unwrap();
+        assert_eq!(events[0]["type"], json!("workflow_started"));
+        assert!(events
+            .iter()
+            .any(|event| event["node_id"] == json!("review")));
+
+        server
+            .cancel_workflow_run(rmcp::handler::server::wrapper::Parameters(
+                serde_json::from_value(json!({ "id": id })).expect("expected request"),
+            ))
+            .await
+            .expect("expected workflow run to be cancelled");
+    }
+
     #[tokio::test]
     async fn test_instruction_alias_tool_returns_resource_content() {
         let server = CodemodMcpServer::default();
@@ -166,6 +166,30 @@ pub struct UseJSAstGrep {
     #[serde(default)]
     #[ts(optional, as = "Option<SemanticAnalysisConfig>")]
     pub semantic_analysis: Option<SemanticAnalysisConfig>,
+
+    /// Limit the run to changed files and the files that transitively import them (optional).
+    /// Each entry is a path relative to the target, or a git range such as `main..HEAD`.
+    #[serde(default)]
+    #[ts(optional, as = "Option<Vec<String>>")]
+    pub affected_by: Option<Vec<String>>,
+
+    /// Refuse to write files the codemod leaves with syntax errors they did not have
+    /// (optional, defaults to false)
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub check_syntax: Option<bool>,
+
+    /// Refuse to write files the codemod would change again when re-run on its output
+    /// (optional, defaults to false)
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub check_idempotent: Option<bool>,
+
+    /// Also run JavaScript and TypeScript codemods on `.vue` and `.svelte` files when
+    /// `include` is not set (optional, defaults to true)
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub include_components: Option<bool>,
 }
 
 /// Configuration for semantic analysis in JS AST grep.
@@ -197,6 +221,11 @@ pub struct SemanticAnalysisDetailed {
     #[serde(default)]
     #[ts(optional, as = "Option<String>")]
     pub root: Option<String>,
+    /// Directory of `<language>.scm` locals queries, relative to the codemod
+    /// package, overriding the built-in queries of the tree-sitter provider
+    #[serde(default)]
+    #[ts(optional, as = "Option<String>")]
+    pub queries: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
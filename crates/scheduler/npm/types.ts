@@ -43,6 +43,11 @@ export type SemanticAnalysisDetailed = {
    * Custom workspace root path (only used when mode is "workspace")
    */
   root?: string;
+  /**
+   * Directory of `<language>.scm` locals queries, relative to the codemod
+   * package, overriding the built-in queries of the tree-sitter provider
+   */
+  queries?: string;
 };
 export type UseAI = {
   /**
@@ -150,6 +155,26 @@ export type UseJSAstGrep = {
    * - `{"mode": "workspace", "root": "/path/to/workspace"}` - workspace-wide with custom root
    */
   semantic_analysis?: SemanticAnalysisConfig;
+  /**
+   * Limit the run to changed files and the files that transitively import them (optional).
+   * Each entry is a path relative to the target, or a git range such as `main..HEAD`.
+   */
+  affected_by?: Array<string>;
+  /**
+   * Refuse to write files the codemod leaves with syntax errors they did not have
+   * (optional, defaults to false)
+   */
+  check_syntax?: boolean;
+  /**
+   * Refuse to write files the codemod would change again when re-run on its output
+   * (optional, defaults to false)
+   */
+  check_idempotent?: boolean;
+  /**
+   * Also run JavaScript and TypeScript codemods on `.vue` and `.svelte` files when
+   * `include` is not set (optional, defaults to true)
+   */
+  include_components?: boolean;
 };
 export type LlrtSupportedModules =
   | "abort"
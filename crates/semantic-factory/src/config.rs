@@ -50,6 +50,13 @@ pub struct SemanticConfig {
     pub fs_root: Option<VfsPath>,
     /// How to enumerate workspace files during indexing.
     pub walker: WorkspaceWalker,
+    /// Directory with `<language>.scm` files overriding the built-in
+    /// tree-sitter locals queries. Only used by the generic tree-sitter
+    /// provider.
+    pub query_dir: Option<PathBuf>,
+    /// Directory of the persistent workspace index. When set, workspace
+    /// providers reuse per-file results from earlier runs.
+    pub index_dir: Option<PathBuf>,
 }
 
 impl std::fmt::Debug for SemanticConfig {
@@ -58,6 +65,8 @@ impl std::fmt::Debug for SemanticConfig {
             .field("scope", &self.scope)
             .field("fs_root", &self.fs_root.as_ref().map(|_| "<VfsPath>"))
             .field("walker", &self.walker)
+            .field("query_dir", &self.query_dir)
+            .field("index_dir", &self.index_dir)
             .finish()
     }
 }
@@ -69,6 +78,8 @@ impl SemanticConfig {
             scope: SemanticScope::FileScope,
             fs_root: None,
             walker: WorkspaceWalker::Ignore,
+            query_dir: None,
+            index_dir: None,
         }
     }
 
@@ -78,6 +89,8 @@ impl SemanticConfig {
             scope: SemanticScope::FileScope,
             fs_root: Some(fs_root),
             walker: WorkspaceWalker::Ignore,
+            query_dir: None,
+            index_dir: None,
         }
     }
 
@@ -87,6 +100,8 @@ impl SemanticConfig {
             scope: SemanticScope::WorkspaceScope { root },
             fs_root: None,
             walker: WorkspaceWalker::Ignore,
+            query_dir: None,
+            index_dir: None,
         }
     }
 
@@ -96,6 +111,8 @@ impl SemanticConfig {
             scope: SemanticScope::WorkspaceScope { root },
             fs_root: Some(fs_root),
             walker: WorkspaceWalker::Ignore,
+            query_dir: None,
+            index_dir: None,
         }
     }
 
@@ -105,4 +122,18 @@ impl SemanticConfig {
         self.walker = walker;
         self
     }
+
+    /// Override the built-in tree-sitter locals queries with the
+    /// `<language>.scm` files found in `dir`.
+    pub fn with_query_dir(mut self, dir: PathBuf) -> Self {
+        self.query_dir = Some(dir);
+        self
+    }
+
+    /// Persist the workspace index under `dir`, shared across processes.
+    /// No-op for file-scope configurations.
+    pub fn with_index_dir(mut self, dir: PathBuf) -> Self {
+        self.index_dir = Some(dir);
+        self
+    }
 }
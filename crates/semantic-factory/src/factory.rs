@@ -5,6 +5,7 @@ use std::sync::Arc;
 use language_core::SemanticProvider;
 use language_javascript::OxcSemanticProvider;
 use language_python::RuffSemanticProvider;
+use language_tree_sitter::TreeSitterSemanticProvider;
 
 use crate::config::{SemanticConfig, SemanticScope, WorkspaceWalker};
 
@@ -42,7 +43,7 @@ impl SemanticFactory {
         match language.to_lowercase().as_str() {
             // JavaScript/TypeScript family
             "javascript" | "typescript" | "js" | "ts" | "jsx" | "tsx" | "mjs" | "cjs" => {
-                Some(Arc::new(match (&config.scope, config.fs_root) {
+                let provider = match (&config.scope, config.fs_root) {
                     (SemanticScope::FileScope, None) => OxcSemanticProvider::file_scope(),
                     (SemanticScope::FileScope, Some(fs_root)) => {
                         OxcSemanticProvider::file_scope_with_fs(fs_root)
@@ -68,21 +69,50 @@ impl SemanticFactory {
                             )
                         }
                     },
+                };
+                Some(Arc::new(match &config.index_dir {
+                    Some(dir) => provider.with_index_dir(dir),
+                    None => provider,
                 }))
             }
             // Python
-            "python" | "py" => Some(Arc::new(match (&config.scope, config.fs_root) {
-                (SemanticScope::FileScope, None) => RuffSemanticProvider::file_scope(),
-                (SemanticScope::FileScope, Some(fs_root)) => {
-                    RuffSemanticProvider::file_scope_with_fs(fs_root)
-                }
-                (SemanticScope::WorkspaceScope { root }, None) => {
-                    RuffSemanticProvider::workspace_scope(root.clone())
-                }
-                (SemanticScope::WorkspaceScope { root }, Some(fs_root)) => {
-                    RuffSemanticProvider::workspace_scope_with_fs(root.clone(), fs_root)
-                }
-            })),
+            "python" | "py" => {
+                let provider = match (&config.scope, config.fs_root) {
+                    (SemanticScope::FileScope, None) => RuffSemanticProvider::file_scope(),
+                    (SemanticScope::FileScope, Some(fs_root)) => {
+                        RuffSemanticProvider::file_scope_with_fs(fs_root)
+                    }
+                    (SemanticScope::WorkspaceScope { root }, None) => {
+                        RuffSemanticProvider::workspace_scope(root.clone())
+                    }
+                    (SemanticScope::WorkspaceScope { root }, Some(fs_root)) => {
+                        RuffSemanticProvider::workspace_scope_with_fs(root.clone(), fs_root)
+                    }
+                };
+                Some(Arc::new(match &config.index_dir {
+                    Some(dir) => provider.with_index_dir(dir),
+                    None => provider,
+                }))
+            }
+            // Other languages with a locals query
+            lang if TreeSitterSemanticProvider::is_supported_language(lang) => {
+                let provider = match (&config.scope, config.fs_root) {
+                    (SemanticScope::FileScope, None) => TreeSitterSemanticProvider::file_scope(),
+                    (SemanticScope::FileScope, Some(fs_root)) => {
+                        TreeSitterSemanticProvider::file_scope_with_fs(fs_root)
+                    }
+                    (SemanticScope::WorkspaceScope { root }, None) => {
+                        TreeSitterSemanticProvider::workspace_scope(root.clone())
+                    }
+                    (SemanticScope::WorkspaceScope { .. }, Some(fs_root)) => {
+                        TreeSitterSemanticProvider::workspace_scope_with_fs(fs_root)
+                    }
+                };
+                Some(Arc::new(match config.query_dir {
+                    Some(dir) => provider.with_query_dir(dir),
+                    None => provider,
+                }))
+            }
             // Languages without semantic support
             "css" | "html" | "json" | "yaml" | "markdown" | "md" => None,
             // Unknown languages - no semantic support
@@ -95,19 +125,20 @@ impl SemanticFactory {
 
     /// Check if a language has semantic analysis support.
     pub fn supports_language(language: &str) -> bool {
-        matches!(
-            language.to_lowercase().as_str(),
-            "javascript"
-                | "typescript"
-                | "js"
-                | "ts"
-                | "jsx"
-                | "tsx"
-                | "mjs"
-                | "cjs"
-                | "python"
-                | "py"
-        )
+        TreeSitterSemanticProvider::is_supported_language(language)
+            || matches!(
+                language.to_lowercase().as_str(),
+                "javascript"
+                    | "typescript"
+                    | "js"
+                    | "ts"
+                    | "jsx"
+                    | "tsx"
+                    | "mjs"
+                    | "cjs"
+                    | "python"
+                    | "py"
+            )
     }
 }
 
@@ -164,6 +195,21 @@ mod tests {
         assert!(!SemanticFactory::supports_language("css"));
         assert!(!SemanticFactory::supports_language("html"));
         assert!(!SemanticFactory::supports_language("unknown"));
+        assert!(SemanticFactory::supports_language("go"));
+        assert!(SemanticFactory::supports_language("java"));
+        assert!(SemanticFactory::supports_language("rust"));
+    }
+
+    #[test]
+    fn test_create_tree_sitter_languages() {
+        for language in ["go", "java", "rust", "cpp", "ruby"] {
+            let provider = SemanticFactory::create(language, SemanticConfig::file_scope());
+            assert!(provider.is_some(), "{language}");
+            assert!(provider.unwrap().supports_language(language));
+        }
+        let config = SemanticConfig::workspace_scope(std::path::PathBuf::from("/tmp"));
+        let provider = SemanticFactory::create("go", config).unwrap();
+        assert_eq!(provider.mode(), ProviderMode::WorkspaceScope);
     }
 
     #[test]
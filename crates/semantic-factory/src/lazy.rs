@@ -1,16 +1,17 @@
 //! Lazy-initialized semantic provider.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
 use language_core::{
-    ByteRange, DefinitionOptions, DefinitionResult, ProviderMode, ReferencesResult,
-    SemanticProvider, SemanticResult,
+    ByteRange, ControlFlowGraph, DefinitionOptions, DefinitionResult, ProviderMode,
+    ReferencesResult, ScopeInfo, SemanticProvider, SemanticResult,
 };
 use vfs::VfsPath;
 
 use crate::config::SemanticConfig;
 use crate::factory::SemanticFactory;
+use language_tree_sitter::TreeSitterSemanticProvider;
 
 /// A lazy-initialized semantic provider that creates the underlying
 /// provider on first use.
@@ -137,7 +138,7 @@ impl LazySemanticProvider {
             Some("json") => "json",
             Some("yaml" | "yml") => "yaml",
             Some("md" | "markdown") => "markdown",
-            _ => "unknown",
+            _ => TreeSitterSemanticProvider::detect_language(file_path).unwrap_or("unknown"),
         }
     }
 }
@@ -185,6 +186,38 @@ impl SemanticProvider for LazySemanticProvider {
         }
     }
 
+    fn get_scopes(&self, file_path: &Path, range: ByteRange) -> SemanticResult<Vec<ScopeInfo>> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.get_scopes(file_path, range),
+            None => Ok(Vec::new()), // Unsupported language
+        }
+    }
+
+    fn get_control_flow(
+        &self,
+        file_path: &Path,
+        range: ByteRange,
+    ) -> SemanticResult<Option<ControlFlowGraph>> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.get_control_flow(file_path, range),
+            None => Ok(None), // Unsupported language
+        }
+    }
+
+    fn get_control_flows(
+        &self,
+        file_path: &Path,
+        content: &str,
+    ) -> SemanticResult<Option<Vec<ControlFlowGraph>>> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.get_control_flows(file_path, content),
+            None => Ok(None), // Unsupported language
+        }
+    }
+
     fn notify_file_processed(&self, file_path: &Path, content: &str) -> SemanticResult<()> {
         let lang = Self::detect_language(file_path);
         match self.get_or_init(lang) {
@@ -193,6 +226,40 @@ impl SemanticProvider for LazySemanticProvider {
         }
     }
 
+    fn set_file_overlay(&self, file_path: &Path, content: Option<&str>) -> SemanticResult<()> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.set_file_overlay(file_path, content),
+            None => Ok(()), // Unsupported language - no-op
+        }
+    }
+
+    fn get_imports(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.get_imports(file_path),
+            None => Ok(Vec::new()), // Unsupported language
+        }
+    }
+
+    fn get_importers(&self, file_path: &Path) -> SemanticResult<Vec<PathBuf>> {
+        let lang = Self::detect_language(file_path);
+        match self.get_or_init(lang) {
+            Some(provider) => provider.get_importers(file_path),
+            None => Ok(Vec::new()), // Unsupported language
+        }
+    }
+
+    fn get_dependents(&self, file_paths: &[PathBuf]) -> SemanticResult<Vec<PathBuf>> {
+        let provider = file_paths
+            .iter()
+            .find_map(|path| self.get_or_init(Self::detect_language(path)));
+        match provider {
+            Some(provider) => provider.get_dependents(file_paths),
+            None => Ok(Vec::new()), // No supported file
+        }
+    }
+
     fn supports_language(&self, lang: &str) -> bool {
         SemanticFactory::supports_language(lang)
     }
@@ -292,6 +359,14 @@ mod tests {
             LazySemanticProvider::detect_language(Path::new("test.css")),
             "css"
         );
+        assert_eq!(
+            LazySemanticProvider::detect_language(Path::new("main.go")),
+            "go"
+        );
+        assert_eq!(
+            LazySemanticProvider::detect_language(Path::new("Main.java")),
+            "java"
+        );
         assert_eq!(
             LazySemanticProvider::detect_language(Path::new("test.unknown")),
             "unknown"
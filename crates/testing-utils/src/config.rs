@@ -61,6 +61,10 @@ pub struct TestOptions {
     pub strictness: Strictness,
     pub language: Option<String>,
     pub expected_extension: Option<String>,
+    /// Fail tests whose output has syntax errors their input did not
+    pub check_syntax: bool,
+    /// Re-run the transform on each expected output and require no change
+    pub check_idempotent: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +72,10 @@ pub enum ReporterType {
     Console,
     Json,
     Terse,
+    /// JUnit XML, for CI servers such as Jenkins and GitLab
+    Junit,
+    /// Test Anything Protocol (version 13)
+    Tap,
 }
 
 impl FromStr for ReporterType {
@@ -78,13 +86,26 @@ impl FromStr for ReporterType {
             "console" => Ok(ReporterType::Console),
             "json" => Ok(ReporterType::Json),
             "terse" => Ok(ReporterType::Terse),
+            "junit" => Ok(ReporterType::Junit),
+            "tap" => Ok(ReporterType::Tap),
             _ => Err(format!(
-                "Invalid reporter type: {s}. Valid options: console, json, terse"
+                "Invalid reporter type: {s}. Valid options: console, json, terse, junit, tap"
             )),
         }
     }
 }
 
+impl ReporterType {
+    /// Whether the report is meant to be read by tools. Other output should
+    /// then go to stderr so the report can be redirected to a file.
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(
+            self,
+            ReporterType::Json | ReporterType::Junit | ReporterType::Tap
+        )
+    }
+}
+
 impl TestOptions {
     pub fn to_libtest_args(&self) -> Arguments {
         Arguments {
@@ -98,9 +119,14 @@ impl TestOptions {
             format: Some(match self.reporter {
                 ReporterType::Console => libtest_mimic::FormatSetting::Pretty,
                 ReporterType::Json => libtest_mimic::FormatSetting::Json,
-                ReporterType::Terse => libtest_mimic::FormatSetting::Terse,
+                ReporterType::Terse | ReporterType::Junit | ReporterType::Tap => {
+                    libtest_mimic::FormatSetting::Terse
+                }
             }),
-            quiet: matches!(self.reporter, ReporterType::Terse),
+            quiet: matches!(
+                self.reporter,
+                ReporterType::Terse | ReporterType::Junit | ReporterType::Tap
+            ),
             ..Default::default()
         }
     }
@@ -0,0 +1,411 @@
+//! Golden-diff snapshots of a codemod run over a corpus.
+//!
+//! A corpus snapshot is the full set of diffs a codemod produces on a pinned
+//! checkout, written as one unified diff with files in path order. Comparing a
+//! new run against the approved snapshot hunk by hunk shows how a change to
+//! the codemod changes its output at scale: the "diff of diffs".
+//!
+//! Files the codemod failed on are recorded as `# error <path>: <message>`
+//! lines ahead of the diffs, so new failures are regressions too.
+
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+
+/// Context lines around each hunk in a snapshot.
+const SNAPSHOT_CONTEXT_LINES: usize = 3;
+
+const ERROR_PREFIX: &str = "# error ";
+
+/// What the codemod did to one corpus file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorpusFileResult {
+    /// The file was rewritten
+    Modified {
+        original: String,
+        modified: String,
+        /// New path, relative to the corpus root, when the file was renamed
+        renamed_to: Option<String>,
+    },
+    /// The codemod failed on the file
+    Failed(String),
+}
+
+/// Render the snapshot of a corpus run. `results` maps corpus-relative paths
+/// to what happened to them; unchanged files are left out.
+pub fn render_corpus_snapshot(
+    source: &str,
+    results: &BTreeMap<String, CorpusFileResult>,
+) -> String {
+    let mut header = format!("# corpus {source}\n");
+    let mut diffs = String::new();
+    for (path, result) in results {
+        match result {
+            CorpusFileResult::Modified {
+                original,
+                modified,
+                renamed_to,
+            } => {
+                let new_path = renamed_to.as_deref().unwrap_or(path);
+                let diff = TextDiff::from_lines(original, modified);
+                let _ = write!(
+                    diffs,
+                    "{}",
+                    diff.unified_diff()
+                        .context_radius(SNAPSHOT_CONTEXT_LINES)
+                        .header(&format!("a/{path}"), &format!("b/{new_path}"))
+                );
+                if renamed_to.is_some() && original == modified {
+                    let _ = writeln!(diffs, "--- a/{path}\n+++ b/{new_path}");
+                }
+            }
+            CorpusFileResult::Failed(message) => {
+                let message = message.lines().next().unwrap_or_default().trim();
+                let _ = writeln!(header, "{ERROR_PREFIX}{path}: {message}");
+            }
+        }
+    }
+    if diffs.is_empty() {
+        header
+    } else {
+        format!("{header}\n{diffs}")
+    }
+}
+
+/// One `@@` hunk of a file's diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotHunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    /// Lines after the header, newline-terminated
+    pub body: String,
+}
+
+impl SnapshotHunk {
+    /// Start line of the hunk in the original file. Hunks of two runs over
+    /// the same checkout that start on the same line touch the same code.
+    fn original_start(&self) -> Option<&str> {
+        self.header.strip_prefix("@@ -")?.split([',', ' ']).next()
+    }
+}
+
+/// A parsed corpus snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusSnapshot {
+    /// Hunks by file, keyed on the original path
+    pub files: BTreeMap<String, Vec<SnapshotHunk>>,
+    /// First line of the error, by file
+    pub errors: BTreeMap<String, String>,
+}
+
+impl CorpusSnapshot {
+    pub fn parse(text: &str) -> Self {
+        let mut snapshot = Self::default();
+        let mut current: Option<String> = None;
+        let mut lines = text.lines().peekable();
+        while let Some(line) = lines.next() {
+            if let Some(error) = line.strip_prefix(ERROR_PREFIX) {
+                if let Some((path, message)) = error.split_once(": ") {
+                    snapshot
+                        .errors
+                        .insert(path.to_string(), message.to_string());
+                }
+            } else if let Some(path) = line
+                .strip_prefix("--- a/")
+                .filter(|_| lines.peek().is_some_and(|next| next.starts_with("+++ b/")))
+            {
+                lines.next();
+                snapshot.files.entry(path.to_string()).or_default();
+                current = Some(path.to_string());
+            } else if line.starts_with("@@") {
+                if let Some(hunks) = current.as_ref().and_then(|p| snapshot.files.get_mut(p)) {
+                    hunks.push(SnapshotHunk {
+                        header: line.to_string(),
+                        body: String::new(),
+                    });
+                }
+            } else if let Some(hunk) = current
+                .as_ref()
+                .and_then(|p| snapshot.files.get_mut(p))
+                .and_then(|hunks| hunks.last_mut())
+            {
+                hunk.body.push_str(line);
+                hunk.body.push('\n');
+            }
+        }
+        snapshot
+    }
+}
+
+/// How one file's result differs from the approved snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorpusFileDelta {
+    /// The codemod now changes a file it left alone
+    NewlyChanged { hunks: usize },
+    /// The codemod no longer changes the file
+    NoLongerChanged { hunks: usize },
+    /// Both runs change the file, differently
+    Hunks {
+        added: Vec<SnapshotHunk>,
+        removed: Vec<SnapshotHunk>,
+        /// Approved and new hunk starting on the same line
+        changed: Vec<(SnapshotHunk, SnapshotHunk)>,
+    },
+    /// The codemod now fails on the file
+    NewError(String),
+    /// The codemod no longer fails on the file
+    ErrorFixed(String),
+    /// The codemod fails on the file with a different error
+    ErrorChanged { approved: String, actual: String },
+}
+
+/// Differences between an approved corpus snapshot and a new run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusComparison {
+    pub files: BTreeMap<String, Vec<CorpusFileDelta>>,
+}
+
+impl CorpusComparison {
+    pub fn compare(approved: &CorpusSnapshot, actual: &CorpusSnapshot) -> Self {
+        let mut comparison = Self::default();
+        let mut push = |path: &str, delta: CorpusFileDelta| {
+            comparison
+                .files
+                .entry(path.to_string())
+                .or_default()
+                .push(delta);
+        };
+
+        for (path, hunks) in &actual.files {
+            match approved.files.get(path) {
+                None => push(path, CorpusFileDelta::NewlyChanged { hunks: hunks.len() }),
+                Some(approved_hunks) if approved_hunks != hunks => {
+                    push(path, compare_hunks(approved_hunks, hunks))
+                }
+                Some(_) => {}
+            }
+        }
+        for (path, hunks) in &approved.files {
+            if !actual.files.contains_key(path) {
+                push(
+                    path,
+                    CorpusFileDelta::NoLongerChanged { hunks: hunks.len() },
+                );
+            }
+        }
+
+        for (path, message) in &actual.errors {
+            match approved.errors.get(path) {
+                None => push(path, CorpusFileDelta::NewError(message.clone())),
+                Some(approved) if approved != message => push(
+                    path,
+                    CorpusFileDelta::ErrorChanged {
+                        approved: approved.clone(),
+                        actual: message.clone(),
+                    },
+                ),
+                Some(_) => {}
+            }
+        }
+        for (path, message) in &approved.errors {
+            if !actual.errors.contains_key(path) {
+                push(path, CorpusFileDelta::ErrorFixed(message.clone()));
+            }
+        }
+
+        comparison
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+fn compare_hunks(approved: &[SnapshotHunk], actual: &[SnapshotHunk]) -> CorpusFileDelta {
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut unmatched: Vec<&SnapshotHunk> = approved.iter().collect();
+
+    for hunk in actual {
+        if let Some(index) = unmatched.iter().position(|approved| *approved == hunk) {
+            unmatched.remove(index);
+        } else if let Some(index) = unmatched.iter().position(|approved| {
+            approved.original_start().is_some()
+                && approved.original_start() == hunk.original_start()
+        }) {
+            changed.push((unmatched.remove(index).clone(), hunk.clone()));
+        } else {
+            added.push(hunk.clone());
+        }
+    }
+
+    CorpusFileDelta::Hunks {
+        added,
+        removed: unmatched.into_iter().cloned().collect(),
+        changed,
+    }
+}
+
+impl fmt::Display for CorpusComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Corpus output differs from the approved snapshot in {} file(s):",
+            self.files.len()
+        )?;
+        for (path, deltas) in &self.files {
+            for delta in deltas {
+                write_delta(f, path, delta)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_delta(f: &mut fmt::Formatter<'_>, path: &str, delta: &CorpusFileDelta) -> fmt::Result {
+    match delta {
+        CorpusFileDelta::NewlyChanged { hunks } => {
+            writeln!(f, "\n{path}: newly changed ({hunks} hunk(s))")
+        }
+        CorpusFileDelta::NoLongerChanged { hunks } => {
+            writeln!(f, "\n{path}: no longer changed ({hunks} hunk(s))")
+        }
+        CorpusFileDelta::Hunks {
+            added,
+            removed,
+            changed,
+        } => {
+            writeln!(
+                f,
+                "\n{path}: {} hunk(s) added, {} removed, {} changed",
+                added.len(),
+                removed.len(),
+                changed.len()
+            )?;
+            for hunk in added {
+                writeln!(f, "  added {}", hunk.header)?;
+                write_indented(f, &hunk.body, "    ")?;
+            }
+            for hunk in removed {
+                writeln!(f, "  removed {}", hunk.header)?;
+                write_indented(f, &hunk.body, "    ")?;
+            }
+            for (approved, actual) in changed {
+                writeln!(f, "  changed {} -> {}", approved.header, actual.header)?;
+                let diff = TextDiff::from_lines(&approved.body, &actual.body);
+                for change in diff.iter_all_changes() {
+                    write!(f, "   {}{}", change.tag(), change)?;
+                }
+            }
+            Ok(())
+        }
+        CorpusFileDelta::NewError(message) => writeln!(f, "\n{path}: now fails: {message}"),
+        CorpusFileDelta::ErrorFixed(message) => {
+            writeln!(f, "\n{path}: no longer fails (was: {message})")
+        }
+        CorpusFileDelta::ErrorChanged { approved, actual } => {
+            writeln!(f, "\n{path}: fails differently: {actual} (was: {approved})")
+        }
+    }
+}
+
+fn write_indented(f: &mut fmt::Formatter<'_>, text: &str, indent: &str) -> fmt::Result {
+    for line in text.lines() {
+        writeln!(f, "{indent}{line}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified(original: &str, modified: &str) -> CorpusFileResult {
+        CorpusFileResult::Modified {
+            original: original.to_string(),
+            modified: modified.to_string(),
+            renamed_to: None,
+        }
+    }
+
+    fn snapshot(results: &[(&str, CorpusFileResult)]) -> CorpusSnapshot {
+        let results = results
+            .iter()
+            .map(|(path, result)| (path.to_string(), result.clone()))
+            .collect();
+        CorpusSnapshot::parse(&render_corpus_snapshot("fixture", &results))
+    }
+
+    const ORIGINAL: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+
+    #[test]
+    fn rendered_snapshot_parses_back() {
+        let parsed = snapshot(&[
+            (
+                "src/a.js",
+                modified(ORIGINAL, &ORIGINAL.replace("b\n", "B\n")),
+            ),
+            (
+                "src/b.js",
+                CorpusFileResult::Failed("boom\nstack".to_string()),
+            ),
+        ]);
+
+        assert_eq!(parsed.files["src/a.js"].len(), 1);
+        assert!(parsed.files["src/a.js"][0].body.contains("+B\n"));
+        assert_eq!(parsed.errors["src/b.js"], "boom");
+    }
+
+    #[test]
+    fn identical_runs_have_no_differences() {
+        let run = snapshot(&[(
+            "src/a.js",
+            modified(ORIGINAL, &ORIGINAL.replace("b\n", "B\n")),
+        )]);
+        assert!(CorpusComparison::compare(&run, &run).is_empty());
+    }
+
+    #[test]
+    fn hunks_are_reported_as_added_removed_or_changed() {
+        let approved = snapshot(&[
+            (
+                "src/a.js",
+                modified(ORIGINAL, &ORIGINAL.replace("b\n", "B\n")),
+            ),
+            ("src/gone.js", modified("x\n", "y\n")),
+        ]);
+        let actual = snapshot(&[
+            (
+                "src/a.js",
+                modified(
+                    ORIGINAL,
+                    &ORIGINAL.replace("b\n", "BB\n").replace("k\n", "K\n"),
+                ),
+            ),
+            ("src/new.js", CorpusFileResult::Failed("boom".to_string())),
+        ]);
+
+        let comparison = CorpusComparison::compare(&approved, &actual);
+        let CorpusFileDelta::Hunks {
+            added,
+            removed,
+            changed,
+        } = &comparison.files["src/a.js"][0]
+        else {
+            panic!("expected hunk changes");
+        };
+        assert_eq!((added.len(), removed.len(), changed.len()), (1, 0, 1));
+        assert_eq!(
+            comparison.files["src/gone.js"],
+            vec![CorpusFileDelta::NoLongerChanged { hunks: 1 }]
+        );
+        assert_eq!(
+            comparison.files["src/new.js"],
+            vec![CorpusFileDelta::NewError("boom".to_string())]
+        );
+
+        let report = comparison.to_string();
+        assert!(report.contains("src/a.js: 1 hunk(s) added, 0 removed, 1 changed"));
+        assert!(report.contains("+BB"));
+    }
+}
@@ -0,0 +1,421 @@
+//! Per-fixture settings from a `test.yaml` file next to the fixture's input.
+//!
+//! ```yaml
+//! strictness: ast
+//! semantic: workspace
+//! params:
+//!   flavor: react
+//! expect:
+//!   outputs:
+//!     migrated: "true"
+//! variants:
+//!   - name: vue
+//!     params:
+//!       flavor: vue
+//! ```
+//!
+//! A fixture with `variants` runs once per variant. Variant settings are
+//! merged over the fixture's, and each variant can have its own expected
+//! output (`expected.<variant>.<ext>`, or `expected.<variant>/` for
+//! directory fixtures).
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::Strictness;
+use crate::fixtures::TestError;
+
+/// File names of the fixture configuration, in order of precedence.
+pub const FIXTURE_CONFIG_FILES: [&str; 2] = ["test.yaml", "test.yml"];
+
+/// Semantic analysis mode for a fixture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemanticMode {
+    /// Single-file analysis
+    File,
+    /// Workspace-wide analysis rooted at the fixture
+    Workspace,
+}
+
+/// `skip: true` or `skip: "reason"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum SkipMarker {
+    Flag(bool),
+    Reason(String),
+}
+
+impl SkipMarker {
+    fn reason(&self) -> Option<String> {
+        match self {
+            SkipMarker::Flag(true) => Some(String::new()),
+            SkipMarker::Flag(false) => None,
+            SkipMarker::Reason(reason) => Some(reason.clone()),
+        }
+    }
+}
+
+/// Contents of a fixture's `test.yaml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureConfig {
+    /// Path of the file the configuration was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Don't run the fixture
+    pub skip: Option<SkipMarker>,
+    /// Only run fixtures marked `only`
+    #[serde(default)]
+    pub only: bool,
+    /// Comparison strictness, overriding `--strictness`
+    pub strictness: Option<String>,
+    /// Semantic analysis mode, overriding `--semantic-workspace`
+    pub semantic: Option<SemanticMode>,
+    /// Parameters passed to the codemod
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    /// Matrix values passed to the codemod
+    #[serde(default)]
+    pub matrix: HashMap<String, Value>,
+    /// Shared state set before the codemod runs
+    #[serde(default)]
+    pub state: HashMap<String, Value>,
+    /// Expectations beyond the output files
+    #[serde(default)]
+    pub expect: FixtureExpectations,
+    /// Parameterizations to run the fixture with
+    #[serde(default)]
+    pub variants: Vec<FixtureVariant>,
+}
+
+/// One parameterization of a fixture.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureVariant {
+    pub name: String,
+    pub skip: Option<SkipMarker>,
+    #[serde(default)]
+    pub only: bool,
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    #[serde(default)]
+    pub matrix: HashMap<String, Value>,
+    #[serde(default)]
+    pub state: HashMap<String, Value>,
+    /// Expected output file (or directory), relative to the fixture
+    pub expected: Option<PathBuf>,
+    #[serde(default)]
+    pub expect: FixtureExpectations,
+}
+
+/// Expected results of running the codemod, other than the output files.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureExpectations {
+    /// The codemod is expected to fail
+    pub error: Option<bool>,
+    /// Step outputs set with `setStepOutput`
+    pub outputs: Option<BTreeMap<String, String>>,
+    /// Shared state values after the run
+    pub state: Option<BTreeMap<String, Value>>,
+    /// Total count of each metric
+    pub metrics: Option<BTreeMap<String, u64>>,
+}
+
+impl FixtureExpectations {
+    /// Overlay `other`: its expectations replace these key by key.
+    fn merge(&mut self, other: &FixtureExpectations) {
+        if other.error.is_some() {
+            self.error = other.error;
+        }
+        fn merge_map<V: Clone>(
+            base: &mut Option<BTreeMap<String, V>>,
+            other: &Option<BTreeMap<String, V>>,
+        ) {
+            if let Some(other) = other {
+                base.get_or_insert_with(BTreeMap::new).extend(other.clone());
+            }
+        }
+        merge_map(&mut self.outputs, &other.outputs);
+        merge_map(&mut self.state, &other.state);
+        merge_map(&mut self.metrics, &other.metrics);
+    }
+
+    /// Whether there is anything to check after the codemod ran.
+    pub fn has_checks(&self) -> bool {
+        self.outputs.is_some() || self.state.is_some() || self.metrics.is_some()
+    }
+
+    /// Compare the step outputs, shared state and metric totals of a run
+    /// with the expectations. Only the listed keys are compared; a metric
+    /// expected to be 0 may be missing.
+    pub fn check(
+        &self,
+        outputs: &HashMap<String, String>,
+        state: &HashMap<String, Value>,
+        metric_totals: &HashMap<String, u64>,
+    ) -> Result<(), String> {
+        let mut mismatches = Vec::new();
+
+        for (name, expected) in self.outputs.iter().flatten() {
+            match outputs.get(name) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => mismatches.push(format!(
+                    "step output '{name}': expected {expected:?}, got {actual:?}"
+                )),
+                None => mismatches.push(format!(
+                    "step output '{name}': expected {expected:?}, but it was not set"
+                )),
+            }
+        }
+        for (key, expected) in self.state.iter().flatten() {
+            match state.get(key) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => {
+                    mismatches.push(format!("state '{key}': expected {expected}, got {actual}"))
+                }
+                None => mismatches.push(format!(
+                    "state '{key}': expected {expected}, but it was not set"
+                )),
+            }
+        }
+        for (name, expected) in self.metrics.iter().flatten() {
+            let actual = metric_totals.get(name).copied().unwrap_or(0);
+            if actual != *expected {
+                mismatches.push(format!(
+                    "metric '{name}': expected a total of {expected}, got {actual}"
+                ));
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expectations from test.yaml not met:\n  {}",
+                mismatches.join("\n  ")
+            ))
+        }
+    }
+}
+
+/// Settings for one test case: a fixture, or one variant of it.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureSettings {
+    /// Name of the variant, if the fixture has variants
+    pub variant: Option<String>,
+    /// Reason the test is skipped (possibly empty), if it is
+    pub skip: Option<String>,
+    pub only: bool,
+    pub strictness: Option<Strictness>,
+    pub semantic: Option<SemanticMode>,
+    pub params: HashMap<String, Value>,
+    pub matrix: HashMap<String, Value>,
+    pub state: HashMap<String, Value>,
+    pub expect: FixtureExpectations,
+    /// Expected output of the variant: relative to the fixture as written
+    /// in `test.yaml`, resolved to the variant's own file at discovery
+    pub expected: Option<PathBuf>,
+}
+
+impl FixtureConfig {
+    /// Load the configuration of the fixture in `fixture_dir`, if it has one.
+    pub fn load(fixture_dir: &Path) -> Result<Option<FixtureConfig>, TestError> {
+        let Some(path) = FIXTURE_CONFIG_FILES
+            .iter()
+            .map(|name| fixture_dir.join(name))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&path)?;
+        let mut config: FixtureConfig =
+            serde_yaml::from_str(&content).map_err(|e| TestError::InvalidFixtureConfig {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
+        config.path = path;
+        Ok(Some(config))
+    }
+
+    /// Resolve the settings of every test case of the fixture: one per
+    /// variant, or a single one without variants.
+    pub fn settings(&self) -> Result<Vec<FixtureSettings>, TestError> {
+        let invalid = |message: String| TestError::InvalidFixtureConfig {
+            path: self.path.clone(),
+            message,
+        };
+
+        let strictness = self
+            .strictness
+            .as_deref()
+            .map(str::parse::<Strictness>)
+            .transpose()
+            .map_err(invalid)?;
+        let base = FixtureSettings {
+            variant: None,
+            skip: self.skip.as_ref().and_then(SkipMarker::reason),
+            only: self.only,
+            strictness,
+            semantic: self.semantic,
+            params: self.params.clone(),
+            matrix: self.matrix.clone(),
+            state: self.state.clone(),
+            expect: self.expect.clone(),
+            expected: None,
+        };
+        if self.variants.is_empty() {
+            return Ok(vec![base]);
+        }
+
+        let mut names = HashSet::new();
+        self.variants
+            .iter()
+            .map(|variant| {
+                if variant.name.is_empty()
+                    || variant.name.contains(['/', '\\', '[', ']'])
+                    || !names.insert(variant.name.as_str())
+                {
+                    return Err(invalid(format!(
+                        "variant names must be unique and must not contain '/', '\\', '[' or ']': {:?}",
+                        variant.name
+                    )));
+                }
+
+                let mut settings = base.clone();
+                settings.variant = Some(variant.name.clone());
+                if let Some(reason) = variant.skip.as_ref().and_then(SkipMarker::reason) {
+                    settings.skip = Some(reason);
+                }
+                settings.only |= variant.only;
+                settings.params.extend(variant.params.clone());
+                settings.matrix.extend(variant.matrix.clone());
+                settings.state.extend(variant.state.clone());
+                settings.expect.merge(&variant.expect);
+                settings.expected = variant.expected.clone();
+                Ok(settings)
+            })
+            .collect()
+    }
+}
+
+impl FixtureSettings {
+    /// Name of the test case for a fixture named `fixture_name`.
+    pub fn case_name(&self, fixture_name: &str) -> String {
+        match &self.variant {
+            Some(variant) => format!("{fixture_name}[{variant}]"),
+            None => fixture_name.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(yaml: &str) -> FixtureConfig {
+        serde_yaml::from_str(yaml).expect("valid test.yaml")
+    }
+
+    #[test]
+    fn variants_are_merged_over_the_fixture() {
+        let config = parse(
+            r#"
+strictness: ast
+semantic: workspace
+params: { flavor: react, strict: true }
+expect:
+  outputs: { migrated: "true" }
+variants:
+  - name: vue
+    params: { flavor: vue }
+    expect:
+      metrics: { replaced: 2 }
+  - name: legacy
+    skip: not supported yet
+    expected: expected.old.ts
+"#,
+        );
+
+        let settings = config.settings().unwrap();
+        assert_eq!(settings.len(), 2);
+
+        let vue = &settings[0];
+        assert_eq!(vue.case_name("rename"), "rename[vue]");
+        assert_eq!(vue.strictness, Some(Strictness::Ast));
+        assert_eq!(vue.semantic, Some(SemanticMode::Workspace));
+        assert_eq!(vue.params["flavor"], json!("vue"));
+        assert_eq!(vue.params["strict"], json!(true));
+        assert!(vue.expect.outputs.is_some());
+        assert_eq!(vue.expect.metrics.as_ref().unwrap()["replaced"], 2);
+        assert_eq!(vue.skip, None);
+
+        let legacy = &settings[1];
+        assert_eq!(legacy.skip.as_deref(), Some("not supported yet"));
+        assert_eq!(legacy.expected, Some(PathBuf::from("expected.old.ts")));
+        assert_eq!(legacy.params["flavor"], json!("react"));
+    }
+
+    #[test]
+    fn fixture_without_variants_has_one_case() {
+        let config = parse("skip: true\nonly: true\n");
+        let settings = config.settings().unwrap();
+
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings[0].case_name("rename"), "rename");
+        assert_eq!(settings[0].skip.as_deref(), Some(""));
+        assert!(settings[0].only);
+    }
+
+    #[test]
+    fn invalid_settings_are_reported() {
+        assert!(serde_yaml::from_str::<FixtureConfig>("parms: {}").is_err());
+
+        let config = parse("strictness: fuzzy\n");
+        assert!(matches!(
+            config.settings(),
+            Err(TestError::InvalidFixtureConfig { .. })
+        ));
+
+        let config = parse("variants:\n  - name: a\n  - name: a\n");
+        assert!(config.settings().is_err());
+    }
+
+    #[test]
+    fn expectations_report_every_mismatch() {
+        let expect = FixtureExpectations {
+            error: None,
+            outputs: Some(BTreeMap::from([("done".to_string(), "yes".to_string())])),
+            state: Some(BTreeMap::from([("count".to_string(), json!(2))])),
+            metrics: Some(BTreeMap::from([
+                ("replaced".to_string(), 3),
+                ("skipped".to_string(), 0),
+            ])),
+        };
+        assert!(expect.has_checks());
+
+        let outputs = HashMap::from([("done".to_string(), "yes".to_string())]);
+        let state = HashMap::from([("count".to_string(), json!(2))]);
+        let metrics = HashMap::from([("replaced".to_string(), 3)]);
+        assert_eq!(expect.check(&outputs, &state, &metrics), Ok(()));
+
+        let error = expect
+            .check(
+                &HashMap::new(),
+                &state,
+                &HashMap::from([("replaced".to_string(), 1)]),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "Expectations from test.yaml not met:\n  \
+             step output 'done': expected \"yes\", but it was not set\n  \
+             metric 'replaced': expected a total of 3, got 1"
+        );
+    }
+}
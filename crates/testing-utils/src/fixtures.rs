@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::fixture_config::{FixtureConfig, FixtureSettings};
+
 /// Represents a single transformation test case with input and expected output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformationTestCase {
@@ -31,6 +33,8 @@ pub struct FileSystemTestCase {
     pub should_error: bool,
     pub layout: FileSystemTestCaseLayout,
     pub entrypoint_files: Vec<PathBuf>,
+    /// Settings from the fixture's `test.yaml`
+    pub settings: FixtureSettings,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -60,6 +64,7 @@ pub struct UnifiedTestCase {
     pub input_path: Option<PathBuf>,
     pub logical_input_path: Option<PathBuf>,
     pub expected_output_path: Option<PathBuf>,
+    pub settings: FixtureSettings,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -92,6 +97,9 @@ pub enum TestError {
     #[error("Invalid file path: {0}")]
     InvalidFilePath(PathBuf),
 
+    #[error("Invalid fixture configuration {path}: {message}")]
+    InvalidFixtureConfig { path: PathBuf, message: String },
+
     #[error("Cannot update snapshots for test '{test_name}' - it's not a filesystem-based test")]
     SnapshotUpdateNotSupported { test_name: String },
 
@@ -127,6 +135,7 @@ impl TestSource {
                         input_path: None,    // Direct cases don't have a file path
                         logical_input_path: None,
                         expected_output_path: None, // Direct cases don't have an expected output file
+                        settings: FixtureSettings::default(),
                     })
                     .collect())
             }
@@ -156,6 +165,10 @@ impl FileSystemTestCase {
                         expected_file.content.clone(),
                         Some(expected_file.path.clone()),
                     ),
+                    // A variant's own expected file is created by --update-snapshots
+                    None if fs_case.settings.expected.is_some() => {
+                        (String::new(), fs_case.settings.expected.clone())
+                    }
                     None => {
                         let expected_path =
                             match build_expected_path(&input_file.path, expected_extension) {
@@ -182,6 +195,7 @@ impl FileSystemTestCase {
                     input_path: Some(input_file.path.clone()),
                     logical_input_path: Some(input_file.path.clone()),
                     expected_output_path: expected_path,
+                    settings: fs_case.settings.clone(),
                 });
             }
         }
@@ -203,7 +217,7 @@ impl FileSystemTestCase {
 
             if path.is_dir() {
                 if let Ok(test_case) = Self::from_directory(&path, extensions, expected_extension) {
-                    test_cases.push(test_case);
+                    test_cases.extend(test_case.with_fixture_config()?);
                 }
             }
         }
@@ -273,6 +287,7 @@ impl FileSystemTestCase {
                 should_error,
                 layout: FileSystemTestCaseLayout::SingleFile,
                 entrypoint_files: Vec::new(),
+                settings: FixtureSettings::default(),
             });
         }
 
@@ -301,12 +316,69 @@ impl FileSystemTestCase {
                     expected_dir,
                 },
                 entrypoint_files,
+                settings: FixtureSettings::default(),
             });
         }
 
         Err(TestError::InvalidTestStructure(test_dir.to_path_buf()))
     }
 
+    /// Apply the fixture's `test.yaml`, if any: one test case per variant,
+    /// or the fixture itself with its settings.
+    fn with_fixture_config(self) -> Result<Vec<FileSystemTestCase>, TestError> {
+        let Some(config) = FixtureConfig::load(&self.path)? else {
+            return Ok(vec![self]);
+        };
+
+        config
+            .settings()?
+            .into_iter()
+            .map(|settings| self.clone().with_settings(settings))
+            .collect()
+    }
+
+    fn with_settings(mut self, mut settings: FixtureSettings) -> Result<Self, TestError> {
+        self.name = settings.case_name(&self.name);
+        self.should_error |= settings.expect.error == Some(true);
+
+        if let Some(variant) = &settings.variant {
+            let explicit = settings.expected.take().map(|path| self.path.join(path));
+            match &mut self.layout {
+                FileSystemTestCaseLayout::SingleFile => {
+                    for key in self.input_files.keys() {
+                        let ext = key.extension().and_then(|e| e.to_str()).unwrap_or("");
+                        let candidate = explicit
+                            .clone()
+                            .unwrap_or_else(|| self.path.join(format!("expected.{variant}.{ext}")));
+                        if candidate.is_file() {
+                            self.expected_files
+                                .insert(key.clone(), TestFile::from_path(&candidate)?);
+                            settings.expected = Some(candidate);
+                        } else if explicit.is_some() {
+                            self.expected_files.remove(key);
+                            settings.expected = Some(candidate);
+                        }
+                    }
+                }
+                FileSystemTestCaseLayout::DirectorySnapshot { expected_dir, .. } => {
+                    let candidate = explicit
+                        .clone()
+                        .unwrap_or_else(|| self.path.join(format!("expected.{variant}")));
+                    if candidate.is_dir() {
+                        self.expected_files = collect_files_in_directory(&candidate, None)?;
+                        *expected_dir = candidate;
+                    } else if explicit.is_some() {
+                        self.expected_files = HashMap::new();
+                        *expected_dir = candidate;
+                    }
+                }
+            }
+        }
+
+        self.settings = settings;
+        Ok(self)
+    }
+
     /// Check if expected files exist, or return an error that can be handled by --update-snapshots
     pub fn validate_expected_files(&self) -> Result<(), TestError> {
         if matches!(
@@ -1,9 +1,15 @@
 pub mod config;
+pub mod corpus;
+pub mod fixture_config;
 pub mod fixtures;
+pub mod report;
 pub mod runner;
 pub mod strictness;
 
 pub use config::*;
+pub use corpus::*;
+pub use fixture_config::*;
 pub use fixtures::*;
+pub use report::*;
 pub use runner::*;
 pub use strictness::*;
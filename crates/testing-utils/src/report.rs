@@ -0,0 +1,278 @@
+//! JUnit XML and TAP reports built from a [`TestSummary`].
+//!
+//! Both include the duration of every test case, the strictness level used
+//! for comparisons, skipped tests and the full failure message, which holds
+//! the diff between the expected and actual output.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::config::{ReporterType, Strictness};
+use crate::runner::TestSummary;
+
+/// Name of the test suite in reports.
+const SUITE_NAME: &str = "jssg";
+
+/// Render `summary` in the format of `reporter`, or `None` for reporters
+/// that are printed by libtest while the tests run.
+pub fn render_report(
+    reporter: &ReporterType,
+    summary: &TestSummary,
+    strictness: Strictness,
+) -> Option<String> {
+    match reporter {
+        ReporterType::Junit => Some(junit_report(summary, strictness)),
+        ReporterType::Tap => Some(tap_report(summary, strictness)),
+        ReporterType::Console | ReporterType::Json | ReporterType::Terse => None,
+    }
+}
+
+/// Render a JUnit XML report.
+pub fn junit_report(summary: &TestSummary, strictness: Strictness) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\"",
+        summary.total,
+        summary.failed,
+        summary.errors,
+        summary.ignored,
+        seconds(summary.duration)
+    );
+    let _ = writeln!(xml, "<testsuites name=\"{SUITE_NAME}\" {counts}>");
+    let _ = writeln!(xml, "  <testsuite name=\"{SUITE_NAME}\" {counts}>");
+    let _ = writeln!(xml, "    <properties>");
+    let _ = writeln!(
+        xml,
+        "      <property name=\"strictness\" value=\"{strictness}\"/>"
+    );
+    let _ = writeln!(xml, "    </properties>");
+
+    for detail in &summary.details {
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{SUITE_NAME}\" time=\"{}\"",
+            escape_xml(&detail.name),
+            seconds(detail.duration)
+        );
+        // Fixtures can override the suite's strictness in their test.yaml
+        let own_strictness = detail.strictness != strictness;
+        if detail.passed && detail.skipped.is_none() && !own_strictness {
+            xml.push_str("/>\n");
+            continue;
+        }
+
+        let _ = writeln!(xml, ">");
+        if own_strictness {
+            let _ = writeln!(
+                xml,
+                "      <properties><property name=\"strictness\" value=\"{}\"/></properties>",
+                detail.strictness
+            );
+        }
+        if let Some(reason) = &detail.skipped {
+            let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape_xml(reason));
+        } else if !detail.passed {
+            let message = detail.error_message.as_deref().unwrap_or("Test failed");
+            let first_line = message.lines().next().unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"failure\">{}</failure>",
+                escape_xml(first_line),
+                escape_xml(message)
+            );
+        }
+        let _ = writeln!(xml, "    </testcase>");
+    }
+
+    let _ = writeln!(xml, "  </testsuite>");
+    xml.push_str("</testsuites>");
+    xml
+}
+
+/// Render a TAP version 13 report. Every test point has a YAML block with
+/// its duration and, for failures, the failure message. Skipped tests are
+/// marked with a `SKIP` directive.
+pub fn tap_report(summary: &TestSummary, strictness: Strictness) -> String {
+    let mut tap = String::from("TAP version 13\n");
+    let _ = writeln!(tap, "1..{}", summary.details.len());
+    let _ = writeln!(tap, "# strictness: {strictness}");
+
+    for (index, detail) in summary.details.iter().enumerate() {
+        let status = if detail.passed { "ok" } else { "not ok" };
+        // `#` starts a directive in the description
+        let name = detail.name.replace('#', "\\#");
+        let directive = match &detail.skipped {
+            Some(reason) if reason.is_empty() => " # SKIP".to_string(),
+            Some(reason) => format!(" # SKIP {}", reason.replace('\n', " ")),
+            None => String::new(),
+        };
+        let _ = writeln!(tap, "{status} {} - {name}{directive}", index + 1);
+        let _ = writeln!(tap, "  ---");
+        let _ = writeln!(tap, "  duration_ms: {}", detail.duration.as_millis());
+        if !detail.passed {
+            let _ = writeln!(tap, "  strictness: {}", detail.strictness);
+            let _ = writeln!(tap, "  message: |-");
+            let message = detail.error_message.as_deref().unwrap_or("Test failed");
+            for line in message.lines() {
+                let _ = writeln!(tap, "    {line}");
+            }
+        }
+        let _ = writeln!(tap, "  ...");
+    }
+
+    let _ = write!(
+        tap,
+        "# tests {}, pass {}, fail {}",
+        summary.details.len(),
+        summary.passed,
+        summary.failed
+    );
+    if summary.ignored > 0 {
+        let _ = write!(tap, ", skip {}", summary.ignored);
+    }
+    tap
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+/// Escape text for XML attributes and content, dropping control characters
+/// XML 1.0 can't represent.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            ch if ch < ' ' => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::TestResultDetail;
+
+    fn summary() -> TestSummary {
+        TestSummary::from_details(
+            vec![
+                TestResultDetail {
+                    name: "renames-import".to_string(),
+                    passed: true,
+                    error_message: None,
+                    duration: Duration::from_millis(12),
+                    skipped: None,
+                    strictness: Strictness::Loose,
+                },
+                TestResultDetail {
+                    name: "keeps <default> #1".to_string(),
+                    passed: false,
+                    error_message: Some(
+                        "Output mismatch for test 'keeps <default> #1':\n-let a;\n+const a;\n"
+                            .to_string(),
+                    ),
+                    duration: Duration::from_millis(1500),
+                    skipped: None,
+                    strictness: Strictness::Loose,
+                },
+            ],
+            Duration::from_secs(2),
+        )
+    }
+
+    #[test]
+    fn junit_report_lists_cases_with_failures_and_strictness() {
+        let xml = junit_report(&summary(), Strictness::Loose);
+
+        assert!(xml.contains(
+            "<testsuite name=\"jssg\" tests=\"2\" failures=\"1\" errors=\"0\" skipped=\"0\" time=\"2.000\">"
+        ));
+        assert!(xml.contains("<property name=\"strictness\" value=\"loose\"/>"));
+        assert!(
+            xml.contains("<testcase name=\"renames-import\" classname=\"jssg\" time=\"0.012\"/>")
+        );
+        assert!(xml.contains(
+            "<testcase name=\"keeps &lt;default&gt; #1\" classname=\"jssg\" time=\"1.500\">"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"Output mismatch for test &apos;keeps &lt;default&gt; #1&apos;:\" type=\"failure\">"
+        ));
+        assert!(xml.contains("-let a;\n+const a;\n</failure>"));
+    }
+
+    #[test]
+    fn tap_report_has_plan_points_and_diagnostics() {
+        let tap = tap_report(&summary(), Strictness::Loose);
+
+        assert_eq!(
+            tap,
+            "TAP version 13\n\
+             1..2\n\
+             # strictness: loose\n\
+             ok 1 - renames-import\n  \
+             ---\n  \
+             duration_ms: 12\n  \
+             ...\n\
+             not ok 2 - keeps <default> \\#1\n  \
+             ---\n  \
+             duration_ms: 1500\n  \
+             strictness: loose\n  \
+             message: |-\n    \
+             Output mismatch for test 'keeps <default> #1':\n    \
+             -let a;\n    \
+             +const a;\n  \
+             ...\n\
+             # tests 2, pass 1, fail 1"
+        );
+    }
+
+    #[test]
+    fn reports_mark_skipped_cases_and_case_strictness() {
+        let summary = TestSummary::from_details(
+            vec![
+                TestResultDetail {
+                    name: "flaky[vue]".to_string(),
+                    passed: true,
+                    error_message: None,
+                    duration: Duration::ZERO,
+                    skipped: Some("needs a network".to_string()),
+                    strictness: Strictness::Strict,
+                },
+                TestResultDetail {
+                    name: "formatting".to_string(),
+                    passed: true,
+                    error_message: None,
+                    duration: Duration::from_millis(3),
+                    skipped: None,
+                    strictness: Strictness::Cst,
+                },
+            ],
+            Duration::from_millis(3),
+        );
+        assert_eq!(summary.ignored, 1);
+        assert_eq!(summary.passed, 1);
+
+        let xml = junit_report(&summary, Strictness::Strict);
+        assert!(xml.contains("skipped=\"1\""));
+        assert!(xml.contains("<skipped message=\"needs a network\"/>"));
+        assert!(xml.contains("<property name=\"strictness\" value=\"cst\"/>"));
+
+        let tap = tap_report(&summary, Strictness::Strict);
+        assert!(tap.contains("ok 1 - flaky[vue] # SKIP needs a network\n"));
+        assert!(tap.ends_with("# tests 2, pass 1, fail 0, skip 1"));
+    }
+
+    #[test]
+    fn libtest_reporters_have_no_rendered_report() {
+        assert!(render_report(&ReporterType::Console, &summary(), Strictness::Strict).is_none());
+        assert!(render_report(&ReporterType::Tap, &summary(), Strictness::Strict).is_some());
+    }
+}
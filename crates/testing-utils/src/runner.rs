@@ -1,20 +1,26 @@
 use anyhow::{Context, Result};
 use codemod_llrt_capabilities::types::LlrtSupportedModules;
-use codemod_sandbox::sandbox::engine::ExecutionResult;
+use codemod_sandbox::sandbox::engine::{
+    introduced_syntax_issues, summarize_syntax_issues, ExecutionResult,
+};
+use codemod_sandbox::CodemodLang;
 use libtest_mimic::{run, Trial};
 use similar::TextDiff;
 use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::time::{Duration, Instant};
 use std::{collections::HashSet, future::Future};
 use tempfile::TempDir;
 use tokio::time::timeout;
 use walkdir::WalkDir;
 
 use crate::{
-    config::{Strictness, TestOptions},
+    config::{ReporterType, Strictness, TestOptions},
+    fixture_config::FixtureSettings,
     fixtures::{FileSystemTestCase, FileSystemTestCaseLayout, TestSource, UnifiedTestCase},
+    report::render_report,
     strictness::{ast_compare, cst_compare, detect_language, loose_compare},
 };
 
@@ -58,6 +64,15 @@ pub struct ExecutionRequest {
     pub workspace_root: Option<PathBuf>,
     pub test_case_root: PathBuf,
     pub entrypoint_count: usize,
+    /// Settings from the fixture's `test.yaml`
+    pub settings: FixtureSettings,
+    /// First execution of the test case
+    pub starts_case: bool,
+    /// Last execution of the test case, after which its outputs are complete
+    pub ends_case: bool,
+    /// Second run over the test's output, checking that the transform is
+    /// idempotent. Fixture expectations do not apply to it.
+    pub rerun: bool,
 }
 
 /// Execution function type - takes input code and file path, returns transformation result
@@ -75,6 +90,13 @@ pub struct TestResultDetail {
     pub name: String,
     pub passed: bool,
     pub error_message: Option<String>,
+    /// Time spent running the test. Every assertion of a directory fixture
+    /// reports the duration of the whole fixture.
+    pub duration: Duration,
+    /// Reason the test was skipped (possibly empty), if it was
+    pub skipped: Option<String>,
+    /// Strictness the outputs were compared with
+    pub strictness: Strictness,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +108,8 @@ pub struct TestSummary {
     pub ignored: usize,
     /// Detailed results for each test, including error messages for failures.
     pub details: Vec<TestResultDetail>,
+    /// Wall-clock time of the whole run.
+    pub duration: Duration,
 }
 
 impl TestSummary {
@@ -104,6 +128,29 @@ impl TestSummary {
             errors: 0,
             ignored,
             details: Vec::new(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Build a summary from detailed results, for reporters that don't go
+    /// through libtest.
+    pub fn from_details(details: Vec<TestResultDetail>, duration: Duration) -> Self {
+        let ignored = details
+            .iter()
+            .filter(|detail| detail.skipped.is_some())
+            .count();
+        let passed = details
+            .iter()
+            .filter(|detail| detail.passed && detail.skipped.is_none())
+            .count();
+        Self {
+            total: details.len(),
+            passed,
+            failed: details.len() - passed - ignored,
+            errors: 0,
+            ignored,
+            details,
+            duration,
         }
     }
 
@@ -113,9 +160,54 @@ impl TestSummary {
     }
 }
 
+/// Report results of tests that already ran: as a JUnit or TAP report, or
+/// through libtest for the other reporters.
+pub fn report_results(
+    details: Vec<TestResultDetail>,
+    options: &TestOptions,
+    duration: Duration,
+) -> TestSummary {
+    // JUnit and TAP are rendered from the details instead of libtest output
+    if matches!(options.reporter, ReporterType::Junit | ReporterType::Tap) {
+        let summary = TestSummary::from_details(details, duration);
+        if let Some(report) = render_report(&options.reporter, &summary, options.strictness) {
+            println!("{report}");
+        }
+        return summary;
+    }
+
+    let trials: Vec<Trial> = details
+        .iter()
+        .map(|detail| {
+            let result = match &detail.error_message {
+                Some(message) if !detail.passed => Err(message.clone()),
+                _ => Ok(()),
+            };
+            Trial::test(detail.name.clone(), move || {
+                result.map_err(libtest_mimic::Failed::from)
+            })
+            .with_ignored_flag(detail.skipped.is_some())
+        })
+        .collect();
+
+    let mut args = options.to_libtest_args();
+
+    args.filter = None;
+
+    let result = run(&args, trials);
+
+    let mut summary = TestSummary::from_libtest_result(result);
+    summary.details = details;
+    summary.duration = duration;
+    summary
+}
+
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 pub struct TestRunner {
     options: TestOptions,
     test_source: TestSource,
+    watch_paths: Vec<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -124,6 +216,23 @@ struct AssertionResult {
     result: Result<()>,
 }
 
+/// Result of one assertion together with how it was run.
+struct CaseOutcome {
+    name: String,
+    result: Result<()>,
+    duration: Duration,
+    skipped: Option<String>,
+    strictness: Strictness,
+}
+
+/// Where an execution falls within its test case.
+#[derive(Debug, Clone, Copy)]
+struct CasePosition {
+    starts_case: bool,
+    ends_case: bool,
+    rerun: bool,
+}
+
 struct DirectoryExecution {
     assertion_results: Vec<AssertionResult>,
     fixed_results: HashMap<PathBuf, Result<()>>,
@@ -135,14 +244,31 @@ enum DiscoveredTestCase {
     Directory(FileSystemTestCase),
 }
 
+impl DiscoveredTestCase {
+    fn settings(&self) -> &FixtureSettings {
+        match self {
+            DiscoveredTestCase::Unified(test_case) => &test_case.settings,
+            DiscoveredTestCase::Directory(test_case) => &test_case.settings,
+        }
+    }
+}
+
 impl TestRunner {
     pub fn new(options: TestOptions, test_source: TestSource) -> Self {
         Self {
             options,
             test_source,
+            watch_paths: Vec::new(),
         }
     }
 
+    /// Additional paths that trigger a re-run in watch mode, such as the
+    /// codemod sources. The test directory is always watched.
+    pub fn with_watch_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.watch_paths = paths;
+        self
+    }
+
     /// Run tests with the provided execution function
     /// The extensions parameter should be a list of file extensions to look for (e.g., [".js", ".ts"])
     pub async fn run_tests<'a>(
@@ -157,14 +283,14 @@ impl TestRunner {
                 .await;
         }
 
-        self.run_tests_once(extensions, execution_fn, capabilities)
+        self.run_tests_once(extensions, &execution_fn, capabilities)
             .await
     }
 
     async fn run_tests_once<'a>(
         &mut self,
         extensions: &[&str],
-        execution_fn: ExecutionFn<'a>,
+        execution_fn: &ExecutionFn<'a>,
         capabilities: Option<HashSet<LlrtSupportedModules>>,
     ) -> Result<TestSummary> {
         let discovered_cases = self
@@ -182,16 +308,60 @@ impl TestRunner {
             return Err(anyhow::anyhow!("No test cases found"));
         }
 
+        let run_started = Instant::now();
+        let any_only = discovered_cases
+            .iter()
+            .any(|discovered_case| discovered_case.settings().only);
         let mut test_results = Vec::new();
         for discovered_case in discovered_cases {
+            let settings = discovered_case.settings();
+            let skipped = settings
+                .skip
+                .clone()
+                .or_else(|| (any_only && !settings.only).then(String::new));
+            let options = match settings.strictness {
+                Some(strictness) => TestOptions {
+                    strictness,
+                    ..self.options.clone()
+                },
+                None => self.options.clone(),
+            };
+
+            if let Some(reason) = skipped {
+                let names = match &discovered_case {
+                    DiscoveredTestCase::Unified(test_case) => vec![test_case.name.clone()],
+                    DiscoveredTestCase::Directory(test_case) => {
+                        Self::planned_directory_assertion_names(test_case)
+                    }
+                };
+                let results = names
+                    .into_iter()
+                    .map(|name| AssertionResult {
+                        name,
+                        result: Ok(()),
+                    })
+                    .collect();
+                test_results.extend(self.filter_assertion_results(results).into_iter().map(
+                    |result| CaseOutcome {
+                        name: result.name,
+                        result: result.result,
+                        duration: Duration::ZERO,
+                        skipped: Some(reason.clone()),
+                        strictness: options.strictness,
+                    },
+                ));
+                continue;
+            }
+
+            let case_started = Instant::now();
             let case_results = match discovered_case {
                 DiscoveredTestCase::Unified(test_case) => {
                     let result = timeout(
-                        self.options.timeout,
+                        options.timeout,
                         Self::execute_test_case(
                             &test_case,
-                            &execution_fn,
-                            &self.options,
+                            execution_fn,
+                            &options,
                             capabilities.clone(),
                         ),
                     )
@@ -201,7 +371,7 @@ impl TestRunner {
                         Err(anyhow::anyhow!(
                             "Test '{}' timed out after {:?}",
                             test_case.name,
-                            self.options.timeout
+                            options.timeout
                         ))
                     });
 
@@ -213,8 +383,8 @@ impl TestRunner {
                 DiscoveredTestCase::Directory(test_case) => {
                     Self::execute_directory_test_case(
                         &test_case,
-                        &execution_fn,
-                        &self.options,
+                        execution_fn,
+                        &options,
                         capabilities.clone(),
                     )
                     .await?
@@ -227,46 +397,38 @@ impl TestRunner {
             }
 
             let failed = filtered_results.iter().any(|result| result.result.is_err());
-            test_results.extend(
-                filtered_results
-                    .into_iter()
-                    .map(|result| (result.name, result.result)),
-            );
+            let duration = case_started.elapsed();
+            test_results.extend(filtered_results.into_iter().map(|result| CaseOutcome {
+                name: result.name,
+                result: result.result,
+                duration,
+                skipped: None,
+                strictness: options.strictness,
+            }));
 
             if self.options.should_fail_fast() && failed {
-                println!("Stopping test execution due to --fail-fast and test failure");
+                eprintln!("Stopping test execution due to --fail-fast and test failure");
                 break;
             }
         }
 
-        // Capture detailed results before passing to libtest_mimic
         let details: Vec<TestResultDetail> = test_results
-            .iter()
-            .map(|(name, result)| TestResultDetail {
-                name: name.clone(),
-                passed: result.is_ok(),
-                error_message: result.as_ref().err().map(|e| e.to_string()),
-            })
-            .collect();
-
-        let trials: Vec<Trial> = test_results
             .into_iter()
-            .map(|(name, result)| {
-                Trial::test(name, move || {
-                    result.map_err(|e| libtest_mimic::Failed::from(format!("{e}")))
-                })
+            .map(|outcome| TestResultDetail {
+                name: outcome.name,
+                passed: outcome.result.is_ok(),
+                error_message: outcome.result.err().map(|e| e.to_string()),
+                duration: outcome.duration,
+                skipped: outcome.skipped,
+                strictness: outcome.strictness,
             })
             .collect();
 
-        let mut args = self.options.to_libtest_args();
-
-        args.filter = None;
-
-        let result = run(&args, trials);
-
-        let mut summary = TestSummary::from_libtest_result(result);
-        summary.details = details;
-        Ok(summary)
+        Ok(report_results(
+            details,
+            &self.options,
+            run_started.elapsed(),
+        ))
     }
 
     async fn execute_test_case<'a>(
@@ -285,26 +447,27 @@ impl TestRunner {
             .parent()
             .unwrap_or_else(|| Path::new("."))
             .to_path_buf();
-        let execution_result = execution_fn(
-            ExecutionRequest {
-                input_code: test_case.input_code.clone(),
-                input_path,
-                logical_input_path: test_case
-                    .logical_input_path
-                    .clone()
-                    .or_else(|| test_case.input_path.clone()),
-                workspace_root: None,
-                test_case_root,
-                entrypoint_count: 1,
-            },
-            capabilities.clone(),
-        )
-        .await?;
+        let request = ExecutionRequest {
+            input_code: test_case.input_code.clone(),
+            input_path,
+            logical_input_path: test_case
+                .logical_input_path
+                .clone()
+                .or_else(|| test_case.input_path.clone()),
+            workspace_root: None,
+            test_case_root,
+            entrypoint_count: 1,
+            settings: test_case.settings.clone(),
+            starts_case: true,
+            ends_case: true,
+            rerun: false,
+        };
+        let execution_result = execution_fn(request.clone(), capabilities.clone()).await?;
 
         if should_expect_error {
             match execution_result {
                 TransformationResult::Error(_) => {
-                    println!("Test '{}' failed as expected", test_case.name);
+                    eprintln!("Test '{}' failed as expected", test_case.name);
                     return Ok(());
                 }
                 TransformationResult::Success(_) => {
@@ -326,7 +489,15 @@ impl TestRunner {
             }
         };
 
-        if !Self::contents_match(
+        if let Some(issues) = Self::syntax_errors(&test_case.input_code, &actual_content, options) {
+            return Err(anyhow::anyhow!(
+                "Output of test '{}' no longer parses: {}",
+                test_case.name,
+                issues
+            ));
+        }
+
+        let settled_output = if Self::contents_match(
             &test_case.expected_output_code,
             &actual_content,
             options,
@@ -335,27 +506,63 @@ impl TestRunner {
                 .as_deref()
                 .or(test_case.input_path.as_deref()),
         ) {
-            if options.update_snapshots {
-                test_case
-                    .update_expected_output(&actual_content)
-                    .with_context(|| {
-                        format!("Failed to update snapshot for test '{}'", test_case.name)
-                    })?;
-                return Ok(());
-            } else {
-                let diff =
-                    Self::generate_diff(&test_case.expected_output_code, &actual_content, options);
-                return Err(anyhow::anyhow!(
-                    "Output mismatch for test '{}':\n{}",
-                    test_case.name,
-                    diff
-                ));
+            test_case.expected_output_code.clone()
+        } else if options.update_snapshots {
+            test_case
+                .update_expected_output(&actual_content)
+                .with_context(|| {
+                    format!("Failed to update snapshot for test '{}'", test_case.name)
+                })?;
+            actual_content
+        } else {
+            let diff =
+                Self::generate_diff(&test_case.expected_output_code, &actual_content, options);
+            return Err(anyhow::anyhow!(
+                "Output mismatch for test '{}':\n{}",
+                test_case.name,
+                diff
+            ));
+        };
+
+        if options.check_idempotent {
+            let rerun = ExecutionRequest {
+                input_code: settled_output.clone(),
+                rerun: true,
+                ..request
+            };
+            match execution_fn(rerun, capabilities).await? {
+                TransformationResult::Success(output) if output.content == settled_output => {}
+                TransformationResult::Success(output) => {
+                    return Err(anyhow::anyhow!(
+                        "Test '{}' is not idempotent, a second run changed its output:\n{}",
+                        test_case.name,
+                        Self::generate_diff(&settled_output, &output.content, options)
+                    ));
+                }
+                TransformationResult::Error(error) => {
+                    return Err(anyhow::anyhow!(
+                        "Test '{}' is not idempotent, a second run failed:\n{}",
+                        test_case.name,
+                        error
+                    ));
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Summary of the syntax errors `output` has and `input` did not, when
+    /// `options` asks for the check and names a known language.
+    fn syntax_errors(input: &str, output: &str, options: &TestOptions) -> Option<String> {
+        if !options.check_syntax {
+            return None;
+        }
+        let language: CodemodLang = options.language.as_deref()?.parse().ok()?;
+        let issues = introduced_syntax_issues(language, input, output);
+        (!issues.is_empty()).then(|| summarize_syntax_issues(&issues))
+    }
+
     fn discover_test_cases(
         &self,
         extensions: &[&str],
@@ -459,7 +666,8 @@ impl TestRunner {
             workspace.path(),
             execution_fn,
             options,
-            capabilities,
+            capabilities.clone(),
+            false,
         )
         .await?;
 
@@ -494,9 +702,95 @@ impl TestRunner {
             }
         }
 
+        let passed = execution
+            .assertion_results
+            .iter()
+            .all(|assertion| assertion.result.is_ok());
+        if options.check_idempotent && passed && !test_case.should_error {
+            let result = Self::check_directory_idempotent(
+                test_case,
+                input_dir,
+                workspace.path(),
+                execution_fn,
+                options,
+                capabilities,
+            )
+            .await?;
+            execution.assertion_results.push(AssertionResult {
+                name: format!("{} (idempotent)", test_case.name),
+                result,
+            });
+        }
+
         Ok(execution.assertion_results)
     }
 
+    /// Run the entrypoints again over a copy of the case's output and
+    /// require every file to stay the same.
+    async fn check_directory_idempotent<'a>(
+        test_case: &FileSystemTestCase,
+        input_dir: &Path,
+        output_dir: &Path,
+        execution_fn: &ExecutionFn<'a>,
+        options: &TestOptions,
+        capabilities: Option<HashSet<LlrtSupportedModules>>,
+    ) -> Result<Result<()>> {
+        let workspace = TempDir::new().context("Failed to create temp test workspace")?;
+        Self::copy_directory_contents(output_dir, workspace.path())
+            .context("Failed to copy test output into temp workspace")?;
+        let execution = Self::execute_directory_entrypoints(
+            test_case,
+            input_dir,
+            workspace.path(),
+            execution_fn,
+            options,
+            capabilities,
+            true,
+        )
+        .await?;
+
+        let failure = execution
+            .assertion_results
+            .into_iter()
+            .map(|assertion| assertion.result)
+            .chain(execution.fixed_results.into_values())
+            .find_map(Result::err);
+        if let Some(error) = failure {
+            return Ok(Err(anyhow::anyhow!(
+                "Test '{}' is not idempotent, a second run failed:\n{}",
+                test_case.name,
+                error
+            )));
+        }
+
+        let before = Self::collect_workspace_files(output_dir)?;
+        let after = Self::collect_workspace_files(workspace.path())?;
+        let paths: BTreeSet<&PathBuf> = before.keys().chain(after.keys()).collect();
+        let mut changes = Vec::new();
+        for path in paths {
+            match (before.get(path), after.get(path)) {
+                (Some(first), Some(second)) if first != second => changes.push(format!(
+                    "{}:\n{}",
+                    path.display(),
+                    Self::generate_diff(first, second, options)
+                )),
+                (Some(_), None) => changes.push(format!("{}: deleted", path.display())),
+                (None, Some(_)) => changes.push(format!("{}: created", path.display())),
+                _ => {}
+            }
+        }
+
+        Ok(if changes.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Test '{}' is not idempotent, a second run changed its output:\n{}",
+                test_case.name,
+                changes.join("\n")
+            ))
+        })
+    }
+
     fn planned_directory_assertion_names(test_case: &FileSystemTestCase) -> Vec<String> {
         let mut known_paths: BTreeSet<PathBuf> = Self::tracked_paths(&test_case.input_files);
         known_paths.extend(Self::tracked_paths(&test_case.expected_files));
@@ -534,6 +828,7 @@ impl TestRunner {
         execution_fn: &ExecutionFn<'a>,
         options: &TestOptions,
         capabilities: Option<HashSet<LlrtSupportedModules>>,
+        rerun: bool,
     ) -> Result<DirectoryExecution> {
         let mut execution = DirectoryExecution {
             assertion_results: Vec::new(),
@@ -543,9 +838,22 @@ impl TestRunner {
         let entrypoint_counts = Self::directory_entrypoint_counts(test_case);
 
         let mut deferred_deletions: Vec<PathBuf> = Vec::new();
+        let mut starts_case = true;
 
-        for relative_path in &test_case.entrypoint_files {
-            let Some(result) = Self::execute_directory_entrypoint(
+        for (index, relative_path) in test_case.entrypoint_files.iter().enumerate() {
+            // Entrypoints renamed away by earlier ones are not executed
+            if !workspace_root.join(relative_path).exists() {
+                continue;
+            }
+            let ends_case = !test_case.entrypoint_files[index + 1..]
+                .iter()
+                .any(|later| workspace_root.join(later).exists());
+            let position = CasePosition {
+                starts_case,
+                ends_case,
+                rerun,
+            };
+            let result = Self::execute_directory_entrypoint(
                 test_case,
                 input_dir,
                 workspace_root,
@@ -555,9 +863,11 @@ impl TestRunner {
                 capabilities.clone(),
                 &entrypoint_counts,
                 &mut deferred_deletions,
+                position,
             )
-            .await?
-            else {
+            .await?;
+            starts_case = false;
+            let Some(result) = result else {
                 continue;
             };
 
@@ -593,6 +903,7 @@ impl TestRunner {
         capabilities: Option<HashSet<LlrtSupportedModules>>,
         entrypoint_counts: &HashMap<PathBuf, usize>,
         deferred_deletions: &mut Vec<PathBuf>,
+        position: CasePosition,
     ) -> Result<Option<Result<()>>> {
         let actual_path = workspace_root.join(relative_path);
         if !actual_path.exists() {
@@ -614,6 +925,7 @@ impl TestRunner {
             .to_path_buf();
         let entrypoint_count = entrypoint_counts.get(&metrics_group).copied().unwrap_or(1);
 
+        let original_code = options.check_syntax.then(|| input_code.clone());
         let execution_result = timeout(
             options.timeout,
             execution_fn(
@@ -624,6 +936,10 @@ impl TestRunner {
                     workspace_root: Some(workspace_root.to_path_buf()),
                     test_case_root: test_case.path.clone(),
                     entrypoint_count,
+                    settings: test_case.settings.clone(),
+                    starts_case: position.starts_case,
+                    ends_case: position.ends_case,
+                    rerun: position.rerun,
                 },
                 capabilities,
             ),
@@ -649,13 +965,24 @@ impl TestRunner {
                 assertion_name
             )),
             Ok(TransformationResult::Success(output)) => {
-                if let Some(path_to_delete) =
-                    Self::apply_transformation_output(&actual_path, output)?
-                {
-                    // Defer deletion — will be applied after all entrypoints are processed
-                    deferred_deletions.push(path_to_delete);
+                let syntax_errors = original_code
+                    .as_deref()
+                    .and_then(|original| Self::syntax_errors(original, &output.content, options));
+                if let Some(issues) = syntax_errors {
+                    Err(anyhow::anyhow!(
+                        "Output of '{}' no longer parses: {}",
+                        assertion_name,
+                        issues
+                    ))
+                } else {
+                    if let Some(path_to_delete) =
+                        Self::apply_transformation_output(&actual_path, output)?
+                    {
+                        // Defer deletion — will be applied after all entrypoints are processed
+                        deferred_deletions.push(path_to_delete);
+                    }
+                    return Ok(None);
                 }
-                return Ok(None);
             }
         };
 
@@ -848,7 +1175,9 @@ impl TestRunner {
         false
     }
 
-    fn contents_match(
+    /// Compare file contents with the strictness of `options`. Tree-based
+    /// comparisons use `options.language`, or the language of `input_path`.
+    pub fn contents_match(
         expected: &str,
         actual: &str,
         options: &TestOptions,
@@ -900,7 +1229,8 @@ impl TestRunner {
         }
     }
 
-    fn generate_diff(expected: &str, actual: &str, options: &TestOptions) -> String {
+    /// Line diff of `expected` and `actual`, with `options.context_lines` of context.
+    pub fn generate_diff(expected: &str, actual: &str, options: &TestOptions) -> String {
         use similar::ChangeTag;
         use std::fmt::Write;
 
@@ -941,13 +1271,59 @@ impl TestRunner {
         capabilities: Option<HashSet<LlrtSupportedModules>>,
     ) -> Result<TestSummary> {
         println!("Running in watch mode. Press Ctrl+C to exit.");
-        let initial_summary = self
-            .run_tests_once(extensions, execution_fn, capabilities)
-            .await?;
+        let mut snapshot = self.watch_snapshot();
+        loop {
+            if let Err(e) = self
+                .run_tests_once(extensions, &execution_fn, capabilities.clone())
+                .await
+            {
+                eprintln!("{e}");
+            }
+
+            println!("\nWatching for changes...");
+            loop {
+                tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+                let next_snapshot = self.watch_snapshot();
+                if next_snapshot != snapshot {
+                    snapshot = next_snapshot;
+                    break;
+                }
+            }
+        }
+    }
 
-        println!("Watch mode not fully implemented yet. Use --no-watch for now.");
+    /// Modification times and sizes of every watched file. Dependency and
+    /// hidden directories are skipped.
+    fn watch_snapshot(&self) -> BTreeSet<(PathBuf, Option<std::time::SystemTime>, u64)> {
+        let test_directory = match &self.test_source {
+            TestSource::Directory(path) => Some(path),
+            TestSource::Cases(_) => None,
+        };
 
-        Ok(initial_summary)
+        test_directory
+            .into_iter()
+            .chain(self.watch_paths.iter())
+            .flat_map(|root| {
+                WalkDir::new(root)
+                    .into_iter()
+                    .filter_entry(|entry| {
+                        entry.depth() == 0 || {
+                            let name = entry.file_name().to_string_lossy();
+                            name != "node_modules" && !name.starts_with('.')
+                        }
+                    })
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_file())
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((
+                    entry.path().to_path_buf(),
+                    metadata.modified().ok(),
+                    metadata.len(),
+                ))
+            })
+            .collect()
     }
 
     fn should_expect_error_name(
@@ -1149,6 +1525,8 @@ mod tests {
             strictness: Strictness::Strict,
             language: Some("javascript".to_string()),
             expected_extension: None,
+            check_syntax: false,
+            check_idempotent: false,
         }
     }
 
@@ -1229,6 +1607,56 @@ mod tests {
             .any(|detail| detail.name == "multi_old.js" && detail.passed));
     }
 
+    #[tokio::test]
+    async fn fixture_variants_run_with_their_params_and_expected_files() {
+        let temp = TempDir::new().expect("temp dir");
+        let tests_dir = temp.path().join("tests");
+        let fixture_dir = tests_dir.join("greet");
+        write_fixture_file(fixture_dir.join("input.js"), "hello");
+        write_fixture_file(fixture_dir.join("expected.js"), "hello world");
+        write_fixture_file(fixture_dir.join("expected.loud.js"), "hello WORLD");
+        write_fixture_file(
+            fixture_dir.join("test.yaml"),
+            "params:\n  name: world\n\
+             variants:\n\
+             \x20 - name: default\n\
+             \x20 - name: loud\n\
+             \x20   params: { name: WORLD }\n\
+             \x20 - name: later\n\
+             \x20   skip: not implemented\n",
+        );
+
+        let execution_fn = boxed_execution_fn(|request| async move {
+            let name = request.settings.params["name"].as_str().unwrap_or_default();
+            Ok(TransformationResult::Success(TransformOutput {
+                content: format!("{} {name}", request.input_code),
+                rename_to: None,
+            }))
+        });
+
+        let mut runner = TestRunner::new(test_options(false), TestSource::Directory(tests_dir));
+        let summary = runner
+            .run_tests(&[".js"], execution_fn, None)
+            .await
+            .expect("run tests");
+
+        assert!(summary.is_success());
+        assert_eq!(summary.ignored, 1);
+        let names: Vec<_> = summary
+            .details
+            .iter()
+            .map(|detail| (detail.name.as_str(), detail.skipped.is_some()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("greet[default]", false),
+                ("greet[later]", true),
+                ("greet[loud]", false)
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn directory_fixture_reports_unexpected_extra_files() {
         let temp = TempDir::new().expect("temp dir");
@@ -1470,4 +1898,62 @@ mod tests {
         assert_eq!(summary.details[0].name, "fail-fast_a.js");
         assert_eq!(later_executions.load(Ordering::SeqCst), 0);
     }
+
+    #[tokio::test]
+    async fn syntax_and_idempotency_checks_flag_faulty_transforms() {
+        let temp = TempDir::new().expect("temp dir");
+        let tests_dir = temp.path().join("tests");
+        for (name, input, expected) in [
+            ("broken", "call(1);\n", "call(1;\n"),
+            ("growing", "a;\n", "a;\nb;\n"),
+            ("stable", "a;\n", "b;\n"),
+        ] {
+            write_fixture_file(tests_dir.join(name).join("input.js"), input);
+            write_fixture_file(tests_dir.join(name).join("expected.js"), expected);
+        }
+        write_fixture_file(tests_dir.join("tree/input/main.js"), "a;\n");
+        write_fixture_file(tests_dir.join("tree/expected/main.js"), "a;\nb;\n");
+
+        let execution_fn = boxed_execution_fn(|request| async move {
+            let case = request
+                .test_case_root
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .find(|name| ["broken", "growing", "stable", "tree"].contains(&name.as_str()))
+                .unwrap_or_default();
+            let content = match case.as_str() {
+                "broken" => request.input_code.replace("(1)", "(1"),
+                "stable" => request.input_code.replace('a', "b"),
+                _ => format!("{}b;\n", request.input_code),
+            };
+            Ok(TransformationResult::Success(TransformOutput {
+                content,
+                rename_to: None,
+            }))
+        });
+
+        let mut options = test_options(false);
+        options.check_syntax = true;
+        options.check_idempotent = true;
+        let mut runner = TestRunner::new(options, TestSource::Directory(tests_dir));
+        let summary = runner
+            .run_tests(&[".js"], execution_fn, None)
+            .await
+            .expect("run tests");
+
+        let error = |name: &str| {
+            summary
+                .details
+                .iter()
+                .find(|detail| detail.name.contains(name))
+                .unwrap_or_else(|| panic!("missing test {name}"))
+                .error_message
+                .clone()
+                .unwrap_or_default()
+        };
+        assert!(error("broken").contains("no longer parses"));
+        assert!(error("growing").contains("not idempotent"));
+        assert_eq!(error("stable"), "");
+        assert!(error("tree (idempotent)").contains("not idempotent"));
+    }
 }
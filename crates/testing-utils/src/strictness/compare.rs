@@ -594,6 +594,75 @@ mod tests {
         assert!(loose_compare(expected, actual, "json"));
     }
 
+    #[test]
+    fn test_yaml_mapping_key_order() {
+        let expected = "name: app\nversion: 1\nscripts:\n  build: tsc\n  test: jest\n";
+        let actual = "scripts:\n  test: jest\n  build: tsc\nname: app\nversion: 1\n";
+        assert!(loose_compare(expected, actual, "yaml"));
+        assert!(!ast_compare(expected, actual, "yaml"));
+    }
+
+    #[test]
+    fn test_yaml_sequence_order_matters() {
+        let expected = "steps:\n  - build\n  - test\n";
+        let actual = "steps:\n  - test\n  - build\n";
+        assert!(!loose_compare(expected, actual, "yaml"));
+    }
+
+    #[test]
+    fn test_css_declaration_and_selector_order() {
+        let expected = "a, b { color: red; margin: 0; }";
+        let actual = "b, a { margin: 0; color: red; }";
+        assert!(loose_compare(expected, actual, "css"));
+    }
+
+    #[test]
+    fn test_css_overriding_declaration_order_matters() {
+        let expected = "a { margin: 0; margin-left: 4px; }";
+        let actual = "a { margin-left: 4px; margin: 0; }";
+        assert!(!loose_compare(expected, actual, "css"));
+    }
+
+    #[test]
+    fn test_html_attribute_order() {
+        let expected = r#"<a href="/" class="link">Home</a>"#;
+        let actual = r#"<a class="link" href="/">Home</a>"#;
+        assert!(loose_compare(expected, actual, "html"));
+        assert!(!loose_compare(
+            expected,
+            r#"<a class="nav" href="/">Home</a>"#,
+            "html"
+        ));
+    }
+
+    #[test]
+    fn test_java_implements_and_throws_order() {
+        let expected = "class A implements B, C { void f() throws X, Y {} }";
+        let actual = "class A implements C, B { void f() throws Y, X {} }";
+        assert!(loose_compare(expected, actual, "java"));
+    }
+
+    #[test]
+    fn test_java_argument_order_matters() {
+        let expected = "class A { void f() { g(1, 2); } }";
+        let actual = "class A { void f() { g(2, 1); } }";
+        assert!(!loose_compare(expected, actual, "java"));
+    }
+
+    #[test]
+    fn test_csharp_object_initializer_order() {
+        let expected = "var p = new Point { X = 1, Y = 2 };";
+        let actual = "var p = new Point { Y = 2, X = 1 };";
+        assert!(loose_compare(expected, actual, "csharp"));
+    }
+
+    #[test]
+    fn test_csharp_collection_initializer_order_matters() {
+        let expected = "var l = new List<int> { 1, 2 };";
+        let actual = "var l = new List<int> { 2, 1 };";
+        assert!(!loose_compare(expected, actual, "csharp"));
+    }
+
     #[test]
     fn test_unknown_language_falls_back_to_exact() {
         assert!(loose_compare("some code", "some code", "unknown_lang"));
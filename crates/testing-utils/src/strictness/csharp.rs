@@ -0,0 +1,137 @@
+//! C# semantic normalizer.
+
+use std::collections::HashSet;
+use tree_sitter::Parser;
+
+use super::traits::{NormalizedNode, ParserProvider, SemanticNormalizer};
+use super::utils::extract_sort_key;
+
+const COMMENT_SCOPE_KINDS: &[&str] = &[
+    "compilation_unit",
+    "namespace_declaration",
+    "file_scoped_namespace_declaration",
+    "declaration_list",
+    "block",
+    "switch_body",
+];
+
+/// Semantic normalizer for C#.
+pub struct CSharpNormalizer;
+
+impl ParserProvider for CSharpNormalizer {
+    fn language_ids(&self) -> &[&'static str] {
+        &["csharp", "cs", "c#"]
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &[".cs"]
+    }
+
+    fn get_parser(&self) -> Option<Parser> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_c_sharp::LANGUAGE.into())
+            .ok()?;
+        Some(parser)
+    }
+}
+
+impl SemanticNormalizer for CSharpNormalizer {
+    fn unordered_node_types(&self) -> HashSet<&'static str> {
+        ["base_list", "attribute_list"].into_iter().collect()
+    }
+
+    fn normalize_children(
+        &self,
+        node_kind: &str,
+        children: Vec<NormalizedNode>,
+    ) -> (Vec<NormalizedNode>, bool) {
+        match node_kind {
+            "initializer_expression" => (normalize_initializer(children), true),
+            _ => (children, false),
+        }
+    }
+
+    fn comment_scope_kinds(&self) -> &'static [&'static str] {
+        COMMENT_SCOPE_KINDS
+    }
+}
+
+/// Normalize an initializer expression.
+///
+/// Only sorts object initializers, whose elements are all member
+/// assignments. Collection and array initializers keep their order.
+/// ```csharp
+/// new Point { X = 1, Y = 2 }  // object initializer - can reorder
+/// new List<int> { 1, 2, 3 }   // collection initializer - order matters
+/// ```
+fn normalize_initializer(mut children: Vec<NormalizedNode>) -> Vec<NormalizedNode> {
+    if !children.is_empty()
+        && children
+            .iter()
+            .all(|child| child.kind == "assignment_expression")
+    {
+        children.sort_by_key(extract_sort_key);
+    }
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(member: &str, value: &str) -> NormalizedNode {
+        NormalizedNode::new(
+            "assignment_expression".into(),
+            vec![
+                NormalizedNode::leaf("identifier".into(), member.into()),
+                NormalizedNode::leaf("integer_literal".into(), value.into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_language_ids() {
+        let normalizer = CSharpNormalizer;
+        assert!(normalizer.handles_language("csharp"));
+        assert!(normalizer.handles_language("cs"));
+        assert!(normalizer.handles_language("C#"));
+        assert!(!normalizer.handles_language("java"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let normalizer = CSharpNormalizer;
+        assert!(normalizer.handles_extension(".cs"));
+        assert!(!normalizer.handles_extension(".java"));
+    }
+
+    #[test]
+    fn test_get_parser() {
+        assert!(CSharpNormalizer.get_parser().is_some());
+    }
+
+    #[test]
+    fn test_unordered_types() {
+        let types = CSharpNormalizer.unordered_node_types();
+        assert!(types.contains("base_list"));
+        assert!(types.contains("attribute_list"));
+        assert!(!types.contains("argument_list"));
+    }
+
+    #[test]
+    fn test_normalize_children_object_initializer() {
+        let children = vec![assignment("Y", "2"), assignment("X", "1")];
+        let (sorted, handled) =
+            CSharpNormalizer.normalize_children("initializer_expression", children);
+        assert!(handled);
+        assert_eq!(sorted[0], assignment("X", "1"));
+
+        let collection = vec![
+            NormalizedNode::leaf("integer_literal".into(), "2".into()),
+            NormalizedNode::leaf("integer_literal".into(), "1".into()),
+        ];
+        let (kept, _) = CSharpNormalizer.normalize_children("initializer_expression", collection);
+        assert_eq!(kept[0].text.as_deref(), Some("2"));
+    }
+}
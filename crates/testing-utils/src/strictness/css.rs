@@ -0,0 +1,142 @@
+//! CSS semantic normalizer.
+
+use std::collections::HashSet;
+use tree_sitter::Parser;
+
+use super::traits::{NormalizedNode, ParserProvider, SemanticNormalizer};
+use super::utils::extract_sort_key;
+
+const COMMENT_SCOPE_KINDS: &[&str] = &["stylesheet", "block"];
+
+/// Semantic normalizer for CSS.
+pub struct CssNormalizer;
+
+impl ParserProvider for CssNormalizer {
+    fn language_ids(&self) -> &[&'static str] {
+        &["css"]
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &[".css"]
+    }
+
+    fn get_parser(&self) -> Option<Parser> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_css::LANGUAGE.into())
+            .ok()?;
+        Some(parser)
+    }
+}
+
+impl SemanticNormalizer for CssNormalizer {
+    fn unordered_node_types(&self) -> HashSet<&'static str> {
+        // `a, b {}` selects the same elements as `b, a {}`
+        ["selectors"].into_iter().collect()
+    }
+
+    fn normalize_children(
+        &self,
+        node_kind: &str,
+        children: Vec<NormalizedNode>,
+    ) -> (Vec<NormalizedNode>, bool) {
+        match node_kind {
+            "block" if declarations_are_independent(&children) => {
+                let mut children = children;
+                children.sort_by_key(extract_sort_key);
+                (children, true)
+            }
+            _ => (children, false),
+        }
+    }
+
+    fn comment_scope_kinds(&self) -> &'static [&'static str] {
+        COMMENT_SCOPE_KINDS
+    }
+}
+
+/// Whether a block only holds declarations whose order cannot matter.
+///
+/// Later declarations win over earlier ones, so blocks that set a property
+/// twice, or set both a shorthand and one of its longhands, keep their order:
+/// ```css
+/// a { color: red; margin: 0; }             /* can reorder */
+/// a { margin: 0; margin-left: 4px; }       /* order matters */
+/// ```
+fn declarations_are_independent(children: &[NormalizedNode]) -> bool {
+    let mut properties = Vec::with_capacity(children.len());
+    for child in children {
+        if child.kind != "declaration" {
+            return false;
+        }
+        match child.children.first().and_then(|c| c.text.as_deref()) {
+            Some(property) => properties.push(property.to_ascii_lowercase()),
+            None => return false,
+        }
+    }
+
+    properties.iter().enumerate().all(|(i, property)| {
+        properties.iter().skip(i + 1).all(|other| {
+            other != property
+                && !other.starts_with(&format!("{property}-"))
+                && !property.starts_with(&format!("{other}-"))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declaration(property: &str, value: &str) -> NormalizedNode {
+        NormalizedNode::new(
+            "declaration".into(),
+            vec![
+                NormalizedNode::leaf("property_name".into(), property.into()),
+                NormalizedNode::leaf("plain_value".into(), value.into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_language_ids() {
+        let normalizer = CssNormalizer;
+        assert!(normalizer.handles_language("css"));
+        assert!(normalizer.handles_language("CSS"));
+        assert!(!normalizer.handles_language("html"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let normalizer = CssNormalizer;
+        assert!(normalizer.handles_extension(".css"));
+        assert!(!normalizer.handles_extension(".html"));
+    }
+
+    #[test]
+    fn test_get_parser() {
+        assert!(CssNormalizer.get_parser().is_some());
+    }
+
+    #[test]
+    fn test_independent_declarations_are_sorted() {
+        let children = vec![declaration("margin", "0"), declaration("color", "red")];
+        let (sorted, handled) = CssNormalizer.normalize_children("block", children);
+        assert!(handled);
+        assert_eq!(sorted[0], declaration("color", "red"));
+    }
+
+    #[test]
+    fn test_overriding_declarations_keep_their_order() {
+        let shorthand = vec![
+            declaration("margin", "0"),
+            declaration("margin-left", "4px"),
+        ];
+        assert!(!declarations_are_independent(&shorthand));
+
+        let repeated = vec![declaration("color", "red"), declaration("color", "blue")];
+        let (children, handled) = CssNormalizer.normalize_children("block", repeated);
+        assert!(!handled);
+        assert_eq!(children[0], declaration("color", "red"));
+    }
+}
@@ -86,12 +86,17 @@ mod tests {
         assert_eq!(detect_language(Path::new("file.go")), Some("go"));
         assert_eq!(detect_language(Path::new("file.rs")), Some("rust"));
         assert_eq!(detect_language(Path::new("file.json")), Some("json"));
+        assert_eq!(detect_language(Path::new("file.yml")), Some("yaml"));
+        assert_eq!(detect_language(Path::new("file.css")), Some("css"));
+        assert_eq!(detect_language(Path::new("file.html")), Some("html"));
+        assert_eq!(detect_language(Path::new("file.java")), Some("java"));
+        assert_eq!(detect_language(Path::new("file.cs")), Some("csharp"));
     }
 
     #[test]
     fn test_detect_language_unknown() {
-        assert_eq!(detect_language(Path::new("file.css")), None);
-        assert_eq!(detect_language(Path::new("file.html")), None);
+        assert_eq!(detect_language(Path::new("file.kt")), None);
+        assert_eq!(detect_language(Path::new("file.vue")), None);
         assert_eq!(detect_language(Path::new("file")), None);
     }
 
@@ -0,0 +1,113 @@
+//! HTML semantic normalizer.
+
+use std::collections::HashSet;
+use tree_sitter::Parser;
+
+use super::traits::{NormalizedNode, ParserProvider, SemanticNormalizer};
+use super::utils::extract_sort_key;
+
+const COMMENT_SCOPE_KINDS: &[&str] = &["document", "element"];
+
+/// Semantic normalizer for HTML.
+pub struct HtmlNormalizer;
+
+impl ParserProvider for HtmlNormalizer {
+    fn language_ids(&self) -> &[&'static str] {
+        &["html", "htm"]
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &[".html", ".htm"]
+    }
+
+    fn get_parser(&self) -> Option<Parser> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_html::LANGUAGE.into())
+            .ok()?;
+        Some(parser)
+    }
+}
+
+impl SemanticNormalizer for HtmlNormalizer {
+    fn unordered_node_types(&self) -> HashSet<&'static str> {
+        // Tags are handled in normalize_children so the tag name stays first
+        HashSet::new()
+    }
+
+    fn normalize_children(
+        &self,
+        node_kind: &str,
+        children: Vec<NormalizedNode>,
+    ) -> (Vec<NormalizedNode>, bool) {
+        match node_kind {
+            "start_tag" | "self_closing_tag" => (sort_attributes(children), true),
+            _ => (children, false),
+        }
+    }
+
+    fn comment_scope_kinds(&self) -> &'static [&'static str] {
+        COMMENT_SCOPE_KINDS
+    }
+}
+
+/// Sort a tag's attributes by name, keeping the tag name in front.
+/// ```html
+/// <a href="/" class="link">  <!-- same as <a class="link" href="/"> -->
+/// ```
+fn sort_attributes(children: Vec<NormalizedNode>) -> Vec<NormalizedNode> {
+    let (mut attributes, mut rest): (Vec<_>, Vec<_>) = children
+        .into_iter()
+        .partition(|child| child.kind == "attribute");
+    attributes.sort_by_key(extract_sort_key);
+    rest.append(&mut attributes);
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(name: &str) -> NormalizedNode {
+        NormalizedNode::new(
+            "attribute".into(),
+            vec![NormalizedNode::leaf("attribute_name".into(), name.into())],
+        )
+    }
+
+    #[test]
+    fn test_language_ids() {
+        let normalizer = HtmlNormalizer;
+        assert!(normalizer.handles_language("html"));
+        assert!(normalizer.handles_language("HTML"));
+        assert!(!normalizer.handles_language("css"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let normalizer = HtmlNormalizer;
+        assert!(normalizer.handles_extension(".html"));
+        assert!(normalizer.handles_extension(".htm"));
+        assert!(!normalizer.handles_extension(".css"));
+    }
+
+    #[test]
+    fn test_get_parser() {
+        assert!(HtmlNormalizer.get_parser().is_some());
+    }
+
+    #[test]
+    fn test_normalize_children_sorts_attributes_after_tag_name() {
+        let children = vec![
+            NormalizedNode::leaf("tag_name".into(), "a".into()),
+            attribute("href"),
+            attribute("class"),
+        ];
+
+        let (sorted, handled) = HtmlNormalizer.normalize_children("start_tag", children);
+        assert!(handled);
+        assert_eq!(sorted[0].kind, "tag_name");
+        assert_eq!(sorted[1], attribute("class"));
+        assert_eq!(sorted[2], attribute("href"));
+    }
+}
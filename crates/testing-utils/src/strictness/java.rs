@@ -0,0 +1,132 @@
+//! Java semantic normalizer.
+
+use std::collections::HashSet;
+use tree_sitter::Parser;
+
+use super::traits::{NormalizedNode, ParserProvider, SemanticNormalizer};
+use super::utils::extract_sort_key;
+
+const COMMENT_SCOPE_KINDS: &[&str] = &[
+    "program",
+    "class_body",
+    "interface_body",
+    "enum_body",
+    "block",
+    "constructor_body",
+    "switch_block",
+];
+
+/// Semantic normalizer for Java.
+pub struct JavaNormalizer;
+
+impl ParserProvider for JavaNormalizer {
+    fn language_ids(&self) -> &[&'static str] {
+        &["java"]
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &[".java"]
+    }
+
+    fn get_parser(&self) -> Option<Parser> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .ok()?;
+        Some(parser)
+    }
+}
+
+impl SemanticNormalizer for JavaNormalizer {
+    fn unordered_node_types(&self) -> HashSet<&'static str> {
+        // type_list covers `implements`, interface `extends` and `permits`
+        ["throws", "type_list", "catch_type"].into_iter().collect()
+    }
+
+    fn normalize_children(
+        &self,
+        node_kind: &str,
+        children: Vec<NormalizedNode>,
+    ) -> (Vec<NormalizedNode>, bool) {
+        match node_kind {
+            "annotation_argument_list" => (normalize_annotation_arguments(children), true),
+            _ => (children, false),
+        }
+    }
+
+    fn comment_scope_kinds(&self) -> &'static [&'static str] {
+        COMMENT_SCOPE_KINDS
+    }
+}
+
+/// Normalize annotation arguments.
+///
+/// Only sorts when every argument is named. A single unnamed value is the
+/// annotation's `value` element and stays as is.
+/// ```java
+/// @Column(name = "id", nullable = false)  // named - can reorder
+/// @SuppressWarnings("unchecked")          // unnamed
+/// ```
+fn normalize_annotation_arguments(mut children: Vec<NormalizedNode>) -> Vec<NormalizedNode> {
+    if children
+        .iter()
+        .all(|child| child.kind == "element_value_pair")
+    {
+        children.sort_by_key(extract_sort_key);
+    }
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(name: &str) -> NormalizedNode {
+        NormalizedNode::new(
+            "element_value_pair".into(),
+            vec![
+                NormalizedNode::leaf("identifier".into(), name.into()),
+                NormalizedNode::leaf("true".into(), "true".into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_language_ids() {
+        let normalizer = JavaNormalizer;
+        assert!(normalizer.handles_language("java"));
+        assert!(normalizer.handles_language("Java"));
+        assert!(!normalizer.handles_language("javascript"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let normalizer = JavaNormalizer;
+        assert!(normalizer.handles_extension(".java"));
+        assert!(!normalizer.handles_extension(".js"));
+    }
+
+    #[test]
+    fn test_get_parser() {
+        assert!(JavaNormalizer.get_parser().is_some());
+    }
+
+    #[test]
+    fn test_unordered_types() {
+        let types = JavaNormalizer.unordered_node_types();
+        assert!(types.contains("throws"));
+        assert!(types.contains("type_list"));
+        assert!(types.contains("catch_type"));
+        assert!(!types.contains("argument_list"));
+    }
+
+    #[test]
+    fn test_normalize_children_named_annotation_arguments() {
+        let children = vec![pair("nullable"), pair("name")];
+        let (sorted, handled) =
+            JavaNormalizer.normalize_children("annotation_argument_list", children);
+        assert!(handled);
+        assert_eq!(sorted[0], pair("name"));
+        assert_eq!(sorted[1], pair("nullable"));
+    }
+}
@@ -5,9 +5,10 @@
 //! - Object property ordering
 //! - Dictionary key ordering
 //! - Keyword argument ordering (Python)
+//! - Mapping key ordering (YAML) and attribute ordering (HTML)
 //! - Comment indentation differences (for non-indentation-sensitive languages like JS, TS, Go, Rust, JSON)
 //!
-//! Note: Python preserves indentation checking since indentation is semantically significant.
+//! Note: Python and YAML preserve indentation checking since indentation is semantically significant.
 //!
 //! Note: Whitespace normalization is handled separately by the test runner
 //! (e.g., via the `ignore_whitespace` option for strict mode). The AST/CST/loose
@@ -49,9 +50,13 @@
 //! ```
 
 mod compare;
+mod csharp;
+mod css;
 mod detect;
 mod diff;
 mod go;
+mod html;
+mod java;
 mod javascript;
 mod json;
 mod python;
@@ -60,6 +65,7 @@ mod rust_lang;
 mod traits;
 mod typescript;
 mod utils;
+mod yaml;
 
 pub use compare::{
     ast_compare, ast_compare_with_parser_registry, ast_compare_with_registry, cst_compare,
@@ -77,9 +83,14 @@ pub use diff::{semantic_diff, semantic_diff_with_registry, DiffEntry, DiffKind,
 pub use registry::{NormalizerRegistry, ParserRegistry};
 pub use traits::{NormalizedNode, ParserProvider, SemanticNormalizer};
 
+pub use csharp::CSharpNormalizer;
+pub use css::CssNormalizer;
 pub use go::GoNormalizer;
+pub use html::HtmlNormalizer;
+pub use java::JavaNormalizer;
 pub use javascript::JavaScriptNormalizer;
 pub use json::JsonNormalizer;
 pub use python::PythonNormalizer;
 pub use rust_lang::RustNormalizer;
 pub use typescript::{TsxNormalizer, TypeScriptNormalizer};
+pub use yaml::YamlNormalizer;
@@ -3,13 +3,18 @@
 use once_cell::sync::Lazy;
 use tree_sitter::Parser;
 
+use super::csharp::CSharpNormalizer;
+use super::css::CssNormalizer;
 use super::go::GoNormalizer;
+use super::html::HtmlNormalizer;
+use super::java::JavaNormalizer;
 use super::javascript::JavaScriptNormalizer;
 use super::json::JsonNormalizer;
 use super::python::PythonNormalizer;
 use super::rust_lang::RustNormalizer;
 use super::traits::{ParserProvider, SemanticNormalizer};
 use super::typescript::{TsxNormalizer, TypeScriptNormalizer};
+use super::yaml::YamlNormalizer;
 
 /// Lazily initialized default normalizer registry.
 ///
@@ -59,6 +64,11 @@ impl ParserRegistry {
         registry.register(Box::new(GoNormalizer));
         registry.register(Box::new(RustNormalizer));
         registry.register(Box::new(JsonNormalizer));
+        registry.register(Box::new(YamlNormalizer));
+        registry.register(Box::new(CssNormalizer));
+        registry.register(Box::new(HtmlNormalizer));
+        registry.register(Box::new(JavaNormalizer));
+        registry.register(Box::new(CSharpNormalizer));
         registry
     }
 
@@ -155,6 +165,11 @@ impl NormalizerRegistry {
         registry.register(Box::new(GoNormalizer));
         registry.register(Box::new(RustNormalizer));
         registry.register(Box::new(JsonNormalizer));
+        registry.register(Box::new(YamlNormalizer));
+        registry.register(Box::new(CssNormalizer));
+        registry.register(Box::new(HtmlNormalizer));
+        registry.register(Box::new(JavaNormalizer));
+        registry.register(Box::new(CSharpNormalizer));
         registry
     }
 
@@ -217,7 +232,8 @@ mod tests {
     #[test]
     fn test_with_defaults_has_all_normalizers() {
         let registry = NormalizerRegistry::with_defaults();
-        assert_eq!(registry.len(), 7); // JS, TS, TSX, Python, Go, Rust, JSON
+        // JS, TS, TSX, Python, Go, Rust, JSON, YAML, CSS, HTML, Java, C#
+        assert_eq!(registry.len(), 12);
     }
 
     #[test]
@@ -233,8 +249,13 @@ mod tests {
         assert!(registry.get("golang").is_some());
         assert!(registry.get("rust").is_some());
         assert!(registry.get("json").is_some());
+        assert!(registry.get("yaml").is_some());
+        assert!(registry.get("css").is_some());
+        assert!(registry.get("html").is_some());
+        assert!(registry.get("java").is_some());
+        assert!(registry.get("csharp").is_some());
         assert!(registry.get("unknown").is_none());
-        assert!(registry.get("css").is_none());
+        assert!(registry.get("kotlin").is_none());
     }
 
     #[test]
@@ -249,9 +270,14 @@ mod tests {
         assert!(registry.get_by_extension(".go").is_some());
         assert!(registry.get_by_extension(".rs").is_some());
         assert!(registry.get_by_extension(".json").is_some());
+        assert!(registry.get_by_extension(".yml").is_some());
+        assert!(registry.get_by_extension(".css").is_some());
+        assert!(registry.get_by_extension(".html").is_some());
+        assert!(registry.get_by_extension(".java").is_some());
+        assert!(registry.get_by_extension(".cs").is_some());
 
-        assert!(registry.get_by_extension(".css").is_none());
-        assert!(registry.get_by_extension(".html").is_none());
+        assert!(registry.get_by_extension(".kt").is_none());
+        assert!(registry.get_by_extension(".vue").is_none());
     }
 
     #[test]
@@ -279,21 +305,22 @@ mod tests {
     fn test_supports_language() {
         let registry = NormalizerRegistry::default();
         assert!(registry.supports_language("javascript"));
-        assert!(!registry.supports_language("css"));
+        assert!(!registry.supports_language("kotlin"));
     }
 
     #[test]
     fn test_supports_extension() {
         let registry = NormalizerRegistry::default();
         assert!(registry.supports_extension(".js"));
-        assert!(!registry.supports_extension(".css"));
+        assert!(!registry.supports_extension(".kt"));
     }
 
     // ParserRegistry tests
     #[test]
     fn test_parser_registry_with_defaults() {
         let registry = ParserRegistry::with_defaults();
-        assert_eq!(registry.len(), 7); // JS, TS, TSX, Python, Go, Rust, JSON
+        // JS, TS, TSX, Python, Go, Rust, JSON, YAML, CSS, HTML, Java, C#
+        assert_eq!(registry.len(), 12);
     }
 
     #[test]
@@ -308,6 +335,8 @@ mod tests {
         assert!(registry.get("go").is_some());
         assert!(registry.get("rust").is_some());
         assert!(registry.get("json").is_some());
+        assert!(registry.get("yaml").is_some());
+        assert!(registry.get("java").is_some());
         assert!(registry.get("unknown").is_none());
     }
 
@@ -324,6 +353,7 @@ mod tests {
     fn test_parser_registry_supports_language() {
         let registry = ParserRegistry::default();
         assert!(registry.supports_language("javascript"));
-        assert!(!registry.supports_language("css"));
+        assert!(registry.supports_language("css"));
+        assert!(!registry.supports_language("kotlin"));
     }
 }
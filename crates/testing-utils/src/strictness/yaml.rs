@@ -0,0 +1,78 @@
+//! YAML semantic normalizer.
+
+use std::collections::HashSet;
+use tree_sitter::Parser;
+
+use super::traits::{ParserProvider, SemanticNormalizer};
+
+const COMMENT_SCOPE_KINDS: &[&str] = &["stream", "document", "block_sequence"];
+
+/// Semantic normalizer for YAML.
+pub struct YamlNormalizer;
+
+impl ParserProvider for YamlNormalizer {
+    fn language_ids(&self) -> &[&'static str] {
+        &["yaml", "yml"]
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &[".yaml", ".yml"]
+    }
+
+    fn get_parser(&self) -> Option<Parser> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_yaml::LANGUAGE.into())
+            .ok()?;
+        Some(parser)
+    }
+}
+
+impl SemanticNormalizer for YamlNormalizer {
+    fn unordered_node_types(&self) -> HashSet<&'static str> {
+        // Mapping keys are unordered; sequences keep their order
+        ["block_mapping", "flow_mapping"].into_iter().collect()
+    }
+
+    fn comment_scope_kinds(&self) -> &'static [&'static str] {
+        COMMENT_SCOPE_KINDS
+    }
+
+    fn is_indentation_sensitive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_ids() {
+        let normalizer = YamlNormalizer;
+        assert!(normalizer.handles_language("yaml"));
+        assert!(normalizer.handles_language("YML"));
+        assert!(!normalizer.handles_language("json"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let normalizer = YamlNormalizer;
+        assert!(normalizer.handles_extension(".yaml"));
+        assert!(normalizer.handles_extension(".yml"));
+        assert!(!normalizer.handles_extension(".json"));
+    }
+
+    #[test]
+    fn test_get_parser() {
+        assert!(YamlNormalizer.get_parser().is_some());
+    }
+
+    #[test]
+    fn test_unordered_types() {
+        let types = YamlNormalizer.unordered_node_types();
+        assert!(types.contains("block_mapping"));
+        assert!(types.contains("flow_mapping"));
+        assert!(!types.contains("block_sequence"));
+    }
+}
@@ -0,0 +1,9 @@
+fn main() {
+    // Grammars compiled from source at runtime target the same platform as
+    // this binary; `cc` needs the triple spelled out outside of build scripts.
+    println!(
+        "cargo:rustc-env=BUILD_TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+}
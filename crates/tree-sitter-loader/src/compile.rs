@@ -0,0 +1,203 @@
+use crate::{parser_extension, LoaderError};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory holding the `parser.c` of the grammar in `grammar_dir`.
+pub(crate) fn parser_source_dir(grammar_dir: &Path) -> Option<PathBuf> {
+    [grammar_dir.join("src"), grammar_dir.to_path_buf()]
+        .into_iter()
+        .find(|dir| dir.join("parser.c").is_file())
+}
+
+/// Compile the grammar in `grammar_dir` into a shared library under
+/// `cache_dir`, reusing an earlier build of the same sources.
+pub(crate) fn compile_grammar(
+    name: &str,
+    grammar_dir: &Path,
+    cache_dir: &Path,
+) -> Result<PathBuf, LoaderError> {
+    let src_dir = parser_source_dir(grammar_dir).ok_or_else(|| {
+        LoaderError::Compile(format!(
+            "{name}: no src/parser.c found in {}",
+            grammar_dir.display()
+        ))
+    })?;
+
+    let parser = src_dir.join("parser.c");
+    let scanner = ["scanner.c", "scanner.cc", "scanner.cpp"]
+        .iter()
+        .map(|file| src_dir.join(file))
+        .find(|path| path.is_file());
+    let is_cpp = scanner
+        .as_ref()
+        .is_some_and(|path| path.extension().is_some_and(|ext| ext != "c"));
+
+    let output_dir = cache_dir.join("compiled").join(name);
+    let output = output_dir.join(format!(
+        "{}.{}",
+        source_hash(&src_dir, &parser, scanner.as_deref())?,
+        parser_extension()
+    ));
+    if output.is_file() {
+        log::debug!("Compiled parser {name} already cached at {output:?}");
+        return Ok(output);
+    }
+
+    log::info!("Compiling tree-sitter parser for {name} from {grammar_dir:?} ...");
+    std::fs::create_dir_all(&output_dir)?;
+    let temp_output = output_dir.join(format!(
+        ".{}.{}.tmp",
+        output.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id()
+    ));
+
+    let compiler = cc::Build::new()
+        .cargo_metadata(false)
+        .cargo_warnings(false)
+        .emit_rerun_if_env_changed(false)
+        .target(env!("BUILD_TARGET"))
+        .host(env!("BUILD_TARGET"))
+        .opt_level(2)
+        .debug(false)
+        .warnings(false)
+        .cpp(is_cpp)
+        .try_get_compiler()
+        .map_err(|e| LoaderError::Compile(format!("{name}: no C compiler available: {e}")))?;
+
+    let mut command = compiler.to_command();
+    if compiler.is_like_msvc() {
+        command
+            .args(["/nologo", "/LD", "/utf-8"])
+            .arg(format!("/I{}", src_dir.display()))
+            .arg(format!("/Fe{}", temp_output.display()))
+            .arg(&parser);
+        if let Some(scanner) = &scanner {
+            command.arg(scanner);
+        }
+    } else {
+        command
+            .args(["-shared", "-fPIC", "-fno-exceptions"])
+            .arg("-I")
+            .arg(&src_dir)
+            .arg("-o")
+            .arg(&temp_output)
+            .arg("-xc")
+            .arg(&parser);
+        if let Some(scanner) = &scanner {
+            command
+                .arg(if is_cpp { "-xc++" } else { "-xc" })
+                .arg(scanner);
+        }
+    }
+
+    let result = run_compiler(name, command);
+    if result.is_ok() {
+        std::fs::rename(&temp_output, &output)?;
+    } else {
+        let _ = std::fs::remove_file(&temp_output);
+    }
+    result?;
+
+    log::info!("Compiled {name} parser to {output:?}");
+    Ok(output)
+}
+
+fn run_compiler(name: &str, mut command: Command) -> Result<(), LoaderError> {
+    let output = command
+        .output()
+        .map_err(|e| LoaderError::Compile(format!("{name}: failed to run compiler: {e}")))?;
+    if !output.status.success() {
+        // MSVC reports diagnostics on stdout, gcc and clang on stderr.
+        let diagnostics = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Err(LoaderError::Compile(format!(
+            "{name}: compiler exited with {}: {}",
+            output.status,
+            diagnostics.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Hash of everything that affects the compiled library: the target, the
+/// parser and scanner sources and the bundled `tree_sitter/*.h` headers.
+fn source_hash(
+    src_dir: &Path,
+    parser: &Path,
+    scanner: Option<&Path>,
+) -> Result<String, LoaderError> {
+    let mut files = vec![parser.to_path_buf()];
+    files.extend(scanner.map(Path::to_path_buf));
+    if let Ok(headers) = std::fs::read_dir(src_dir.join("tree_sitter")) {
+        let mut headers: Vec<_> = headers.flatten().map(|entry| entry.path()).collect();
+        headers.sort();
+        files.extend(headers.into_iter().filter(|path| path.is_file()));
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(env!("BUILD_TARGET").as_bytes());
+    for file in files {
+        hasher.update([0]);
+        hasher.update(
+            file.strip_prefix(src_dir)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .as_bytes(),
+        );
+        hasher.update([0]);
+        hasher.update(std::fs::read(&file)?);
+    }
+    Ok(format!("{:x}", hasher.finalize())[..16].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cached_parser_has_symbol;
+
+    #[test]
+    fn compiles_and_caches_grammar_sources() {
+        let grammar = tempfile::tempdir().expect("create grammar directory");
+        let cache = tempfile::tempdir().expect("create parser cache");
+        let src = grammar.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("parser.c"),
+            "const void *tree_sitter_demo(void) { return 0; }\n",
+        )
+        .unwrap();
+
+        let library = compile_grammar("demo", grammar.path(), cache.path())
+            .expect("compile grammar with the system C compiler");
+        assert!(cached_parser_has_symbol(&library, "tree_sitter_demo"));
+
+        let modified = std::fs::metadata(&library).unwrap().modified().unwrap();
+        let again = compile_grammar("demo", grammar.path(), cache.path()).unwrap();
+        assert_eq!(again, library);
+        assert_eq!(
+            std::fs::metadata(&again).unwrap().modified().unwrap(),
+            modified
+        );
+
+        std::fs::write(
+            src.join("parser.c"),
+            "const void *tree_sitter_demo(void) { return (void *)1; }\n",
+        )
+        .unwrap();
+        let rebuilt = compile_grammar("demo", grammar.path(), cache.path()).unwrap();
+        assert_ne!(rebuilt, library);
+    }
+
+    #[test]
+    fn missing_parser_source_is_reported() {
+        let grammar = tempfile::tempdir().expect("create grammar directory");
+        let cache = tempfile::tempdir().expect("create parser cache");
+
+        let error = compile_grammar("demo", grammar.path(), cache.path()).unwrap_err();
+        assert!(matches!(error, LoaderError::Compile(message) if message.contains("parser.c")));
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A tree-sitter language declared by a codemod package (the `languages`
+/// list in `codemod.yaml`).
+///
+/// ```yaml
+/// languages:
+///   - name: hcl
+///     extensions: [hcl, tf]
+///     source: ./grammars/tree-sitter-hcl
+///   - name: nginx
+///     extensions: [conf]
+///     download:
+///       linux-x64:
+///         url: https://example.com/tree-sitter-nginx/linux-x64.so
+///         sha256: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageDeclaration {
+    /// Name used in `language:` fields and `CodemodLang::from_str`.
+    pub name: String,
+    /// File extensions (without the dot) that map to this language.
+    pub extensions: Vec<String>,
+    /// Exported parser function. Defaults to `tree_sitter_<name>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Character substituted for `$` in patterns when the grammar does not
+    /// accept `$` in identifiers. Defaults to `_`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expando_char: Option<char>,
+    /// Where the parser comes from.
+    #[serde(flatten)]
+    pub grammar: GrammarSource,
+}
+
+/// Where a declared language's parser comes from. Relative paths are
+/// resolved against the directory containing `codemod.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrammarSource {
+    /// A prebuilt shared library (`.so`, `.dylib` or `.dll`).
+    Library(PathBuf),
+    /// A `.wasm` grammar as produced by `tree-sitter build --wasm`. The
+    /// native engine compiles the grammar sources next to the module.
+    Wasm(PathBuf),
+    /// A grammar directory containing `src/parser.c` and optionally
+    /// `src/scanner.c` or `src/scanner.cc`. Compiled with the system C
+    /// compiler and cached by content hash.
+    Source(PathBuf),
+    /// Prebuilt shared libraries keyed by platform (`linux-x64`,
+    /// `linux-arm64`, `darwin-x64`, `darwin-arm64`, `win32-x64`).
+    Download(BTreeMap<String, DownloadArtifact>),
+}
+
+/// A downloadable parser pinned to its SHA-256 digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadArtifact {
+    pub url: String,
+    /// Lowercase hex SHA-256 of the artifact. Downloads and cached copies
+    /// that do not match are rejected.
+    pub sha256: String,
+}
+
+impl LanguageDeclaration {
+    pub fn symbol(&self) -> String {
+        self.symbol
+            .clone()
+            .unwrap_or_else(|| format!("tree_sitter_{}", self.name.replace('-', "_")))
+    }
+
+    pub fn expando_char(&self) -> char {
+        self.expando_char.unwrap_or('_')
+    }
+
+    /// Make local grammar paths absolute by resolving them against `base_dir`.
+    pub fn resolve_paths(mut self, base_dir: &Path) -> Self {
+        match &mut self.grammar {
+            GrammarSource::Library(path)
+            | GrammarSource::Wasm(path)
+            | GrammarSource::Source(path) => {
+                if path.is_relative() {
+                    *path = base_dir.join(&*path);
+                }
+            }
+            GrammarSource::Download(_) => {}
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_manifest_declarations() {
+        let yaml = r#"
+- name: hcl
+  extensions: [hcl, tf]
+  source: grammars/tree-sitter-hcl
+- name: nginx
+  extensions: [conf]
+  symbol: tree_sitter_nginx_conf
+  download:
+    linux-x64:
+      url: https://example.com/nginx/linux-x64.so
+      sha256: abc123
+"#;
+        let declarations: Vec<LanguageDeclaration> = serde_yaml::from_str(yaml).unwrap();
+
+        let hcl = declarations[0].clone().resolve_paths(Path::new("/pkg"));
+        assert_eq!(
+            hcl.grammar,
+            GrammarSource::Source(PathBuf::from("/pkg/grammars/tree-sitter-hcl"))
+        );
+        assert_eq!(hcl.symbol(), "tree_sitter_hcl");
+        assert_eq!(hcl.expando_char(), '_');
+
+        let nginx = &declarations[1];
+        assert_eq!(nginx.symbol(), "tree_sitter_nginx_conf");
+        let GrammarSource::Download(artifacts) = &nginx.grammar else {
+            panic!("expected a download grammar");
+        };
+        assert_eq!(artifacts["linux-x64"].sha256, "abc123");
+    }
+}
@@ -1,7 +1,13 @@
+mod compile;
+mod declaration;
+
+pub use declaration::{DownloadArtifact, GrammarSource, LanguageDeclaration};
+
 use ast_grep_dynamic::{DynamicLang, Registration};
 use object::{Object, ObjectSymbol};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
-use std::sync::Once;
+use std::sync::{Mutex, Once};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -18,8 +24,28 @@ pub enum LoaderError {
     LockedCache(#[source] std::io::Error),
     #[error("Unsupported platform: os={os}, arch={arch}")]
     UnsupportedPlatform { os: String, arch: String },
+    #[error("Failed to compile parser: {0}")]
+    Compile(String),
+    #[error("No pinned sha256 for parser: {0}")]
+    Unpinned(String),
+    #[error("Checksum mismatch for {name} parser: expected sha256 {expected}, got {actual}")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Parser unavailable in offline mode: {0}")]
+    Offline(String),
+    #[error("Unsupported grammar: {0}")]
+    UnsupportedGrammar(String),
+    #[error("Dynamic parsers are already initialized; cannot add languages: {0}")]
+    AlreadyInitialized(String),
 }
 
+/// Environment variable that restricts the loader to cached and locally
+/// provided parsers.
+pub const OFFLINE_ENV: &str = "CODEMOD_PARSER_OFFLINE";
+
 struct DynamicLanguageDefinition {
     name: &'static str,
     symbol: &'static str,
@@ -28,16 +54,15 @@ struct DynamicLanguageDefinition {
     urls: &'static [(&'static str, &'static str, &'static str)], // (os, arch, url)
 }
 
+macro_rules! parser_bucket {
+    () => {
+        "https://tree-sitter-parsers.s3.us-east-1.amazonaws.com/tree-sitter/parsers/"
+    };
+}
+
 macro_rules! parser_url {
     ($parser:literal, $revision:literal, $artifact:literal) => {
-        concat!(
-            "https://tree-sitter-parsers.s3.us-east-1.amazonaws.com/tree-sitter/parsers/",
-            $parser,
-            "/",
-            $revision,
-            "/",
-            $artifact
-        )
+        concat!(parser_bucket!(), $parser, "/", $revision, "/", $artifact)
     };
 }
 
@@ -138,6 +163,85 @@ fn current_platform() -> Result<(&'static str, &'static str), LoaderError> {
     Ok((os, arch))
 }
 
+/// Platform key used for parser artifacts, e.g. `linux-x64` or `darwin-arm64`.
+fn platform_key() -> Result<&'static str, LoaderError> {
+    Ok(match current_platform()? {
+        ("macos", "aarch64") => "darwin-arm64",
+        ("macos", _) => "darwin-x64",
+        ("linux", "aarch64") => "linux-arm64",
+        ("linux", _) => "linux-x64",
+        (_, "aarch64") => "win32-arm64",
+        _ => "win32-x64",
+    })
+}
+
+pub(crate) fn parser_extension() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "dll"
+    } else if cfg!(target_os = "macos") {
+        "dylib"
+    } else {
+        "so"
+    }
+}
+
+/// Whether [`OFFLINE_ENV`] is set to anything other than `0` or `false`.
+pub fn is_offline() -> bool {
+    std::env::var(OFFLINE_ENV).is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Expected digests of the built-in parser artifacts, shipped with the
+/// binary so that neither downloads nor cached copies are trusted on first
+/// use.
+const PINNED_DIGESTS: &str = include_str!("../parsers.sha256");
+
+/// Look up the digest of `url` in `sha256sum`-formatted `digests`, whose
+/// paths are relative to the parser bucket.
+fn pinned_digest<'a>(digests: &'a str, url: &str) -> Option<&'a str> {
+    let artifact = url.strip_prefix(parser_bucket!())?;
+    digests
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, path)| path.trim_start().trim_start_matches('*') == artifact)
+        .map(|(digest, _)| digest)
+}
+
+fn download(url: &str) -> Result<Vec<u8>, LoaderError> {
+    let response = reqwest::blocking::get(url)
+        .map_err(|e| LoaderError::Download(format!("HTTP request failed: {e}")))?;
+
+    if !response.status().is_success() {
+        return Err(LoaderError::Download(format!(
+            "HTTP {} for {}",
+            response.status(),
+            url
+        )));
+    }
+
+    let bytes = response
+        .bytes()
+        .map_err(|e| LoaderError::Download(format!("Failed to read response body: {e}")))?;
+    Ok(bytes.to_vec())
+}
+
+/// Write through a temporary file so concurrent processes never load a
+/// partially written parser.
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), LoaderError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, path).map_err(|error| {
+        let _ = std::fs::remove_file(&temp_path);
+        LoaderError::Io(error)
+    })
+}
+
 fn get_cache_dir() -> Result<PathBuf, LoaderError> {
     if let Ok(dir) = std::env::var("CODEMOD_PARSER_CACHE_DIR") {
         return Ok(PathBuf::from(dir));
@@ -179,6 +283,7 @@ fn cached_parser_has_symbol(path: &Path, symbol: &str) -> bool {
 fn ensure_parser_cached(
     def: &DynamicLanguageDefinition,
     cache_dir: &Path,
+    offline: bool,
 ) -> Result<PathBuf, LoaderError> {
     let (os, arch) = current_platform()?;
 
@@ -192,57 +297,73 @@ fn ensure_parser_cached(
             arch: arch.to_string(),
         })?;
 
-    let ext = if cfg!(target_os = "windows") {
-        "dll"
-    } else if cfg!(target_os = "macos") {
-        "dylib"
-    } else {
-        "so"
-    };
+    let expected = pinned_digest(PINNED_DIGESTS, url)
+        .ok_or_else(|| LoaderError::Unpinned(format!("{} ({url})", def.name)))?;
+    ensure_pinned_parser(def, url, expected, cache_dir, offline)
+}
 
-    let filename = format!("{}.{}", def.name, ext);
+/// Download the built-in parser at `url` into `cache_dir` unless a copy
+/// matching `expected` is already cached.
+fn ensure_pinned_parser(
+    def: &DynamicLanguageDefinition,
+    url: &str,
+    expected: &str,
+    cache_dir: &Path,
+    offline: bool,
+) -> Result<PathBuf, LoaderError> {
+    let expected = expected.to_ascii_lowercase();
+    let filename = format!("{}.{}", def.name, parser_extension());
     let parser_dir = cache_dir.join(def.name);
     let cached_path = parser_dir.join(&filename);
 
     if cached_path.exists() {
-        if !cached_parser_has_symbol(&cached_path, def.symbol) {
-            log::warn!(
-                "Cached parser {} at {:?} does not export {}; redownloading",
-                def.name,
-                cached_path,
-                def.symbol
-            );
-            if let Err(error) = std::fs::remove_file(&cached_path) {
-                if cfg!(target_os = "windows") && error.raw_os_error() == Some(5) {
-                    return Err(LoaderError::LockedCache(error));
-                }
-                return Err(LoaderError::Io(error));
-            }
+        let problem = if !cached_parser_has_symbol(&cached_path, def.symbol) {
+            Some(format!("does not export {}", def.symbol))
+        } else if sha256_hex(&std::fs::read(&cached_path)?) != expected {
+            Some("does not match its pinned sha256".to_string())
         } else {
+            None
+        };
+        let Some(problem) = problem else {
             log::debug!("Parser {} already cached at {:?}", def.name, cached_path);
             return Ok(cached_path);
+        };
+
+        log::warn!(
+            "Cached parser {} at {:?} {}; redownloading",
+            def.name,
+            cached_path,
+            problem
+        );
+        if let Err(error) = std::fs::remove_file(&cached_path) {
+            if cfg!(target_os = "windows") && error.raw_os_error() == Some(5) {
+                return Err(LoaderError::LockedCache(error));
+            }
+            return Err(LoaderError::Io(error));
         }
     }
 
-    log::info!("Downloading tree-sitter parser for {} ...", def.name);
-    std::fs::create_dir_all(&parser_dir)?;
-
-    let response = reqwest::blocking::get(url)
-        .map_err(|e| LoaderError::Download(format!("HTTP request failed: {e}")))?;
-
-    if !response.status().is_success() {
-        return Err(LoaderError::Download(format!(
-            "HTTP {} for {}",
-            response.status(),
-            url
+    if offline {
+        return Err(LoaderError::Offline(format!(
+            "{} is not cached in {}",
+            def.name,
+            parser_dir.display()
         )));
     }
 
-    let bytes = response
-        .bytes()
-        .map_err(|e| LoaderError::Download(format!("Failed to read response body: {e}")))?;
+    log::info!("Downloading tree-sitter parser for {} ...", def.name);
+    let bytes = download(url)?;
+    let actual = sha256_hex(&bytes);
+    if actual != expected {
+        return Err(LoaderError::ChecksumMismatch {
+            name: def.name.to_string(),
+            expected,
+            actual,
+        });
+    }
 
-    std::fs::write(&cached_path, &bytes)?;
+    std::fs::create_dir_all(&parser_dir)?;
+    write_atomically(&cached_path, &bytes)?;
     log::info!(
         "Downloaded {} parser to {:?} ({} bytes)",
         def.name,
@@ -253,15 +374,130 @@ fn ensure_parser_cached(
     Ok(cached_path)
 }
 
+/// Resolve a package-declared language to a loadable shared library,
+/// compiling or downloading it into `cache_dir` when needed.
+fn ensure_declared_parser(
+    decl: &LanguageDeclaration,
+    cache_dir: &Path,
+    offline: bool,
+) -> Result<PathBuf, LoaderError> {
+    let library = match &decl.grammar {
+        GrammarSource::Library(path) => {
+            if !path.is_file() {
+                return Err(LoaderError::Register(format!(
+                    "{}: parser library {} does not exist",
+                    decl.name,
+                    path.display()
+                )));
+            }
+            path.clone()
+        }
+        GrammarSource::Wasm(path) => {
+            if !path.is_file() {
+                return Err(LoaderError::Register(format!(
+                    "{}: wasm grammar {} does not exist",
+                    decl.name,
+                    path.display()
+                )));
+            }
+            // The native engine cannot execute wasm, but `tree-sitter build
+            // --wasm` writes the module into the grammar directory, so build
+            // the same grammar from the sources next to it.
+            let grammar_dir = path
+                .parent()
+                .filter(|dir| compile::parser_source_dir(dir).is_some())
+                .ok_or_else(|| {
+                    LoaderError::UnsupportedGrammar(format!(
+                        "{}: {} is a wasm grammar without src/parser.c next to it; \
+                         the native engine needs the grammar sources or a prebuilt `library`",
+                        decl.name,
+                        path.display()
+                    ))
+                })?;
+            compile::compile_grammar(&decl.name, grammar_dir, cache_dir)?
+        }
+        GrammarSource::Source(dir) => compile::compile_grammar(&decl.name, dir, cache_dir)?,
+        GrammarSource::Download(artifacts) => {
+            let platform = platform_key()?;
+            let artifact = artifacts.get(platform).ok_or_else(|| {
+                LoaderError::Register(format!(
+                    "{}: no parser download declared for {platform}",
+                    decl.name
+                ))
+            })?;
+            ensure_declared_download(&decl.name, artifact, cache_dir, offline)?
+        }
+    };
+
+    let symbol = decl.symbol();
+    if !cached_parser_has_symbol(&library, &symbol) {
+        return Err(LoaderError::Register(format!(
+            "{}: {} does not export {symbol}",
+            decl.name,
+            library.display()
+        )));
+    }
+    Ok(library)
+}
+
+fn ensure_declared_download(
+    name: &str,
+    artifact: &DownloadArtifact,
+    cache_dir: &Path,
+    offline: bool,
+) -> Result<PathBuf, LoaderError> {
+    let expected = artifact.sha256.trim().to_ascii_lowercase();
+    let parser_dir = cache_dir.join("declared").join(name);
+    let cached_path = parser_dir.join(format!("{expected}.{}", parser_extension()));
+
+    if cached_path.is_file() {
+        if sha256_hex(&std::fs::read(&cached_path)?) == expected {
+            log::debug!("Parser {name} already cached at {cached_path:?}");
+            return Ok(cached_path);
+        }
+        log::warn!("Cached parser {name} at {cached_path:?} does not match its sha256");
+        std::fs::remove_file(&cached_path)?;
+    }
+
+    if offline {
+        return Err(LoaderError::Offline(format!(
+            "{name} is not cached in {}",
+            parser_dir.display()
+        )));
+    }
+
+    log::info!("Downloading tree-sitter parser for {name} ...");
+    let bytes = download(&artifact.url)?;
+    let actual = sha256_hex(&bytes);
+    if actual != expected {
+        return Err(LoaderError::ChecksumMismatch {
+            name: name.to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    std::fs::create_dir_all(&parser_dir)?;
+    write_atomically(&cached_path, &bytes)?;
+    Ok(cached_path)
+}
+
 fn prepare_registrations(
     definitions: &[DynamicLanguageDefinition],
+    declared: &[LanguageDeclaration],
     cache_dir: &Path,
+    offline: bool,
 ) -> Result<Vec<Registration>, LoaderError> {
     let mut registrations = Vec::new();
     let mut failures = Vec::new();
 
+    // A package may replace a built-in language with its own grammar.
+    let definitions = definitions
+        .iter()
+        .filter(|def| !declared.iter().any(|decl| decl.name == def.name));
+
     for def in definitions {
-        match ensure_parser_cached(def, cache_dir) {
+        match ensure_parser_cached(def, cache_dir, offline) {
             Ok(lib_path) => registrations.push(Registration {
                 lang_name: def.name.to_string(),
                 lib_path,
@@ -270,7 +506,12 @@ fn prepare_registrations(
                 expando_char: Some(def.expando_char),
                 extensions: def.extensions.iter().map(|s| s.to_string()).collect(),
             }),
-            Err(error @ LoaderError::LockedCache(_)) => {
+            Err(
+                error @ (LoaderError::LockedCache(_)
+                | LoaderError::Offline(_)
+                | LoaderError::Unpinned(_)
+                | LoaderError::ChecksumMismatch { .. }),
+            ) => {
                 log::warn!(
                     "Dynamic parser {} is unavailable and will be skipped: {error}",
                     def.name
@@ -281,6 +522,19 @@ fn prepare_registrations(
         }
     }
 
+    // Declared languages were requested explicitly, so any failure is fatal.
+    for decl in declared {
+        let lib_path = ensure_declared_parser(decl, cache_dir, offline)?;
+        registrations.push(Registration {
+            lang_name: decl.name.clone(),
+            lib_path,
+            symbol: decl.symbol(),
+            meta_var_char: None,
+            expando_char: Some(decl.expando_char()),
+            extensions: decl.extensions.clone(),
+        });
+    }
+
     if registrations.is_empty() {
         return Err(LoaderError::Register(format!(
             "No dynamic parsers are available: {}",
@@ -291,13 +545,74 @@ fn prepare_registrations(
     Ok(registrations)
 }
 
+struct Declared {
+    languages: Vec<LanguageDeclaration>,
+    frozen: bool,
+}
+
+static DECLARED: Mutex<Declared> = Mutex::new(Declared {
+    languages: Vec::new(),
+    frozen: false,
+});
+
+/// Add package-declared languages to the next registration.
+///
+/// Must be called before [`init`]: parsers are registered once per process,
+/// so adding a new language afterwards fails with
+/// [`LoaderError::AlreadyInitialized`]. Re-declaring an already configured
+/// language is a no-op. Relative grammar paths are resolved against
+/// `base_dir`, normally the directory containing `codemod.yaml`.
+pub fn configure(languages: Vec<LanguageDeclaration>, base_dir: &Path) -> Result<(), LoaderError> {
+    let mut declared = DECLARED.lock().unwrap_or_else(|e| e.into_inner());
+    let added: Vec<_> = languages
+        .into_iter()
+        .map(|language| language.resolve_paths(base_dir))
+        .filter(|language| !declared.languages.contains(language))
+        .collect();
+    if added.is_empty() {
+        return Ok(());
+    }
+    if let Some(language) = added
+        .iter()
+        .find(|language| language.name.trim().is_empty())
+    {
+        return Err(LoaderError::Register(format!(
+            "declared language with extensions {:?} has no name",
+            language.extensions
+        )));
+    }
+    if declared.frozen {
+        let names: Vec<_> = added
+            .iter()
+            .map(|language| language.name.as_str())
+            .collect();
+        return Err(LoaderError::AlreadyInitialized(names.join(", ")));
+    }
+
+    for language in added {
+        declared
+            .languages
+            .retain(|existing| existing.name != language.name);
+        declared.languages.push(language);
+    }
+    Ok(())
+}
+
 /// Register available dynamic language parsers, downloading any that are missing.
 ///
 /// This should be called once before using dynamic languages. A failure to prepare
-/// one parser does not prevent unrelated parsers from being registered.
+/// one built-in parser does not prevent unrelated parsers from being registered;
+/// languages added with [`configure`] must all load. With [`OFFLINE_ENV`] set,
+/// only cached and locally provided parsers are used.
 pub fn register_all() -> Result<(), LoaderError> {
     let cache_dir = get_cache_dir()?;
-    let registrations = prepare_registrations(get_definitions(), &cache_dir)?;
+    let declared = {
+        let mut declared = DECLARED.lock().unwrap_or_else(|e| e.into_inner());
+        declared.frozen = true;
+        declared.languages.clone()
+    };
+    let registrations =
+        prepare_registrations(get_definitions(), &declared, &cache_dir, is_offline())?;
 
     unsafe {
         DynamicLang::register(registrations).map_err(|e| LoaderError::Register(format!("{e}")))?;
@@ -344,7 +659,7 @@ mod tests {
             urls: &[],
         };
 
-        let error = match prepare_registrations(&[definition], cache.path()) {
+        let error = match prepare_registrations(&[definition], &[], cache.path(), false) {
             Ok(_) => panic!("non-locking parser failures must propagate"),
             Err(error) => error,
         };
@@ -352,11 +667,173 @@ mod tests {
         assert!(matches!(error, LoaderError::UnsupportedPlatform { .. }));
     }
 
+    #[test]
+    fn offline_mode_uses_only_verified_cached_downloads() {
+        let cache = tempfile::tempdir().expect("create parser cache");
+        let bytes = b"parser library";
+        let artifact = DownloadArtifact {
+            url: "https://example.invalid/parser".to_string(),
+            sha256: sha256_hex(bytes).to_uppercase(),
+        };
+        let cached_path = cache.path().join("declared").join("demo").join(format!(
+            "{}.{}",
+            sha256_hex(bytes),
+            parser_extension()
+        ));
+        std::fs::create_dir_all(cached_path.parent().unwrap()).unwrap();
+
+        std::fs::write(&cached_path, bytes).unwrap();
+        let resolved = ensure_declared_download("demo", &artifact, cache.path(), true)
+            .expect("use the cached parser offline");
+        assert_eq!(resolved, cached_path);
+
+        // A tampered cache entry is discarded rather than loaded
+        std::fs::write(&cached_path, b"tampered").unwrap();
+        let error = ensure_declared_download("demo", &artifact, cache.path(), true).unwrap_err();
+        assert!(matches!(error, LoaderError::Offline(_)));
+        assert!(!cached_path.exists());
+    }
+
+    #[test]
+    fn wasm_grammars_are_built_from_their_sources() {
+        let grammar = tempfile::tempdir().expect("create grammar directory");
+        let cache = tempfile::tempdir().expect("create parser cache");
+        std::fs::create_dir_all(grammar.path().join("src")).unwrap();
+        std::fs::write(
+            grammar.path().join("src").join("parser.c"),
+            "const void *tree_sitter_demo(void) { return 0; }\n",
+        )
+        .unwrap();
+        let wasm_path = grammar.path().join("tree-sitter-demo.wasm");
+        std::fs::write(&wasm_path, b"\0asm").unwrap();
+
+        let declaration = LanguageDeclaration {
+            name: "demo".to_string(),
+            extensions: vec!["demo".to_string()],
+            symbol: None,
+            expando_char: None,
+            grammar: GrammarSource::Wasm(wasm_path),
+        };
+        let library = ensure_declared_parser(&declaration, cache.path(), true)
+            .expect("compile the grammar next to the wasm module");
+        assert!(cached_parser_has_symbol(&library, "tree_sitter_demo"));
+    }
+
+    #[test]
+    fn builtin_parsers_must_match_pinned_digests() {
+        let url = parser_url!("tree-sitter-demo", "abc123", "linux-x64.so");
+        let digests = "# comment\n\
+                       0123abcd  tree-sitter-demo/abc123/darwin-arm64.dylib\n\
+                       4567ef01 *tree-sitter-demo/abc123/linux-x64.so\n";
+        assert_eq!(pinned_digest(digests, url), Some("4567ef01"));
+        assert_eq!(pinned_digest(digests, "https://example.com/demo.so"), None);
+
+        let cache = tempfile::tempdir().expect("create parser cache");
+        let definition = DynamicLanguageDefinition {
+            name: "demo",
+            symbol: "tree_sitter_demo",
+            extensions: &["demo"],
+            expando_char: '_',
+            urls: &[],
+        };
+        let grammar = tempfile::tempdir().expect("create grammar directory");
+        std::fs::create_dir_all(grammar.path().join("src")).unwrap();
+        std::fs::write(
+            grammar.path().join("src").join("parser.c"),
+            "const void *tree_sitter_demo(void) { return 0; }\n",
+        )
+        .unwrap();
+        let library = compile::compile_grammar("demo", grammar.path(), cache.path()).unwrap();
+        let bytes = std::fs::read(&library).unwrap();
+        let cached_path = cache
+            .path()
+            .join("demo")
+            .join(format!("demo.{}", parser_extension()));
+        std::fs::create_dir_all(cached_path.parent().unwrap()).unwrap();
+        std::fs::write(&cached_path, &bytes).unwrap();
+
+        let resolved =
+            ensure_pinned_parser(&definition, url, &sha256_hex(&bytes), cache.path(), true)
+                .expect("use the cached parser matching its pinned digest");
+        assert_eq!(resolved, cached_path);
+
+        // A cached copy that is not the pinned artifact is never loaded
+        let error = ensure_pinned_parser(&definition, url, &"0".repeat(64), cache.path(), true)
+            .unwrap_err();
+        assert!(matches!(error, LoaderError::Offline(_)));
+        assert!(!cached_path.exists());
+
+        let unpinned = DynamicLanguageDefinition {
+            urls: &[
+                ("macos", "aarch64", "https://example.com/demo.dylib"),
+                ("macos", "x86_64", "https://example.com/demo.dylib"),
+                ("linux", "aarch64", "https://example.com/demo.so"),
+                ("linux", "x86_64", "https://example.com/demo.so"),
+                ("windows", "x86_64", "https://example.com/demo.dll"),
+            ],
+            ..definition
+        };
+        assert!(matches!(
+            ensure_parser_cached(&unpinned, cache.path(), false),
+            Err(LoaderError::Unpinned(_))
+        ));
+    }
+
+    #[test]
+    fn every_built_in_parser_is_pinned() {
+        let unpinned = get_definitions()
+            .iter()
+            .flat_map(|definition| definition.urls.iter())
+            .map(|(_, _, url)| *url)
+            .filter(|url| pinned_digest(PINNED_DIGESTS, url).is_none())
+            .collect::<Vec<_>>();
+        assert!(
+            unpinned.is_empty(),
+            "parsers.sha256 has no digest for {unpinned:#?}; run scripts/pin-tree-sitter-parsers.sh"
+        );
+    }
+
+    #[test]
+    fn declared_grammars_report_unloadable_sources() {
+        let cache = tempfile::tempdir().expect("create parser cache");
+        let declare = |grammar| LanguageDeclaration {
+            name: "demo".to_string(),
+            extensions: vec!["demo".to_string()],
+            symbol: None,
+            expando_char: None,
+            grammar,
+        };
+
+        let wasm_path = cache.path().join("demo.wasm");
+        let wasm = declare(GrammarSource::Wasm(wasm_path.clone()));
+        assert!(matches!(
+            ensure_declared_parser(&wasm, cache.path(), false),
+            Err(LoaderError::Register(message)) if message.contains("does not exist")
+        ));
+        std::fs::write(&wasm_path, b"\0asm").unwrap();
+        assert!(matches!(
+            ensure_declared_parser(&wasm, cache.path(), false),
+            Err(LoaderError::UnsupportedGrammar(_))
+        ));
+
+        let missing = declare(GrammarSource::Library(cache.path().join("missing.so")));
+        assert!(matches!(
+            ensure_declared_parser(&missing, cache.path(), false),
+            Err(LoaderError::Register(message)) if message.contains("does not exist")
+        ));
+
+        let other_platform = declare(GrammarSource::Download(Default::default()));
+        assert!(matches!(
+            ensure_declared_parser(&other_platform, cache.path(), true),
+            Err(LoaderError::Register(message)) if message.contains("no parser download")
+        ));
+    }
+
     #[test]
     #[ignore = "downloads published parser artifacts"]
     fn published_parsers_export_expected_symbols() {
         let cache = tempfile::tempdir().expect("create parser cache");
-        let registrations = prepare_registrations(get_definitions(), cache.path())
+        let registrations = prepare_registrations(get_definitions(), &[], cache.path(), false)
             .expect("download and validate published parsers");
 
         let registered_names = registrations
@@ -452,7 +929,7 @@ mod tests {
                 },
             ];
 
-            let registrations = prepare_registrations(&definitions, cache.path())
+            let registrations = prepare_registrations(&definitions, &[], cache.path(), false)
                 .expect("prepare the unrelated parser despite the locked cache entry");
 
             assert_eq!(registrations.len(), 1);
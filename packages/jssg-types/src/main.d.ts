@@ -42,6 +42,9 @@ export type {
   Transform,
   GetSelectorOptions,
   GetSelector,
+  Project,
+  ProjectEdit,
+  TransformProject,
 } from "codemod:ast-grep";
 
 export { parse, parseAsync, jssgTransform } from "codemod:ast-grep";
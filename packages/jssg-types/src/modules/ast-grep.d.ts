@@ -84,6 +84,92 @@ declare module "codemod:ast-grep" {
      * @returns An array of file references, each with a root and nodes
      */
     references(): Array<FileReferences<M>>;
+
+    /**
+     * Get the type of the expression at this node's position.
+     *
+     * - Python: the type inferred by ty (e.g. `dict[str, int]`).
+     * - JavaScript/TypeScript: the declared type, taken from annotations,
+     *   literal initializers, function return annotations (for calls) and
+     *   type aliases, including aliases imported from other files.
+     *
+     * Returns null if:
+     * - No semantic provider is configured
+     * - No type can be determined for this node
+     *
+     * @returns The type as source text, or null
+     */
+    typeInfo(): string | null;
+    /**
+     * Get the innermost lexical scope containing this node.
+     *
+     * Scopes come from OXC for JavaScript/TypeScript and from the ruff AST
+     * for Python, following each language's scoping rules.
+     *
+     * Returns null if:
+     * - No semantic provider is configured
+     * - No scope information is available for this file
+     *
+     * @returns The innermost scope, or null
+     */
+    scope(): Scope<M> | null;
+    /**
+     * Check whether this node can run at all, i.e. it is not dead code after
+     * a `return`, `throw`/`raise`, `break` or `continue`.
+     *
+     * The control-flow graph covers the innermost function containing the
+     * node (or the module's top level). Calls are assumed not to throw.
+     *
+     * @throws If no semantic provider is configured
+     * @returns false if no path from the function entry reaches this node
+     */
+    isReachable(): boolean;
+    /**
+     * Check whether every path from the function entry to `other` passes
+     * through this node.
+     *
+     * @param other A node in the same function
+     * @throws If no semantic provider is configured
+     * @returns false if the nodes are in different functions or files
+     */
+    dominates(other: SgNode<M>): boolean;
+    /**
+     * Check whether every path from `other` to the function exit passes
+     * through this node, e.g. a `close()` that runs after every `open()`.
+     *
+     * @param other A node in the same function
+     * @throws If no semantic provider is configured
+     * @returns false if the nodes are in different functions or files
+     */
+    postDominates(other: SgNode<M>): boolean;
+    /**
+     * Get the statements after which control leaves the function containing
+     * this node: `return` and uncaught `throw`/`raise` statements, and the
+     * last statement of every path that falls off the end of the body.
+     *
+     * @throws If no semantic provider is configured
+     * @returns Exit statements in source order
+     */
+    exitPoints(): Array<SgNode<M>>;
+    /**
+     * Rename the symbol at this node's position across the workspace.
+     *
+     * Renames the declaration, all references and the import/export
+     * specifiers that name the symbol. Aliased imports keep their local
+     * name, and shorthand properties are expanded (`{ old }` becomes
+     * `{ old: newName }`).
+     *
+     * Edits for other files are applied together with the rest of the run.
+     * If one of those files changes on disk before they are applied, the
+     * file is reported as an error and left untouched.
+     *
+     * @param newName - The new identifier
+     * @returns The edits for this node's file, to pass to `commitEdits()`
+     * @throws Error if no semantic provider is configured
+     * @throws Error if the symbol has no definition in the workspace
+     * @throws Error if the edits conflict with other changes to the same file
+     */
+    renameSymbol(newName: string): Array<Edit>;
   }
   /** Represents the parsed tree of code. */
   export class SgRoot<M extends TypesMap = TypesMap> {
@@ -158,6 +244,19 @@ declare module "codemod:ast-grep" {
      * ```
      */
     rename(newPath: string): void;
+    /**
+     * Absolute paths of the workspace files this file imports or re-exports from.
+     * Packages outside the workspace are not included.
+     *
+     * Requires workspace-scope semantic analysis; returns an empty array otherwise.
+     */
+    imports(): string[];
+    /**
+     * Absolute paths of the workspace files that import or re-export from this file.
+     *
+     * Requires workspace-scope semantic analysis; returns an empty array otherwise.
+     */
+    importedBy(): string[];
   }
 
   interface NodeMethod<M extends TypesMap, Args extends unknown[] = []> {
@@ -437,6 +536,56 @@ declare module "codemod:ast-grep" {
     nodes: Array<SgNode<M>>;
   }
 
+  /**
+   * The kind of a lexical scope.
+   * - 'module': the top level of a file
+   * - 'function': a function, method, arrow function or lambda
+   * - 'class': a class body
+   * - 'block': a block, loop, `catch` clause or comprehension
+   */
+  export type ScopeKind = "module" | "function" | "class" | "block";
+
+  /**
+   * A name declared in a scope.
+   */
+  export interface ScopeBinding<M extends TypesMap = TypesMap> {
+    /** The declared name */
+    name: string;
+    /** The kind of symbol, e.g. 'variable', 'function', 'parameter', 'import' */
+    kind: string;
+    /** The declaring identifier, or null if it can't be located */
+    node: SgNode<M> | null;
+  }
+
+  /**
+   * A lexical scope, returned by `node.scope()`.
+   */
+  export class Scope<M extends TypesMap = TypesMap> {
+    /** The kind of scope */
+    kind(): ScopeKind;
+    /** The node that introduces this scope (the whole file for the module scope) */
+    node(): SgNode<M> | null;
+    /** The enclosing scope, or null for the module scope */
+    parent(): Scope<M> | null;
+    /** Names declared directly in this scope, in declaration order */
+    bindings(): Array<ScopeBinding<M>>;
+    /** Whether `name` is declared in this scope or an enclosing one */
+    has(name: string): boolean;
+    /** Whether `name` is declared directly in this scope */
+    hasOwn(name: string): boolean;
+    /**
+     * Names used in this scope (including nested scopes) that are declared
+     * outside it: bindings of enclosing scopes and globals, sorted.
+     */
+    freeNames(): string[];
+    /**
+     * A name that can be declared in this scope without colliding with any
+     * binding visible here, declared in a nested scope or used from outside.
+     * Returns `base` if it is free, otherwise `base1`, `base2`, ...
+     */
+    uniqueName(base: string): string;
+  }
+
   /**
    * Reference
    * https://tree-sitter.github.io/tree-sitter/using-parsers#static-node-types
@@ -548,6 +697,27 @@ declare module "codemod:ast-grep" {
     matrixValues?: Record<string, any>;
     dryRun?: boolean;
     targetDir: string;
+    /** Set when the transform runs on a region embedded in a host file */
+    embedded?: EmbeddedRegion;
+  };
+
+  /**
+   * A code region inside a host file (a `<script>` or `<style>` block of a
+   * Vue, Svelte or HTML file, or a Markdown code fence). The root passed to
+   * the transform is the whole host file with everything outside the region
+   * blanked out, so ranges are host file offsets.
+   */
+  export type EmbeddedRegion = {
+    host: "vue" | "svelte" | "html" | "markdown";
+    kind: "script" | "style" | "fence";
+    /** Language named by the block, e.g. `ts` or `typescript` */
+    language: string;
+    /** Position of the region among the regions transformed in this file */
+    index: number;
+    /** Byte offset where the region starts in the host file */
+    start: number;
+    /** Byte offset where the region ends in the host file */
+    end: number;
   };
 
   /**
@@ -560,12 +730,54 @@ declare module "codemod:ast-grep" {
     options: TransformOptions<T>,
   ) => Promise<string | null>;
 
+  /**
+   * Handle passed to `transformProject`. Files are parsed lazily, and parsed
+   * roots share the step's semantic provider.
+   */
+  export interface Project<T extends TypesMap = TypesMap> {
+    /** Absolute path of the target directory */
+    readonly root: string;
+    /** Language the step was configured with */
+    readonly language: string;
+    /** Absolute paths of the files selected by the step's include/exclude globs */
+    files(): string[];
+    /** Whether a file exists inside the target directory */
+    exists(path: string): boolean;
+    /** Read a file inside the target directory */
+    readFile(path: string): string;
+    /** Parse a file, defaulting to the step language */
+    parseFile<U extends TypesMap = T>(path: string, language?: string): SgRoot<U>;
+    /**
+     * Absolute paths of the files that import any of `paths`, directly or
+     * transitively. Requires workspace-scope semantic analysis; returns an
+     * empty array otherwise.
+     */
+    dependentsOf(paths: string | string[]): string[];
+  }
+
+  /**
+   * A change returned from `transformProject`. Relative paths resolve against
+   * the target directory; every path may appear in at most one edit.
+   */
+  export type ProjectEdit =
+    | { type: "edit"; path: string; content: string }
+    | { type: "create"; path: string; content: string }
+    | { type: "rename"; from: string; to: string; content?: string }
+    | { type: "delete"; path: string };
+
+  export type TransformProject<T extends TypesMap = TypesMap> = (
+    project: Project<T>,
+    options: Omit<TransformOptions<T>, "matches">,
+  ) => Promise<ProjectEdit[] | null> | ProjectEdit[] | null;
+
   export type GetSelectorOptions<_T extends TypesMap> = {
     params: Record<string, string>;
+    matrixValues?: Record<string, any>;
   };
 
   export type GetSelector<T extends TypesMap> = ({
     params,
+    matrixValues,
   }: GetSelectorOptions<T>) => RuleConfig<T>;
 
   /**
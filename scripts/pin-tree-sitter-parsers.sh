@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+# Regenerate crates/tree-sitter-loader/parsers.sha256 from the parser
+# revisions in crates/tree-sitter-loader/src/lib.rs. Run it after changing a
+# revision in `get_definitions`.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+bucket="https://tree-sitter-parsers.s3.us-east-1.amazonaws.com/tree-sitter/parsers"
+artifacts="darwin-arm64.dylib darwin-x64.dylib linux-arm64.so linux-x64.so win32-x64.dll"
+lib="crates/tree-sitter-loader/src/lib.rs"
+out="crates/tree-sitter-loader/parsers.sha256"
+tmp="$(mktemp -d)"
+trap 'rm -rf "$tmp"' EXIT
+
+# `parser_urls!("tree-sitter-x", "<revision>")` spans three lines
+parsers="$(
+  sed -n '/^ *urls: parser_urls!($/,/^ *),$/p' "$lib" \
+    | sed -n 's/^ *"\([^"]*\)",\{0,1\}$/\1/p' \
+    | paste -d ' ' - -
+)"
+if [[ -z "$parsers" ]]; then
+  echo "No parser_urls! revisions found in $lib"
+  exit 1
+fi
+
+{
+  sed -n '/^#/p' "$out"
+  while read -r parser revision; do
+    for artifact in $artifacts; do
+      path="$parser/$revision/$artifact"
+      curl --fail --silent --show-error --location --output "$tmp/artifact" "$bucket/$path"
+      printf '%s  %s\n' "$(sha256sum "$tmp/artifact" | cut -d ' ' -f 1)" "$path"
+    done
+  done <<<"$parsers"
+} >"$tmp/parsers.sha256"
+
+mv "$tmp/parsers.sha256" "$out"
+echo "Updated $out"